@@ -0,0 +1,66 @@
+//! Next-available lookups on a year-long two-shift calendar.
+//!
+//! Run with `cargo bench --bench calendar`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use u_schedule::models::Calendar;
+
+const HOUR_MS: i64 = 3_600_000;
+const DAY_MS: i64 = 24 * HOUR_MS;
+
+/// Two 8-hour shifts per day for a year, with weekly 4-hour maintenance.
+fn year_of_shifts() -> Calendar {
+    (0..365).fold(Calendar::new("two-shift"), |cal, day| {
+        let base = day * DAY_MS;
+        let cal = cal
+            .with_window(base + 6 * HOUR_MS, base + 14 * HOUR_MS)
+            .with_window(base + 14 * HOUR_MS, base + 22 * HOUR_MS);
+        if day % 7 == 6 {
+            cal.with_blocked(base + 10 * HOUR_MS, base + 14 * HOUR_MS)
+        } else {
+            cal
+        }
+    })
+}
+
+fn bench(name: &str, queries: &[i64], f: impl Fn(i64) -> Option<i64>) {
+    let start = Instant::now();
+    let mut found = 0;
+    for &t in queries {
+        found += usize::from(black_box(f(black_box(t))).is_some());
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:<32} {:>10.1} ns/query ({found} hits)",
+        elapsed.as_nanos() as f64 / queries.len() as f64
+    );
+}
+
+fn main() {
+    let calendar = year_of_shifts();
+    // Every 17 minutes through the year: a forward scan like a scheduler's.
+    let forward: Vec<i64> = (0..365 * DAY_MS).step_by(17 * 60_000).collect();
+    // Pseudo-random order defeats the cursor.
+    let mut shuffled = forward.clone();
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    for i in (1..shuffled.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        shuffled.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+
+    let sample = &forward[..forward.len() / 20];
+    bench("Calendar (forward, 5% sample)", sample, |t| {
+        calendar.next_available_time(t)
+    });
+    let index = calendar.index();
+    bench("CalendarIndex (forward)", &forward, |t| {
+        index.next_available_time(t)
+    });
+    bench("CalendarIndex (random order)", &shuffled, |t| {
+        index.next_available_time(t)
+    });
+}
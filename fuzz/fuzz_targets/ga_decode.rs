@@ -0,0 +1,23 @@
+//! Decoding random chromosomes of arbitrary instances must not panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use u_metaheur::ga::GaProblem;
+use u_schedule::ga::SchedulingGaProblem;
+use u_schedule_fuzz::FuzzInstance;
+
+fuzz_target!(|input: (FuzzInstance, u64)| {
+    let (instance, seed) = input;
+    let (tasks, resources) = instance.to_models();
+    // Shared task IDs are rejected up front.
+    let Ok(problem) = SchedulingGaProblem::try_new(&tasks, &resources) else {
+        return;
+    };
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let chromosome = problem.create_individual(&mut rng);
+    let _ = problem.decode(&chromosome);
+    let _ = problem.evaluate(&chromosome);
+});
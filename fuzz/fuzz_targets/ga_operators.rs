@@ -0,0 +1,41 @@
+//! Crossover and mutation (POX, LOX, JOX; swap, insert, invert) must not
+//! panic, and their children must decode.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use u_metaheur::ga::GaProblem;
+use u_schedule::ga::operators::{CrossoverType, GeneticOperators, MutationType};
+use u_schedule::ga::SchedulingGaProblem;
+use u_schedule_fuzz::FuzzInstance;
+
+fuzz_target!(|input: (FuzzInstance, u64, u8)| {
+    let (instance, seed, choice) = input;
+    let (tasks, resources) = instance.to_models();
+    let operators = GeneticOperators {
+        crossover_type: match choice % 3 {
+            0 => CrossoverType::POX,
+            1 => CrossoverType::LOX,
+            _ => CrossoverType::JOX,
+        },
+        mutation_type: match (choice / 3) % 3 {
+            0 => MutationType::Swap,
+            1 => MutationType::Insert,
+            _ => MutationType::Invert,
+        },
+    };
+    // Shared task IDs are rejected up front.
+    let Ok(problem) = SchedulingGaProblem::try_new(&tasks, &resources) else {
+        return;
+    };
+    let problem = problem.with_operators(operators);
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let p1 = problem.create_individual(&mut rng);
+    let p2 = problem.create_individual(&mut rng);
+    for mut child in problem.crossover(&p1, &p2, &mut rng) {
+        problem.mutate(&mut child, &mut rng);
+        let _ = problem.decode(&child);
+    }
+});
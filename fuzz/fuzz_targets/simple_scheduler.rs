@@ -0,0 +1,15 @@
+//! The greedy scheduler must not panic, even on invalid input, and its
+//! output must survive the schedule audit.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use u_schedule::scheduler::SimpleScheduler;
+use u_schedule::validation::check_schedule;
+use u_schedule_fuzz::FuzzInstance;
+
+fuzz_target!(|instance: FuzzInstance| {
+    let (tasks, resources) = instance.to_models();
+    let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+    let _ = check_schedule(&schedule, &tasks, &resources);
+});
@@ -0,0 +1,12 @@
+//! Input validation must report, never panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use u_schedule::validation::validate_input;
+use u_schedule_fuzz::FuzzInstance;
+
+fuzz_target!(|instance: FuzzInstance| {
+    let (tasks, resources) = instance.to_models();
+    let _ = validate_input(&tasks, &resources);
+});
@@ -0,0 +1,137 @@
+//! Arbitrary scheduling instances for the fuzz targets.
+//!
+//! IDs are drawn from small domains so that duplicate IDs, dangling
+//! references, precedence cycles, repeated sequence numbers and empty
+//! candidate lists all occur often.
+
+use arbitrary::Arbitrary;
+use u_schedule::models::{
+    Activity, ActivityDuration, PrecedenceMode, Resource, ResourceRequirement, ResourceType, Task,
+};
+
+/// Upper bound on tasks per instance (keeps runs fast).
+const MAX_TASKS: usize = 12;
+/// Upper bound on activities per task.
+const MAX_ACTIVITIES: usize = 6;
+/// Upper bound on resources per instance.
+const MAX_RESOURCES: usize = 8;
+
+/// A fuzzed problem instance.
+#[derive(Debug, Arbitrary)]
+pub struct FuzzInstance {
+    pub tasks: Vec<FuzzTask>,
+    pub resources: Vec<FuzzResource>,
+}
+
+#[derive(Debug, Arbitrary)]
+pub struct FuzzTask {
+    pub id: u8,
+    pub category: u8,
+    pub priority: i8,
+    pub deadline_ms: Option<i32>,
+    pub release_ms: Option<i32>,
+    pub activities: Vec<FuzzActivity>,
+}
+
+#[derive(Debug, Arbitrary)]
+pub struct FuzzActivity {
+    pub id: u8,
+    pub sequence: i8,
+    pub setup_ms: i16,
+    pub process_ms: i16,
+    pub teardown_ms: i16,
+    pub candidates: Vec<u8>,
+    pub quantity: i8,
+    pub predecessors: Vec<u8>,
+    pub mode: u8,
+    pub lag_ms: i16,
+    pub min_split_ms: Option<i16>,
+}
+
+#[derive(Debug, Arbitrary)]
+pub struct FuzzResource {
+    pub id: u8,
+    pub human: bool,
+    pub capacity: i8,
+    pub efficiency: u8,
+}
+
+/// Resource ID of a fuzzed index.
+fn resource_id(i: u8) -> String {
+    format!("R{}", i % 10)
+}
+
+/// Activity ID of a fuzzed index.
+fn activity_id(i: u8) -> String {
+    format!("A{}", i % 32)
+}
+
+impl FuzzInstance {
+    /// Builds domain models.
+    pub fn to_models(&self) -> (Vec<Task>, Vec<Resource>) {
+        let tasks = self
+            .tasks
+            .iter()
+            .take(MAX_TASKS)
+            .map(|t| {
+                let task_id = format!("T{}", t.id % 16);
+                let mut task = Task::new(&task_id)
+                    .with_category(format!("C{}", t.category % 3))
+                    .with_priority(t.priority as i32);
+                task.deadline = t.deadline_ms.map(i64::from);
+                task.release_time = t.release_ms.map(i64::from);
+                for a in t.activities.iter().take(MAX_ACTIVITIES) {
+                    let mode = match a.mode % 4 {
+                        0 => PrecedenceMode::FinishToStart,
+                        1 => PrecedenceMode::StartToStart,
+                        2 => PrecedenceMode::FinishToFinish,
+                        _ => PrecedenceMode::Overlap {
+                            percent: f64::from(a.lag_ms % 101),
+                        },
+                    };
+                    let mut activity =
+                        Activity::new(activity_id(a.id), &task_id, a.sequence as i32)
+                            .with_duration(ActivityDuration::new(
+                                a.setup_ms.into(),
+                                a.process_ms.into(),
+                                a.teardown_ms.into(),
+                            ))
+                            .with_requirement(
+                                ResourceRequirement::new("Machine")
+                                    .with_quantity(a.quantity as i32)
+                                    .with_candidates(
+                                        a.candidates.iter().map(|&c| resource_id(c)).collect(),
+                                    ),
+                            )
+                            .with_precedence_mode(mode, a.lag_ms.into());
+                    for &p in &a.predecessors {
+                        activity = activity.with_predecessor(activity_id(p));
+                    }
+                    if let Some(min_split) = a.min_split_ms {
+                        activity = activity.with_splitting(min_split.into());
+                    }
+                    task = task.with_activity(activity);
+                }
+                task
+            })
+            .collect();
+
+        let resources = self
+            .resources
+            .iter()
+            .take(MAX_RESOURCES)
+            .map(|r| {
+                let resource_type = if r.human {
+                    ResourceType::Human
+                } else {
+                    ResourceType::Primary
+                };
+                Resource::new(resource_id(r.id), resource_type)
+                    .with_capacity(r.capacity as i32)
+                    .with_efficiency(f64::from(r.efficiency) / 100.0)
+            })
+            .collect();
+
+        (tasks, resources)
+    }
+}
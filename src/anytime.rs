@@ -0,0 +1,575 @@
+//! Anytime solving: incumbents published as they are found.
+//!
+//! A planner watching a GA run wants to see the plan improve, and to
+//! accept it as soon as it is good enough instead of waiting out the
+//! time limit. [`AnytimeSolver::solve_anytime`] calls back with each new
+//! [`Incumbent`] (the schedule, its objective value and the time since
+//! the start), and the callback answers whether to go on.
+//!
+//! # Contract
+//!
+//! - Incumbents are strictly improving in the caller's [`Objective`];
+//!   their `number`s count from 1.
+//! - The callback runs on the solving thread; a slow callback slows the
+//!   solver. To hand incumbents to another thread (e.g. a UI), send them
+//!   through a channel from the callback.
+//! - Answering [`Progress::Stop`] ends the run as soon as the solver can
+//!   stop; `solve_anytime` then returns the best schedule found so far.
+//!   Stopping never makes the result worse than the last incumbent.
+//!
+//! # Solvers
+//!
+//! | [`SolverSpec`] | Incumbents |
+//! |----------------|------------|
+//! | `Greedy` | One, the schedule |
+//! | `Ga` | The best individual after every generation that improved it |
+//! | `Cp` | One, the solver's result |
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::mpsc;
+//!
+//! use u_schedule::anytime::{AnytimeSolver, Progress};
+//! use u_schedule::bundle::SolverSpec;
+//! use u_schedule::ga::SchedulingGaOptions;
+//! use u_schedule::models::{Activity, ActivityDuration, Resource, ResourceRequirement, Task};
+//! use u_schedule::objective::Objective;
+//! use u_schedule::scheduler::ScheduleRequest;
+//!
+//! let job = |id: &str, ms: i64| {
+//!     Task::new(id).with_activity(
+//!         Activity::new(format!("{id}_O1"), id, 0)
+//!             .with_duration(ActivityDuration::fixed(ms))
+//!             .with_requirement(
+//!                 ResourceRequirement::new("Machine").with_candidates(vec!["M1".into(), "M2".into()]),
+//!             ),
+//!     )
+//! };
+//! let tasks = (0..6).map(|i| job(&format!("J{i}"), 1000 * (i + 1))).collect();
+//! let request = ScheduleRequest::new(tasks, vec![Resource::primary("M1"), Resource::primary("M2")]);
+//! let spec = SolverSpec::ga(SchedulingGaOptions { seed: Some(1), ..Default::default() });
+//!
+//! // Stream incumbents to another thread; stop once the plan is good enough.
+//! let (sender, receiver) = mpsc::channel();
+//! let schedule = spec
+//!     .solve_anytime(&request, &Objective::makespan_tardiness(0.0), &mut |incumbent| {
+//!         sender.send((incumbent.number, incumbent.objective)).unwrap();
+//!         if incumbent.objective <= 11_000.0 { Progress::Stop } else { Progress::Continue }
+//!     })
+//!     .unwrap();
+//! drop(sender);
+//!
+//! let values: Vec<f64> = receiver.iter().map(|(_, objective)| objective).collect();
+//! assert!(values.windows(2).all(|w| w[1] < w[0]));
+//! assert_eq!(schedule.makespan_ms() as f64, *values.last().unwrap());
+//! ```
+//!
+//! # Budgeted Solving
+//!
+//! [`solve_with_budget`] runs the solvers in stages within a wall-clock
+//! budget: the greedy scheduler for an immediate baseline, then the GA,
+//! then CP with whatever time is left. The best schedule of all stages
+//! is returned in a [`BudgetedSolution`] naming the stage that found it.
+//!
+//! # Platform Notes
+//!
+//! `wasm32-unknown-unknown` has no clock: there, elapsed times read 0,
+//! GA runs are bounded by their generations instead of time limits, and
+//! [`solve_with_budget`] runs the greedy and GA stages only.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use u_metaheur::ga::GaProblem;
+
+use crate::bundle::SolverSpec;
+use crate::ga::{evolve, ScheduleChromosome, SchedulingGaOptions, SchedulingGaProblem};
+use crate::models::{Schedule, Task, ViolationType};
+use crate::objective::Objective;
+use crate::scheduler::ScheduleRequest;
+use crate::validation::{check_schedule, ValidationError};
+
+/// A new best schedule found during a run.
+#[derive(Debug, Clone)]
+pub struct Incumbent {
+    /// Position in the run's stream of incumbents, from 1.
+    pub number: usize,
+    /// Time since the run started (ms).
+    pub elapsed_ms: u64,
+    /// Objective value of the schedule (lower is better).
+    pub objective: f64,
+    /// The schedule.
+    pub schedule: Schedule,
+}
+
+/// The caller's answer to an incumbent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// Keep searching.
+    Continue,
+    /// The plan is good enough: stop and return the best one.
+    Stop,
+}
+
+/// Callback receiving incumbents.
+pub type IncumbentCallback<'a> = dyn FnMut(&Incumbent) -> Progress + Send + 'a;
+
+/// A solver that publishes improving schedules while it runs.
+pub trait AnytimeSolver {
+    /// Solves `request`, calling `on_incumbent` with every schedule that
+    /// improves `objective`, and returns the best schedule.
+    ///
+    /// # Errors
+    /// Returns an error if the request fails the solver's validation.
+    fn solve_anytime(
+        &self,
+        request: &ScheduleRequest,
+        objective: &Objective,
+        on_incumbent: &mut IncumbentCallback<'_>,
+    ) -> Result<Schedule, ValidationError>;
+}
+
+impl AnytimeSolver for SolverSpec {
+    fn solve_anytime(
+        &self,
+        request: &ScheduleRequest,
+        objective: &Objective,
+        on_incumbent: &mut IncumbentCallback<'_>,
+    ) -> Result<Schedule, ValidationError> {
+        let mut publisher = Publisher::new(&request.tasks, objective, on_incumbent);
+        let Some((problem, config)) = self.ga_setup(request)? else {
+            let schedule = self.solve(request)?;
+            publisher.offer(&schedule);
+            return Ok(schedule);
+        };
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let streaming = StreamingProblem {
+            problem: &problem,
+            best: Mutex::new(None),
+            published: Mutex::new((f64::INFINITY, publisher)),
+            cancel: Arc::clone(&cancel),
+        };
+        let result = evolve(&streaming, &config, Some(cancel));
+        let schedule = problem.decode(&result.best);
+        let (_, mut publisher) = streaming.published.into_inner().expect("publisher lock");
+        publisher.offer(&schedule);
+        Ok(schedule)
+    }
+}
+
+/// Numbers, times and forwards improving schedules.
+struct Publisher<'a> {
+    started: Stopwatch,
+    tasks: &'a [Task],
+    objective: &'a Objective,
+    on_incumbent: &'a mut IncumbentCallback<'a>,
+    best: f64,
+    count: usize,
+}
+
+impl<'a> Publisher<'a> {
+    fn new(
+        tasks: &'a [Task],
+        objective: &'a Objective,
+        on_incumbent: &'a mut IncumbentCallback<'a>,
+    ) -> Self {
+        Self {
+            started: Stopwatch::start(),
+            tasks,
+            objective,
+            on_incumbent,
+            best: f64::INFINITY,
+            count: 0,
+        }
+    }
+
+    /// Publishes `schedule` if it improves the objective.
+    fn offer(&mut self, schedule: &Schedule) -> Progress {
+        let value = self.objective.evaluate(schedule, self.tasks);
+        if value >= self.best {
+            return Progress::Continue;
+        }
+        self.best = value;
+        self.count += 1;
+        (self.on_incumbent)(&Incumbent {
+            number: self.count,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            objective: value,
+            schedule: schedule.clone(),
+        })
+    }
+}
+
+/// The GA problem, remembering the fittest individual evaluated and
+/// publishing it after each generation that improved it.
+struct StreamingProblem<'a, 'p> {
+    problem: &'a SchedulingGaProblem,
+    best: Mutex<Option<(f64, ScheduleChromosome)>>,
+    /// Fitness last published, and the publisher.
+    published: Mutex<(f64, Publisher<'p>)>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl GaProblem for StreamingProblem<'_, '_> {
+    type Individual = ScheduleChromosome;
+
+    fn create_individual<R: Rng>(&self, rng: &mut R) -> ScheduleChromosome {
+        self.problem.create_individual(rng)
+    }
+
+    fn evaluate(&self, individual: &ScheduleChromosome) -> f64 {
+        let fitness = self.problem.evaluate(individual);
+        let mut best = self.best.lock().expect("best lock");
+        if best.as_ref().is_none_or(|(f, _)| fitness < *f) {
+            *best = Some((fitness, individual.clone()));
+        }
+        fitness
+    }
+
+    fn crossover<R: Rng>(
+        &self,
+        parent1: &ScheduleChromosome,
+        parent2: &ScheduleChromosome,
+        rng: &mut R,
+    ) -> Vec<ScheduleChromosome> {
+        self.problem.crossover(parent1, parent2, rng)
+    }
+
+    fn mutate<R: Rng>(&self, individual: &mut ScheduleChromosome, rng: &mut R) {
+        self.problem.mutate(individual, rng);
+    }
+
+    fn on_generation(&self, _generation: usize, _best_fitness: f64) {
+        let Some((fitness, chromosome)) = self.best.lock().expect("best lock").clone() else {
+            return;
+        };
+        let mut published = self.published.lock().expect("publisher lock");
+        if fitness >= published.0 {
+            return;
+        }
+        published.0 = fitness;
+        if published.1.offer(&self.problem.decode(&chromosome)) == Progress::Stop {
+            self.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Stage of [`solve_with_budget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SolveStage {
+    /// Greedy baseline.
+    Greedy,
+    /// Genetic algorithm.
+    Ga,
+    /// Constraint programming.
+    Cp,
+}
+
+/// Outcome of one stage of [`solve_with_budget`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageReport {
+    /// The stage.
+    pub stage: SolveStage,
+    /// Objective value of the stage's schedule.
+    pub objective: f64,
+    /// Hard violations plus unscheduled activities of the schedule.
+    pub defects: usize,
+    /// Time the stage ran (ms).
+    pub elapsed_ms: u64,
+    /// Whether the stage's schedule became the best so far.
+    pub improved: bool,
+}
+
+/// Best schedule of a budgeted run and how it was found.
+#[derive(Debug, Clone)]
+pub struct BudgetedSolution {
+    /// The best schedule, with its [`check_schedule`] violations.
+    pub schedule: Schedule,
+    /// Stage that produced it.
+    pub stage: SolveStage,
+    /// Its objective value.
+    pub objective: f64,
+    /// Stages run, in order.
+    pub stages: Vec<StageReport>,
+    /// Total time of the run (ms).
+    pub elapsed_ms: u64,
+}
+
+/// Solves `request` within `budget`: a greedy baseline, improved by the
+/// GA for up to three quarters of the remaining time (or its default
+/// generations, if sooner) and by CP for the rest. Stages without time
+/// left are skipped.
+///
+/// Schedules are compared by their defects — violations other than
+/// deadline and flow-time misses, plus activities left unscheduled — and
+/// then by `(1 − w) × makespan + w × weighted tardiness` with the GA's
+/// default tardiness weight. A later stage wins only if strictly better.
+/// The greedy stage always runs, so a zero budget returns its schedule.
+/// On wasm32 the budget cannot be measured: the GA runs its default
+/// generations and CP is skipped.
+///
+/// # Errors
+/// Returns an error if the request fails the GA's validation.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use u_schedule::anytime::{solve_with_budget, SolveStage};
+/// use u_schedule::models::{Activity, Resource, ResourceRequirement, Task};
+/// use u_schedule::scheduler::ScheduleRequest;
+///
+/// let task = Task::new("J1").with_activity(
+///     Activity::new("J1_O1", "J1", 0)
+///         .with_process_time(1000)
+///         .with_requirement(ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()])),
+/// );
+/// let request = ScheduleRequest::new(vec![task], vec![Resource::primary("M1")]);
+///
+/// let solution = solve_with_budget(&request, Duration::from_millis(200)).unwrap();
+/// assert_eq!(solution.schedule.makespan_ms(), 1000);
+/// // Nothing beats the greedy plan here.
+/// assert_eq!(solution.stage, SolveStage::Greedy);
+/// assert_eq!(solution.stages[0].stage, SolveStage::Greedy);
+/// ```
+pub fn solve_with_budget(
+    request: &ScheduleRequest,
+    budget: Duration,
+) -> Result<BudgetedSolution, ValidationError> {
+    let started = Stopwatch::start();
+    let options = SchedulingGaOptions::default();
+    let objective = Objective::makespan_tardiness(options.tardiness_weight);
+    let activities = request.tasks.iter().map(|t| t.activities.len()).sum();
+    let remaining_ms = || budget.saturating_sub(started.elapsed()).as_millis() as u64;
+
+    let mut best: Option<(Schedule, SolveStage, (usize, f64))> = None;
+    let mut stages = Vec::new();
+    let mut run = |stage: SolveStage, spec: SolverSpec| -> Result<(), ValidationError> {
+        let stage_started = Stopwatch::start();
+        let mut schedule = spec.solve(request)?;
+        schedule.violations = check_schedule(&schedule, &request.tasks, &request.resources);
+        let score = (
+            defects(&schedule, activities),
+            objective.evaluate(&schedule, &request.tasks),
+        );
+        let improved = best.as_ref().is_none_or(|(_, _, b)| score < *b);
+        stages.push(StageReport {
+            stage,
+            objective: score.1,
+            defects: score.0,
+            elapsed_ms: stage_started.elapsed().as_millis() as u64,
+            improved,
+        });
+        if improved {
+            best = Some((schedule, stage, score));
+        }
+        Ok(())
+    };
+
+    run(SolveStage::Greedy, SolverSpec::greedy())?;
+    let ga_ms = remaining_ms() * 3 / 4;
+    if ga_ms > 0 {
+        let spec = SolverSpec::Ga {
+            options,
+            population_size: None,
+            generations: None,
+            time_limit_ms: Some(ga_ms),
+            generations_run: None,
+        };
+        run(SolveStage::Ga, spec)?;
+    }
+    let cp_ms = remaining_ms();
+    if cp_ms > 0 && cfg!(not(target_arch = "wasm32")) {
+        let spec = SolverSpec::Cp {
+            horizon_ms: None,
+            time_limit_ms: Some(cp_ms as i64),
+        };
+        run(SolveStage::Cp, spec)?;
+    }
+
+    let (schedule, stage, (_, objective)) = best.expect("greedy stage ran");
+    Ok(BudgetedSolution {
+        schedule,
+        stage,
+        objective,
+        stages,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Wall-clock timer; always reads zero on wasm32, which has no clock.
+#[derive(Debug, Clone, Copy)]
+struct Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    started: Instant,
+}
+
+impl Stopwatch {
+    fn start() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            started: Instant::now(),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+/// Violations other than deadline and flow-time misses, plus activities
+/// of `activities` without an assignment.
+fn defects(schedule: &Schedule, activities: usize) -> usize {
+    let hard = schedule
+        .violations
+        .iter()
+        .filter(|v| {
+            !matches!(
+                v.violation_type,
+                ViolationType::DeadlineMiss | ViolationType::FlowTimeExceeded
+            )
+        })
+        .count();
+    let scheduled: HashSet<&str> = schedule
+        .assignments
+        .iter()
+        .filter(|a| !a.is_conditioning())
+        .map(|a| a.activity_id.as_str())
+        .collect();
+    hard + activities.saturating_sub(scheduled.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ga::SchedulingGaOptions;
+    use crate::models::{Activity, ActivityDuration, Resource, ResourceRequirement};
+
+    fn request() -> ScheduleRequest {
+        let tasks = (1..=8)
+            .map(|i| {
+                let id = format!("J{i}");
+                Task::new(&id).with_deadline(3000).with_activity(
+                    Activity::new(format!("{id}_O1"), &id, 0)
+                        .with_duration(ActivityDuration::fixed(500 * i))
+                        .with_requirement(
+                            ResourceRequirement::new("Machine").with_candidates(vec![
+                                "M1".into(),
+                                "M2".into(),
+                                "M3".into(),
+                            ]),
+                        ),
+                )
+            })
+            .collect();
+        let resources = vec![
+            Resource::primary("M1"),
+            Resource::primary("M2"),
+            Resource::primary("M3"),
+        ];
+        ScheduleRequest::new(tasks, resources)
+    }
+
+    #[test]
+    fn test_ga_streams_improving_incumbents() {
+        let request = request();
+        let objective = Objective::makespan_tardiness(0.5);
+        let spec = SolverSpec::Ga {
+            options: SchedulingGaOptions {
+                seed: Some(4),
+                ..Default::default()
+            },
+            population_size: Some(20),
+            generations: Some(30),
+            time_limit_ms: None,
+            generations_run: None,
+        };
+
+        let mut incumbents = Vec::new();
+        let schedule = spec
+            .solve_anytime(&request, &objective, &mut |incumbent| {
+                incumbents.push(incumbent.clone());
+                Progress::Continue
+            })
+            .unwrap();
+        assert!(!incumbents.is_empty());
+        assert!(incumbents
+            .iter()
+            .enumerate()
+            .all(|(i, inc)| inc.number == i + 1));
+        assert!(incumbents
+            .windows(2)
+            .all(|w| w[1].objective < w[0].objective && w[1].elapsed_ms >= w[0].elapsed_ms));
+        let last = incumbents.last().unwrap();
+        assert_eq!(
+            objective.evaluate(&schedule, &request.tasks),
+            last.objective
+        );
+        assert_eq!(last.schedule.fingerprint(), schedule.fingerprint());
+
+        // Stopping at the first incumbent returns it.
+        let mut seen = 0;
+        let first = spec
+            .solve_anytime(&request, &objective, &mut |_| {
+                seen += 1;
+                Progress::Stop
+            })
+            .unwrap();
+        assert_eq!(seen, 1);
+        assert_eq!(
+            objective.evaluate(&first, &request.tasks),
+            incumbents[0].objective
+        );
+    }
+
+    #[test]
+    fn test_single_shot_solvers() {
+        let request = request();
+        let objective = Objective::makespan_tardiness(0.0);
+        let mut values = Vec::new();
+        let schedule = SolverSpec::greedy()
+            .solve_anytime(&request, &objective, &mut |incumbent| {
+                values.push(incumbent.objective);
+                Progress::Continue
+            })
+            .unwrap();
+        assert_eq!(values, [schedule.makespan_ms() as f64]);
+    }
+
+    #[test]
+    fn test_budgeted_stages() {
+        let request = request();
+        let solution = solve_with_budget(&request, Duration::from_millis(300)).unwrap();
+        let stages: Vec<_> = solution.stages.iter().map(|s| s.stage).collect();
+        assert_eq!(stages, [SolveStage::Greedy, SolveStage::Ga, SolveStage::Cp]);
+        assert!(solution.stages[0].improved);
+        // The winner is the last improving stage, and no worse than greedy.
+        let winner = solution.stages.iter().rev().find(|s| s.improved).unwrap();
+        assert_eq!(winner.stage, solution.stage);
+        assert_eq!(winner.objective, solution.objective);
+        assert!(solution.objective <= solution.stages[0].objective);
+        assert_eq!(winner.defects, 0);
+
+        // Without a budget only the baseline runs.
+        let baseline = solve_with_budget(&request, Duration::ZERO).unwrap();
+        assert_eq!(baseline.stages.len(), 1);
+        assert_eq!(baseline.stage, SolveStage::Greedy);
+        assert_eq!(baseline.objective, solution.stages[0].objective);
+    }
+}
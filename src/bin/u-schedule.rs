@@ -0,0 +1,559 @@
+//! `u-schedule` command-line tool (feature `cli`).
+//!
+//! Reads and writes the JSON instance format ([`ScheduleRequest`]) so
+//! support engineers can reproduce customer issues from exported data.
+//!
+//! ```text
+//! u-schedule validate <problem.json>
+//! u-schedule solve <problem.json> [--engine greedy|ga|cp] [--rules <rules.json>]
+//!                  [--seed <n>] [--generations <n>] [--time-limit-ms <n>] [--out <file>]
+//!                  [--bundle <bundle.json>]
+//! u-schedule kpi <problem.json> <schedule.json>
+//! u-schedule cost <problem.json> <schedule.json> [--csv assignments|tasks]
+//! u-schedule sequence <problem.json> <schedule.json> [--format json|csv]
+//!                     [--generated-at-ms <n>]
+//! u-schedule compare <problem.json> <a.json> <b.json>
+//! u-schedule replay <bundle.json>
+//! ```
+//!
+//! `solve --bundle` also writes a [`RunBundle`] (problem, solver
+//! configuration and seed, schedule, environment); `replay` re-runs it
+//! and fails unless the schedule comes out identical.
+//!
+//! Schedule files may be a bare `Schedule` or a `solve` output
+//! (`{ "schedule": ..., "kpi": ... }`).
+
+use std::fs;
+use std::process::ExitCode;
+
+use serde::Serialize;
+use serde_json::Value;
+use u_schedule::bundle::{RunBundle, SolverSpec};
+use u_schedule::dispatching::RuleEngineSpec;
+use u_schedule::ga::SchedulingGaOptions;
+use u_schedule::models::Schedule;
+use u_schedule::scheduler::{CostModel, ScheduleKpi, ScheduleRequest, SequenceExport};
+use u_schedule::validation::validate_input;
+
+const USAGE: &str = "\
+usage:
+  u-schedule validate <problem.json>
+  u-schedule solve <problem.json> [--engine greedy|ga|cp] [--rules <rules.json>]
+                   [--seed <n>] [--generations <n>] [--time-limit-ms <n>] [--out <file>]
+                   [--bundle <bundle.json>]
+  u-schedule kpi <problem.json> <schedule.json>
+  u-schedule cost <problem.json> <schedule.json> [--csv assignments|tasks]
+  u-schedule sequence <problem.json> <schedule.json> [--format json|csv]
+                      [--generated-at-ms <n>]
+  u-schedule compare <problem.json> <a.json> <b.json>
+  u-schedule replay <bundle.json>";
+
+/// Command outcome: text for stdout and whether it represents success.
+#[derive(Debug)]
+struct Outcome {
+    output: String,
+    success: bool,
+}
+
+impl Outcome {
+    fn ok(output: String) -> Self {
+        Self {
+            output,
+            success: true,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SolveOutput {
+    schedule: Schedule,
+    kpi: ScheduleKpi,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(outcome) => {
+            println!("{}", outcome.output);
+            if outcome.success {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<Outcome, String> {
+    let (command, rest) = args.split_first().ok_or(USAGE)?;
+    let (positional, flags) = split_flags(rest)?;
+    match command.as_str() {
+        "validate" => {
+            let [problem] = positional.as_slice() else {
+                return Err(USAGE.into());
+            };
+            validate(&read_problem(problem)?)
+        }
+        "solve" => {
+            let [problem] = positional.as_slice() else {
+                return Err(USAGE.into());
+            };
+            let output = solve(&read_problem(problem)?, &flags)?;
+            match flag(&flags, "out") {
+                Some(path) => {
+                    fs::write(path, &output).map_err(|e| format!("cannot write {path}: {e}"))?;
+                    Ok(Outcome::ok(format!("wrote {path}")))
+                }
+                None => Ok(Outcome::ok(output)),
+            }
+        }
+        "kpi" => {
+            let [problem, schedule] = positional.as_slice() else {
+                return Err(USAGE.into());
+            };
+            let request = read_problem(problem)?;
+            let kpi = ScheduleKpi::calculate(&read_schedule(schedule)?, &request.tasks);
+            Ok(Outcome::ok(to_json(&kpi)?))
+        }
+        "cost" => {
+            let [problem, schedule] = positional.as_slice() else {
+                return Err(USAGE.into());
+            };
+            let request = read_problem(problem)?;
+            let report = CostModel::new().evaluate(&read_schedule(schedule)?, &request.resources);
+            match flag(&flags, "csv") {
+                None => Ok(Outcome::ok(to_json(&report)?)),
+                Some("assignments") => Ok(Outcome::ok(report.assignments_csv())),
+                Some("tasks") => Ok(Outcome::ok(report.tasks_csv())),
+                Some(other) => Err(format!("unknown --csv `{other}`")),
+            }
+        }
+        "sequence" => {
+            let [problem, schedule] = positional.as_slice() else {
+                return Err(USAGE.into());
+            };
+            let request = read_problem(problem)?;
+            let generated_at_ms = numeric_flag(&flags, "generated-at-ms")?.unwrap_or(0);
+            let export =
+                SequenceExport::new(&read_schedule(schedule)?, &request.tasks, generated_at_ms);
+            match flag(&flags, "format").unwrap_or("json") {
+                "json" => Ok(Outcome::ok(to_json(&export)?)),
+                "csv" => Ok(Outcome::ok(export.to_csv())),
+                other => Err(format!("unknown --format `{other}`")),
+            }
+        }
+        "compare" => {
+            let [problem, a, b] = positional.as_slice() else {
+                return Err(USAGE.into());
+            };
+            let request = read_problem(problem)?;
+            Ok(Outcome::ok(compare(
+                &request,
+                (a, &read_schedule(a)?),
+                (b, &read_schedule(b)?),
+            )))
+        }
+        "replay" => {
+            let [bundle] = positional.as_slice() else {
+                return Err(USAGE.into());
+            };
+            let bundle = RunBundle::from_json(&read_file(bundle)?)
+                .map_err(|e| format!("{bundle}: invalid bundle: {e}"))?;
+            replay(&bundle)
+        }
+        "help" | "--help" | "-h" => Ok(Outcome::ok(USAGE.into())),
+        other => Err(format!("unknown command `{other}`\n{USAGE}")),
+    }
+}
+
+/// `--name value` pairs, in command-line order.
+type Flags<'a> = Vec<(&'a str, &'a str)>;
+
+/// Splits `--name value` pairs from positional arguments.
+fn split_flags(args: &[String]) -> Result<(Vec<&str>, Flags<'_>), String> {
+    let mut positional = Vec::new();
+    let mut flags = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.strip_prefix("--") {
+            Some(name) => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("missing value for --{name}"))?;
+                flags.push((name, value.as_str()));
+            }
+            None => positional.push(arg.as_str()),
+        }
+    }
+    Ok((positional, flags))
+}
+
+fn flag<'a>(flags: &[(&str, &'a str)], name: &str) -> Option<&'a str> {
+    flags
+        .iter()
+        .rev()
+        .find(|(n, _)| *n == name)
+        .map(|(_, v)| *v)
+}
+
+fn numeric_flag<T: std::str::FromStr>(
+    flags: &[(&str, &str)],
+    name: &str,
+) -> Result<Option<T>, String> {
+    flag(flags, name)
+        .map(|v| v.parse().map_err(|_| format!("invalid --{name} `{v}`")))
+        .transpose()
+}
+
+fn read_file(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))
+}
+
+fn read_problem(path: &str) -> Result<ScheduleRequest, String> {
+    serde_json::from_str(&read_file(path)?).map_err(|e| format!("{path}: invalid problem: {e}"))
+}
+
+fn read_schedule(path: &str) -> Result<Schedule, String> {
+    let mut value: Value =
+        serde_json::from_str(&read_file(path)?).map_err(|e| format!("{path}: {e}"))?;
+    if let Some(inner) = value.get_mut("schedule") {
+        value = inner.take();
+    }
+    serde_json::from_value(value).map_err(|e| format!("{path}: invalid schedule: {e}"))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| e.to_string())
+}
+
+fn validate(request: &ScheduleRequest) -> Result<Outcome, String> {
+    match validate_input(&request.tasks, &request.resources) {
+        Ok(()) => Ok(Outcome::ok(format!(
+            "OK: {} tasks, {} resources",
+            request.tasks.len(),
+            request.resources.len()
+        ))),
+        Err(errors) => Ok(Outcome {
+            output: errors
+                .iter()
+                .map(|e| format!("{:?}: {}", e.kind, e.message))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            success: false,
+        }),
+    }
+}
+
+fn solve(request: &ScheduleRequest, flags: &[(&str, &str)]) -> Result<String, String> {
+    let seed: Option<u64> = numeric_flag(flags, "seed")?;
+    let spec = match flag(flags, "engine").unwrap_or("greedy") {
+        "greedy" => SolverSpec::Greedy {
+            rules: match flag(flags, "rules") {
+                Some(path) => Some(
+                    serde_json::from_str::<RuleEngineSpec>(&read_file(path)?)
+                        .map_err(|e| format!("{path}: invalid rules: {e}"))?,
+                ),
+                None => None,
+            },
+        },
+        "ga" => SolverSpec::Ga {
+            options: SchedulingGaOptions {
+                seed,
+                ..SchedulingGaOptions::default()
+            },
+            population_size: None,
+            generations: numeric_flag(flags, "generations")?,
+            time_limit_ms: numeric_flag(flags, "time-limit-ms")?,
+            generations_run: None,
+        },
+        "cp" => SolverSpec::Cp {
+            horizon_ms: None,
+            time_limit_ms: numeric_flag(flags, "time-limit-ms")?,
+        },
+        other => {
+            return Err(format!(
+                "unknown engine `{other}` (expected greedy, ga or cp)"
+            ))
+        }
+    };
+    let schedule = match flag(flags, "bundle") {
+        Some(path) => {
+            let bundle = RunBundle::record(request.clone(), spec).map_err(|e| e.message)?;
+            fs::write(path, bundle.to_json()).map_err(|e| format!("cannot write {path}: {e}"))?;
+            bundle.schedule
+        }
+        None => spec.solve(request).map_err(|e| e.message)?,
+    };
+    let kpi = ScheduleKpi::calculate(&schedule, &request.tasks);
+    to_json(&SolveOutput { schedule, kpi })
+}
+
+fn replay(bundle: &RunBundle) -> Result<Outcome, String> {
+    let report = bundle.replay().map_err(|e| e.message)?;
+    let mut lines = vec![format!(
+        "{} run: expected {}, replayed {}",
+        bundle.solver.engine(),
+        report.expected_fingerprint,
+        report.actual_fingerprint
+    )];
+    if !report.problem_intact {
+        lines.push("problem does not match its recorded fingerprint".into());
+    }
+    lines.extend(
+        report
+            .environment_differences
+            .iter()
+            .map(|d| format!("environment differs: {d}")),
+    );
+    lines.push(if report.is_reproduced() {
+        "reproduced".into()
+    } else {
+        "NOT reproduced".into()
+    });
+    Ok(Outcome {
+        output: lines.join("\n"),
+        success: report.is_reproduced(),
+    })
+}
+
+fn compare(request: &ScheduleRequest, a: (&str, &Schedule), b: (&str, &Schedule)) -> String {
+    let ka = ScheduleKpi::calculate(a.1, &request.tasks);
+    let kb = ScheduleKpi::calculate(b.1, &request.tasks);
+    let rows: [(&str, f64, f64); 6] = [
+        ("makespan_ms", ka.makespan_ms as f64, kb.makespan_ms as f64),
+        (
+            "total_tardiness_ms",
+            ka.total_tardiness_ms as f64,
+            kb.total_tardiness_ms as f64,
+        ),
+        (
+            "max_tardiness_ms",
+            ka.max_tardiness_ms as f64,
+            kb.max_tardiness_ms as f64,
+        ),
+        ("on_time_rate", ka.on_time_rate, kb.on_time_rate),
+        ("avg_utilization", ka.avg_utilization, kb.avg_utilization),
+        ("avg_flow_time_ms", ka.avg_flow_time_ms, kb.avg_flow_time_ms),
+    ];
+
+    let mut out = format!("{:<20} {:>16} {:>16} {:>16}\n", "metric", a.0, b.0, "delta");
+    for (name, va, vb) in rows {
+        out.push_str(&format!(
+            "{name:<20} {va:>16.3} {vb:>16.3} {:>+16.3}\n",
+            vb - va
+        ));
+    }
+
+    let changed =
+        a.1.assignments
+            .iter()
+            .filter(|x| {
+                b.1.assignment_for_activity(&x.activity_id).is_none_or(|y| {
+                    (&y.resource_id, y.start_ms, y.end_ms) != (&x.resource_id, x.start_ms, x.end_ms)
+                })
+            })
+            .count();
+    if a.1.fingerprint() == b.1.fingerprint() {
+        out.push_str("schedules are identical");
+    } else {
+        out.push_str(&format!(
+            "{changed} of {} assignments moved or missing",
+            a.1.assignment_count()
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use u_schedule::golden;
+
+    fn temp_file(name: &str, contents: &str) -> String {
+        let path: PathBuf =
+            std::env::temp_dir().join(format!("u-schedule-cli-{}-{name}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn problem_file(name: &str) -> String {
+        let request = golden::flexible_shop().request();
+        temp_file(name, &serde_json::to_string(&request).unwrap())
+    }
+
+    #[test]
+    fn test_validate() {
+        let problem = problem_file("validate.json");
+        let outcome = run(&args(&["validate", &problem])).unwrap();
+        assert!(outcome.success);
+        assert!(outcome.output.starts_with("OK: 4 tasks"));
+
+        let mut request = golden::flexible_shop().request();
+        request.resources.push(request.resources[0].clone());
+        let bad = temp_file("invalid.json", &serde_json::to_string(&request).unwrap());
+        let outcome = run(&args(&["validate", &bad])).unwrap();
+        assert!(!outcome.success, "{}", outcome.output);
+    }
+
+    #[test]
+    fn test_solve_engines_and_kpi() {
+        let problem = problem_file("solve.json");
+        for engine in ["greedy", "ga", "cp"] {
+            let out = run(&args(&[
+                "solve",
+                &problem,
+                "--engine",
+                engine,
+                "--seed",
+                "1",
+                "--generations",
+                "5",
+                "--time-limit-ms",
+                "1000",
+            ]))
+            .unwrap()
+            .output;
+            let value: Value = serde_json::from_str(&out).unwrap();
+            assert!(
+                value["kpi"]["makespan_ms"].as_i64().unwrap() > 0,
+                "{engine}"
+            );
+
+            let schedule = temp_file(&format!("{engine}-out.json"), &out);
+            let kpi = run(&args(&["kpi", &problem, &schedule])).unwrap().output;
+            let kpi: Value = serde_json::from_str(&kpi).unwrap();
+            assert_eq!(kpi["makespan_ms"], value["kpi"]["makespan_ms"]);
+        }
+    }
+
+    #[test]
+    fn test_cost() {
+        let mut request = golden::flexible_shop().request();
+        for r in &mut request.resources {
+            r.cost_per_hour = Some(36.0);
+        }
+        let problem = temp_file("cost.json", &serde_json::to_string(&request).unwrap());
+        let out = run(&args(&["solve", &problem])).unwrap().output;
+        let schedule = temp_file("cost-out.json", &out);
+
+        let json = run(&args(&["cost", &problem, &schedule])).unwrap().output;
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["assignments"].as_array().unwrap().len(), 8);
+
+        let csv = run(&args(&["cost", &problem, &schedule, "--csv", "tasks"]))
+            .unwrap()
+            .output;
+        assert!(csv.starts_with("task_id,machine,labor,setup,overtime,total\n"));
+        assert_eq!(csv.lines().count(), 5);
+        assert!(run(&args(&["cost", &problem, &schedule, "--csv", "pdf"]))
+            .unwrap_err()
+            .contains("unknown --csv"));
+    }
+
+    #[test]
+    fn test_sequence() {
+        let request = golden::flexible_shop().request();
+        let problem = temp_file("sequence.json", &serde_json::to_string(&request).unwrap());
+        let out = run(&args(&["solve", &problem])).unwrap().output;
+        let schedule = temp_file("sequence-out.json", &out);
+
+        let json = run(&args(&[
+            "sequence",
+            &problem,
+            &schedule,
+            "--generated-at-ms",
+            "7",
+        ]))
+        .unwrap()
+        .output;
+        let export: SequenceExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(export.generated_at_ms, 7);
+        assert_eq!(
+            export.queues.iter().map(|q| q.entries.len()).sum::<usize>(),
+            8
+        );
+        assert!(export.queues.iter().all(|q| q.verify()));
+
+        let csv = run(&args(&["sequence", &problem, &schedule, "--format", "csv"]))
+            .unwrap()
+            .output;
+        assert_eq!(csv.lines().count(), 10);
+        assert!(
+            run(&args(&["sequence", &problem, &schedule, "--format", "xml"]))
+                .unwrap_err()
+                .contains("unknown --format")
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let problem = problem_file("compare.json");
+        let out = run(&args(&["solve", &problem])).unwrap().output;
+        let a = temp_file("a.json", &out);
+        let report = run(&args(&["compare", &problem, &a, &a])).unwrap().output;
+        assert!(report.contains("schedules are identical"));
+        assert!(report.contains("makespan_ms"));
+
+        let mut value: Value = serde_json::from_str(&out).unwrap();
+        value["schedule"]["assignments"][0]["start_ms"] = Value::from(999_999);
+        let b = temp_file("b.json", &value.to_string());
+        let report = run(&args(&["compare", &problem, &a, &b])).unwrap().output;
+        assert!(report.contains("1 of 8 assignments moved or missing"));
+    }
+
+    #[test]
+    fn test_bundle_replay() {
+        let problem = problem_file("bundle.json");
+        let bundle = temp_file("bundle-run.json", "");
+        let out = run(&args(&[
+            "solve",
+            &problem,
+            "--engine",
+            "ga",
+            "--generations",
+            "5",
+            "--bundle",
+            &bundle,
+        ]))
+        .unwrap()
+        .output;
+        let recorded = RunBundle::from_json(&read_file(&bundle).unwrap()).unwrap();
+        let value: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(value["kpi"]["makespan_ms"], recorded.kpi.makespan_ms);
+
+        let outcome = run(&args(&["replay", &bundle])).unwrap();
+        assert!(outcome.success, "{}", outcome.output);
+        assert!(outcome.output.ends_with("reproduced"));
+
+        let mut edited = recorded.clone();
+        edited.schedule_fingerprint = "0".repeat(16);
+        let edited = temp_file("bundle-edited.json", &edited.to_json());
+        let outcome = run(&args(&["replay", &edited])).unwrap();
+        assert!(!outcome.success);
+    }
+
+    #[test]
+    fn test_usage_errors() {
+        assert!(run(&[]).is_err());
+        assert!(run(&args(&["frobnicate"]))
+            .unwrap_err()
+            .contains("unknown command"));
+        assert!(run(&args(&["solve"])).unwrap_err().contains("usage"));
+        let problem = problem_file("errors.json");
+        assert!(run(&args(&["solve", &problem, "--engine", "magic"]))
+            .unwrap_err()
+            .contains("unknown engine"));
+        assert!(run(&args(&["solve", &problem, "--seed"]))
+            .unwrap_err()
+            .contains("missing value"));
+    }
+}
@@ -0,0 +1,500 @@
+//! Run bundles: reproducible snapshots of solver runs.
+//!
+//! When a customer reports a planning discrepancy, support needs the
+//! exact inputs of the run, not a description of them. A [`RunBundle`]
+//! holds everything in one JSON document: the problem, the solver and
+//! its configuration (seed included), the resulting schedule with its
+//! KPIs and fingerprint, and the environment that produced it.
+//! [`RunBundle::replay`] re-runs the solver and checks that the schedule
+//! comes out bit-for-bit identical.
+//!
+//! # Determinism
+//!
+//! [`RunBundle::to_json`] writes object keys in sorted order, so equal
+//! bundles serialize to equal bytes (maps in the models are hash maps).
+//! Runs are reproducible because:
+//! - an unseeded GA gets a random seed at recording time, stored in the
+//!   bundle,
+//! - a GA stopped by its time limit records the generations it ran, and
+//!   replay runs exactly that many without a time limit.
+//!
+//! A CP run that hit its time limit is not reproducible; its replay may
+//! differ.
+//!
+//! # Example
+//!
+//! ```
+//! use u_schedule::bundle::{RunBundle, SolverSpec};
+//! use u_schedule::models::{Activity, Resource, Task};
+//! use u_schedule::scheduler::ScheduleRequest;
+//!
+//! let task = Task::new("J1").with_activity(Activity::new("J1_O1", "J1", 0).with_process_time(1000));
+//! let request = ScheduleRequest::new(vec![task], vec![Resource::primary("M1")]);
+//! let bundle = RunBundle::record(request, SolverSpec::greedy()).unwrap();
+//!
+//! let json = bundle.to_json();
+//! let restored = RunBundle::from_json(&json).unwrap();
+//! assert_eq!(restored.to_json(), json);
+//! assert!(restored.replay().unwrap().is_reproduced());
+//! ```
+
+use serde::{Deserialize, Serialize};
+use u_metaheur::cp::{SimpleCpSolver, SolverConfig};
+use u_metaheur::ga::GaConfig;
+
+use crate::cp::ScheduleCpBuilder;
+use crate::dispatching::{RuleEngine, RuleEngineSpec};
+use crate::ga::{evolve, SchedulingGaOptions, SchedulingGaProblem};
+use crate::models::{Fnv1a, Schedule};
+use crate::scheduler::{ScheduleKpi, ScheduleRequest, SimpleScheduler};
+use crate::validation::ValidationError;
+
+/// Version of the bundle layout.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Solver and configuration of a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "engine", rename_all = "lowercase")]
+pub enum SolverSpec {
+    /// Greedy scheduler with an optional rule engine.
+    Greedy {
+        /// Task ordering rules (default: priority order).
+        #[serde(default)]
+        rules: Option<RuleEngineSpec>,
+    },
+    /// Genetic algorithm.
+    Ga {
+        /// Problem configuration and seed.
+        #[serde(default)]
+        options: SchedulingGaOptions,
+        /// Population size (default: the GA runner's).
+        #[serde(default)]
+        population_size: Option<usize>,
+        /// Maximum generations (default: the GA runner's).
+        #[serde(default)]
+        generations: Option<usize>,
+        /// Wall-clock limit in ms (ignored on replay).
+        #[serde(default)]
+        time_limit_ms: Option<u64>,
+        /// Generations actually run, filled in by [`RunBundle::record`].
+        #[serde(default)]
+        generations_run: Option<usize>,
+    },
+    /// Constraint programming with the built-in solver.
+    Cp {
+        /// Planning horizon in ms (default: start time, or the end of the
+        /// latest locked time, plus all work and release times).
+        #[serde(default)]
+        horizon_ms: Option<i64>,
+        /// Wall-clock limit in ms (default: the solver's).
+        #[serde(default)]
+        time_limit_ms: Option<i64>,
+    },
+}
+
+impl SolverSpec {
+    /// The greedy scheduler in priority order.
+    pub fn greedy() -> Self {
+        Self::Greedy { rules: None }
+    }
+
+    /// The GA with the runner's default population and generations.
+    pub fn ga(options: SchedulingGaOptions) -> Self {
+        Self::Ga {
+            options,
+            population_size: None,
+            generations: None,
+            time_limit_ms: None,
+            generations_run: None,
+        }
+    }
+
+    /// The CP solver with the default horizon and time limit.
+    pub fn cp() -> Self {
+        Self::Cp {
+            horizon_ms: None,
+            time_limit_ms: None,
+        }
+    }
+
+    /// Engine name (`"greedy"`, `"ga"` or `"cp"`).
+    pub fn engine(&self) -> &'static str {
+        match self {
+            Self::Greedy { .. } => "greedy",
+            Self::Ga { .. } => "ga",
+            Self::Cp { .. } => "cp",
+        }
+    }
+
+    /// Solves `request`.
+    ///
+    /// A GA spec with `generations_run` runs exactly that many
+    /// generations without a time limit.
+    pub fn solve(&self, request: &ScheduleRequest) -> Result<Schedule, ValidationError> {
+        self.run(request).map(|(schedule, _)| schedule)
+    }
+
+    /// GA problem and runner configuration of a GA spec (`None` for the
+    /// other engines).
+    pub(crate) fn ga_setup(
+        &self,
+        request: &ScheduleRequest,
+    ) -> Result<Option<(SchedulingGaProblem, GaConfig)>, ValidationError> {
+        let Self::Ga {
+            options,
+            population_size,
+            generations,
+            time_limit_ms,
+            generations_run,
+        } = self
+        else {
+            return Ok(None);
+        };
+        let problem = SchedulingGaProblem::try_new(&request.tasks, &request.resources)?
+            .with_transition_matrices(request.transition_matrices.clone())
+            .with_tooling(request.tooling.clone())
+            .with_affinity(&request.tasks, &request.affinity)
+            .with_locks(&request.tasks, &request.locks)
+            .with_options(options);
+        let mut config = options.apply_to(GaConfig::default());
+        if let Some(size) = population_size {
+            config = config.with_population_size(*size);
+        }
+        match (generations_run, generations) {
+            (Some(n), _) | (None, Some(n)) => config = config.with_max_generations(*n),
+            (None, None) => {}
+        }
+        if let (None, Some(limit)) = (generations_run, time_limit_ms) {
+            config = config.with_time_limit_ms(*limit);
+        }
+        Ok(Some((problem, config)))
+    }
+
+    /// Solves `request`, returning the GA generations run.
+    fn run(&self, request: &ScheduleRequest) -> Result<(Schedule, Option<usize>), ValidationError> {
+        match self {
+            Self::Greedy { rules } => {
+                let mut scheduler = SimpleScheduler::new();
+                if let Some(spec) = rules {
+                    scheduler = scheduler.with_rule_engine(RuleEngine::from_spec(spec));
+                }
+                Ok((scheduler.schedule_request(request), None))
+            }
+            Self::Ga { .. } => {
+                let (problem, config) = self.ga_setup(request)?.expect("GA spec");
+                let result = evolve(&problem, &config, None);
+                Ok((problem.decode(&result.best), Some(result.generations)))
+            }
+            Self::Cp {
+                horizon_ms,
+                time_limit_ms,
+            } => {
+                let horizon = horizon_ms.unwrap_or_else(|| {
+                    let locked_until = request
+                        .locks
+                        .blocked_windows()
+                        .values()
+                        .flatten()
+                        .map(|w| w.end_ms)
+                        .fold(request.start_time_ms, i64::max);
+                    locked_until
+                        + request
+                            .tasks
+                            .iter()
+                            .map(|t| t.total_duration_ms() + t.release_time.unwrap_or(0))
+                            .sum::<i64>()
+                });
+                let mut config = SolverConfig::default();
+                if let Some(limit) = time_limit_ms {
+                    config.time_limit_ms = *limit;
+                }
+                let builder = ScheduleCpBuilder::new(&request.tasks, &request.resources)
+                    .with_transition_matrices(request.transition_matrices.clone())
+                    .with_tooling(request.tooling.clone())
+                    .with_locks(request.locks.clone());
+                Ok((
+                    builder.solve(&SimpleCpSolver::new(), &config, horizon).0,
+                    None,
+                ))
+            }
+        }
+    }
+}
+
+/// Where a run was recorded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunEnvironment {
+    /// Library name and version, e.g. `u-schedule 0.1.0`.
+    pub generator: String,
+    /// Operating system (`std::env::consts::OS`).
+    pub os: String,
+    /// CPU architecture (`std::env::consts::ARCH`).
+    pub arch: String,
+    /// Enabled optional features.
+    pub features: Vec<String>,
+}
+
+impl RunEnvironment {
+    /// The running environment.
+    pub fn current() -> Self {
+        let features = [
+            ("cli", cfg!(feature = "cli")),
+            ("ffi", cfg!(feature = "ffi")),
+            ("python", cfg!(feature = "python")),
+            ("wasm", cfg!(feature = "wasm")),
+        ];
+        Self {
+            generator: concat!("u-schedule ", env!("CARGO_PKG_VERSION")).to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            features: features
+                .iter()
+                .filter(|(_, on)| *on)
+                .map(|(name, _)| name.to_string())
+                .collect(),
+        }
+    }
+
+    /// Fields that differ from `other`, as `field: ours ≠ theirs`.
+    pub fn differences(&self, other: &Self) -> Vec<String> {
+        let fields = [
+            ("generator", &self.generator, &other.generator),
+            ("os", &self.os, &other.os),
+            ("arch", &self.arch, &other.arch),
+        ];
+        let mut differences: Vec<String> = fields
+            .iter()
+            .filter(|(_, a, b)| a != b)
+            .map(|(name, a, b)| format!("{name}: {a} ≠ {b}"))
+            .collect();
+        if self.features != other.features {
+            differences.push(format!(
+                "features: [{}] ≠ [{}]",
+                self.features.join(", "),
+                other.features.join(", ")
+            ));
+        }
+        differences
+    }
+}
+
+/// A solver run: inputs, configuration, result and environment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunBundle {
+    /// Bundle layout version ([`BUNDLE_FORMAT_VERSION`]).
+    pub format_version: u32,
+    /// Recording time (ms), if the caller set one.
+    #[serde(default)]
+    pub recorded_at_ms: Option<i64>,
+    /// Environment of the recording.
+    pub environment: RunEnvironment,
+    /// Solver and configuration.
+    pub solver: SolverSpec,
+    /// The problem as solved.
+    pub problem: ScheduleRequest,
+    /// FNV-1a hash of the problem's canonical JSON (16 hex digits).
+    pub problem_fingerprint: String,
+    /// The resulting schedule.
+    pub schedule: Schedule,
+    /// [`Schedule::fingerprint`] of the result (16 hex digits).
+    pub schedule_fingerprint: String,
+    /// KPIs of the result.
+    pub kpi: ScheduleKpi,
+}
+
+impl RunBundle {
+    /// Solves `problem` with `solver` and records the run.
+    ///
+    /// An unseeded GA is given a random seed, and a GA run records the
+    /// generations it ran, so the bundle replays exactly.
+    pub fn record(
+        problem: ScheduleRequest,
+        mut solver: SolverSpec,
+    ) -> Result<Self, ValidationError> {
+        if let SolverSpec::Ga { options, .. } = &mut solver {
+            options.seed.get_or_insert_with(rand::random);
+        }
+        let (schedule, generations) = solver.run(&problem)?;
+        if let SolverSpec::Ga {
+            generations_run, ..
+        } = &mut solver
+        {
+            *generations_run = generations;
+        }
+        Ok(Self::from_schedule(problem, solver, schedule))
+    }
+
+    /// Records a run whose schedule was computed elsewhere.
+    pub fn from_schedule(problem: ScheduleRequest, solver: SolverSpec, schedule: Schedule) -> Self {
+        Self {
+            format_version: BUNDLE_FORMAT_VERSION,
+            recorded_at_ms: None,
+            environment: RunEnvironment::current(),
+            problem_fingerprint: problem_fingerprint(&problem),
+            schedule_fingerprint: format!("{:016x}", schedule.fingerprint()),
+            kpi: ScheduleKpi::calculate(&schedule, &problem.tasks),
+            solver,
+            problem,
+            schedule,
+        }
+    }
+
+    /// Sets the recording time.
+    pub fn with_recorded_at(mut self, recorded_at_ms: i64) -> Self {
+        self.recorded_at_ms = Some(recorded_at_ms);
+        self
+    }
+
+    /// Serializes the bundle to pretty-printed JSON with sorted keys.
+    pub fn to_json(&self) -> String {
+        canonical_json(self, true)
+    }
+
+    /// Deserializes a bundle.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Re-runs the recorded solver on the recorded problem.
+    pub fn replay(&self) -> Result<ReplayReport, ValidationError> {
+        let schedule = self.solver.solve(&self.problem)?;
+        Ok(ReplayReport {
+            expected_fingerprint: self.schedule_fingerprint.clone(),
+            actual_fingerprint: format!("{:016x}", schedule.fingerprint()),
+            problem_intact: problem_fingerprint(&self.problem) == self.problem_fingerprint,
+            environment_differences: self.environment.differences(&RunEnvironment::current()),
+            schedule,
+        })
+    }
+}
+
+/// Outcome of [`RunBundle::replay`].
+#[derive(Debug, Clone)]
+pub struct ReplayReport {
+    /// The replayed schedule.
+    pub schedule: Schedule,
+    /// Fingerprint recorded in the bundle.
+    pub expected_fingerprint: String,
+    /// Fingerprint of the replayed schedule.
+    pub actual_fingerprint: String,
+    /// Whether the problem still matches its recorded fingerprint (false
+    /// if the bundle was edited).
+    pub problem_intact: bool,
+    /// How the replaying environment differs from the recording one.
+    pub environment_differences: Vec<String>,
+}
+
+impl ReplayReport {
+    /// Whether the replay produced the recorded schedule.
+    pub fn is_reproduced(&self) -> bool {
+        self.expected_fingerprint == self.actual_fingerprint
+    }
+}
+
+/// JSON with object keys in sorted order.
+fn canonical_json<T: Serialize>(value: &T, pretty: bool) -> String {
+    // `Value` objects are ordered maps, which sorts the keys.
+    let value = serde_json::to_value(value).expect("bundle contains only serializable data");
+    if pretty {
+        serde_json::to_string_pretty(&value)
+    } else {
+        serde_json::to_string(&value)
+    }
+    .expect("bundle contains only serializable data")
+}
+
+fn problem_fingerprint(problem: &ScheduleRequest) -> String {
+    let mut hasher = Fnv1a::new();
+    hasher.write_str(&canonical_json(problem, false));
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, ActivityDuration, Resource, ResourceRequirement, Task};
+
+    fn request() -> ScheduleRequest {
+        let tasks = (0..6)
+            .map(|i| {
+                let id = format!("J{i}");
+                Task::new(&id)
+                    .with_deadline(1000 * (i + 1))
+                    .with_attribute("customer", format!("C{}", i % 3))
+                    .with_activity(
+                        Activity::new(format!("{id}_O1"), &id, 0)
+                            .with_duration(ActivityDuration::fixed(300 + 100 * (i % 4)))
+                            .with_requirement(
+                                ResourceRequirement::new("Machine")
+                                    .with_candidates(vec!["M1".into(), "M2".into()]),
+                            ),
+                    )
+            })
+            .collect();
+        ScheduleRequest::new(
+            tasks,
+            vec![Resource::primary("M1"), Resource::primary("M2")],
+        )
+    }
+
+    #[test]
+    fn test_canonical_json_round_trip() {
+        let bundle = RunBundle::record(request(), SolverSpec::greedy())
+            .unwrap()
+            .with_recorded_at(1_700_000_000_000);
+        let json = bundle.to_json();
+        let restored = RunBundle::from_json(&json).unwrap();
+
+        // Hash-map ordering does not leak into the bytes.
+        assert_eq!(restored.to_json(), json);
+        assert_eq!(
+            restored.problem_fingerprint,
+            problem_fingerprint(&restored.problem)
+        );
+        assert_eq!(restored.kpi.makespan_ms, bundle.schedule.makespan_ms());
+        assert_eq!(restored.format_version, BUNDLE_FORMAT_VERSION);
+    }
+
+    #[test]
+    fn test_ga_replay_is_exact() {
+        let spec = SolverSpec::Ga {
+            options: SchedulingGaOptions::default(),
+            population_size: Some(12),
+            generations: Some(15),
+            time_limit_ms: Some(60_000),
+            generations_run: None,
+        };
+        let bundle = RunBundle::record(request(), spec).unwrap();
+        let SolverSpec::Ga {
+            options,
+            generations_run,
+            ..
+        } = &bundle.solver
+        else {
+            panic!("expected a GA spec");
+        };
+        assert!(options.seed.is_some());
+        assert!(generations_run.is_some());
+
+        let replay = RunBundle::from_json(&bundle.to_json())
+            .unwrap()
+            .replay()
+            .unwrap();
+        assert!(replay.is_reproduced());
+        assert!(replay.problem_intact);
+        assert!(replay.environment_differences.is_empty());
+    }
+
+    #[test]
+    fn test_replay_detects_edits() {
+        let mut bundle = RunBundle::record(request(), SolverSpec::cp()).unwrap();
+        bundle.problem.tasks[0].activities[0].duration.process_ms += 1000;
+        let replay = bundle.replay().unwrap();
+        assert!(!replay.problem_intact);
+        assert!(!replay.is_reproduced());
+
+        let mut other = RunEnvironment::current();
+        other.os = "plan9".into();
+        assert_eq!(RunEnvironment::current().differences(&other).len(), 1);
+    }
+}
@@ -8,11 +8,20 @@
 //! - Laborie et al. (2018), "IBM ILOG CP Optimizer for Scheduling"
 //! - Baptiste et al. (2001), "Constraint-Based Scheduling"
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use u_metaheur::cp::{CpModel, CpSolution, CpSolver, IntervalVar, Objective, SolverConfig};
+use u_metaheur::cp::{
+    Constraint as CpConstraint, CpModel, CpSolution, CpSolver, IntervalVar, Objective, SolverConfig,
+};
 
-use crate::models::{Assignment, Constraint, Resource, Schedule, Task, TransitionMatrixCollection};
+use crate::models::{
+    Activity, Assignment, Constraint, Lock, LockSet, PriorityClass, Resource, ResourceRequirement,
+    Schedule, SyncGroups, Task, ToolingRules, TransitionMatrixCollection, WeightProfile,
+};
+use crate::objective::ObjectiveTerm;
+use crate::precedence::PrecedenceBounds;
+use crate::scheduler::CapacityLeveling;
+use crate::validation::check_schedule;
 
 /// Builds a CP model from scheduling domain objects.
 ///
@@ -32,10 +41,14 @@ use crate::models::{Assignment, Constraint, Resource, Schedule, Task, Transition
 /// ```
 pub struct ScheduleCpBuilder<'a> {
     tasks: &'a [Task],
-    #[allow(dead_code)]
     resources: &'a [Resource],
     constraints: Vec<Constraint>,
     transition_matrices: TransitionMatrixCollection,
+    leveling: Option<CapacityLeveling>,
+    locks: LockSet,
+    weights: Option<WeightProfile>,
+    tooling: ToolingRules,
+    bound_tightening: bool,
 }
 
 impl<'a> ScheduleCpBuilder<'a> {
@@ -46,6 +59,11 @@ impl<'a> ScheduleCpBuilder<'a> {
             resources,
             constraints: Vec::new(),
             transition_matrices: TransitionMatrixCollection::new(),
+            leveling: None,
+            locks: LockSet::new(),
+            weights: None,
+            tooling: ToolingRules::new(),
+            bound_tightening: false,
         }
     }
 
@@ -61,45 +79,187 @@ impl<'a> ScheduleCpBuilder<'a> {
         self
     }
 
+    /// Limits concurrent use of a resource pool.
+    ///
+    /// Modeled as a `Cumulative` constraint over the derived demand
+    /// profile: every activity whose requirement can only be met from the
+    /// pool demands its quantity, and at most `leveling.limit` units may
+    /// be in use at any instant. This is the instantaneous form of the
+    /// per-bucket limit; the bucket length is not used.
+    pub fn with_leveling(mut self, leveling: CapacityLeveling) -> Self {
+        self.leveling = Some(leveling);
+        self
+    }
+
+    /// Sets locks.
+    ///
+    /// Activities of locked tasks and frozen activities become fixed
+    /// intervals at their locked times; locked activities keep a single
+    /// candidate; each reserved
+    /// window becomes a fixed interval `lock:<resource>:<n>` in the
+    /// resource's no-overlap set (and so counts toward the makespan).
+    pub fn with_locks(mut self, locks: LockSet) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Sets tooling rules.
+    ///
+    /// Each category's tool pool becomes a `Cumulative` constraint: the
+    /// activities of its tasks demand one tool each, and at most as many
+    /// run at once as the pool has tools among the resources. Transfer
+    /// delays and the choice of tool are not modeled.
+    pub fn with_tooling(mut self, tooling: ToolingRules) -> Self {
+        self.tooling = tooling;
+        self
+    }
+
+    /// Sets per-task tardiness rates.
+    ///
+    /// The model has no tardiness variables, so the end times of the final
+    /// activities of tasks with a deadline stand in for their tardiness,
+    /// each weighted by the task's cost per hour. Without a profile, tasks
+    /// with an explicit [`Task::tardiness_cost_per_hour`] still get a term
+    /// (the others at the default rate).
+    pub fn with_weight_profile(mut self, weights: WeightProfile) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    /// Narrows start domains with the precedence closure.
+    ///
+    /// Each unlocked activity starts no earlier than its earliest start
+    /// and no later than its latest start against the horizon (see
+    /// [`PrecedenceBounds`]); deadlines stay soft and do not bound starts.
+    /// The model keeps its solutions but gives the solver smaller domains.
+    /// Cyclic precedences leave the domains unchanged.
+    pub fn with_bound_tightening(mut self) -> Self {
+        self.bound_tightening = true;
+        self
+    }
+
     /// Builds a CP model with the given planning horizon.
     ///
     /// Creates:
-    /// - An `IntervalVar` per activity
+    /// - An `IntervalVar` per activity, ending by the task's
+    ///   [`flow_deadline`](Task::flow_deadline) if it has one
     /// - `NoOverlap` constraints per resource (from candidate assignments)
     /// - `Precedence` constraints for intra-task activity ordering
     /// - User-defined constraints
+    /// - `SameStart` constraints chaining the members of each group of
+    ///   synchronized activities ([`Activity::synchronized_with`] links and
+    ///   `Synchronize` constraints; see [`SyncGroups`])
+    ///
+    /// Precedence modes are lowered to end-to-start delays, which is exact
+    /// for fixed durations (e.g., start-to-start lag `L` becomes delay
+    /// `L − d(before)`).
+    /// - A `Cumulative` constraint for capacity leveling, if set, one per
+    ///   tool pool, and one per resource pool a requirement draws several
+    ///   units from (each activity on the pool demands its requirement's
+    ///   quantity of the pool's total capacity)
     /// - `MinimizeMaxEnd` objective (makespan minimization)
+    ///
+    /// When tasks span several priority classes, the objective becomes
+    /// `Hierarchical`: one `Minimize` per class (highest first) over the
+    /// end times of that class's final activities (terms name interval
+    /// variables), then `MinimizeMaxEnd`. Tardiness rates (see
+    /// [`with_weight_profile`](Self::with_weight_profile)) add a weighted
+    /// `Minimize` level just before `MinimizeMaxEnd`.
     pub fn build(&self, horizon_ms: i64) -> CpModel {
         let mut model = CpModel::new("scheduling", horizon_ms);
+        let locked_tasks = self.locks.locked_tasks();
+        let frozen = self.locks.frozen_activities();
+        let bounds = self
+            .bound_tightening
+            .then(|| PrecedenceBounds::compute_with(self.tasks, 0, Some(horizon_ms), false).ok())
+            .flatten();
 
         // Create interval variables for each activity
         for task in self.tasks {
             let release = task.release_time.unwrap_or(0);
 
+            if locked_tasks.contains(task.id.as_str()) {
+                for activity in &task.activities {
+                    if let Some(a) = self.locked_assignment(&activity.id) {
+                        model.add_interval(IntervalVar::new(
+                            &activity.id,
+                            a.start_ms,
+                            a.start_ms,
+                            a.duration_ms(),
+                            a.end_ms.max(horizon_ms),
+                        ));
+                    }
+                }
+                continue;
+            }
+
+            // A maximum flow time caps every activity's end.
+            let end_max = task
+                .flow_deadline()
+                .map_or(horizon_ms, |limit| limit.min(horizon_ms));
             for activity in &task.activities {
+                if frozen.contains(activity.id.as_str()) {
+                    if let Some(a) = self.locked_assignment(&activity.id) {
+                        model.add_interval(IntervalVar::new(
+                            &activity.id,
+                            a.start_ms,
+                            a.start_ms,
+                            a.duration_ms(),
+                            a.end_ms.max(horizon_ms),
+                        ));
+                        continue;
+                    }
+                }
                 let duration = activity.duration.process_ms;
+                let (mut start_min, mut start_max) = (release, end_max - duration);
+                if let Some(b) = bounds.as_ref().and_then(|b| b.get(&activity.id)) {
+                    start_min = start_min.max(b.earliest_start_ms);
+                    start_max = start_max.min(b.latest_start_ms);
+                }
                 let interval = IntervalVar::new(
                     &activity.id,
-                    release,               // start_min
-                    horizon_ms - duration, // start_max
-                    duration,              // fixed duration
-                    horizon_ms,            // end_max
+                    start_min, // release, or the earliest start
+                    start_max, // end_max − duration, or the latest start
+                    duration,  // fixed duration
+                    end_max,   // end_max
                 );
                 model.add_interval(interval);
             }
 
             // Intra-task precedence: activity[i] before activity[i+1]
-            for i in 0..task.activities.len().saturating_sub(1) {
-                model.add_precedence(
-                    task.activities[i].id.clone(),
-                    task.activities[i + 1].id.clone(),
-                    0,
+            for pair in task.activities.windows(2) {
+                let (prev, next) = (&pair[0], &pair[1]);
+                let delay = next.precedence_mode.end_to_start_delay(
+                    next.precedence_lag_ms,
+                    prev.duration.process_ms,
+                    next.duration.process_ms,
                 );
+                model.add_precedence(prev.id.clone(), next.id.clone(), delay);
             }
         }
 
         // No-overlap constraints per resource
-        let resource_activities = self.collect_resource_activities();
+        let mut resource_activities = self.collect_resource_activities();
+        for (i, lock) in self.locks.locks.iter().enumerate() {
+            if let Lock::ResourceWindow {
+                resource_id,
+                window,
+            } = lock
+            {
+                let name = format!("lock:{resource_id}:{i}");
+                model.add_interval(IntervalVar::new(
+                    &name,
+                    window.start_ms,
+                    window.start_ms,
+                    window.duration_ms(),
+                    window.end_ms.max(horizon_ms),
+                ));
+                resource_activities
+                    .entry(resource_id.clone())
+                    .or_default()
+                    .push(name);
+            }
+        }
         for activity_ids in resource_activities.values() {
             if activity_ids.len() > 1 {
                 model.add_no_overlap(activity_ids.clone());
@@ -107,14 +267,26 @@ impl<'a> ScheduleCpBuilder<'a> {
         }
 
         // User-defined constraints
+        let process_ms: HashMap<&str, i64> = self
+            .tasks
+            .iter()
+            .flat_map(|t| &t.activities)
+            .map(|a| (a.id.as_str(), a.duration.process_ms))
+            .collect();
         for constraint in &self.constraints {
             match constraint {
                 Constraint::Precedence {
                     before,
                     after,
                     min_delay_ms,
+                    mode,
                 } => {
-                    model.add_precedence(before.clone(), after.clone(), *min_delay_ms);
+                    let delay = mode.end_to_start_delay(
+                        *min_delay_ms,
+                        process_ms.get(before.as_str()).copied().unwrap_or(0),
+                        process_ms.get(after.as_str()).copied().unwrap_or(0),
+                    );
+                    model.add_precedence(before.clone(), after.clone(), delay);
                 }
                 Constraint::NoOverlap {
                     resource_id: _,
@@ -130,20 +302,203 @@ impl<'a> ScheduleCpBuilder<'a> {
                     // Simplified: skip (handled by no-overlap for capacity=1)
                     let _ = max_capacity;
                 }
+                Constraint::Synchronize { .. } => {
+                    // Grouped with the activity links below
+                }
                 _ => {
-                    // TimeWindow, TransitionCost, Synchronize — advanced constraints
+                    // TimeWindow, TransitionCost — advanced constraints
                     // Not yet supported by the simple CP formulation
                 }
             }
         }
+        let groups = SyncGroups::from_tasks(self.tasks).with_constraints(&self.constraints);
+        for group in groups.groups() {
+            for pair in group.windows(2) {
+                model.add_constraint(CpConstraint::SameStart {
+                    interval1: pair[0].clone(),
+                    interval2: pair[1].clone(),
+                });
+            }
+        }
+
+        if let Some((intervals, demands, capacity)) = self.leveling_demand() {
+            model.add_cumulative(intervals, demands, capacity);
+        }
+        for (intervals, tools) in self.tool_demand() {
+            let demands = vec![1; intervals.len()];
+            model.add_cumulative(intervals, demands, tools);
+        }
+        for (intervals, demands, capacity) in self.unit_demand() {
+            model.add_cumulative(intervals, demands, capacity);
+        }
 
-        // Objective: minimize makespan
-        model.set_objective(Objective::MinimizeMaxEnd);
+        model.set_objective(self.objective());
 
         model
     }
 
-    /// Solves the scheduling problem and returns a Schedule.
+    /// Activities drawing on the leveling pool, with their demands.
+    fn leveling_demand(&self) -> Option<(Vec<String>, Vec<i64>, i64)> {
+        let leveling = self.leveling.as_ref()?;
+        let pool: HashSet<&str> = self
+            .resources
+            .iter()
+            .filter(|r| leveling.in_pool(r))
+            .map(|r| r.id.as_str())
+            .collect();
+
+        let (intervals, demands): (Vec<String>, Vec<i64>) = self
+            .tasks
+            .iter()
+            .flat_map(|t| &t.activities)
+            .filter_map(|a| {
+                let demand: i64 = a
+                    .resource_requirements
+                    .iter()
+                    .filter(|req| {
+                        !req.candidates.is_empty()
+                            && req.candidates.iter().all(|c| pool.contains(c.as_str()))
+                    })
+                    .map(|req| i64::from(req.quantity))
+                    .sum();
+                (demand > 0).then(|| (a.id.clone(), demand))
+            })
+            .unzip();
+        if intervals.is_empty() {
+            return None;
+        }
+        Some((intervals, demands, leveling.limit as i64))
+    }
+
+    /// Activities of each tool pool's category with the pool size, for
+    /// pools that can run short.
+    fn tool_demand(&self) -> Vec<(Vec<String>, i64)> {
+        let known: HashSet<&str> = self.resources.iter().map(|r| r.id.as_str()).collect();
+        self.tooling
+            .requirements
+            .iter()
+            .filter_map(|req| {
+                let tools = req
+                    .tools
+                    .iter()
+                    .filter(|t| known.contains(t.as_str()))
+                    .count();
+                let intervals: Vec<String> = self
+                    .tasks
+                    .iter()
+                    .filter(|t| t.category == req.category)
+                    .flat_map(|t| t.activities.iter().map(|a| a.id.clone()))
+                    .collect();
+                (intervals.len() > tools).then_some((intervals, tools as i64))
+            })
+            .collect()
+    }
+
+    /// Activities drawing on each resource pool that some requirement
+    /// needs several units of, with their demands and the pool capacity.
+    fn unit_demand(&self) -> Vec<(Vec<String>, Vec<i64>, i64)> {
+        let locked_tasks = self.locks.locked_tasks();
+        let pool_of = |req: &'a ResourceRequirement| {
+            let mut pool = req.resolve(self.resources);
+            pool.sort_unstable();
+            pool.dedup();
+            pool
+        };
+        let activities = || {
+            self.tasks
+                .iter()
+                .filter(|t| !locked_tasks.contains(t.id.as_str()))
+                .flat_map(|t| &t.activities)
+        };
+        let mut pools: Vec<Vec<&str>> = activities()
+            .flat_map(|a| &a.resource_requirements)
+            .filter(|req| req.quantity > 1)
+            .map(pool_of)
+            .collect();
+        pools.sort();
+        pools.dedup();
+
+        pools
+            .into_iter()
+            .map(|pool| {
+                let capacity: i64 = self
+                    .resources
+                    .iter()
+                    .filter(|r| pool.contains(&r.id.as_str()))
+                    .map(|r| i64::from(r.capacity.max(1)))
+                    .sum();
+                let (intervals, demands) = activities()
+                    .filter_map(|a| {
+                        let demand: i64 = a
+                            .resource_requirements
+                            .iter()
+                            .filter(|req| pool_of(req) == pool)
+                            .map(|req| i64::from(req.quantity.max(1)))
+                            .sum();
+                        (demand > 0).then(|| (a.id.clone(), demand))
+                    })
+                    .unzip();
+                (intervals, demands, capacity)
+            })
+            .collect()
+    }
+
+    /// Makespan, preceded by lexicographic class completion and weighted
+    /// deadline-task completion when they apply.
+    fn objective(&self) -> Objective {
+        let weights = self.weights.clone().unwrap_or_default();
+        let class_terms: Vec<Vec<(String, f64)>> = PriorityClass::ALL
+            .iter()
+            .filter_map(|&class| {
+                ObjectiveTerm::ClassCompletion(class).completion_terms(self.tasks, &weights)
+            })
+            .filter(|terms| !terms.is_empty())
+            .collect();
+
+        let mut objectives: Vec<Objective> = Vec::new();
+        if class_terms.len() > 1 {
+            objectives.extend(
+                class_terms
+                    .into_iter()
+                    .map(|terms| Objective::Minimize { terms }),
+            );
+        }
+        let tardiness_terms = self.tardiness_terms(&weights);
+        if !tardiness_terms.is_empty() {
+            objectives.push(Objective::Minimize {
+                terms: tardiness_terms,
+            });
+        }
+        if objectives.is_empty() {
+            return Objective::MinimizeMaxEnd;
+        }
+        objectives.push(Objective::MinimizeMaxEnd);
+        Objective::Hierarchical { objectives }
+    }
+
+    /// [`ObjectiveTerm::TardinessCost`] over unlocked tasks (empty unless
+    /// a weight profile or a task rate is set).
+    fn tardiness_terms(&self, weights: &WeightProfile) -> Vec<(String, f64)> {
+        if self.weights.is_none()
+            && self
+                .tasks
+                .iter()
+                .all(|t| t.tardiness_cost_per_hour.is_none())
+        {
+            return Vec::new();
+        }
+        let locked_tasks = self.locks.locked_tasks();
+        let unlocked = self
+            .tasks
+            .iter()
+            .filter(|t| !locked_tasks.contains(t.id.as_str()));
+        ObjectiveTerm::TardinessCost
+            .completion_terms(unlocked, weights)
+            .unwrap_or_default()
+    }
+
+    /// Solves the scheduling problem and returns a Schedule carrying its
+    /// [`check_schedule`] violations.
     pub fn solve<S: CpSolver>(
         &self,
         solver: &S,
@@ -153,7 +508,9 @@ impl<'a> ScheduleCpBuilder<'a> {
         let model = self.build(horizon_ms);
         let solution = solver.solve(&model, config);
 
-        let schedule = self.decode_solution(&solution);
+        let mut schedule = self.decode_solution(&solution);
+        schedule.insert_conditioning(self.resources);
+        schedule.violations = check_schedule(&schedule, self.tasks, self.resources);
         (schedule, solution)
     }
 
@@ -165,24 +522,58 @@ impl<'a> ScheduleCpBuilder<'a> {
             return schedule;
         }
 
+        let locked_tasks = self.locks.locked_tasks();
+        let frozen = self.locks.frozen_activities();
         for task in self.tasks {
+            if locked_tasks.contains(task.id.as_str()) {
+                for a in self
+                    .locks
+                    .locked_assignments()
+                    .filter(|a| a.task_id == task.id)
+                {
+                    schedule.add_assignment(a.clone());
+                }
+                continue;
+            }
             for activity in &task.activities {
+                if frozen.contains(activity.id.as_str()) {
+                    for a in self.locks.frozen_assignments(&activity.id) {
+                        schedule.add_assignment(a.clone());
+                    }
+                    continue;
+                }
                 if let Some(interval_sol) = solution.intervals.get(&activity.id) {
                     if interval_sol.is_present {
                         // Determine resource (from candidates, pick first for now)
-                        let resource_id = activity
-                            .candidate_resources()
+                        let resource_id = self
+                            .candidates(activity)
                             .first()
                             .map(|s| s.to_string())
                             .unwrap_or_default();
 
-                        schedule.add_assignment(Assignment::new(
-                            &activity.id,
-                            &task.id,
-                            &resource_id,
-                            interval_sol.start,
-                            interval_sol.end,
-                        ));
+                        let mut held = vec![resource_id.as_str()];
+                        for req in activity.resource_requirements.iter() {
+                            let pool = req.resolve(self.resources);
+                            let mut units = pool.iter().filter(|id| held.contains(id)).count();
+                            for &id in &pool {
+                                if units >= req.quantity.max(1) as usize {
+                                    break;
+                                }
+                                if !held.contains(&id) {
+                                    held.push(id);
+                                    units += 1;
+                                }
+                            }
+                        }
+                        for id in held {
+                            schedule.add_assignment(Assignment::new(
+                                &activity.id,
+                                &task.id,
+                                id,
+                                interval_sol.start,
+                                interval_sol.end,
+                            ));
+                        }
                     }
                 }
             }
@@ -195,9 +586,20 @@ impl<'a> ScheduleCpBuilder<'a> {
     fn collect_resource_activities(&self) -> HashMap<String, Vec<String>> {
         let mut map: HashMap<String, Vec<String>> = HashMap::new();
 
+        let locked_tasks = self.locks.locked_tasks();
+        let frozen = self.locks.frozen_activities();
         for task in self.tasks {
             for activity in &task.activities {
-                for candidate in activity.candidate_resources() {
+                let candidates = if locked_tasks.contains(task.id.as_str())
+                    || frozen.contains(activity.id.as_str())
+                {
+                    self.locked_assignment(&activity.id)
+                        .map(|a| vec![a.resource_id.as_str()])
+                        .unwrap_or_default()
+                } else {
+                    self.candidates(activity)
+                };
+                for candidate in candidates {
                     map.entry(candidate.to_string())
                         .or_default()
                         .push(activity.id.clone());
@@ -207,12 +609,30 @@ impl<'a> ScheduleCpBuilder<'a> {
 
         map
     }
+
+    /// Candidate resources of an activity, narrowed by its lock.
+    fn candidates<'b>(&'b self, activity: &'b Activity) -> Vec<&'b str> {
+        let candidates = activity.resolved_candidates(self.resources);
+        match self.locks.resource_for(&activity.id) {
+            Some(locked) if candidates.contains(&locked) => vec![locked],
+            _ => candidates,
+        }
+    }
+
+    /// Processing assignment of an activity of a locked task, or of a
+    /// frozen activity.
+    fn locked_assignment(&self, activity_id: &str) -> Option<&Assignment> {
+        self.locks
+            .locked_assignments()
+            .filter(|a| a.activity_id == activity_id)
+            .max_by_key(|a| a.process_ms())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{Activity, ActivityDuration, ResourceRequirement, ResourceType};
+    use crate::models::{Activity, ActivityDuration, PrecedenceMode, ResourceType, ToolingRules};
     use u_metaheur::cp::SimpleCpSolver;
 
     fn make_test_data() -> (Vec<Task>, Vec<Resource>) {
@@ -257,6 +677,120 @@ mod tests {
         assert!(model.constraint_count() >= 2);
     }
 
+    #[test]
+    fn test_priority_class_objective() {
+        let (mut tasks, resources) = make_test_data();
+        let model = ScheduleCpBuilder::new(&tasks, &resources).build(100_000);
+        assert!(matches!(model.objective, Some(Objective::MinimizeMaxEnd)));
+
+        tasks[1].priority_class = PriorityClass::Rush;
+        let model = ScheduleCpBuilder::new(&tasks, &resources).build(100_000);
+        let Some(Objective::Hierarchical { objectives }) = model.objective else {
+            panic!("expected hierarchical objective");
+        };
+        assert_eq!(objectives.len(), 3);
+        assert!(matches!(
+            &objectives[0],
+            Objective::Minimize { terms } if terms == &vec![("T2_O1".to_string(), 1.0)]
+        ));
+        assert!(matches!(
+            &objectives[1],
+            Objective::Minimize { terms } if terms[0].0 == "T1_O2"
+        ));
+        assert!(matches!(objectives[2], Objective::MinimizeMaxEnd));
+    }
+
+    #[test]
+    fn test_weight_profile_objective() {
+        let (mut tasks, resources) = make_test_data();
+        tasks[0].deadline = Some(5000);
+        tasks[1].deadline = Some(5000);
+        tasks[1].attributes.insert("customer".into(), "ACME".into());
+
+        // Deadlines alone keep the plain makespan objective.
+        let model = ScheduleCpBuilder::new(&tasks, &resources).build(100_000);
+        assert!(matches!(model.objective, Some(Objective::MinimizeMaxEnd)));
+
+        let weights = WeightProfile::new().with_customer("ACME", 50.0);
+        let model = ScheduleCpBuilder::new(&tasks, &resources)
+            .with_weight_profile(weights)
+            .build(100_000);
+        let Some(Objective::Hierarchical { objectives }) = model.objective else {
+            panic!("expected hierarchical objective");
+        };
+        assert_eq!(objectives.len(), 2);
+        assert!(matches!(
+            &objectives[0],
+            Objective::Minimize { terms } if terms == &vec![
+                ("T1_O2".to_string(), 1.0),
+                ("T2_O1".to_string(), 50.0),
+            ]
+        ));
+        assert!(matches!(objectives[1], Objective::MinimizeMaxEnd));
+
+        // An explicit task rate is enough to add the level.
+        tasks[0].tardiness_cost_per_hour = Some(5.0);
+        let model = ScheduleCpBuilder::new(&tasks, &resources).build(100_000);
+        let Some(Objective::Hierarchical { objectives }) = model.objective else {
+            panic!("expected hierarchical objective");
+        };
+        assert!(matches!(
+            &objectives[0],
+            Objective::Minimize { terms } if terms[0] == ("T1_O2".to_string(), 5.0)
+        ));
+    }
+
+    #[test]
+    fn test_build_with_leveling() {
+        use u_metaheur::cp::Constraint as CpConstraint;
+
+        let (mut tasks, mut resources) = make_test_data();
+        resources.push(Resource::human("W1"));
+        resources.push(Resource::human("W2"));
+        tasks[1].activities[0] = tasks[1].activities[0].clone().with_requirement(
+            ResourceRequirement::new("Operator")
+                .with_quantity(2)
+                .with_candidates(vec!["W1".into(), "W2".into()]),
+        );
+
+        let model = ScheduleCpBuilder::new(&tasks, &resources)
+            .with_leveling(
+                CapacityLeveling::new(3_600_000, 1).with_resource_type(ResourceType::Human),
+            )
+            .build(100_000);
+        let cumulative: Vec<_> = model
+            .constraints
+            .iter()
+            .filter_map(|c| match c {
+                CpConstraint::Cumulative {
+                    intervals,
+                    demands,
+                    capacity,
+                } => Some((intervals.clone(), demands.clone(), *capacity)),
+                _ => None,
+            })
+            .collect();
+        // Leveling pool, then the two operators the activity holds.
+        assert_eq!(
+            cumulative,
+            vec![
+                (vec!["T2_O1".to_string()], vec![2], 1),
+                (vec!["T2_O1".to_string()], vec![2], 2),
+            ]
+        );
+
+        // No pool activities: no constraint.
+        let model = ScheduleCpBuilder::new(&tasks[..1], &resources)
+            .with_leveling(
+                CapacityLeveling::new(3_600_000, 1).with_resource_type(ResourceType::Human),
+            )
+            .build(100_000);
+        assert!(!model
+            .constraints
+            .iter()
+            .any(|c| matches!(c, CpConstraint::Cumulative { .. })));
+    }
+
     #[test]
     fn test_build_with_constraints() {
         let (tasks, resources) = make_test_data();
@@ -268,6 +802,44 @@ mod tests {
         assert!(model.constraint_count() >= 3);
     }
 
+    #[test]
+    fn test_precedence_modes_lowered_to_delays() {
+        use u_metaheur::cp::Constraint as CpConstraint;
+
+        let (mut tasks, resources) = make_test_data();
+        // T1_O2 (2000 ms) may start when T1_O1 (1000 ms) is half done.
+        tasks[0].activities[1].precedence_mode = PrecedenceMode::Overlap { percent: 50.0 };
+        let constraints = vec![Constraint::precedence_with_mode(
+            "T1_O2",
+            "T2_O1",
+            PrecedenceMode::FinishToFinish,
+            100,
+        )];
+        let model = ScheduleCpBuilder::new(&tasks, &resources)
+            .with_constraints(constraints)
+            .build(100_000);
+
+        let delays: Vec<(String, String, i64)> = model
+            .constraints
+            .iter()
+            .filter_map(|c| match c {
+                CpConstraint::Precedence {
+                    before,
+                    after,
+                    min_delay,
+                } => Some((before.clone(), after.clone(), *min_delay)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            delays,
+            vec![
+                ("T1_O1".into(), "T1_O2".into(), -500),
+                ("T1_O2".into(), "T2_O1".into(), 100 - 1500),
+            ]
+        );
+    }
+
     #[test]
     fn test_solve_basic() {
         let (tasks, resources) = make_test_data();
@@ -281,6 +853,131 @@ mod tests {
         assert!(schedule.makespan_ms() > 0);
     }
 
+    #[test]
+    fn test_type_based_requirement() {
+        let (mut tasks, mut resources) = make_test_data();
+        resources.push(Resource::new("M2", ResourceType::Primary));
+        for task in &mut tasks {
+            for activity in &mut task.activities {
+                activity.resource_requirements = vec![ResourceRequirement::new("Primary")];
+            }
+        }
+        let (schedule, _) = ScheduleCpBuilder::new(&tasks, &resources).solve(
+            &SimpleCpSolver::new(),
+            &SolverConfig::default(),
+            100_000,
+        );
+        assert_eq!(schedule.assignment_count(), 3);
+        assert!(schedule
+            .assignments
+            .iter()
+            .all(|a| a.resource_id == "M1" || a.resource_id == "M2"));
+    }
+
+    #[test]
+    fn test_max_flow_bounds_intervals() {
+        let (mut tasks, resources) = make_test_data();
+        tasks[1] = tasks[1].clone().with_release_time(1000).with_max_flow(2000);
+        let model = ScheduleCpBuilder::new(&tasks, &resources).build(100_000);
+        let t2 = &model.intervals["T2_O1"];
+        assert_eq!((t2.start.min, t2.start.max), (1000, 1500));
+        assert_eq!(t2.end.max, 3000);
+    }
+
+    #[test]
+    fn test_bound_tightening() {
+        let (mut tasks, resources) = make_test_data();
+        tasks[1].deadline = Some(100);
+        let plain = ScheduleCpBuilder::new(&tasks, &resources).build(10_000);
+        let o1 = &plain.intervals["T1_O1"];
+        assert_eq!((o1.start.min, o1.start.max), (0, 9000));
+
+        let model = ScheduleCpBuilder::new(&tasks, &resources)
+            .with_bound_tightening()
+            .build(10_000);
+        let bounds = |id: &str| {
+            let v = &model.intervals[id];
+            (v.start.min, v.start.max)
+        };
+        assert_eq!(bounds("T1_O1"), (0, 7000));
+        assert_eq!(bounds("T1_O2"), (1000, 8000));
+        // T2's deadline stays soft.
+        assert_eq!(bounds("T2_O1"), (0, 8500));
+    }
+
+    #[test]
+    fn test_tool_pool_adds_cumulative() {
+        let (tasks, mut resources) = make_test_data();
+        resources.push(Resource::new("M2", ResourceType::Primary));
+        resources.push(Resource::new("DIE", ResourceType::Secondary));
+        let tasks: Vec<Task> = tasks
+            .into_iter()
+            .map(|t| t.with_category("housing"))
+            .collect();
+        let plain = ScheduleCpBuilder::new(&tasks, &resources).build(100_000);
+        let builder = ScheduleCpBuilder::new(&tasks, &resources)
+            .with_tooling(ToolingRules::new().with_requirement("housing", vec!["DIE".into()]));
+        assert_eq!(builder.tool_demand()[0].0.len(), 3);
+        let model = builder.build(100_000);
+        assert_eq!(model.constraint_count(), plain.constraint_count() + 1);
+
+        // A pool at least as large as the demand adds nothing.
+        let spare = ScheduleCpBuilder::new(&tasks[1..], &resources)
+            .with_tooling(ToolingRules::new().with_requirement("housing", vec!["DIE".into()]));
+        assert!(spare.tool_demand().is_empty());
+    }
+
+    #[test]
+    fn test_synchronized_activities_same_start() {
+        let (mut tasks, resources) = make_test_data();
+        tasks[1].activities[0].synchronized_with = vec!["T1_O2".into()];
+        let model = ScheduleCpBuilder::new(&tasks, &resources)
+            .with_constraints(vec![Constraint::synchronize(vec![
+                "T1_O1".into(),
+                "T1_O2".into(),
+            ])])
+            .build(100_000);
+
+        let pairs: Vec<(&str, &str)> = model
+            .constraints
+            .iter()
+            .filter_map(|c| match c {
+                CpConstraint::SameStart {
+                    interval1,
+                    interval2,
+                } => Some((interval1.as_str(), interval2.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(pairs, vec![("T1_O1", "T1_O2"), ("T1_O2", "T2_O1")]);
+    }
+
+    #[test]
+    fn test_locks() {
+        let (tasks, resources) = make_test_data();
+        let mut manual = Schedule::new();
+        manual.add_assignment(Assignment::new("T2_O1", "T2", "M1", 0, 1500));
+        let locks = LockSet::new()
+            .with_lock(Lock::task(&manual, "T2"))
+            .with_lock(Lock::resource_window("M1", 1500, 2000));
+        let builder = ScheduleCpBuilder::new(&tasks, &resources).with_locks(locks);
+        let model = builder.build(100_000);
+
+        // The locked task and the reserved window are fixed intervals.
+        assert_eq!(model.interval_count(), 4);
+        let t2 = &model.intervals["T2_O1"];
+        assert_eq!((t2.start.min, t2.start.max), (0, 0));
+        let window = &model.intervals["lock:M1:1"];
+        assert_eq!((window.start.min, window.start.max), (1500, 1500));
+
+        // Locked tasks are decoded as locked, whatever the solver does.
+        let (schedule, solution) =
+            builder.solve(&SimpleCpSolver::new(), &SolverConfig::default(), 100_000);
+        assert!(solution.is_solution_found());
+        let t2 = schedule.assignment_for_activity("T2_O1").unwrap();
+        assert_eq!((t2.start_ms, t2.end_ms), (0, 1500));
+    }
+
     #[test]
     fn test_intra_task_precedence() {
         let (tasks, resources) = make_test_data();
@@ -1,6 +1,22 @@
 //! Scheduling context for dispatching rule evaluation.
+//!
+//! Contexts are built by hand with the `with_*` methods, or derived from
+//! a partial schedule with [`SchedulingContext::from_schedule`]:
+//!
+//! | Field | Derived as |
+//! |-------|------------|
+//! | `remaining_work` | Total duration of the task's unscheduled activities |
+//! | `next_queue_length` | Queue at the least loaded candidate of the task's next activity (WINQ) |
+//! | `resource_utilization` | Busy time over `max(current time, makespan)` (LPUL) |
+//!
+//! [`with_precedence_bounds`](SchedulingContext::with_precedence_bounds)
+//! adds the latest start of each task's next activity from the
+//! precedence closure, which MST then reads as the task's slack.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{Activity, Schedule, Task};
+use crate::precedence::PrecedenceBounds;
 
 /// Runtime scheduling state passed to dispatching rules.
 ///
@@ -22,6 +38,9 @@ pub struct SchedulingContext {
     pub arrival_times: HashMap<String, i64>,
     /// Average processing time across all tasks (for ATC normalization).
     pub average_processing_time: Option<f64>,
+    /// Latest start of each task's next activity (task_id → ms), from
+    /// the [precedence closure](crate::precedence).
+    pub latest_starts: HashMap<String, i64>,
 }
 
 impl SchedulingContext {
@@ -62,4 +81,217 @@ impl SchedulingContext {
         self.average_processing_time = Some(avg_ms);
         self
     }
+
+    /// Sets the latest start of a task's next activity.
+    pub fn with_latest_start(mut self, task_id: impl Into<String>, time_ms: i64) -> Self {
+        self.latest_starts.insert(task_id.into(), time_ms);
+        self
+    }
+
+    /// Sets the latest start of each task's first activity without an
+    /// assignment in `schedule` from `bounds`.
+    pub fn with_precedence_bounds(
+        mut self,
+        bounds: &PrecedenceBounds,
+        tasks: &[Task],
+        schedule: &Schedule,
+    ) -> Self {
+        for task in tasks {
+            let next = task
+                .activities
+                .iter()
+                .find(|a| schedule.assignment_for_activity(&a.id).is_none());
+            if let Some(latest) = next.and_then(|a| bounds.latest_start(&a.id)) {
+                self.latest_starts.insert(task.id.clone(), latest);
+            }
+        }
+        self
+    }
+
+    /// Derives a context at `current_time_ms` from a partial schedule and
+    /// the tasks (scheduled or not).
+    ///
+    /// An activity counts as scheduled once it has an assignment. A task's
+    /// chain of unscheduled activities, in order, is its route: the first
+    /// is the activity being dispatched, the second the one whose queue
+    /// WINQ looks at. The queue of a resource is the number of its
+    /// assignments not finished by `current_time_ms`, plus the other
+    /// tasks whose dispatched activity lists it as a candidate. Tasks
+    /// with no second activity get no queue entry (WINQ scores 0).
+    ///
+    /// Utilization covers every resource with an assignment or named as a
+    /// candidate by an unscheduled activity.
+    pub fn from_schedule(current_time_ms: i64, schedule: &Schedule, tasks: &[Task]) -> Self {
+        let scheduled: HashSet<&str> = schedule
+            .assignments
+            .iter()
+            .map(|a| a.activity_id.as_str())
+            .collect();
+        let routes: Vec<(&Task, Vec<&Activity>)> = tasks
+            .iter()
+            .map(|t| {
+                let pending = t
+                    .activities
+                    .iter()
+                    .filter(|a| !scheduled.contains(a.id.as_str()))
+                    .collect();
+                (t, pending)
+            })
+            .collect();
+
+        let mut context = Self::at_time(current_time_ms);
+
+        // Queues: unfinished assignments, then dispatchable activities
+        let mut queue: HashMap<&str, usize> = HashMap::new();
+        for a in schedule
+            .assignments
+            .iter()
+            .filter(|a| a.end_ms > current_time_ms)
+        {
+            *queue.entry(a.resource_id.as_str()).or_insert(0) += 1;
+        }
+        let mut waiting: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (task, pending) in &routes {
+            if let Some(first) = pending.first() {
+                for r in first.candidate_resources() {
+                    waiting.entry(r).or_default().push(task.id.as_str());
+                }
+            }
+        }
+
+        for (task, pending) in &routes {
+            if pending.is_empty() {
+                continue;
+            }
+            context.remaining_work.insert(
+                task.id.clone(),
+                pending.iter().map(|a| a.duration.total_ms()).sum(),
+            );
+            let Some(next) = pending.get(1) else {
+                continue;
+            };
+            let shortest = next
+                .candidate_resources()
+                .into_iter()
+                .map(|r| {
+                    let others = waiting
+                        .get(r)
+                        .map_or(0, |ids| ids.iter().filter(|&&id| id != task.id).count());
+                    queue.get(r).copied().unwrap_or(0) + others
+                })
+                .min();
+            if let Some(length) = shortest {
+                context.next_queue_length.insert(task.id.clone(), length);
+            }
+        }
+
+        // Utilization over the planned horizon
+        let horizon = schedule.makespan_ms().max(current_time_ms);
+        let resource_ids: HashSet<&str> = schedule
+            .assignments
+            .iter()
+            .map(|a| a.resource_id.as_str())
+            .chain(
+                routes
+                    .iter()
+                    .flat_map(|(_, pending)| pending.iter().flat_map(|a| a.candidate_resources())),
+            )
+            .collect();
+        for id in resource_ids {
+            let load = schedule.resource_utilization(id, horizon).unwrap_or(0.0);
+            context.resource_utilization.insert(id.to_string(), load);
+        }
+
+        context
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dispatching::rules::{Lpul, Winq};
+    use crate::dispatching::DispatchingRule;
+    use crate::models::{ActivityDuration, Assignment, ResourceRequirement};
+
+    fn op(id: &str, task: &str, seq: i32, ms: i64, candidates: &[&str]) -> Activity {
+        Activity::new(id, task, seq)
+            .with_duration(ActivityDuration::fixed(ms))
+            .with_requirement(
+                ResourceRequirement::new("Machine")
+                    .with_candidates(candidates.iter().map(|c| c.to_string()).collect()),
+            )
+    }
+
+    #[test]
+    fn test_from_schedule() {
+        let tasks = vec![
+            // J1: O1 done on M1; next dispatch O2 (M2), then O3 (M3)
+            Task::new("J1")
+                .with_activity(op("J1_O1", "J1", 0, 1000, &["M1"]))
+                .with_activity(op("J1_O2", "J1", 1, 2000, &["M2"]))
+                .with_activity(op("J1_O3", "J1", 2, 500, &["M3"])),
+            // J2: dispatch O1 (M3), then O2 (M1 or M2)
+            Task::new("J2")
+                .with_activity(op("J2_O1", "J2", 0, 700, &["M3"]))
+                .with_activity(op("J2_O2", "J2", 1, 300, &["M1", "M2"])),
+            // J3: single activity waiting for M2
+            Task::new("J3").with_activity(op("J3_O1", "J3", 0, 400, &["M2"])),
+        ];
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+        schedule.add_assignment(Assignment::new("X", "J0", "M2", 500, 1500));
+
+        let ctx = SchedulingContext::from_schedule(1000, &schedule, &tasks);
+
+        assert_eq!(ctx.remaining_work["J1"], 2500);
+        assert_eq!(ctx.remaining_work["J2"], 1000);
+        assert_eq!(ctx.remaining_work["J3"], 400);
+
+        // M3 queue for J1_O3: J2 waits on M3
+        assert_eq!(ctx.next_queue_length["J1"], 1);
+        // M1 is free and nobody else waits there; M2 has X unfinished,
+        // plus J1 and J3 waiting
+        assert_eq!(ctx.next_queue_length["J2"], 0);
+        assert!(!ctx.next_queue_length.contains_key("J3"));
+
+        assert_eq!(ctx.resource_utilization["M1"], 2.0 / 3.0);
+        assert_eq!(ctx.resource_utilization["M2"], 2.0 / 3.0);
+        assert_eq!(ctx.resource_utilization["M3"], 0.0);
+
+        // The rules read the derived maps
+        assert!(Winq.evaluate(&tasks[1], &ctx) < Winq.evaluate(&tasks[0], &ctx));
+        assert!(Lpul.evaluate(&tasks[1], &ctx) < Lpul.evaluate(&tasks[0], &ctx));
+    }
+
+    #[test]
+    fn test_from_empty_schedule() {
+        let tasks = vec![Task::new("J1").with_activity(op("J1_O1", "J1", 0, 1000, &["M1"]))];
+        let ctx = SchedulingContext::from_schedule(0, &Schedule::new(), &tasks);
+        assert_eq!(ctx.remaining_work["J1"], 1000);
+        assert!(ctx.next_queue_length.is_empty());
+        assert_eq!(ctx.resource_utilization["M1"], 0.0);
+    }
+
+    #[test]
+    fn test_precedence_bounds_slack() {
+        use crate::dispatching::rules::Mst;
+
+        // J1 has a loose deadline, but J2's urgent B waits for its A.
+        let tasks = vec![
+            Task::new("J1")
+                .with_deadline(100_000)
+                .with_activity(op("A", "J1", 0, 1000, &["M1"])),
+            Task::new("J2")
+                .with_deadline(2500)
+                .with_activity(op("B", "J2", 0, 1000, &["M2"]).with_predecessor("A")),
+        ];
+        let bounds = PrecedenceBounds::compute(&tasks, 0, Some(100_000)).unwrap();
+        let plain = SchedulingContext::from_schedule(0, &Schedule::new(), &tasks);
+        assert!(Mst.evaluate(&tasks[0], &plain) > Mst.evaluate(&tasks[1], &plain));
+
+        let ctx = plain.with_precedence_bounds(&bounds, &tasks, &Schedule::new());
+        assert_eq!(ctx.latest_starts["J1"], 500);
+        assert_eq!(ctx.latest_starts["J2"], 1500);
+        assert!(Mst.evaluate(&tasks[0], &ctx) < Mst.evaluate(&tasks[1], &ctx));
+    }
 }
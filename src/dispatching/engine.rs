@@ -3,16 +3,39 @@
 //! Composes multiple dispatching rules with configurable evaluation modes
 //! and tie-breaking strategies.
 //!
+//! # Ordering Guarantees
+//!
+//! Scores closer than a rule's epsilon should count as ties, but "closer
+//! than ε" is not transitive (a ≈ b and b ≈ c while a < c), which left
+//! the order of such tasks to the sort algorithm. Instead, each score is
+//! snapped to the nearest multiple of the rule's epsilon (default 1e-9,
+//! see [`RuleEngine::with_epsilon`] and
+//! [`RuleEngine::with_rule_epsilon`]) and snapped scores are compared
+//! exactly. Tasks tied on every rule and the final tie-breaker keep their
+//! input order (the sort is stable), so a ranking depends only on the
+//! scores and the input order, never on the sort's internals. NaN scores
+//! rank last.
+//!
+//! Built-in rules use IEEE 754 arithmetic, which is exact and identical
+//! on every platform, except ATC's `exp`, whose last bits may differ
+//! between math libraries. Such differences only change the order when a
+//! score lies on a snapping boundary; choose an epsilon well above the
+//! scores' rounding error (e.g. 1e-6 for ATC) and
+//! [`TieBreaker::ById`] for orders that are reproducible across machines.
+//!
 //! # Reference
 //! Haupt (1989), "A Survey of Priority Rule-Based Scheduling"
 
+use std::cmp::Ordering;
 use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+
 use super::{DispatchingRule, RuleScore, SchedulingContext};
 use crate::models::Task;
 
 /// How multiple rules are combined.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub enum EvaluationMode {
     /// Apply rules in sequence; use next rule only on ties.
     #[default]
@@ -22,19 +45,61 @@ pub enum EvaluationMode {
 }
 
 /// How ties are broken after all rules are exhausted.
-#[derive(Debug, Clone, Default)]
+///
+/// Tasks still tied keep their input order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub enum TieBreaker {
     /// Use the next rule in the chain (default).
     #[default]
     NextRule,
     /// Deterministic by task ID (lexicographic).
     ById,
+    /// Earliest release time first; tasks without one come first.
+    ByReleaseTime,
+    /// Earliest arrival first, from the context's
+    /// [`arrival_times`](SchedulingContext::arrival_times) or else the
+    /// release time (as in [`Fifo`](super::rules::Fifo)).
+    ByArrival,
+    /// Highest task priority first.
+    ByPriority,
+    /// A pseudo-random order, reproducible from the seed: each task's
+    /// place hashes the seed with its ID, so it does not depend on the
+    /// input order either.
+    SeededRandom {
+        /// Seed.
+        seed: u64,
+    },
+}
+
+impl TieBreaker {
+    /// Order of two tasks tied on every rule.
+    fn compare(&self, a: &Task, b: &Task, context: &SchedulingContext) -> Ordering {
+        let arrival = |t: &Task| {
+            context
+                .arrival_times
+                .get(&t.id)
+                .copied()
+                .unwrap_or_else(|| t.release_time.unwrap_or(0))
+        };
+        match self {
+            Self::NextRule => Ordering::Equal,
+            Self::ById => a.id.cmp(&b.id),
+            Self::ByReleaseTime => a.release_time.cmp(&b.release_time),
+            Self::ByArrival => arrival(a).cmp(&arrival(b)),
+            Self::ByPriority => b.priority.cmp(&a.priority),
+            Self::SeededRandom { seed } => {
+                seeded_hash(*seed, &[&a.id]).cmp(&seeded_hash(*seed, &[&b.id]))
+            }
+        }
+    }
 }
 
 #[derive(Clone)]
 struct WeightedRule {
     rule: Arc<dyn DispatchingRule>,
     weight: f64,
+    /// Tie tolerance overriding the engine's.
+    epsilon: Option<f64>,
 }
 
 /// A composable rule engine for task prioritization.
@@ -75,6 +140,7 @@ impl RuleEngine {
         self.rules.push(WeightedRule {
             rule: Arc::new(rule),
             weight: 1.0,
+            epsilon: None,
         });
         self
     }
@@ -88,6 +154,7 @@ impl RuleEngine {
         self.rules.push(WeightedRule {
             rule: Arc::new(rule),
             weight,
+            epsilon: None,
         });
         self
     }
@@ -97,6 +164,20 @@ impl RuleEngine {
         self.rules.push(WeightedRule {
             rule: Arc::new(rule),
             weight: 0.0,
+            epsilon: None,
+        });
+        self
+    }
+
+    /// Adds an already shared rule with an explicit weight.
+    ///
+    /// Used when rules are constructed at runtime (e.g., from a
+    /// [`RuleEngineSpec`](super::RuleEngineSpec)).
+    pub fn with_shared_rule(mut self, rule: Arc<dyn DispatchingRule>, weight: f64) -> Self {
+        self.rules.push(WeightedRule {
+            rule,
+            weight,
+            epsilon: None,
         });
         self
     }
@@ -113,33 +194,72 @@ impl RuleEngine {
         self
     }
 
+    /// Sets the tie tolerance of rules without their own and of the
+    /// weighted sum (default: 1e-9; negative values count as 0, i.e.
+    /// exact comparison).
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon.max(0.0);
+        self
+    }
+
+    /// Sets the tie tolerance of the most recently added rule, e.g. 1.0
+    /// to treat deadlines within a millisecond as equal (no effect on an
+    /// empty engine).
+    pub fn with_rule_epsilon(mut self, epsilon: f64) -> Self {
+        if let Some(last) = self.rules.last_mut() {
+            last.epsilon = Some(epsilon.max(0.0));
+        }
+        self
+    }
+
     /// Sorts tasks by priority (highest priority first).
     ///
     /// Returns indices into the original task slice, sorted by rule evaluation.
     pub fn sort_indices(&self, tasks: &[Task], context: &SchedulingContext) -> Vec<usize> {
+        let tasks: Vec<&Task> = tasks.iter().collect();
+        self.rank(&tasks, context)
+    }
+
+    /// Like [`sort_indices`](Self::sort_indices), over borrowed tasks
+    /// (e.g., the queue of one machine).
+    ///
+    /// Each rule is evaluated once per task, and its scores are snapped to
+    /// the rule's epsilon before comparison. Ties keep the input order.
+    pub fn rank(&self, tasks: &[&Task], context: &SchedulingContext) -> Vec<usize> {
         if tasks.is_empty() {
             return Vec::new();
         }
 
-        let mut indices: Vec<usize> = (0..tasks.len()).collect();
-
-        match &self.mode {
-            EvaluationMode::Sequential => {
-                indices.sort_by(|&a, &b| self.compare_sequential(&tasks[a], &tasks[b], context));
-            }
-            EvaluationMode::Weighted => {
-                let scores: Vec<f64> = tasks
-                    .iter()
-                    .map(|t| self.weighted_score(t, context))
-                    .collect();
-                indices.sort_by(|&a, &b| {
-                    scores[a]
-                        .partial_cmp(&scores[b])
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
-            }
-        }
+        // Snapped scores per task, compared in order
+        let keys: Vec<Vec<f64>> = match &self.mode {
+            EvaluationMode::Sequential => tasks
+                .iter()
+                .map(|t| {
+                    self.rules
+                        .iter()
+                        .map(|wr| {
+                            let epsilon = wr.epsilon.unwrap_or(self.epsilon);
+                            snap(wr.rule.evaluate(t, context), epsilon)
+                        })
+                        .collect()
+                })
+                .collect(),
+            EvaluationMode::Weighted => tasks
+                .iter()
+                .map(|t| vec![snap(self.weighted_score(t, context), self.epsilon)])
+                .collect(),
+        };
 
+        let mut indices: Vec<usize> = (0..tasks.len()).collect();
+        indices.sort_by(|&a, &b| {
+            keys[a]
+                .iter()
+                .zip(&keys[b])
+                .map(|(x, y)| x.total_cmp(y))
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| self.tie_breaker.compare(tasks[a], tasks[b], context))
+        });
         indices
     }
 
@@ -156,30 +276,6 @@ impl RuleEngine {
             .collect()
     }
 
-    fn compare_sequential(
-        &self,
-        a: &Task,
-        b: &Task,
-        context: &SchedulingContext,
-    ) -> std::cmp::Ordering {
-        for wr in &self.rules {
-            let score_a = wr.rule.evaluate(a, context);
-            let score_b = wr.rule.evaluate(b, context);
-
-            if (score_a - score_b).abs() > self.epsilon {
-                return score_a
-                    .partial_cmp(&score_b)
-                    .unwrap_or(std::cmp::Ordering::Equal);
-            }
-        }
-
-        // All rules tied → use final tie-breaker
-        match &self.tie_breaker {
-            TieBreaker::NextRule => std::cmp::Ordering::Equal,
-            TieBreaker::ById => a.id.cmp(&b.id),
-        }
-    }
-
     fn weighted_score(&self, task: &Task, context: &SchedulingContext) -> f64 {
         self.rules
             .iter()
@@ -188,6 +284,33 @@ impl RuleEngine {
     }
 }
 
+/// Index of the multiple of `epsilon` nearest to `score` (the score
+/// itself for 0 or ±∞); NaN becomes +∞.
+fn snap(score: f64, epsilon: f64) -> f64 {
+    if score.is_nan() {
+        f64::INFINITY
+    } else if epsilon > 0.0 && score.is_finite() {
+        (score / epsilon).round()
+    } else {
+        score
+    }
+}
+
+/// A pseudo-random 64-bit value from `seed` and `parts`, the same on
+/// every run and platform.
+pub(crate) fn seeded_hash(seed: u64, parts: &[&str]) -> u64 {
+    // FNV-1a over the parts, mixed with the seed by SplitMix64
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let bytes: Vec<&[u8]> = parts.iter().map(|p| p.as_bytes()).collect();
+    for byte in bytes.join(&0xff) {
+        hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+    }
+    let mut z = hash ^ seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
 impl Default for RuleEngine {
     fn default() -> Self {
         Self::new()
@@ -206,6 +329,7 @@ impl std::fmt::Debug for RuleEngine {
                     .collect::<Vec<_>>(),
             )
             .field("mode", &self.mode)
+            .field("epsilon", &self.epsilon)
             .finish()
     }
 }
@@ -317,6 +441,48 @@ mod tests {
         assert_eq!(tasks[indices[0]].id, "A");
     }
 
+    #[test]
+    fn test_tie_breaker_variants() {
+        let tasks = vec![
+            make_task("A", 1000, None, 1).with_release_time(2000),
+            make_task("B", 1000, None, 3),
+            make_task("C", 1000, None, 2).with_release_time(1000),
+        ];
+        let order = |tie_breaker: TieBreaker, ctx: &SchedulingContext| {
+            RuleEngine::new()
+                .with_rule(rules::Spt)
+                .with_final_tie_breaker(tie_breaker)
+                .sort_indices(&tasks, ctx)
+                .into_iter()
+                .map(|i| tasks[i].id.as_str())
+                .collect::<Vec<_>>()
+        };
+        let ctx = SchedulingContext::at_time(0);
+        assert_eq!(order(TieBreaker::NextRule, &ctx), ["A", "B", "C"]);
+        assert_eq!(order(TieBreaker::ByReleaseTime, &ctx), ["B", "C", "A"]);
+        assert_eq!(order(TieBreaker::ByPriority, &ctx), ["B", "C", "A"]);
+        // B arrived after A; C falls back to its release time
+        let arrived = SchedulingContext::at_time(0)
+            .with_arrival_time("A", 500)
+            .with_arrival_time("B", 1500);
+        assert_eq!(order(TieBreaker::ByArrival, &arrived), ["A", "C", "B"]);
+
+        let random = |seed| order(TieBreaker::SeededRandom { seed }, &ctx);
+        assert_eq!(random(7), random(7));
+        assert!((0..16).any(|seed| random(seed) != random(7)));
+        // Independent of the input order
+        let reversed: Vec<Task> = tasks.iter().rev().cloned().collect();
+        let engine = RuleEngine::new()
+            .with_rule(rules::Spt)
+            .with_final_tie_breaker(TieBreaker::SeededRandom { seed: 7 });
+        let ids: Vec<&str> = engine
+            .sort_indices(&reversed, &ctx)
+            .into_iter()
+            .map(|i| reversed[i].id.as_str())
+            .collect();
+        assert_eq!(ids, random(7));
+    }
+
     #[test]
     fn test_empty_tasks() {
         let ctx = SchedulingContext::at_time(0);
@@ -350,4 +516,52 @@ mod tests {
         assert!((scores[0] - 3000.0).abs() < 1e-10); // SPT score
         assert!((scores[1] - 20_000.0).abs() < 1e-10); // EDD score
     }
+
+    /// Fixed score per task ID.
+    #[derive(Debug)]
+    struct Scores(Vec<(&'static str, f64)>);
+
+    impl DispatchingRule for Scores {
+        fn name(&self) -> &'static str {
+            "Scores"
+        }
+
+        fn evaluate(&self, task: &Task, _context: &SchedulingContext) -> RuleScore {
+            self.0
+                .iter()
+                .find(|(id, _)| *id == task.id)
+                .map_or(0.0, |s| s.1)
+        }
+    }
+
+    #[test]
+    fn test_epsilon_snapping_is_stable() {
+        let tasks = vec![
+            make_task("C", 3000, None, 0),
+            make_task("B", 2000, None, 0),
+            make_task("A", 1000, None, 0),
+        ];
+        let ctx = SchedulingContext::at_time(0);
+        let rank = |a: f64, b: f64, c: f64, epsilon: f64| {
+            RuleEngine::new()
+                .with_rule(Scores(vec![("A", a), ("B", b), ("C", c)]))
+                .with_rule_epsilon(epsilon)
+                .with_tie_breaker(rules::Spt)
+                .sort_indices(&tasks, &ctx)
+        };
+        // Exact: B, A, C. With ε = 1, A and B snap to 10 and SPT decides.
+        assert_eq!(rank(10.4, 10.0, 10.8, 0.0), [1, 2, 0]);
+        assert_eq!(rank(10.4, 10.0, 10.8, 1.0), [2, 1, 0]);
+        // A ≈ B and B ≈ C within ε, but A < C: snapping still gives one
+        // consistent order (10 | 11, 11).
+        assert_eq!(rank(10.0, 10.6, 11.2, 1.0), [2, 1, 0]);
+
+        // Full ties keep the input order; NaN ranks last.
+        let fifo = RuleEngine::new().with_rule(rules::Fifo);
+        assert_eq!(fifo.sort_indices(&tasks, &ctx), [0, 1, 2]);
+        let nan = RuleEngine::new()
+            .with_rule(Scores(vec![("A", 1.0), ("B", 1.2), ("C", f64::NAN)]))
+            .with_epsilon(0.5);
+        assert_eq!(nan.sort_indices(&tasks, &ctx), [1, 2, 0]);
+    }
 }
@@ -25,9 +25,12 @@
 mod context;
 mod engine;
 pub mod rules;
+mod spec;
 
 pub use context::SchedulingContext;
+pub(crate) use engine::seeded_hash;
 pub use engine::{EvaluationMode, RuleEngine, TieBreaker};
+pub use spec::{RuleEngineSpec, RuleSpec, WeightedRuleSpec};
 
 use crate::models::Task;
 use std::fmt::Debug;
@@ -175,7 +175,13 @@ impl DispatchingRule for Edd {
 /// Slack = (deadline - current_time) - remaining_work.
 /// Prioritizes tasks with least slack (most urgent).
 ///
-/// Tasks without deadlines get maximum slack (lowest priority).
+/// With a [latest start](SchedulingContext::latest_starts) of the task's
+/// next activity, slack is latest_start - current_time instead: the
+/// slack along the longest path through the precedence graph, including
+/// other tasks' successors.
+///
+/// Tasks without deadlines or latest starts get maximum slack (lowest
+/// priority).
 #[derive(Debug, Clone, Copy)]
 pub struct Mst;
 
@@ -185,6 +191,9 @@ impl DispatchingRule for Mst {
     }
 
     fn evaluate(&self, task: &Task, context: &SchedulingContext) -> RuleScore {
+        if let Some(&latest) = context.latest_starts.get(&task.id) {
+            return (latest - context.current_time_ms) as f64;
+        }
         let deadline = match task.deadline {
             Some(d) => d,
             None => return f64::MAX,
@@ -307,6 +316,87 @@ impl Atc {
     pub fn with_k(k: f64) -> Self {
         Self { k }
     }
+
+    /// Creates an ATC rule with `k` chosen from the instance's due-date
+    /// statistics (see [`DueDateStatistics::atc_k`]).
+    ///
+    /// Falls back to the default `k` if no task has a deadline or the
+    /// tasks have no work.
+    pub fn tuned_for(tasks: &[Task], context: &SchedulingContext) -> Self {
+        DueDateStatistics::from_tasks(tasks, context)
+            .map_or_else(Self::default, |stats| Self::with_k(stats.atc_k()))
+    }
+}
+
+/// Due-date tightness of an instance, for tuning look-ahead rules.
+///
+/// With `C` the estimated makespan (current time plus the remaining work
+/// of all tasks, as on a single machine) and due dates measured from the
+/// current time:
+///
+/// | Statistic | Formula | Meaning |
+/// |-----------|---------|---------|
+/// | Tardiness factor τ | `1 − mean(d) / C` | Near 1: tight, most tasks late; ≤ 0: loose |
+/// | Due-date range R | `(max(d) − min(d)) / C` | Spread of the due dates |
+///
+/// # Reference
+/// Pinedo (2016), "Scheduling: Theory, Algorithms, and Systems", §14.2
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DueDateStatistics {
+    /// Tardiness factor τ.
+    pub tardiness_factor: f64,
+    /// Due-date range R.
+    pub due_date_range: f64,
+    /// Estimated makespan from the current time (ms).
+    pub makespan_estimate_ms: f64,
+}
+
+impl DueDateStatistics {
+    /// Computes the statistics over tasks with a deadline.
+    ///
+    /// Remaining work is taken from `context.remaining_work`, falling
+    /// back to each task's total duration. Returns `None` if no task has
+    /// a deadline or there is no remaining work.
+    pub fn from_tasks(tasks: &[Task], context: &SchedulingContext) -> Option<Self> {
+        let makespan: i64 = tasks
+            .iter()
+            .map(|t| {
+                context
+                    .remaining_work
+                    .get(&t.id)
+                    .copied()
+                    .unwrap_or_else(|| t.total_duration_ms())
+            })
+            .sum();
+        let due: Vec<f64> = tasks
+            .iter()
+            .filter_map(|t| t.deadline)
+            .map(|d| (d - context.current_time_ms) as f64)
+            .collect();
+        if makespan <= 0 || due.is_empty() {
+            return None;
+        }
+
+        let c = makespan as f64;
+        let mean = due.iter().sum::<f64>() / due.len() as f64;
+        let max = due.iter().copied().fold(f64::MIN, f64::max);
+        let min = due.iter().copied().fold(f64::MAX, f64::min);
+        Some(Self {
+            tardiness_factor: 1.0 - mean / c,
+            due_date_range: (max - min) / c,
+            makespan_estimate_ms: c,
+        })
+    }
+
+    /// ATC look-ahead parameter: `4.5 + R` for `R ≤ 0.5`, else `6 − 2R`.
+    ///
+    /// Wide due-date ranges call for a shorter look-ahead. The result is
+    /// kept at least 1.0 for very wide ranges.
+    pub fn atc_k(&self) -> f64 {
+        let r = self.due_date_range;
+        let k = if r <= 0.5 { 4.5 + r } else { 6.0 - 2.0 * r };
+        k.max(1.0)
+    }
 }
 
 impl DispatchingRule for Atc {
@@ -377,7 +467,8 @@ impl DispatchingRule for Fifo {
 /// Work In Next Queue.
 ///
 /// Prioritizes tasks whose next resource has the shortest queue.
-/// Uses `context.next_queue_length`.
+/// Uses `context.next_queue_length`, derived from a partial schedule by
+/// [`SchedulingContext::from_schedule`].
 #[derive(Debug, Clone, Copy)]
 pub struct Winq;
 
@@ -402,7 +493,8 @@ impl DispatchingRule for Winq {
 /// Least Planned Utilization Level.
 ///
 /// Prioritizes tasks whose candidate resources have the lowest utilization.
-/// Uses `context.resource_utilization` and `task.activities[0].resource_requirements`.
+/// Uses `context.resource_utilization` (see
+/// [`SchedulingContext::from_schedule`]) and `task.activities[0].resource_requirements`.
 #[derive(Debug, Clone, Copy)]
 pub struct Lpul;
 
@@ -590,6 +682,39 @@ mod tests {
         assert!(atc.evaluate(&urgent, &ctx) < atc.evaluate(&relaxed, &ctx));
     }
 
+    #[test]
+    fn test_atc_tuned_for() {
+        let ctx = SchedulingContext::at_time(0);
+        // Total work 4000; due dates 2000..4000 from now.
+        let tasks = vec![
+            make_task("a", 1000, Some(2000), 0),
+            make_task("b", 1000, Some(3000), 0),
+            make_task("c", 2000, Some(4000), 0),
+        ];
+        let stats = DueDateStatistics::from_tasks(&tasks, &ctx).unwrap();
+        assert_eq!(stats.makespan_estimate_ms, 4000.0);
+        assert!((stats.tardiness_factor - 0.25).abs() < 1e-12);
+        assert!((stats.due_date_range - 0.5).abs() < 1e-12);
+        assert!((Atc::tuned_for(&tasks, &ctx).k - 5.0).abs() < 1e-12);
+
+        // Wider range → shorter look-ahead
+        let wide = vec![
+            make_task("a", 1000, Some(0), 0),
+            make_task("b", 1000, Some(3000), 0),
+        ];
+        assert!((Atc::tuned_for(&wide, &ctx).k - 3.0).abs() < 1e-12);
+
+        // Remaining work from the context shrinks the horizon
+        let later = SchedulingContext::at_time(1000).with_remaining_work("c", 0);
+        let stats = DueDateStatistics::from_tasks(&tasks, &later).unwrap();
+        assert_eq!(stats.makespan_estimate_ms, 2000.0);
+
+        // No deadlines → default
+        let none = vec![make_task("a", 1000, None, 0)];
+        assert!(DueDateStatistics::from_tasks(&none, &ctx).is_none());
+        assert_eq!(Atc::tuned_for(&none, &ctx).k, Atc::default().k);
+    }
+
     #[test]
     fn test_atc_no_deadline() {
         let ctx = SchedulingContext::at_time(0);
@@ -0,0 +1,264 @@
+//! Serializable rule engine configuration.
+//!
+//! Dispatching rules are trait objects and cannot be deserialized directly.
+//! [`RuleSpec`] names a built-in rule (with its parameters), and
+//! [`RuleEngineSpec`] describes a complete engine, so rule selection can
+//! live in deployment configuration.
+//!
+//! # JSON Format
+//!
+//! ```json
+//! {
+//!   "mode": "Sequential",
+//!   "rules": [{ "rule": "EDD", "epsilon": 1.0 }, { "rule": "ATC", "k": 3.0, "weight": 0.5 }],
+//!   "tie_breakers": [{ "rule": "SPT" }],
+//!   "final_tie_breaker": "ById",
+//!   "epsilon": 1e-6
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::rules;
+use super::{DispatchingRule, EvaluationMode, RuleEngine, TieBreaker};
+
+/// A built-in dispatching rule, identified by its conventional name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "UPPERCASE")]
+pub enum RuleSpec {
+    /// Shortest Processing Time.
+    Spt,
+    /// Longest Processing Time.
+    Lpt,
+    /// Least Work Remaining.
+    Lwkr,
+    /// Most Work Remaining.
+    Mwkr,
+    /// Weighted Shortest Processing Time.
+    Wspt,
+    /// Earliest Due Date.
+    Edd,
+    /// Minimum Slack Time.
+    Mst,
+    /// Critical Ratio.
+    Cr,
+    /// Slack per Remaining Operations.
+    Sro,
+    /// Apparent Tardiness Cost with lookahead `k`.
+    Atc {
+        /// Lookahead parameter (default: 2.0).
+        #[serde(default = "default_atc_k")]
+        k: f64,
+    },
+    /// First In First Out.
+    Fifo,
+    /// Work In Next Queue.
+    Winq,
+    /// Least Planned Utilization Level.
+    Lpul,
+    /// Task priority.
+    Priority,
+}
+
+fn default_atc_k() -> f64 {
+    rules::Atc::default().k
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+impl RuleSpec {
+    /// Instantiates the rule.
+    pub fn build(&self) -> Arc<dyn DispatchingRule> {
+        match self {
+            Self::Spt => Arc::new(rules::Spt),
+            Self::Lpt => Arc::new(rules::Lpt),
+            Self::Lwkr => Arc::new(rules::Lwkr),
+            Self::Mwkr => Arc::new(rules::Mwkr),
+            Self::Wspt => Arc::new(rules::Wspt),
+            Self::Edd => Arc::new(rules::Edd),
+            Self::Mst => Arc::new(rules::Mst),
+            Self::Cr => Arc::new(rules::Cr),
+            Self::Sro => Arc::new(rules::Sro),
+            Self::Atc { k } => Arc::new(rules::Atc::with_k(*k)),
+            Self::Fifo => Arc::new(rules::Fifo),
+            Self::Winq => Arc::new(rules::Winq),
+            Self::Lpul => Arc::new(rules::Lpul),
+            Self::Priority => Arc::new(rules::Priority),
+        }
+    }
+}
+
+/// A rule with its weight in the engine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightedRuleSpec {
+    /// The rule.
+    #[serde(flatten)]
+    pub rule: RuleSpec,
+    /// Weight (used in `Weighted` mode; default: 1.0).
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    /// Tie tolerance (default: the engine's; see
+    /// [`RuleEngine::with_rule_epsilon`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epsilon: Option<f64>,
+}
+
+impl WeightedRuleSpec {
+    /// Creates a rule spec with weight 1.0.
+    pub fn new(rule: RuleSpec) -> Self {
+        Self {
+            rule,
+            weight: 1.0,
+            epsilon: None,
+        }
+    }
+
+    /// Sets the weight.
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Sets the tie tolerance.
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = Some(epsilon);
+        self
+    }
+}
+
+/// Serializable description of a [`RuleEngine`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleEngineSpec {
+    /// How rule scores are combined.
+    #[serde(default)]
+    pub mode: EvaluationMode,
+    /// Primary rules, in evaluation order.
+    pub rules: Vec<WeightedRuleSpec>,
+    /// Tie-breaking rules (weight 0.0, Sequential mode only).
+    #[serde(default)]
+    pub tie_breakers: Vec<RuleSpec>,
+    /// Final tie-breaking strategy.
+    #[serde(default)]
+    pub final_tie_breaker: TieBreaker,
+    /// Tie tolerance of rules without their own (default: 1e-9; see
+    /// [`RuleEngine::with_epsilon`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epsilon: Option<f64>,
+}
+
+impl RuleEngineSpec {
+    /// Creates a spec with a single primary rule.
+    pub fn single(rule: RuleSpec) -> Self {
+        Self {
+            rules: vec![WeightedRuleSpec::new(rule)],
+            ..Self::default()
+        }
+    }
+}
+
+impl RuleEngine {
+    /// Builds a rule engine from a serializable spec.
+    ///
+    /// # Example
+    /// ```
+    /// use u_schedule::dispatching::{RuleEngine, RuleEngineSpec, RuleSpec};
+    ///
+    /// let spec = RuleEngineSpec::single(RuleSpec::Atc { k: 3.0 });
+    /// let engine = RuleEngine::from_spec(&spec);
+    /// ```
+    pub fn from_spec(spec: &RuleEngineSpec) -> Self {
+        let mut engine = RuleEngine::new()
+            .with_mode(spec.mode.clone())
+            .with_final_tie_breaker(spec.final_tie_breaker.clone());
+        if let Some(epsilon) = spec.epsilon {
+            engine = engine.with_epsilon(epsilon);
+        }
+        for wr in &spec.rules {
+            engine = engine.with_shared_rule(wr.rule.build(), wr.weight);
+            if let Some(epsilon) = wr.epsilon {
+                engine = engine.with_rule_epsilon(epsilon);
+            }
+        }
+        for tb in &spec.tie_breakers {
+            engine = engine.with_shared_rule(tb.build(), 0.0);
+        }
+        engine
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dispatching::SchedulingContext;
+    use crate::models::{Activity, Task};
+
+    fn make_task(id: &str, duration_ms: i64, deadline: Option<i64>) -> Task {
+        let mut task = Task::new(id)
+            .with_activity(Activity::new(format!("{id}_O1"), id, 0).with_process_time(duration_ms));
+        task.deadline = deadline;
+        task
+    }
+
+    #[test]
+    fn test_rule_spec_json_roundtrip() {
+        let spec = RuleEngineSpec {
+            mode: EvaluationMode::Weighted,
+            rules: vec![
+                WeightedRuleSpec::new(RuleSpec::Edd).with_weight(0.7),
+                WeightedRuleSpec::new(RuleSpec::Atc { k: 3.0 })
+                    .with_weight(0.3)
+                    .with_epsilon(1e-6),
+            ],
+            tie_breakers: vec![RuleSpec::Spt],
+            final_tie_breaker: TieBreaker::ById,
+            epsilon: Some(0.5),
+        };
+        let json = serde_json::to_string(&spec).unwrap();
+        let back: RuleEngineSpec = serde_json::from_str(&json).unwrap();
+        assert_eq!(spec, back);
+    }
+
+    #[test]
+    fn test_parse_minimal_json() {
+        let json = r#"{ "rules": [{ "rule": "EDD" }, { "rule": "ATC" }], "tie_breakers": [{ "rule": "SPT" }] }"#;
+        let spec: RuleEngineSpec = serde_json::from_str(json).unwrap();
+        assert_eq!(spec.mode, EvaluationMode::Sequential);
+        assert_eq!(spec.rules[0].weight, 1.0);
+        assert_eq!(spec.rules[1].rule, RuleSpec::Atc { k: 2.0 });
+        assert_eq!(spec.final_tie_breaker, TieBreaker::NextRule);
+    }
+
+    #[test]
+    fn test_from_spec_orders_like_builder() {
+        let tasks = vec![
+            make_task("A", 2000, Some(10_000)),
+            make_task("B", 1000, Some(10_000)),
+            make_task("C", 1000, Some(5_000)),
+        ];
+        let ctx = SchedulingContext::at_time(0);
+
+        let spec = RuleEngineSpec {
+            rules: vec![WeightedRuleSpec::new(RuleSpec::Edd)],
+            tie_breakers: vec![RuleSpec::Spt],
+            ..RuleEngineSpec::default()
+        };
+        let from_spec = RuleEngine::from_spec(&spec).sort_indices(&tasks, &ctx);
+        let built = RuleEngine::new()
+            .with_rule(rules::Edd)
+            .with_tie_breaker(rules::Spt)
+            .sort_indices(&tasks, &ctx);
+
+        assert_eq!(from_spec, built);
+        assert_eq!(from_spec, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn test_unknown_rule_rejected() {
+        let json = r#"{ "rules": [{ "rule": "MAGIC" }] }"#;
+        assert!(serde_json::from_str::<RuleEngineSpec>(json).is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! Realized duration statistics (expected vs. actual).
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Activity, PertEstimate, Resource, Schedule, Task};
+
+/// Realized processing-time statistics for one (category, resource) pair.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DurationStats {
+    /// Task category.
+    pub category: String,
+    /// Resource ID.
+    pub resource_id: String,
+    /// Number of samples.
+    pub count: usize,
+    /// Mean actual processing time (ms).
+    pub mean_ms: f64,
+    /// Sample standard deviation (ms); 0 for a single sample.
+    pub std_dev_ms: f64,
+    /// 10th percentile (ms).
+    pub p10_ms: i64,
+    /// Median (ms).
+    pub median_ms: i64,
+    /// 90th percentile (ms).
+    pub p90_ms: i64,
+    /// Mean of actual / planned processing time (> 1 = slower than planned).
+    pub mean_ratio: f64,
+}
+
+impl DurationStats {
+    /// PERT estimate from the realized distribution (P10, median, P90).
+    ///
+    /// Percentiles rather than min/max keep single outliers from
+    /// stretching the estimate.
+    pub fn pert_estimate(&self) -> PertEstimate {
+        PertEstimate::new(self.p10_ms, self.median_ms, self.p90_ms)
+    }
+}
+
+/// Suggested efficiency for a resource.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EfficiencySuggestion {
+    /// Resource ID.
+    pub resource_id: String,
+    /// Efficiency currently configured.
+    pub current: f64,
+    /// Suggested efficiency: total planned / total actual processing time.
+    pub suggested: f64,
+    /// Number of samples.
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    planned_ms: i64,
+    actual_ms: i64,
+}
+
+/// Learns realized durations from as-built schedules.
+///
+/// Samples are keyed by (task category, resource). The actual processing
+/// time of an assignment is its duration minus setup; the planned one is
+/// the activity's `process_ms`. Assignments for unknown activities or
+/// with non-positive planned or actual time are ignored.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::execution::DurationEstimator;
+/// use u_schedule::models::{Activity, ActivityDuration, Assignment, Schedule, Task};
+///
+/// let tasks = vec![Task::new("J1").with_category("A").with_activity(
+///     Activity::new("O1", "J1", 0).with_duration(ActivityDuration::fixed(1000)),
+/// )];
+/// let mut as_built = Schedule::new();
+/// as_built.add_assignment(Assignment::new("O1", "J1", "M1", 0, 1200));
+///
+/// let mut estimator = DurationEstimator::new(&tasks);
+/// estimator.observe(&as_built);
+/// let stats = estimator.stats("A", "M1").unwrap();
+/// assert!((stats.mean_ratio - 1.2).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DurationEstimator {
+    /// Activity ID → (category, planned process time).
+    activities: HashMap<String, (String, i64)>,
+    samples: BTreeMap<(String, String), Vec<Sample>>,
+}
+
+impl DurationEstimator {
+    /// Creates an estimator for the activities of `tasks`.
+    pub fn new(tasks: &[Task]) -> Self {
+        let activities = tasks
+            .iter()
+            .flat_map(|t| {
+                t.activities.iter().map(move |a: &Activity| {
+                    (a.id.clone(), (t.category.clone(), a.duration.process_ms))
+                })
+            })
+            .collect();
+        Self {
+            activities,
+            samples: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the assignments of an as-built schedule as samples.
+    pub fn observe(&mut self, as_built: &Schedule) {
+        for a in &as_built.assignments {
+            let Some((category, planned_ms)) = self.activities.get(&a.activity_id) else {
+                continue;
+            };
+            let actual_ms = a.process_ms();
+            if *planned_ms <= 0 || actual_ms <= 0 {
+                continue;
+            }
+            self.samples
+                .entry((category.clone(), a.resource_id.clone()))
+                .or_default()
+                .push(Sample {
+                    planned_ms: *planned_ms,
+                    actual_ms,
+                });
+        }
+    }
+
+    /// Total number of samples.
+    pub fn sample_count(&self) -> usize {
+        self.samples.values().map(Vec::len).sum()
+    }
+
+    /// Statistics for one (category, resource) pair.
+    pub fn stats(&self, category: &str, resource_id: &str) -> Option<DurationStats> {
+        self.samples
+            .get(&(category.to_string(), resource_id.to_string()))
+            .map(|s| summarize(category, resource_id, s))
+    }
+
+    /// Statistics for all pairs with at least `min_samples` samples,
+    /// ordered by (category, resource).
+    pub fn all_stats(&self, min_samples: usize) -> Vec<DurationStats> {
+        self.samples
+            .iter()
+            .filter(|(_, s)| s.len() >= min_samples.max(1))
+            .map(|((c, r), s)| summarize(c, r, s))
+            .collect()
+    }
+
+    /// Efficiency suggestions for resources with at least `min_samples`
+    /// samples (across categories).
+    ///
+    /// A resource that runs 20% over plan gets `suggested ≈ 0.83`.
+    pub fn efficiency_suggestions(
+        &self,
+        resources: &[Resource],
+        min_samples: usize,
+    ) -> Vec<EfficiencySuggestion> {
+        let mut totals: BTreeMap<&str, (i64, i64, usize)> = BTreeMap::new();
+        for ((_, resource_id), samples) in &self.samples {
+            let entry = totals.entry(resource_id.as_str()).or_default();
+            for s in samples {
+                entry.0 += s.planned_ms;
+                entry.1 += s.actual_ms;
+                entry.2 += 1;
+            }
+        }
+        totals
+            .into_iter()
+            .filter(|(_, (_, _, n))| *n >= min_samples.max(1))
+            .map(|(resource_id, (planned, actual, n))| EfficiencySuggestion {
+                resource_id: resource_id.to_string(),
+                current: resources
+                    .iter()
+                    .find(|r| r.id == resource_id)
+                    .map(|r| r.efficiency)
+                    .unwrap_or(1.0),
+                suggested: planned as f64 / actual as f64,
+                samples: n,
+            })
+            .collect()
+    }
+}
+
+fn summarize(category: &str, resource_id: &str, samples: &[Sample]) -> DurationStats {
+    let n = samples.len();
+    let mut actual: Vec<i64> = samples.iter().map(|s| s.actual_ms).collect();
+    actual.sort_unstable();
+    let mean = actual.iter().sum::<i64>() as f64 / n as f64;
+    let std_dev = if n > 1 {
+        let ss: f64 = actual.iter().map(|&x| (x as f64 - mean).powi(2)).sum();
+        (ss / (n - 1) as f64).sqrt()
+    } else {
+        0.0
+    };
+    let mean_ratio = samples
+        .iter()
+        .map(|s| s.actual_ms as f64 / s.planned_ms as f64)
+        .sum::<f64>()
+        / n as f64;
+
+    DurationStats {
+        category: category.to_string(),
+        resource_id: resource_id.to_string(),
+        count: n,
+        mean_ms: mean,
+        std_dev_ms: std_dev,
+        p10_ms: percentile(&actual, 0.1),
+        median_ms: percentile(&actual, 0.5),
+        p90_ms: percentile(&actual, 0.9),
+        mean_ratio,
+    }
+}
+
+/// Nearest-rank percentile of a sorted, non-empty slice.
+fn percentile(sorted: &[i64], q: f64) -> i64 {
+    let rank = (q * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ActivityDuration, Assignment};
+
+    fn tasks() -> Vec<Task> {
+        (0..10)
+            .map(|i| {
+                let id = format!("J{i}");
+                Task::new(&id)
+                    .with_category(if i < 5 { "A" } else { "B" })
+                    .with_activity(
+                        Activity::new(format!("{id}_O1"), &id, 0)
+                            .with_duration(ActivityDuration::fixed(1000)),
+                    )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stats_per_category_and_resource() {
+        let mut as_built = Schedule::new();
+        // Category A on M1: 1000, 1100, 1200, 1300, 1400.
+        for i in 0..5 {
+            let d = 1000 + 100 * i;
+            as_built.add_assignment(Assignment::new(format!("J{i}_O1"), "", "M1", 0, d));
+        }
+        // Category B on M2: 900 each, preceded by 300 setup.
+        for i in 5..10 {
+            as_built.add_assignment(
+                Assignment::new(format!("J{i}_O1"), "", "M2", 0, 1200).with_setup(300),
+            );
+        }
+        as_built.add_assignment(Assignment::new("unknown", "", "M1", 0, 500));
+
+        let mut est = DurationEstimator::new(&tasks());
+        est.observe(&as_built);
+        assert_eq!(est.sample_count(), 10);
+
+        let a = est.stats("A", "M1").unwrap();
+        assert_eq!(a.count, 5);
+        assert!((a.mean_ms - 1200.0).abs() < 1e-9);
+        assert!((a.std_dev_ms - 158.113_883).abs() < 1e-5);
+        assert_eq!((a.p10_ms, a.median_ms, a.p90_ms), (1000, 1200, 1400));
+        assert!((a.mean_ratio - 1.2).abs() < 1e-9);
+        let pert = a.pert_estimate();
+        assert_eq!(
+            (pert.optimistic_ms, pert.most_likely_ms, pert.pessimistic_ms),
+            (1000, 1200, 1400)
+        );
+
+        let b = est.stats("B", "M2").unwrap();
+        assert_eq!(b.median_ms, 900);
+        assert_eq!(b.std_dev_ms, 0.0);
+        assert!(est.stats("A", "M2").is_none());
+    }
+
+    #[test]
+    fn test_all_stats_min_samples() {
+        let mut as_built = Schedule::new();
+        as_built.add_assignment(Assignment::new("J0_O1", "J0", "M1", 0, 1000));
+        as_built.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+        as_built.add_assignment(Assignment::new("J5_O1", "J5", "M1", 0, 1000));
+
+        let mut est = DurationEstimator::new(&tasks());
+        est.observe(&as_built);
+        assert_eq!(est.all_stats(1).len(), 2);
+        let frequent = est.all_stats(2);
+        assert_eq!(frequent.len(), 1);
+        assert_eq!(frequent[0].category, "A");
+    }
+
+    #[test]
+    fn test_efficiency_suggestions() {
+        let mut as_built = Schedule::new();
+        as_built.add_assignment(Assignment::new("J0_O1", "J0", "M1", 0, 1250));
+        as_built.add_assignment(Assignment::new("J5_O1", "J5", "M1", 0, 1250));
+        as_built.add_assignment(Assignment::new("J1_O1", "J1", "M2", 0, 800));
+
+        let mut est = DurationEstimator::new(&tasks());
+        est.observe(&as_built);
+        let resources = vec![Resource::primary("M1").with_efficiency(1.1)];
+        let suggestions = est.efficiency_suggestions(&resources, 1);
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].resource_id, "M1");
+        assert_eq!(suggestions[0].current, 1.1);
+        assert!((suggestions[0].suggested - 0.8).abs() < 1e-9);
+        assert_eq!(suggestions[0].samples, 2);
+        assert_eq!(suggestions[1].current, 1.0);
+        assert!((suggestions[1].suggested - 1.25).abs() < 1e-9);
+
+        assert_eq!(est.efficiency_suggestions(&resources, 2).len(), 1);
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        assert_eq!(percentile(&[5], 0.1), 5);
+        assert_eq!(percentile(&[1, 2, 3, 4], 0.5), 2);
+        assert_eq!(percentile(&[1, 2, 3, 4], 0.9), 4);
+    }
+}
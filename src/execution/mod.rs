@@ -0,0 +1,42 @@
+//! Plan execution tracking.
+//!
+//! Ingests actual start/finish events from the shop floor, maintains the
+//! as-built schedule alongside the plan, and measures schedule adherence.
+//! The tracker also derives a residual [`ScheduleRequest`] for
+//! rescheduling the work that has not started yet (optionally preempting
+//! running work for rush orders), and
+//! [`DurationEstimator`] and [`SetupMatrixLearner`] turn as-built
+//! schedules into data-driven duration estimates and setup matrices.
+//!
+//! # Usage
+//!
+//! ```
+//! use u_schedule::execution::{ExecutionEvent, ExecutionTracker};
+//! use u_schedule::models::{Assignment, Schedule};
+//!
+//! let mut plan = Schedule::new();
+//! plan.add_assignment(Assignment::new("O1", "J1", "M1", 0, 1000));
+//!
+//! let mut tracker = ExecutionTracker::new(plan);
+//! tracker.record(ExecutionEvent::started("O1", "M1", 200)).unwrap();
+//! tracker.record(ExecutionEvent::finished("O1", 1300)).unwrap();
+//!
+//! let kpi = tracker.adherence(0);
+//! assert_eq!(kpi.avg_start_deviation_ms, 200.0);
+//! ```
+//!
+//! # Reference
+//! Vieira, Herrmann & Lin (2003), "Rescheduling Manufacturing Systems:
+//! A Framework of Strategies, Policies, and Methods"
+//!
+//! [`ScheduleRequest`]: crate::scheduler::ScheduleRequest
+
+mod durations;
+mod preemption;
+mod setups;
+mod tracker;
+
+pub use durations::{DurationEstimator, DurationStats, EfficiencySuggestion};
+pub use preemption::{Preemption, PreemptionReport};
+pub use setups::{SetupMatrixLearner, TransitionSamples};
+pub use tracker::{ActivityStatus, AdherenceKpi, ExecutionError, ExecutionEvent, ExecutionTracker};
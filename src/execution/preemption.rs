@@ -0,0 +1,291 @@
+//! Preemption of running work for rush orders.
+//!
+//! [`ExecutionTracker::reschedule_request`] treats in-progress work as
+//! untouchable, so a Rush order waits until a machine frees up.
+//! [`ExecutionTracker::preemptive_reschedule_request`] instead interrupts
+//! a splittable activity in progress when a waiting Rush task needs its
+//! resource, and returns the remainder as work to resume later.
+//!
+//! # Rules
+//!
+//! A Rush task that is released, has nothing in progress and whose next
+//! activity finds every candidate resource busy frees one of them. The
+//! running activity is preempted only if it is
+//! [`splittable`](crate::models::Activity::splittable), belongs to a
+//! non-Rush task, and both the work done and the work remaining are at
+//! least its [`min_split_ms`](crate::models::Activity::min_split_ms).
+//! Among several candidates, the one busy longest is preempted.
+//!
+//! Work done is counted from the actual start, net of the planned setup.
+//! The remainder keeps its activity ID and is locked to its resource, so
+//! the as-built part and the resumed part form one
+//! [`SegmentedAssignment`].
+//!
+//! [`SegmentedAssignment`]: crate::models::SegmentedAssignment
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ActivityStatus, ExecutionTracker};
+use crate::models::{Activity, Lock, PriorityClass, Resource, Schedule, Task};
+use crate::scheduler::ScheduleRequest;
+
+/// An in-progress activity interrupted for a Rush task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preemption {
+    /// Interrupted activity.
+    pub activity_id: String,
+    /// Its task.
+    pub task_id: String,
+    /// Resource freed.
+    pub resource_id: String,
+    /// Rush task the resource was freed for.
+    pub rush_task_id: String,
+    /// Interruption time (ms).
+    pub at_ms: i64,
+    /// Processing done before the interruption (ms).
+    pub done_ms: i64,
+    /// Processing left to resume (ms).
+    pub remaining_ms: i64,
+    /// When the activity would have ended without preemption (ms).
+    pub expected_end_ms: i64,
+}
+
+/// Preemptions made for a reschedule, with their disruption.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreemptionReport {
+    /// Interrupted activities, in order of start event.
+    pub preemptions: Vec<Preemption>,
+}
+
+impl PreemptionReport {
+    /// Whether nothing was preempted.
+    pub fn is_empty(&self) -> bool {
+        self.preemptions.is_empty()
+    }
+
+    /// Disruption cost: total delay of the interrupted activities in
+    /// `rescheduled` past their expected end without preemption (ms).
+    ///
+    /// Includes any setup repeated on resumption; activities missing
+    /// from `rescheduled` count nothing.
+    pub fn disruption_ms(&self, rescheduled: &Schedule) -> i64 {
+        self.preemptions
+            .iter()
+            .filter_map(|p| {
+                let end = rescheduled
+                    .assignments
+                    .iter()
+                    .filter(|a| a.activity_id == p.activity_id)
+                    .map(|a| a.end_ms)
+                    .max()?;
+                Some((end - p.expected_end_ms).max(0))
+            })
+            .sum()
+    }
+}
+
+impl ExecutionTracker {
+    /// Like [`reschedule_request`](Self::reschedule_request), but running
+    /// splittable work of a non-Rush task is preempted for a released Rush
+    /// task whose next activity finds every candidate resource busy, if
+    /// both the work done and the work left reach the activity's
+    /// [`min_split_ms`](crate::models::Activity::min_split_ms).
+    ///
+    /// Preempted activities stay in the residual with their remaining
+    /// processing time, locked to their resource, which is no longer
+    /// reserved. The tracker still reports them in progress until their
+    /// finish event.
+    pub fn preemptive_reschedule_request(
+        &self,
+        tasks: &[Task],
+        resources: &[Resource],
+        now_ms: i64,
+    ) -> (ScheduleRequest, PreemptionReport) {
+        let activities: HashMap<&str, (&Task, &Activity)> = tasks
+            .iter()
+            .flat_map(|t| t.activities.iter().map(move |a| (a.id.as_str(), (t, a))))
+            .collect();
+        // Running work per resource: (activity, start, expected end).
+        let mut busy: HashMap<&str, (&str, i64, i64)> = HashMap::new();
+        for (activity_id, resource_id, start_ms) in self.running() {
+            let end = self.expected_end(activity_id, start_ms, now_ms);
+            if end > now_ms {
+                busy.insert(resource_id, (activity_id, start_ms, end));
+            }
+        }
+
+        let mut report = PreemptionReport::default();
+        for rush in tasks
+            .iter()
+            .filter(|t| t.priority_class == PriorityClass::Rush)
+            .filter(|t| t.release_time.is_none_or(|r| r <= now_ms))
+        {
+            let statuses: Vec<ActivityStatus> =
+                rush.activities.iter().map(|a| self.status(&a.id)).collect();
+            if statuses
+                .iter()
+                .any(|s| matches!(s, ActivityStatus::InProgress { .. }))
+            {
+                continue;
+            }
+            let Some(next) = rush
+                .activities
+                .iter()
+                .zip(&statuses)
+                .find(|(_, s)| **s == ActivityStatus::NotStarted)
+                .map(|(a, _)| a)
+            else {
+                continue;
+            };
+            let candidates = next.resolved_candidates(resources);
+            if candidates.is_empty() || candidates.iter().any(|c| !busy.contains_key(c)) {
+                continue;
+            }
+
+            let victim = candidates
+                .iter()
+                .filter_map(|&resource_id| {
+                    let (activity_id, start_ms, end) = busy[resource_id];
+                    let (task, activity) = activities.get(activity_id)?;
+                    let setup = self
+                        .plan()
+                        .assignment_for_activity(activity_id)
+                        .map_or(0, |a| a.setup_ms);
+                    let process = activity.duration.process_ms;
+                    let done = (now_ms - start_ms - setup).clamp(0, process);
+                    let remaining = process - done;
+                    let preemptible = activity.splittable
+                        && task.priority_class != PriorityClass::Rush
+                        && done > 0
+                        && remaining > 0
+                        && done >= activity.min_split_ms
+                        && remaining >= activity.min_split_ms;
+                    preemptible.then(|| Preemption {
+                        activity_id: activity_id.to_string(),
+                        task_id: task.id.clone(),
+                        resource_id: resource_id.to_string(),
+                        rush_task_id: rush.id.clone(),
+                        at_ms: now_ms,
+                        done_ms: done,
+                        remaining_ms: remaining,
+                        expected_end_ms: end,
+                    })
+                })
+                .max_by_key(|p| p.expected_end_ms);
+            if let Some(preemption) = victim {
+                busy.remove(preemption.resource_id.as_str());
+                report.preemptions.push(preemption);
+            }
+        }
+        let order: HashMap<&str, usize> = self
+            .running()
+            .enumerate()
+            .map(|(i, (id, _, _))| (id, i))
+            .collect();
+        report
+            .preemptions
+            .sort_by_key(|p| order[p.activity_id.as_str()]);
+
+        let remaining: HashMap<&str, i64> = report
+            .preemptions
+            .iter()
+            .map(|p| (p.activity_id.as_str(), p.remaining_ms))
+            .collect();
+        let mut request = self.residual_request(tasks, resources, now_ms, &remaining);
+        for p in &report.preemptions {
+            request.locks = request
+                .locks
+                .with_lock(Lock::activity_resource(&p.activity_id, &p.resource_id));
+        }
+        (request, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::ExecutionEvent;
+    use crate::models::{ActivityDuration, Assignment, ResourceRequirement};
+    use crate::scheduler::SimpleScheduler;
+
+    fn job(id: &str, ms: i64, class: PriorityClass) -> Task {
+        Task::new(id).with_priority_class(class).with_activity(
+            Activity::new(format!("{id}_O1"), id, 0)
+                .with_duration(ActivityDuration::fixed(ms))
+                .with_requirement(
+                    ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                )
+                .with_splitting(1000),
+        )
+    }
+
+    fn running_long_job() -> ExecutionTracker {
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("LONG_O1", "LONG", "M1", 0, 10_200).with_setup(200));
+        let mut tracker = ExecutionTracker::new(plan);
+        tracker
+            .record(ExecutionEvent::started("LONG_O1", "M1", 0))
+            .unwrap();
+        tracker
+    }
+
+    #[test]
+    fn test_rush_preempts_running_work() {
+        let tasks = vec![
+            job("LONG", 10_000, PriorityClass::Normal),
+            job("RUSH", 2000, PriorityClass::Rush),
+        ];
+        let resources = vec![Resource::primary("M1")];
+        let tracker = running_long_job();
+
+        let (request, report) = tracker.preemptive_reschedule_request(&tasks, &resources, 4200);
+        assert_eq!(report.preemptions.len(), 1);
+        let p = &report.preemptions[0];
+        assert_eq!(
+            (p.activity_id.as_str(), p.rush_task_id.as_str()),
+            ("LONG_O1", "RUSH")
+        );
+        assert_eq!((p.done_ms, p.remaining_ms), (4000, 6000));
+        assert_eq!(p.expected_end_ms, 10_200);
+        assert!(request.locks.blocked_windows().is_empty());
+
+        // The rush job runs now; the remainder resumes after it.
+        let rescheduled = SimpleScheduler::new().schedule_request(&request);
+        let rush = rescheduled.assignment_for_activity("RUSH_O1").unwrap();
+        assert_eq!((rush.start_ms, rush.end_ms), (4200, 6200));
+        let resumed = rescheduled.assignment_for_activity("LONG_O1").unwrap();
+        assert_eq!((resumed.start_ms, resumed.end_ms), (6200, 12_200));
+        assert_eq!(report.disruption_ms(&rescheduled), 2000);
+
+        // Without preemption the rush job waits for the expected end.
+        let waiting = tracker.reschedule_request(&tasks, &resources, 4200);
+        let rescheduled = SimpleScheduler::new().schedule_request(&waiting);
+        let rush = rescheduled.assignment_for_activity("RUSH_O1").unwrap();
+        assert_eq!((rush.start_ms, rush.end_ms), (10_200, 12_200));
+    }
+
+    #[test]
+    fn test_min_split_respected() {
+        let tasks = vec![
+            job("LONG", 10_000, PriorityClass::Normal),
+            job("RUSH", 2000, PriorityClass::Rush),
+        ];
+        let resources = vec![Resource::primary("M1")];
+        let tracker = running_long_job();
+
+        // 500 ms done, 9500 left: the done part is below the minimum split.
+        let (request, report) = tracker.preemptive_reschedule_request(&tasks, &resources, 700);
+        assert!(report.is_empty());
+        assert_eq!(request.tasks.len(), 1);
+
+        // Normal orders never preempt.
+        let normal = vec![
+            job("LONG", 10_000, PriorityClass::Normal),
+            job("NEXT", 2000, PriorityClass::Normal),
+        ];
+        let (_, report) = tracker.preemptive_reschedule_request(&normal, &resources, 4200);
+        assert!(report.is_empty());
+    }
+}
@@ -0,0 +1,312 @@
+//! Setup-time matrix learning from historical schedules.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Schedule, Task, TransitionMatrix, TransitionMatrixCollection};
+
+/// Observed changeovers for one (resource, from, to) triple.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransitionSamples {
+    /// Resource ID.
+    pub resource_id: String,
+    /// Category of the preceding activity.
+    pub from: String,
+    /// Category of the following activity.
+    pub to: String,
+    /// Samples kept after outlier filtering.
+    pub kept: usize,
+    /// Samples dropped as outliers.
+    pub dropped: usize,
+    /// Learned transition time: median of kept samples (ms).
+    pub estimate_ms: i64,
+}
+
+/// Infers [`TransitionMatrix`] entries from historical assignments.
+///
+/// For consecutive assignments on a resource, the observed changeover is
+/// the time from the end of the first to the start of processing of the
+/// second (`next.start + next.setup − prev.end`). Samples are grouped by
+/// the task categories of the pair, filtered, and summarized by their
+/// median.
+///
+/// # Outlier Filtering
+///
+/// 1. Gaps above `max_gap_ms` (idle time, shift breaks) are dropped.
+/// 2. Per pair, samples outside Tukey's fences
+///    `[Q1 − k·IQR, Q3 + k·IQR]` are dropped.
+/// 3. Pairs with fewer than `min_samples` kept samples are not emitted.
+///
+/// Each learned matrix's `default_ms` is the median of all kept
+/// cross-category samples on that resource, so unseen pairs get a
+/// plausible value.
+///
+/// # Reference
+/// Tukey (1977), "Exploratory Data Analysis"
+#[derive(Debug, Clone)]
+pub struct SetupMatrixLearner {
+    /// Activity ID → task category.
+    categories: HashMap<String, String>,
+    /// (resource, from, to) → raw samples (ms).
+    samples: BTreeMap<(String, String, String), Vec<i64>>,
+    min_samples: usize,
+    iqr_factor: f64,
+    max_gap_ms: Option<i64>,
+}
+
+impl SetupMatrixLearner {
+    /// Creates a learner for the activities of `tasks`.
+    ///
+    /// Defaults: `min_samples = 3`, `iqr_factor = 1.5`, no gap cap.
+    pub fn new(tasks: &[Task]) -> Self {
+        let categories = tasks
+            .iter()
+            .flat_map(|t| {
+                t.activities
+                    .iter()
+                    .map(|a| (a.id.clone(), t.category.clone()))
+            })
+            .collect();
+        Self {
+            categories,
+            samples: BTreeMap::new(),
+            min_samples: 3,
+            iqr_factor: 1.5,
+            max_gap_ms: None,
+        }
+    }
+
+    /// Sets the minimum kept samples for a pair to be learned.
+    pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples.max(1);
+        self
+    }
+
+    /// Sets Tukey's fence multiplier `k`.
+    pub fn with_iqr_factor(mut self, factor: f64) -> Self {
+        self.iqr_factor = factor;
+        self
+    }
+
+    /// Drops observed gaps longer than this (treated as idle time).
+    pub fn with_max_gap_ms(mut self, max_gap_ms: i64) -> Self {
+        self.max_gap_ms = Some(max_gap_ms);
+        self
+    }
+
+    /// Adds the consecutive assignment pairs of a historical schedule.
+    ///
+    /// Pairs involving activities without a known category, and
+    /// overlapping pairs (negative gap), are skipped.
+    pub fn observe(&mut self, history: &Schedule) {
+        let mut by_resource: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+        for a in &history.assignments {
+            by_resource
+                .entry(a.resource_id.as_str())
+                .or_default()
+                .push(a);
+        }
+        for (resource_id, mut list) in by_resource {
+            list.sort_by_key(|a| (a.start_ms, a.end_ms));
+            for pair in list.windows(2) {
+                let (prev, next) = (pair[0], pair[1]);
+                let (Some(from), Some(to)) = (
+                    self.categories.get(&prev.activity_id),
+                    self.categories.get(&next.activity_id),
+                ) else {
+                    continue;
+                };
+                let gap = next.start_ms + next.setup_ms - prev.end_ms;
+                if gap < 0 {
+                    continue;
+                }
+                self.samples
+                    .entry((resource_id.to_string(), from.clone(), to.clone()))
+                    .or_default()
+                    .push(gap);
+            }
+        }
+    }
+
+    /// Per-triple learning results (including pairs below `min_samples`),
+    /// ordered by (resource, from, to).
+    pub fn samples(&self) -> Vec<TransitionSamples> {
+        self.samples
+            .iter()
+            .filter_map(|((resource_id, from, to), raw)| {
+                let (kept, dropped) = self.filter(raw);
+                let estimate_ms = median(&kept)?;
+                Some(TransitionSamples {
+                    resource_id: resource_id.clone(),
+                    from: from.clone(),
+                    to: to.clone(),
+                    kept: kept.len(),
+                    dropped,
+                    estimate_ms,
+                })
+            })
+            .collect()
+    }
+
+    /// Learned transition matrices, one per observed resource.
+    pub fn learn(&self) -> TransitionMatrixCollection {
+        let mut matrices: BTreeMap<String, (TransitionMatrix, Vec<i64>)> = BTreeMap::new();
+        for ((resource_id, from, to), raw) in &self.samples {
+            let (kept, _) = self.filter(raw);
+            let Some(estimate) = median(&kept) else {
+                continue;
+            };
+            let (matrix, cross) = matrices.entry(resource_id.clone()).or_insert_with(|| {
+                (
+                    TransitionMatrix::new(format!("learned:{resource_id}"), resource_id),
+                    Vec::new(),
+                )
+            });
+            if from != to {
+                cross.extend_from_slice(&kept);
+            }
+            if kept.len() >= self.min_samples {
+                matrix.set_transition(from, to, estimate);
+            }
+        }
+
+        let mut collection = TransitionMatrixCollection::new();
+        for (_, (matrix, cross)) in matrices {
+            let default_ms = median(&cross).unwrap_or(0);
+            collection.add(matrix.with_default(default_ms));
+        }
+        collection
+    }
+
+    /// Applies the gap cap and Tukey's fences; returns sorted kept samples
+    /// and the number dropped.
+    fn filter(&self, raw: &[i64]) -> (Vec<i64>, usize) {
+        let mut kept: Vec<i64> = raw
+            .iter()
+            .copied()
+            .filter(|&g| self.max_gap_ms.is_none_or(|max| g <= max))
+            .collect();
+        kept.sort_unstable();
+        if kept.len() >= 4 {
+            let q1 = quantile(&kept, 0.25);
+            let q3 = quantile(&kept, 0.75);
+            let fence = self.iqr_factor * (q3 - q1);
+            let (lo, hi) = (q1 - fence, q3 + fence);
+            kept.retain(|&g| (g as f64) >= lo && (g as f64) <= hi);
+        }
+        let dropped = raw.len() - kept.len();
+        (kept, dropped)
+    }
+}
+
+/// Linear-interpolated quantile of a sorted, non-empty slice.
+fn quantile(sorted: &[i64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lo = pos.floor() as usize;
+    let hi = pos.ceil() as usize;
+    let frac = pos - lo as f64;
+    sorted[lo] as f64 + (sorted[hi] - sorted[lo]) as f64 * frac
+}
+
+/// Median of a sorted slice, rounded to ms.
+fn median(sorted: &[i64]) -> Option<i64> {
+    if sorted.is_empty() {
+        None
+    } else {
+        Some(quantile(sorted, 0.5).round() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, Assignment};
+
+    /// Tasks J0..J{n} alternating categories "A"/"B", one activity each.
+    fn tasks(n: usize) -> Vec<Task> {
+        (0..n)
+            .map(|i| {
+                let id = format!("J{i}");
+                Task::new(&id)
+                    .with_category(if i % 2 == 0 { "A" } else { "B" })
+                    .with_activity(Activity::new(format!("{id}_O1"), &id, 0))
+            })
+            .collect()
+    }
+
+    /// Sequence of J0..J{n} on M1, 100ms each, with given gaps between them.
+    fn history(gaps: &[i64]) -> Schedule {
+        let mut s = Schedule::new();
+        let mut t = 0;
+        for i in 0..=gaps.len() {
+            s.add_assignment(Assignment::new(format!("J{i}_O1"), "", "M1", t, t + 100));
+            t += 100 + gaps.get(i).copied().unwrap_or(0);
+        }
+        s
+    }
+
+    #[test]
+    fn test_learns_median_per_pair() {
+        // Alternating A→B (even index) and B→A (odd index).
+        let gaps = [10, 30, 12, 30, 14, 30, 12];
+        let mut learner = SetupMatrixLearner::new(&tasks(8));
+        learner.observe(&history(&gaps));
+
+        let m = learner.learn();
+        assert_eq!(m.len(), 1);
+        assert_eq!(m.get_transition_time("M1", "A", "B"), 12);
+        assert_eq!(m.get_transition_time("M1", "B", "A"), 30);
+        // Default: median of all cross-category samples.
+        let matrix = m.get("M1").unwrap();
+        assert_eq!(matrix.default_ms, 14);
+        assert_eq!(matrix.transition_count(), 2);
+    }
+
+    #[test]
+    fn test_setup_inside_assignment_counts() {
+        let tasks = tasks(2);
+        let mut s = Schedule::new();
+        s.add_assignment(Assignment::new("J0_O1", "", "M1", 0, 100));
+        s.add_assignment(Assignment::new("J1_O1", "", "M1", 110, 230).with_setup(20));
+        let mut learner = SetupMatrixLearner::new(&tasks).with_min_samples(1);
+        learner.observe(&s);
+        assert_eq!(learner.learn().get_transition_time("M1", "A", "B"), 30);
+    }
+
+    #[test]
+    fn test_outliers_are_dropped() {
+        // A→B gaps: 10, 11, 12, 11, 500 (idle) — interleaved B→A gaps of 5.
+        let gaps = [10, 5, 11, 5, 12, 5, 11, 5, 500];
+        let mut learner = SetupMatrixLearner::new(&tasks(10));
+        learner.observe(&history(&gaps));
+
+        let ab = learner
+            .samples()
+            .into_iter()
+            .find(|s| s.from == "A" && s.to == "B")
+            .unwrap();
+        assert_eq!((ab.kept, ab.dropped, ab.estimate_ms), (4, 1, 11));
+    }
+
+    #[test]
+    fn test_max_gap_and_min_samples() {
+        let gaps = [10, 5, 10, 5, 3_600_000];
+        let mut learner = SetupMatrixLearner::new(&tasks(6))
+            .with_max_gap_ms(60_000)
+            .with_min_samples(3);
+        learner.observe(&history(&gaps));
+
+        // A→B: 10, 10 (idle gap capped) → below min_samples; B→A: 5, 5 too.
+        let m = learner.learn();
+        assert_eq!(m.get("M1").unwrap().transition_count(), 0);
+        assert_eq!(m.get("M1").unwrap().default_ms, 8);
+
+        let ab = learner
+            .samples()
+            .into_iter()
+            .find(|s| s.from == "A")
+            .unwrap();
+        assert_eq!((ab.kept, ab.dropped), (2, 1));
+    }
+}
@@ -0,0 +1,606 @@
+//! Execution tracker: plan vs. as-built.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Assignment, Lock, LockSet, Resource, Schedule, Task};
+use crate::scheduler::ScheduleRequest;
+
+/// A shop-floor event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionEvent {
+    /// An activity actually started on a resource.
+    Started {
+        /// Activity ID.
+        activity_id: String,
+        /// Resource actually used (may differ from the plan).
+        resource_id: String,
+        /// Actual start time (ms).
+        time_ms: i64,
+    },
+    /// An activity actually finished.
+    Finished {
+        /// Activity ID.
+        activity_id: String,
+        /// Actual finish time (ms).
+        time_ms: i64,
+    },
+}
+
+impl ExecutionEvent {
+    /// Creates a start event.
+    pub fn started(
+        activity_id: impl Into<String>,
+        resource_id: impl Into<String>,
+        time_ms: i64,
+    ) -> Self {
+        Self::Started {
+            activity_id: activity_id.into(),
+            resource_id: resource_id.into(),
+            time_ms,
+        }
+    }
+
+    /// Creates a finish event.
+    pub fn finished(activity_id: impl Into<String>, time_ms: i64) -> Self {
+        Self::Finished {
+            activity_id: activity_id.into(),
+            time_ms,
+        }
+    }
+}
+
+/// Error for an event inconsistent with the recorded history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutionError {
+    /// The activity already has a start event.
+    AlreadyStarted(String),
+    /// A finish event arrived for an activity that never started.
+    NotStarted(String),
+    /// The activity already has a finish event.
+    AlreadyFinished(String),
+    /// The finish time precedes the recorded start time.
+    FinishBeforeStart {
+        /// Activity ID.
+        activity_id: String,
+        /// Recorded start (ms).
+        start_ms: i64,
+        /// Rejected finish (ms).
+        finish_ms: i64,
+    },
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyStarted(id) => write!(f, "activity {id} already started"),
+            Self::NotStarted(id) => write!(f, "activity {id} finished without starting"),
+            Self::AlreadyFinished(id) => write!(f, "activity {id} already finished"),
+            Self::FinishBeforeStart {
+                activity_id,
+                start_ms,
+                finish_ms,
+            } => write!(
+                f,
+                "activity {activity_id} finish {finish_ms} precedes start {start_ms}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExecutionError {}
+
+/// Execution state of an activity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityStatus {
+    /// No start event recorded.
+    NotStarted,
+    /// Started, not finished.
+    InProgress {
+        /// Actual start (ms).
+        start_ms: i64,
+    },
+    /// Started and finished.
+    Completed {
+        /// Actual start (ms).
+        start_ms: i64,
+        /// Actual finish (ms).
+        end_ms: i64,
+    },
+}
+
+/// Schedule adherence indicators (plan vs. actual).
+///
+/// Deviations are actual − planned, so positive means late.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdherenceKpi {
+    /// Planned activities with a start event.
+    pub started_count: usize,
+    /// Planned activities with a finish event.
+    pub completed_count: usize,
+    /// Fraction of started activities that started no later than
+    /// planned start + tolerance (1.0 if none started).
+    pub on_time_start_rate: f64,
+    /// Mean start deviation (ms).
+    pub avg_start_deviation_ms: f64,
+    /// Mean absolute start deviation (ms).
+    pub avg_abs_start_deviation_ms: f64,
+    /// Mean finish deviation of completed activities (ms).
+    pub avg_finish_deviation_ms: f64,
+    /// Started activities run on a different resource than planned.
+    pub resource_changes: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Actual {
+    resource_id: String,
+    start_ms: i64,
+    end_ms: Option<i64>,
+}
+
+/// Tracks actual execution against a planned schedule.
+///
+/// Events for activities not in the plan are accepted and appear in the
+/// as-built schedule, but do not count toward adherence.
+#[derive(Debug, Clone)]
+pub struct ExecutionTracker {
+    plan: Schedule,
+    actuals: HashMap<String, Actual>,
+    /// Activity IDs in order of first start event.
+    order: Vec<String>,
+}
+
+impl ExecutionTracker {
+    /// Creates a tracker for a plan.
+    pub fn new(plan: Schedule) -> Self {
+        Self {
+            plan,
+            actuals: HashMap::new(),
+            order: Vec::new(),
+        }
+    }
+
+    /// The planned schedule.
+    pub fn plan(&self) -> &Schedule {
+        &self.plan
+    }
+
+    /// Records an event.
+    ///
+    /// # Errors
+    /// Rejects events inconsistent with the history (double start,
+    /// finish without start, double finish, finish before start).
+    pub fn record(&mut self, event: ExecutionEvent) -> Result<(), ExecutionError> {
+        match event {
+            ExecutionEvent::Started {
+                activity_id,
+                resource_id,
+                time_ms,
+            } => {
+                if self.actuals.contains_key(&activity_id) {
+                    return Err(ExecutionError::AlreadyStarted(activity_id));
+                }
+                self.order.push(activity_id.clone());
+                self.actuals.insert(
+                    activity_id,
+                    Actual {
+                        resource_id,
+                        start_ms: time_ms,
+                        end_ms: None,
+                    },
+                );
+                Ok(())
+            }
+            ExecutionEvent::Finished {
+                activity_id,
+                time_ms,
+            } => {
+                let actual = self
+                    .actuals
+                    .get_mut(&activity_id)
+                    .ok_or_else(|| ExecutionError::NotStarted(activity_id.clone()))?;
+                if actual.end_ms.is_some() {
+                    return Err(ExecutionError::AlreadyFinished(activity_id));
+                }
+                if time_ms < actual.start_ms {
+                    return Err(ExecutionError::FinishBeforeStart {
+                        activity_id,
+                        start_ms: actual.start_ms,
+                        finish_ms: time_ms,
+                    });
+                }
+                actual.end_ms = Some(time_ms);
+                Ok(())
+            }
+        }
+    }
+
+    /// Records a sequence of events, stopping at the first error.
+    pub fn record_all(
+        &mut self,
+        events: impl IntoIterator<Item = ExecutionEvent>,
+    ) -> Result<(), ExecutionError> {
+        events.into_iter().try_for_each(|e| self.record(e))
+    }
+
+    /// Execution state of an activity.
+    pub fn status(&self, activity_id: &str) -> ActivityStatus {
+        match self.actuals.get(activity_id) {
+            None => ActivityStatus::NotStarted,
+            Some(Actual {
+                start_ms,
+                end_ms: None,
+                ..
+            }) => ActivityStatus::InProgress {
+                start_ms: *start_ms,
+            },
+            Some(Actual {
+                start_ms,
+                end_ms: Some(end_ms),
+                ..
+            }) => ActivityStatus::Completed {
+                start_ms: *start_ms,
+                end_ms: *end_ms,
+            },
+        }
+    }
+
+    /// The as-built schedule: completed activities with actual times.
+    ///
+    /// Task IDs are taken from the plan (empty for unplanned activities).
+    pub fn as_built(&self) -> Schedule {
+        let mut schedule = Schedule::new();
+        for id in &self.order {
+            let actual = &self.actuals[id];
+            if let Some(end_ms) = actual.end_ms {
+                let task_id = self
+                    .plan
+                    .assignment_for_activity(id)
+                    .map(|a| a.task_id.as_str())
+                    .unwrap_or("");
+                schedule.add_assignment(Assignment::new(
+                    id,
+                    task_id,
+                    &actual.resource_id,
+                    actual.start_ms,
+                    end_ms,
+                ));
+            }
+        }
+        schedule
+    }
+
+    /// Schedule adherence KPIs.
+    ///
+    /// Each planned activity counts once, however many assignments it
+    /// has (split segments, helper resources): its planned start and
+    /// finish span all of them, and its planned resource is that of its
+    /// first assignment.
+    ///
+    /// # Arguments
+    /// * `tolerance_ms` - Allowed start delay that still counts as on time.
+    pub fn adherence(&self, tolerance_ms: i64) -> AdherenceKpi {
+        let mut started = 0usize;
+        let mut on_time = 0usize;
+        let mut start_dev = 0i64;
+        let mut abs_start_dev = 0i64;
+        let mut completed = 0usize;
+        let mut finish_dev = 0i64;
+        let mut resource_changes = 0usize;
+
+        // Activity → (primary resource, planned start, planned end).
+        let mut planned: HashMap<&str, (&str, i64, i64)> = HashMap::new();
+        let mut activities = Vec::new();
+        for a in &self.plan.assignments {
+            planned
+                .entry(a.activity_id.as_str())
+                .and_modify(|(_, start, end)| {
+                    *start = (*start).min(a.start_ms);
+                    *end = (*end).max(a.end_ms);
+                })
+                .or_insert_with(|| {
+                    activities.push(a.activity_id.as_str());
+                    (a.resource_id.as_str(), a.start_ms, a.end_ms)
+                });
+        }
+
+        for id in activities {
+            let Some(actual) = self.actuals.get(id) else {
+                continue;
+            };
+            let (resource_id, start_ms, end_ms) = planned[id];
+            started += 1;
+            let deviation = actual.start_ms - start_ms;
+            start_dev += deviation;
+            abs_start_dev += deviation.abs();
+            if deviation <= tolerance_ms {
+                on_time += 1;
+            }
+            if actual.resource_id != resource_id {
+                resource_changes += 1;
+            }
+            if let Some(actual_end) = actual.end_ms {
+                completed += 1;
+                finish_dev += actual_end - end_ms;
+            }
+        }
+
+        let mean = |sum: i64, n: usize| if n == 0 { 0.0 } else { sum as f64 / n as f64 };
+        AdherenceKpi {
+            started_count: started,
+            completed_count: completed,
+            on_time_start_rate: if started == 0 {
+                1.0
+            } else {
+                on_time as f64 / started as f64
+            },
+            avg_start_deviation_ms: mean(start_dev, started),
+            avg_abs_start_deviation_ms: mean(abs_start_dev, started),
+            avg_finish_deviation_ms: mean(finish_dev, completed),
+            resource_changes,
+        }
+    }
+
+    /// Builds the residual problem for rescheduling at `now_ms`.
+    ///
+    /// - Started activities (completed or in progress) are removed; tasks
+    ///   with no remaining activities are dropped.
+    /// - A task with an in-progress activity is released no earlier than
+    ///   that activity's expected end (actual start + planned duration,
+    ///   but not before `now_ms`).
+    /// - Resources running an in-progress activity are reserved with a
+    ///   [`Lock::ResourceWindow`] until its expected end.
+    pub fn reschedule_request(
+        &self,
+        tasks: &[Task],
+        resources: &[Resource],
+        now_ms: i64,
+    ) -> ScheduleRequest {
+        self.residual_request(tasks, resources, now_ms, &HashMap::new())
+    }
+
+    /// In-progress activities as `(activity_id, resource_id, start_ms)`,
+    /// in order of start event.
+    pub(super) fn running(&self) -> impl Iterator<Item = (&str, &str, i64)> {
+        self.order.iter().filter_map(|id| {
+            let actual = &self.actuals[id];
+            actual.end_ms.is_none().then_some((
+                id.as_str(),
+                actual.resource_id.as_str(),
+                actual.start_ms,
+            ))
+        })
+    }
+
+    /// Expected end of an activity started at `start_ms`: start plus
+    /// planned duration, but not before `now_ms`.
+    pub(super) fn expected_end(&self, activity_id: &str, start_ms: i64, now_ms: i64) -> i64 {
+        let planned = self
+            .plan
+            .assignment_for_activity(activity_id)
+            .map(|a| a.duration_ms())
+            .unwrap_or(0);
+        (start_ms + planned).max(now_ms)
+    }
+
+    /// Residual problem at `now_ms`, keeping the in-progress activities of
+    /// `preempted` (activity ID → remaining processing ms) as unstarted
+    /// work with their remaining time instead of blocking their resource.
+    pub(super) fn residual_request(
+        &self,
+        tasks: &[Task],
+        resources: &[Resource],
+        now_ms: i64,
+        preempted: &HashMap<&str, i64>,
+    ) -> ScheduleRequest {
+        let expected_end =
+            |id: &str, actual: &Actual| self.expected_end(id, actual.start_ms, now_ms);
+
+        let mut resource_busy_until: HashMap<&str, i64> = HashMap::new();
+        for (id, actual) in &self.actuals {
+            if actual.end_ms.is_none() && !preempted.contains_key(id.as_str()) {
+                let end = expected_end(id, actual);
+                let busy = resource_busy_until
+                    .entry(actual.resource_id.as_str())
+                    .or_insert(end);
+                *busy = (*busy).max(end);
+            }
+        }
+
+        let mut remaining = Vec::new();
+        for task in tasks {
+            let mut release = task.release_time.unwrap_or(now_ms).max(now_ms);
+            let mut residual = task.clone();
+            residual
+                .activities
+                .retain_mut(|activity| match self.actuals.get(&activity.id) {
+                    None => true,
+                    Some(actual) => {
+                        if let Some(&remaining) = preempted.get(activity.id.as_str()) {
+                            activity.duration.process_ms = remaining;
+                            return true;
+                        }
+                        if actual.end_ms.is_none() {
+                            release = release.max(expected_end(&activity.id, actual));
+                        }
+                        false
+                    }
+                });
+            if !residual.activities.is_empty() {
+                residual.release_time = Some(release);
+                remaining.push(residual);
+            }
+        }
+
+        let mut locks = LockSet::new();
+        for r in resources {
+            match resource_busy_until.get(r.id.as_str()) {
+                Some(&until) if until > now_ms => {
+                    locks = locks.with_lock(Lock::resource_window(&r.id, now_ms, until));
+                }
+                _ => {}
+            }
+        }
+
+        ScheduleRequest::new(remaining, resources.to_vec())
+            .with_start_time(now_ms)
+            .with_locks(locks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, ActivityDuration, ResourceRequirement};
+    use crate::scheduler::SimpleScheduler;
+
+    fn plan() -> Schedule {
+        let mut s = Schedule::new();
+        s.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+        s.add_assignment(Assignment::new("J1_O2", "J1", "M2", 1000, 3000));
+        s.add_assignment(Assignment::new("J2_O1", "J2", "M1", 1000, 2500));
+        s
+    }
+
+    fn make_task(id: &str, durations: &[(i64, &str)]) -> Task {
+        let mut task = Task::new(id);
+        for (i, &(d, m)) in durations.iter().enumerate() {
+            task = task.with_activity(
+                Activity::new(format!("{id}_O{}", i + 1), id, i as i32)
+                    .with_duration(ActivityDuration::fixed(d))
+                    .with_requirement(
+                        ResourceRequirement::new("Machine").with_candidates(vec![m.into()]),
+                    ),
+            );
+        }
+        task
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        let mut t = ExecutionTracker::new(plan());
+        assert_eq!(t.status("J1_O1"), ActivityStatus::NotStarted);
+        t.record(ExecutionEvent::started("J1_O1", "M1", 100))
+            .unwrap();
+        assert_eq!(
+            t.status("J1_O1"),
+            ActivityStatus::InProgress { start_ms: 100 }
+        );
+        t.record(ExecutionEvent::finished("J1_O1", 1200)).unwrap();
+        assert_eq!(
+            t.status("J1_O1"),
+            ActivityStatus::Completed {
+                start_ms: 100,
+                end_ms: 1200
+            }
+        );
+    }
+
+    #[test]
+    fn test_rejects_inconsistent_events() {
+        let mut t = ExecutionTracker::new(plan());
+        assert_eq!(
+            t.record(ExecutionEvent::finished("J1_O1", 10)),
+            Err(ExecutionError::NotStarted("J1_O1".into()))
+        );
+        t.record(ExecutionEvent::started("J1_O1", "M1", 100))
+            .unwrap();
+        assert!(matches!(
+            t.record(ExecutionEvent::started("J1_O1", "M1", 150)),
+            Err(ExecutionError::AlreadyStarted(_))
+        ));
+        assert!(matches!(
+            t.record(ExecutionEvent::finished("J1_O1", 50)),
+            Err(ExecutionError::FinishBeforeStart { .. })
+        ));
+        t.record(ExecutionEvent::finished("J1_O1", 900)).unwrap();
+        assert!(matches!(
+            t.record(ExecutionEvent::finished("J1_O1", 950)),
+            Err(ExecutionError::AlreadyFinished(_))
+        ));
+    }
+
+    #[test]
+    fn test_as_built_and_adherence() {
+        let mut t = ExecutionTracker::new(plan());
+        t.record_all([
+            ExecutionEvent::started("J1_O1", "M1", 0),
+            ExecutionEvent::finished("J1_O1", 1200),
+            ExecutionEvent::started("J2_O1", "M1", 1200),
+            ExecutionEvent::started("J1_O2", "M3", 1300),
+            ExecutionEvent::finished("J1_O2", 3100),
+        ])
+        .unwrap();
+
+        let built = t.as_built();
+        assert_eq!(built.assignment_count(), 2);
+        let o2 = built.assignment_for_activity("J1_O2").unwrap();
+        assert_eq!((o2.task_id.as_str(), o2.resource_id.as_str()), ("J1", "M3"));
+
+        let kpi = t.adherence(200);
+        assert_eq!(kpi.started_count, 3);
+        assert_eq!(kpi.completed_count, 2);
+        // Deviations: 0, +300, +200 → mean 166.7; all but J1_O2 on time.
+        assert!((kpi.avg_start_deviation_ms - 500.0 / 3.0).abs() < 1e-9);
+        assert!((kpi.on_time_start_rate - 2.0 / 3.0).abs() < 1e-9);
+        // Finish deviations: +200, +100.
+        assert!((kpi.avg_finish_deviation_ms - 150.0).abs() < 1e-9);
+        assert_eq!(kpi.resource_changes, 1);
+    }
+
+    #[test]
+    fn test_adherence_counts_each_activity_once() {
+        // J1_O1 is split on M1 and assisted by operator W1 throughout.
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 500));
+        plan.add_assignment(Assignment::new("J1_O1", "J1", "W1", 0, 1300));
+        plan.add_assignment(Assignment::new("J1_O1", "J1", "M1", 800, 1300));
+        let mut t = ExecutionTracker::new(plan);
+        t.record_all([
+            ExecutionEvent::started("J1_O1", "M1", 100),
+            ExecutionEvent::finished("J1_O1", 1400),
+        ])
+        .unwrap();
+
+        let kpi = t.adherence(0);
+        assert_eq!((kpi.started_count, kpi.completed_count), (1, 1));
+        assert_eq!(kpi.avg_start_deviation_ms, 100.0);
+        assert_eq!(kpi.avg_finish_deviation_ms, 100.0);
+        assert_eq!(kpi.on_time_start_rate, 0.0);
+        assert_eq!(kpi.resource_changes, 0);
+    }
+
+    #[test]
+    fn test_adherence_empty() {
+        let kpi = ExecutionTracker::new(plan()).adherence(0);
+        assert_eq!(kpi.started_count, 0);
+        assert_eq!(kpi.on_time_start_rate, 1.0);
+        assert_eq!(kpi.avg_start_deviation_ms, 0.0);
+    }
+
+    #[test]
+    fn test_reschedule_request_avoids_in_progress_work() {
+        let tasks = vec![
+            make_task("J1", &[(1000, "M1"), (2000, "M2")]),
+            make_task("J2", &[(1500, "M1")]),
+        ];
+        let resources = vec![Resource::primary("M1"), Resource::primary("M2")];
+        let mut t = ExecutionTracker::new(plan());
+        t.record(ExecutionEvent::started("J1_O1", "M1", 0)).unwrap();
+
+        let request = t.reschedule_request(&tasks, &resources, 200);
+        assert_eq!(request.start_time_ms, 200);
+        // J1 keeps only O2, released when O1 is expected to end.
+        assert_eq!(request.tasks[0].activities.len(), 1);
+        assert_eq!(request.tasks[0].release_time, Some(1000));
+
+        // M1 is busy with J1_O1 until 1000: J2_O1 must not overlap it.
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+        let j2 = schedule.assignment_for_activity("J2_O1").unwrap();
+        assert_eq!((j2.resource_id.as_str(), j2.start_ms), ("M1", 1000));
+        let o2 = schedule.assignment_for_activity("J1_O2").unwrap();
+        assert_eq!(o2.start_ms, 1000);
+    }
+}
@@ -0,0 +1,462 @@
+//! C ABI (feature `ffi`).
+//!
+//! JSON-in/JSON-out entry points for embedding the scheduler in-process
+//! from C, C# (P/Invoke) or any other language with a C FFI. Problems use
+//! the [`ScheduleRequest`] JSON instance format; results are
+//! `{ "schedule": ..., "kpi": ... }`.
+//!
+//! # Conventions
+//!
+//! - Strings are NUL-terminated UTF-8.
+//! - Returned strings are owned by the caller and must be released with
+//!   [`us_string_free`].
+//! - On failure, functions return null (or a nonzero status) and the
+//!   message is available from [`us_last_error`] on the same thread.
+//! - Panics are caught at the boundary and reported as errors.
+//!
+//! # Sessions
+//!
+//! A [`UsSession`] is an opaque handle holding a problem and its latest
+//! schedule, for long-lived solver sessions (e.g. repeated what-if
+//! solves as the shop floor changes).
+//!
+//! ```text
+//! UsSession* s = us_session_new(problem_json);
+//! char* result = us_session_solve(s, "{\"engine\":\"greedy\"}");
+//! ...
+//! us_string_free(result);
+//! us_session_free(s);
+//! ```
+//!
+//! # Build
+//!
+//! ```text
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! ```
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bundle::SolverSpec;
+use crate::dispatching::RuleEngineSpec;
+use crate::ga::SchedulingGaOptions;
+use crate::models::Schedule;
+use crate::scheduler::{ScheduleKpi, ScheduleRequest};
+use crate::validation::validate_input;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Solver selection for [`us_solve`] and [`us_session_solve`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "engine", rename_all = "lowercase")]
+pub enum SolveOptions {
+    /// Greedy scheduler with an optional rule engine.
+    Greedy {
+        /// Task ordering rules (default: priority order).
+        #[serde(default)]
+        rules: Option<RuleEngineSpec>,
+    },
+    /// Genetic algorithm.
+    Ga(GaSettings),
+}
+
+/// GA run settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GaSettings {
+    /// Problem configuration and seed.
+    #[serde(flatten)]
+    pub options: SchedulingGaOptions,
+    /// Population size (default: 50).
+    pub population_size: usize,
+    /// Maximum generations (default: 200).
+    pub generations: usize,
+    /// Wall-clock limit in ms (default: none).
+    pub time_limit_ms: Option<u64>,
+}
+
+impl Default for GaSettings {
+    fn default() -> Self {
+        Self {
+            options: SchedulingGaOptions::default(),
+            population_size: 50,
+            generations: 200,
+            time_limit_ms: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SolveOutput<'a> {
+    schedule: &'a Schedule,
+    kpi: ScheduleKpi,
+}
+
+/// Opaque solver session.
+pub struct UsSession {
+    request: ScheduleRequest,
+    last_schedule: Option<Schedule>,
+}
+
+fn solve(request: &ScheduleRequest, options: &SolveOptions) -> Result<Schedule, String> {
+    let spec = match options.clone() {
+        SolveOptions::Greedy { rules } => SolverSpec::Greedy { rules },
+        SolveOptions::Ga(settings) => SolverSpec::Ga {
+            options: settings.options,
+            population_size: Some(settings.population_size),
+            generations: Some(settings.generations),
+            time_limit_ms: settings.time_limit_ms,
+            generations_run: None,
+        },
+    };
+    spec.solve(request).map_err(|e| e.message)
+}
+
+fn render(request: &ScheduleRequest, schedule: &Schedule) -> Result<String, String> {
+    let kpi = ScheduleKpi::calculate(schedule, &request.tasks);
+    serde_json::to_string(&SolveOutput { schedule, kpi }).map_err(|e| e.to_string())
+}
+
+fn parse_options(json: Option<&str>) -> Result<SolveOptions, String> {
+    match json {
+        Some(json) => serde_json::from_str(json).map_err(|e| format!("invalid options JSON: {e}")),
+        None => Ok(SolveOptions::Greedy { rules: None }),
+    }
+}
+
+fn parse_request(json: &str) -> Result<ScheduleRequest, String> {
+    serde_json::from_str(json).map_err(|e| format!("invalid problem JSON: {e}"))
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Reads a required C string argument.
+///
+/// # Safety
+/// `ptr` must be null or a valid NUL-terminated string.
+unsafe fn read_str<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err(format!("`{name}` is null"));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| format!("`{name}` is not valid UTF-8"))
+}
+
+/// Reads an optional C string argument (null = absent).
+///
+/// # Safety
+/// `ptr` must be null or a valid NUL-terminated string.
+unsafe fn read_opt_str<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>, String> {
+    if ptr.is_null() {
+        Ok(None)
+    } else {
+        read_str(ptr, name).map(Some)
+    }
+}
+
+/// Runs `f`, converting errors and panics into a null return + last error.
+fn guard_string(f: impl FnOnce() -> Result<String, String>) -> *mut c_char {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(s)) => match CString::new(s) {
+            Ok(c) => c.into_raw(),
+            Err(e) => {
+                set_last_error(e.to_string());
+                ptr::null_mut()
+            }
+        },
+        Ok(Err(message)) => {
+            set_last_error(message);
+            ptr::null_mut()
+        }
+        Err(_) => {
+            set_last_error("internal panic".into());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the last error message on this thread, or null.
+///
+/// The pointer is valid until the next failing call on this thread.
+#[no_mangle]
+pub extern "C" fn us_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+/// Frees a string returned by this library.
+///
+/// # Safety
+/// `s` must be null or a pointer returned by this library, freed once.
+#[no_mangle]
+pub unsafe extern "C" fn us_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Validates a problem. Returns a JSON array of error messages.
+///
+/// # Safety
+/// `problem_json` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn us_validate(problem_json: *const c_char) -> *mut c_char {
+    guard_string(|| {
+        let request = parse_request(read_str(problem_json, "problem_json")?)?;
+        let messages: Vec<String> = match validate_input(&request.tasks, &request.resources) {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.into_iter().map(|e| e.message).collect(),
+        };
+        serde_json::to_string(&messages).map_err(|e| e.to_string())
+    })
+}
+
+/// Solves a problem in one call.
+///
+/// `options_json` selects the engine (null = greedy), e.g.
+/// `{"engine":"ga","generations":100,"seed":1}`.
+///
+/// # Safety
+/// Both arguments must be null or valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn us_solve(
+    problem_json: *const c_char,
+    options_json: *const c_char,
+) -> *mut c_char {
+    guard_string(|| {
+        let request = parse_request(read_str(problem_json, "problem_json")?)?;
+        let options = parse_options(read_opt_str(options_json, "options_json")?)?;
+        render(&request, &solve(&request, &options)?)
+    })
+}
+
+/// Creates a session from a problem. Returns null on error.
+///
+/// # Safety
+/// `problem_json` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn us_session_new(problem_json: *const c_char) -> *mut UsSession {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        parse_request(read_str(problem_json, "problem_json")?)
+    }));
+    match result {
+        Ok(Ok(request)) => Box::into_raw(Box::new(UsSession {
+            request,
+            last_schedule: None,
+        })),
+        Ok(Err(message)) => {
+            set_last_error(message);
+            ptr::null_mut()
+        }
+        Err(_) => {
+            set_last_error("internal panic".into());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Replaces the session's problem. Returns 0 on success, -1 on error.
+///
+/// The previous schedule is kept until the next solve.
+///
+/// # Safety
+/// `session` must come from [`us_session_new`]; `problem_json` must be
+/// a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn us_session_update(
+    session: *mut UsSession,
+    problem_json: *const c_char,
+) -> i32 {
+    let Some(session) = session.as_mut() else {
+        set_last_error("`session` is null".into());
+        return -1;
+    };
+    match read_str(problem_json, "problem_json").and_then(parse_request) {
+        Ok(request) => {
+            session.request = request;
+            0
+        }
+        Err(message) => {
+            set_last_error(message);
+            -1
+        }
+    }
+}
+
+/// Solves the session's problem and stores the schedule.
+///
+/// # Safety
+/// `session` must come from [`us_session_new`]; `options_json` must be
+/// null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn us_session_solve(
+    session: *mut UsSession,
+    options_json: *const c_char,
+) -> *mut c_char {
+    guard_string(|| {
+        let session = session.as_mut().ok_or("`session` is null")?;
+        let options = parse_options(read_opt_str(options_json, "options_json")?)?;
+        let schedule = solve(&session.request, &options)?;
+        let out = render(&session.request, &schedule);
+        session.last_schedule = Some(schedule);
+        out
+    })
+}
+
+/// Returns the session's latest schedule and KPIs, or null if unsolved.
+///
+/// # Safety
+/// `session` must come from [`us_session_new`].
+#[no_mangle]
+pub unsafe extern "C" fn us_session_last_schedule(session: *const UsSession) -> *mut c_char {
+    guard_string(|| {
+        let session = session.as_ref().ok_or("`session` is null")?;
+        let schedule = session
+            .last_schedule
+            .as_ref()
+            .ok_or("session has not been solved")?;
+        render(&session.request, schedule)
+    })
+}
+
+/// Destroys a session.
+///
+/// # Safety
+/// `session` must be null or come from [`us_session_new`], freed once.
+#[no_mangle]
+pub unsafe extern "C" fn us_session_free(session: *mut UsSession) {
+    if !session.is_null() {
+        drop(Box::from_raw(session));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::golden;
+    use crate::models::{Lock, LockSet};
+
+    fn problem() -> CString {
+        CString::new(serde_json::to_string(&golden::flexible_shop().request()).unwrap()).unwrap()
+    }
+
+    unsafe fn take(s: *mut c_char) -> serde_json::Value {
+        assert!(!s.is_null(), "unexpected error: {}", last_error());
+        let value = serde_json::from_str(CStr::from_ptr(s).to_str().unwrap()).unwrap();
+        us_string_free(s);
+        value
+    }
+
+    fn last_error() -> String {
+        let p = us_last_error();
+        if p.is_null() {
+            return String::new();
+        }
+        unsafe { CStr::from_ptr(p) }.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_one_shot_greedy_and_ga() {
+        let problem = problem();
+        unsafe {
+            let greedy = take(us_solve(problem.as_ptr(), ptr::null()));
+            assert_eq!(
+                greedy["schedule"]["assignments"].as_array().unwrap().len(),
+                8
+            );
+
+            let opts =
+                CString::new(r#"{"engine":"ga","population_size":10,"generations":5,"seed":1}"#)
+                    .unwrap();
+            let ga = take(us_solve(problem.as_ptr(), opts.as_ptr()));
+            assert!(ga["kpi"]["makespan_ms"].as_i64().unwrap() > 0);
+        }
+    }
+
+    #[test]
+    fn test_ga_honors_locks() {
+        let mut request = golden::flexible_shop().request();
+        let greedy = SolverSpec::greedy().solve(&request).unwrap();
+        let first = greedy
+            .assignments
+            .iter()
+            .min_by_key(|a| a.start_ms)
+            .unwrap();
+        let (resource, until) = (first.resource_id.clone(), greedy.makespan_ms());
+        request.locks = LockSet::new().with_lock(Lock::resource_window(&resource, 0, until));
+        let problem = CString::new(serde_json::to_string(&request).unwrap()).unwrap();
+        let opts = CString::new(r#"{"engine":"ga","population_size":10,"generations":5,"seed":1}"#)
+            .unwrap();
+
+        let ga = unsafe { take(us_solve(problem.as_ptr(), opts.as_ptr())) };
+        let schedule: Schedule = serde_json::from_value(ga["schedule"].clone()).unwrap();
+        assert!(schedule
+            .assignments
+            .iter()
+            .filter(|a| a.resource_id == resource)
+            .all(|a| a.start_ms >= until));
+    }
+
+    #[test]
+    fn test_errors_are_reported() {
+        let bad = CString::new("{").unwrap();
+        unsafe {
+            assert!(us_solve(bad.as_ptr(), ptr::null()).is_null());
+            assert!(last_error().contains("invalid problem JSON"));
+            assert!(us_validate(ptr::null()).is_null());
+            assert!(last_error().contains("is null"));
+
+            let opts = CString::new(r#"{"engine":"magic"}"#).unwrap();
+            assert!(us_solve(problem().as_ptr(), opts.as_ptr()).is_null());
+            assert!(last_error().contains("invalid options JSON"));
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        unsafe {
+            let errors = take(us_validate(problem().as_ptr()));
+            assert_eq!(errors, serde_json::json!([]));
+        }
+    }
+
+    #[test]
+    fn test_session_lifecycle() {
+        let problem = problem();
+        unsafe {
+            let session = us_session_new(problem.as_ptr());
+            assert!(!session.is_null());
+            assert!(us_session_last_schedule(session).is_null());
+
+            let rules =
+                CString::new(r#"{"engine":"greedy","rules":{"rules":[{"rule":"SPT"}]}}"#).unwrap();
+            let solved = take(us_session_solve(session, rules.as_ptr()));
+            let last = take(us_session_last_schedule(session));
+            assert_eq!(solved, last);
+
+            let smaller =
+                CString::new(serde_json::to_string(&golden::setup_heavy().request()).unwrap())
+                    .unwrap();
+            assert_eq!(us_session_update(session, smaller.as_ptr()), 0);
+            let resolved = take(us_session_solve(session, ptr::null()));
+            assert_eq!(
+                resolved["schedule"]["assignments"]
+                    .as_array()
+                    .unwrap()
+                    .len(),
+                6
+            );
+
+            us_session_free(session);
+        }
+    }
+}
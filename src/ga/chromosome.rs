@@ -253,13 +253,15 @@ pub fn pox_crossover<R: Rng>(
     activities: &[ActivityInfo],
     rng: &mut R,
 ) -> (ScheduleChromosome, ScheduleChromosome) {
-    // Collect unique task IDs
+    // Collect unique task IDs in first-appearance order (deterministic
+    // under a seeded RNG, unlike HashMap iteration order)
     let task_ids: Vec<String> = {
-        let mut seen = HashMap::new();
-        for act in activities {
-            seen.entry(act.task_id.clone()).or_insert(());
-        }
-        seen.into_keys().collect()
+        let mut seen = std::collections::HashSet::new();
+        activities
+            .iter()
+            .filter(|act| seen.insert(act.task_id.as_str()))
+            .map(|act| act.task_id.clone())
+            .collect()
     };
 
     if task_ids.is_empty() {
@@ -314,6 +316,9 @@ fn pox_build_child(
 /// 3. Fills remaining positions circularly from parent 2, preserving
 ///    parent 2's relative order.
 ///
+/// If the parents are not permutations of the same task multiset, each
+/// child is a copy of its primary parent's OSV.
+///
 /// # Reference
 /// Falkenauer & Bouffouix (1991), "A genetic algorithm for job shop"
 pub fn lox_crossover<R: Rng>(
@@ -335,8 +340,10 @@ pub fn lox_crossover<R: Rng>(
         (end, start)
     };
 
-    let child1_osv = lox_build_child(&p1.osv, &p2.osv, start, end);
-    let child2_osv = lox_build_child(&p2.osv, &p1.osv, start, end);
+    let child1_osv =
+        lox_build_child(&p1.osv, &p2.osv, start, end).unwrap_or_else(|| p1.osv.clone());
+    let child2_osv =
+        lox_build_child(&p2.osv, &p1.osv, start, end).unwrap_or_else(|| p2.osv.clone());
 
     let child1 = ScheduleChromosome {
         osv: child1_osv,
@@ -353,54 +360,50 @@ pub fn lox_crossover<R: Rng>(
     (child1, child2)
 }
 
-fn lox_build_child(p1: &[String], p2: &[String], start: usize, end: usize) -> Vec<String> {
+/// Builds one LOX child: `p1[start..=end]` in place, the other slots
+/// filled circularly from `end + 1` with `p2` read circularly from
+/// `end + 1`, minus one occurrence per segment gene.
+///
+/// Every slot is filled exactly once: the segment holds `end − start + 1`
+/// genes, and removing them (as a multiset) from `p2` leaves exactly as
+/// many genes as there are free slots. Returns `None` if the parents are
+/// not permutations of the same multiset.
+fn lox_build_child(p1: &[String], p2: &[String], start: usize, end: usize) -> Option<Vec<String>> {
     let len = p1.len();
-    let mut child = vec![String::new(); len];
-
-    // Copy segment from P1
-    let segment: Vec<String> = p1[start..=end].to_vec();
-    for (i, item) in segment.iter().enumerate() {
-        child[start + i] = item.clone();
+    if p2.len() != len || start > end || end >= len {
+        return None;
     }
 
-    // Count how many of each task are in the segment
-    let mut seg_counts: HashMap<&str, usize> = HashMap::new();
-    for item in &segment {
-        *seg_counts.entry(item.as_str()).or_insert(0) += 1;
+    // Genes of the segment still to be removed from P2
+    let mut in_segment: HashMap<&str, usize> = HashMap::new();
+    for item in &p1[start..=end] {
+        *in_segment.entry(item.as_str()).or_insert(0) += 1;
     }
+    let mut fill = (1..=len)
+        .map(|i| p2[(end + i) % len].as_str())
+        .filter(|item| match in_segment.get_mut(item) {
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                false
+            }
+            _ => true,
+        });
 
-    // Track how many of each task from P2 we've already skipped
-    let mut p2_counts: HashMap<&str, usize> = HashMap::new();
-    for item in p2 {
-        *p2_counts.entry(item.as_str()).or_insert(0) += 1;
+    let mut child: Vec<Option<&str>> = vec![None; len];
+    for i in start..=end {
+        child[i] = Some(p1[i].as_str());
     }
-
-    // Fill remaining positions circularly from P2
-    let mut child_idx = (end + 1) % len;
-    let mut skip_counts: HashMap<&str, usize> = HashMap::new();
-
-    for i in 0..len {
-        let p2_idx = (end + 1 + i) % len;
-        let item = &p2[p2_idx];
-
-        let seg_count = seg_counts.get(item.as_str()).copied().unwrap_or(0);
-        let skipped = skip_counts.get(item.as_str()).copied().unwrap_or(0);
-
-        if skipped < seg_count {
-            // Skip this occurrence (already in segment)
-            *skip_counts.entry(item.as_str()).or_insert(0) += 1;
-            continue;
+    for i in 1..=len {
+        let pos = (end + i) % len;
+        if child[pos].is_none() {
+            child[pos] = Some(fill.next()?);
         }
-
-        if child_idx == start {
-            break;
-        }
-
-        child[child_idx] = item.clone();
-        child_idx = (child_idx + 1) % len;
     }
-
-    child
+    if fill.next().is_some() {
+        return None;
+    }
+    let child: Vec<String> = child.into_iter().flatten().map(str::to_string).collect();
+    same_multiset(p1, &child).then_some(child)
 }
 
 /// Performs JOX (Job-based Order Crossover).
@@ -412,6 +415,9 @@ fn lox_build_child(p1: &[String], p2: &[String], start: usize, end: usize) -> Ve
 /// Differs from POX: JOX preserves exact positions (absolute), while
 /// POX preserves positions relative to the template layout.
 ///
+/// If the parents are not permutations of the same task multiset, each
+/// child is a copy of its primary parent's OSV.
+///
 /// # Reference
 /// Yamada & Nakano (1997), "Job shop scheduling"
 pub fn jox_crossover<R: Rng>(
@@ -420,12 +426,15 @@ pub fn jox_crossover<R: Rng>(
     activities: &[ActivityInfo],
     rng: &mut R,
 ) -> (ScheduleChromosome, ScheduleChromosome) {
+    // Unique task IDs in first-appearance order (deterministic under a
+    // seeded RNG, unlike HashSet iteration order)
     let task_ids: Vec<String> = {
         let mut seen = std::collections::HashSet::new();
-        for act in activities {
-            seen.insert(act.task_id.clone());
-        }
-        seen.into_iter().collect()
+        activities
+            .iter()
+            .filter(|a| seen.insert(a.task_id.as_str()))
+            .map(|a| a.task_id.clone())
+            .collect()
     };
 
     if task_ids.is_empty() {
@@ -436,8 +445,8 @@ pub fn jox_crossover<R: Rng>(
     let selected: std::collections::HashSet<String> =
         task_ids.choose_multiple(rng, set_size).cloned().collect();
 
-    let child1_osv = jox_build_child(&p1.osv, &p2.osv, &selected);
-    let child2_osv = jox_build_child(&p2.osv, &p1.osv, &selected);
+    let child1_osv = jox_build_child(&p1.osv, &p2.osv, &selected).unwrap_or_else(|| p1.osv.clone());
+    let child2_osv = jox_build_child(&p2.osv, &p1.osv, &selected).unwrap_or_else(|| p2.osv.clone());
 
     let child1 = ScheduleChromosome {
         osv: child1_osv,
@@ -454,31 +463,52 @@ pub fn jox_crossover<R: Rng>(
     (child1, child2)
 }
 
+/// Builds one JOX child: genes of `selected` tasks stay at their
+/// positions in `primary`, the other slots are filled in order with the
+/// unselected genes of `donor`.
+///
+/// Both parents hold the same number of unselected genes, which is the
+/// number of free slots, so every slot is filled exactly once. Returns
+/// `None` if the parents are not permutations of the same multiset.
 fn jox_build_child(
     primary: &[String],
     donor: &[String],
     selected: &std::collections::HashSet<String>,
-) -> Vec<String> {
-    let mut child = vec![String::new(); primary.len()];
-
-    // Place selected jobs from primary at their exact positions
-    for (i, task) in primary.iter().enumerate() {
-        if selected.contains(task) {
-            child[i] = task.clone();
-        }
+) -> Option<Vec<String>> {
+    if primary.len() != donor.len() {
+        return None;
     }
-
-    // Fill remaining positions from donor in order
-    let mut donor_iter = donor.iter().filter(|t| !selected.contains(t.as_str()));
+    let mut fill = donor
+        .iter()
+        .filter(|t| !selected.contains(t.as_str()))
+        .map(String::as_str);
+
+    let mut child: Vec<Option<&str>> = primary
+        .iter()
+        .map(|t| selected.contains(t).then_some(t.as_str()))
+        .collect();
     for slot in &mut child {
-        if slot.is_empty() {
-            if let Some(t) = donor_iter.next() {
-                *slot = t.clone();
-            }
+        if slot.is_none() {
+            *slot = Some(fill.next()?);
         }
     }
+    if fill.next().is_some() {
+        return None;
+    }
+    let child: Vec<String> = child.into_iter().flatten().map(str::to_string).collect();
+    same_multiset(primary, &child).then_some(child)
+}
 
-    child
+/// Whether two OSVs hold the same task IDs with the same counts.
+fn same_multiset(a: &[String], b: &[String]) -> bool {
+    let mut counts: HashMap<&str, i64> = HashMap::new();
+    for t in a {
+        *counts.entry(t.as_str()).or_insert(0) += 1;
+    }
+    for t in b {
+        *counts.entry(t.as_str()).or_insert(0) -= 1;
+    }
+    counts.values().all(|&n| n == 0)
 }
 
 // ======================== Mutation operators ========================
@@ -548,18 +578,21 @@ mod tests {
     fn sample_activities() -> Vec<ActivityInfo> {
         vec![
             ActivityInfo {
+                activity_id: "T1_O1".into(),
                 task_id: "T1".into(),
                 sequence: 1,
                 process_ms: 1000,
                 candidates: vec!["M1".into(), "M2".into()],
             },
             ActivityInfo {
+                activity_id: "T1_O2".into(),
                 task_id: "T1".into(),
                 sequence: 2,
                 process_ms: 2000,
                 candidates: vec!["M2".into()],
             },
             ActivityInfo {
+                activity_id: "T2_O1".into(),
                 task_id: "T2".into(),
                 sequence: 1,
                 process_ms: 1500,
@@ -850,4 +883,159 @@ mod tests {
         assert_eq!(ch.resource_for("T2", 1), Some("M1"));
         assert!(ch.is_valid(&acts));
     }
+
+    /// All distinct arrangements of a multiset.
+    fn arrangements(items: &[&str]) -> Vec<Vec<String>> {
+        let mut sorted: Vec<String> = items.iter().map(|s| s.to_string()).collect();
+        sorted.sort();
+        let mut out = Vec::new();
+        fn walk(rest: &mut Vec<String>, current: &mut Vec<String>, out: &mut Vec<Vec<String>>) {
+            if rest.is_empty() {
+                out.push(current.clone());
+                return;
+            }
+            for i in 0..rest.len() {
+                if i > 0 && rest[i] == rest[i - 1] {
+                    continue;
+                }
+                let item = rest.remove(i);
+                current.push(item.clone());
+                walk(rest, current, out);
+                current.pop();
+                rest.insert(i, item);
+            }
+        }
+        walk(&mut sorted, &mut Vec::new(), &mut out);
+        out
+    }
+
+    fn sorted(osv: &[String]) -> Vec<String> {
+        let mut v = osv.to_vec();
+        v.sort();
+        v
+    }
+
+    // Includes an empty task ID, which the old empty-slot sentinel
+    // could not tell apart from an unfilled position.
+    const MULTISET: [&str; 5] = ["A", "A", "B", "", ""];
+
+    #[test]
+    fn test_lox_exhaustive_small_permutations() {
+        let perms = arrangements(&MULTISET);
+        assert_eq!(perms.len(), 30);
+        let len = MULTISET.len();
+
+        for p1 in &perms {
+            for p2 in &perms {
+                for start in 0..len {
+                    for end in start..len {
+                        let child = lox_build_child(p1, p2, start, end).unwrap();
+                        assert_eq!(sorted(&child), sorted(p1), "{p1:?} {p2:?} [{start},{end}]");
+                        assert_eq!(child[start..=end], p1[start..=end]);
+
+                        // Outside the segment, circularly from end+1:
+                        // P2's circular order minus the segment genes
+                        let mut expected: Vec<&String> =
+                            (1..=len).map(|i| &p2[(end + i) % len]).collect();
+                        for gene in &p1[start..=end] {
+                            let k = expected.iter().position(|g| *g == gene).unwrap();
+                            expected.remove(k);
+                        }
+                        let outside: Vec<&String> = (1..=len)
+                            .map(|i| (end + i) % len)
+                            .filter(|&pos| pos < start || pos > end)
+                            .map(|pos| &child[pos])
+                            .collect();
+                        assert_eq!(outside, expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_jox_exhaustive_small_permutations() {
+        let perms = arrangements(&MULTISET);
+        let tasks = ["A", "B", ""];
+
+        for p1 in &perms {
+            for p2 in &perms {
+                for mask in 0..(1u32 << tasks.len()) {
+                    let selected: std::collections::HashSet<String> = tasks
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| mask & (1 << i) != 0)
+                        .map(|(_, t)| t.to_string())
+                        .collect();
+                    let child = jox_build_child(p1, p2, &selected).unwrap();
+                    assert_eq!(sorted(&child), sorted(p1), "{p1:?} {p2:?} {selected:?}");
+                    for (i, gene) in p1.iter().enumerate() {
+                        if selected.contains(gene) {
+                            assert_eq!(&child[i], gene);
+                        }
+                    }
+                    let donor: Vec<&String> =
+                        p2.iter().filter(|g| !selected.contains(*g)).collect();
+                    let filled: Vec<&String> =
+                        child.iter().filter(|g| !selected.contains(*g)).collect();
+                    assert_eq!(filled, donor);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mismatched_parents_fall_back() {
+        let p1: Vec<String> = ["A", "A", "B"].iter().map(|s| s.to_string()).collect();
+        let shorter: Vec<String> = ["A", "B"].iter().map(|s| s.to_string()).collect();
+        let other: Vec<String> = ["A", "B", "B"].iter().map(|s| s.to_string()).collect();
+
+        assert!(lox_build_child(&p1, &shorter, 0, 1).is_none());
+        assert!(lox_build_child(&p1, &other, 0, 0).is_none());
+        let selected: std::collections::HashSet<String> = ["B".to_string()].into();
+        assert!(jox_build_child(&p1, &shorter, &selected).is_none());
+        assert!(jox_build_child(&p1, &other, &selected).is_none());
+    }
+
+    #[test]
+    fn test_operators_keep_small_permutations_valid() {
+        let acts: Vec<ActivityInfo> = [("A", 1), ("A", 2), ("B", 1), ("", 1), ("", 2)]
+            .iter()
+            .map(|&(task, sequence)| ActivityInfo {
+                activity_id: format!("{task}_O{sequence}"),
+                task_id: task.into(),
+                sequence,
+                process_ms: 1000,
+                candidates: vec!["M1".into(), "M2".into()],
+            })
+            .collect();
+        let perms = arrangements(&MULTISET);
+        let mut rng = SmallRng::seed_from_u64(7);
+
+        for (i, p1) in perms.iter().enumerate() {
+            for p2 in &perms[i..] {
+                let a = ScheduleChromosome {
+                    osv: p1.clone(),
+                    ..ScheduleChromosome::random(&acts, &mut rng)
+                };
+                let b = ScheduleChromosome {
+                    osv: p2.clone(),
+                    ..ScheduleChromosome::random(&acts, &mut rng)
+                };
+                for (c1, c2) in [
+                    pox_crossover(&a, &b, &acts, &mut rng),
+                    lox_crossover(&a, &b, &acts, &mut rng),
+                    jox_crossover(&a, &b, &acts, &mut rng),
+                ] {
+                    assert!(c1.is_valid(&acts) && c2.is_valid(&acts), "{p1:?} {p2:?}");
+                }
+                let mut m = a.clone();
+                swap_mutation(&mut m, &mut rng);
+                insert_mutation(&mut m, &mut rng);
+                invert_mutation(&mut m, &mut rng);
+                mav_mutation(&mut m, &acts, &mut rng);
+                assert!(m.is_valid(&acts));
+            }
+        }
+    }
 }
@@ -0,0 +1,146 @@
+//! GA runs that work on every target.
+//!
+//! `GaRunner` reads the wall clock and may evaluate on rayon's thread
+//! pool, neither of which exists on `wasm32-unknown-unknown`. [`evolve`]
+//! calls `GaRunner` on other targets and, on wasm32, runs the same
+//! generational loop on the calling thread without a clock.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use u_metaheur::ga::{GaConfig, GaProblem, GaResult};
+
+/// Runs the GA of `problem` under `config`, stopping early once `cancel`
+/// is set.
+///
+/// On wasm32 the time limit and `parallel` are ignored (runs are bounded
+/// by generations and the stagnation limit), unseeded runs use seed 0,
+/// and no per-generation statistics are recorded.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn evolve<P: GaProblem>(
+    problem: &P,
+    config: &GaConfig,
+    cancel: Option<Arc<AtomicBool>>,
+) -> GaResult<P::Individual> {
+    u_metaheur::ga::GaRunner::run_with_cancel(problem, config, cancel)
+}
+
+/// Runs the GA of `problem` under `config`, stopping early once `cancel`
+/// is set.
+///
+/// On wasm32 the time limit and `parallel` are ignored (runs are bounded
+/// by generations and the stagnation limit), unseeded runs use seed 0,
+/// and no per-generation statistics are recorded.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn evolve<P: GaProblem>(
+    problem: &P,
+    config: &GaConfig,
+    cancel: Option<Arc<AtomicBool>>,
+) -> GaResult<P::Individual> {
+    use std::sync::atomic::Ordering;
+
+    use rand::Rng;
+    use u_metaheur::ga::{Fitness, Individual};
+
+    config.validate().expect("invalid GaConfig");
+    let mut rng = u_numflow::random::create_rng(config.seed.unwrap_or(0));
+    let evaluate = |individual: &mut P::Individual| {
+        let fitness = problem.evaluate(individual);
+        individual.set_fitness(fitness);
+    };
+    let best_of = |population: &[P::Individual]| {
+        population
+            .iter()
+            .min_by(|a, b| {
+                a.fitness()
+                    .partial_cmp(&b.fitness())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .expect("population is non-empty")
+            .clone()
+    };
+
+    let mut population: Vec<P::Individual> = (0..config.population_size)
+        .map(|_| problem.create_individual(&mut rng))
+        .collect();
+    population.iter_mut().for_each(evaluate);
+    let mut best = best_of(&population);
+    let mut fitness_history = vec![best.fitness().to_f64()];
+    let mut stagnation = 0;
+    let mut generations = 0;
+    let mut cancelled = false;
+    let mut stagnated = false;
+
+    while generations < config.max_generations {
+        if cancel
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+        {
+            cancelled = true;
+            break;
+        }
+        population.sort_by(|a, b| {
+            a.fitness()
+                .partial_cmp(&b.fitness())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let elites = (config.population_size as f64 * config.elite_ratio) as usize;
+        let mut next = population[..elites].to_vec();
+        while next.len() < config.population_size {
+            let p1 = config.selection.select(&population, &mut rng);
+            let p2 = config.selection.select(&population, &mut rng);
+            let children = if rng.random_range(0.0..1.0) < config.crossover_rate {
+                problem.crossover(&population[p1], &population[p2], &mut rng)
+            } else {
+                vec![population[p1].clone()]
+            };
+            for mut child in children {
+                if next.len() >= config.population_size {
+                    break;
+                }
+                if rng.random_range(0.0..1.0) < config.mutation_rate {
+                    problem.mutate(&mut child, &mut rng);
+                }
+                next.push(child);
+            }
+        }
+        next[elites..].iter_mut().for_each(evaluate);
+        population = next;
+        generations += 1;
+
+        let generation_best = best_of(&population);
+        if generation_best.fitness() < best.fitness() {
+            let (old, new) = (best.fitness().to_f64(), generation_best.fitness().to_f64());
+            let improvement = if old.abs() > 1e-15 {
+                (old - new).abs() / old.abs()
+            } else {
+                (old - new).abs()
+            };
+            best = generation_best;
+            if improvement >= config.convergence_threshold {
+                stagnation = 0;
+            } else {
+                stagnation += 1;
+            }
+        } else {
+            stagnation += 1;
+        }
+        fitness_history.push(best.fitness().to_f64());
+        problem.on_generation(generations, best.fitness());
+        if config.stagnation_limit > 0 && stagnation >= config.stagnation_limit {
+            stagnated = true;
+            break;
+        }
+    }
+
+    GaResult {
+        best_fitness: best.fitness(),
+        best,
+        generations,
+        stagnated,
+        cancelled,
+        timed_out: false,
+        fitness_history,
+        generation_stats: Vec::new(),
+    }
+}
@@ -33,9 +33,10 @@
 //!     .with_seed(42);
 //! let result = GaRunner::run(&problem, &config);
 //!
-//! // 4. Decode best solution
-//! let schedule = problem.decode(&result.best);
-//! println!("Makespan: {} ms", schedule.makespan_ms());
+//! // 4. Decode best solution, with KPIs and the fitness terms
+//! let solution = problem.decode_with_kpi(&result.best, &tasks);
+//! println!("Makespan: {} ms", solution.kpi.makespan_ms);
+//! println!("Tardiness term: {}", solution.fitness.tardiness);
 //! ```
 //!
 //! # Initialization Strategies
@@ -45,6 +46,9 @@
 //! - **25%** load-balanced (even resource utilization)
 //! - **25%** SPT (shortest processing time, if `process_times` provided)
 //!
+//! The mix, operators, fitness weights, and seed can be persisted as JSON
+//! via [`SchedulingGaOptions`].
+//!
 //! # Crossover Operators
 //!
 //! | Operator | Description | Reference |
@@ -66,6 +70,7 @@
 //! # Submodules
 //!
 //! - [`operators`]: Runtime-selectable crossover and mutation strategies
+//! - `options`: Serializable run configuration ([`SchedulingGaOptions`])
 //!
 //! # References
 //!
@@ -74,11 +79,15 @@
 //! - Conway et al. (1967), "Theory of Scheduling" (SPT heuristic)
 
 mod chromosome;
+mod evolve;
 pub mod operators;
+mod options;
 mod problem;
 
 pub use chromosome::{
     insert_mutation, invert_mutation, jox_crossover, lox_crossover, mav_mutation, pox_crossover,
     swap_mutation, ScheduleChromosome,
 };
-pub use problem::{ActivityInfo, SchedulingGaProblem};
+pub(crate) use evolve::evolve;
+pub use options::{InitializationMix, SchedulingGaOptions};
+pub use problem::{ActivityInfo, DecodedSolution, FitnessBreakdown, SchedulingGaProblem};
@@ -14,6 +14,7 @@
 //! ```
 
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use super::chromosome::{
     insert_mutation, invert_mutation, jox_crossover, lox_crossover, mav_mutation, pox_crossover,
@@ -22,7 +23,7 @@ use super::chromosome::{
 use super::problem::ActivityInfo;
 
 /// Crossover strategy for scheduling chromosomes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CrossoverType {
     /// Precedence Operation Crossover (Bierwirth et al., 1996).
     POX,
@@ -33,7 +34,7 @@ pub enum CrossoverType {
 }
 
 /// Mutation strategy for scheduling chromosomes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MutationType {
     /// Swap two random positions in the OSV.
     Swap,
@@ -59,7 +60,7 @@ pub enum MutationType {
 ///     mutation_type: MutationType::Invert,
 /// };
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GeneticOperators {
     /// Crossover strategy.
     pub crossover_type: CrossoverType,
@@ -78,6 +79,9 @@ impl Default for GeneticOperators {
 
 impl GeneticOperators {
     /// Performs crossover using the configured strategy.
+    ///
+    /// In debug builds, asserts that valid parents yield valid children
+    /// ([`ScheduleChromosome::is_valid`]).
     pub fn crossover<R: Rng>(
         &self,
         p1: &ScheduleChromosome,
@@ -85,28 +89,42 @@ impl GeneticOperators {
         activities: &[ActivityInfo],
         rng: &mut R,
     ) -> (ScheduleChromosome, ScheduleChromosome) {
-        match self.crossover_type {
+        let children = match self.crossover_type {
             CrossoverType::POX => pox_crossover(p1, p2, activities, rng),
             CrossoverType::LOX => lox_crossover(p1, p2, activities, rng),
             CrossoverType::JOX => jox_crossover(p1, p2, activities, rng),
-        }
+        };
+        debug_assert!(
+            !(p1.is_valid(activities) && p2.is_valid(activities))
+                || (children.0.is_valid(activities) && children.1.is_valid(activities)),
+            "{:?} crossover produced an invalid chromosome from valid parents",
+            self.crossover_type
+        );
+        children
     }
 
     /// Performs mutation using the configured strategy.
     ///
     /// Always also applies MAV mutation to diversify resource assignments.
+    /// In debug builds, asserts that a valid chromosome stays valid.
     pub fn mutate<R: Rng>(
         &self,
         chromosome: &mut ScheduleChromosome,
         activities: &[ActivityInfo],
         rng: &mut R,
     ) {
+        let was_valid = cfg!(debug_assertions) && chromosome.is_valid(activities);
         match self.mutation_type {
             MutationType::Swap => swap_mutation(chromosome, rng),
             MutationType::Insert => insert_mutation(chromosome, rng),
             MutationType::Invert => invert_mutation(chromosome, rng),
         }
         mav_mutation(chromosome, activities, rng);
+        debug_assert!(
+            !was_valid || chromosome.is_valid(activities),
+            "{:?} mutation produced an invalid chromosome",
+            self.mutation_type
+        );
     }
 }
 
@@ -119,18 +137,21 @@ mod tests {
     fn sample_activities() -> Vec<ActivityInfo> {
         vec![
             ActivityInfo {
+                activity_id: "T1_O1".into(),
                 task_id: "T1".into(),
                 sequence: 1,
                 process_ms: 1000,
                 candidates: vec!["M1".into(), "M2".into()],
             },
             ActivityInfo {
+                activity_id: "T1_O2".into(),
                 task_id: "T1".into(),
                 sequence: 2,
                 process_ms: 2000,
                 candidates: vec!["M2".into()],
             },
             ActivityInfo {
+                activity_id: "T2_O1".into(),
                 task_id: "T2".into(),
                 sequence: 1,
                 process_ms: 1500,
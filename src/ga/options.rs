@@ -0,0 +1,269 @@
+//! Serializable GA bridge configuration.
+//!
+//! [`SchedulingGaOptions`] captures every knob of [`SchedulingGaProblem`]
+//! that is not derived from the input data (operators, fitness weights,
+//! initialization mix, penalties) plus the RNG seed, so that a GA run can
+//! be persisted and reproduced without recompiling.
+//!
+//! # Example
+//!
+//! ```
+//! use u_schedule::ga::SchedulingGaOptions;
+//!
+//! let json = r#"{ "tardiness_weight": 0.8, "seed": 7 }"#;
+//! let options = SchedulingGaOptions::from_json(json).unwrap();
+//! assert_eq!(options.seed, Some(7));
+//! assert_eq!(SchedulingGaOptions::from_json(&options.to_json()).unwrap(), options);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use u_metaheur::ga::GaConfig;
+
+use super::operators::GeneticOperators;
+use super::problem::SchedulingGaProblem;
+use crate::scheduler::CapacityLeveling;
+
+/// Relative weights of the initial population strategies.
+///
+/// Weights are normalized, so `(2, 1, 1)` is equivalent to `(0.5, 0.25, 0.25)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InitializationMix {
+    /// Random OSV + random MAV.
+    pub random: f64,
+    /// Random OSV + least-loaded MAV.
+    pub load_balanced: f64,
+    /// Random OSV + shortest-processing-time MAV
+    /// (falls back to load-balanced when no `process_times` are set).
+    pub shortest_time: f64,
+}
+
+impl Default for InitializationMix {
+    fn default() -> Self {
+        Self {
+            random: 0.5,
+            load_balanced: 0.25,
+            shortest_time: 0.25,
+        }
+    }
+}
+
+impl InitializationMix {
+    /// Cumulative thresholds `(random, random + load_balanced)` on `[0, 1)`.
+    pub(crate) fn thresholds(&self) -> (f64, f64) {
+        let r = self.random.max(0.0);
+        let lb = self.load_balanced.max(0.0);
+        let spt = self.shortest_time.max(0.0);
+        let total = r + lb + spt;
+        if total <= 0.0 {
+            return (1.0, 1.0);
+        }
+        (r / total, (r + lb) / total)
+    }
+}
+
+/// Serializable configuration for a scheduling GA run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchedulingGaOptions {
+    /// Crossover and mutation strategy.
+    pub operators: GeneticOperators,
+    /// Tardiness weight in fitness (0.0 = pure makespan, 1.0 = pure tardiness).
+    pub tardiness_weight: f64,
+    /// Initial population mix.
+    pub initialization: InitializationMix,
+    /// Fitness penalty per activity that could not be scheduled
+    /// (e.g., no resource assigned).
+    pub unscheduled_penalty: f64,
+    /// Weight of the human workload spread (max − min assigned ms).
+    pub fairness_weight: f64,
+    /// Weight of the affinity regret.
+    pub affinity_weight: f64,
+    /// Capacity limit per time bucket.
+    pub leveling: Option<CapacityLeveling>,
+    /// Weight of the capacity leveling excess.
+    pub leveling_weight: f64,
+    /// Penalty per changeover beyond a resource's setup limit.
+    pub setup_limit_weight: f64,
+    /// RNG seed for reproducible runs (`None` = nondeterministic).
+    pub seed: Option<u64>,
+}
+
+impl Default for SchedulingGaOptions {
+    fn default() -> Self {
+        Self {
+            operators: GeneticOperators::default(),
+            tardiness_weight: 0.5,
+            initialization: InitializationMix::default(),
+            unscheduled_penalty: 0.0,
+            fairness_weight: 0.0,
+            affinity_weight: 1.0,
+            leveling: None,
+            leveling_weight: 1.0,
+            setup_limit_weight: 3_600_000.0,
+            seed: None,
+        }
+    }
+}
+
+impl SchedulingGaOptions {
+    /// Parses options from JSON. Missing fields take their defaults.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Serializes options to pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("options contain only serializable data")
+    }
+
+    /// Applies the seed to a GA runner configuration.
+    pub fn apply_to(&self, config: GaConfig) -> GaConfig {
+        match self.seed {
+            Some(seed) => config.with_seed(seed),
+            None => config,
+        }
+    }
+}
+
+impl SchedulingGaProblem {
+    /// Applies serialized options to this problem.
+    pub fn with_options(mut self, options: &SchedulingGaOptions) -> Self {
+        self.operators = options.operators.clone();
+        self.tardiness_weight = options.tardiness_weight.clamp(0.0, 1.0);
+        self.initialization = options.initialization.clone();
+        self.unscheduled_penalty = options.unscheduled_penalty.max(0.0);
+        self.fairness_weight = options.fairness_weight.max(0.0);
+        self.affinity_weight = options.affinity_weight.max(0.0);
+        self.leveling = options.leveling.clone();
+        self.leveling_weight = options.leveling_weight.max(0.0);
+        self.setup_limit_weight = options.setup_limit_weight.max(0.0);
+        self
+    }
+
+    /// Captures the current configuration as serializable options.
+    ///
+    /// The seed is not part of the problem and is left unset.
+    pub fn options(&self) -> SchedulingGaOptions {
+        SchedulingGaOptions {
+            operators: self.operators.clone(),
+            tardiness_weight: self.tardiness_weight,
+            initialization: self.initialization.clone(),
+            unscheduled_penalty: self.unscheduled_penalty,
+            fairness_weight: self.fairness_weight,
+            affinity_weight: self.affinity_weight,
+            leveling: self.leveling.clone(),
+            leveling_weight: self.leveling_weight,
+            setup_limit_weight: self.setup_limit_weight,
+            seed: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ga::operators::{CrossoverType, MutationType};
+    use crate::models::{Activity, Resource, ResourceRequirement, Task};
+    use u_metaheur::ga::GaRunner;
+
+    fn make_problem_data() -> (Vec<Task>, Vec<Resource>) {
+        let tasks = (0..3)
+            .map(|i| {
+                let id = format!("T{i}");
+                Task::new(&id).with_deadline(2000).with_activity(
+                    Activity::new(format!("{id}_O1"), &id, 0)
+                        .with_process_time(1000 + i * 100)
+                        .with_requirement(
+                            ResourceRequirement::new("Machine")
+                                .with_candidates(vec!["M1".into(), "M2".into()]),
+                        ),
+                )
+            })
+            .collect();
+        (
+            tasks,
+            vec![Resource::primary("M1"), Resource::primary("M2")],
+        )
+    }
+
+    #[test]
+    fn test_defaults_match_problem_defaults() {
+        let (tasks, resources) = make_problem_data();
+        let problem = SchedulingGaProblem::new(&tasks, &resources);
+        assert_eq!(problem.options(), SchedulingGaOptions::default());
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let options = SchedulingGaOptions {
+            operators: GeneticOperators {
+                crossover_type: CrossoverType::JOX,
+                mutation_type: MutationType::Invert,
+            },
+            tardiness_weight: 0.9,
+            initialization: InitializationMix {
+                random: 1.0,
+                load_balanced: 0.0,
+                shortest_time: 0.0,
+            },
+            unscheduled_penalty: 1e6,
+            fairness_weight: 0.25,
+            affinity_weight: 2.0,
+            leveling: Some(CapacityLeveling::new(3_600_000, 6)),
+            leveling_weight: 0.5,
+            setup_limit_weight: 60_000.0,
+            seed: Some(42),
+        };
+        let back = SchedulingGaOptions::from_json(&options.to_json()).unwrap();
+        assert_eq!(back, options);
+    }
+
+    #[test]
+    fn test_partial_json_uses_defaults() {
+        let options = SchedulingGaOptions::from_json(
+            r#"{ "operators": { "crossover_type": "LOX", "mutation_type": "Swap" } }"#,
+        )
+        .unwrap();
+        assert_eq!(options.operators.crossover_type, CrossoverType::LOX);
+        assert_eq!(options.tardiness_weight, 0.5);
+        assert_eq!(options.seed, None);
+    }
+
+    #[test]
+    fn test_seeded_runs_reproduce() {
+        let (tasks, resources) = make_problem_data();
+        let options = SchedulingGaOptions {
+            seed: Some(11),
+            ..SchedulingGaOptions::default()
+        };
+        let restored = SchedulingGaOptions::from_json(&options.to_json()).unwrap();
+
+        let run = |opts: &SchedulingGaOptions| {
+            let problem = SchedulingGaProblem::new(&tasks, &resources).with_options(opts);
+            let config = opts.apply_to(
+                GaConfig::default()
+                    .with_population_size(10)
+                    .with_max_generations(5)
+                    .with_parallel(false),
+            );
+            GaRunner::run(&problem, &config).best_fitness
+        };
+        assert_eq!(run(&options), run(&restored));
+    }
+
+    #[test]
+    fn test_mix_thresholds() {
+        let mix = InitializationMix {
+            random: 2.0,
+            load_balanced: 1.0,
+            shortest_time: 1.0,
+        };
+        assert_eq!(mix.thresholds(), (0.5, 0.75));
+        let degenerate = InitializationMix {
+            random: 0.0,
+            load_balanced: 0.0,
+            shortest_time: 0.0,
+        };
+        assert_eq!(degenerate.thresholds(), (1.0, 1.0));
+    }
+}
@@ -6,20 +6,31 @@
 //! # Reference
 //! Cheng et al. (1996), "A Tutorial Survey of JSSP using GA"
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use u_metaheur::ga::GaProblem;
 
 use super::chromosome::ScheduleChromosome;
 use super::operators::GeneticOperators;
-use crate::models::{Assignment, Resource, Schedule, Task, TransitionMatrixCollection};
+use super::options::InitializationMix;
+use crate::models::{
+    earliest_fit, AffinityTable, Assignment, LockSet, PriorityClass, Resource, ResourceType,
+    Schedule, SyncGroups, Task, TimeWindow, ToolingRules, TransitionMatrixCollection,
+    WeightProfile,
+};
+use crate::objective;
+use crate::scheduler::{CapacityLeveling, ScheduleKpi, WorkloadFairness};
+use crate::validation::{check_schedule, ValidationError, ValidationErrorKind};
 
 /// Compact activity descriptor for GA encoding.
 ///
 /// Extracted from `Task`/`Activity` to avoid cloning full domain objects.
 #[derive(Debug, Clone)]
 pub struct ActivityInfo {
+    /// Activity ID (used for decoded assignments).
+    pub activity_id: String,
     /// Parent task ID.
     pub task_id: String,
     /// Activity sequence within task (1-based).
@@ -32,17 +43,34 @@ pub struct ActivityInfo {
 
 impl ActivityInfo {
     /// Extracts activity info from domain tasks.
+    ///
+    /// Activities are numbered 1, 2, … within their task, so tasks sharing
+    /// an ID yield duplicate `(task_id, sequence)` keys; use
+    /// [`try_from_tasks`](Self::try_from_tasks) to reject those.
     pub fn from_tasks(tasks: &[Task]) -> Vec<Self> {
+        Self::from_tasks_with_resources(tasks, &[])
+    }
+
+    /// Like [`from_tasks`](Self::from_tasks), but requirements without
+    /// candidates are expanded to the matching `resources` (see
+    /// [`ResourceRequirement::resolve`](crate::models::ResourceRequirement::resolve)).
+    pub fn from_tasks_with_resources(tasks: &[Task], resources: &[Resource]) -> Vec<Self> {
         let mut infos = Vec::new();
         for task in tasks {
             for (i, activity) in task.activities.iter().enumerate() {
+                // Tools are mounted on the machine, never chosen as it
+                let tool = activity.tool_requirement(resources);
                 infos.push(ActivityInfo {
+                    activity_id: activity.id.clone(),
                     task_id: task.id.clone(),
                     sequence: (i + 1) as i32,
                     process_ms: activity.duration.process_ms,
                     candidates: activity
-                        .candidate_resources()
-                        .into_iter()
+                        .resource_requirements
+                        .iter()
+                        .enumerate()
+                        .filter(|&(k, _)| Some(k) != tool)
+                        .flat_map(|(_, req)| req.resolve(resources))
                         .map(|s| s.to_string())
                         .collect(),
                 });
@@ -50,6 +78,84 @@ impl ActivityInfo {
         }
         infos
     }
+
+    /// Like [`from_tasks`](Self::from_tasks), but fails on the first
+    /// duplicate `(task_id, sequence)` key.
+    pub fn try_from_tasks(tasks: &[Task]) -> Result<Vec<Self>, ValidationError> {
+        let infos = Self::from_tasks(tasks);
+        Self::check_unique(&infos)?;
+        Ok(infos)
+    }
+
+    /// Checks that no two infos share a `(task_id, sequence)` key, which
+    /// chromosome and decoder indices require.
+    pub fn check_unique(infos: &[Self]) -> Result<(), ValidationError> {
+        let mut seen = HashSet::new();
+        for info in infos {
+            if !seen.insert((info.task_id.as_str(), info.sequence)) {
+                return Err(ValidationError::new(
+                    ValidationErrorKind::DuplicateSequence,
+                    format!(
+                        "Duplicate activity key: task '{}', sequence {}",
+                        info.task_id, info.sequence
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Weighted fitness terms of a decoded schedule.
+///
+/// Each field is a term of [`SchedulingGaProblem`]'s fitness after its
+/// weight was applied (ms-equivalent); terms whose weight is zero are 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FitnessBreakdown {
+    /// Makespan, weighted by `1 - tardiness_weight`.
+    pub makespan: f64,
+    /// Class- and rate-scaled total tardiness.
+    pub tardiness: f64,
+    /// Total overrun of maximum flow times.
+    pub flow_overrun: f64,
+    /// Class-weighted mean completion time.
+    pub class: f64,
+    /// Workload spread across human resources.
+    pub fairness: f64,
+    /// Affinity regret of resource choices.
+    pub affinity: f64,
+    /// Capacity leveling excess.
+    pub leveling: f64,
+    /// Changeovers beyond resource setup limits.
+    pub setup_limit: f64,
+    /// Penalty for activities left unscheduled.
+    pub unscheduled: f64,
+}
+
+impl FitnessBreakdown {
+    /// The fitness: sum of all terms (lower is better).
+    pub fn total(&self) -> f64 {
+        self.makespan
+            + self.tardiness
+            + self.flow_overrun
+            + self.class
+            + self.fairness
+            + self.affinity
+            + self.leveling
+            + self.setup_limit
+            + self.unscheduled
+    }
+}
+
+/// A decoded chromosome with its KPIs and fitness terms.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedSolution {
+    /// The schedule, as [`SchedulingGaProblem::decode`] returns it.
+    pub schedule: Schedule,
+    /// KPIs of the schedule.
+    pub kpi: ScheduleKpi,
+    /// Fitness terms, consistent with the GA's evaluation.
+    pub fitness: FitnessBreakdown,
 }
 
 /// GA problem definition for scheduling optimization.
@@ -81,8 +187,21 @@ pub struct SchedulingGaProblem {
     pub deadlines: HashMap<String, i64>,
     /// Task release times (task_id → release_ms).
     pub release_times: HashMap<String, i64>,
+    /// Latest completions from maximum flow times (task_id → ms; see
+    /// [`Task::flow_deadline`]).
+    pub flow_deadlines: HashMap<String, i64>,
+    /// Weight of the total flow-time overrun (ms) in fitness (default: 10.0).
+    pub flow_weight: f64,
     /// Weight for tardiness in fitness (default: 0.5).
     pub tardiness_weight: f64,
+    /// Task priority classes (task_id → class).
+    pub task_classes: HashMap<String, PriorityClass>,
+    /// Tardiness rate per task relative to the default rate
+    /// (task_id → factor; missing = 1.0). See [`WeightProfile`].
+    pub tardiness_rates: HashMap<String, f64>,
+    /// Weight of the class-weighted mean completion time in fitness
+    /// (default: 1.0 if tasks span several priority classes, else 0.0).
+    pub class_weight: f64,
     /// Per-resource processing times: `(task_id, sequence, resource_id) → ms`.
     ///
     /// Used for SPT (Shortest Processing Time) initialization.
@@ -93,30 +212,117 @@ pub struct SchedulingGaProblem {
     /// Default: POX crossover + Swap mutation.
     /// Override with [`with_operators`](SchedulingGaProblem::with_operators).
     pub operators: GeneticOperators,
+    /// Initial population strategy mix (default: 50% random,
+    /// 25% load-balanced, 25% SPT).
+    pub initialization: InitializationMix,
+    /// Fitness penalty per activity left unscheduled by the decoder (default: 0.0).
+    pub unscheduled_penalty: f64,
+    /// Weight of the workload spread (max − min assigned ms) across
+    /// human resources in fitness (default: 0.0).
+    pub fairness_weight: f64,
+    /// Affinity regret per `(task_id, resource_id)`: best candidate score
+    /// minus this resource's score (ms-equivalent, ≥ 0).
+    pub affinity_regret: HashMap<(String, String), f64>,
+    /// Weight of the total affinity regret in fitness (default: 1.0).
+    pub affinity_weight: f64,
+    /// Capacity limit per time bucket (default: none).
+    pub leveling: Option<CapacityLeveling>,
+    /// Weight of the leveling excess in fitness (default: 1.0).
+    pub leveling_weight: f64,
+    /// Fitness penalty per changeover beyond a resource's
+    /// [`max_setups_per_window`](Resource::max_setups_per_window)
+    /// (ms-equivalent; default: 3 600 000, one hour).
+    pub setup_limit_weight: f64,
+    /// Setup crew candidates per `(task_id, sequence)`, for activities
+    /// whose setup needs a secondary resource.
+    pub setup_crews: HashMap<(String, i32), Vec<String>>,
+    /// Tools that categories must mount, with transfer delays.
+    pub tooling: ToolingRules,
+    /// Tool candidates per `(task_id, sequence)`, for activities with a
+    /// [`tool_requirement`](crate::models::Activity::tool_requirement)
+    /// (used when the category has no tool pool).
+    pub tool_pools: HashMap<(String, i32), Vec<String>>,
+    /// Assignments of locked tasks and frozen activities, copied into
+    /// every decoded schedule.
+    pub locked_assignments: Vec<Assignment>,
+    /// End of each frozen activity; decoding skips it and starts the
+    /// task's later activities after it.
+    pub frozen_ends: HashMap<String, i64>,
+    /// Time unavailable to unlocked work, per resource (sorted, disjoint).
+    pub blocked: HashMap<String, Vec<TimeWindow>>,
+    /// Activities that must start together; decoding repairs them.
+    pub sync_groups: SyncGroups,
     /// Precomputed index: `(task_id, sequence) → activities index`.
     ///
     /// Built once at construction, enables O(1) activity lookup during decode.
     activity_index: HashMap<(String, i32), usize>,
+    /// Input tasks, for auditing decoded schedules.
+    tasks: Vec<Task>,
 }
 
 impl SchedulingGaProblem {
     /// Creates a problem from domain models.
+    ///
+    /// # Panics
+    /// If two tasks share an ID (see [`try_new`](Self::try_new)).
     pub fn new(tasks: &[Task], resources: &[Resource]) -> Self {
-        let activities = ActivityInfo::from_tasks(tasks);
+        Self::try_new(tasks, resources).unwrap_or_else(|e| panic!("{}", e.message))
+    }
+
+    /// Creates a problem from domain models, rejecting tasks that share
+    /// an ID (their activities would collide in the `(task_id, sequence)`
+    /// index).
+    pub fn try_new(tasks: &[Task], resources: &[Resource]) -> Result<Self, ValidationError> {
+        let activities = ActivityInfo::from_tasks_with_resources(tasks, resources);
+        ActivityInfo::check_unique(&activities)?;
         let mut task_categories = HashMap::new();
         let mut deadlines = HashMap::new();
         let mut release_times = HashMap::new();
+        let mut flow_deadlines = HashMap::new();
+        let mut task_classes = HashMap::new();
+        let mut tardiness_rates = HashMap::new();
+        let mut setup_crews = HashMap::new();
+        let mut tool_pools = HashMap::new();
 
         for task in tasks {
+            for (i, activity) in task.activities.iter().enumerate() {
+                if let Some(req) = &activity.setup_requirement {
+                    if !req.candidates.is_empty() {
+                        setup_crews
+                            .insert((task.id.clone(), (i + 1) as i32), req.candidates.clone());
+                    }
+                }
+                if let Some(k) = activity.tool_requirement(resources) {
+                    let pool = activity.resource_requirements[k].resolve(resources);
+                    tool_pools.insert(
+                        (task.id.clone(), (i + 1) as i32),
+                        pool.into_iter().map(String::from).collect(),
+                    );
+                }
+            }
             task_categories.insert(task.id.clone(), task.category.clone());
+            task_classes.insert(task.id.clone(), task.priority_class);
+            if task.tardiness_cost_per_hour.is_some() {
+                tardiness_rates.insert(
+                    task.id.clone(),
+                    WeightProfile::default().relative_weight(task),
+                );
+            }
             if let Some(dl) = task.deadline {
                 deadlines.insert(task.id.clone(), dl);
             }
             if let Some(rt) = task.release_time {
                 release_times.insert(task.id.clone(), rt);
             }
+            if let Some(limit) = task.flow_deadline() {
+                flow_deadlines.insert(task.id.clone(), limit);
+            }
         }
 
+        let mixed_classes = tasks
+            .windows(2)
+            .any(|w| w[0].priority_class != w[1].priority_class);
+
         // Build (task_id, sequence) → index lookup for O(1) decode
         let activity_index: HashMap<(String, i32), usize> = activities
             .iter()
@@ -124,18 +330,39 @@ impl SchedulingGaProblem {
             .map(|(i, a)| ((a.task_id.clone(), a.sequence), i))
             .collect();
 
-        Self {
+        Ok(Self {
             activities,
             resources: resources.to_vec(),
             task_categories,
             transition_matrices: TransitionMatrixCollection::new(),
             deadlines,
             release_times,
+            flow_deadlines,
+            flow_weight: 10.0,
             tardiness_weight: 0.5,
+            class_weight: if mixed_classes { 1.0 } else { 0.0 },
+            task_classes,
+            tardiness_rates,
             process_times: HashMap::new(),
             operators: GeneticOperators::default(),
+            initialization: InitializationMix::default(),
+            unscheduled_penalty: 0.0,
+            fairness_weight: 0.0,
+            affinity_regret: HashMap::new(),
+            affinity_weight: 1.0,
+            leveling: None,
+            leveling_weight: 1.0,
+            setup_limit_weight: 3_600_000.0,
+            setup_crews,
+            tooling: ToolingRules::new(),
+            tool_pools,
+            locked_assignments: Vec::new(),
+            frozen_ends: HashMap::new(),
+            blocked: HashMap::new(),
+            sync_groups: SyncGroups::from_tasks(tasks),
             activity_index,
-        }
+            tasks: tasks.to_vec(),
+        })
     }
 
     /// Sets transition matrices.
@@ -144,12 +371,154 @@ impl SchedulingGaProblem {
         self
     }
 
+    /// Sets tooling rules: an activity of a category with a tool pool
+    /// waits for the earliest-ready tool (plus its transfer when it moves
+    /// to another machine), which is booked for the activity's interval.
+    pub fn with_tooling(mut self, tooling: ToolingRules) -> Self {
+        self.tooling = tooling;
+        self
+    }
+
     /// Sets tardiness weight (0.0 = pure makespan, 1.0 = pure tardiness).
     pub fn with_tardiness_weight(mut self, weight: f64) -> Self {
         self.tardiness_weight = weight.clamp(0.0, 1.0);
         self
     }
 
+    /// Sets the weight of the class-weighted mean completion time.
+    ///
+    /// 0.0 ignores priority classes except in tardiness weighting.
+    pub fn with_class_weight(mut self, weight: f64) -> Self {
+        self.class_weight = weight.max(0.0);
+        self
+    }
+
+    /// Sets the weight of the human workload spread in fitness.
+    ///
+    /// Counteracts makespan's tendency to load the fastest few people.
+    pub fn with_fairness_weight(mut self, weight: f64) -> Self {
+        self.fairness_weight = weight.max(0.0);
+        self
+    }
+
+    /// Sets the weight of maximum flow time overruns in fitness.
+    ///
+    /// The limit is hard in intent; the default of 10.0 makes an overrun
+    /// cost more than the makespan it could save.
+    pub fn with_flow_weight(mut self, weight: f64) -> Self {
+        self.flow_weight = weight.max(0.0);
+        self
+    }
+
+    /// Penalizes capacity peaks above `leveling.limit` per bucket.
+    ///
+    /// Each resource above the limit in a bucket costs `bucket_ms × weight`,
+    /// the same scale as makespan.
+    pub fn with_leveling(mut self, leveling: CapacityLeveling, weight: f64) -> Self {
+        self.leveling = Some(leveling);
+        self.leveling_weight = weight.max(0.0);
+        self
+    }
+
+    /// Sets the fitness penalty per changeover beyond a resource's
+    /// [`max_setups_per_window`](Resource::max_setups_per_window).
+    pub fn with_setup_limit_weight(mut self, weight: f64) -> Self {
+        self.setup_limit_weight = weight.max(0.0);
+        self
+    }
+
+    /// Sets per-task tardiness rates.
+    ///
+    /// `tasks` must be the tasks the problem was built from. Each task's
+    /// tardiness is scaled by its rate relative to the profile's default,
+    /// so `tardiness_weight` keeps its meaning for default-rate tasks.
+    pub fn with_weight_profile(mut self, tasks: &[Task], weights: &WeightProfile) -> Self {
+        self.tardiness_rates = tasks
+            .iter()
+            .map(|t| (t.id.clone(), weights.relative_weight(t)))
+            .collect();
+        self
+    }
+
+    /// Sets soft task–resource preferences.
+    ///
+    /// `tasks` must be the tasks the problem was built from. Assigning an
+    /// activity to a candidate that scores below the best candidate costs
+    /// the difference (see [`AffinityTable`] for units).
+    pub fn with_affinity(mut self, tasks: &[Task], affinity: &AffinityTable) -> Self {
+        self.affinity_regret.clear();
+        if affinity.is_empty() {
+            return self;
+        }
+        let resources: HashMap<&str, &Resource> =
+            self.resources.iter().map(|r| (r.id.as_str(), r)).collect();
+        for task in tasks {
+            let mut candidates: Vec<&str> = task
+                .activities
+                .iter()
+                .flat_map(|a| a.resolved_candidates(&self.resources))
+                .collect();
+            candidates.sort_unstable();
+            candidates.dedup();
+            let scores: Vec<(&str, f64)> = candidates
+                .into_iter()
+                .filter_map(|id| resources.get(id).map(|r| (id, affinity.score(task, r))))
+                .collect();
+            let best = scores
+                .iter()
+                .map(|&(_, s)| s)
+                .fold(f64::NEG_INFINITY, f64::max);
+            for (id, score) in scores {
+                if best > score {
+                    self.affinity_regret
+                        .insert((task.id.clone(), id.to_string()), best - score);
+                }
+            }
+        }
+        self
+    }
+
+    /// Sets locks.
+    ///
+    /// `tasks` must be the tasks the problem was built from. Locked tasks
+    /// are removed from the encoding and their assignments copied into
+    /// every decoded schedule, as are the assignments of frozen
+    /// activities (whose genes are ignored); locked activities keep a
+    /// single candidate; other activities avoid reserved and locked time.
+    pub fn with_locks(mut self, tasks: &[Task], locks: &LockSet) -> Self {
+        let locked_tasks = locks.locked_tasks();
+        for task in tasks {
+            for (i, activity) in task.activities.iter().enumerate() {
+                let Some(&idx) = self.activity_index.get(&(task.id.clone(), (i + 1) as i32)) else {
+                    continue;
+                };
+                if let Some(resource_id) = locks.resource_for(&activity.id) {
+                    let info = &mut self.activities[idx];
+                    if info.candidates.iter().any(|c| c == resource_id) {
+                        info.candidates = vec![resource_id.to_string()];
+                    }
+                }
+            }
+        }
+        self.activities
+            .retain(|a| !locked_tasks.contains(a.task_id.as_str()));
+        self.activity_index = self
+            .activities
+            .iter()
+            .enumerate()
+            .map(|(i, a)| ((a.task_id.clone(), a.sequence), i))
+            .collect();
+        self.locked_assignments = locks.locked_assignments().cloned().collect();
+        self.frozen_ends = HashMap::new();
+        for id in locks.frozen_activities() {
+            if let Some(end) = locks.frozen_assignments(id).map(|a| a.end_ms).max() {
+                self.frozen_ends.insert(id.to_string(), end);
+            }
+        }
+        self.blocked = locks.blocked_windows();
+        self
+    }
+
     /// Sets per-resource processing times for SPT initialization.
     ///
     /// When set, 25% of the initial population uses SPT (Shortest Processing
@@ -181,12 +550,73 @@ impl SchedulingGaProblem {
         self
     }
 
-    /// Decodes a chromosome into a Schedule.
+    /// Decodes a chromosome into a Schedule with resource warm-ups and
+    /// cool-downs, carrying its [`check_schedule`] violations.
     pub fn decode(&self, chromosome: &ScheduleChromosome) -> Schedule {
+        self.audit(self.decode_assignments(chromosome))
+    }
+
+    /// Decodes a chromosome (as [`decode`](Self::decode)) with the KPIs
+    /// of the schedule and the fitness terms the GA optimized.
+    ///
+    /// The fitness is computed exactly as during evolution, so
+    /// `fitness.total()` equals the chromosome's GA fitness (e.g.
+    /// `GaResult::best_fitness` for the best individual).
+    pub fn decode_with_kpi(
+        &self,
+        chromosome: &ScheduleChromosome,
+        tasks: &[Task],
+    ) -> DecodedSolution {
+        let schedule = self.decode_assignments(chromosome);
+        let fitness = self.fitness_breakdown(&schedule);
+        let schedule = self.audit(schedule);
+        DecodedSolution {
+            kpi: ScheduleKpi::calculate(&schedule, tasks),
+            schedule,
+            fitness,
+        }
+    }
+
+    /// Adds resource warm-ups and cool-downs and the schedule's
+    /// [`check_schedule`] violations.
+    fn audit(&self, mut schedule: Schedule) -> Schedule {
+        schedule.insert_conditioning(&self.resources);
+        schedule.violations = check_schedule(&schedule, &self.tasks, &self.resources);
+        schedule
+    }
+
+    /// Decodes a chromosome without auditing it (fitness evaluation).
+    ///
+    /// Synchronized activities are repaired by decoding again with start
+    /// floors at each group's latest start until all members align.
+    fn decode_assignments(&self, chromosome: &ScheduleChromosome) -> Schedule {
+        let mut floors = HashMap::new();
+        let mut rounds = 0;
+        loop {
+            let schedule = self.decode_with_floors(chromosome, &floors);
+            rounds += 1;
+            if rounds > SyncGroups::MAX_REPAIR_ROUNDS
+                || !self.sync_groups.raise_floors(&schedule, &mut floors)
+            {
+                return schedule;
+            }
+        }
+    }
+
+    /// One decoding pass; activities start no earlier than their `floors`.
+    fn decode_with_floors(
+        &self,
+        chromosome: &ScheduleChromosome,
+        floors: &HashMap<String, i64>,
+    ) -> Schedule {
         let mut schedule = Schedule::new();
+        for a in &self.locked_assignments {
+            schedule.add_assignment(a.clone());
+        }
         let mut resource_available: HashMap<&str, i64> = HashMap::new();
         let mut task_available: HashMap<&str, i64> = HashMap::new();
         let mut last_category: HashMap<&str, &str> = HashMap::new();
+        let mut tool_location: HashMap<&str, &str> = HashMap::new();
 
         // Initialize resource availability
         for resource in &self.resources {
@@ -202,6 +632,11 @@ impl SchedulingGaProblem {
                 Some(&idx) => &self.activities[idx],
                 None => continue,
             };
+            if let Some(&end) = self.frozen_ends.get(&act.activity_id) {
+                let ready = task_available.entry(task_id.as_str()).or_insert(end);
+                *ready = (*ready).max(end);
+                continue;
+            }
 
             // Get assigned resource from MAV
             let resource_id = match chromosome.resource_for(task_id, *seq) {
@@ -213,7 +648,8 @@ impl SchedulingGaProblem {
             let resource_ready = resource_available.get(resource_id).copied().unwrap_or(0);
             let task_ready = task_available.get(task_id.as_str()).copied().unwrap_or(0);
             let release = self.release_times.get(task_id).copied().unwrap_or(0);
-            let earliest = resource_ready.max(task_ready).max(release);
+            let floor = floors.get(&act.activity_id).copied().unwrap_or(0);
+            let earliest = resource_ready.max(task_ready).max(release).max(floor);
 
             // Setup time
             let setup = if let Some(&prev_cat) = last_category.get(resource_id) {
@@ -228,12 +664,74 @@ impl SchedulingGaProblem {
                 0
             };
 
-            let start = earliest;
+            // Setup crew: wait for the earliest-free setter
+            let crew = match self.setup_crews.get(&(task_id.clone(), *seq)) {
+                Some(crews) if setup > 0 => crews
+                    .iter()
+                    .filter_map(|c| resource_available.get(c.as_str()).map(|&t| (c.as_str(), t)))
+                    .min_by_key(|&(_, t)| t),
+                _ => None,
+            };
+
+            // Tooling: the earliest-ready tool, moved from another machine
+            let task_cat = self.task_categories.get(task_id).map_or("", |s| s.as_str());
+            let pool = self.tooling.tools_for(task_cat).or_else(|| {
+                self.tool_pools
+                    .get(&(task_id.clone(), *seq))
+                    .map(Vec::as_slice)
+            });
+            let tool = match pool {
+                Some(pool) => {
+                    let ready = pool
+                        .iter()
+                        .filter_map(|t| {
+                            let free = *resource_available.get(t.as_str())?;
+                            let moved = tool_location
+                                .get(t.as_str())
+                                .is_some_and(|&m| m != resource_id);
+                            let transfer = if moved {
+                                self.tooling.transfer_time(t)
+                            } else {
+                                0
+                            };
+                            Some((t.as_str(), free + transfer))
+                        })
+                        .min_by_key(|&(_, at)| at);
+                    match ready {
+                        Some(tool) => Some(tool),
+                        None => continue,
+                    }
+                }
+                None => None,
+            };
+
+            let start = crew.map_or(earliest, |(_, free)| earliest.max(free));
+            let start = tool.map_or(start, |(_, at)| start.max(at));
+            let start = match self.blocked.get(resource_id) {
+                Some(windows) => earliest_fit(windows, start, setup + act.process_ms),
+                None => start,
+            };
             let end = start + setup + act.process_ms;
 
             schedule.add_assignment(
-                Assignment::new(&act.task_id, task_id, resource_id, start, end).with_setup(setup),
+                Assignment::new(&act.activity_id, task_id, resource_id, start, end)
+                    .with_setup(setup),
             );
+            if let Some((setter, _)) = crew {
+                schedule.add_assignment(
+                    Assignment::new(&act.activity_id, task_id, setter, start, start + setup)
+                        .with_setup(setup),
+                );
+                resource_available.insert(setter, start + setup);
+            }
+            if let Some((tool_id, _)) = tool {
+                schedule.add_assignment(
+                    Assignment::new(&act.activity_id, task_id, tool_id, start, end)
+                        .with_setup(setup),
+                );
+                resource_available.insert(tool_id, end);
+                tool_location.insert(tool_id, resource_id);
+            }
 
             // Update state
             resource_available.insert(resource_id, end);
@@ -247,21 +745,123 @@ impl SchedulingGaProblem {
     }
 
     /// Computes fitness: weighted combination of makespan and tardiness.
+    ///
+    /// Tardiness is scaled per task by its priority class relative to
+    /// Normal and by its tardiness rate, and `class_weight` adds the mean completion time weighted
+    /// by [`PriorityClass::weight`], which pushes higher classes earlier.
+    /// `fairness_weight` adds the human workload spread,
+    /// `affinity_weight` the affinity regret of resource choices,
+    /// `leveling_weight` the capacity leveling excess, and
+    /// `setup_limit_weight` the changeovers beyond resource setup limits.
     fn compute_fitness(&self, schedule: &Schedule) -> f64 {
+        self.fitness_breakdown(schedule).total()
+    }
+
+    /// Weighted fitness terms of a decoded schedule (see
+    /// [`compute_fitness`](Self::compute_fitness)).
+    fn fitness_breakdown(&self, schedule: &Schedule) -> FitnessBreakdown {
         let makespan = schedule.makespan_ms() as f64;
+        let class_of = |task_id: &str| self.task_classes.get(task_id).copied().unwrap_or_default();
+
+        let total_tardiness = objective::weighted_tardiness(
+            schedule,
+            self.deadlines.iter().map(|(task_id, &deadline)| {
+                let rate = self.tardiness_rates.get(task_id).copied().unwrap_or(1.0);
+                let factor = objective::tardiness_factor(class_of(task_id), rate);
+                (task_id.as_str(), deadline, factor)
+            }),
+        );
+
+        let flow_overrun = objective::weighted_tardiness(
+            schedule,
+            self.flow_deadlines
+                .iter()
+                .map(|(task_id, &limit)| (task_id.as_str(), limit, 1.0)),
+        );
+
+        let class_term = if self.class_weight > 0.0 {
+            self.class_weight
+                * objective::class_weighted_completion(
+                    schedule,
+                    self.task_classes
+                        .iter()
+                        .map(|(task_id, &class)| (task_id.as_str(), class)),
+                )
+        } else {
+            0.0
+        };
+
+        let fairness_term = if self.fairness_weight > 0.0 {
+            let humans: Vec<&str> = self
+                .resources
+                .iter()
+                .filter(|r| r.resource_type == ResourceType::Human)
+                .map(|r| r.id.as_str())
+                .collect();
+            self.fairness_weight * WorkloadFairness::calculate(schedule, &humans).spread_ms as f64
+        } else {
+            0.0
+        };
+
+        let affinity_term = if self.affinity_regret.is_empty() {
+            0.0
+        } else {
+            let regret: f64 = schedule
+                .assignments
+                .iter()
+                .filter_map(|a| {
+                    self.affinity_regret
+                        .get(&(a.task_id.clone(), a.resource_id.clone()))
+                })
+                .sum();
+            self.affinity_weight * regret
+        };
+
+        let leveling_term = match &self.leveling {
+            Some(leveling) => {
+                let excess = leveling.profile(schedule, &self.resources).excess;
+                self.leveling_weight * (excess as i64 * leveling.bucket_ms) as f64
+            }
+            None => 0.0,
+        };
+
+        let setup_limit_term = if self.setup_limit_weight > 0.0 {
+            let excess: usize = self
+                .resources
+                .iter()
+                .filter_map(|r| r.max_setups_per_window.map(|limit| (r, limit)))
+                .flat_map(|(r, limit)| {
+                    schedule
+                        .changeovers_per_window(r, &self.tasks)
+                        .into_iter()
+                        .map(move |(_, count)| count.saturating_sub(limit))
+                })
+                .sum();
+            self.setup_limit_weight * excess as f64
+        } else {
+            0.0
+        };
 
-        let total_tardiness: f64 = self
-            .deadlines
+        // Setup-crew assignments are setup-only; don't count them as activities.
+        let scheduled = schedule
+            .assignments
             .iter()
-            .map(|(task_id, &deadline)| {
-                let completion = schedule.task_completion_time(task_id).unwrap_or(0);
-                (completion - deadline).max(0) as f64
-            })
-            .sum();
+            .filter(|a| a.setup_ms == 0 || a.process_ms() > 0)
+            .count();
+        let unscheduled = self.activities.len().saturating_sub(scheduled) as f64;
 
-        // Weighted combination (both terms in ms, comparable scale)
-        let makespan_weight = 1.0 - self.tardiness_weight;
-        makespan_weight * makespan + self.tardiness_weight * total_tardiness
+        // Weighted terms (all in ms, comparable scale)
+        FitnessBreakdown {
+            makespan: (1.0 - self.tardiness_weight) * makespan,
+            tardiness: self.tardiness_weight * total_tardiness,
+            flow_overrun: self.flow_weight * flow_overrun,
+            class: class_term,
+            fairness: fairness_term,
+            affinity: affinity_term,
+            leveling: leveling_term,
+            setup_limit: setup_limit_term,
+            unscheduled: self.unscheduled_penalty * unscheduled,
+        }
     }
 }
 
@@ -269,11 +869,13 @@ impl GaProblem for SchedulingGaProblem {
     type Individual = ScheduleChromosome;
 
     fn create_individual<R: Rng>(&self, rng: &mut R) -> ScheduleChromosome {
-        // 50% random, 25% load-balanced, 25% SPT (or load-balanced if no process_times)
+        // Default mix: 50% random, 25% load-balanced, 25% SPT
+        // (SPT falls back to load-balanced if no process_times)
+        let (random_cut, balanced_cut) = self.initialization.thresholds();
         let roll: f64 = rng.random_range(0.0..1.0);
-        if roll < 0.5 {
+        if roll < random_cut {
             ScheduleChromosome::random(&self.activities, rng)
-        } else if roll < 0.75 || self.process_times.is_empty() {
+        } else if roll < balanced_cut || self.process_times.is_empty() {
             let cap: HashMap<String, i64> = self
                 .resources
                 .iter()
@@ -286,7 +888,7 @@ impl GaProblem for SchedulingGaProblem {
     }
 
     fn evaluate(&self, individual: &ScheduleChromosome) -> f64 {
-        let schedule = self.decode(individual);
+        let schedule = self.decode_assignments(individual);
         self.compute_fitness(&schedule)
     }
 
@@ -315,7 +917,9 @@ unsafe impl Sync for SchedulingGaProblem {}
 mod tests {
     use super::*;
     use crate::ga::operators::{CrossoverType, MutationType};
-    use crate::models::{Activity, ActivityDuration, ResourceRequirement, ResourceType};
+    use crate::models::{
+        Activity, ActivityDuration, ResourceRequirement, ResourceType, ToolingRules,
+    };
     use rand::rngs::SmallRng;
     use rand::SeedableRng;
     use u_metaheur::ga::{GaConfig, GaRunner};
@@ -374,6 +978,35 @@ mod tests {
         assert_eq!(infos[2].task_id, "T2");
     }
 
+    #[test]
+    fn test_duplicate_activity_keys_rejected() {
+        let (mut tasks, resources) = make_test_problem();
+        assert!(ActivityInfo::try_from_tasks(&tasks).is_ok());
+        assert!(SchedulingGaProblem::try_new(&tasks, &resources).is_ok());
+
+        // A second task "T2" would map (T2, 1) to two activities.
+        let mut twin = tasks[1].clone();
+        twin.activities[0].id = "T2b_O1".into();
+        tasks.push(twin);
+        let err = ActivityInfo::try_from_tasks(&tasks).unwrap_err();
+        assert_eq!(err.kind, ValidationErrorKind::DuplicateSequence);
+        assert!(err.message.contains("'T2', sequence 1"));
+        assert!(SchedulingGaProblem::try_new(&tasks, &resources).is_err());
+
+        let mut infos = ActivityInfo::from_tasks(&tasks[..2]);
+        assert!(ActivityInfo::check_unique(&infos).is_ok());
+        infos[1].sequence = 1;
+        assert!(ActivityInfo::check_unique(&infos).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Duplicate activity key")]
+    fn test_new_panics_on_shared_task_id() {
+        let (mut tasks, resources) = make_test_problem();
+        tasks.push(tasks[0].clone());
+        let _ = SchedulingGaProblem::new(&tasks, &resources);
+    }
+
     #[test]
     fn test_decode_chromosome() {
         let (tasks, resources) = make_test_problem();
@@ -387,6 +1020,110 @@ mod tests {
         assert!(schedule.makespan_ms() > 0);
     }
 
+    #[test]
+    fn test_type_based_candidates_expanded() {
+        let (mut tasks, resources) = make_test_problem();
+        tasks[1].activities[0].resource_requirements[0]
+            .candidates
+            .clear();
+        tasks[1].activities[0].resource_requirements[0].resource_type = "primary".into();
+        let problem = SchedulingGaProblem::new(&tasks, &resources);
+        let t2 = problem
+            .activities
+            .iter()
+            .find(|a| a.activity_id == "T2_O1")
+            .unwrap();
+        assert_eq!(t2.candidates, vec!["M1", "M2", "M3"]);
+
+        let mut rng = SmallRng::seed_from_u64(3);
+        let schedule = problem.decode(&problem.create_individual(&mut rng));
+        assert_eq!(schedule.assignments_for_task("T2").len(), 1);
+    }
+
+    #[test]
+    fn test_flow_overrun_penalized() {
+        let (tasks, resources) = make_test_problem();
+        let limited: Vec<Task> = tasks
+            .iter()
+            .cloned()
+            .map(|t| {
+                if t.id == "T2" {
+                    t.with_max_flow(1000)
+                } else {
+                    t
+                }
+            })
+            .collect();
+        let plain = SchedulingGaProblem::new(&tasks, &resources);
+        let problem = SchedulingGaProblem::new(&limited, &resources);
+        assert_eq!(problem.flow_deadlines.get("T2"), Some(&1000));
+
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("T2_O1", "T2", "M1", 1000, 2500));
+        // 1500 ms over, at the default weight of 10.
+        let extra = problem.compute_fitness(&schedule) - plain.compute_fitness(&schedule);
+        assert!((extra - 15_000.0).abs() < 1e-6);
+        assert_eq!(
+            problem.with_flow_weight(0.0).compute_fitness(&schedule),
+            plain.compute_fitness(&schedule)
+        );
+    }
+
+    #[test]
+    fn test_locks() {
+        use crate::models::{Lock, LockSet};
+
+        let (tasks, resources) = make_test_problem();
+        let mut manual = Schedule::new();
+        manual.add_assignment(Assignment::new("T2_O1", "T2", "M3", 0, 1500));
+        let locks = LockSet::new()
+            .with_lock(Lock::task(&manual, "T2"))
+            .with_lock(Lock::activity_resource("T1_O1", "M1"))
+            .with_lock(Lock::resource_window("M2", 0, 5000));
+        let problem = SchedulingGaProblem::new(&tasks, &resources).with_locks(&tasks, &locks);
+        assert_eq!(problem.activities.len(), 2);
+
+        let mut rng = SmallRng::seed_from_u64(7);
+        for _ in 0..10 {
+            let schedule = problem.decode(&problem.create_individual(&mut rng));
+            let t2 = schedule.assignments_for_task("T2");
+            assert_eq!(t2.len(), 1);
+            assert_eq!((t2[0].resource_id.as_str(), t2[0].start_ms), ("M3", 0));
+            let t1 = schedule.assignments_for_task("T1");
+            assert_eq!(t1[0].resource_id, "M1");
+            assert!(t1[1].start_ms >= 5000);
+        }
+    }
+
+    #[test]
+    fn test_frozen_activities() {
+        use crate::models::LockSet;
+
+        let (tasks, resources) = make_test_problem();
+        let mut current = Schedule::new();
+        current.add_assignment(Assignment::new("T1_O1", "T1", "M2", 2000, 3000));
+        current.add_assignment(Assignment::new("T2_O1", "T2", "M1", 4000, 5000));
+        let locks = LockSet::freeze(&current, |a| a.activity_id == "T1_O1");
+        let problem = SchedulingGaProblem::new(&tasks, &resources).with_locks(&tasks, &locks);
+        assert_eq!(problem.frozen_ends["T1_O1"], 3000);
+
+        let mut rng = SmallRng::seed_from_u64(11);
+        for _ in 0..10 {
+            let schedule = problem.decode(&problem.create_individual(&mut rng));
+            let t1 = schedule.assignments_for_task("T1");
+            assert_eq!(t1.len(), 2);
+            assert_eq!(
+                (t1[0].resource_id.as_str(), t1[0].start_ms, t1[0].end_ms),
+                ("M2", 2000, 3000)
+            );
+            assert!(t1[1].start_ms >= 3000);
+            // Other work avoids the frozen interval.
+            for a in schedule.assignments_for_resource("M2") {
+                assert!(a.activity_id == "T1_O1" || a.end_ms <= 2000 || a.start_ms >= 3000);
+            }
+        }
+    }
+
     #[test]
     fn test_fitness_computation() {
         let (tasks, resources) = make_test_problem();
@@ -415,6 +1152,36 @@ mod tests {
         assert!(result.generations > 0);
     }
 
+    #[test]
+    fn test_decode_with_kpi_matches_fitness() {
+        let (mut tasks, resources) = make_test_problem();
+        // T1 needs at least 3000 ms: always late.
+        tasks[0] = tasks[0].clone().with_deadline(1500);
+        let problem = SchedulingGaProblem::new(&tasks, &resources).with_fairness_weight(0.1);
+        let config = GaConfig::default()
+            .with_population_size(20)
+            .with_max_generations(10)
+            .with_seed(7)
+            .with_parallel(false);
+        let result = GaRunner::run(&problem, &config);
+
+        let solution = problem.decode_with_kpi(&result.best, &tasks);
+        assert_eq!(solution.fitness.total(), result.best_fitness);
+        assert_eq!(
+            solution.schedule.fingerprint(),
+            problem.decode(&result.best).fingerprint()
+        );
+        assert_eq!(solution.kpi.makespan_ms, solution.schedule.makespan_ms());
+        assert!(solution.kpi.total_tardiness_ms >= 1500);
+        assert_eq!(
+            solution.fitness.makespan,
+            (1.0 - problem.tardiness_weight) * solution.kpi.makespan_ms as f64
+        );
+        assert!(solution.fitness.tardiness >= problem.tardiness_weight * 1500.0);
+        assert_eq!(solution.fitness.fairness, 0.0); // no human resources
+        assert_eq!(solution.fitness.unscheduled, 0.0);
+    }
+
     #[test]
     fn test_crossover_and_mutation() {
         let (tasks, resources) = make_test_problem();
@@ -448,6 +1215,383 @@ mod tests {
         assert!(f1 != f2 || (f1 == 0.0 && f2 == 0.0));
     }
 
+    #[test]
+    fn test_priority_class_fitness() {
+        let make = |id: &str, ms: i64, class: PriorityClass| {
+            Task::new(id).with_priority_class(class).with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_duration(ActivityDuration::fixed(ms))
+                    .with_requirement(
+                        ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                    ),
+            )
+        };
+        let tasks = vec![
+            make("R", 5000, PriorityClass::Rush),
+            make("F", 500, PriorityClass::Filler),
+        ];
+        let resources = vec![Resource::new("M1", ResourceType::Primary)];
+        let problem = SchedulingGaProblem::new(&tasks, &resources);
+        assert_eq!(problem.class_weight, 1.0);
+
+        let mut rng = SmallRng::seed_from_u64(1);
+        let mut rush_first = problem.create_individual(&mut rng);
+        rush_first.osv = vec!["R".into(), "F".into()];
+        let mut filler_first = rush_first.clone();
+        filler_first.osv = vec!["F".into(), "R".into()];
+
+        // Same makespan; the class term prefers Rush first.
+        assert!(problem.evaluate(&rush_first) < problem.evaluate(&filler_first));
+
+        let unweighted = SchedulingGaProblem::new(&tasks, &resources).with_class_weight(0.0);
+        assert_eq!(
+            unweighted.evaluate(&rush_first),
+            unweighted.evaluate(&filler_first)
+        );
+        let (single, _) = make_test_problem();
+        assert_eq!(
+            SchedulingGaProblem::new(&single, &resources).class_weight,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_weight_profile() {
+        let make = |id: &str, customer: &str| {
+            Task::new(id)
+                .with_deadline(0)
+                .with_attribute("customer", customer)
+                .with_activity(
+                    Activity::new(format!("{id}_O1"), id, 0)
+                        .with_duration(ActivityDuration::fixed(1000))
+                        .with_requirement(
+                            ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                        ),
+                )
+        };
+        let tasks = vec![make("A", "ACME"), make("B", "Other")];
+        let resources = vec![Resource::new("M1", ResourceType::Primary)];
+        let weights = WeightProfile::new().with_customer("ACME", 10.0);
+        let problem = SchedulingGaProblem::new(&tasks, &resources)
+            .with_tardiness_weight(1.0)
+            .with_weight_profile(&tasks, &weights);
+        assert_eq!(problem.tardiness_rates["A"], 10.0);
+        assert_eq!(problem.tardiness_rates["B"], 1.0);
+
+        let mut rng = SmallRng::seed_from_u64(1);
+        let mut acme_first = problem.create_individual(&mut rng);
+        acme_first.osv = vec!["A".into(), "B".into()];
+        let mut other_first = acme_first.clone();
+        other_first.osv = vec!["B".into(), "A".into()];
+
+        // A late by 1000 and B by 2000: 10·1000 + 2000 vs 1000 + 10·2000.
+        assert_eq!(problem.evaluate(&acme_first), 12_000.0);
+        assert_eq!(problem.evaluate(&other_first), 21_000.0);
+
+        // Explicit task rates apply without a profile.
+        let explicit = vec![tasks[0].clone().with_tardiness_cost(3.0)];
+        let problem = SchedulingGaProblem::new(&explicit, &resources);
+        assert_eq!(problem.tardiness_rates["A"], 3.0);
+    }
+
+    #[test]
+    fn test_fairness_weight() {
+        let tasks: Vec<Task> = (0..2)
+            .map(|i| {
+                let id = format!("T{i}");
+                Task::new(&id).with_activity(
+                    Activity::new(format!("{id}_O1"), &id, 0)
+                        .with_duration(ActivityDuration::fixed(1000))
+                        .with_requirement(
+                            ResourceRequirement::new("Crew")
+                                .with_candidates(vec!["W1".into(), "W2".into()]),
+                        ),
+                )
+            })
+            .collect();
+        let resources = vec![
+            Resource::new("W1", ResourceType::Human),
+            Resource::new("W2", ResourceType::Human),
+        ];
+        let problem = SchedulingGaProblem::new(&tasks, &resources)
+            .with_tardiness_weight(0.0)
+            .with_fairness_weight(1.0);
+
+        let mut rng = SmallRng::seed_from_u64(3);
+        let mut shared = problem.create_individual(&mut rng);
+        shared.set_resource("T0", 1, "W1".into());
+        shared.set_resource("T1", 1, "W2".into());
+        let mut piled = shared.clone();
+        piled.set_resource("T1", 1, "W1".into());
+
+        // Shared: makespan 1000, spread 0. Piled: makespan 2000, spread 2000.
+        assert_eq!(problem.evaluate(&shared), 1000.0);
+        assert_eq!(problem.evaluate(&piled), 4000.0);
+    }
+
+    #[test]
+    fn test_affinity_regret() {
+        use crate::models::AffinityRule;
+
+        let (mut tasks, resources) = make_test_problem();
+        tasks[0] = tasks[0].clone().with_attribute("preferred_machine", "M2");
+        let affinity = AffinityTable::new().with_rule(AffinityRule::matching(
+            "preferred_machine",
+            "id",
+            700.0,
+        ));
+        let problem = SchedulingGaProblem::new(&tasks, &resources)
+            .with_tardiness_weight(0.0)
+            .with_affinity(&tasks, &affinity);
+        assert_eq!(
+            problem.affinity_regret,
+            HashMap::from([(("T1".to_string(), "M1".to_string()), 700.0)])
+        );
+
+        let mut rng = SmallRng::seed_from_u64(5);
+        let mut preferred = problem.create_individual(&mut rng);
+        preferred.set_resource("T1", 1, "M2".into());
+        let mut other = preferred.clone();
+        other.set_resource("T1", 1, "M1".into());
+        let makespan = |c| problem.decode(c).makespan_ms() as f64;
+        assert_eq!(problem.evaluate(&preferred), makespan(&preferred));
+        assert_eq!(problem.evaluate(&other), makespan(&other) + 700.0);
+    }
+
+    #[test]
+    fn test_setup_crew_delays_changeover() {
+        use crate::models::TransitionMatrix;
+
+        let task = |id: &str, category: &str| {
+            Task::new(id).with_category(category).with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_duration(ActivityDuration::fixed(1000))
+                    .with_requirement(
+                        ResourceRequirement::new("Machine")
+                            .with_candidates(vec!["M1".into(), "M2".into()]),
+                    )
+                    .with_setup_requirement(
+                        ResourceRequirement::new("Setter").with_candidates(vec!["S1".into()]),
+                    ),
+            )
+        };
+        let tasks = vec![
+            task("A1", "A"),
+            task("A2", "A"),
+            task("B1", "B"),
+            task("B2", "B"),
+        ];
+        let resources = vec![
+            Resource::new("M1", ResourceType::Primary),
+            Resource::new("M2", ResourceType::Primary),
+            Resource::new("S1", ResourceType::Human),
+        ];
+        let matrices = TransitionMatrixCollection::new()
+            .with_matrix(TransitionMatrix::new("m1", "M1").with_default(500))
+            .with_matrix(TransitionMatrix::new("m2", "M2").with_default(500));
+        let mut problem = SchedulingGaProblem::new(&tasks, &resources)
+            .with_transition_matrices(matrices)
+            .with_tardiness_weight(0.0);
+        problem.unscheduled_penalty = 1e6;
+        assert_eq!(problem.setup_crews.len(), 4);
+
+        let mut rng = SmallRng::seed_from_u64(7);
+        let mut c = problem.create_individual(&mut rng);
+        c.osv = ["A1", "A2", "B1", "B2"].map(String::from).to_vec();
+        for (id, m) in [("A1", "M1"), ("A2", "M2"), ("B1", "M1"), ("B2", "M2")] {
+            c.set_resource(id, 1, m.into());
+        }
+
+        let schedule = problem.decode(&c);
+        // Both changeovers need S1: the second waits for the first.
+        let setter: Vec<(i64, i64)> = schedule
+            .assignments_for_resource("S1")
+            .iter()
+            .map(|a| (a.start_ms, a.end_ms))
+            .collect();
+        assert_eq!(setter, vec![(1000, 1500), (1500, 2000)]);
+        assert_eq!(schedule.makespan_ms(), 3000);
+        // Setter assignments don't hide unscheduled activities.
+        assert_eq!(problem.evaluate(&c), 3000.0);
+    }
+
+    #[test]
+    fn test_shared_tool_serializes_machines() {
+        let tasks: Vec<Task> = ["J1", "J2"]
+            .iter()
+            .map(|&id| {
+                Task::new(id).with_category("housing").with_activity(
+                    Activity::new(format!("{id}_O1"), id, 0)
+                        .with_duration(ActivityDuration::fixed(1000))
+                        .with_requirement(
+                            ResourceRequirement::new("Machine")
+                                .with_candidates(vec!["M1".into(), "M2".into()]),
+                        ),
+                )
+            })
+            .collect();
+        let resources = vec![
+            Resource::new("M1", ResourceType::Primary),
+            Resource::new("M2", ResourceType::Primary),
+            Resource::new("DIE", ResourceType::Secondary),
+        ];
+        let problem = SchedulingGaProblem::new(&tasks, &resources).with_tooling(
+            ToolingRules::new()
+                .with_requirement("housing", vec!["DIE".into()])
+                .with_transfer_ms(300),
+        );
+
+        let mut rng = SmallRng::seed_from_u64(3);
+        let mut c = problem.create_individual(&mut rng);
+        c.osv = ["J1", "J2"].map(String::from).to_vec();
+        c.set_resource("J1", 1, "M1".into());
+        c.set_resource("J2", 1, "M2".into());
+        let schedule = problem.decode(&c);
+        let j2 = schedule.assignment_for_activity("J2_O1").unwrap();
+        assert_eq!((j2.resource_id.as_str(), j2.start_ms), ("M2", 1300));
+
+        // On the same machine the die stays mounted.
+        c.set_resource("J2", 1, "M1".into());
+        let schedule = problem.decode(&c);
+        assert_eq!(schedule.makespan_ms(), 2000);
+        assert_eq!(schedule.assignments_for_resource("DIE").len(), 2);
+    }
+
+    #[test]
+    fn test_activity_fixture_moves_with_transfer() {
+        let tasks: Vec<Task> = ["J1", "J2"]
+            .iter()
+            .map(|&id| {
+                Task::new(id).with_activity(
+                    Activity::new(format!("{id}_O1"), id, 0)
+                        .with_duration(ActivityDuration::fixed(1000))
+                        .with_requirement(
+                            ResourceRequirement::new("Machine")
+                                .with_candidates(vec!["M1".into(), "M2".into()]),
+                        )
+                        .with_requirement(
+                            ResourceRequirement::new("Fixture").with_candidates(vec!["FX".into()]),
+                        ),
+                )
+            })
+            .collect();
+        let resources = vec![
+            Resource::new("M1", ResourceType::Primary),
+            Resource::new("M2", ResourceType::Primary),
+            Resource::new("FX", ResourceType::Secondary),
+        ];
+        let problem = SchedulingGaProblem::new(&tasks, &resources)
+            .with_tooling(ToolingRules::new().with_transfer_ms(300));
+        // The fixture is never a machine choice.
+        assert_eq!(problem.activities[0].candidates, ["M1", "M2"]);
+
+        let mut rng = SmallRng::seed_from_u64(3);
+        let mut c = problem.create_individual(&mut rng);
+        c.osv = ["J1", "J2"].map(String::from).to_vec();
+        c.set_resource("J1", 1, "M1".into());
+        c.set_resource("J2", 1, "M2".into());
+        let schedule = problem.decode(&c);
+        let fixture: Vec<(i64, i64)> = schedule
+            .assignments_for_resource("FX")
+            .iter()
+            .map(|a| (a.start_ms, a.end_ms))
+            .collect();
+        assert_eq!(fixture, [(0, 1000), (1300, 2300)]);
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_synchronized_activities_repaired() {
+        let (mut tasks, resources) = make_test_problem();
+        tasks[1].activities[0].synchronized_with = vec!["T1_O2".into()];
+        let problem = SchedulingGaProblem::new(&tasks, &resources);
+
+        let mut rng = SmallRng::seed_from_u64(5);
+        let mut c = problem.create_individual(&mut rng);
+        c.osv = ["T2", "T1", "T1"].map(String::from).to_vec();
+        c.set_resource("T1", 1, "M1".into());
+        c.set_resource("T2", 1, "M3".into());
+        let schedule = problem.decode(&c);
+        let start = |id: &str| schedule.assignment_for_activity(id).unwrap().start_ms;
+        // T2_O1 would start at 0; it waits for T1_O2 after T1_O1.
+        assert_eq!(start("T2_O1"), 1000);
+        assert_eq!(start("T1_O2"), 1000);
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_leveling_penalizes_peaks() {
+        let tasks: Vec<Task> = (0..2)
+            .map(|i| {
+                let id = format!("T{i}");
+                Task::new(&id).with_activity(
+                    Activity::new(format!("{id}_O1"), &id, 0)
+                        .with_duration(ActivityDuration::fixed(1000))
+                        .with_requirement(
+                            ResourceRequirement::new("Crew")
+                                .with_candidates(vec!["W1".into(), "W2".into()]),
+                        ),
+                )
+            })
+            .collect();
+        let resources = vec![
+            Resource::new("W1", ResourceType::Human),
+            Resource::new("W2", ResourceType::Human),
+        ];
+        let leveling = CapacityLeveling::new(500, 1).with_resource_type(ResourceType::Human);
+        let problem = SchedulingGaProblem::new(&tasks, &resources)
+            .with_tardiness_weight(0.0)
+            .with_leveling(leveling, 1.0);
+
+        let mut rng = SmallRng::seed_from_u64(3);
+        let mut parallel = problem.create_individual(&mut rng);
+        parallel.set_resource("T0", 1, "W1".into());
+        parallel.set_resource("T1", 1, "W2".into());
+        let mut serial = parallel.clone();
+        serial.set_resource("T1", 1, "W1".into());
+
+        // Parallel: makespan 1000, two buckets with one extra person each.
+        assert_eq!(problem.evaluate(&parallel), 1000.0 + 2.0 * 500.0);
+        assert_eq!(problem.evaluate(&serial), 2000.0);
+    }
+
+    #[test]
+    fn test_setup_limit_penalized() {
+        let tasks: Vec<Task> = ["A", "B"]
+            .iter()
+            .enumerate()
+            .map(|(i, category)| {
+                let id = format!("T{i}");
+                Task::new(&id).with_category(*category).with_activity(
+                    Activity::new(format!("{id}_O1"), &id, 0)
+                        .with_duration(ActivityDuration::fixed(1000))
+                        .with_requirement(
+                            ResourceRequirement::new("Machine")
+                                .with_candidates(vec!["M1".into(), "M2".into()]),
+                        ),
+                )
+            })
+            .collect();
+        let resources = vec![
+            Resource::primary("M1").with_max_setups_per_window(0),
+            Resource::primary("M2"),
+        ];
+        let problem = SchedulingGaProblem::new(&tasks, &resources)
+            .with_tardiness_weight(0.0)
+            .with_setup_limit_weight(60_000.0);
+
+        let mut rng = SmallRng::seed_from_u64(3);
+        let mut parallel = problem.create_individual(&mut rng);
+        parallel.set_resource("T0", 1, "M1".into());
+        parallel.set_resource("T1", 1, "M2".into());
+        let mut serial = parallel.clone();
+        serial.set_resource("T1", 1, "M1".into());
+
+        // Serial: one changeover on M1, which allows none.
+        assert_eq!(problem.evaluate(&parallel), 1000.0);
+        assert_eq!(problem.evaluate(&serial), 2000.0 + 60_000.0);
+    }
+
     #[test]
     fn test_spt_initialization() {
         let (tasks, resources) = make_test_problem();
@@ -0,0 +1,738 @@
+//! Golden instances for solver regression testing.
+//!
+//! Small canonical scheduling instances with known reference KPIs, plus
+//! [`assert_schedule_quality`] to check that a solver's schedule is
+//! feasible and within a tolerance of the reference. Downstream crates
+//! can run these after upgrading to detect solver quality regressions.
+//!
+//! # Instances
+//!
+//! | Instance | Shape | Reference makespan | Optimal |
+//! |----------|-------|--------------------|---------|
+//! | [`ft06`] | 6 jobs × 6 machines job shop | 55 s | yes |
+//! | [`flexible_shop`] | 4 jobs × 2 ops, 3 machines, 2 candidates each | 10 s | yes |
+//! | [`setup_heavy`] | 6 jobs, 1 machine, 3 setup families | 22 s | yes |
+//!
+//! Processing times are given in seconds (1 time unit = 1000 ms).
+//!
+//! # Cross-Solver Consistency
+//!
+//! [`check_solver_consistency`] runs the greedy, GA and CP backends on
+//! one instance and audits each schedule the same way, to catch objective
+//! and constraint definitions drifting apart between modules:
+//!
+//! | Check | Fails when |
+//! |-------|------------|
+//! | Validator | [`crate::validation::check_schedule`] reports violations |
+//! | Quality | [`check_schedule_quality`] reports issues (any makespan) |
+//! | Optimum | The makespan beats a proven-optimal reference |
+//! | KPI | KPIs disagree with the schedule (makespan, tardiness, on-time rate) |
+//! | Objective | The solver's objective differs from the one recomputed from KPIs |
+//!
+//! The CP formulation does not model sequence-dependent setups, so its
+//! schedules fail the setup checks on [`setup_heavy`].
+//!
+//! # Example
+//!
+//! ```
+//! use u_schedule::golden;
+//! use u_schedule::scheduler::SimpleScheduler;
+//!
+//! let instance = golden::setup_heavy();
+//! let schedule = SimpleScheduler::new().schedule_request(&instance.request());
+//! let report = golden::check_schedule_quality(&instance, &schedule, f64::INFINITY);
+//! assert!(report.issues.is_empty());
+//! ```
+//!
+//! # References
+//!
+//! - Fisher & Thompson (1963), "Probabilistic learning combinations of
+//!   local job-shop scheduling rules"
+//! - Brandimarte (1993), "Routing and scheduling in a flexible job shop by tabu search"
+
+use std::collections::HashMap;
+
+use u_metaheur::cp::{SimpleCpSolver, SolverConfig};
+use u_metaheur::ga::{GaConfig, GaProblem, GaRunner};
+
+use crate::cp::ScheduleCpBuilder;
+use crate::ga::SchedulingGaProblem;
+use crate::models::{
+    Activity, ActivityDuration, Resource, ResourceRequirement, Schedule, Task, TransitionMatrix,
+    TransitionMatrixCollection,
+};
+use crate::scheduler::{ScheduleKpi, ScheduleRequest, SimpleScheduler};
+use crate::validation::check_schedule;
+
+/// Relative tolerance when comparing objective values.
+const OBJECTIVE_TOLERANCE: f64 = 1e-9;
+
+/// Milliseconds per time unit in the golden instances.
+pub const TIME_UNIT_MS: i64 = 1000;
+
+/// A canonical instance with its reference KPIs.
+#[derive(Debug, Clone)]
+pub struct GoldenInstance {
+    /// Instance name.
+    pub name: &'static str,
+    /// Tasks to schedule.
+    pub tasks: Vec<Task>,
+    /// Available resources.
+    pub resources: Vec<Resource>,
+    /// Sequence-dependent setup times.
+    pub transition_matrices: TransitionMatrixCollection,
+    /// Reference (best known) makespan in ms.
+    pub reference_makespan_ms: i64,
+    /// Whether the reference makespan is proven optimal.
+    pub optimal: bool,
+}
+
+impl GoldenInstance {
+    /// Builds a schedule request for this instance.
+    pub fn request(&self) -> ScheduleRequest {
+        ScheduleRequest::new(self.tasks.clone(), self.resources.clone())
+            .with_transition_matrices(self.transition_matrices.clone())
+    }
+}
+
+/// Result of checking a schedule against a golden instance.
+#[derive(Debug, Clone)]
+pub struct QualityReport {
+    /// KPIs of the checked schedule.
+    pub kpi: ScheduleKpi,
+    /// Relative makespan gap to the reference: (makespan - ref) / ref.
+    pub gap: f64,
+    /// Feasibility and quality problems found (empty = acceptable).
+    pub issues: Vec<String>,
+}
+
+impl QualityReport {
+    /// Whether the schedule is feasible and within tolerance.
+    pub fn is_acceptable(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// One solver's schedule, audited by [`check_solver_consistency`].
+#[derive(Debug, Clone)]
+pub struct SolverCheck {
+    /// Solver name (`"greedy"`, `"ga"` or `"cp"`).
+    pub solver: &'static str,
+    /// The solver's schedule.
+    pub schedule: Schedule,
+    /// KPIs of the schedule.
+    pub kpi: ScheduleKpi,
+    /// Objective reported by the solver (`None` for greedy).
+    pub reported_objective: Option<f64>,
+    /// Objective recomputed from the KPIs (`None` if not comparable).
+    pub recomputed_objective: Option<f64>,
+    /// Inconsistencies found (empty = consistent).
+    pub issues: Vec<String>,
+}
+
+impl SolverCheck {
+    /// Whether no inconsistency was found.
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// All golden instances.
+pub fn all() -> Vec<GoldenInstance> {
+    vec![ft06(), flexible_shop(), setup_heavy()]
+}
+
+/// Fisher & Thompson 6×6 job shop (optimal makespan 55).
+pub fn ft06() -> GoldenInstance {
+    // (machine, duration) per operation, in routing order.
+    const ROUTES: [[(usize, i64); 6]; 6] = [
+        [(2, 1), (0, 3), (1, 6), (3, 7), (5, 3), (4, 6)],
+        [(1, 8), (2, 5), (4, 10), (5, 10), (0, 10), (3, 4)],
+        [(2, 5), (3, 4), (5, 8), (0, 9), (1, 1), (4, 7)],
+        [(1, 5), (0, 5), (2, 5), (3, 3), (4, 8), (5, 9)],
+        [(2, 9), (1, 3), (4, 5), (5, 4), (0, 3), (3, 1)],
+        [(1, 3), (3, 3), (5, 9), (0, 10), (4, 4), (2, 1)],
+    ];
+
+    let tasks = ROUTES
+        .iter()
+        .enumerate()
+        .map(|(j, route)| {
+            let routing = route
+                .iter()
+                .map(|&(m, d)| (d, vec![format!("M{m}")]))
+                .collect();
+            make_task(&format!("J{j}"), "default", routing)
+        })
+        .collect();
+
+    GoldenInstance {
+        name: "ft06",
+        tasks,
+        resources: (0..6).map(|m| Resource::primary(format!("M{m}"))).collect(),
+        transition_matrices: TransitionMatrixCollection::new(),
+        reference_makespan_ms: 55 * TIME_UNIT_MS,
+        optimal: true,
+    }
+}
+
+/// Small flexible job shop: each operation has two candidate machines.
+///
+/// The reference makespan equals the load lower bound ⌈28 / 3⌉ = 10.
+pub fn flexible_shop() -> GoldenInstance {
+    let routes: [[(i64, [&str; 2]); 2]; 4] = [
+        [(4, ["M1", "M2"]), (3, ["M2", "M3"])],
+        [(3, ["M1", "M2"]), (4, ["M2", "M3"])],
+        [(2, ["M1", "M3"]), (5, ["M2", "M3"])],
+        [(5, ["M1", "M2"]), (2, ["M1", "M3"])],
+    ];
+
+    let tasks = routes
+        .iter()
+        .enumerate()
+        .map(|(j, route)| {
+            let routing = route
+                .iter()
+                .map(|(d, cands)| (*d, cands.iter().map(|c| c.to_string()).collect()))
+                .collect();
+            make_task(&format!("J{j}"), "default", routing)
+        })
+        .collect();
+
+    GoldenInstance {
+        name: "flexible_shop",
+        tasks,
+        resources: ["M1", "M2", "M3"].map(Resource::primary).to_vec(),
+        transition_matrices: TransitionMatrixCollection::new(),
+        reference_makespan_ms: 10 * TIME_UNIT_MS,
+        optimal: true,
+    }
+}
+
+/// Single machine with three setup families and asymmetric changeovers.
+///
+/// Forward changes (A→B, B→C, C→A) cost 2, backward changes cost 6.
+/// Tasks are listed interleaved, so naive input order pays many setups;
+/// grouping families yields 18 (processing) + 2 × 2 (setups) = 22.
+pub fn setup_heavy() -> GoldenInstance {
+    let jobs = [
+        ("J0", "A", 3),
+        ("J1", "B", 4),
+        ("J2", "C", 2),
+        ("J3", "A", 5),
+        ("J4", "B", 3),
+        ("J5", "C", 1),
+    ];
+    let tasks = jobs
+        .iter()
+        .map(|&(id, family, d)| make_task(id, family, vec![(d, vec!["M1".to_string()])]))
+        .collect();
+
+    let mut matrix = TransitionMatrix::new("families", "M1").with_default(6 * TIME_UNIT_MS);
+    for (from, to) in [("A", "B"), ("B", "C"), ("C", "A")] {
+        matrix.set_transition(from, to, 2 * TIME_UNIT_MS);
+    }
+
+    GoldenInstance {
+        name: "setup_heavy",
+        tasks,
+        resources: vec![Resource::primary("M1")],
+        transition_matrices: TransitionMatrixCollection::new().with_matrix(matrix),
+        reference_makespan_ms: 22 * TIME_UNIT_MS,
+        optimal: true,
+    }
+}
+
+fn make_task(id: &str, category: &str, routing: Vec<(i64, Vec<String>)>) -> Task {
+    let mut task = Task::new(id).with_category(category);
+    for (seq, (duration, candidates)) in routing.into_iter().enumerate() {
+        task = task.with_activity(
+            Activity::new(format!("{id}_O{}", seq + 1), id, seq as i32)
+                .with_duration(ActivityDuration::fixed(duration * TIME_UNIT_MS))
+                .with_requirement(ResourceRequirement::new("Machine").with_candidates(candidates)),
+        );
+    }
+    task
+}
+
+/// Checks a schedule against a golden instance.
+///
+/// Verifies that every activity is scheduled exactly once on one of its
+/// candidates for at least its processing time, that routing order and
+/// release times are respected, that no resource exceeds its capacity,
+/// that sequence-dependent setups are honored, and that the makespan is
+/// within `tolerance` (relative, e.g. `0.1` = 10%) of the reference.
+///
+/// Assignments are matched to activities by `activity_id`.
+pub fn check_schedule_quality(
+    instance: &GoldenInstance,
+    schedule: &Schedule,
+    tolerance: f64,
+) -> QualityReport {
+    let mut issues = Vec::new();
+
+    // Coverage, candidates, durations, routing order.
+    for task in &instance.tasks {
+        let mut prev_end: Option<(&str, i64)> = None;
+        for activity in &task.activities {
+            let matches: Vec<_> = schedule
+                .assignments
+                .iter()
+                .filter(|a| a.activity_id == activity.id)
+                .collect();
+            let a = match matches.as_slice() {
+                [a] => *a,
+                [] => {
+                    issues.push(format!("activity {} is not scheduled", activity.id));
+                    prev_end = None;
+                    continue;
+                }
+                _ => {
+                    issues.push(format!(
+                        "activity {} is scheduled {} times",
+                        activity.id,
+                        matches.len()
+                    ));
+                    prev_end = None;
+                    continue;
+                }
+            };
+
+            let candidates = activity.resolved_candidates(&instance.resources);
+            if !candidates.is_empty() && !candidates.contains(&a.resource_id.as_str()) {
+                issues.push(format!(
+                    "activity {} assigned to non-candidate resource {}",
+                    activity.id, a.resource_id
+                ));
+            }
+            if a.process_ms() < activity.duration.process_ms {
+                issues.push(format!(
+                    "activity {} runs {} ms, needs {} ms",
+                    activity.id,
+                    a.process_ms(),
+                    activity.duration.process_ms
+                ));
+            }
+            if let Some(release) = task.release_time {
+                if a.start_ms < release {
+                    issues.push(format!(
+                        "activity {} starts at {} before release {}",
+                        activity.id, a.start_ms, release
+                    ));
+                }
+            }
+            if let Some((prev_id, end)) = prev_end {
+                if a.start_ms < end {
+                    issues.push(format!(
+                        "activity {} starts at {} before predecessor {} ends at {}",
+                        activity.id, a.start_ms, prev_id, end
+                    ));
+                }
+            }
+            prev_end = Some((&activity.id, a.end_ms));
+        }
+    }
+
+    // Capacity and setups per resource.
+    let categories: HashMap<&str, &str> = instance
+        .tasks
+        .iter()
+        .map(|t| (t.id.as_str(), t.category.as_str()))
+        .collect();
+    for resource in &instance.resources {
+        let mut on_resource = schedule.assignments_for_resource(&resource.id);
+        on_resource.sort_by_key(|a| (a.start_ms, a.end_ms));
+
+        let mut events: Vec<(i64, i32)> = on_resource
+            .iter()
+            .flat_map(|a| [(a.start_ms, 1), (a.end_ms, -1)])
+            .collect();
+        events.sort();
+        let mut load = 0;
+        for (time, delta) in events {
+            load += delta;
+            if load > resource.capacity {
+                issues.push(format!(
+                    "resource {} runs {} activities at {} (capacity {})",
+                    resource.id, load, time, resource.capacity
+                ));
+                break;
+            }
+        }
+
+        for pair in on_resource.windows(2) {
+            let from = categories.get(pair[0].task_id.as_str()).copied();
+            let to = categories.get(pair[1].task_id.as_str()).copied();
+            if let (Some(from), Some(to)) = (from, to) {
+                let required =
+                    instance
+                        .transition_matrices
+                        .get_transition_time(&resource.id, from, to);
+                if pair[1].setup_ms < required {
+                    issues.push(format!(
+                        "activity {} on {} has setup {} ms, needs {} ms after {}",
+                        pair[1].activity_id, resource.id, pair[1].setup_ms, required, from
+                    ));
+                }
+            }
+        }
+    }
+
+    // Quality.
+    let kpi = ScheduleKpi::calculate(schedule, &instance.tasks);
+    let reference = instance.reference_makespan_ms.max(1) as f64;
+    let gap = (kpi.makespan_ms as f64 - reference) / reference;
+    if gap > tolerance {
+        issues.push(format!(
+            "makespan {} ms is {:.1}% above reference {} ms (tolerance {:.1}%)",
+            kpi.makespan_ms,
+            gap * 100.0,
+            instance.reference_makespan_ms,
+            tolerance * 100.0
+        ));
+    }
+
+    QualityReport { kpi, gap, issues }
+}
+
+/// Asserts that a schedule is feasible and within `tolerance` of the
+/// instance's reference makespan.
+///
+/// # Panics
+/// Panics with a list of all issues if the check fails.
+pub fn assert_schedule_quality(instance: &GoldenInstance, schedule: &Schedule, tolerance: f64) {
+    let report = check_schedule_quality(instance, schedule, tolerance);
+    if !report.is_acceptable() {
+        panic!(
+            "schedule quality check failed for `{}`:\n  - {}",
+            instance.name,
+            report.issues.join("\n  - ")
+        );
+    }
+}
+
+/// Runs greedy, GA and CP on an instance and audits each schedule.
+///
+/// Uses small, seeded budgets (GA: 20 × 20 generations; CP: 2 s), so the
+/// check is fast and reproducible; see [`check_ga`] and [`check_cp`] for
+/// other budgets.
+pub fn check_solver_consistency(instance: &GoldenInstance) -> Vec<SolverCheck> {
+    vec![
+        check_greedy(instance),
+        check_ga(
+            instance,
+            &GaConfig::default()
+                .with_population_size(20)
+                .with_max_generations(20)
+                .with_seed(1),
+        ),
+        check_cp(
+            instance,
+            &SolverConfig {
+                time_limit_ms: 2000,
+                ..SolverConfig::default()
+            },
+        ),
+    ]
+}
+
+/// Asserts that every solver passes [`check_solver_consistency`].
+///
+/// # Panics
+/// Panics with all issues of all solvers if any check fails.
+pub fn assert_solver_consistency(instance: &GoldenInstance) {
+    let failures: Vec<String> = check_solver_consistency(instance)
+        .iter()
+        .flat_map(|c| c.issues.iter().map(move |i| format!("{}: {i}", c.solver)))
+        .collect();
+    if !failures.is_empty() {
+        panic!(
+            "solver consistency check failed for `{}`:\n  - {}",
+            instance.name,
+            failures.join("\n  - ")
+        );
+    }
+}
+
+/// Audits the greedy scheduler's schedule.
+pub fn check_greedy(instance: &GoldenInstance) -> SolverCheck {
+    let schedule = SimpleScheduler::new().schedule_request(&instance.request());
+    audit(instance, "greedy", schedule, None, None)
+}
+
+/// Audits the GA's best schedule.
+///
+/// The reported objective is the GA's best fitness, which must also match
+/// a fresh evaluation of the best chromosome. It is recomputed as
+/// `(1 − w) × makespan + w × total tardiness` from the KPIs; that formula
+/// covers problems with one priority class and no tardiness rates (all
+/// golden instances), otherwise no recomputed objective is given.
+pub fn check_ga(instance: &GoldenInstance, config: &GaConfig) -> SolverCheck {
+    let problem = SchedulingGaProblem::new(&instance.tasks, &instance.resources)
+        .with_transition_matrices(instance.transition_matrices.clone());
+    let result = GaRunner::run(&problem, config);
+    let schedule = problem.decode(&result.best);
+    let kpi = ScheduleKpi::calculate(&schedule, &instance.tasks);
+
+    let recomputed =
+        (problem.class_weight == 0.0 && problem.tardiness_rates.is_empty()).then(|| {
+            let w = problem.tardiness_weight;
+            (1.0 - w) * kpi.makespan_ms as f64 + w * kpi.total_tardiness_ms as f64
+        });
+    let mut check = audit(
+        instance,
+        "ga",
+        schedule,
+        Some(result.best_fitness),
+        recomputed,
+    );
+    let reevaluated = problem.evaluate(&result.best);
+    if !same_objective(reevaluated, result.best_fitness) {
+        check.issues.push(format!(
+            "best fitness {} differs from re-evaluation {}",
+            result.best_fitness, reevaluated
+        ));
+    }
+    check
+}
+
+/// Audits the CP schedule.
+///
+/// Without deadlines the CP objective is the makespan; with deadlines it
+/// is lexicographic and no recomputed objective is given.
+pub fn check_cp(instance: &GoldenInstance, config: &SolverConfig) -> SolverCheck {
+    // Twice the total work: every activity fits even when run serially.
+    let horizon: i64 = 2 * instance
+        .tasks
+        .iter()
+        .map(|t| t.total_duration_ms())
+        .sum::<i64>();
+    let (schedule, solution) = ScheduleCpBuilder::new(&instance.tasks, &instance.resources)
+        .with_transition_matrices(instance.transition_matrices.clone())
+        .solve(&SimpleCpSolver::new(), config, horizon.max(1));
+
+    let recomputed = instance
+        .tasks
+        .iter()
+        .all(|t| t.deadline.is_none())
+        .then(|| schedule.makespan_ms() as f64);
+    let mut check = audit(
+        instance,
+        "cp",
+        schedule,
+        solution.objective_value,
+        recomputed,
+    );
+    if !solution.is_solution_found() {
+        check
+            .issues
+            .push(format!("no solution found ({:?})", solution.status));
+    }
+    check
+}
+
+/// Checks a schedule with the validator, the quality check, the KPIs and
+/// the objective.
+fn audit(
+    instance: &GoldenInstance,
+    solver: &'static str,
+    schedule: Schedule,
+    reported_objective: Option<f64>,
+    recomputed_objective: Option<f64>,
+) -> SolverCheck {
+    let mut issues: Vec<String> = check_schedule(&schedule, &instance.tasks, &instance.resources)
+        .into_iter()
+        .map(|v| format!("validator: {}", v.message))
+        .collect();
+
+    let quality = check_schedule_quality(instance, &schedule, f64::INFINITY);
+    issues.extend(quality.issues);
+    if instance.optimal && quality.gap < 0.0 {
+        issues.push(format!(
+            "makespan {} ms beats the proven optimum {} ms",
+            quality.kpi.makespan_ms, instance.reference_makespan_ms
+        ));
+    }
+
+    let kpi = quality.kpi;
+    if kpi.makespan_ms != schedule.makespan_ms() {
+        issues.push(format!(
+            "KPI makespan {} ms differs from schedule makespan {} ms",
+            kpi.makespan_ms,
+            schedule.makespan_ms()
+        ));
+    }
+    if kpi.max_tardiness_ms > kpi.total_tardiness_ms || kpi.total_tardiness_ms < 0 {
+        issues.push(format!(
+            "KPI tardiness inconsistent: max {} ms, total {} ms",
+            kpi.max_tardiness_ms, kpi.total_tardiness_ms
+        ));
+    }
+    if !(0.0..=1.0).contains(&kpi.on_time_rate) {
+        issues.push(format!(
+            "KPI on-time rate {} outside [0, 1]",
+            kpi.on_time_rate
+        ));
+    }
+
+    if let (Some(reported), Some(recomputed)) = (reported_objective, recomputed_objective) {
+        if !same_objective(reported, recomputed) {
+            issues.push(format!(
+                "reported objective {reported} differs from recomputed {recomputed}"
+            ));
+        }
+    }
+
+    SolverCheck {
+        solver,
+        schedule,
+        kpi,
+        reported_objective,
+        recomputed_objective,
+        issues,
+    }
+}
+
+/// Whether two objective values agree within [`OBJECTIVE_TOLERANCE`].
+fn same_objective(a: f64, b: f64) -> bool {
+    (a - b).abs() <= OBJECTIVE_TOLERANCE * a.abs().max(b.abs()).max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Assignment;
+    use crate::scheduler::SimpleScheduler;
+    use crate::validation::validate_input;
+
+    fn s(units: i64) -> i64 {
+        units * TIME_UNIT_MS
+    }
+
+    #[test]
+    fn test_instances_are_valid_input() {
+        for instance in all() {
+            assert!(
+                validate_input(&instance.tasks, &instance.resources).is_ok(),
+                "{} failed validation",
+                instance.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_simple_scheduler_is_feasible_on_all() {
+        let scheduler = SimpleScheduler::new();
+        for instance in all() {
+            let schedule = scheduler.schedule_request(&instance.request());
+            let report = check_schedule_quality(&instance, &schedule, f64::INFINITY);
+            assert!(
+                report.issues.is_empty(),
+                "{}: {:?}",
+                instance.name,
+                report.issues
+            );
+            assert!(report.gap >= 0.0, "{} beat the optimum", instance.name);
+        }
+    }
+
+    #[test]
+    fn test_setup_heavy_optimal_sequence() {
+        let instance = setup_heavy();
+        // A A B B C C: setups only on A→B and B→C.
+        let mut schedule = Schedule::new();
+        let plan = [
+            ("J0", 0, 3, 0),
+            ("J3", 3, 8, 0),
+            ("J1", 8, 14, 2),
+            ("J4", 14, 17, 0),
+            ("J2", 17, 21, 2),
+            ("J5", 21, 22, 0),
+        ];
+        for (task, start, end, setup) in plan {
+            schedule.add_assignment(
+                Assignment::new(format!("{task}_O1"), task, "M1", s(start), s(end))
+                    .with_setup(s(setup)),
+            );
+        }
+        assert_schedule_quality(&instance, &schedule, 0.0);
+    }
+
+    #[test]
+    fn test_detects_infeasibility() {
+        let instance = flexible_shop();
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("J0_O1", "J0", "M1", 0, s(4)));
+        schedule.add_assignment(Assignment::new("J0_O2", "J0", "M2", s(3), s(6)));
+        schedule.add_assignment(Assignment::new("J1_O1", "J1", "M1", s(2), s(5)));
+        schedule.add_assignment(Assignment::new("J1_O2", "J1", "M1", s(5), s(9)));
+
+        let report = check_schedule_quality(&instance, &schedule, f64::INFINITY);
+        let has = |needle: &str| report.issues.iter().any(|i| i.contains(needle));
+        assert!(has("J0_O2 starts at 3000 before predecessor"));
+        assert!(has("resource M1 runs 2 activities"));
+        assert!(has("non-candidate resource M1"));
+        assert!(has("J2_O1 is not scheduled"));
+        assert!(!report.is_acceptable());
+    }
+
+    #[test]
+    fn test_detects_missing_setup() {
+        let instance = setup_heavy();
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("J0_O1", "J0", "M1", 0, s(3)));
+        schedule.add_assignment(Assignment::new("J1_O1", "J1", "M1", s(3), s(7)));
+        let report = check_schedule_quality(&instance, &schedule, f64::INFINITY);
+        assert!(report
+            .issues
+            .iter()
+            .any(|i| i.contains("J1_O1 on M1 has setup 0 ms, needs 2000 ms")));
+    }
+
+    #[test]
+    #[should_panic(expected = "above reference")]
+    fn test_assert_rejects_poor_quality() {
+        let instance = setup_heavy();
+        let schedule = SimpleScheduler::new().schedule_request(&instance.request());
+        // Input order interleaves families and pays extra setups.
+        assert_schedule_quality(&instance, &schedule, 0.0);
+    }
+
+    #[test]
+    fn test_solver_consistency() {
+        assert_solver_consistency(&ft06());
+        assert_solver_consistency(&flexible_shop());
+
+        // CP ignores sequence-dependent setups: only setup issues (and
+        // the optimum they let it beat) are expected.
+        for check in check_solver_consistency(&setup_heavy()) {
+            if check.solver == "cp" {
+                assert!(!check.is_consistent());
+                assert!(
+                    check
+                        .issues
+                        .iter()
+                        .all(|i| i.contains("has setup") || i.contains("beats the proven optimum")),
+                    "{:?}",
+                    check.issues
+                );
+            } else {
+                assert!(
+                    check.is_consistent(),
+                    "{}: {:?}",
+                    check.solver,
+                    check.issues
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_objective_mismatch_is_reported() {
+        let instance = flexible_shop();
+        let schedule = SimpleScheduler::new().schedule_request(&instance.request());
+        let check = audit(&instance, "test", schedule, Some(1.0), Some(2.0));
+        assert_eq!(check.issues.len(), 1);
+        assert!(check.issues[0].starts_with("reported objective 1"));
+    }
+}
@@ -0,0 +1,500 @@
+//! Import of schedules produced by external systems.
+//!
+//! A plan from a third-party APS or a spreadsheet names tasks, activities
+//! and resources its own way. [`ScheduleImporter`] maps those IDs onto the
+//! model through an [`IdMap`], flags rows it cannot reconcile as
+//! [`ImportIssue`]s, and audits the result with
+//! [`check_schedule`], so the plan can be compared with ours by KPI and
+//! validator.
+//!
+//! # Reconciliation
+//!
+//! | Row problem | Handling |
+//! |-------------|----------|
+//! | Unknown activity | [`UnknownActivity`](ImportIssue::UnknownActivity); row dropped |
+//! | End before start, or setup outside the interval | [`InvalidInterval`](ImportIssue::InvalidInterval); row dropped |
+//! | Unknown resource | [`UnknownResource`](ImportIssue::UnknownResource); row kept (the audit reports it too) |
+//! | Task differs from the activity's task | [`TaskMismatch`](ImportIssue::TaskMismatch); the model's task is used |
+//! | Model activity without a row | [`Unscheduled`](ImportIssue::Unscheduled) |
+//!
+//! IDs without a mapping are used as-is.
+//!
+//! # CSV
+//!
+//! [`ExternalAssignment::parse_csv`] reads rows with a header naming the
+//! columns `activity_id`, `resource_id`, `start_ms`, `end_ms` and
+//! optionally `task_id` and `setup_ms`, in any order. Fields may be
+//! quoted (`"a, b"`, with `""` for a quote).
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Assignment, Resource, Schedule, Task};
+use crate::validation::check_schedule;
+
+/// One row of an external schedule, in the external system's IDs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalAssignment {
+    /// External activity (operation) ID.
+    pub activity_id: String,
+    /// External task (order) ID, if the system reports one.
+    #[serde(default)]
+    pub task_id: Option<String>,
+    /// External resource ID.
+    pub resource_id: String,
+    /// Start, setup included (ms).
+    pub start_ms: i64,
+    /// End (ms).
+    pub end_ms: i64,
+    /// Setup at the start of the interval (ms).
+    #[serde(default)]
+    pub setup_ms: i64,
+}
+
+impl ExternalAssignment {
+    /// Creates a row without task ID or setup.
+    pub fn new(
+        activity_id: impl Into<String>,
+        resource_id: impl Into<String>,
+        start_ms: i64,
+        end_ms: i64,
+    ) -> Self {
+        Self {
+            activity_id: activity_id.into(),
+            task_id: None,
+            resource_id: resource_id.into(),
+            start_ms,
+            end_ms,
+            setup_ms: 0,
+        }
+    }
+
+    /// Sets the external task ID.
+    pub fn with_task(mut self, task_id: impl Into<String>) -> Self {
+        self.task_id = Some(task_id.into());
+        self
+    }
+
+    /// Sets the setup time.
+    pub fn with_setup(mut self, setup_ms: i64) -> Self {
+        self.setup_ms = setup_ms;
+        self
+    }
+
+    /// Parses CSV rows (see the [module docs](self) for the columns).
+    ///
+    /// # Errors
+    /// A missing required column, a row with too few fields, or a time
+    /// that is not an integer.
+    pub fn parse_csv(text: &str) -> Result<Vec<Self>, CsvError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty());
+        let Some((_, header)) = lines.next() else {
+            return Ok(Vec::new());
+        };
+        let header = split_csv_line(header);
+        let column = |name: &str| header.iter().position(|h| h.trim() == name);
+        let required = |name: &str| {
+            column(name).ok_or_else(|| CsvError {
+                line: 1,
+                message: format!("missing column `{name}`"),
+            })
+        };
+        let activity = required("activity_id")?;
+        let resource = required("resource_id")?;
+        let start = required("start_ms")?;
+        let end = required("end_ms")?;
+        let task = column("task_id");
+        let setup = column("setup_ms");
+
+        lines
+            .map(|(i, line)| {
+                let line_no = i + 1;
+                let fields = split_csv_line(line);
+                let field = |index: usize| {
+                    fields.get(index).map(|f| f.trim()).ok_or_else(|| CsvError {
+                        line: line_no,
+                        message: format!("expected at least {} fields", index + 1),
+                    })
+                };
+                let time = |index: usize| {
+                    let value = field(index)?;
+                    value.parse::<i64>().map_err(|_| CsvError {
+                        line: line_no,
+                        message: format!("invalid time `{value}`"),
+                    })
+                };
+                Ok(Self {
+                    activity_id: field(activity)?.to_string(),
+                    task_id: match task {
+                        Some(index) => Some(field(index)?.to_string()).filter(|t| !t.is_empty()),
+                        None => None,
+                    },
+                    resource_id: field(resource)?.to_string(),
+                    start_ms: time(start)?,
+                    end_ms: time(end)?,
+                    setup_ms: match setup {
+                        Some(index) if !field(index)?.is_empty() => time(index)?,
+                        _ => 0,
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+/// Splits a CSV line into fields, honoring double quotes.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// A CSV row that could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvError {
+    /// Line number (1-based).
+    pub line: usize,
+    /// What is wrong.
+    pub message: String,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// External → model ID translation, per entity kind.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IdMap {
+    /// Activity IDs.
+    #[serde(default)]
+    pub activities: HashMap<String, String>,
+    /// Task IDs.
+    #[serde(default)]
+    pub tasks: HashMap<String, String>,
+    /// Resource IDs.
+    #[serde(default)]
+    pub resources: HashMap<String, String>,
+}
+
+impl IdMap {
+    /// Creates an empty map (all IDs pass through).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps an external activity ID.
+    pub fn with_activity(mut self, external: impl Into<String>, id: impl Into<String>) -> Self {
+        self.activities.insert(external.into(), id.into());
+        self
+    }
+
+    /// Maps an external task ID.
+    pub fn with_task(mut self, external: impl Into<String>, id: impl Into<String>) -> Self {
+        self.tasks.insert(external.into(), id.into());
+        self
+    }
+
+    /// Maps an external resource ID.
+    pub fn with_resource(mut self, external: impl Into<String>, id: impl Into<String>) -> Self {
+        self.resources.insert(external.into(), id.into());
+        self
+    }
+
+    fn lookup<'a>(map: &'a HashMap<String, String>, external: &'a str) -> &'a str {
+        map.get(external).map_or(external, String::as_str)
+    }
+}
+
+/// A row or model entity the import could not reconcile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportIssue {
+    /// The row's activity is not in the model.
+    UnknownActivity {
+        /// External activity ID.
+        external_id: String,
+    },
+    /// The row's resource is not in the model.
+    UnknownResource {
+        /// Model activity ID.
+        activity_id: String,
+        /// Resource ID after mapping.
+        resource_id: String,
+    },
+    /// The row names a different task than the activity belongs to.
+    TaskMismatch {
+        /// Model activity ID.
+        activity_id: String,
+        /// Task ID stated by the row, after mapping.
+        stated: String,
+        /// Task the activity belongs to.
+        expected: String,
+    },
+    /// The row ends before it starts, or its setup does not fit.
+    InvalidInterval {
+        /// External activity ID.
+        external_id: String,
+    },
+    /// A model activity has no row.
+    Unscheduled {
+        /// Model activity ID.
+        activity_id: String,
+    },
+}
+
+/// An external schedule reconciled with the model.
+#[derive(Debug, Clone)]
+pub struct ImportedSchedule {
+    /// The plan in model IDs, with [`check_schedule`] violations.
+    pub schedule: Schedule,
+    /// Reconciliation issues, in row order, then unscheduled activities.
+    pub issues: Vec<ImportIssue>,
+}
+
+impl ImportedSchedule {
+    /// Whether every row mapped cleanly and every activity is scheduled.
+    pub fn is_reconciled(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Maps external schedules onto tasks and resources.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::import::{ExternalAssignment, IdMap, ImportIssue, ScheduleImporter};
+/// use u_schedule::models::{Activity, Resource, Task};
+///
+/// let tasks = vec![Task::new("J1").with_activity(Activity::new("J1_O1", "J1", 0).with_process_time(1000))];
+/// let resources = vec![Resource::primary("M1")];
+///
+/// let rows = ExternalAssignment::parse_csv(
+///     "activity_id,resource_id,start_ms,end_ms\nOP-10,CNC-1,0,1000\nOP-99,CNC-1,1000,2000\n",
+/// )
+/// .unwrap();
+/// let importer = ScheduleImporter::new(
+///     IdMap::new().with_activity("OP-10", "J1_O1").with_resource("CNC-1", "M1"),
+/// );
+/// let imported = importer.import(&rows, &tasks, &resources);
+/// assert_eq!(imported.schedule.assignment_count(), 1);
+/// assert_eq!(
+///     imported.issues,
+///     vec![ImportIssue::UnknownActivity { external_id: "OP-99".into() }]
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleImporter {
+    ids: IdMap,
+}
+
+impl ScheduleImporter {
+    /// Creates an importer with an ID map.
+    pub fn new(ids: IdMap) -> Self {
+        Self { ids }
+    }
+
+    /// Reconciles `rows` with `tasks` and `resources`.
+    pub fn import(
+        &self,
+        rows: &[ExternalAssignment],
+        tasks: &[Task],
+        resources: &[Resource],
+    ) -> ImportedSchedule {
+        let task_of: HashMap<&str, &str> = tasks
+            .iter()
+            .flat_map(|t| {
+                t.activities
+                    .iter()
+                    .map(move |a| (a.id.as_str(), t.id.as_str()))
+            })
+            .collect();
+        let known_resources: HashSet<&str> = resources.iter().map(|r| r.id.as_str()).collect();
+
+        let mut schedule = Schedule::new();
+        let mut issues = Vec::new();
+        for row in rows {
+            let activity_id = IdMap::lookup(&self.ids.activities, &row.activity_id);
+            let Some(&task_id) = task_of.get(activity_id) else {
+                issues.push(ImportIssue::UnknownActivity {
+                    external_id: row.activity_id.clone(),
+                });
+                continue;
+            };
+            if row.end_ms < row.start_ms
+                || row.setup_ms < 0
+                || row.setup_ms > row.end_ms - row.start_ms
+            {
+                issues.push(ImportIssue::InvalidInterval {
+                    external_id: row.activity_id.clone(),
+                });
+                continue;
+            }
+            if let Some(stated) = &row.task_id {
+                let stated = IdMap::lookup(&self.ids.tasks, stated);
+                if stated != task_id {
+                    issues.push(ImportIssue::TaskMismatch {
+                        activity_id: activity_id.to_string(),
+                        stated: stated.to_string(),
+                        expected: task_id.to_string(),
+                    });
+                }
+            }
+            let resource_id = IdMap::lookup(&self.ids.resources, &row.resource_id);
+            if !known_resources.contains(resource_id) {
+                issues.push(ImportIssue::UnknownResource {
+                    activity_id: activity_id.to_string(),
+                    resource_id: resource_id.to_string(),
+                });
+            }
+            schedule.add_assignment(
+                Assignment::new(activity_id, task_id, resource_id, row.start_ms, row.end_ms)
+                    .with_setup(row.setup_ms),
+            );
+        }
+
+        let scheduled: HashSet<&str> = schedule
+            .assignments
+            .iter()
+            .map(|a| a.activity_id.as_str())
+            .collect();
+        issues.extend(
+            tasks
+                .iter()
+                .flat_map(|t| &t.activities)
+                .filter(|a| !scheduled.contains(a.id.as_str()))
+                .map(|a| ImportIssue::Unscheduled {
+                    activity_id: a.id.clone(),
+                }),
+        );
+        schedule.violations = check_schedule(&schedule, tasks, resources);
+
+        ImportedSchedule { schedule, issues }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, ViolationType};
+
+    fn model() -> (Vec<Task>, Vec<Resource>) {
+        let task = |id: &str| {
+            Task::new(id)
+                .with_activity(Activity::new(format!("{id}_O1"), id, 0).with_process_time(1000))
+                .with_activity(Activity::new(format!("{id}_O2"), id, 1).with_process_time(1000))
+        };
+        (
+            vec![task("J1"), task("J2")],
+            vec![Resource::primary("M1"), Resource::primary("M2")],
+        )
+    }
+
+    #[test]
+    fn test_reconciliation() {
+        let (tasks, resources) = model();
+        let ids = IdMap::new()
+            .with_task("ORD-1", "J1")
+            .with_task("ORD-2", "J2")
+            .with_resource("LATHE", "M1");
+        let rows = vec![
+            ExternalAssignment::new("J1_O1", "LATHE", 0, 1000).with_task("ORD-1"),
+            ExternalAssignment::new("J1_O2", "M2", 1000, 2000).with_task("ORD-2"),
+            ExternalAssignment::new("J2_O1", "OVEN", 0, 1000),
+            ExternalAssignment::new("J2_O2", "M2", 3000, 2000),
+            ExternalAssignment::new("X", "M1", 0, 10),
+        ];
+        let imported = ScheduleImporter::new(ids).import(&rows, &tasks, &resources);
+
+        assert_eq!(
+            imported.issues,
+            vec![
+                ImportIssue::TaskMismatch {
+                    activity_id: "J1_O2".into(),
+                    stated: "J2".into(),
+                    expected: "J1".into(),
+                },
+                ImportIssue::UnknownResource {
+                    activity_id: "J2_O1".into(),
+                    resource_id: "OVEN".into(),
+                },
+                ImportIssue::InvalidInterval {
+                    external_id: "J2_O2".into(),
+                },
+                ImportIssue::UnknownActivity {
+                    external_id: "X".into(),
+                },
+                ImportIssue::Unscheduled {
+                    activity_id: "J2_O2".into(),
+                },
+            ]
+        );
+        assert!(!imported.is_reconciled());
+        let schedule = &imported.schedule;
+        assert_eq!(schedule.assignment_count(), 3);
+        assert_eq!(
+            schedule
+                .assignment_for_activity("J1_O1")
+                .unwrap()
+                .resource_id,
+            "M1"
+        );
+        assert_eq!(
+            schedule.assignment_for_activity("J1_O2").unwrap().task_id,
+            "J1"
+        );
+        assert!(schedule.violations.iter().any(|v| v.violation_type
+            == ViolationType::ResourceUnavailable
+            && v.entity_id == "OVEN"));
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let csv = "\
+task_id,activity_id,resource_id,start_ms,end_ms,setup_ms
+\"ORD, 1\",J1_O1,M1,0,1000,200
+,J1_O2,M2,1000,2000,
+";
+        let rows = ExternalAssignment::parse_csv(csv).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ExternalAssignment::new("J1_O1", "M1", 0, 1000)
+                    .with_task("ORD, 1")
+                    .with_setup(200),
+                ExternalAssignment::new("J1_O2", "M2", 1000, 2000),
+            ]
+        );
+
+        let err = ExternalAssignment::parse_csv("activity_id,start_ms,end_ms\n").unwrap_err();
+        assert_eq!(err.message, "missing column `resource_id`");
+        let err = ExternalAssignment::parse_csv(
+            "activity_id,resource_id,start_ms,end_ms\nJ1_O1,M1,soon,1000\n",
+        )
+        .unwrap_err();
+        assert_eq!((err.line, err.message.as_str()), (2, "invalid time `soon`"));
+        assert!(ExternalAssignment::parse_csv("").unwrap().is_empty());
+    }
+}
@@ -7,13 +7,47 @@
 //!
 //! # Modules
 //!
-//! - **`models`**: Domain types — `Task`, `Activity`, `Resource`, `Schedule`,
-//!   `Assignment`, `Calendar`, `Constraint`, `TransitionMatrix`
-//! - **`validation`**: Input integrity checks (duplicate IDs, DAG cycles, resource refs)
+//! - **`models`**: Domain types — `Task`, `Activity`, `Resource`, `LearningCurve`, `Schedule`,
+//!   `Assignment`, `SegmentedAssignment`, `AssignmentConfidence`, `Annotation`, `Calendar`, `CalendarSet`, `Constraint`, `SyncGroups`, `TransitionMatrix`, `DurationModel`, `ToolingRules`, `BatchRules`, `LockSet`,
+//!   `WeightProfile`, `ThroughputTargets`, `CompactSchedule`, `ScheduleHistory`; time unit constants and `Duration`
+//!   conversions (`units`)
+//! - **`validation`**: Input integrity checks (duplicate IDs, DAG cycles, resource refs, numeric sanity) and schedule audits
+//! - **`precedence`**: Precedence closure: earliest and latest starts, true slack, critical activities and unattainable deadlines
 //! - **`dispatching`**: Priority dispatching rules (SPT, EDD, ATC, etc.) and rule engine
-//! - **`scheduler`**: Greedy scheduler and KPI evaluation
+//! - **`scheduler`**: Schedulers, improvers and plan analysis:
+//!   - Construction: greedy `SimpleScheduler` (cost-aware resource selection, machine-selection
+//!     rules; serial, active or non-delay generation), `DispatchScheduler` simulation (queue
+//!     disciplines and statistics, stall detection), `BackwardScheduler`, `SetpointScheduler`,
+//!     `ParallelScheduler`, `CampaignOptimizer`, `RollingHorizon`
+//!   - Capacity: `AggregatePlanner`, `OrderAggregator`, `CapacityAnalyzer`, `CapacityLeveling`
+//!   - Improvement: `LnsImprover`, `TabuImprover`, `ScheduleSmoother`, `ScheduleShuffler`
+//!   - What-if: `SensitivityAnalyzer`, `ParameterSweep`, `OrderPromiser`, `ConfidenceEstimator`,
+//!     `RandomBaseline`
+//!   - Editing and repair: `ScheduleEditor`, `right_shift_repair`, `stale_assignments`,
+//!     `ScheduleExplainer`
+//!   - Evaluation and export: `ScheduleKpi`, `WorkloadFairness`, `CostModel`, `SequenceExport`
 //! - **`ga`**: GA-based scheduling with OSV/MAV encoding
 //! - **`cp`**: CP-based scheduling formulation
+//! - **`objective`**: Objective terms (makespan, tardiness, class completion) shared by KPIs, GA, LNS and CP
+//! - **`execution`**: Plan-vs-actual execution tracking, adherence KPIs, rescheduling input with rush-order preemption, duration and setup learning
+//! - **`ffi`**: C ABI with JSON payloads and session handles (feature `ffi`)
+//! - **`import`**: Import of external schedules with ID mapping, reconciliation issues and a validator audit
+//! - **`anytime`**: Anytime solving contract: solvers stream improving incumbents (schedule, objective, elapsed time) to a callback that can stop the run; budgeted greedy → GA → CP solving
+//! - **`bundle`**: Run bundles (problem, solver configuration and seed, schedule, KPIs, environment) with deterministic JSON and exact replay
+//! - **`golden`**: Canonical instances with reference KPIs and cross-solver consistency checks for regression tests
+//! - **`python`**: Python bindings (feature `python`)
+//! - **`wasm`**: JSON browser facade (feature `wasm`)
+//!
+//! # Features
+//!
+//! | Feature | Enables |
+//! |---------|---------|
+//! | `std` (default) | Everything but the bindings; without it only `models` builds, on `alloc` |
+//! | `ffi`, `python`, `wasm`, `cli` | Bindings and the command-line tool (imply `std`) |
+//!
+//! Without `std`, model maps and sets are `BTreeMap`/`BTreeSet`, and
+//! the probabilistic duration helpers that need `u-numflow` or float
+//! math (`PertEstimate::duration_at_confidence` and friends) are absent.
 //!
 //! # Architecture
 //!
@@ -28,9 +62,38 @@
 //! - Blazewicz et al. (2019), "Handbook on Scheduling"
 //! - Haupt (1989), "A Survey of Priority Rule-Based Scheduling"
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod anytime;
+#[cfg(feature = "std")]
+pub mod bundle;
+#[cfg(feature = "std")]
 pub mod cp;
+#[cfg(feature = "std")]
 pub mod dispatching;
+#[cfg(feature = "std")]
+pub mod execution;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
 pub mod ga;
+#[cfg(feature = "std")]
+pub mod golden;
+#[cfg(feature = "std")]
+pub mod import;
 pub mod models;
+#[cfg(feature = "std")]
+pub mod objective;
+#[cfg(feature = "std")]
+pub mod precedence;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "std")]
 pub mod scheduler;
+#[cfg(feature = "std")]
 pub mod validation;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -10,11 +10,22 @@
 //! - **Process**: Core work time
 //! - **Teardown**: Cleanup/cooldown time
 //!
+//! The setup portion may need its own resource (a setter or changeover
+//! crew) in addition to the machine; see [`Activity::setup_requirement`].
+//! A further requirement on [`Secondary`](ResourceType::Secondary)
+//! resources is a tool mounted on the machine; see
+//! [`Activity::tool_requirement`].
+//!
 //! # Reference
 //! Pinedo (2016), "Scheduling: Theory, Algorithms, and Systems", Ch. 2
 
+use core::time::Duration;
+
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+
+use super::prelude::*;
+use super::units;
+use super::{PrecedenceMode, Resource, ResourceType};
 
 /// An activity (operation) to be scheduled.
 ///
@@ -39,6 +50,23 @@ pub struct Activity {
     pub splittable: bool,
     /// Minimum duration (ms) of each split segment.
     pub min_split_ms: i64,
+    /// Secondary resource needed only during setup (e.g., a setter).
+    ///
+    /// When the setup time is non-zero, one candidate of this requirement
+    /// is occupied for `[start, start + setup)` alongside the machine.
+    #[serde(default)]
+    pub setup_requirement: Option<ResourceRequirement>,
+    /// Relation to the previous activity of the task (default:
+    /// finish-to-start).
+    #[serde(default)]
+    pub precedence_mode: PrecedenceMode,
+    /// Lag of `precedence_mode` (ms).
+    #[serde(default)]
+    pub precedence_lag_ms: i64,
+    /// IDs of activities (usually of other tasks) that must start at the
+    /// same time as this one (see [`SyncGroups`](super::SyncGroups)).
+    #[serde(default)]
+    pub synchronized_with: Vec<String>,
     /// Domain-specific metadata.
     pub attributes: HashMap<String, String>,
 }
@@ -55,6 +83,10 @@ impl Activity {
             predecessors: Vec::new(),
             splittable: false,
             min_split_ms: 0,
+            setup_requirement: None,
+            precedence_mode: PrecedenceMode::FinishToStart,
+            precedence_lag_ms: 0,
+            synchronized_with: Vec::new(),
             attributes: HashMap::new(),
         }
     }
@@ -71,6 +103,11 @@ impl Activity {
         self
     }
 
+    /// Sets the processing time from a [`Duration`] (setup=0, teardown=0).
+    pub fn with_process_duration(self, process: Duration) -> Self {
+        self.with_process_time(units::to_ms(process))
+    }
+
     /// Adds a resource requirement.
     pub fn with_requirement(mut self, req: ResourceRequirement) -> Self {
         self.resource_requirements.push(req);
@@ -83,6 +120,12 @@ impl Activity {
         self
     }
 
+    /// Starts this activity together with another one.
+    pub fn with_synchronized(mut self, activity_id: impl Into<String>) -> Self {
+        self.synchronized_with.push(activity_id.into());
+        self
+    }
+
     /// Enables preemption with a minimum split size.
     pub fn with_splitting(mut self, min_split_ms: i64) -> Self {
         self.splittable = true;
@@ -90,13 +133,117 @@ impl Activity {
         self
     }
 
+    /// Enables preemption with a minimum split size given as a [`Duration`].
+    pub fn with_splitting_duration(self, min_split: Duration) -> Self {
+        self.with_splitting(units::to_ms(min_split))
+    }
+
+    /// Requires a secondary resource during setup.
+    pub fn with_setup_requirement(mut self, req: ResourceRequirement) -> Self {
+        self.setup_requirement = Some(req);
+        self
+    }
+
+    /// Sets the relation to the previous activity of the task.
+    pub fn with_precedence_mode(mut self, mode: PrecedenceMode, lag_ms: i64) -> Self {
+        self.precedence_mode = mode;
+        self.precedence_lag_ms = lag_ms;
+        self
+    }
+
+    /// Adds a domain-specific attribute.
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+
     /// Returns all candidate resource IDs across all requirements.
+    ///
+    /// Excludes the [`setup_requirement`](Self::setup_requirement).
     pub fn candidate_resources(&self) -> Vec<&str> {
         self.resource_requirements
             .iter()
             .flat_map(|r| r.candidates.iter().map(|s| s.as_str()))
             .collect()
     }
+
+    /// Like [`candidate_resources`](Self::candidate_resources), but
+    /// requirements without candidates are resolved against `resources`
+    /// (see [`ResourceRequirement::resolve`]).
+    pub fn resolved_candidates<'a>(&'a self, resources: &'a [Resource]) -> Vec<&'a str> {
+        self.resource_requirements
+            .iter()
+            .flat_map(|r| r.resolve(resources))
+            .collect()
+    }
+
+    /// Index of the requirement naming the tool (or fixture) mounted on
+    /// the machine: the first requirement after the machine's whose
+    /// resources are all [`Secondary`](ResourceType::Secondary).
+    ///
+    /// Schedulers book one tool of it like a category's
+    /// [tool pool](super::ToolingRules): a tool is on one machine at a
+    /// time and moving it costs its transfer time.
+    pub fn tool_requirement(&self, resources: &[Resource]) -> Option<usize> {
+        self.resource_requirements
+            .iter()
+            .enumerate()
+            .skip(1)
+            .find(|(_, req)| {
+                let pool = req.resolve(resources);
+                !pool.is_empty()
+                    && pool.iter().all(|id| {
+                        resources
+                            .iter()
+                            .any(|r| r.id == *id && r.resource_type == ResourceType::Secondary)
+                    })
+            })
+            .map(|(i, _)| i)
+    }
+
+    /// Required skills that `resource` holds, but not throughout
+    /// `[start_ms, end_ms)`: its qualification lapses (or is not yet
+    /// valid) during the work.
+    ///
+    /// Only requirements `resource` can fulfill count: those listing it as
+    /// a candidate, or without candidates and [`matches`](ResourceRequirement::matches).
+    pub fn lapsed_skills<'a>(
+        &'a self,
+        resource: &'a Resource,
+        start_ms: i64,
+        end_ms: i64,
+    ) -> Vec<&'a str> {
+        self.skills_on(resource)
+            .filter(|s| resource.has_skill(s) && !resource.has_skill_during(s, start_ms, end_ms))
+            .collect()
+    }
+
+    /// Earliest time (ms) from which `resource` holds the required skills
+    /// it has certifications for; `i64::MIN` if none start later.
+    pub fn qualified_from(&self, resource: &Resource) -> i64 {
+        self.skills_on(resource)
+            .filter_map(|name| {
+                resource
+                    .skills
+                    .iter()
+                    .filter(|s| s.name == name)
+                    .map(|s| s.valid_from_ms.unwrap_or(i64::MIN))
+                    .min()
+            })
+            .max()
+            .unwrap_or(i64::MIN)
+    }
+
+    /// Required skills of the requirements `resource` can fulfill.
+    fn skills_on<'a>(&'a self, resource: &'a Resource) -> impl Iterator<Item = &'a str> {
+        self.resource_requirements
+            .iter()
+            .filter(move |r| {
+                r.candidates.contains(&resource.id)
+                    || (r.candidates.is_empty() && r.matches(resource))
+            })
+            .flat_map(|r| r.required_skills.iter().map(String::as_str))
+    }
 }
 
 /// Time components of an activity.
@@ -131,10 +278,39 @@ impl ActivityDuration {
         Self::new(0, process_ms, 0)
     }
 
+    /// Creates a duration with all three components from [`Duration`]s.
+    pub fn from_durations(setup: Duration, process: Duration, teardown: Duration) -> Self {
+        Self::new(
+            units::to_ms(setup),
+            units::to_ms(process),
+            units::to_ms(teardown),
+        )
+    }
+
     /// Total duration (setup + process + teardown).
     pub fn total_ms(&self) -> i64 {
         self.setup_ms + self.process_ms + self.teardown_ms
     }
+
+    /// Setup time as a [`Duration`].
+    pub fn setup_duration(&self) -> Duration {
+        units::from_ms(self.setup_ms)
+    }
+
+    /// Processing time as a [`Duration`].
+    pub fn process_duration(&self) -> Duration {
+        units::from_ms(self.process_ms)
+    }
+
+    /// Teardown time as a [`Duration`].
+    pub fn teardown_duration(&self) -> Duration {
+        units::from_ms(self.teardown_ms)
+    }
+
+    /// Total duration as a [`Duration`].
+    pub fn total_duration(&self) -> Duration {
+        units::from_ms(self.total_ms())
+    }
 }
 
 impl Default for ActivityDuration {
@@ -147,14 +323,15 @@ impl Default for ActivityDuration {
 ///
 /// Specifies what type and quantity of resources are needed,
 /// with optional candidate filtering and skill requirements.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ResourceRequirement {
     /// Required resource type (e.g., "Machine", "Operator").
     pub resource_type: String,
-    /// Number of resource units needed simultaneously.
+    /// Number of resource units needed simultaneously, held as that many
+    /// distinct resources.
     pub quantity: i32,
     /// Specific resource IDs that can fulfill this requirement.
-    /// Empty = any resource of the correct type.
+    /// Empty = any resource of the correct type (see [`resolve`](Self::resolve)).
     pub candidates: Vec<String>,
     /// Required skills (matched against `Resource.skills`).
     pub required_skills: Vec<String>,
@@ -188,11 +365,49 @@ impl ResourceRequirement {
         self.required_skills.push(skill.into());
         self
     }
+
+    /// Whether `resource` is of the required type and has every required
+    /// skill.
+    ///
+    /// Types match by [`ResourceType::name`], ignoring ASCII case.
+    pub fn matches(&self, resource: &Resource) -> bool {
+        resource
+            .resource_type
+            .name()
+            .eq_ignore_ascii_case(&self.resource_type)
+            && self.required_skills.iter().all(|s| resource.has_skill(s))
+    }
+
+    /// Like [`matches`](Self::matches), but every required skill must be
+    /// valid throughout `[start_ms, end_ms)`.
+    pub fn matches_during(&self, resource: &Resource, start_ms: i64, end_ms: i64) -> bool {
+        self.matches(resource)
+            && self
+                .required_skills
+                .iter()
+                .all(|s| resource.has_skill_during(s, start_ms, end_ms))
+    }
+
+    /// Resource IDs that can fulfill this requirement.
+    ///
+    /// The explicit candidates if any; otherwise every resource of
+    /// `resources` that [`matches`](Self::matches), in order.
+    pub fn resolve<'a>(&'a self, resources: &'a [Resource]) -> Vec<&'a str> {
+        if !self.candidates.is_empty() {
+            return self.candidates.iter().map(|s| s.as_str()).collect();
+        }
+        resources
+            .iter()
+            .filter(|r| self.matches(r))
+            .map(|r| r.id.as_str())
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::ResourceType;
 
     #[test]
     fn test_activity_builder() {
@@ -212,6 +427,29 @@ mod tests {
         assert_eq!(act.min_split_ms, 200);
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_setup_requirement_not_a_candidate() {
+        let act = Activity::new("O1", "J1", 0)
+            .with_requirement(
+                ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+            )
+            .with_setup_requirement(
+                ResourceRequirement::new("Setter").with_candidates(vec!["S1".into()]),
+            );
+        assert_eq!(act.candidate_resources(), vec!["M1"]);
+        assert_eq!(act.setup_requirement.unwrap().candidates, vec!["S1"]);
+
+        // Older JSON without the field still parses.
+        let json = r#"{"id":"O1","task_id":"J1","sequence":0,
+            "duration":{"setup_ms":0,"process_ms":1,"teardown_ms":0},
+            "resource_requirements":[],"predecessors":[],"splittable":false,
+            "min_split_ms":0,"attributes":{}}"#;
+        let parsed: Activity = serde_json::from_str(json).unwrap();
+        assert!(parsed.setup_requirement.is_none());
+        assert_eq!(parsed.precedence_mode, PrecedenceMode::FinishToStart);
+    }
+
     #[test]
     fn test_activity_duration_fixed() {
         let d = ActivityDuration::fixed(1000);
@@ -255,4 +493,96 @@ mod tests {
         assert!(candidates.contains(&"M1"));
         assert!(candidates.contains(&"W1"));
     }
+
+    #[test]
+    fn test_type_based_resolution() {
+        let resources = vec![
+            Resource::primary("M1"),
+            Resource::human("W1").with_skill("welding", 0.8),
+            Resource::human("W2"),
+            Resource::new("C1", ResourceType::Custom("Crane".into())),
+        ];
+        let any_human = ResourceRequirement::new("human");
+        assert_eq!(any_human.resolve(&resources), vec!["W1", "W2"]);
+        let welder = ResourceRequirement::new("Human").with_skill("welding");
+        assert_eq!(welder.resolve(&resources), vec!["W1"]);
+        assert_eq!(
+            ResourceRequirement::new("Crane").resolve(&resources),
+            vec!["C1"]
+        );
+        // Explicit candidates win.
+        let pinned = ResourceRequirement::new("Human").with_candidates(vec!["W2".into()]);
+        assert_eq!(pinned.resolve(&resources), vec!["W2"]);
+
+        let act = Activity::new("O1", "J1", 0)
+            .with_requirement(ResourceRequirement::new("Primary"))
+            .with_requirement(welder);
+        assert!(act.candidate_resources().is_empty());
+        assert_eq!(act.resolved_candidates(&resources), vec!["M1", "W1"]);
+    }
+
+    #[test]
+    fn test_tool_requirement() {
+        let resources = vec![
+            Resource::primary("M1"),
+            Resource::secondary("FX"),
+            Resource::new("W1", ResourceType::Human),
+        ];
+        let on = |ids: &[&str]| {
+            ResourceRequirement::new("Any")
+                .with_candidates(ids.iter().map(|id| id.to_string()).collect())
+        };
+        let act = Activity::new("O1", "J1", 0)
+            .with_requirement(on(&["M1"]))
+            .with_requirement(on(&["W1"]))
+            .with_requirement(on(&["FX"]));
+        assert_eq!(act.tool_requirement(&resources), Some(2));
+        // The machine's own requirement and mixed pools are no tools.
+        let mixed = Activity::new("O2", "J1", 1)
+            .with_requirement(on(&["FX"]))
+            .with_requirement(on(&["FX", "W1"]));
+        assert_eq!(mixed.tool_requirement(&resources), None);
+    }
+
+    #[test]
+    fn test_lapsed_skills() {
+        use crate::models::Skill;
+        let welder = Resource::human("W1").with_certification(
+            Skill::new("welding", 0.8)
+                .with_valid_from(1000)
+                .with_valid_until(5000),
+        );
+        let req = ResourceRequirement::new("Human").with_skill("welding");
+        assert!(req.matches_during(&welder, 1000, 5000));
+        assert!(!req.matches_during(&welder, 4000, 6000));
+
+        let act = Activity::new("O1", "J1", 0).with_requirement(req);
+        assert!(act.lapsed_skills(&welder, 2000, 3000).is_empty());
+        assert_eq!(act.lapsed_skills(&welder, 4000, 6000), vec!["welding"]);
+        assert_eq!(act.qualified_from(&welder), 1000);
+        assert_eq!(act.qualified_from(&Resource::human("W2")), i64::MIN);
+    }
+
+    #[test]
+    fn test_duration_builders() {
+        use crate::models::units::MINUTE_MS;
+
+        let act = Activity::new("O1", "J1", 0)
+            .with_process_duration(Duration::from_secs(300))
+            .with_splitting_duration(Duration::from_secs(60));
+        assert_eq!(act.duration.process_ms, 5 * MINUTE_MS);
+        assert_eq!(act.min_split_ms, MINUTE_MS);
+
+        let d = ActivityDuration::from_durations(
+            Duration::from_secs(60),
+            Duration::from_secs(120),
+            Duration::from_millis(1500),
+        );
+        assert_eq!(
+            (d.setup_ms, d.process_ms, d.teardown_ms),
+            (60_000, 120_000, 1500)
+        );
+        assert_eq!(d.total_duration(), Duration::from_millis(181_500));
+        assert_eq!(d.teardown_duration(), Duration::from_millis(1500));
+    }
 }
@@ -0,0 +1,199 @@
+//! Soft task–resource affinity.
+//!
+//! Expresses preferences such as "orders for customer X should run on
+//! line A" without turning them into hard candidate restrictions. Rules
+//! compare task attributes with resource attributes and add a score.
+//!
+//! # Units
+//!
+//! Scores are in milliseconds of equivalent waiting time: a score of
+//! 60 000 means the preferred resource is worth starting up to one minute
+//! later. Negative scores express aversion.
+//!
+//! The pseudo-attribute `id` on the resource side refers to the resource
+//! ID, so `Match { task_attribute: "preferred_machine", resource_attribute:
+//! "id" }` models a preferred machine.
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+use super::{Resource, Task};
+
+/// One affinity rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum AffinityRule {
+    /// Scores when the task attribute equals the resource attribute.
+    Match {
+        /// Task attribute key.
+        task_attribute: String,
+        /// Resource attribute key (`id` = resource ID).
+        resource_attribute: String,
+        /// Score (ms-equivalent).
+        score: f64,
+    },
+    /// Scores when both attributes have the given values.
+    Pair {
+        /// Task attribute key.
+        task_attribute: String,
+        /// Required task attribute value.
+        task_value: String,
+        /// Resource attribute key (`id` = resource ID).
+        resource_attribute: String,
+        /// Required resource attribute value.
+        resource_value: String,
+        /// Score (ms-equivalent).
+        score: f64,
+    },
+}
+
+impl AffinityRule {
+    /// Creates a [`Match`](AffinityRule::Match) rule.
+    pub fn matching(
+        task_attribute: impl Into<String>,
+        resource_attribute: impl Into<String>,
+        score: f64,
+    ) -> Self {
+        Self::Match {
+            task_attribute: task_attribute.into(),
+            resource_attribute: resource_attribute.into(),
+            score,
+        }
+    }
+
+    /// Creates a [`Pair`](AffinityRule::Pair) rule.
+    pub fn pair(
+        task_attribute: impl Into<String>,
+        task_value: impl Into<String>,
+        resource_attribute: impl Into<String>,
+        resource_value: impl Into<String>,
+        score: f64,
+    ) -> Self {
+        Self::Pair {
+            task_attribute: task_attribute.into(),
+            task_value: task_value.into(),
+            resource_attribute: resource_attribute.into(),
+            resource_value: resource_value.into(),
+            score,
+        }
+    }
+
+    /// Score contributed for a task on a resource (0.0 if not applicable).
+    pub fn score(&self, task: &Task, resource: &Resource) -> f64 {
+        match self {
+            Self::Match {
+                task_attribute,
+                resource_attribute,
+                score,
+            } => match (
+                task.attributes.get(task_attribute),
+                resource_attribute_value(resource, resource_attribute),
+            ) {
+                (Some(t), Some(r)) if t == r => *score,
+                _ => 0.0,
+            },
+            Self::Pair {
+                task_attribute,
+                task_value,
+                resource_attribute,
+                resource_value,
+                score,
+            } => {
+                let task_ok = task.attributes.get(task_attribute) == Some(task_value);
+                let resource_ok = resource_attribute_value(resource, resource_attribute)
+                    == Some(resource_value.as_str());
+                if task_ok && resource_ok {
+                    *score
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+fn resource_attribute_value<'a>(resource: &'a Resource, key: &str) -> Option<&'a str> {
+    match resource.attributes.get(key) {
+        Some(v) => Some(v.as_str()),
+        None if key == "id" => Some(resource.id.as_str()),
+        None => None,
+    }
+}
+
+/// A set of affinity rules; scores of matching rules add up.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AffinityTable {
+    /// Rules.
+    pub rules: Vec<AffinityRule>,
+}
+
+impl AffinityTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule.
+    pub fn with_rule(mut self, rule: AffinityRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Whether the table has no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Total affinity of a task for a resource.
+    pub fn score(&self, task: &Task, resource: &Resource) -> f64 {
+        self.rules.iter().map(|r| r.score(task, resource)).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_rule_by_id() {
+        let table = AffinityTable::new().with_rule(AffinityRule::matching(
+            "preferred_machine",
+            "id",
+            5000.0,
+        ));
+        let task = Task::new("J1").with_attribute("preferred_machine", "M2");
+        assert_eq!(table.score(&task, &Resource::primary("M1")), 0.0);
+        assert_eq!(table.score(&task, &Resource::primary("M2")), 5000.0);
+        assert_eq!(table.score(&Task::new("J2"), &Resource::primary("M2")), 0.0);
+    }
+
+    #[test]
+    fn test_pair_rules_add_up() {
+        let table = AffinityTable::new()
+            .with_rule(AffinityRule::pair("material", "steel", "line", "A", 1000.0))
+            .with_rule(AffinityRule::matching("customer", "customer", 500.0))
+            .with_rule(AffinityRule::pair(
+                "material", "steel", "line", "B", -2000.0,
+            ));
+        let task = Task::new("J1")
+            .with_attribute("material", "steel")
+            .with_attribute("customer", "ACME");
+        let line_a = Resource::primary("M1")
+            .with_attribute("line", "A")
+            .with_attribute("customer", "ACME");
+        let line_b = Resource::primary("M2").with_attribute("line", "B");
+        assert_eq!(table.score(&task, &line_a), 1500.0);
+        assert_eq!(table.score(&task, &line_b), -2000.0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_json_roundtrip() {
+        let table = AffinityTable::new()
+            .with_rule(AffinityRule::matching("preferred_machine", "id", 1.0))
+            .with_rule(AffinityRule::pair("a", "x", "b", "y", -1.0));
+        let json = serde_json::to_string(&table).unwrap();
+        assert!(json.contains(r#""kind":"Match""#));
+        assert_eq!(serde_json::from_str::<AffinityTable>(&json).unwrap(), table);
+    }
+}
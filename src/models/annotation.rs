@@ -0,0 +1,168 @@
+//! Planner notes attached to a schedule.
+//!
+//! Planners explain their decisions ("held for customer approval",
+//! "M3 on reduced speed this week") next to the plan. An [`Annotation`]
+//! is stored in [`Schedule::annotations`] and serialized with it, so it
+//! travels between services; [`Schedule::carry_annotations`] moves the
+//! notes of a previous plan onto a replanned one.
+//!
+//! # Targets
+//!
+//! | Target | Attached to |
+//! |--------|-------------|
+//! | [`Assignment`](AnnotationTarget::Assignment) | An activity's assignment(s), by activity ID |
+//! | [`Task`](AnnotationTarget::Task) | A task |
+//! | [`Resource`](AnnotationTarget::Resource) | A resource |
+//! | [`Schedule`](AnnotationTarget::Schedule) | The plan as a whole |
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+use super::Schedule;
+
+/// What an annotation refers to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AnnotationTarget {
+    /// The assignment(s) of an activity.
+    Assignment(String),
+    /// A task.
+    Task(String),
+    /// A resource.
+    Resource(String),
+    /// The whole schedule.
+    Schedule,
+}
+
+/// A note on a schedule, with its author and time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotation {
+    /// What the note refers to.
+    pub target: AnnotationTarget,
+    /// Who wrote it.
+    pub author: String,
+    /// When it was written (ms).
+    pub created_ms: i64,
+    /// The note.
+    pub text: String,
+}
+
+impl Annotation {
+    /// Creates a note.
+    pub fn new(
+        target: AnnotationTarget,
+        author: impl Into<String>,
+        created_ms: i64,
+        text: impl Into<String>,
+    ) -> Self {
+        Self {
+            target,
+            author: author.into(),
+            created_ms,
+            text: text.into(),
+        }
+    }
+
+    /// Whether the target still exists in `schedule`.
+    ///
+    /// Resource and schedule notes always apply; assignment and task
+    /// notes need an assignment of their activity or task.
+    pub fn applies_to(&self, schedule: &Schedule) -> bool {
+        match &self.target {
+            AnnotationTarget::Assignment(activity_id) => schedule
+                .assignments
+                .iter()
+                .any(|a| &a.activity_id == activity_id),
+            AnnotationTarget::Task(task_id) => {
+                schedule.assignments.iter().any(|a| &a.task_id == task_id)
+            }
+            AnnotationTarget::Resource(_) | AnnotationTarget::Schedule => true,
+        }
+    }
+}
+
+impl Schedule {
+    /// Adds a note.
+    pub fn annotate(&mut self, annotation: Annotation) {
+        self.annotations.push(annotation);
+    }
+
+    /// Notes on `target`, oldest first.
+    pub fn annotations_for(&self, target: &AnnotationTarget) -> Vec<&Annotation> {
+        let mut notes: Vec<&Annotation> = self
+            .annotations
+            .iter()
+            .filter(|n| &n.target == target)
+            .collect();
+        notes.sort_by_key(|n| n.created_ms);
+        notes
+    }
+
+    /// Copies the notes of `previous` whose target still exists here
+    /// (see [`Annotation::applies_to`]), skipping ones already present.
+    ///
+    /// Returns the notes left behind because their activity or task is
+    /// no longer scheduled.
+    pub fn carry_annotations(&mut self, previous: &Schedule) -> Vec<Annotation> {
+        let mut dropped = Vec::new();
+        for note in &previous.annotations {
+            if !note.applies_to(self) {
+                dropped.push(note.clone());
+            } else if !self.annotations.contains(note) {
+                self.annotations.push(note.clone());
+            }
+        }
+        dropped
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::models::Assignment;
+
+    #[test]
+    fn test_annotations_travel() {
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("O1", "J1", "M1", 0, 1000));
+        plan.add_assignment(Assignment::new("O2", "J2", "M1", 1000, 2000));
+        let on_o1 = AnnotationTarget::Assignment("O1".into());
+        plan.annotate(Annotation::new(on_o1.clone(), "kim", 20, "keep on M1"));
+        plan.annotate(Annotation::new(on_o1.clone(), "lee", 10, "tooling ready"));
+        plan.annotate(Annotation::new(
+            AnnotationTarget::Task("J2".into()),
+            "kim",
+            30,
+            "awaiting approval",
+        ));
+        plan.annotate(Annotation::new(
+            AnnotationTarget::Resource("M1".into()),
+            "lee",
+            40,
+            "reduced speed",
+        ));
+
+        let texts: Vec<&str> = plan
+            .annotations_for(&on_o1)
+            .iter()
+            .map(|n| n.text.as_str())
+            .collect();
+        assert_eq!(texts, vec!["tooling ready", "keep on M1"]);
+
+        let json = serde_json::to_string(&plan).unwrap();
+        let parsed: Schedule = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.annotations, plan.annotations);
+
+        // The replan dropped J2.
+        let mut replan = Schedule::new();
+        replan.add_assignment(Assignment::new("O1", "J1", "M2", 0, 1000));
+        let dropped = replan.carry_annotations(&plan);
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].text, "awaiting approval");
+        assert_eq!(replan.annotations.len(), 3);
+        assert!(replan.carry_annotations(&plan).len() == 1 && replan.annotations.len() == 3);
+
+        // Schedules serialized without notes still parse.
+        let bare: Schedule = serde_json::from_str(r#"{"assignments":[],"violations":[]}"#).unwrap();
+        assert!(bare.annotations.is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+//! Batch processing resources.
+//!
+//! Ovens, autoclaves and heat-treatment furnaces process several
+//! activities at once: loaded together, run one cycle, unloaded together.
+//! [`BatchRules`] on a [`Resource`](super::Resource) state how many
+//! activities one batch holds and which task categories may share it.
+//! Members of a batch start and end together; the schedule audit counts
+//! a batch as a single load of the resource.
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+
+/// Batching on one resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchRules {
+    /// Maximum activities per batch.
+    pub max_size: usize,
+    /// Groups of task categories that may share a batch. Activities of
+    /// the same category always may.
+    #[serde(default)]
+    pub compatible: Vec<Vec<String>>,
+}
+
+impl BatchRules {
+    /// Batches of up to `max_size` activities (at least 1) of one
+    /// category.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size: max_size.max(1),
+            compatible: Vec::new(),
+        }
+    }
+
+    /// Lets `categories` share batches.
+    pub fn with_compatible(mut self, categories: Vec<String>) -> Self {
+        self.compatible.push(categories);
+        self
+    }
+
+    /// Whether activities of categories `a` and `b` may share a batch.
+    pub fn compatible(&self, a: &str, b: &str) -> bool {
+        a == b
+            || self
+                .compatible
+                .iter()
+                .any(|group| group.iter().any(|c| c == a) && group.iter().any(|c| c == b))
+    }
+
+    /// Whether activities of `categories` (one per activity) form a valid
+    /// batch: no more than the maximum size, all pairwise compatible.
+    pub fn admits(&self, categories: &[&str]) -> bool {
+        categories.len() <= self.max_size
+            && categories
+                .iter()
+                .enumerate()
+                .all(|(i, a)| categories[i + 1..].iter().all(|b| self.compatible(a, b)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compatibility() {
+        let rules = BatchRules::new(3).with_compatible(vec!["steel".into(), "alloy".into()]);
+        assert!(rules.compatible("steel", "alloy"));
+        assert!(rules.compatible("glass", "glass"));
+        assert!(!rules.compatible("steel", "glass"));
+
+        assert!(rules.admits(&["steel", "alloy", "steel"]));
+        assert!(!rules.admits(&["steel", "alloy", "glass"]));
+        assert!(!rules.admits(&["steel"; 4]));
+        assert_eq!(BatchRules::new(0).max_size, 1);
+    }
+}
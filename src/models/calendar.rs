@@ -11,9 +11,23 @@
 //! Blocked periods override time windows. A timestamp is available iff:
 //! - It falls within at least one `time_windows` entry, AND
 //! - It does NOT fall within any `blocked_periods` entry.
+//!
+//! # Composition
+//! Calendars combine as sets of available time: [`Calendar::intersect`]
+//! (plant ∩ shift), [`Calendar::union`] (shift A ∪ overtime), and
+//! [`Calendar::subtract`] (shift − vacations). [`CalendarSet`](super::CalendarSet)
+//! applies such layers per resource. The same operations on plain window
+//! sets ([`union_windows`], [`intersect_windows`], [`subtract_windows`],
+//! [`complement_windows`]) serve occupancy and capacity queries.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+use super::prelude::*;
+use super::units;
+
 /// A time interval [start, end).
 ///
 /// Half-open interval: includes start, excludes end.
@@ -37,6 +51,12 @@ impl TimeWindow {
         self.end_ms - self.start_ms
     }
 
+    /// Duration of this window as a [`Duration`] (saturating for
+    /// unbounded windows).
+    pub fn duration(&self) -> Duration {
+        units::from_ms(self.end_ms.saturating_sub(self.start_ms))
+    }
+
     /// Whether a timestamp falls within this window.
     #[inline]
     pub fn contains(&self, time_ms: i64) -> bool {
@@ -47,6 +67,13 @@ impl TimeWindow {
     pub fn overlaps(&self, other: &Self) -> bool {
         self.start_ms < other.end_ms && other.start_ms < self.end_ms
     }
+
+    /// The common part of two windows, if non-empty.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let start = self.start_ms.max(other.start_ms);
+        let end = self.end_ms.min(other.end_ms);
+        (end > start).then(|| Self::new(start, end))
+    }
 }
 
 /// Resource availability calendar.
@@ -118,47 +145,219 @@ impl Calendar {
     ///
     /// Returns `None` if no future availability exists.
     pub fn next_available_time(&self, from_ms: i64) -> Option<i64> {
-        if self.is_working_time(from_ms) {
-            return Some(from_ms);
+        self.available_intervals()
+            .iter()
+            .find(|w| w.end_ms > from_ms)
+            .map(|w| w.start_ms.max(from_ms))
+    }
+
+    /// Computes total available time within a range [start, end).
+    pub fn available_time_in_range(&self, start_ms: i64, end_ms: i64) -> i64 {
+        if end_ms <= start_ms {
+            return 0;
         }
+        let range = TimeWindow::new(start_ms, end_ms);
+        self.available_intervals()
+            .iter()
+            .filter_map(|w| overlap_duration(&range, w))
+            .sum()
+    }
 
-        // If no windows, we must be in a blocked period
-        if self.time_windows.is_empty() {
-            // Find end of current blocked period
-            for bp in &self.blocked_periods {
-                if bp.contains(from_ms) {
-                    let candidate = bp.end_ms;
-                    if self.is_working_time(candidate) {
-                        return Some(candidate);
-                    }
-                }
+    /// Builds an index for fast repeated queries.
+    pub fn index(&self) -> CalendarIndex {
+        CalendarIndex::new(self)
+    }
+
+    /// Reports malformed windows and blocked periods.
+    ///
+    /// Checks for empty or negative-length intervals, unsorted or
+    /// overlapping time windows, and blocked periods outside every time
+    /// window. Returns one message per problem; empty if well-formed.
+    pub fn check(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        let fmt = |w: &TimeWindow| format!("[{}, {})", w.start_ms, w.end_ms);
+
+        for (kind, list) in [
+            ("time window", &self.time_windows),
+            ("blocked period", &self.blocked_periods),
+        ] {
+            for w in list.iter().filter(|w| w.end_ms <= w.start_ms) {
+                issues.push(format!("{kind} {} has no positive length", fmt(w)));
             }
-            return None;
         }
 
-        // Search windows sorted by start time
-        let mut candidates: Vec<i64> = self
+        if self
+            .time_windows
+            .windows(2)
+            .any(|p| p[1].start_ms < p[0].start_ms)
+        {
+            issues.push("time windows are not sorted by start".to_string());
+        }
+        let mut sorted: Vec<&TimeWindow> = self
             .time_windows
             .iter()
-            .filter(|w| w.end_ms > from_ms)
-            .map(|w| w.start_ms.max(from_ms))
+            .filter(|w| w.end_ms > w.start_ms)
             .collect();
-        candidates.sort();
+        sorted.sort_by_key(|w| w.start_ms);
+        for p in sorted.windows(2).filter(|p| p[0].overlaps(p[1])) {
+            issues.push(format!(
+                "time windows {} and {} overlap",
+                fmt(p[0]),
+                fmt(p[1])
+            ));
+        }
 
-        for candidate in candidates {
-            if self.is_working_time(candidate) {
-                return Some(candidate);
-            }
-            // If candidate is blocked, try end of the blocking period
+        if !self.time_windows.is_empty() {
             for bp in &self.blocked_periods {
-                if bp.contains(candidate) && bp.end_ms < i64::MAX && self.is_working_time(bp.end_ms)
-                {
-                    return Some(bp.end_ms);
+                if bp.end_ms > bp.start_ms && !self.time_windows.iter().any(|w| w.overlaps(bp)) {
+                    issues.push(format!(
+                        "blocked period {} lies outside all time windows",
+                        fmt(bp)
+                    ));
                 }
             }
         }
+        issues
+    }
+
+    /// Rewrites the calendar in canonical form without changing its
+    /// availability.
+    ///
+    /// Sorts windows and blocked periods, merges overlapping or adjacent
+    /// ones, and drops empty intervals and blocked periods outside every
+    /// time window. A normalized calendar passes [`check`](Self::check).
+    pub fn normalize(&mut self) {
+        self.time_windows = merge_windows(&self.time_windows);
+        self.blocked_periods = merge_windows(&self.blocked_periods);
+        if !self.time_windows.is_empty() {
+            let windows = &self.time_windows;
+            self.blocked_periods
+                .retain(|bp| windows.iter().any(|w| w.overlaps(bp)));
+        }
+    }
+}
+
+impl Calendar {
+    /// Available time as sorted, disjoint, non-adjacent intervals.
+    ///
+    /// Unbounded availability extends to `i64::MIN` / `i64::MAX`.
+    pub fn available_intervals(&self) -> Vec<TimeWindow> {
+        let base = if self.time_windows.is_empty() {
+            vec![TimeWindow::new(i64::MIN, i64::MAX)]
+        } else {
+            merge_windows(&self.time_windows)
+        };
+        subtract_windows(&base, &self.blocked_periods)
+    }
+
+    /// Creates a calendar available exactly during `intervals`.
+    ///
+    /// Availability unbounded on both sides is stored as blocked gaps
+    /// without windows; no availability is stored as one blocked period
+    /// spanning all time.
+    pub fn from_intervals(id: impl Into<String>, intervals: &[TimeWindow]) -> Self {
+        let mut calendar = Self::new(id);
+        let merged = merge_windows(intervals);
+        let unbounded = merged.first().is_some_and(|w| w.start_ms == i64::MIN)
+            && merged.last().is_some_and(|w| w.end_ms == i64::MAX);
+        if merged.is_empty() {
+            calendar.blocked_periods = vec![TimeWindow::new(i64::MIN, i64::MAX)];
+        } else if unbounded {
+            calendar.blocked_periods = merged
+                .windows(2)
+                .map(|w| TimeWindow::new(w[0].end_ms, w[1].start_ms))
+                .collect();
+        } else {
+            calendar.time_windows = merged;
+        }
+        calendar
+    }
+
+    /// Time available in both calendars (ID `a&b`).
+    pub fn intersect(&self, other: &Calendar) -> Calendar {
+        let out = intersect_windows(&self.available_intervals(), &other.available_intervals());
+        Self::from_intervals(format!("{}&{}", self.id, other.id), &out)
+    }
+
+    /// Time available in either calendar (ID `a|b`).
+    pub fn union(&self, other: &Calendar) -> Calendar {
+        let out = union_windows(&self.available_intervals(), &other.available_intervals());
+        Self::from_intervals(format!("{}|{}", self.id, other.id), &out)
+    }
+
+    /// Time available in this calendar but not in `other` (ID `a-b`).
+    pub fn subtract(&self, other: &Calendar) -> Calendar {
+        let out = subtract_windows(&self.available_intervals(), &other.available_intervals());
+        Self::from_intervals(format!("{}-{}", self.id, other.id), &out)
+    }
+}
+
+/// Precomputed availability of a [`Calendar`].
+///
+/// Stores the available intervals sorted and disjoint. Lookups binary
+/// search them, starting from a cursor at the last hit, so scans that
+/// move forward in time (as schedulers do) rarely search at all. The
+/// index is a snapshot: rebuild it after changing the calendar.
+#[derive(Debug)]
+pub struct CalendarIndex {
+    intervals: Vec<TimeWindow>,
+    cursor: AtomicUsize,
+}
+
+impl Clone for CalendarIndex {
+    fn clone(&self) -> Self {
+        Self {
+            intervals: self.intervals.clone(),
+            cursor: AtomicUsize::new(self.cursor.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl CalendarIndex {
+    /// Indexes a calendar.
+    pub fn new(calendar: &Calendar) -> Self {
+        Self {
+            intervals: calendar.available_intervals(),
+            cursor: AtomicUsize::new(0),
+        }
+    }
+
+    /// Available intervals, sorted and disjoint.
+    pub fn intervals(&self) -> &[TimeWindow] {
+        &self.intervals
+    }
 
-        None
+    /// Position of the first interval ending after `time_ms`.
+    fn locate(&self, time_ms: i64) -> usize {
+        let n = self.intervals.len();
+        let fits = |i: usize| {
+            (i == n || self.intervals[i].end_ms > time_ms)
+                && (i == 0 || self.intervals[i - 1].end_ms <= time_ms)
+        };
+        let cursor = self.cursor.load(Ordering::Relaxed).min(n);
+        let i = if fits(cursor) {
+            cursor
+        } else if cursor < n && fits(cursor + 1) {
+            cursor + 1
+        } else {
+            self.intervals.partition_point(|w| w.end_ms <= time_ms)
+        };
+        self.cursor.store(i, Ordering::Relaxed);
+        i
+    }
+
+    /// Whether a timestamp is within working time.
+    pub fn is_working_time(&self, time_ms: i64) -> bool {
+        self.intervals
+            .get(self.locate(time_ms))
+            .is_some_and(|w| w.start_ms <= time_ms)
+    }
+
+    /// Finds the next available time at or after `from_ms`.
+    pub fn next_available_time(&self, from_ms: i64) -> Option<i64> {
+        self.intervals
+            .get(self.locate(from_ms))
+            .map(|w| w.start_ms.max(from_ms))
     }
 
     /// Computes total available time within a range [start, end).
@@ -166,47 +365,97 @@ impl Calendar {
         if end_ms <= start_ms {
             return 0;
         }
-
         let range = TimeWindow::new(start_ms, end_ms);
+        self.intervals[self.locate(start_ms)..]
+            .iter()
+            .take_while(|w| w.start_ms < end_ms)
+            .filter_map(|w| overlap_duration(&range, w))
+            .sum()
+    }
+}
 
-        // If no windows, total = range - blocked
-        if self.time_windows.is_empty() {
-            let blocked: i64 = self
-                .blocked_periods
-                .iter()
-                .filter_map(|bp| overlap_duration(&range, bp))
-                .sum();
-            return range.duration_ms() - blocked;
+// ---- Window-set algebra ----
+//
+// A window set is a list of `TimeWindow`s read as the union of its
+// members. Inputs may be unsorted, overlapping, or contain empty windows;
+// outputs are normalized: sorted, disjoint, non-adjacent, non-empty.
+
+/// Normalizes a window set: sorts it, merges overlapping or touching
+/// windows, and drops empty ones.
+pub fn merge_windows(windows: &[TimeWindow]) -> Vec<TimeWindow> {
+    let mut sorted: Vec<TimeWindow> = windows
+        .iter()
+        .filter(|w| w.end_ms > w.start_ms)
+        .cloned()
+        .collect();
+    sorted.sort_by_key(|w| w.start_ms);
+
+    let mut merged: Vec<TimeWindow> = Vec::with_capacity(sorted.len());
+    for w in sorted {
+        match merged.last_mut() {
+            Some(last) if w.start_ms <= last.end_ms => last.end_ms = last.end_ms.max(w.end_ms),
+            _ => merged.push(w),
         }
+    }
+    merged
+}
 
-        // Sum window intersections with range, minus blocked intersections
-        let mut available: i64 = 0;
-        for w in &self.time_windows {
-            if let Some(dur) = overlap_duration(&range, w) {
-                available += dur;
-            }
-        }
+/// Time covered by `a` or `b`.
+pub fn union_windows(a: &[TimeWindow], b: &[TimeWindow]) -> Vec<TimeWindow> {
+    let all: Vec<TimeWindow> = a.iter().chain(b).cloned().collect();
+    merge_windows(&all)
+}
 
-        // Subtract blocked intersections
-        let blocked: i64 = self
-            .blocked_periods
-            .iter()
-            .filter_map(|bp| overlap_duration(&range, bp))
-            .sum();
+/// Time covered by both `a` and `b`.
+pub fn intersect_windows(a: &[TimeWindow], b: &[TimeWindow]) -> Vec<TimeWindow> {
+    let (a, b) = (merge_windows(a), merge_windows(b));
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if let Some(w) = a[i].intersection(&b[j]) {
+            out.push(w);
+        }
+        if a[i].end_ms < b[j].end_ms {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    out
+}
 
-        (available - blocked).max(0)
+/// Time covered by `a` but not by `b`.
+pub fn subtract_windows(a: &[TimeWindow], b: &[TimeWindow]) -> Vec<TimeWindow> {
+    let (a, b) = (merge_windows(a), merge_windows(b));
+    let mut out = Vec::new();
+    let mut j = 0;
+    for w in &a {
+        // Cuts ending before this window cannot affect later ones either.
+        while j < b.len() && b[j].end_ms <= w.start_ms {
+            j += 1;
+        }
+        let mut start = w.start_ms;
+        for cut in b[j..].iter().take_while(|c| c.start_ms < w.end_ms) {
+            if cut.start_ms > start {
+                out.push(TimeWindow::new(start, cut.start_ms));
+            }
+            start = start.max(cut.end_ms);
+        }
+        if start < w.end_ms {
+            out.push(TimeWindow::new(start, w.end_ms));
+        }
     }
+    out
+}
+
+/// Time within `range` not covered by `windows` (e.g., idle gaps).
+pub fn complement_windows(windows: &[TimeWindow], range: &TimeWindow) -> Vec<TimeWindow> {
+    subtract_windows(core::slice::from_ref(range), windows)
 }
 
 /// Computes overlap duration between two time windows.
 fn overlap_duration(a: &TimeWindow, b: &TimeWindow) -> Option<i64> {
-    let start = a.start_ms.max(b.start_ms);
-    let end = a.end_ms.min(b.end_ms);
-    if end > start {
-        Some(end - start)
-    } else {
-        None
-    }
+    a.intersection(b).map(|w| w.duration_ms())
 }
 
 #[cfg(test)]
@@ -234,6 +483,27 @@ mod tests {
         assert!(!a.overlaps(&c));
     }
 
+    #[test]
+    fn test_window_set_algebra() {
+        let w = TimeWindow::new;
+        let a = vec![w(50, 80), w(0, 30), w(20, 40), w(90, 90)];
+        let b = vec![w(35, 60), w(70, 100)];
+
+        assert_eq!(merge_windows(&a), vec![w(0, 40), w(50, 80)]);
+        assert_eq!(union_windows(&a, &b), vec![w(0, 100)]);
+        assert_eq!(
+            intersect_windows(&a, &b),
+            vec![w(35, 40), w(50, 60), w(70, 80)]
+        );
+        assert_eq!(subtract_windows(&a, &b), vec![w(0, 35), w(60, 70)]);
+        assert_eq!(
+            complement_windows(&a, &w(-10, 60)),
+            vec![w(-10, 0), w(40, 50)]
+        );
+        assert!(complement_windows(&[], &w(5, 5)).is_empty());
+        assert_eq!(w(0, 10).intersection(&w(10, 20)), None);
+    }
+
     #[test]
     fn test_calendar_always_available() {
         let cal = Calendar::always_available("cal1");
@@ -294,6 +564,133 @@ mod tests {
         assert_eq!(avail2, 10_000); // 60k-70k (50k-60k blocked)
     }
 
+    #[test]
+    fn test_composition() {
+        let plant = Calendar::new("plant")
+            .with_window(0, 100)
+            .with_window(200, 300)
+            .with_blocked(50, 60);
+        let shift = Calendar::new("shift").with_window(40, 250);
+
+        let both = plant.intersect(&shift);
+        assert_eq!(both.id, "plant&shift");
+        assert_eq!(
+            both.available_intervals(),
+            vec![
+                TimeWindow::new(40, 50),
+                TimeWindow::new(60, 100),
+                TimeWindow::new(200, 250)
+            ]
+        );
+
+        let either = plant.union(&shift);
+        assert_eq!(either.available_intervals(), vec![TimeWindow::new(0, 300)]);
+
+        let vacation = Calendar::new("vacation").with_window(80, 220);
+        let left = plant.subtract(&vacation);
+        assert_eq!(
+            left.time_windows,
+            vec![
+                TimeWindow::new(0, 50),
+                TimeWindow::new(60, 80),
+                TimeWindow::new(220, 300)
+            ]
+        );
+        assert!(left.blocked_periods.is_empty());
+    }
+
+    #[test]
+    fn test_composition_with_unbounded_calendars() {
+        let always = Calendar::always_available("all").with_blocked(10, 20);
+        let holidays = Calendar::new("holidays").with_window(100, 200);
+
+        // Unbounded results keep the "no windows + blocked" form.
+        let open = always.subtract(&holidays);
+        assert!(open.time_windows.is_empty());
+        assert_eq!(
+            open.blocked_periods,
+            vec![TimeWindow::new(10, 20), TimeWindow::new(100, 200)]
+        );
+        assert!(open.is_working_time(50));
+        assert!(!open.is_working_time(150));
+
+        // Nothing left: blocked forever.
+        let none = holidays.subtract(&Calendar::always_available("all"));
+        assert!(none.available_intervals().is_empty());
+        assert!(!none.is_working_time(150));
+        assert_eq!(none.next_available_time(0), None);
+    }
+
+    #[test]
+    fn test_check_and_normalize() {
+        let mut cal = Calendar::new("messy")
+            .with_window(200, 300)
+            .with_window(0, 100)
+            .with_window(100, 150) // adjacent to [0, 100)
+            .with_window(250, 400) // overlaps [200, 300)
+            .with_window(500, 500)
+            .with_blocked(120, 130)
+            .with_blocked(160, 190) // between windows
+            .with_blocked(90, 80);
+        let issues = cal.check();
+        assert_eq!(issues.len(), 5, "{issues:?}");
+        assert!(issues.iter().any(|i| i.contains("[500, 500)")));
+        assert!(issues.iter().any(|i| i.contains("not sorted")));
+        assert!(issues
+            .iter()
+            .any(|i| i.contains("[200, 300) and [250, 400)")));
+        assert!(issues.iter().any(|i| i.contains("[160, 190) lies outside")));
+
+        let before = cal.available_intervals();
+        cal.normalize();
+        assert!(cal.check().is_empty());
+        assert_eq!(
+            cal.time_windows,
+            vec![TimeWindow::new(0, 150), TimeWindow::new(200, 400)]
+        );
+        assert_eq!(cal.blocked_periods, vec![TimeWindow::new(120, 130)]);
+        assert_eq!(cal.available_intervals(), before);
+    }
+
+    #[test]
+    fn test_queries_tolerate_malformed_calendars() {
+        // Unsorted, chained blocked periods without windows
+        let cal = Calendar::always_available("cal")
+            .with_blocked(20, 30)
+            .with_blocked(10, 20);
+        assert_eq!(cal.next_available_time(12), Some(30));
+
+        // Overlapping windows and a blocked period outside them
+        let cal = Calendar::new("cal")
+            .with_window(0, 100)
+            .with_window(50, 150)
+            .with_blocked(200, 300);
+        assert_eq!(cal.available_time_in_range(0, 300), 150);
+    }
+
+    #[test]
+    fn test_index_matches_calendar() {
+        let cal = Calendar::new("shifts")
+            .with_window(0, 8_000)
+            .with_window(16_000, 24_000)
+            .with_window(32_000, 40_000)
+            .with_blocked(4_000, 6_000);
+        let index = cal.index();
+
+        // Forward scan, then jumps back and far ahead
+        for t in (0..45_000).step_by(500).chain([3_000, 39_999, 100, 40_000]) {
+            assert_eq!(
+                index.next_available_time(t),
+                cal.next_available_time(t),
+                "t={t}"
+            );
+            assert_eq!(index.is_working_time(t), cal.is_working_time(t), "t={t}");
+        }
+        assert_eq!(index.available_time_in_range(2_000, 20_000), 8_000);
+        assert_eq!(index.available_time_in_range(0, 50_000), 22_000);
+        assert_eq!(index.next_available_time(40_000), None);
+    }
+
     #[test]
     fn test_available_time_no_windows() {
         let cal = Calendar::always_available("cal").with_blocked(20_000, 30_000);
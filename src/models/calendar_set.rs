@@ -0,0 +1,218 @@
+//! Layered calendar composition.
+//!
+//! Resources usually inherit several calendars — the plant calendar, a
+//! team shift pattern, a labor agreement, individual vacations. A
+//! [`CalendarSet`] keeps these calendars by ID and resolves the effective
+//! calendar of each resource by applying layers in order:
+//!
+//! 1. Start from the resource's own calendar (always available if none).
+//! 2. Apply the common layers, shared by all resources.
+//! 3. Apply the resource's own layers.
+//!
+//! # Example
+//!
+//! ```
+//! use u_schedule::models::{Calendar, CalendarOp, CalendarSet, Resource};
+//!
+//! let set = CalendarSet::new()
+//!     .with_calendar(Calendar::new("plant").with_window(0, 1000))
+//!     .with_calendar(Calendar::new("day-shift").with_window(200, 800))
+//!     .with_calendar(Calendar::new("w1-vacation").with_window(300, 400))
+//!     .with_common_layer(CalendarOp::Intersect, "plant")
+//!     .with_layer("W1", CalendarOp::Intersect, "day-shift")
+//!     .with_layer("W1", CalendarOp::Subtract, "w1-vacation");
+//!
+//! let effective = set.resolve(&Resource::human("W1")).unwrap();
+//! assert!(effective.is_working_time(250));
+//! assert!(!effective.is_working_time(350));
+//! assert!(!effective.is_working_time(900));
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+use super::{Calendar, Resource};
+
+/// How a layer combines with the calendar resolved so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CalendarOp {
+    /// Keep only time also available in the layer.
+    Intersect,
+    /// Add time available in the layer.
+    Union,
+    /// Remove time available in the layer (e.g., vacations).
+    Subtract,
+}
+
+/// One composition step: an operation with a calendar ID.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarLayer {
+    /// Operation.
+    pub op: CalendarOp,
+    /// ID of a calendar in the set.
+    pub calendar_id: String,
+}
+
+/// Named calendars plus composition layers per resource.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CalendarSet {
+    /// Calendars by ID.
+    pub calendars: HashMap<String, Calendar>,
+    /// Layers applied to every resource, in order.
+    #[serde(default)]
+    pub common_layers: Vec<CalendarLayer>,
+    /// Layers per resource ID, applied after the common layers.
+    #[serde(default)]
+    pub resource_layers: HashMap<String, Vec<CalendarLayer>>,
+}
+
+impl CalendarSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a calendar (replacing one with the same ID).
+    pub fn with_calendar(mut self, calendar: Calendar) -> Self {
+        self.calendars.insert(calendar.id.clone(), calendar);
+        self
+    }
+
+    /// Adds a layer applied to every resource.
+    pub fn with_common_layer(mut self, op: CalendarOp, calendar_id: impl Into<String>) -> Self {
+        self.common_layers.push(CalendarLayer {
+            op,
+            calendar_id: calendar_id.into(),
+        });
+        self
+    }
+
+    /// Adds a layer for one resource.
+    pub fn with_layer(
+        mut self,
+        resource_id: impl Into<String>,
+        op: CalendarOp,
+        calendar_id: impl Into<String>,
+    ) -> Self {
+        self.resource_layers
+            .entry(resource_id.into())
+            .or_default()
+            .push(CalendarLayer {
+                op,
+                calendar_id: calendar_id.into(),
+            });
+        self
+    }
+
+    /// Resolves the effective calendar of a resource.
+    ///
+    /// Returns an error naming the first layer whose calendar is not in
+    /// the set.
+    pub fn resolve(&self, resource: &Resource) -> Result<Calendar, String> {
+        let base = resource
+            .calendar
+            .clone()
+            .unwrap_or_else(|| Calendar::always_available(&resource.id));
+        let own = self
+            .resource_layers
+            .get(&resource.id)
+            .map_or(&[][..], |l| l.as_slice());
+
+        self.common_layers
+            .iter()
+            .chain(own)
+            .try_fold(base, |calendar, layer| {
+                let other = self.calendars.get(&layer.calendar_id).ok_or_else(|| {
+                    format!(
+                        "resource `{}` references unknown calendar `{}`",
+                        resource.id, layer.calendar_id
+                    )
+                })?;
+                Ok(match layer.op {
+                    CalendarOp::Intersect => calendar.intersect(other),
+                    CalendarOp::Union => calendar.union(other),
+                    CalendarOp::Subtract => calendar.subtract(other),
+                })
+            })
+    }
+
+    /// Replaces the calendar of every resource with a layer by its
+    /// effective calendar.
+    ///
+    /// Resources are left unchanged if any layer cannot be resolved.
+    pub fn apply(&self, resources: &mut [Resource]) -> Result<(), String> {
+        let mut resolved = Vec::new();
+        for (i, resource) in resources.iter().enumerate() {
+            if self.common_layers.is_empty() && !self.resource_layers.contains_key(&resource.id) {
+                continue;
+            }
+            resolved.push((i, self.resolve(resource)?));
+        }
+        for (i, calendar) in resolved {
+            resources[i].calendar = Some(calendar);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TimeWindow;
+
+    fn set() -> CalendarSet {
+        CalendarSet::new()
+            .with_calendar(Calendar::new("plant").with_window(0, 1000))
+            .with_calendar(Calendar::new("early").with_window(0, 500))
+            .with_calendar(Calendar::new("overtime").with_window(1000, 1200))
+            .with_calendar(Calendar::new("vacation").with_window(100, 200))
+            .with_common_layer(CalendarOp::Intersect, "plant")
+            .with_layer("W1", CalendarOp::Intersect, "early")
+            .with_layer("W1", CalendarOp::Subtract, "vacation")
+            .with_layer("W2", CalendarOp::Union, "overtime")
+    }
+
+    #[test]
+    fn test_layers_apply_in_order() {
+        let set = set();
+        let w1 = set.resolve(&Resource::human("W1")).unwrap();
+        assert_eq!(
+            w1.available_intervals(),
+            vec![TimeWindow::new(0, 100), TimeWindow::new(200, 500)]
+        );
+        let w2 = set.resolve(&Resource::human("W2")).unwrap();
+        assert_eq!(w2.available_intervals(), vec![TimeWindow::new(0, 1200)]);
+
+        // The resource's own calendar is the starting point.
+        let own = Resource::human("W3").with_calendar(Calendar::new("own").with_window(900, 2000));
+        let w3 = set.resolve(&own).unwrap();
+        assert_eq!(w3.available_intervals(), vec![TimeWindow::new(900, 1000)]);
+    }
+
+    #[test]
+    fn test_apply_and_unknown_calendar() {
+        let mut resources = vec![Resource::human("W1"), Resource::human("W2")];
+        set().apply(&mut resources).unwrap();
+        assert!(!resources[0].calendar.as_ref().unwrap().is_working_time(150));
+        assert!(resources[1]
+            .calendar
+            .as_ref()
+            .unwrap()
+            .is_working_time(1100));
+
+        let broken = set().with_layer("W2", CalendarOp::Subtract, "missing");
+        let mut resources = vec![Resource::human("W1"), Resource::human("W2")];
+        let err = broken.apply(&mut resources).unwrap_err();
+        assert!(err.contains("missing"));
+        assert!(resources[0].calendar.is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_json_roundtrip() {
+        let json = serde_json::to_string(&set()).unwrap();
+        let parsed: CalendarSet = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.calendars.len(), 4);
+        assert_eq!(parsed.resource_layers["W1"][1].op, CalendarOp::Subtract);
+    }
+}
@@ -0,0 +1,320 @@
+//! Compact schedule representation for very large outputs.
+//!
+//! [`Assignment`] owns three `String` IDs, so a schedule with hundreds of
+//! thousands of assignments holds as many copies of the same task and
+//! resource IDs. [`CompactSchedule`] stores each distinct ID once in a
+//! shared string table and refers to it by index.
+//!
+//! # Memory per Assignment (64-bit)
+//!
+//! | Form | Inline | Heap |
+//! |------|--------|------|
+//! | [`Assignment`] | 104 bytes | 3 string allocations (each rounded up to the allocator's size class, typically 16–32 bytes for short IDs) |
+//! | [`CompactAssignment`] | 48 bytes | none |
+//!
+//! The string table adds `24 bytes + length` per distinct ID, shared by
+//! all assignments. For 200k assignments over 200k activities, 20k tasks
+//! and 100 machines (IDs of about 8 bytes) this is roughly 10 MB of
+//! assignments plus 7 MB of strings, against 21 MB of structs plus 600k
+//! string allocations for [`Schedule`]. Serialized, the compact form
+//! also avoids repeating IDs.
+//!
+//! # Usage
+//!
+//! ```
+//! use u_schedule::models::{Assignment, CompactSchedule, Schedule};
+//!
+//! let mut schedule = Schedule::new();
+//! schedule.add_assignment(Assignment::new("O1", "J1", "M1", 0, 1000));
+//! schedule.add_assignment(Assignment::new("O2", "J1", "M1", 1000, 2000));
+//!
+//! let compact = CompactSchedule::from_schedule(&schedule);
+//! assert_eq!(compact.strings().len(), 4); // O1, J1, M1, O2
+//! assert_eq!(compact.iter().nth(1).unwrap().activity_id, "O2");
+//! assert_eq!(compact.to_schedule().assignments.len(), 2);
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+use super::{Annotation, Assignment, AssignmentConfidence, Schedule, Violation};
+
+/// An assignment whose IDs are indices into a string table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactAssignment {
+    /// Activity ID (string table index).
+    pub activity: u32,
+    /// Task ID (string table index).
+    pub task: u32,
+    /// Resource ID (string table index).
+    pub resource: u32,
+    /// Start time (ms).
+    pub start_ms: i64,
+    /// End time (ms).
+    pub end_ms: i64,
+    /// Setup time portion (ms).
+    pub setup_ms: i64,
+    /// Teardown time portion (ms).
+    #[serde(default)]
+    pub teardown_ms: i64,
+}
+
+/// A borrowed view of a compact assignment with resolved IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssignmentRef<'a> {
+    /// Activity ID.
+    pub activity_id: &'a str,
+    /// Parent task ID.
+    pub task_id: &'a str,
+    /// Resource ID.
+    pub resource_id: &'a str,
+    /// Start time (ms).
+    pub start_ms: i64,
+    /// End time (ms).
+    pub end_ms: i64,
+    /// Setup time portion (ms).
+    pub setup_ms: i64,
+    /// Teardown time portion (ms).
+    pub teardown_ms: i64,
+}
+
+impl AssignmentRef<'_> {
+    /// Converts to an owned [`Assignment`].
+    pub fn to_assignment(&self) -> Assignment {
+        Assignment::new(
+            self.activity_id,
+            self.task_id,
+            self.resource_id,
+            self.start_ms,
+            self.end_ms,
+        )
+        .with_setup(self.setup_ms)
+        .with_teardown(self.teardown_ms)
+    }
+}
+
+/// A schedule with IDs interned in a shared string table.
+///
+/// Read-only once built: convert from a [`Schedule`], query through
+/// [`iter`](Self::iter), and convert back with
+/// [`to_schedule`](Self::to_schedule).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompactSchedule {
+    strings: Vec<String>,
+    assignments: Vec<CompactAssignment>,
+    violations: Vec<Violation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    annotations: Vec<Annotation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    confidence: Vec<AssignmentConfidence>,
+}
+
+impl CompactSchedule {
+    /// Builds the compact form of `schedule`, in assignment order.
+    ///
+    /// # Panics
+    /// If the schedule has more than `u32::MAX` distinct IDs.
+    pub fn from_schedule(schedule: &Schedule) -> Self {
+        fn intern<'s>(
+            id: &'s str,
+            index: &mut HashMap<&'s str, u32>,
+            strings: &mut Vec<String>,
+        ) -> u32 {
+            *index.entry(id).or_insert_with(|| {
+                strings.push(id.to_string());
+                u32::try_from(strings.len() - 1).expect("more than u32::MAX distinct IDs")
+            })
+        }
+
+        let mut strings: Vec<String> = Vec::new();
+        let mut index: HashMap<&str, u32> = HashMap::new();
+        let assignments = schedule
+            .assignments
+            .iter()
+            .map(|a| CompactAssignment {
+                activity: intern(&a.activity_id, &mut index, &mut strings),
+                task: intern(&a.task_id, &mut index, &mut strings),
+                resource: intern(&a.resource_id, &mut index, &mut strings),
+                start_ms: a.start_ms,
+                end_ms: a.end_ms,
+                setup_ms: a.setup_ms,
+                teardown_ms: a.teardown_ms,
+            })
+            .collect();
+        Self {
+            strings,
+            assignments,
+            violations: schedule.violations.clone(),
+            annotations: schedule.annotations.clone(),
+            confidence: schedule.confidence.clone(),
+        }
+    }
+
+    /// Converts back to the ergonomic form.
+    pub fn to_schedule(&self) -> Schedule {
+        Schedule {
+            assignments: self.iter().map(|a| a.to_assignment()).collect(),
+            violations: self.violations.clone(),
+            annotations: self.annotations.clone(),
+            confidence: self.confidence.clone(),
+        }
+    }
+
+    /// The string table.
+    pub fn strings(&self) -> &[String] {
+        &self.strings
+    }
+
+    /// The compact assignments.
+    pub fn assignments(&self) -> &[CompactAssignment] {
+        &self.assignments
+    }
+
+    /// Constraint violations.
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Planner notes.
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// Per-assignment confidence.
+    pub fn confidence(&self) -> &[AssignmentConfidence] {
+        &self.confidence
+    }
+
+    /// String at `index` of the table.
+    ///
+    /// # Panics
+    /// If `index` is out of range.
+    pub fn string(&self, index: u32) -> &str {
+        &self.strings[index as usize]
+    }
+
+    /// Assignments with resolved IDs.
+    pub fn iter(&self) -> impl Iterator<Item = AssignmentRef<'_>> {
+        self.assignments.iter().map(|a| AssignmentRef {
+            activity_id: self.string(a.activity),
+            task_id: self.string(a.task),
+            resource_id: self.string(a.resource),
+            start_ms: a.start_ms,
+            end_ms: a.end_ms,
+            setup_ms: a.setup_ms,
+            teardown_ms: a.teardown_ms,
+        })
+    }
+
+    /// Number of assignments.
+    pub fn len(&self) -> usize {
+        self.assignments.len()
+    }
+
+    /// Whether the schedule has no assignments.
+    pub fn is_empty(&self) -> bool {
+        self.assignments.is_empty()
+    }
+
+    /// Latest end time (0 if empty).
+    pub fn makespan_ms(&self) -> i64 {
+        self.assignments.iter().map(|a| a.end_ms).max().unwrap_or(0)
+    }
+
+    /// Approximate heap and inline memory in bytes (excluding violations
+    /// and allocator overhead).
+    pub fn memory_bytes(&self) -> usize {
+        core::mem::size_of::<Self>()
+            + self.assignments.capacity() * core::mem::size_of::<CompactAssignment>()
+            + self.strings.capacity() * core::mem::size_of::<String>()
+            + self.strings.iter().map(String::capacity).sum::<usize>()
+    }
+}
+
+impl From<&Schedule> for CompactSchedule {
+    fn from(schedule: &Schedule) -> Self {
+        Self::from_schedule(schedule)
+    }
+}
+
+impl From<&CompactSchedule> for Schedule {
+    fn from(compact: &CompactSchedule) -> Self {
+        compact.to_schedule()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::models::AnnotationTarget;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_round_trip() {
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("O1", "J1", "M1", 0, 1000).with_setup(200));
+        schedule.add_assignment(Assignment::new("O2", "J1", "M2", 1000, 1500));
+        schedule.add_assignment(Assignment::new("O3", "J2", "M1", 1000, 3000));
+        schedule.add_violation(Violation::deadline_miss("J2", "late"));
+        schedule.annotate(Annotation::new(
+            AnnotationTarget::Task("J2".into()),
+            "kim",
+            0,
+            "rush",
+        ));
+
+        let compact = CompactSchedule::from_schedule(&schedule);
+        assert_eq!(compact.len(), 3);
+        assert_eq!(
+            compact.strings(),
+            ["O1", "J1", "M1", "O2", "M2", "O3", "J2"]
+        );
+        assert_eq!(
+            compact.assignments()[2].resource,
+            compact.assignments()[0].resource
+        );
+        assert_eq!(compact.makespan_ms(), 3000);
+        assert_eq!(compact.violations().len(), 1);
+
+        let back = Schedule::from(&compact);
+        assert_eq!(back.assignments.len(), 3);
+        for (a, b) in schedule.assignments.iter().zip(&back.assignments) {
+            assert_eq!(
+                (&a.activity_id, &a.task_id, &a.resource_id),
+                (&b.activity_id, &b.task_id, &b.resource_id)
+            );
+            assert_eq!(
+                (a.start_ms, a.end_ms, a.setup_ms),
+                (b.start_ms, b.end_ms, b.setup_ms)
+            );
+        }
+        assert_eq!(back.violations, schedule.violations);
+        assert_eq!(back.annotations, schedule.annotations);
+
+        let json = serde_json::to_string(&compact).unwrap();
+        let parsed: CompactSchedule = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.iter().next(), compact.iter().next());
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_memory_per_assignment() {
+        assert_eq!(core::mem::size_of::<Assignment>(), 104);
+        assert_eq!(core::mem::size_of::<CompactAssignment>(), 48);
+
+        let mut schedule = Schedule::new();
+        for i in 0..1000 {
+            schedule.add_assignment(Assignment::new(
+                format!("O{i}"),
+                format!("J{}", i / 10),
+                format!("M{}", i % 5),
+                i,
+                i + 1,
+            ));
+        }
+        let compact = CompactSchedule::from_schedule(&schedule);
+        assert_eq!(compact.strings().len(), 1000 + 100 + 5);
+        assert!(compact.memory_bytes() < 1000 * 104);
+    }
+}
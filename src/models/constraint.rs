@@ -8,7 +8,8 @@
 //! Brucker (2007), "Scheduling Algorithms", Ch. 2
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+
+use super::prelude::*;
 
 /// A scheduling constraint.
 ///
@@ -19,12 +20,17 @@ use std::collections::HashMap;
 pub enum Constraint {
     /// Activity `after` cannot start until `before` finishes + `min_delay_ms`.
     ///
+    /// `mode` relaxes the relation (start-to-start, finish-to-finish,
+    /// overlap); `min_delay_ms` is then the lag of that relation.
+    ///
     /// # Reference
     /// Pinedo (2016), "Scheduling", precedence constraints (Ch. 2.1)
     Precedence {
         before: String,
         after: String,
         min_delay_ms: i64,
+        #[serde(default)]
+        mode: PrecedenceMode,
     },
 
     /// At most `max_capacity` activities may use `resource_id` simultaneously.
@@ -56,7 +62,8 @@ pub enum Constraint {
         cost_ms: i64,
     },
 
-    /// Listed activities must start at the same time.
+    /// Listed activities must start at the same time (across tasks; see
+    /// [`SyncGroups`](super::SyncGroups)).
     Synchronize { activity_ids: Vec<String> },
 }
 
@@ -67,6 +74,7 @@ impl Constraint {
             before: before.into(),
             after: after.into(),
             min_delay_ms: 0,
+            mode: PrecedenceMode::FinishToStart,
         }
     }
 
@@ -80,6 +88,22 @@ impl Constraint {
             before: before.into(),
             after: after.into(),
             min_delay_ms: delay_ms,
+            mode: PrecedenceMode::FinishToStart,
+        }
+    }
+
+    /// Creates a precedence constraint with a mode and lag.
+    pub fn precedence_with_mode(
+        before: impl Into<String>,
+        after: impl Into<String>,
+        mode: PrecedenceMode,
+        lag_ms: i64,
+    ) -> Self {
+        Self::Precedence {
+            before: before.into(),
+            after: after.into(),
+            min_delay_ms: lag_ms,
+            mode,
         }
     }
 
@@ -114,6 +138,69 @@ impl Constraint {
     }
 }
 
+/// Timing relation between a predecessor and a successor activity.
+///
+/// Each mode bounds the successor's start by the predecessor's interval
+/// `[start, end)` and a lag (ms):
+///
+/// | Mode | Successor start ≥ |
+/// |------|-------------------|
+/// | `FinishToStart` | `end + lag` |
+/// | `StartToStart` | `start + lag` |
+/// | `FinishToFinish` | `end + lag − successor duration` |
+/// | `Overlap { percent }` | `start + percent% × (end − start) + lag` |
+///
+/// `Overlap` models lap-phasing: the successor may start once the
+/// predecessor is `percent`% done. A zero lag `FinishToFinish` only
+/// requires the successor to finish no earlier than the predecessor.
+///
+/// # Reference
+/// Elmaghraby & Kamburowski (1992), "The analysis of activity networks
+/// under generalized precedence relations"
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum PrecedenceMode {
+    /// Successor starts after the predecessor finishes (no wait).
+    #[default]
+    FinishToStart,
+    /// Successor starts after the predecessor starts.
+    StartToStart,
+    /// Successor finishes after the predecessor finishes.
+    FinishToFinish,
+    /// Successor starts once the predecessor is `percent`% (0–100) done.
+    Overlap { percent: f64 },
+}
+
+impl PrecedenceMode {
+    /// Earliest successor start after a predecessor occupying
+    /// `[pred_start, pred_end)`.
+    pub fn earliest_start(
+        &self,
+        pred_start: i64,
+        pred_end: i64,
+        lag_ms: i64,
+        successor_ms: i64,
+    ) -> i64 {
+        let bound = match *self {
+            Self::FinishToStart => pred_end,
+            Self::StartToStart => pred_start,
+            Self::FinishToFinish => pred_end - successor_ms,
+            Self::Overlap { percent } => {
+                let done = (pred_end - pred_start) as f64 * percent.clamp(0.0, 100.0) / 100.0;
+                // Rounds half away from zero, like `f64::round` (std only).
+                let rounded = if done >= 0.0 { done + 0.5 } else { done - 0.5 };
+                pred_start + rounded as i64
+            }
+        };
+        bound + lag_ms
+    }
+
+    /// Equivalent minimum delay from predecessor end to successor start,
+    /// for fixed durations (may be negative).
+    pub fn end_to_start_delay(&self, lag_ms: i64, predecessor_ms: i64, successor_ms: i64) -> i64 {
+        self.earliest_start(0, predecessor_ms, lag_ms, successor_ms) - predecessor_ms
+    }
+}
+
 /// Sequence-dependent setup time matrix.
 ///
 /// Maps (from_category, to_category) → setup time in ms.
@@ -130,6 +217,7 @@ pub struct TransitionMatrix {
     /// Resource this matrix applies to.
     pub resource_id: String,
     /// Transition times: (from_category, to_category) → milliseconds.
+    #[serde(with = "transition_entries")]
     transitions: HashMap<(String, String), i64>,
     /// Default setup time when no explicit transition is defined.
     pub default_ms: i64,
@@ -180,6 +268,47 @@ impl TransitionMatrix {
     }
 }
 
+/// Serializes transitions as a list of `{from, to, time_ms}` entries,
+/// since JSON maps cannot have tuple keys.
+mod transition_entries {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{HashMap, String, Vec};
+
+    #[derive(Serialize, Deserialize)]
+    struct Entry {
+        from: String,
+        to: String,
+        time_ms: i64,
+    }
+
+    pub fn serialize<S: Serializer>(
+        map: &HashMap<(String, String), i64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut entries: Vec<Entry> = map
+            .iter()
+            .map(|((from, to), &time_ms)| Entry {
+                from: from.clone(),
+                to: to.clone(),
+                time_ms,
+            })
+            .collect();
+        entries.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+        entries.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<(String, String), i64>, D::Error> {
+        let entries = Vec::<Entry>::deserialize(deserializer)?;
+        Ok(entries
+            .into_iter()
+            .map(|e| ((e.from, e.to), e.time_ms))
+            .collect())
+    }
+}
+
 /// A collection of transition matrices indexed by resource ID.
 ///
 /// Provides unified lookup for sequence-dependent setup times
@@ -206,6 +335,11 @@ impl TransitionMatrixCollection {
         self
     }
 
+    /// Gets the matrix for a resource.
+    pub fn get(&self, resource_id: &str) -> Option<&TransitionMatrix> {
+        self.matrices.get(resource_id)
+    }
+
     /// Gets the transition time for a resource between two categories.
     ///
     /// Returns 0 if no matrix exists for the resource.
@@ -239,10 +373,12 @@ mod tests {
                 before,
                 after,
                 min_delay_ms,
+                mode,
             } => {
                 assert_eq!(before, "O1");
                 assert_eq!(after, "O2");
                 assert_eq!(min_delay_ms, 0);
+                assert_eq!(mode, PrecedenceMode::FinishToStart);
             }
             _ => panic!("wrong variant"),
         }
@@ -259,6 +395,31 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_precedence_modes() {
+        // Predecessor [100, 500), lag 50, successor 300 ms
+        let start = |mode: PrecedenceMode| mode.earliest_start(100, 500, 50, 300);
+        assert_eq!(start(PrecedenceMode::FinishToStart), 550);
+        assert_eq!(start(PrecedenceMode::StartToStart), 150);
+        assert_eq!(start(PrecedenceMode::FinishToFinish), 250);
+        assert_eq!(start(PrecedenceMode::Overlap { percent: 25.0 }), 250);
+
+        let ff = PrecedenceMode::FinishToFinish;
+        assert_eq!(ff.end_to_start_delay(0, 400, 300), -300);
+
+        // Constraints serialized before modes existed default to finish-to-start.
+        let json = r#"{"Precedence":{"before":"O1","after":"O2","min_delay_ms":0}}"#;
+        let c: Constraint = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            c,
+            Constraint::Precedence {
+                mode: PrecedenceMode::FinishToStart,
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn test_capacity_constraint() {
         let c = Constraint::capacity("M1", 2);
@@ -299,6 +460,19 @@ mod tests {
         assert_eq!(tm.get_transition("X", "Y"), 200);
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_transition_matrix_json_roundtrip() {
+        let mut tm = TransitionMatrix::new("changeover", "M1").with_default(500);
+        tm.set_transition("TypeA", "TypeB", 1000);
+        let collection = TransitionMatrixCollection::new().with_matrix(tm);
+
+        let json = serde_json::to_string(&collection).unwrap();
+        let back: TransitionMatrixCollection = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.get_transition_time("M1", "TypeA", "TypeB"), 1000);
+        assert_eq!(back.get_transition_time("M1", "TypeB", "TypeA"), 500);
+    }
+
     #[test]
     fn test_no_overlap_constraint() {
         let c = Constraint::no_overlap("M1", vec!["O1".into(), "O2".into(), "O3".into()]);
@@ -0,0 +1,535 @@
+//! Textual constraint syntax.
+//!
+//! Parses a small line-oriented language into [`Constraint`] values so
+//! that planners can keep constraints in spreadsheets or configuration
+//! files instead of code.
+//!
+//! # Syntax
+//!
+//! One constraint per line. Blank lines and `#` comments are ignored.
+//! Keywords are case-insensitive; identifiers are case-sensitive.
+//!
+//! | Line | Constraint |
+//! |------|-----------|
+//! | `O5 after O3` | `Precedence { before: O3, after: O5, min_delay_ms: 0 }` |
+//! | `O5 after O3 +10m` | Precedence with a 10-minute minimum delay |
+//! | `O5 after O3 ss +5m` | Start-to-start precedence with a 5-minute lag |
+//! | `O5 after O3 ff` | Finish-to-finish precedence |
+//! | `O5 after O3 50%` | Overlap: O5 may start when O3 is half done |
+//! | `M2 max 2 parallel` | `Capacity { resource_id: M2, max_capacity: 2 }` |
+//! | `O1 within 0..8h` | `TimeWindow { activity_id: O1, start_ms: 0, end_ms: 28_800_000 }` |
+//! | `M1 no-overlap O1, O2, O3` | `NoOverlap` on M1 |
+//! | `sync O1, O2` | `Synchronize` |
+//! | `setup A -> B 5m` | `TransitionCost { from: A, to: B, cost_ms: 300_000 }` |
+//!
+//! # Durations
+//!
+//! A duration is an integer followed by an optional unit:
+//! `ms` (default), `s`, `m`, `h`, `d`. Example: `90s`, `1500`, `2h`.
+//! Setup times and window bounds must not be negative.
+//!
+//! # Example
+//!
+//! ```
+//! use u_schedule::models::{parse_constraints, Constraint};
+//!
+//! let text = "O5 after O3 +10m   # routing\nM2 max 2 parallel";
+//! let constraints = parse_constraints(text).unwrap();
+//! assert_eq!(constraints.len(), 2);
+//! assert!(matches!(
+//!     &constraints[0],
+//!     Constraint::Precedence { min_delay_ms: 600_000, .. }
+//! ));
+//! ```
+
+use core::fmt;
+
+use super::prelude::*;
+use super::units::{DAY_MS, HOUR_MS, MINUTE_MS, SECOND_MS};
+use super::{Constraint, PrecedenceMode};
+
+/// Error produced when a constraint line cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintParseError {
+    /// 1-based line number in the input.
+    pub line: usize,
+    /// 1-based column of the offending token, or of the line's first
+    /// character when the line as a whole is malformed.
+    pub column: usize,
+    /// The offending line (trimmed).
+    pub text: String,
+    /// Human-readable description.
+    pub message: String,
+}
+
+impl fmt::Display for ConstraintParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}:{}: {} (in `{}`)",
+            self.line, self.column, self.message, self.text
+        )
+    }
+}
+
+impl core::error::Error for ConstraintParseError {}
+
+/// Parses a multi-line constraint specification.
+///
+/// Stops at the first malformed line and reports its line number.
+pub fn parse_constraints(input: &str) -> Result<Vec<Constraint>, ConstraintParseError> {
+    let mut constraints = Vec::new();
+    for (i, raw) in input.lines().enumerate() {
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let constraint = parse_line(line).map_err(|e| e.locate(i + 1, raw, line))?;
+        constraints.push(constraint);
+    }
+    Ok(constraints)
+}
+
+/// Parses a single constraint line; a trailing `#` comment is ignored.
+pub fn parse_constraint(line: &str) -> Result<Constraint, ConstraintParseError> {
+    let trimmed = strip_comment(line).trim();
+    parse_line(trimmed).map_err(|e| e.locate(1, line, trimmed))
+}
+
+/// Parses a duration literal such as `10m`, `90s`, `1500ms` or `1500`.
+///
+/// Returns the duration in milliseconds.
+pub fn parse_duration_ms(text: &str) -> Result<i64, String> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '-'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    if number.is_empty() || number == "-" {
+        return Err(format!("expected a duration, found `{text}`"));
+    }
+    let value: i64 = number
+        .parse()
+        .map_err(|_| format!("invalid number `{number}`"))?;
+    let factor = match unit.to_ascii_lowercase().as_str() {
+        "" | "ms" => 1,
+        "s" => SECOND_MS,
+        "m" | "min" => MINUTE_MS,
+        "h" => HOUR_MS,
+        "d" => DAY_MS,
+        other => {
+            return Err(format!(
+                "unknown duration unit `{other}` (expected ms, s, m, h or d)"
+            ))
+        }
+    };
+    value
+        .checked_mul(factor)
+        .ok_or_else(|| format!("duration `{text}` overflows"))
+}
+
+/// A parse failure within one line, optionally pinned to the token at fault.
+struct LineError<'a> {
+    message: String,
+    token: Option<&'a str>,
+}
+
+impl<'a> LineError<'a> {
+    fn at(token: &'a str, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            token: Some(token),
+        }
+    }
+
+    /// Resolves the error against `raw`, the untrimmed input line that
+    /// `line` and every token borrow from.
+    fn locate(self, line_no: usize, raw: &str, line: &str) -> ConstraintParseError {
+        let anchor = self.token.unwrap_or(line);
+        let offset = (anchor.as_ptr() as usize)
+            .saturating_sub(raw.as_ptr() as usize)
+            .min(raw.len());
+        ConstraintParseError {
+            line: line_no,
+            column: raw[..offset].chars().count() + 1,
+            text: line.to_string(),
+            message: self.message,
+        }
+    }
+}
+
+impl From<String> for LineError<'_> {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            token: None,
+        }
+    }
+}
+
+impl From<&str> for LineError<'_> {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+/// Parses a duration that must not be negative, such as a setup time or
+/// a window bound.
+fn parse_unsigned_duration_ms(token: &str) -> Result<i64, LineError<'_>> {
+    let ms = parse_duration_ms(token).map_err(|message| LineError::at(token, message))?;
+    if ms < 0 {
+        return Err(LineError::at(
+            token,
+            format!("duration must not be negative, found `{token}`"),
+        ));
+    }
+    Ok(ms)
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(pos) => &line[..pos],
+        None => line,
+    }
+}
+
+fn parse_line(line: &str) -> Result<Constraint, LineError<'_>> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let keyword = |i: usize| tokens.get(i).map(|t| t.to_ascii_lowercase());
+
+    match keyword(0).as_deref() {
+        Some("sync") => {
+            let ids = parse_id_list(&tokens[1..].join(" "))?;
+            if ids.len() < 2 {
+                return Err("`sync` needs at least two activity IDs".into());
+            }
+            return Ok(Constraint::synchronize(ids));
+        }
+        Some("setup") => return parse_setup(&tokens[1..]),
+        _ => {}
+    }
+
+    let Some(&subject) = tokens.first() else {
+        return Err("empty constraint".into());
+    };
+    match keyword(1).as_deref() {
+        Some("after") => {
+            let before = tokens
+                .get(2)
+                .ok_or("`after` must be followed by an activity ID")?;
+            let mut rest = 3;
+            let mode = match tokens.get(rest).map(|t| t.to_ascii_lowercase()) {
+                Some(t) if t == "fs" => PrecedenceMode::FinishToStart,
+                Some(t) if t == "ss" => PrecedenceMode::StartToStart,
+                Some(t) if t == "ff" => PrecedenceMode::FinishToFinish,
+                Some(t) if t.ends_with('%') => {
+                    let percent: f64 = t[..t.len() - 1]
+                        .parse()
+                        .map_err(|_| format!("invalid overlap `{t}`"))?;
+                    if !(0.0..=100.0).contains(&percent) {
+                        return Err(format!("overlap must be within 0–100%, found {t}").into());
+                    }
+                    PrecedenceMode::Overlap { percent }
+                }
+                _ => {
+                    rest -= 1;
+                    PrecedenceMode::FinishToStart
+                }
+            };
+            rest += 1;
+            let delay = match tokens.get(rest) {
+                None => 0,
+                Some(tok) => {
+                    let lag = tok
+                        .strip_prefix('+')
+                        .ok_or_else(|| format!("expected `+<duration>` after `{before}`"))?;
+                    if lag.starts_with(['+', '-']) {
+                        return Err(LineError::at(
+                            tok,
+                            format!("expected an unsigned duration, found `{tok}`"),
+                        ));
+                    }
+                    parse_duration_ms(lag).map_err(|message| LineError::at(tok, message))?
+                }
+            };
+            expect_end(&tokens, rest + 1)?;
+            Ok(Constraint::precedence_with_mode(
+                *before, subject, mode, delay,
+            ))
+        }
+        Some("max") => {
+            let count = tokens
+                .get(2)
+                .ok_or("`max` must be followed by a capacity")?;
+            let capacity: i32 = count
+                .parse()
+                .map_err(|_| format!("invalid capacity `{count}`"))?;
+            if capacity <= 0 {
+                return Err(LineError::at(
+                    count,
+                    format!("capacity must be positive, found {capacity}"),
+                ));
+            }
+            if keyword(3).as_deref() != Some("parallel") {
+                return Err("expected `parallel` after the capacity".into());
+            }
+            expect_end(&tokens, 4)?;
+            Ok(Constraint::capacity(subject, capacity))
+        }
+        Some("within") => {
+            let range = tokens
+                .get(2)
+                .ok_or("`within` must be followed by `<start>..<end>`")?;
+            let (start, end) = range
+                .split_once("..")
+                .ok_or_else(|| format!("expected `<start>..<end>`, found `{range}`"))?;
+            let start_ms = parse_unsigned_duration_ms(start)?;
+            let end_ms = parse_unsigned_duration_ms(end)?;
+            if end_ms <= start_ms {
+                return Err(LineError::at(
+                    end,
+                    format!("window end ({end_ms} ms) must be after start ({start_ms} ms)"),
+                ));
+            }
+            expect_end(&tokens, 3)?;
+            Ok(Constraint::time_window(subject, start_ms, end_ms))
+        }
+        Some("no-overlap") => {
+            let ids = parse_id_list(&tokens[2..].join(" "))?;
+            if ids.len() < 2 {
+                return Err("`no-overlap` needs at least two activity IDs".into());
+            }
+            Ok(Constraint::no_overlap(subject, ids))
+        }
+        Some(other) => Err(LineError::at(
+            tokens[1],
+            format!("unknown keyword `{other}` (expected after, max, within or no-overlap)"),
+        )),
+        None => Err(format!("incomplete constraint `{line}`").into()),
+    }
+}
+
+fn parse_setup<'a>(tokens: &[&'a str]) -> Result<Constraint, LineError<'a>> {
+    // setup A -> B 5m
+    if tokens.len() != 4 || tokens[1] != "->" {
+        return Err("expected `setup <from> -> <to> <duration>`".into());
+    }
+    Ok(Constraint::TransitionCost {
+        from_category: tokens[0].to_string(),
+        to_category: tokens[2].to_string(),
+        cost_ms: parse_unsigned_duration_ms(tokens[3])?,
+    })
+}
+
+fn parse_id_list(text: &str) -> Result<Vec<String>, String> {
+    let ids: Vec<String> = text
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect();
+    if let Some(bad) = ids.iter().find(|id| id.contains(char::is_whitespace)) {
+        return Err(format!("missing comma in ID list near `{bad}`"));
+    }
+    Ok(ids)
+}
+
+fn expect_end<'a>(tokens: &[&'a str], expected_len: usize) -> Result<(), LineError<'a>> {
+    match tokens.get(expected_len) {
+        Some(extra) => Err(LineError::at(
+            extra,
+            format!("unexpected trailing token `{extra}`"),
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_precedence() {
+        let c = parse_constraint("O5 after O3").unwrap();
+        match c {
+            Constraint::Precedence {
+                before,
+                after,
+                min_delay_ms,
+                ..
+            } => {
+                assert_eq!(before, "O3");
+                assert_eq!(after, "O5");
+                assert_eq!(min_delay_ms, 0);
+            }
+            _ => panic!("wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_parse_precedence_with_lag() {
+        let c = parse_constraint("O5 after O3 +10m").unwrap();
+        assert!(matches!(
+            c,
+            Constraint::Precedence {
+                min_delay_ms: 600_000,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_precedence_modes() {
+        let mode = |line: &str| match parse_constraint(line).unwrap() {
+            Constraint::Precedence {
+                mode, min_delay_ms, ..
+            } => (mode, min_delay_ms),
+            _ => panic!("wrong variant"),
+        };
+        assert_eq!(
+            mode("O5 after O3 SS +5m"),
+            (PrecedenceMode::StartToStart, 300_000)
+        );
+        assert_eq!(mode("O5 after O3 ff"), (PrecedenceMode::FinishToFinish, 0));
+        assert_eq!(
+            mode("O5 after O3 50%"),
+            (PrecedenceMode::Overlap { percent: 50.0 }, 0)
+        );
+        assert!(parse_constraint("O5 after O3 150%").is_err());
+        assert!(parse_constraint("O5 after O3 ss +1m extra").is_err());
+    }
+
+    #[test]
+    fn test_parse_capacity() {
+        let c = parse_constraint("M2 max 2 parallel").unwrap();
+        match c {
+            Constraint::Capacity {
+                resource_id,
+                max_capacity,
+            } => {
+                assert_eq!(resource_id, "M2");
+                assert_eq!(max_capacity, 2);
+            }
+            _ => panic!("wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_parse_window_no_overlap_sync_setup() {
+        let text = "
+            O1 within 1h..8h   # shift
+            M1 no-overlap O1, O2, O3
+            SYNC O1, O2
+            setup TypeA -> TypeB 5m
+        ";
+        let cs = parse_constraints(text).unwrap();
+        assert_eq!(cs.len(), 4);
+        assert!(matches!(
+            &cs[0],
+            Constraint::TimeWindow {
+                start_ms: 3_600_000,
+                end_ms: 28_800_000,
+                ..
+            }
+        ));
+        assert!(
+            matches!(&cs[1], Constraint::NoOverlap { resource_id, activity_ids }
+                if resource_id == "M1" && activity_ids.len() == 3)
+        );
+        assert!(
+            matches!(&cs[2], Constraint::Synchronize { activity_ids } if activity_ids.len() == 2)
+        );
+        assert!(matches!(
+            &cs[3],
+            Constraint::TransitionCost {
+                cost_ms: 300_000,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_duration_units() {
+        assert_eq!(parse_duration_ms("1500").unwrap(), 1500);
+        assert_eq!(parse_duration_ms("1500ms").unwrap(), 1500);
+        assert_eq!(parse_duration_ms("90s").unwrap(), 90_000);
+        assert_eq!(parse_duration_ms("2h").unwrap(), 7_200_000);
+        assert_eq!(parse_duration_ms("1d").unwrap(), 86_400_000);
+        assert!(parse_duration_ms("5w").is_err());
+        assert!(parse_duration_ms("m").is_err());
+    }
+
+    #[test]
+    fn test_error_reports_line() {
+        let text = "O2 after O1\n\nM1 max two parallel\n";
+        let err = parse_constraints(text).unwrap_err();
+        assert_eq!(err.line, 3);
+        assert!(err.message.contains("invalid capacity"));
+        assert!(err.to_string().starts_with("line 3:"));
+    }
+
+    #[test]
+    fn test_negative_durations_rejected() {
+        let err = parse_constraints("sync O1, O2\n  setup A -> B -5m").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 16));
+        assert!(err.message.contains("must not be negative"));
+        assert!(err.to_string().starts_with("line 2:16:"));
+
+        let err = parse_constraint("O1 within -1h..8h").unwrap_err();
+        assert_eq!(err.column, 11);
+        assert!(err.message.contains("`-1h`"));
+        let err = parse_constraint("O1 within 0..-8h").unwrap_err();
+        assert_eq!(err.column, 14);
+        assert!(err.message.contains("must not be negative"));
+
+        assert!(parse_constraint("setup A -> B 0").is_ok());
+        assert_eq!(parse_constraint("  bogus").unwrap_err().column, 3);
+    }
+
+    #[test]
+    fn test_error_messages() {
+        assert!(parse_constraint("O5 before O3")
+            .unwrap_err()
+            .message
+            .contains("unknown keyword"));
+        assert!(parse_constraint("O5 after O3 10m")
+            .unwrap_err()
+            .message
+            .contains("+<duration>"));
+        assert!(parse_constraint("O1 within 8h..1h")
+            .unwrap_err()
+            .message
+            .contains("must be after"));
+        assert!(parse_constraint("M2 max 2 parallel now")
+            .unwrap_err()
+            .message
+            .contains("trailing"));
+        assert!(parse_constraint("sync O1 O2")
+            .unwrap_err()
+            .message
+            .contains("comma"));
+        assert!(parse_constraint("O5 after O3 +-10m")
+            .unwrap_err()
+            .message
+            .contains("unsigned"));
+        assert!(parse_constraint("O5 after O3 ++10m").is_err());
+    }
+
+    #[test]
+    fn test_single_line_edge_cases() {
+        for blank in ["", "   ", "# only a comment"] {
+            assert!(parse_constraint(blank)
+                .unwrap_err()
+                .message
+                .contains("empty"));
+        }
+        assert!(matches!(
+            parse_constraint("O5 after O3 +10m   # routing").unwrap(),
+            Constraint::Precedence {
+                min_delay_ms: 600_000,
+                ..
+            }
+        ));
+        assert!(parse_constraint("M2 max 2 parallel # shared fixture").is_ok());
+    }
+}
@@ -0,0 +1,251 @@
+//! Processing times derived from activity attributes.
+//!
+//! Many shops do not keep a processing time per operation but a rule:
+//! cutting takes 40 s per metre, hardened steel machines 30 % slower than
+//! mild steel. A [`DurationModel`] chains [`DurationModifier`]s that
+//! compute an activity's processing time from its (or its task's)
+//! [`attributes`](super::Activity::attributes), starting from the modeled
+//! [`process_ms`](super::ActivityDuration::process_ms).
+//!
+//! Schedulers read processing times from the tasks, so the model is
+//! applied once before solving with [`DurationModel::apply`]; every
+//! scheduler, the GA and the CP model then see the derived times.
+//!
+//! # Example
+//!
+//! ```
+//! use u_schedule::models::{Activity, AttributeFactor, DurationModel, PerUnitTime, Task};
+//!
+//! let mut tasks = vec![Task::new("J1").with_activity(
+//!     Activity::new("J1_CUT", "J1", 0)
+//!         .with_attribute("length_m", "12.5")
+//!         .with_attribute("material", "hardened"),
+//! )];
+//! let model = DurationModel::new()
+//!     .with_modifier(PerUnitTime::new("length_m", 40_000.0).with_base(60_000))
+//!     .with_modifier(AttributeFactor::new("material").with_factor("hardened", 1.3));
+//! model.apply(&mut tasks);
+//! // (60 s + 12.5 m × 40 s/m) × 1.3
+//! assert_eq!(tasks[0].activities[0].duration.process_ms, 728_000);
+//! ```
+
+use alloc::boxed::Box;
+use core::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+use super::{Activity, Task};
+
+/// A rule computing an activity's processing time.
+pub trait DurationModifier: Send + Sync + Debug {
+    /// Modifier name (e.g., "PerUnitTime").
+    fn name(&self) -> &'static str;
+
+    /// Processing time of `activity` of `task` (ms), given the time
+    /// computed so far (the modeled time for the first modifier).
+    fn process_ms(&self, task: &Task, activity: &Activity, current_ms: i64) -> i64;
+}
+
+/// Chain of [`DurationModifier`]s, applied in order.
+#[derive(Debug, Default)]
+pub struct DurationModel {
+    modifiers: Vec<Box<dyn DurationModifier>>,
+}
+
+impl DurationModel {
+    /// Creates a model without modifiers (times unchanged).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a modifier.
+    pub fn with_modifier(mut self, modifier: impl DurationModifier + 'static) -> Self {
+        self.modifiers.push(Box::new(modifier));
+        self
+    }
+
+    /// Number of modifiers.
+    pub fn len(&self) -> usize {
+        self.modifiers.len()
+    }
+
+    /// Whether the model has no modifiers.
+    pub fn is_empty(&self) -> bool {
+        self.modifiers.is_empty()
+    }
+
+    /// Derived processing time of `activity` of `task` (ms, at least 0).
+    pub fn process_ms(&self, task: &Task, activity: &Activity) -> i64 {
+        self.modifiers
+            .iter()
+            .fold(activity.duration.process_ms, |ms, m| {
+                m.process_ms(task, activity, ms)
+            })
+            .max(0)
+    }
+
+    /// Replaces the processing time of every activity of `tasks` by its
+    /// derived time.
+    pub fn apply(&self, tasks: &mut [Task]) {
+        if self.is_empty() {
+            return;
+        }
+        for task in tasks.iter_mut() {
+            let derived: Vec<i64> = task
+                .activities
+                .iter()
+                .map(|a| self.process_ms(task, a))
+                .collect();
+            for (activity, ms) in task.activities.iter_mut().zip(derived) {
+                activity.duration.process_ms = ms;
+            }
+        }
+    }
+}
+
+/// Processing time proportional to a numeric attribute: `base_ms +
+/// value × ms_per_unit` (e.g. length × cutting time per metre).
+///
+/// Activities without the attribute, or with a non-numeric value, keep
+/// their time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerUnitTime {
+    /// Attribute holding the quantity.
+    pub attribute: String,
+    /// Time per unit of the quantity (ms).
+    pub ms_per_unit: f64,
+    /// Fixed time independent of the quantity (ms).
+    #[serde(default)]
+    pub base_ms: i64,
+}
+
+impl PerUnitTime {
+    /// `ms_per_unit` per unit of `attribute`, without a fixed part.
+    pub fn new(attribute: impl Into<String>, ms_per_unit: f64) -> Self {
+        Self {
+            attribute: attribute.into(),
+            ms_per_unit,
+            base_ms: 0,
+        }
+    }
+
+    /// Sets the fixed time.
+    pub fn with_base(mut self, base_ms: i64) -> Self {
+        self.base_ms = base_ms;
+        self
+    }
+}
+
+impl DurationModifier for PerUnitTime {
+    fn name(&self) -> &'static str {
+        "PerUnitTime"
+    }
+
+    fn process_ms(&self, task: &Task, activity: &Activity, current_ms: i64) -> i64 {
+        match attribute(task, activity, &self.attribute).and_then(|v| v.trim().parse::<f64>().ok())
+        {
+            Some(units) => self.base_ms + round(units * self.ms_per_unit),
+            None => current_ms,
+        }
+    }
+}
+
+/// Scales the processing time by a factor chosen by an attribute's
+/// value (e.g. material hardness: "hardened" → 1.3).
+///
+/// Values without a factor keep the time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeFactor {
+    /// Attribute selecting the factor.
+    pub attribute: String,
+    /// Factor per attribute value.
+    #[serde(default)]
+    pub factors: HashMap<String, f64>,
+}
+
+impl AttributeFactor {
+    /// Factors selected by `attribute`, none yet.
+    pub fn new(attribute: impl Into<String>) -> Self {
+        Self {
+            attribute: attribute.into(),
+            factors: HashMap::new(),
+        }
+    }
+
+    /// Scales times of activities whose attribute is `value` by `factor`.
+    pub fn with_factor(mut self, value: impl Into<String>, factor: f64) -> Self {
+        self.factors.insert(value.into(), factor);
+        self
+    }
+}
+
+impl DurationModifier for AttributeFactor {
+    fn name(&self) -> &'static str {
+        "AttributeFactor"
+    }
+
+    fn process_ms(&self, task: &Task, activity: &Activity, current_ms: i64) -> i64 {
+        match attribute(task, activity, &self.attribute).and_then(|v| self.factors.get(v)) {
+            Some(factor) => round(current_ms as f64 * factor),
+            None => current_ms,
+        }
+    }
+}
+
+/// Value of `key` on the activity, else on its task.
+fn attribute<'a>(task: &'a Task, activity: &'a Activity, key: &str) -> Option<&'a str> {
+    activity
+        .attributes
+        .get(key)
+        .or_else(|| task.attributes.get(key))
+        .map(String::as_str)
+}
+
+/// Rounds half away from zero, like `f64::round` (std only).
+fn round(ms: f64) -> i64 {
+    (if ms >= 0.0 { ms + 0.5 } else { ms - 0.5 }) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modifiers_chain() {
+        let task = Task::new("J1").with_attribute("material", "hardened");
+        let cut = Activity::new("O1", "J1", 0)
+            .with_process_time(5000)
+            .with_attribute("length_m", "2");
+        let plain = Activity::new("O2", "J1", 1)
+            .with_process_time(5000)
+            .with_attribute("length_m", "n/a");
+        let model = DurationModel::new()
+            .with_modifier(PerUnitTime::new("length_m", 1500.0).with_base(1000))
+            .with_modifier(
+                AttributeFactor::new("material")
+                    .with_factor("hardened", 1.5)
+                    .with_factor("mild", 1.0),
+            );
+        assert_eq!(model.len(), 2);
+
+        // (1000 + 2 × 1500) × 1.5, the material coming from the task.
+        assert_eq!(model.process_ms(&task, &cut), 6000);
+        // Unparseable length: modeled time, still scaled.
+        assert_eq!(model.process_ms(&task, &plain), 7500);
+        // Unknown material: unchanged.
+        let soft = Task::new("J2").with_attribute("material", "soft");
+        assert_eq!(model.process_ms(&soft, &plain), 5000);
+
+        let mut tasks = vec![task.with_activity(cut).with_activity(plain)];
+        model.apply(&mut tasks);
+        let times: Vec<i64> = tasks[0]
+            .activities
+            .iter()
+            .map(|a| a.duration.process_ms)
+            .collect();
+        assert_eq!(times, [6000, 7500]);
+        DurationModel::new().apply(&mut tasks);
+        assert_eq!(tasks[0].activities[1].duration.process_ms, 7500);
+    }
+}
@@ -0,0 +1,314 @@
+//! Schedule revisions and their audit trail.
+//!
+//! Plans change: a machine breaks, an order is rushed, a planner moves an
+//! operation by hand. Regulated customers must be able to show who changed
+//! the plan, when and why. [`ScheduleHistory`] keeps every committed
+//! revision with its time, author (a person or a solver), triggering
+//! event and the activity-level diff against the previous revision.
+//!
+//! # Example
+//!
+//! ```
+//! use u_schedule::models::{Assignment, Schedule, ScheduleHistory};
+//!
+//! let mut plan = Schedule::new();
+//! plan.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+//! let mut history = ScheduleHistory::new();
+//! history.commit(plan.clone(), 100, "SimpleScheduler", "initial plan");
+//!
+//! plan.assignments[0].start_ms = 500;
+//! plan.assignments[0].end_ms = 1500;
+//! history.commit(plan, 200, "planner:kim", "M1 maintenance overran");
+//!
+//! let change = history.last_start_change("J1").unwrap();
+//! assert_eq!((change.before_ms, change.after_ms), (Some(0), Some(500)));
+//! assert_eq!(change.revision.trigger, "M1 maintenance overran");
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+use super::Schedule;
+
+/// Where and when an activity runs in one revision.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Placement {
+    /// Resources holding the activity, in assignment order.
+    pub resource_ids: Vec<String>,
+    /// Earliest assignment start (ms).
+    pub start_ms: i64,
+    /// Latest assignment end (ms).
+    pub end_ms: i64,
+}
+
+/// How an activity's placement changed between two revisions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityChange {
+    /// Activity ID.
+    pub activity_id: String,
+    /// Parent task ID.
+    pub task_id: String,
+    /// Placement before (`None` if the activity was added).
+    pub before: Option<Placement>,
+    /// Placement after (`None` if the activity was removed).
+    pub after: Option<Placement>,
+}
+
+impl ActivityChange {
+    /// Activities whose placement differs between `before` and `after`,
+    /// by activity ID. Resource warm-ups and cool-downs are ignored.
+    pub fn between(before: &Schedule, after: &Schedule) -> Vec<Self> {
+        let (old, new) = (placements(before), placements(after));
+        let mut changes: Vec<Self> =
+            old.iter()
+                .filter(|(id, (_, p))| new.get(*id).map(|(_, q)| q) != Some(p))
+                .map(|(id, (task_id, p))| Self {
+                    activity_id: id.to_string(),
+                    task_id: task_id.to_string(),
+                    before: Some(p.clone()),
+                    after: new.get(id).map(|(_, q)| q.clone()),
+                })
+                .chain(new.iter().filter(|(id, _)| !old.contains_key(*id)).map(
+                    |(id, (task_id, q))| Self {
+                        activity_id: id.to_string(),
+                        task_id: task_id.to_string(),
+                        before: None,
+                        after: Some(q.clone()),
+                    },
+                ))
+                .collect();
+        changes.sort_by(|a, b| a.activity_id.cmp(&b.activity_id));
+        changes
+    }
+
+    /// Whether the start time changed (or the activity was added or
+    /// removed).
+    pub fn start_changed(&self) -> bool {
+        self.before.as_ref().map(|p| p.start_ms) != self.after.as_ref().map(|p| p.start_ms)
+    }
+}
+
+/// Placement of each activity of `schedule`, with its task ID.
+fn placements(schedule: &Schedule) -> HashMap<&str, (&str, Placement)> {
+    let mut result: HashMap<&str, (&str, Placement)> = HashMap::new();
+    for a in schedule.assignments.iter().filter(|a| !a.is_conditioning()) {
+        let (_, p) = result.entry(a.activity_id.as_str()).or_insert_with(|| {
+            (
+                a.task_id.as_str(),
+                Placement {
+                    resource_ids: Vec::new(),
+                    start_ms: a.start_ms,
+                    end_ms: a.end_ms,
+                },
+            )
+        });
+        if !p.resource_ids.contains(&a.resource_id) {
+            p.resource_ids.push(a.resource_id.clone());
+        }
+        p.start_ms = p.start_ms.min(a.start_ms);
+        p.end_ms = p.end_ms.max(a.end_ms);
+    }
+    result
+}
+
+/// One committed version of a schedule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Revision {
+    /// Revision number, from 1.
+    pub number: usize,
+    /// When the revision was committed (ms).
+    pub created_ms: i64,
+    /// Person or solver that produced it.
+    pub author: String,
+    /// Event that triggered it (e.g. "M3 breakdown", "rush order 4711").
+    pub trigger: String,
+    /// The schedule.
+    pub schedule: Schedule,
+    /// Changes against the previous revision (every activity for the
+    /// first).
+    pub changes: Vec<ActivityChange>,
+}
+
+/// A task's start change in one revision.
+#[derive(Debug, Clone, Copy)]
+pub struct StartChange<'a> {
+    /// The revision that changed it.
+    pub revision: &'a Revision,
+    /// Task start before (`None` if unscheduled).
+    pub before_ms: Option<i64>,
+    /// Task start after (`None` if unscheduled).
+    pub after_ms: Option<i64>,
+}
+
+/// Successive revisions of a schedule, oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleHistory {
+    revisions: Vec<Revision>,
+}
+
+impl ScheduleHistory {
+    /// Creates an empty history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Commits `schedule` as the next revision and returns it.
+    pub fn commit(
+        &mut self,
+        schedule: Schedule,
+        created_ms: i64,
+        author: impl Into<String>,
+        trigger: impl Into<String>,
+    ) -> &Revision {
+        let changes = match self.revisions.last() {
+            Some(previous) => ActivityChange::between(&previous.schedule, &schedule),
+            None => ActivityChange::between(&Schedule::new(), &schedule),
+        };
+        self.revisions.push(Revision {
+            number: self.revisions.len() + 1,
+            created_ms,
+            author: author.into(),
+            trigger: trigger.into(),
+            schedule,
+            changes,
+        });
+        &self.revisions[self.revisions.len() - 1]
+    }
+
+    /// All revisions, oldest first.
+    pub fn revisions(&self) -> &[Revision] {
+        &self.revisions
+    }
+
+    /// Revision `number` (from 1).
+    pub fn revision(&self, number: usize) -> Option<&Revision> {
+        number.checked_sub(1).and_then(|i| self.revisions.get(i))
+    }
+
+    /// The current revision.
+    pub fn latest(&self) -> Option<&Revision> {
+        self.revisions.last()
+    }
+
+    /// Number of revisions.
+    pub fn len(&self) -> usize {
+        self.revisions.len()
+    }
+
+    /// Whether nothing was committed.
+    pub fn is_empty(&self) -> bool {
+        self.revisions.is_empty()
+    }
+
+    /// Changes of an activity, oldest first, with their revisions.
+    pub fn activity_changes(&self, activity_id: &str) -> Vec<(&Revision, &ActivityChange)> {
+        self.revisions
+            .iter()
+            .filter_map(|r| {
+                r.changes
+                    .iter()
+                    .find(|c| c.activity_id == activity_id)
+                    .map(|c| (r, c))
+            })
+            .collect()
+    }
+
+    /// Start changes of a task (its earliest assignment start), oldest
+    /// first. Scheduling a task for the first time counts as a change.
+    pub fn start_changes(&self, task_id: &str) -> Vec<StartChange<'_>> {
+        let mut before = None;
+        let mut result = Vec::new();
+        for revision in &self.revisions {
+            let after = task_start(&revision.schedule, task_id);
+            if after != before {
+                result.push(StartChange {
+                    revision,
+                    before_ms: before,
+                    after_ms: after,
+                });
+            }
+            before = after;
+        }
+        result
+    }
+
+    /// The last revision that changed a task's start, with the times
+    /// before and after: when and why it moved.
+    pub fn last_start_change(&self, task_id: &str) -> Option<StartChange<'_>> {
+        self.start_changes(task_id).pop()
+    }
+}
+
+fn task_start(schedule: &Schedule, task_id: &str) -> Option<i64> {
+    schedule
+        .assignments
+        .iter()
+        .filter(|a| a.task_id == task_id)
+        .map(|a| a.start_ms)
+        .min()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::models::Assignment;
+
+    #[test]
+    fn test_diff_and_queries() {
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("A1", "J1", "M1", 0, 1000));
+        plan.add_assignment(Assignment::new("B1", "J2", "M2", 0, 500));
+        let mut history = ScheduleHistory::new();
+        assert!(history.latest().is_none());
+        assert_eq!(
+            history
+                .commit(plan.clone(), 10, "solver", "initial")
+                .changes
+                .len(),
+            2
+        );
+
+        // B1 changes resource only; J1 is untouched.
+        plan.assignments[1].resource_id = "M3".into();
+        plan.add_assignment(Assignment::new("C1", "J3", "M1", 1000, 2000));
+        let revision = history.commit(plan.clone(), 20, "planner", "M2 down");
+        assert_eq!(revision.number, 2);
+        let ids: Vec<_> = revision
+            .changes
+            .iter()
+            .map(|c| c.activity_id.as_str())
+            .collect();
+        assert_eq!(ids, ["B1", "C1"]);
+        assert!(!revision.changes[0].start_changed());
+        assert!(revision.changes[1].before.is_none());
+
+        // A1 is removed, J2 moves later.
+        plan.assignments.remove(0);
+        plan.assignments[0].start_ms = 300;
+        history.commit(plan, 30, "solver", "rush order");
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.revision(3).unwrap().changes.len(), 2);
+        assert!(history.revision(0).is_none());
+
+        let moves = history.start_changes("J2");
+        assert_eq!(moves.len(), 2);
+        let last = history.last_start_change("J2").unwrap();
+        assert_eq!((last.before_ms, last.after_ms), (Some(0), Some(300)));
+        assert_eq!(
+            (last.revision.created_ms, last.revision.trigger.as_str()),
+            (30, "rush order")
+        );
+        let removed = history.last_start_change("J1").unwrap();
+        assert_eq!(removed.after_ms, None);
+        assert_eq!(history.activity_changes("B1").len(), 3);
+        assert!(history.last_start_change("J9").is_none());
+
+        let json = serde_json::to_string(&history).unwrap();
+        let parsed: ScheduleHistory = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(
+            parsed.latest().unwrap().changes,
+            history.latest().unwrap().changes
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! Learning curves and ramp-up of resources.
+//!
+//! A new line or a new operator does not run at standard rate from day
+//! one. A [`LearningCurve`] on a [`Resource`](super::Resource) stretches
+//! processing times while the resource ramps up, and shrinks them as it
+//! repeats a category of work, until it reaches the nominal
+//! [`process_ms`](super::ActivityDuration::process_ms) of the activity.
+//!
+//! # Model
+//!
+//! The processing time of the `n`-th unit of a category, started at `t`:
+//!
+//! ```text
+//! process(n, t) = nominal × max(1, first_unit_factor × n^log2(learning_rate)) / ramp(t)
+//! ramp(t)       = initial_efficiency → 1.0, linear over [ramp_start_ms, ramp_end_ms]
+//! ```
+//!
+//! Units are counted per resource and task category, starting after the
+//! [`experience`](LearningCurve::experience) the resource already has.
+//! The nominal time is the mature (standard) time; a resource never gets
+//! faster than that.
+//!
+//! # Reference
+//! Wright (1936), "Factors affecting the cost of airplanes", Journal of
+//! the Aeronautical Sciences 3(4)
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+
+/// Time- and repetition-dependent slowdown of a resource.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LearningCurve {
+    /// Start of the ramp-up (ms).
+    pub ramp_start_ms: i64,
+    /// End of the ramp-up, from which the resource runs at full rate (ms).
+    pub ramp_end_ms: i64,
+    /// Efficiency at the start of the ramp-up, in (0, 1] (1.0 = no ramp).
+    pub initial_efficiency: f64,
+    /// Time factor of the first unit of a category, ≥ 1 (1.0 = no
+    /// repetition learning).
+    pub first_unit_factor: f64,
+    /// Time factor per doubling of units, in (0, 1] (e.g. 0.85).
+    pub learning_rate: f64,
+    /// Units already produced per category before planning.
+    pub experience: HashMap<String, u32>,
+}
+
+impl Default for LearningCurve {
+    fn default() -> Self {
+        Self {
+            ramp_start_ms: 0,
+            ramp_end_ms: 0,
+            initial_efficiency: 1.0,
+            first_unit_factor: 1.0,
+            learning_rate: 1.0,
+            experience: HashMap::new(),
+        }
+    }
+}
+
+impl LearningCurve {
+    /// Creates a curve without slowdown.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ramps efficiency linearly from `initial_efficiency` at `start_ms`
+    /// to 1.0 at `end_ms`.
+    pub fn with_ramp(mut self, start_ms: i64, end_ms: i64, initial_efficiency: f64) -> Self {
+        self.ramp_start_ms = start_ms;
+        self.ramp_end_ms = end_ms;
+        self.initial_efficiency = initial_efficiency;
+        self
+    }
+
+    /// Makes the first unit of a category take `first_unit_factor` times
+    /// the nominal time, improving by `learning_rate` per doubling.
+    pub fn with_repetition(mut self, first_unit_factor: f64, learning_rate: f64) -> Self {
+        self.first_unit_factor = first_unit_factor;
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Sets the units of `category` produced before planning.
+    pub fn with_experience(mut self, category: impl Into<String>, units: u32) -> Self {
+        self.experience.insert(category.into(), units);
+        self
+    }
+
+    /// Units of `category` produced before planning.
+    pub fn experience_of(&self, category: &str) -> u32 {
+        self.experience.get(category).copied().unwrap_or(0)
+    }
+
+    /// Efficiency at `time_ms` from the ramp-up, in (0, 1].
+    pub fn ramp_efficiency(&self, time_ms: i64) -> f64 {
+        if time_ms >= self.ramp_end_ms || self.initial_efficiency >= 1.0 {
+            return 1.0;
+        }
+        if time_ms <= self.ramp_start_ms {
+            return self.initial_efficiency;
+        }
+        let progress =
+            (time_ms - self.ramp_start_ms) as f64 / (self.ramp_end_ms - self.ramp_start_ms) as f64;
+        self.initial_efficiency + (1.0 - self.initial_efficiency) * progress
+    }
+
+    /// Time factor of the `unit`-th unit (1-based) of a category, ≥ 1.
+    #[cfg(feature = "std")]
+    pub fn repetition_factor(&self, unit: u32) -> f64 {
+        let exponent = self.learning_rate.log2();
+        (self.first_unit_factor * f64::from(unit.max(1)).powf(exponent)).max(1.0)
+    }
+
+    /// Processing time of the `unit`-th unit started at `time_ms`
+    /// (nominal time for a non-positive nominal time).
+    #[cfg(feature = "std")]
+    pub fn process_ms(&self, nominal_ms: i64, time_ms: i64, unit: u32) -> i64 {
+        if nominal_ms <= 0 {
+            return nominal_ms;
+        }
+        let factor = self.repetition_factor(unit) / self.ramp_efficiency(time_ms);
+        (nominal_ms as f64 * factor).round() as i64
+    }
+
+    /// Problems with the parameters, for input validation.
+    pub fn check(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !(self.initial_efficiency > 0.0 && self.initial_efficiency <= 1.0) {
+            problems.push(format!(
+                "initial efficiency {} is outside (0, 1]",
+                self.initial_efficiency
+            ));
+        }
+        if self.ramp_end_ms < self.ramp_start_ms {
+            problems.push(format!(
+                "ramp ends at {} before it starts at {}",
+                self.ramp_end_ms, self.ramp_start_ms
+            ));
+        }
+        if !(self.first_unit_factor.is_finite() && self.first_unit_factor >= 1.0) {
+            problems.push(format!(
+                "first unit factor {} is below 1",
+                self.first_unit_factor
+            ));
+        }
+        if !(self.learning_rate > 0.0 && self.learning_rate <= 1.0) {
+            problems.push(format!(
+                "learning rate {} is outside (0, 1]",
+                self.learning_rate
+            ));
+        }
+        problems
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramp_and_repetition() {
+        let curve = LearningCurve::new()
+            .with_ramp(0, 1000, 0.5)
+            .with_repetition(2.0, 0.8)
+            .with_experience("A", 3);
+        assert!((curve.ramp_efficiency(-5) - 0.5).abs() < 1e-12);
+        assert!((curve.ramp_efficiency(500) - 0.75).abs() < 1e-12);
+        assert!((curve.ramp_efficiency(2000) - 1.0).abs() < 1e-12);
+
+        // 2.0, 1.6, 1.28, 1.024, then the nominal time.
+        assert!((curve.repetition_factor(1) - 2.0).abs() < 1e-12);
+        assert!((curve.repetition_factor(4) - 1.28).abs() < 1e-12);
+        assert!((curve.repetition_factor(16) - 1.0).abs() < 1e-12);
+
+        assert_eq!(curve.process_ms(1000, 0, 2), 3200);
+        assert_eq!(curve.process_ms(1000, 1000, 2), 1600);
+        assert_eq!(curve.experience_of("A"), 3);
+        assert!(curve.check().is_empty());
+
+        let bad = LearningCurve::new()
+            .with_ramp(10, 0, 0.0)
+            .with_repetition(0.5, 1.5);
+        assert_eq!(bad.check().len(), 4);
+        assert_eq!(LearningCurve::new().process_ms(1000, 0, 1), 1000);
+    }
+}
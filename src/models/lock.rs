@@ -0,0 +1,361 @@
+//! Locks (pins) on parts of a schedule.
+//!
+//! Planners pin human decisions — a rush order placed by hand, an
+//! operation that must stay on its machine, a machine reserved for a
+//! trial run — and let the optimizer fill in the rest. All solvers
+//! ([`SimpleScheduler`], the GA, and the CP builder) honor a [`LockSet`].
+//!
+//! # Granularity
+//!
+//! | Lock | Effect |
+//! |------|--------|
+//! | Task | The task's assignments are kept verbatim; their time is blocked |
+//! | Activity | The activity's assignments are kept verbatim; their time is blocked |
+//! | Activity → resource | The activity may only run on that resource |
+//! | Resource window | Nothing may be scheduled on the resource in the window |
+//!
+//! # Replanning
+//!
+//! To replan mid-shift, freeze the part of the current plan that has
+//! started ([`LockSet::freeze`]) and schedule again: frozen activities
+//! keep their assignments, their time is unavailable to other work, and
+//! the remaining activities of their tasks follow them.
+//!
+//! ```
+//! # #[cfg(feature = "std")]
+//! # fn main() {
+//! use u_schedule::models::{
+//!     Activity, Assignment, LockSet, Resource, ResourceRequirement, Schedule, Task,
+//! };
+//! use u_schedule::scheduler::{ScheduleRequest, SimpleScheduler};
+//!
+//! let op = |id: &str, seq: i32| {
+//!     Activity::new(id, "J1", seq)
+//!         .with_process_time(1000)
+//!         .with_requirement(ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]))
+//! };
+//! let tasks = vec![Task::new("J1").with_activity(op("J1_O1", 0)).with_activity(op("J1_O2", 1))];
+//! let mut current = Schedule::new();
+//! current.add_assignment(Assignment::new("J1_O1", "J1", "M1", 500, 1500));
+//! current.add_assignment(Assignment::new("J1_O2", "J1", "M1", 1500, 2500));
+//!
+//! let locks = LockSet::freeze(&current, |a| a.start_ms < 1000);
+//! let request = ScheduleRequest::new(tasks, vec![Resource::primary("M1")]).with_locks(locks);
+//! let plan = SimpleScheduler::new().schedule_request(&request);
+//! assert_eq!(plan.assignment_for_activity("J1_O1").unwrap().start_ms, 500);
+//! assert_eq!(plan.assignment_for_activity("J1_O2").unwrap().start_ms, 1500);
+//! # }
+//! # #[cfg(not(feature = "std"))]
+//! # fn main() {}
+//! ```
+//!
+//! [`SimpleScheduler`]: crate::scheduler::SimpleScheduler
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+use super::{merge_windows, Assignment, Schedule, Task, TimeWindow};
+
+/// A pinned decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Lock {
+    /// The task is fixed to these assignments.
+    Task {
+        task_id: String,
+        assignments: Vec<Assignment>,
+    },
+
+    /// The activity is fixed to these assignments (frozen).
+    Activity {
+        activity_id: String,
+        assignments: Vec<Assignment>,
+    },
+
+    /// The activity must run on `resource_id` (at any time).
+    ActivityResource {
+        activity_id: String,
+        resource_id: String,
+    },
+
+    /// The resource is reserved during `window`.
+    ResourceWindow {
+        resource_id: String,
+        window: TimeWindow,
+    },
+}
+
+impl Lock {
+    /// Locks a task to its assignments in `schedule`.
+    pub fn task(schedule: &Schedule, task_id: impl Into<String>) -> Self {
+        let task_id = task_id.into();
+        let assignments = schedule
+            .assignments_for_task(&task_id)
+            .into_iter()
+            .cloned()
+            .collect();
+        Self::Task {
+            task_id,
+            assignments,
+        }
+    }
+
+    /// Freezes an activity to its assignments in `schedule`.
+    pub fn activity(schedule: &Schedule, activity_id: impl Into<String>) -> Self {
+        let activity_id = activity_id.into();
+        let assignments = schedule
+            .assignments
+            .iter()
+            .filter(|a| a.activity_id == activity_id)
+            .cloned()
+            .collect();
+        Self::Activity {
+            activity_id,
+            assignments,
+        }
+    }
+
+    /// Locks an activity to a resource.
+    pub fn activity_resource(
+        activity_id: impl Into<String>,
+        resource_id: impl Into<String>,
+    ) -> Self {
+        Self::ActivityResource {
+            activity_id: activity_id.into(),
+            resource_id: resource_id.into(),
+        }
+    }
+
+    /// Reserves a resource during `[start_ms, end_ms)`.
+    pub fn resource_window(resource_id: impl Into<String>, start_ms: i64, end_ms: i64) -> Self {
+        Self::ResourceWindow {
+            resource_id: resource_id.into(),
+            window: TimeWindow::new(start_ms, end_ms),
+        }
+    }
+}
+
+/// The locks of a planning run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LockSet {
+    /// Locks, in no particular order.
+    pub locks: Vec<Lock>,
+}
+
+impl LockSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a lock.
+    pub fn with_lock(mut self, lock: Lock) -> Self {
+        self.locks.push(lock);
+        self
+    }
+
+    /// Freezes every activity of `schedule` with an assignment matching
+    /// `frozen` (e.g. `|a| a.start_ms < now`), with all its assignments.
+    pub fn freeze(schedule: &Schedule, frozen: impl Fn(&Assignment) -> bool) -> Self {
+        let mut activity_ids: Vec<&str> = schedule
+            .assignments
+            .iter()
+            .filter(|a| frozen(a))
+            .map(|a| a.activity_id.as_str())
+            .collect();
+        activity_ids.sort_unstable();
+        activity_ids.dedup();
+        Self {
+            locks: activity_ids
+                .into_iter()
+                .map(|id| Lock::activity(schedule, id))
+                .collect(),
+        }
+    }
+
+    /// Whether the set has no locks.
+    pub fn is_empty(&self) -> bool {
+        self.locks.is_empty()
+    }
+
+    /// IDs of locked tasks.
+    pub fn locked_tasks(&self) -> HashSet<&str> {
+        self.locks
+            .iter()
+            .filter_map(|lock| match lock {
+                Lock::Task { task_id, .. } => Some(task_id.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// IDs of frozen activities.
+    pub fn frozen_activities(&self) -> HashSet<&str> {
+        self.locks
+            .iter()
+            .filter_map(|lock| match lock {
+                Lock::Activity { activity_id, .. } => Some(activity_id.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Assignments of locked tasks and frozen activities.
+    pub fn locked_assignments(&self) -> impl Iterator<Item = &Assignment> {
+        self.locks.iter().flat_map(|lock| match lock {
+            Lock::Task { assignments, .. } | Lock::Activity { assignments, .. } => {
+                assignments.as_slice()
+            }
+            _ => &[],
+        })
+    }
+
+    /// Assignments of a frozen activity (empty if not frozen).
+    pub fn frozen_assignments<'a>(
+        &'a self,
+        activity_id: &'a str,
+    ) -> impl Iterator<Item = &'a Assignment> {
+        self.locks.iter().flat_map(move |lock| match lock {
+            Lock::Activity {
+                activity_id: id,
+                assignments,
+            } if id == activity_id => assignments.as_slice(),
+            _ => &[],
+        })
+    }
+
+    /// Resource an activity is locked to, if any (the last lock wins).
+    pub fn resource_for(&self, activity_id: &str) -> Option<&str> {
+        self.locks.iter().rev().find_map(|lock| match lock {
+            Lock::ActivityResource {
+                activity_id: id,
+                resource_id,
+            } if id == activity_id => Some(resource_id.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Time unavailable to unlocked work, per resource: reserved windows
+    /// plus the assignments of locked tasks and frozen activities, merged
+    /// and sorted.
+    pub fn blocked_windows(&self) -> HashMap<String, Vec<TimeWindow>> {
+        let mut blocked: HashMap<String, Vec<TimeWindow>> = HashMap::new();
+        for lock in &self.locks {
+            if let Lock::ResourceWindow {
+                resource_id,
+                window,
+            } = lock
+            {
+                blocked
+                    .entry(resource_id.clone())
+                    .or_default()
+                    .push(window.clone());
+            }
+        }
+        for a in self.locked_assignments() {
+            blocked
+                .entry(a.resource_id.clone())
+                .or_default()
+                .push(TimeWindow::new(a.start_ms, a.end_ms));
+        }
+        blocked
+            .into_iter()
+            .map(|(id, windows)| (id, merge_windows(&windows)))
+            .collect()
+    }
+
+    /// Restricts candidate resources of locked activities to their lock.
+    ///
+    /// Requirements listing the locked resource keep only it; the others
+    /// are left unchanged.
+    pub fn restrict_candidates(&self, tasks: &mut [Task]) {
+        for act in tasks.iter_mut().flat_map(|t| t.activities.iter_mut()) {
+            let Some(resource_id) = self.resource_for(&act.id) else {
+                continue;
+            };
+            for req in &mut act.resource_requirements {
+                if req.candidates.iter().any(|c| c == resource_id) {
+                    req.candidates = vec![resource_id.to_string()];
+                }
+            }
+        }
+    }
+}
+
+/// Earliest start `≥ from_ms` of a `duration_ms` interval that avoids all
+/// `blocked` windows (sorted and disjoint, as from
+/// [`LockSet::blocked_windows`]).
+pub fn earliest_fit(blocked: &[TimeWindow], from_ms: i64, duration_ms: i64) -> i64 {
+    let mut start = from_ms;
+    for w in blocked {
+        if w.end_ms <= start {
+            continue;
+        }
+        if start + duration_ms <= w.start_ms && (duration_ms > 0 || start < w.start_ms) {
+            break;
+        }
+        start = start.max(w.end_ms);
+    }
+    start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_lock_queries() {
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("O1", "J1", "M1", 100, 200));
+        schedule.add_assignment(Assignment::new("O2", "J2", "M1", 200, 300));
+        let locks = LockSet::new()
+            .with_lock(Lock::task(&schedule, "J1"))
+            .with_lock(Lock::activity_resource("O3", "M2"))
+            .with_lock(Lock::resource_window("M1", 150, 400));
+
+        assert_eq!(locks.locked_tasks(), HashSet::from(["J1"]));
+        assert_eq!(locks.locked_assignments().count(), 1);
+        assert_eq!(locks.resource_for("O3"), Some("M2"));
+        assert_eq!(locks.resource_for("O1"), None);
+        assert_eq!(
+            locks.blocked_windows()["M1"],
+            vec![TimeWindow::new(100, 400)]
+        );
+
+        let json = serde_json::to_string(&locks).unwrap();
+        let parsed: LockSet = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.locks.len(), 3);
+    }
+
+    #[test]
+    fn test_freeze() {
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("O1", "J1", "M1", 0, 400));
+        schedule.add_assignment(Assignment::new("O1", "J1", "M1", 600, 900));
+        schedule.add_assignment(Assignment::new("O2", "J1", "M2", 900, 1500));
+        schedule.add_assignment(Assignment::new("O3", "J2", "M1", 1000, 1200));
+
+        // A split activity freezes with all its segments.
+        let locks = LockSet::freeze(&schedule, |a| a.start_ms < 500);
+        assert_eq!(locks.frozen_activities(), HashSet::from(["O1"]));
+        assert!(locks.locked_tasks().is_empty());
+        assert_eq!(locks.frozen_assignments("O1").count(), 2);
+        assert_eq!(locks.frozen_assignments("O2").count(), 0);
+        assert_eq!(
+            locks.blocked_windows()["M1"],
+            vec![TimeWindow::new(0, 400), TimeWindow::new(600, 900)]
+        );
+        assert!(LockSet::freeze(&schedule, |_| false).is_empty());
+    }
+
+    #[test]
+    fn test_earliest_fit() {
+        let blocked = vec![TimeWindow::new(100, 200), TimeWindow::new(250, 300)];
+        assert_eq!(earliest_fit(&blocked, 0, 100), 0);
+        assert_eq!(earliest_fit(&blocked, 0, 101), 300);
+        assert_eq!(earliest_fit(&blocked, 150, 50), 200);
+        assert_eq!(earliest_fit(&blocked, 150, 60), 300);
+        assert_eq!(earliest_fit(&blocked, 120, 0), 200);
+        assert_eq!(earliest_fit(&[], 42, 1000), 42);
+    }
+}
@@ -14,20 +14,74 @@
 //! | Schedule | Production Plan | OR Schedule | Route Plan |
 
 mod activity;
+mod affinity;
+mod annotation;
+mod batch;
 mod calendar;
+mod calendar_set;
+mod compact;
 mod constraint;
+mod constraint_dsl;
+mod duration_model;
+mod history;
+mod learning;
+mod lock;
 mod resource;
 mod schedule;
+mod sync;
 mod task;
+mod throughput;
 pub mod time_constraints;
+mod tooling;
+pub mod units;
+mod weight_profile;
+mod work_rules;
+
+/// `alloc` types and collections used by the models, so that they build
+/// without `std` (maps and sets fall back to B-trees).
+mod prelude {
+    #[cfg(not(feature = "std"))]
+    pub(crate) use alloc::collections::{BTreeMap as HashMap, BTreeSet as HashSet};
+    pub(crate) use alloc::format;
+    pub(crate) use alloc::string::{String, ToString};
+    pub(crate) use alloc::vec;
+    pub(crate) use alloc::vec::Vec;
+    #[cfg(feature = "std")]
+    pub(crate) use std::collections::{HashMap, HashSet};
+}
 
 pub use activity::{Activity, ActivityDuration, ResourceRequirement};
-pub use calendar::{Calendar, TimeWindow};
-pub use constraint::{Constraint, TransitionMatrix, TransitionMatrixCollection};
+pub use affinity::{AffinityRule, AffinityTable};
+pub use annotation::{Annotation, AnnotationTarget};
+pub use batch::BatchRules;
+pub use calendar::{
+    complement_windows, intersect_windows, merge_windows, subtract_windows, union_windows,
+    Calendar, CalendarIndex, TimeWindow,
+};
+pub use calendar_set::{CalendarLayer, CalendarOp, CalendarSet};
+pub use compact::{AssignmentRef, CompactAssignment, CompactSchedule};
+pub use constraint::{Constraint, PrecedenceMode, TransitionMatrix, TransitionMatrixCollection};
+pub use constraint_dsl::{
+    parse_constraint, parse_constraints, parse_duration_ms, ConstraintParseError,
+};
+pub use duration_model::{AttributeFactor, DurationModel, DurationModifier, PerUnitTime};
+pub use history::{ActivityChange, Placement, Revision, ScheduleHistory, StartChange};
+pub use learning::LearningCurve;
+pub use lock::{earliest_fit, Lock, LockSet};
 pub use resource::{Resource, ResourceType, Skill};
-pub use schedule::{Assignment, Schedule, Violation, ViolationType};
-pub use task::Task;
+#[cfg(feature = "std")]
+pub(crate) use schedule::Fnv1a;
+pub use schedule::{
+    Assignment, AssignmentConfidence, CarryOver, Schedule, SegmentedAssignment, Violation,
+    ViolationType,
+};
+pub use sync::SyncGroups;
+pub use task::{PriorityClass, Task};
+pub use throughput::{CategoryThroughput, ThroughputTargets};
 pub use time_constraints::{
     ActivityTimeConstraint, ConstraintType, ConstraintViolation, ConstraintViolationType,
     DurationDistribution, PertEstimate, TimeWindowViolation, ViolationSeverity,
 };
+pub use tooling::{ToolRequirement, ToolingRules};
+pub use weight_profile::WeightProfile;
+pub use work_rules::WorkRules;
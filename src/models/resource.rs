@@ -8,9 +8,9 @@
 //! Pinedo (2016), "Scheduling: Theory, Algorithms, and Systems", Ch. 1.2
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
-use super::Calendar;
+use super::prelude::*;
+use super::{BatchRules, Calendar, LearningCurve, TimeWindow, WorkRules};
 
 /// A resource that can be assigned to activities.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +31,26 @@ pub struct Resource {
     pub skills: Vec<Skill>,
     /// Economic cost per hour (optional, for cost optimization).
     pub cost_per_hour: Option<f64>,
+    /// Working-time rules (enforced for `ResourceType::Human`).
+    #[serde(default)]
+    pub work_rules: Option<WorkRules>,
+    /// Ramp-up and repetition learning (scales processing times).
+    #[serde(default)]
+    pub learning: Option<LearningCurve>,
+    /// Warm-up before the first assignment of each calendar window (ms).
+    #[serde(default)]
+    pub warm_up_ms: i64,
+    /// Cool-down after the last assignment of each calendar window (ms).
+    #[serde(default)]
+    pub cool_down_ms: i64,
+    /// Maximum category changeovers per calendar window (e.g. what the
+    /// setup crew of a shift can handle). `None` = unlimited.
+    #[serde(default)]
+    pub max_setups_per_window: Option<usize>,
+    /// Batch processing: compatible activities share a cycle (see
+    /// [`BatchRules`]). `None` = one activity at a time.
+    #[serde(default)]
+    pub batching: Option<BatchRules>,
     /// Domain-specific metadata.
     pub attributes: HashMap<String, String>,
 }
@@ -53,13 +73,39 @@ pub enum ResourceType {
     Custom(String),
 }
 
+impl ResourceType {
+    /// Type name: the variant name (e.g., `"Primary"`) or the custom name.
+    ///
+    /// [`ResourceRequirement`](super::ResourceRequirement)s without
+    /// candidates match resources by this name (ASCII case-insensitive).
+    pub fn name(&self) -> &str {
+        match self {
+            ResourceType::Primary => "Primary",
+            ResourceType::Secondary => "Secondary",
+            ResourceType::Human => "Human",
+            ResourceType::Consumable => "Consumable",
+            ResourceType::Custom(name) => name,
+        }
+    }
+}
+
 /// A skill with proficiency level.
+///
+/// Qualifications that expire (certifications, licences) carry a validity
+/// window; a resource holds the skill only for work that lies entirely
+/// inside it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Skill {
     /// Skill name (e.g., "welding", "milling", "anesthesia").
     pub name: String,
     /// Proficiency level (0.0 to 1.0, where 1.0 = expert).
     pub level: f64,
+    /// Start of validity (ms); `None` = always held before expiry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from_ms: Option<i64>,
+    /// Expiry (ms, exclusive); `None` = never lapses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until_ms: Option<i64>,
 }
 
 impl Resource {
@@ -74,6 +120,12 @@ impl Resource {
             calendar: None,
             skills: Vec::new(),
             cost_per_hour: None,
+            work_rules: None,
+            learning: None,
+            warm_up_ms: 0,
+            cool_down_ms: 0,
+            max_setups_per_window: None,
+            batching: None,
             attributes: HashMap::new(),
         }
     }
@@ -111,6 +163,71 @@ impl Resource {
         self
     }
 
+    /// Sets working-time rules.
+    pub fn with_work_rules(mut self, rules: WorkRules) -> Self {
+        self.work_rules = Some(rules);
+        self
+    }
+
+    /// Sets the learning curve.
+    pub fn with_learning(mut self, learning: LearningCurve) -> Self {
+        self.learning = Some(learning);
+        self
+    }
+
+    /// Sets the warm-up before first use in each calendar window (see
+    /// [`Schedule::insert_conditioning`](super::Schedule::insert_conditioning)).
+    pub fn with_warm_up(mut self, ms: i64) -> Self {
+        self.warm_up_ms = ms;
+        self
+    }
+
+    /// Sets the cool-down after last use in each calendar window.
+    pub fn with_cool_down(mut self, ms: i64) -> Self {
+        self.cool_down_ms = ms;
+        self
+    }
+
+    /// Limits category changeovers to `max` per calendar window (see
+    /// [`Schedule::changeovers_per_window`](super::Schedule::changeovers_per_window)).
+    pub fn with_max_setups_per_window(mut self, max: usize) -> Self {
+        self.max_setups_per_window = Some(max);
+        self
+    }
+
+    /// Processes compatible activities in batches.
+    pub fn with_batching(mut self, rules: BatchRules) -> Self {
+        self.batching = Some(rules);
+        self
+    }
+
+    /// The calendar window (shift) that work starting at `time_ms` belongs
+    /// to: the available interval containing it, else the last one opening
+    /// before it, else the first one. Without a calendar (or without any
+    /// availability) the whole time line is one window.
+    pub fn calendar_window(&self, time_ms: i64) -> TimeWindow {
+        let windows = self
+            .calendar
+            .as_ref()
+            .map(Calendar::available_intervals)
+            .unwrap_or_default();
+        windows
+            .iter()
+            .rev()
+            .find(|w| w.start_ms <= time_ms)
+            .or(windows.first())
+            .cloned()
+            .unwrap_or(TimeWindow::new(i64::MIN, i64::MAX))
+    }
+
+    /// Working-time rules that apply during scheduling (human resources only).
+    pub fn enforced_work_rules(&self) -> Option<&WorkRules> {
+        match self.resource_type {
+            ResourceType::Human => self.work_rules.as_ref(),
+            _ => None,
+        }
+    }
+
     /// Sets the availability calendar.
     pub fn with_calendar(mut self, calendar: Calendar) -> Self {
         self.calendar = Some(calendar);
@@ -119,10 +236,13 @@ impl Resource {
 
     /// Adds a skill.
     pub fn with_skill(mut self, name: impl Into<String>, level: f64) -> Self {
-        self.skills.push(Skill {
-            name: name.into(),
-            level: level.clamp(0.0, 1.0),
-        });
+        self.skills.push(Skill::new(name, level));
+        self
+    }
+
+    /// Adds a skill with its validity window (e.g., a certification).
+    pub fn with_certification(mut self, skill: Skill) -> Self {
+        self.skills.push(skill);
         self
     }
 
@@ -143,6 +263,13 @@ impl Resource {
         self.skills.iter().any(|s| s.name == name)
     }
 
+    /// Whether this resource holds a skill throughout `[start_ms, end_ms)`.
+    pub fn has_skill_during(&self, name: &str, start_ms: i64, end_ms: i64) -> bool {
+        self.skills
+            .iter()
+            .any(|s| s.name == name && s.covers(start_ms, end_ms))
+    }
+
     /// Returns the proficiency level for a skill (0.0 if not found).
     pub fn skill_level(&self, name: &str) -> f64 {
         self.skills
@@ -170,8 +297,34 @@ impl Skill {
         Self {
             name: name.into(),
             level: level.clamp(0.0, 1.0),
+            valid_from_ms: None,
+            valid_until_ms: None,
         }
     }
+
+    /// Sets the start of validity.
+    pub fn with_valid_from(mut self, valid_from_ms: i64) -> Self {
+        self.valid_from_ms = Some(valid_from_ms);
+        self
+    }
+
+    /// Sets the expiry.
+    pub fn with_valid_until(mut self, valid_until_ms: i64) -> Self {
+        self.valid_until_ms = Some(valid_until_ms);
+        self
+    }
+
+    /// Whether the skill is valid at `time_ms`.
+    pub fn is_valid_at(&self, time_ms: i64) -> bool {
+        self.valid_from_ms.is_none_or(|from| from <= time_ms)
+            && self.valid_until_ms.is_none_or(|until| time_ms < until)
+    }
+
+    /// Whether the skill is valid throughout `[start_ms, end_ms)`.
+    pub fn covers(&self, start_ms: i64, end_ms: i64) -> bool {
+        self.valid_from_ms.is_none_or(|from| from <= start_ms)
+            && self.valid_until_ms.is_none_or(|until| end_ms <= until)
+    }
 }
 
 #[cfg(test)]
@@ -229,4 +382,20 @@ mod tests {
         assert!((r.skill_level("over") - 1.0).abs() < 1e-10);
         assert!((r.skill_level("under") - 0.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_certification_window() {
+        let r = Resource::human("W1").with_certification(
+            Skill::new("welding", 0.8)
+                .with_valid_from(1000)
+                .with_valid_until(5000),
+        );
+        assert!(r.has_skill("welding"));
+        assert!(r.has_skill_during("welding", 1000, 5000));
+        assert!(!r.has_skill_during("welding", 4000, 6000));
+        assert!(!r.has_skill_during("welding", 0, 2000));
+        assert!(r.skills[0].is_valid_at(4999));
+        assert!(!r.skills[0].is_valid_at(5000));
+        assert!(Skill::new("milling", 1.0).covers(i64::MIN, i64::MAX));
+    }
 }
@@ -4,21 +4,75 @@
 //! time slots. It may include constraint violations for infeasible
 //! or suboptimal solutions.
 //!
+//! # Rolling-Wave Planning
+//!
+//! [`Schedule::truncate`] freezes a plan up to a horizon and returns the
+//! work beyond it as [`CarryOver`] entries;
+//! [`Schedule::carry_over_tasks`] turns them into the tasks of the next
+//! planning cycle.
+//!
+//! # Split Activities
+//!
+//! An activity interrupted by preemption, a breakdown or a shift end is
+//! stored as one [`Assignment`] per worked stretch, all with the same
+//! activity and resource. [`Schedule::segmented_assignments`] groups them
+//! into [`SegmentedAssignment`]s (one per activity and resource, with its
+//! [`TimeWindow`] segments); [`Schedule::add_segmented`] stores one.
+//!
+//! # Warm-Up and Cool-Down
+//!
+//! Resources with [`warm_up_ms`](super::Resource::warm_up_ms) or
+//! [`cool_down_ms`](super::Resource::cool_down_ms) get a setup-only
+//! assignment ([`Assignment::WARM_UP`], [`Assignment::COOL_DOWN`]) right
+//! before the first and right after the last assignment of each calendar
+//! window, added by [`Schedule::insert_conditioning`]. They occupy the
+//! resource, so they count in utilization and capacity checks.
+//!
 //! # Reference
 //! Pinedo (2016), "Scheduling: Theory, Algorithms, and Systems", Ch. 3
 
+use core::time::Duration;
+
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+
+use super::prelude::*;
+use super::{units, ActivityDuration, Annotation, Resource, Task, TimeWindow};
 
 /// A complete schedule (solution to a scheduling problem).
 ///
-/// Contains activity-resource-time assignments and any constraint violations.
+/// Contains activity-resource-time assignments, any constraint violations,
+/// and planner notes.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Schedule {
     /// Activity assignments (activity → resource × time).
     pub assignments: Vec<Assignment>,
     /// Constraint violations detected in this schedule.
     pub violations: Vec<Violation>,
+    /// Planner notes (see [`Annotation`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
+    /// Per-assignment confidence, if estimated (see
+    /// [`AssignmentConfidence`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub confidence: Vec<AssignmentConfidence>,
+}
+
+/// How likely an assignment is to run as planned.
+///
+/// Filled in by `scheduler::ConfidenceEstimator` from duration
+/// distributions; a low probability marks a fragile part of the plan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssignmentConfidence {
+    /// Activity ID.
+    pub activity_id: String,
+    /// Resource ID.
+    pub resource_id: String,
+    /// Planned start, identifying the segment of a split activity (ms).
+    pub start_ms: i64,
+    /// Probability of starting no later than planned.
+    pub start_probability: f64,
+    /// Probability of finishing no later than planned.
+    pub finish_probability: f64,
 }
 
 /// An activity-resource-time assignment.
@@ -39,10 +93,163 @@ pub struct Assignment {
     pub end_ms: i64,
     /// Setup time portion (ms). Included in [start_ms, start_ms + setup_ms).
     pub setup_ms: i64,
+    /// Teardown time portion (ms). Included in [end_ms - teardown_ms, end_ms).
+    #[serde(default)]
+    pub teardown_ms: i64,
+}
+
+/// Work of one assignment left beyond a truncation horizon.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CarryOver {
+    /// Activity ID.
+    pub activity_id: String,
+    /// Parent task ID.
+    pub task_id: String,
+    /// Resource of the truncated assignment.
+    pub resource_id: String,
+    /// Setup time not yet done at the horizon (ms).
+    pub remaining_setup_ms: i64,
+    /// Processing time not yet done at the horizon (ms).
+    pub remaining_process_ms: i64,
+    /// Whether the assignment started before the horizon.
+    pub in_progress: bool,
+}
+
+impl CarryOver {
+    /// Total remaining time (setup + processing) in ms.
+    #[inline]
+    pub fn remaining_ms(&self) -> i64 {
+        self.remaining_setup_ms + self.remaining_process_ms
+    }
+}
+
+/// An activity's work on one resource, possibly split into segments.
+///
+/// Segments are sorted, non-empty and non-touching (contiguous
+/// stretches are merged); the setup, if any, opens the first one and the
+/// teardown closes the last one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SegmentedAssignment {
+    /// Assigned activity ID.
+    pub activity_id: String,
+    /// Parent task ID.
+    pub task_id: String,
+    /// Assigned resource ID.
+    pub resource_id: String,
+    /// Worked intervals.
+    pub segments: Vec<TimeWindow>,
+    /// Setup time at the start of the first segment (ms).
+    pub setup_ms: i64,
+    /// Teardown time at the end of the last segment (ms).
+    #[serde(default)]
+    pub teardown_ms: i64,
+}
+
+impl SegmentedAssignment {
+    /// Creates an assignment worked in `segments` (normalized: sorted,
+    /// empty ones dropped, contiguous and overlapping ones merged).
+    pub fn new(
+        activity_id: impl Into<String>,
+        task_id: impl Into<String>,
+        resource_id: impl Into<String>,
+        segments: Vec<TimeWindow>,
+    ) -> Self {
+        let mut segments: Vec<TimeWindow> = segments
+            .into_iter()
+            .filter(|w| w.end_ms > w.start_ms)
+            .collect();
+        segments.sort_by_key(|w| w.start_ms);
+        let mut merged: Vec<TimeWindow> = Vec::with_capacity(segments.len());
+        for w in segments {
+            match merged.last_mut() {
+                Some(last) if w.start_ms <= last.end_ms => last.end_ms = last.end_ms.max(w.end_ms),
+                _ => merged.push(w),
+            }
+        }
+        Self {
+            activity_id: activity_id.into(),
+            task_id: task_id.into(),
+            resource_id: resource_id.into(),
+            segments: merged,
+            setup_ms: 0,
+            teardown_ms: 0,
+        }
+    }
+
+    /// Sets the setup time.
+    pub fn with_setup(mut self, setup_ms: i64) -> Self {
+        self.setup_ms = setup_ms;
+        self
+    }
+
+    /// Sets the teardown time.
+    pub fn with_teardown(mut self, teardown_ms: i64) -> Self {
+        self.teardown_ms = teardown_ms;
+        self
+    }
+
+    /// Start of the first segment.
+    pub fn first_start_ms(&self) -> Option<i64> {
+        self.segments.first().map(|w| w.start_ms)
+    }
+
+    /// End of the last segment.
+    pub fn last_end_ms(&self) -> Option<i64> {
+        self.segments.last().map(|w| w.end_ms)
+    }
+
+    /// Worked time over all segments, setup included (ms).
+    pub fn worked_ms(&self) -> i64 {
+        self.segments.iter().map(TimeWindow::duration_ms).sum()
+    }
+
+    /// Worked time excluding setup and teardown (ms).
+    pub fn process_ms(&self) -> i64 {
+        self.worked_ms() - self.setup_ms - self.teardown_ms
+    }
+
+    /// Time between the first start and the last end not worked (ms).
+    pub fn interruption_ms(&self) -> i64 {
+        match (self.first_start_ms(), self.last_end_ms()) {
+            (Some(start), Some(end)) => end - start - self.worked_ms(),
+            _ => 0,
+        }
+    }
+
+    /// Whether the work is interrupted at least once.
+    pub fn is_split(&self) -> bool {
+        self.segments.len() > 1
+    }
+
+    /// One [`Assignment`] per segment; the first carries the setup and
+    /// the last the teardown.
+    pub fn to_assignments(&self) -> Vec<Assignment> {
+        let last = self.segments.len().saturating_sub(1);
+        self.segments
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                Assignment::new(
+                    &self.activity_id,
+                    &self.task_id,
+                    &self.resource_id,
+                    w.start_ms,
+                    w.end_ms,
+                )
+                .with_setup(if i == 0 { self.setup_ms } else { 0 })
+                .with_teardown(if i == last { self.teardown_ms } else { 0 })
+            })
+            .collect()
+    }
 }
 
 /// A constraint violation.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Its contribution to [`Schedule::penalty_total`] is
+/// `severity × penalty`: severity ranks the kind of violation, penalty
+/// scales it by magnitude (1 unless the producer sets one, e.g. hours
+/// late for deadline misses reported by schedule audits).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Violation {
     /// Type of violation.
     pub violation_type: ViolationType,
@@ -52,6 +259,13 @@ pub struct Violation {
     pub message: String,
     /// Severity (0-100, higher = worse).
     pub severity: i32,
+    /// Magnitude multiplier of the severity (default: 1.0).
+    #[serde(default = "default_penalty")]
+    pub penalty: f64,
+}
+
+fn default_penalty() -> f64 {
+    1.0
 }
 
 /// Classification of constraint violations.
@@ -67,11 +281,26 @@ pub enum ViolationType {
     ResourceUnavailable,
     /// Resource lacks a required skill.
     SkillMismatch,
+    /// Working-time rule (rest, stretch, daily hours) broken.
+    WorkRule,
+    /// Task completed later after its release than its maximum flow time.
+    FlowTimeExceeded,
+    /// Synchronized activities started at different times.
+    Unsynchronized,
+    /// Activity held fewer resources than a requirement's quantity.
+    InsufficientUnits,
+    /// More category changeovers in a calendar window than the resource allows.
+    TooManySetups,
     /// Domain-specific violation.
     Custom(String),
 }
 
 impl Assignment {
+    /// Activity ID of warm-up assignments.
+    pub const WARM_UP: &'static str = "warm-up";
+    /// Activity ID of cool-down assignments.
+    pub const COOL_DOWN: &'static str = "cool-down";
+
     /// Creates a new assignment.
     pub fn new(
         activity_id: impl Into<String>,
@@ -87,6 +316,7 @@ impl Assignment {
             start_ms,
             end_ms,
             setup_ms: 0,
+            teardown_ms: 0,
         }
     }
 
@@ -96,16 +326,50 @@ impl Assignment {
         self
     }
 
+    /// Sets the teardown time.
+    pub fn with_teardown(mut self, teardown_ms: i64) -> Self {
+        self.teardown_ms = teardown_ms;
+        self
+    }
+
     /// Total duration (end - start) in ms.
     #[inline]
     pub fn duration_ms(&self) -> i64 {
         self.end_ms - self.start_ms
     }
 
-    /// Processing duration excluding setup (ms).
+    /// Processing duration excluding setup and teardown (ms): the
+    /// value-adding part of the assignment.
     #[inline]
     pub fn process_ms(&self) -> i64 {
-        self.duration_ms() - self.setup_ms
+        self.duration_ms() - self.setup_ms - self.teardown_ms
+    }
+
+    /// Total duration (end - start) as a [`Duration`].
+    pub fn duration(&self) -> Duration {
+        units::from_ms(self.duration_ms())
+    }
+
+    /// Setup time as a [`Duration`].
+    pub fn setup_duration(&self) -> Duration {
+        units::from_ms(self.setup_ms)
+    }
+
+    /// Teardown time as a [`Duration`].
+    pub fn teardown_duration(&self) -> Duration {
+        units::from_ms(self.teardown_ms)
+    }
+
+    /// Processing duration excluding setup and teardown as a [`Duration`].
+    pub fn process_duration(&self) -> Duration {
+        units::from_ms(self.process_ms())
+    }
+
+    /// Whether this is a resource warm-up or cool-down rather than work
+    /// on an activity.
+    pub fn is_conditioning(&self) -> bool {
+        self.task_id.is_empty()
+            && (self.activity_id == Self::WARM_UP || self.activity_id == Self::COOL_DOWN)
     }
 }
 
@@ -117,6 +381,18 @@ impl Violation {
             entity_id: task_id.into(),
             message: message.into(),
             severity: 80,
+            penalty: 1.0,
+        }
+    }
+
+    /// Creates a flow-time violation.
+    pub fn flow_time_exceeded(task_id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            violation_type: ViolationType::FlowTimeExceeded,
+            entity_id: task_id.into(),
+            message: message.into(),
+            severity: 85,
+            penalty: 1.0,
         }
     }
 
@@ -127,6 +403,18 @@ impl Violation {
             entity_id: resource_id.into(),
             message: message.into(),
             severity: 90,
+            penalty: 1.0,
+        }
+    }
+
+    /// Creates a working-time rule violation.
+    pub fn work_rule(resource_id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            violation_type: ViolationType::WorkRule,
+            entity_id: resource_id.into(),
+            message: message.into(),
+            severity: 70,
+            penalty: 1.0,
         }
     }
 
@@ -140,8 +428,80 @@ impl Violation {
             entity_id: activity_id.into(),
             message: message.into(),
             severity: 95,
+            penalty: 1.0,
+        }
+    }
+
+    /// Creates a skill mismatch violation.
+    pub fn skill_mismatch(resource_id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            violation_type: ViolationType::SkillMismatch,
+            entity_id: resource_id.into(),
+            message: message.into(),
+            severity: 90,
+            penalty: 1.0,
+        }
+    }
+
+    /// Creates a synchronization violation.
+    pub fn unsynchronized(activity_id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            violation_type: ViolationType::Unsynchronized,
+            entity_id: activity_id.into(),
+            message: message.into(),
+            severity: 95,
+            penalty: 1.0,
+        }
+    }
+
+    /// Creates a violation for an activity short of required units.
+    pub fn insufficient_units(activity_id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            violation_type: ViolationType::InsufficientUnits,
+            entity_id: activity_id.into(),
+            message: message.into(),
+            severity: 90,
+            penalty: 1.0,
+        }
+    }
+
+    /// Creates a violation for a resource changing over too often in a
+    /// calendar window.
+    pub fn too_many_setups(resource_id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            violation_type: ViolationType::TooManySetups,
+            entity_id: resource_id.into(),
+            message: message.into(),
+            severity: 90,
+            penalty: 1.0,
         }
     }
+
+    /// Creates a resource-unavailable violation.
+    pub fn resource_unavailable(
+        resource_id: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            violation_type: ViolationType::ResourceUnavailable,
+            entity_id: resource_id.into(),
+            message: message.into(),
+            severity: 90,
+            penalty: 1.0,
+        }
+    }
+
+    /// Sets the magnitude multiplier.
+    pub fn with_penalty(mut self, penalty: f64) -> Self {
+        self.penalty = penalty;
+        self
+    }
+
+    /// Contribution to [`Schedule::penalty_total`]: `severity × penalty`.
+    #[inline]
+    pub fn weighted_penalty(&self) -> f64 {
+        self.severity as f64 * self.penalty
+    }
 }
 
 impl Schedule {
@@ -165,6 +525,20 @@ impl Schedule {
         self.violations.is_empty()
     }
 
+    /// Sum of [`Violation::weighted_penalty`] over all violations (0 for
+    /// a valid schedule).
+    ///
+    /// Lets schedules be compared on soft-constraint quality, not just
+    /// makespan; the greedy, GA and CP solvers fill
+    /// [`violations`](Self::violations) with the schedule audit
+    /// `validation::check_schedule`.
+    pub fn penalty_total(&self) -> f64 {
+        self.violations
+            .iter()
+            .map(Violation::weighted_penalty)
+            .sum()
+    }
+
     /// Makespan: latest end time across all assignments (ms).
     pub fn makespan_ms(&self) -> i64 {
         self.assignments.iter().map(|a| a.end_ms).max().unwrap_or(0)
@@ -236,10 +610,348 @@ impl Schedule {
             .max()
     }
 
+    /// Assignments grouped per activity and resource, in order of first
+    /// appearance.
+    pub fn segmented_assignments(&self) -> Vec<SegmentedAssignment> {
+        let mut index: HashMap<(&str, &str), usize> = HashMap::new();
+        let mut groups: Vec<(&Assignment, Vec<TimeWindow>, i64, i64)> = Vec::new();
+        for a in &self.assignments {
+            let key = (a.activity_id.as_str(), a.resource_id.as_str());
+            let i = *index.entry(key).or_insert_with(|| {
+                groups.push((a, Vec::new(), i64::MAX, 0));
+                groups.len() - 1
+            });
+            let group = &mut groups[i];
+            group.1.push(TimeWindow::new(a.start_ms, a.end_ms));
+            group.3 += a.teardown_ms;
+            // The setup belongs to the earliest segment.
+            if a.start_ms < group.2 {
+                group.2 = a.start_ms;
+                group.0 = a;
+            }
+        }
+        groups
+            .into_iter()
+            .map(|(first, segments, _, teardown)| {
+                SegmentedAssignment::new(
+                    &first.activity_id,
+                    &first.task_id,
+                    &first.resource_id,
+                    segments,
+                )
+                .with_setup(first.setup_ms)
+                .with_teardown(teardown)
+            })
+            .collect()
+    }
+
+    /// Adds a (possibly split) assignment as one assignment per segment.
+    pub fn add_segmented(&mut self, assignment: &SegmentedAssignment) {
+        self.assignments.extend(assignment.to_assignments());
+    }
+
+    /// Confidence of an assignment, if estimated.
+    pub fn confidence_of(&self, assignment: &Assignment) -> Option<&AssignmentConfidence> {
+        self.confidence.iter().find(|c| {
+            c.activity_id == assignment.activity_id
+                && c.resource_id == assignment.resource_id
+                && c.start_ms == assignment.start_ms
+        })
+    }
+
     /// Number of assignments.
     pub fn assignment_count(&self) -> usize {
         self.assignments.len()
     }
+
+    /// Cuts the schedule at `horizon_ms` and returns the work beyond it.
+    ///
+    /// Assignments ending at or before the horizon are kept. Assignments
+    /// straddling it are shortened to end at the horizon (setup is done
+    /// first and teardown last), and the rest is carried over. Assignments starting at or
+    /// after the horizon are removed and carried over whole. Violations
+    /// are left unchanged.
+    pub fn truncate(&mut self, horizon_ms: i64) -> Vec<CarryOver> {
+        let mut carry_over = Vec::new();
+        self.assignments.retain_mut(|a| {
+            if a.end_ms <= horizon_ms {
+                return true;
+            }
+            let done_ms = (horizon_ms - a.start_ms).max(0);
+            let setup_done = done_ms.min(a.setup_ms);
+            let process_done = (done_ms - setup_done).min(a.process_ms());
+            carry_over.push(CarryOver {
+                activity_id: a.activity_id.clone(),
+                task_id: a.task_id.clone(),
+                resource_id: a.resource_id.clone(),
+                remaining_setup_ms: a.setup_ms - setup_done,
+                remaining_process_ms: a.process_ms() - process_done,
+                in_progress: done_ms > 0,
+            });
+            if done_ms == 0 {
+                return false;
+            }
+            a.end_ms = horizon_ms;
+            a.setup_ms = setup_done;
+            a.teardown_ms = done_ms - setup_done - process_done;
+            true
+        });
+        carry_over
+    }
+
+    /// Builds the tasks of the next planning cycle from a truncated
+    /// schedule and its carry-over.
+    ///
+    /// Starting from `tasks` (the tasks planned before truncation):
+    /// - Activities completed in this schedule are dropped, along with
+    ///   predecessor references to them and tasks left without activities.
+    /// - Carried-over activities keep only their remaining setup and
+    ///   processing time (split pieces on one resource add up; parallel
+    ///   resources take the longest). Teardown is kept.
+    /// - In-progress activities are pinned to the resources they run on
+    ///   (for requirements that list candidates).
+    /// - Activities that were never scheduled are kept unchanged.
+    pub fn carry_over_tasks(&self, tasks: &[Task], carry_over: &[CarryOver]) -> Vec<Task> {
+        // (activity, resource) → (setup, process), summed over split pieces.
+        let mut per_resource: HashMap<(&str, &str), (i64, i64)> = HashMap::new();
+        let mut pinned: HashMap<&str, Vec<&str>> = HashMap::new();
+        for c in carry_over {
+            let entry = per_resource
+                .entry((c.activity_id.as_str(), c.resource_id.as_str()))
+                .or_default();
+            entry.0 += c.remaining_setup_ms;
+            entry.1 += c.remaining_process_ms;
+            if c.in_progress {
+                pinned
+                    .entry(c.activity_id.as_str())
+                    .or_default()
+                    .push(c.resource_id.as_str());
+            }
+        }
+        let mut remaining: HashMap<&str, (i64, i64)> = HashMap::new();
+        for ((activity_id, _), (setup, process)) in per_resource {
+            let entry = remaining.entry(activity_id).or_default();
+            if setup + process > entry.0 + entry.1 {
+                *entry = (setup, process);
+            }
+        }
+        let completed: HashSet<&str> = self
+            .assignments
+            .iter()
+            .map(|a| a.activity_id.as_str())
+            .filter(|id| !remaining.contains_key(id))
+            .collect();
+
+        tasks
+            .iter()
+            .filter_map(|task| {
+                let mut task = task.clone();
+                task.activities
+                    .retain(|act| !completed.contains(act.id.as_str()));
+                if task.activities.is_empty() {
+                    return None;
+                }
+                for act in &mut task.activities {
+                    act.predecessors.retain(|p| !completed.contains(p.as_str()));
+                    if let Some(&(setup, process)) = remaining.get(act.id.as_str()) {
+                        act.duration =
+                            ActivityDuration::new(setup, process, act.duration.teardown_ms);
+                    }
+                    if let Some(resources) = pinned.get(act.id.as_str()) {
+                        for req in &mut act.resource_requirements {
+                            if req
+                                .candidates
+                                .iter()
+                                .any(|c| resources.contains(&c.as_str()))
+                            {
+                                req.candidates.retain(|c| resources.contains(&c.as_str()));
+                            }
+                        }
+                    }
+                }
+                Some(task)
+            })
+            .collect()
+    }
+
+    /// Adds the warm-up and cool-down of `resources` around their work.
+    ///
+    /// Assignments on a resource are grouped by the calendar window their
+    /// start falls in (one group without a calendar). Each group gets a
+    /// [`Assignment::WARM_UP`] of the resource's
+    /// [`warm_up_ms`](Resource::warm_up_ms) ending at its first start, and
+    /// a [`Assignment::COOL_DOWN`] of its
+    /// [`cool_down_ms`](Resource::cool_down_ms) starting at its last end;
+    /// both are setup-only assignments without a task. Work is not moved:
+    /// a warm-up may precede the window opening (pre-shift warm-up), and
+    /// one that collides with earlier work shows up as a capacity
+    /// conflict. Previous conditioning assignments of these resources are
+    /// replaced, so the call is idempotent.
+    pub fn insert_conditioning(&mut self, resources: &[Resource]) {
+        for resource in resources
+            .iter()
+            .filter(|r| r.warm_up_ms > 0 || r.cool_down_ms > 0)
+        {
+            self.assignments
+                .retain(|a| !(a.resource_id == resource.id && a.is_conditioning()));
+            let windows = resource
+                .calendar
+                .as_ref()
+                .map(|c| c.available_intervals())
+                .unwrap_or_default();
+            // (window index, first start, last end)
+            let mut groups: Vec<(Option<usize>, i64, i64)> = Vec::new();
+            for a in self
+                .assignments
+                .iter()
+                .filter(|a| a.resource_id == resource.id && a.end_ms > a.start_ms)
+            {
+                let window = windows.iter().position(|w| w.contains(a.start_ms));
+                match groups.iter_mut().find(|g| g.0 == window) {
+                    Some(group) => {
+                        group.1 = group.1.min(a.start_ms);
+                        group.2 = group.2.max(a.end_ms);
+                    }
+                    None => groups.push((window, a.start_ms, a.end_ms)),
+                }
+            }
+            groups.sort_unstable_by_key(|g| g.1);
+            for (_, first, last) in groups {
+                let (warm, cool) = (resource.warm_up_ms, resource.cool_down_ms);
+                if warm > 0 {
+                    self.assignments.push(
+                        Assignment::new(Assignment::WARM_UP, "", &resource.id, first - warm, first)
+                            .with_setup(warm),
+                    );
+                }
+                if cool > 0 {
+                    self.assignments.push(
+                        Assignment::new(Assignment::COOL_DOWN, "", &resource.id, last, last + cool)
+                            .with_setup(cool),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Category changeovers on `resource` per calendar window, for each
+    /// window with work on it, in time order.
+    ///
+    /// A changeover is work whose task category differs from that of the
+    /// previous work on the resource; it counts in the
+    /// [`calendar_window`](Resource::calendar_window) of its start.
+    /// Setup-only assignments (setup crews, warm-ups) are not work.
+    pub fn changeovers_per_window(
+        &self,
+        resource: &Resource,
+        tasks: &[Task],
+    ) -> Vec<(TimeWindow, usize)> {
+        let category: HashMap<&str, &str> = tasks
+            .iter()
+            .map(|t| (t.id.as_str(), t.category.as_str()))
+            .collect();
+        let mut work: Vec<(i64, &str)> = self
+            .assignments
+            .iter()
+            .filter(|a| a.resource_id == resource.id && a.process_ms() > 0)
+            .filter_map(|a| Some((a.start_ms, *category.get(a.task_id.as_str())?)))
+            .collect();
+        work.sort_unstable();
+
+        let mut counts: Vec<(TimeWindow, usize)> = Vec::new();
+        let mut previous: Option<&str> = None;
+        for (start, cat) in work {
+            let window = resource.calendar_window(start);
+            if counts.last().is_none_or(|(w, _)| *w != window) {
+                counts.push((window, 0));
+            }
+            if previous.is_some_and(|p| p != cat) {
+                if let Some(last) = counts.last_mut() {
+                    last.1 += 1;
+                }
+            }
+            previous = Some(cat);
+        }
+        counts
+    }
+
+    /// Stable 64-bit fingerprint of the assignments.
+    ///
+    /// Assignments are hashed in sorted order (by activity, resource, start,
+    /// then every other hashed field, teardown included), so the
+    /// fingerprint does not depend on insertion order. Violations,
+    /// annotations and confidence are not included. The hash (FNV-1a) is
+    /// fixed across runs, platforms and crate versions, making it suitable
+    /// for caches and regression tests.
+    pub fn fingerprint(&self) -> u64 {
+        let mut sorted: Vec<&Assignment> = self.assignments.iter().collect();
+        sorted.sort_by(|a, b| {
+            (
+                &a.activity_id,
+                &a.resource_id,
+                a.start_ms,
+                &a.task_id,
+                a.end_ms,
+                a.setup_ms,
+                a.teardown_ms,
+            )
+                .cmp(&(
+                    &b.activity_id,
+                    &b.resource_id,
+                    b.start_ms,
+                    &b.task_id,
+                    b.end_ms,
+                    b.setup_ms,
+                    b.teardown_ms,
+                ))
+        });
+
+        let mut hasher = Fnv1a::new();
+        hasher.write_u64(sorted.len() as u64);
+        for a in sorted {
+            hasher.write_str(&a.activity_id);
+            hasher.write_str(&a.task_id);
+            hasher.write_str(&a.resource_id);
+            hasher.write_u64(a.start_ms as u64);
+            hasher.write_u64(a.end_ms as u64);
+            hasher.write_u64(a.setup_ms as u64);
+            hasher.write_u64(a.teardown_ms as u64);
+        }
+        hasher.finish()
+    }
+}
+
+/// 64-bit FNV-1a hasher (deterministic, unlike `DefaultHasher`).
+pub(crate) struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    pub(crate) fn new() -> Self {
+        Self(Self::OFFSET)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    pub(crate) fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// Length-prefixed so that ("ab", "c") and ("a", "bc") differ.
+    pub(crate) fn write_str(&mut self, s: &str) {
+        self.write_u64(s.len() as u64);
+        self.write(s.as_bytes());
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
 }
 
 #[cfg(test)]
@@ -349,5 +1061,233 @@ mod tests {
 
         let v3 = Violation::precedence_violation("O2", "Started before O1");
         assert_eq!(v3.violation_type, ViolationType::PrecedenceViolation);
+
+        let v4 = Violation::resource_unavailable("M1", "Outside shift");
+        assert_eq!(v4.violation_type, ViolationType::ResourceUnavailable);
+    }
+
+    #[test]
+    fn test_segmented_assignments() {
+        let mut s = Schedule::new();
+        s.add_assignment(Assignment::new("O1", "J1", "M1", 5000, 6000));
+        s.add_assignment(Assignment::new("O2", "J1", "M2", 0, 500));
+        s.add_assignment(Assignment::new("O1", "J1", "M1", 1000, 2000).with_setup(200));
+        s.add_assignment(Assignment::new("O1", "J1", "M1", 2000, 3000));
+
+        let segmented = s.segmented_assignments();
+        assert_eq!(segmented.len(), 2);
+        let o1 = &segmented[0];
+        assert_eq!(
+            o1.segments,
+            vec![TimeWindow::new(1000, 3000), TimeWindow::new(5000, 6000)]
+        );
+        assert_eq!(o1.setup_ms, 200);
+        assert_eq!(
+            (o1.first_start_ms(), o1.last_end_ms()),
+            (Some(1000), Some(6000))
+        );
+        assert_eq!(o1.worked_ms(), 3000);
+        assert_eq!(o1.process_ms(), 2800);
+        assert_eq!(o1.interruption_ms(), 2000);
+        assert!(o1.is_split());
+        assert!(!segmented[1].is_split());
+
+        let mut back = Schedule::new();
+        back.add_segmented(o1);
+        assert_eq!(back.assignment_count(), 2);
+        assert_eq!(back.assignments[0].setup_ms, 200);
+        assert_eq!(back.assignments[1].setup_ms, 0);
+        assert_eq!(back.task_completion_time("J1"), Some(6000));
+
+        let empty = SegmentedAssignment::new("O9", "J9", "M1", vec![TimeWindow::new(10, 10)]);
+        assert_eq!(empty.first_start_ms(), None);
+        assert_eq!(empty.interruption_ms(), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_penalty_total() {
+        let mut s = sample_schedule();
+        assert_eq!(s.penalty_total(), 0.0);
+
+        s.add_violation(Violation::deadline_miss("J1", "Late").with_penalty(2.5));
+        s.add_violation(Violation::work_rule("W1", "No rest"));
+        assert_eq!(s.penalty_total(), 80.0 * 2.5 + 70.0);
+
+        // Violations serialized before the penalty field existed count once.
+        let json =
+            r#"{"violation_type":"DeadlineMiss","entity_id":"J1","message":"","severity":80}"#;
+        let v: Violation = serde_json::from_str(json).unwrap();
+        assert_eq!(v.weighted_penalty(), 80.0);
+    }
+
+    #[test]
+    fn test_fingerprint_order_independent() {
+        let s = sample_schedule();
+        let mut reversed = Schedule::new();
+        for a in s.assignments.iter().rev() {
+            reversed.add_assignment(a.clone());
+        }
+        assert_eq!(s.fingerprint(), reversed.fingerprint());
+        assert_eq!(s.fingerprint(), sample_schedule().fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_detects_changes() {
+        let base = sample_schedule().fingerprint();
+
+        let mut moved = sample_schedule();
+        moved.assignments[2].start_ms += 1;
+        assert_ne!(base, moved.fingerprint());
+
+        let mut rerouted = sample_schedule();
+        rerouted.assignments[1].resource_id = "M3".into();
+        assert_ne!(base, rerouted.fingerprint());
+
+        let mut setup = sample_schedule();
+        setup.assignments[2].setup_ms = 100;
+        assert_ne!(base, setup.fingerprint());
+
+        let mut teardown = sample_schedule();
+        teardown.assignments[2].teardown_ms = 100;
+        assert_ne!(base, teardown.fingerprint());
+
+        assert_ne!(base, Schedule::new().fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_orders_ties_by_teardown() {
+        // Identical but for teardown: either insertion order hashes alike.
+        let short = Assignment::new("O1", "J1", "M1", 0, 1000).with_teardown(100);
+        let long = Assignment::new("O1", "J1", "M1", 0, 1000).with_teardown(300);
+        let mut a = Schedule::new();
+        a.add_assignment(short.clone());
+        a.add_assignment(long.clone());
+        let mut b = Schedule::new();
+        b.add_assignment(long);
+        b.add_assignment(short);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_violations() {
+        let mut s = sample_schedule();
+        s.add_violation(Violation::deadline_miss("J1", "late"));
+        assert_eq!(s.fingerprint(), sample_schedule().fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        // Pinned value: changing the hash breaks persisted fingerprints.
+        let mut s = Schedule::new();
+        s.add_assignment(Assignment::new("O1", "J1", "M1", 0, 1000));
+        assert_eq!(s.fingerprint(), 0x4d52_43cb_ebee_5890);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut s = sample_schedule();
+        let carry = s.truncate(3000);
+
+        // O1 (setup 500) is cut; O2 straddles; O3 starts after the horizon.
+        assert_eq!(s.assignment_count(), 2);
+        let o1 = s.assignment_for_activity("O1").unwrap();
+        assert_eq!((o1.end_ms, o1.setup_ms), (3000, 500));
+        assert_eq!(s.makespan_ms(), 3000);
+
+        assert_eq!(carry.len(), 3);
+        let o1 = carry.iter().find(|c| c.activity_id == "O1").unwrap();
+        assert_eq!((o1.remaining_setup_ms, o1.remaining_process_ms), (0, 2000));
+        assert!(o1.in_progress);
+        let o3 = carry.iter().find(|c| c.activity_id == "O3").unwrap();
+        assert_eq!(o3.remaining_ms(), 3000);
+        assert!(!o3.in_progress);
+
+        // Cutting inside the setup leaves the rest of the setup.
+        let mut s = sample_schedule();
+        let carry = s.truncate(200);
+        let o1 = carry.iter().find(|c| c.activity_id == "O1").unwrap();
+        assert_eq!(
+            (o1.remaining_setup_ms, o1.remaining_process_ms),
+            (300, 4500)
+        );
+
+        let mut s = sample_schedule();
+        assert!(s.truncate(10_000).is_empty());
+        assert_eq!(s.assignment_count(), 3);
+    }
+
+    #[test]
+    fn test_carry_over_tasks() {
+        use crate::models::{Activity, ResourceRequirement};
+
+        let machines =
+            || ResourceRequirement::new("Machine").with_candidates(vec!["M1".into(), "M2".into()]);
+        let tasks = vec![
+            Task::new("J1")
+                .with_activity(
+                    Activity::new("O1", "J1", 0)
+                        .with_duration(ActivityDuration::new(0, 1000, 100))
+                        .with_requirement(machines()),
+                )
+                .with_activity(
+                    Activity::new("O2", "J1", 1)
+                        .with_process_time(2000)
+                        .with_predecessor("O1")
+                        .with_requirement(machines()),
+                ),
+            Task::new("J2").with_activity(
+                Activity::new("O3", "J2", 0)
+                    .with_process_time(1000)
+                    .with_requirement(machines()),
+            ),
+            Task::new("J3").with_activity(Activity::new("O4", "J3", 0).with_process_time(500)),
+        ];
+        let mut s = Schedule::new();
+        s.add_assignment(Assignment::new("O1", "J1", "M1", 0, 1000));
+        s.add_assignment(Assignment::new("O2", "J1", "M2", 1000, 3000));
+        s.add_assignment(Assignment::new("O3", "J2", "M1", 1000, 2000));
+        let carry = s.truncate(1500);
+        let next = s.carry_over_tasks(&tasks, &carry);
+
+        // J1 keeps O2 only, pinned to M2 with 1500 ms left.
+        assert_eq!(next.len(), 3);
+        assert_eq!(next[0].activities.len(), 1);
+        let o2 = &next[0].activities[0];
+        assert_eq!(o2.id, "O2");
+        assert!(o2.predecessors.is_empty());
+        assert_eq!(o2.duration.process_ms, 1500);
+        assert_eq!(o2.resource_requirements[0].candidates, vec!["M2"]);
+        // O3 has 500 ms left on M1.
+        assert_eq!(next[1].activities[0].duration.process_ms, 500);
+        // The never-scheduled J3 is unchanged.
+        assert_eq!(next[2].activities[0].duration.process_ms, 500);
+    }
+
+    #[test]
+    fn test_insert_conditioning() {
+        let mut s = sample_schedule();
+        let resources = [
+            Resource::primary("M1")
+                .with_warm_up(300)
+                .with_cool_down(200),
+            Resource::primary("M2"),
+        ];
+        s.insert_conditioning(&resources);
+        s.insert_conditioning(&resources);
+        let extra: Vec<_> = s
+            .assignments
+            .iter()
+            .filter(|a| a.is_conditioning())
+            .map(|a| (a.activity_id.as_str(), a.start_ms, a.end_ms, a.process_ms()))
+            .collect();
+        assert_eq!(
+            extra,
+            vec![
+                (Assignment::WARM_UP, -300, 0, 0),
+                (Assignment::COOL_DOWN, 8000, 8200, 0)
+            ]
+        );
+        assert_eq!(s.assignments_for_resource("M1").len(), 4);
     }
 }
@@ -0,0 +1,192 @@
+//! Synchronized activities.
+//!
+//! Some operations must run at the same moment on different resources:
+//! a test rig and its operator console, a crane and the truck it loads.
+//! They are activities of different tasks, linked by
+//! [`Activity::synchronized_with`](super::Activity::synchronized_with)
+//! (or a [`Constraint::Synchronize`]). [`SyncGroups`] collects the links
+//! into groups whose members must all start together.
+//!
+//! Schedulers place activities one at a time, so a group is aligned by
+//! repair: every member gets a start floor at the group's latest start
+//! and the plan is rebuilt ([`raise_floors`](SyncGroups::raise_floors))
+//! until all members start together.
+//!
+//! # Example
+//!
+//! ```
+//! use u_schedule::models::{Activity, Assignment, Schedule, SyncGroups, Task};
+//!
+//! let tasks = vec![
+//!     Task::new("TEST").with_activity(Activity::new("RIG", "TEST", 0).with_process_time(600)),
+//!     Task::new("OPS")
+//!         .with_activity(Activity::new("CONSOLE", "OPS", 0).with_process_time(600).with_synchronized("RIG")),
+//! ];
+//! let groups = SyncGroups::from_tasks(&tasks);
+//! assert_eq!(groups.groups(), [vec!["CONSOLE".to_string(), "RIG".to_string()]]);
+//!
+//! let mut plan = Schedule::new();
+//! plan.add_assignment(Assignment::new("RIG", "TEST", "R1", 0, 600));
+//! plan.add_assignment(Assignment::new("CONSOLE", "OPS", "C1", 200, 800));
+//! assert_eq!(groups.misaligned(&plan), vec![(&groups.groups()[0][..], 200)]);
+//! ```
+
+use super::prelude::*;
+use super::{Constraint, Schedule, Task};
+
+/// Groups of activities that must start at the same time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncGroups {
+    groups: Vec<Vec<String>>,
+}
+
+impl SyncGroups {
+    /// Rebuilds a repair makes before it gives up; the remaining
+    /// misalignment is reported by the schedule audit.
+    pub const MAX_REPAIR_ROUNDS: usize = 32;
+
+    /// Groups the [`synchronized_with`](super::Activity::synchronized_with)
+    /// links of `tasks` (transitively).
+    pub fn from_tasks(tasks: &[Task]) -> Self {
+        let links = tasks.iter().flat_map(|t| &t.activities).flat_map(|a| {
+            a.synchronized_with
+                .iter()
+                .map(move |other| vec![a.id.clone(), other.clone()])
+        });
+        Self::from_links(links)
+    }
+
+    /// Adds the [`Constraint::Synchronize`] constraints of `constraints`.
+    pub fn with_constraints(self, constraints: &[Constraint]) -> Self {
+        let extra = constraints.iter().filter_map(|c| match c {
+            Constraint::Synchronize { activity_ids } => Some(activity_ids.clone()),
+            _ => None,
+        });
+        Self::from_links(self.groups.into_iter().chain(extra))
+    }
+
+    /// Merges overlapping links into groups of two or more activities,
+    /// each sorted, in order of their first member.
+    fn from_links(links: impl IntoIterator<Item = Vec<String>>) -> Self {
+        let mut groups: Vec<Vec<String>> = Vec::new();
+        for link in links {
+            let mut merged = link;
+            groups.retain(|g| {
+                let overlaps = g.iter().any(|id| merged.contains(id));
+                if overlaps {
+                    merged.extend(g.iter().cloned());
+                }
+                !overlaps
+            });
+            merged.sort();
+            merged.dedup();
+            groups.push(merged);
+        }
+        groups.retain(|g| g.len() > 1);
+        groups.sort();
+        Self { groups }
+    }
+
+    /// Whether there are no groups.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// The groups (members sorted by ID).
+    pub fn groups(&self) -> &[Vec<String>] {
+        &self.groups
+    }
+
+    /// Groups whose scheduled members start at different times, with the
+    /// latest member start. Unscheduled members are ignored.
+    pub fn misaligned(&self, schedule: &Schedule) -> Vec<(&[String], i64)> {
+        if self.groups.is_empty() {
+            return Vec::new();
+        }
+        let starts = activity_starts(schedule);
+        self.groups
+            .iter()
+            .filter_map(|g| {
+                let member_starts: Vec<i64> = g
+                    .iter()
+                    .filter_map(|id| starts.get(id.as_str()).copied())
+                    .collect();
+                let first = *member_starts.first()?;
+                let latest = member_starts.iter().copied().max()?;
+                member_starts
+                    .iter()
+                    .any(|&s| s != first)
+                    .then_some((g.as_slice(), latest))
+            })
+            .collect()
+    }
+
+    /// Raises the start floor of every member of a misaligned group to
+    /// the group's latest start. Returns whether any floor changed.
+    pub fn raise_floors(&self, schedule: &Schedule, floors: &mut HashMap<String, i64>) -> bool {
+        let mut changed = false;
+        for (group, latest) in self.misaligned(schedule) {
+            for id in group {
+                let floor = floors.entry(id.clone()).or_insert(i64::MIN);
+                if *floor < latest {
+                    *floor = latest;
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// Earliest assignment start of each activity.
+fn activity_starts(schedule: &Schedule) -> HashMap<&str, i64> {
+    let mut starts: HashMap<&str, i64> = HashMap::new();
+    for a in &schedule.assignments {
+        let start = starts.entry(a.activity_id.as_str()).or_insert(a.start_ms);
+        *start = (*start).min(a.start_ms);
+    }
+    starts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, Assignment};
+
+    #[test]
+    fn test_groups_merge_transitively() {
+        let tasks = vec![
+            Task::new("A").with_activity(Activity::new("A1", "A", 0).with_synchronized("B1")),
+            Task::new("B").with_activity(Activity::new("B1", "B", 0)),
+            Task::new("C").with_activity(Activity::new("C1", "C", 0).with_synchronized("B1")),
+            Task::new("D").with_activity(Activity::new("D1", "D", 0)),
+        ];
+        let groups = SyncGroups::from_tasks(&tasks)
+            .with_constraints(&[Constraint::synchronize(vec!["D1".into(), "E1".into()])]);
+        assert_eq!(
+            groups.groups(),
+            [
+                vec!["A1".to_string(), "B1".into(), "C1".into()],
+                vec!["D1".to_string(), "E1".into()],
+            ]
+        );
+        assert!(SyncGroups::from_tasks(&tasks[1..2]).is_empty());
+    }
+
+    #[test]
+    fn test_raise_floors() {
+        let groups = SyncGroups::from_links([vec!["X".to_string(), "Y".to_string()]]);
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("X", "T1", "R1", 0, 100));
+        plan.add_assignment(Assignment::new("Y", "T2", "R2", 300, 400));
+
+        let mut floors = HashMap::new();
+        assert!(groups.raise_floors(&plan, &mut floors));
+        assert_eq!(floors["X"], 300);
+        assert_eq!(floors["Y"], 300);
+        assert!(!groups.raise_floors(&plan, &mut floors));
+
+        plan.assignments[0].start_ms = 300;
+        assert!(groups.misaligned(&plan).is_empty());
+    }
+}
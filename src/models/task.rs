@@ -6,15 +6,62 @@
 //! # Reference
 //! Pinedo (2016), "Scheduling: Theory, Algorithms, and Systems", Ch. 1
 
+use core::time::Duration;
+
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
-use super::Activity;
+use super::prelude::*;
+use super::{units, Activity};
+
+/// Order priority class.
+///
+/// Classes are strict: no lower-class task may delay a higher-class task.
+/// `priority` only orders tasks within a class.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum PriorityClass {
+    /// Expedited orders, scheduled before everything else.
+    Rush,
+    /// Regular orders.
+    #[default]
+    Normal,
+    /// Fill-in work, scheduled only into remaining capacity.
+    Filler,
+}
+
+impl PriorityClass {
+    /// All classes, highest first.
+    pub const ALL: [PriorityClass; 3] = [Self::Rush, Self::Normal, Self::Filler];
+
+    /// Rank (0 = highest).
+    pub fn rank(self) -> usize {
+        self as usize
+    }
+
+    /// Objective weight for weighted formulations (Rush 100, Normal 10,
+    /// Filler 1); one order of magnitude per class approximates a
+    /// lexicographic objective.
+    pub fn weight(self) -> f64 {
+        match self {
+            Self::Rush => 100.0,
+            Self::Normal => 10.0,
+            Self::Filler => 1.0,
+        }
+    }
+}
 
 /// A task (job) to be scheduled.
 ///
 /// Contains one or more activities and scheduling metadata (priority, deadlines).
-/// Activities within a task may have precedence constraints forming a DAG.
+///
+/// By default the activities run in list order, each one following the
+/// previous through its [`precedence_mode`](Activity::precedence_mode).
+/// Once any activity lists another activity of the same task among its
+/// [`predecessors`](Activity::predecessors), the task is a DAG
+/// ([`is_dag`](Self::is_dag)): activities follow only their explicit
+/// predecessors (finish-to-start), so independent branches can run in
+/// parallel.
 ///
 /// # Time Representation
 /// All times are in milliseconds relative to a scheduling epoch (t=0).
@@ -29,10 +76,23 @@ pub struct Task {
     pub category: String,
     /// Scheduling priority (higher = more important).
     pub priority: i32,
+    /// Order class; dominates `priority` (default: Normal).
+    #[serde(default)]
+    pub priority_class: PriorityClass,
     /// Latest completion time (ms). `None` = no deadline.
     pub deadline: Option<i64>,
     /// Earliest start time (ms). `None` = available immediately.
     pub release_time: Option<i64>,
+    /// Tardiness cost per hour late. `None` = resolved by a
+    /// [`WeightProfile`](super::WeightProfile).
+    #[serde(default)]
+    pub tardiness_cost_per_hour: Option<f64>,
+    /// Maximum flow time: completion − release (ms). `None` = unlimited.
+    ///
+    /// A hard limit for work that must not wait in WIP too long (e.g.,
+    /// perishable product), independent of the deadline.
+    #[serde(default)]
+    pub max_flow_ms: Option<i64>,
     /// Activities (operations) that compose this task.
     pub activities: Vec<Activity>,
     /// Domain-specific key-value metadata.
@@ -47,8 +107,11 @@ impl Task {
             name: String::new(),
             category: String::new(),
             priority: 0,
+            priority_class: PriorityClass::Normal,
             deadline: None,
             release_time: None,
+            tardiness_cost_per_hour: None,
+            max_flow_ms: None,
             activities: Vec::new(),
             attributes: HashMap::new(),
         }
@@ -72,6 +135,12 @@ impl Task {
         self
     }
 
+    /// Sets the order class.
+    pub fn with_priority_class(mut self, class: PriorityClass) -> Self {
+        self.priority_class = class;
+        self
+    }
+
     /// Sets the deadline (latest completion time in ms).
     pub fn with_deadline(mut self, deadline_ms: i64) -> Self {
         self.deadline = Some(deadline_ms);
@@ -84,6 +153,35 @@ impl Task {
         self
     }
 
+    /// Sets the tardiness cost per hour late.
+    pub fn with_tardiness_cost(mut self, cost_per_hour: f64) -> Self {
+        self.tardiness_cost_per_hour = Some(cost_per_hour.max(0.0));
+        self
+    }
+
+    /// Sets the maximum flow time (completion − release, in ms).
+    pub fn with_max_flow(mut self, max_flow_ms: i64) -> Self {
+        self.max_flow_ms = Some(max_flow_ms);
+        self
+    }
+
+    /// Sets the maximum flow time from a [`Duration`].
+    pub fn with_max_flow_duration(self, max_flow: Duration) -> Self {
+        self.with_max_flow(units::to_ms(max_flow))
+    }
+
+    /// Maximum flow time as a [`Duration`], if limited.
+    pub fn max_flow_duration(&self) -> Option<Duration> {
+        self.max_flow_ms.map(units::from_ms)
+    }
+
+    /// Latest completion allowed by [`max_flow_ms`](Self::max_flow_ms),
+    /// counted from the release time (0 if unset).
+    pub fn flow_deadline(&self) -> Option<i64> {
+        self.max_flow_ms
+            .map(|max_flow| self.release_time.unwrap_or(0).saturating_add(max_flow))
+    }
+
     /// Adds an activity to this task.
     pub fn with_activity(mut self, activity: Activity) -> Self {
         self.activities.push(activity);
@@ -110,6 +208,50 @@ impl Task {
     pub fn activity_count(&self) -> usize {
         self.activities.len()
     }
+
+    /// Whether an activity has a predecessor within this task, making the
+    /// activities a DAG instead of a chain.
+    pub fn is_dag(&self) -> bool {
+        self.activities.iter().any(|a| {
+            a.predecessors
+                .iter()
+                .any(|p| self.activities.iter().any(|b| &b.id == p))
+        })
+    }
+
+    /// Index of the activity that implicitly precedes activity `index`:
+    /// the previous one in a chain, none in a DAG.
+    pub fn chained_predecessor(&self, index: usize) -> Option<usize> {
+        index.checked_sub(1).filter(|_| !self.is_dag())
+    }
+
+    /// Activity indices in an order that respects intra-task predecessors
+    /// (list order for a chain).
+    ///
+    /// Among ready activities, the one listed first goes first. Activities
+    /// on a cycle are appended in list order.
+    pub fn activity_order(&self) -> Vec<usize> {
+        let n = self.activities.len();
+        let mut order = Vec::with_capacity(n);
+        let mut done = vec![false; n];
+        while order.len() < n {
+            let ready = (0..n).find(|&i| {
+                !done[i]
+                    && self.activities[i].predecessors.iter().all(|p| {
+                        self.activities
+                            .iter()
+                            .zip(&done)
+                            .all(|(b, &placed)| placed || &b.id != p)
+                    })
+            });
+            let Some(next) = ready.or_else(|| (0..n).find(|&i| !done[i])) else {
+                break;
+            };
+            done[next] = true;
+            order.push(next);
+        }
+        order
+    }
 }
 
 #[cfg(test)]
@@ -151,10 +293,46 @@ mod tests {
         assert!(task.has_activities());
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_priority_class() {
+        assert_eq!(Task::new("J1").priority_class, PriorityClass::Normal);
+        assert!(PriorityClass::Rush < PriorityClass::Normal);
+        assert_eq!(PriorityClass::Filler.rank(), 2);
+
+        let task: Task = serde_json::from_str(
+            r#"{ "id": "J1", "name": "", "category": "", "priority": 0,
+                 "deadline": null, "release_time": null, "activities": [],
+                 "attributes": {} }"#,
+        )
+        .unwrap();
+        assert_eq!(task.priority_class, PriorityClass::Normal);
+    }
+
     #[test]
     fn test_task_empty() {
         let task = Task::new("empty");
         assert_eq!(task.total_duration_ms(), 0);
         assert!(!task.has_activities());
     }
+
+    #[test]
+    fn test_activity_order() {
+        let op = |id: &str| Activity::new(id, "J1", 0);
+        let chain = Task::new("J1")
+            .with_activity(op("O1"))
+            .with_activity(op("O2"));
+        assert!(!chain.is_dag());
+        assert_eq!(chain.chained_predecessor(1), Some(0));
+
+        // O1 → {O2, O3} → O4, listed out of order; X1 is in another task.
+        let dag = Task::new("J1")
+            .with_activity(op("O4").with_predecessor("O2").with_predecessor("O3"))
+            .with_activity(op("O2").with_predecessor("O1"))
+            .with_activity(op("O1").with_predecessor("X1"))
+            .with_activity(op("O3").with_predecessor("O1"));
+        assert!(dag.is_dag());
+        assert_eq!(dag.chained_predecessor(1), None);
+        assert_eq!(dag.activity_order(), vec![2, 1, 3, 0]);
+    }
 }
@@ -0,0 +1,146 @@
+//! Throughput targets per category.
+//!
+//! Besides due dates, plans are often judged by output: "finish 40 of A
+//! and 25 of B this week". [`ThroughputTargets`] states how many tasks of
+//! each category should complete by the end of the horizon;
+//! [`measure`](ThroughputTargets::measure) compares a schedule against
+//! them.
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+use super::{Schedule, Task};
+
+/// Target completions per category by the end of a horizon.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThroughputTargets {
+    /// End of the horizon (ms); tasks completing later do not count.
+    pub horizon_end_ms: i64,
+    /// Target number of completed tasks, by category.
+    #[serde(default)]
+    pub targets: HashMap<String, usize>,
+}
+
+impl Default for ThroughputTargets {
+    fn default() -> Self {
+        Self {
+            horizon_end_ms: i64::MAX,
+            targets: HashMap::new(),
+        }
+    }
+}
+
+/// Planned against target completions of one category.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryThroughput {
+    /// Task category.
+    pub category: String,
+    /// Target completions.
+    pub target: usize,
+    /// Tasks planned to complete within the horizon.
+    pub planned: usize,
+    /// `max(0, target − planned)`.
+    pub shortfall: usize,
+}
+
+impl ThroughputTargets {
+    /// Creates empty targets for a horizon ending at `horizon_end_ms`.
+    pub fn new(horizon_end_ms: i64) -> Self {
+        Self {
+            horizon_end_ms,
+            targets: HashMap::new(),
+        }
+    }
+
+    /// Targets `count` completions of `category`.
+    pub fn with_target(mut self, category: impl Into<String>, count: usize) -> Self {
+        self.targets.insert(category.into(), count);
+        self
+    }
+
+    /// Whether no category has a target.
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    /// Target completions of `category` (0 if none).
+    pub fn target(&self, category: &str) -> usize {
+        self.targets.get(category).copied().unwrap_or(0)
+    }
+
+    /// Planned against target completions of each targeted category, by
+    /// category name. Unscheduled tasks count as not completed.
+    pub fn measure(&self, schedule: &Schedule, tasks: &[Task]) -> Vec<CategoryThroughput> {
+        let mut planned: HashMap<&str, usize> = HashMap::new();
+        for task in tasks
+            .iter()
+            .filter(|t| self.targets.contains_key(&t.category))
+        {
+            if schedule
+                .task_completion_time(&task.id)
+                .is_some_and(|end| end <= self.horizon_end_ms)
+            {
+                *planned.entry(task.category.as_str()).or_default() += 1;
+            }
+        }
+        let mut result: Vec<CategoryThroughput> = self
+            .targets
+            .iter()
+            .map(|(category, &target)| {
+                let planned = planned.get(category.as_str()).copied().unwrap_or(0);
+                CategoryThroughput {
+                    category: category.clone(),
+                    target,
+                    planned,
+                    shortfall: target.saturating_sub(planned),
+                }
+            })
+            .collect();
+        result.sort_by(|a, b| a.category.cmp(&b.category));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Assignment;
+
+    #[test]
+    fn test_measure() {
+        let tasks = vec![
+            Task::new("A1").with_category("A"),
+            Task::new("A2").with_category("A"),
+            Task::new("B1").with_category("B"),
+            Task::new("C1").with_category("C"),
+        ];
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("A1_O1", "A1", "M1", 0, 1000));
+        plan.add_assignment(Assignment::new("A2_O1", "A2", "M1", 1000, 3000));
+        plan.add_assignment(Assignment::new("B1_O1", "B1", "M2", 0, 500));
+
+        let targets = ThroughputTargets::new(2000)
+            .with_target("B", 1)
+            .with_target("A", 2);
+        let measured = targets.measure(&plan, &tasks);
+        // A2 completes after the horizon.
+        assert_eq!(
+            measured,
+            vec![
+                CategoryThroughput {
+                    category: "A".into(),
+                    target: 2,
+                    planned: 1,
+                    shortfall: 1,
+                },
+                CategoryThroughput {
+                    category: "B".into(),
+                    target: 1,
+                    planned: 1,
+                    shortfall: 0,
+                },
+            ]
+        );
+        assert_eq!(targets.target("C"), 0);
+    }
+}
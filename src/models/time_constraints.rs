@@ -19,6 +19,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::prelude::*;
+
 // ================================
 // Time Constraint (Hard/Soft)
 // ================================
@@ -392,6 +394,7 @@ impl PertEstimate {
     /// Duration at specified confidence level.
     ///
     /// Uses normal approximation via `u_numflow::special::inverse_normal_cdf`.
+    #[cfg(feature = "std")]
     pub fn duration_at_confidence(&self, confidence: f64) -> i64 {
         let z = u_numflow::special::inverse_normal_cdf(confidence);
         (self.mean_ms() + z * self.std_dev_ms()) as i64
@@ -400,6 +403,7 @@ impl PertEstimate {
     /// Probability of completing within given duration.
     ///
     /// Uses `u_numflow::special::standard_normal_cdf`.
+    #[cfg(feature = "std")]
     pub fn probability_of_completion(&self, duration_ms: i64) -> f64 {
         let z = (duration_ms as f64 - self.mean_ms()) / self.std_dev_ms();
         u_numflow::special::standard_normal_cdf(z)
@@ -411,11 +415,13 @@ impl PertEstimate {
     }
 
     /// 85th percentile duration.
+    #[cfg(feature = "std")]
     pub fn p85(&self) -> i64 {
         self.duration_at_confidence(0.85)
     }
 
     /// 95th percentile duration.
+    #[cfg(feature = "std")]
     pub fn p95(&self) -> i64 {
         self.duration_at_confidence(0.95)
     }
@@ -446,6 +452,7 @@ pub enum DurationDistribution {
 
 impl DurationDistribution {
     /// Expected (mean) duration.
+    #[cfg(feature = "std")]
     pub fn expected_duration_ms(&self) -> f64 {
         match self {
             Self::Fixed(d) => *d as f64,
@@ -461,6 +468,7 @@ impl DurationDistribution {
     }
 
     /// Duration at confidence level.
+    #[cfg(feature = "std")]
     pub fn duration_at_confidence(&self, confidence: f64) -> i64 {
         match self {
             Self::Fixed(d) => *d,
@@ -560,6 +568,7 @@ mod tests {
         assert_eq!(pert.pessimistic_ms, 12000);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_pert_confidence_levels() {
         let pert = PertEstimate::new(6000, 10000, 14000);
@@ -569,6 +578,7 @@ mod tests {
         assert!(pert.p85() > pert.p50());
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_duration_distribution_expected() {
         let fixed = DurationDistribution::Fixed(5000);
@@ -0,0 +1,109 @@
+//! Shared tooling: tools and fixtures mounted on machines.
+//!
+//! Some categories of work need a tool (a die, a mold, a fixture) that
+//! exists only a few times in the plant. The tool is mounted on the
+//! machine for the whole activity and has to be moved when another
+//! machine needs it. [`ToolingRules`] list the tool pool of each
+//! category and the transfer delay of moving a tool.
+//!
+//! Tools are [`Resource`](super::Resource)s, usually
+//! [`Secondary`](super::ResourceType::Secondary). A scheduler books the
+//! chosen tool for the activity's interval, so no tool is ever on two
+//! machines at once, and delays the next use on another machine by the
+//! transfer time. The first mount is free.
+//!
+//! An activity can also name its tool itself: a further
+//! [requirement](super::Activity::resource_requirements) on `Secondary`
+//! resources (see [`Activity::tool_requirement`](super::Activity::tool_requirement))
+//! is a tool pool for that activity, moved with the same transfer times.
+//! A category's pool takes precedence.
+//!
+//! # Example
+//!
+//! ```
+//! use u_schedule::models::ToolingRules;
+//!
+//! let tooling = ToolingRules::new()
+//!     .with_requirement("housing", vec!["DIE-1".into()])
+//!     .with_transfer_ms(30 * 60_000)
+//!     .with_tool_transfer("DIE-1", 45 * 60_000);
+//! assert_eq!(tooling.tools_for("housing").unwrap(), ["DIE-1"]);
+//! assert_eq!(tooling.transfer_time("DIE-1"), 45 * 60_000);
+//! assert!(tooling.tools_for("cover").is_none());
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+
+/// A category's tool pool: one of `tools` must be mounted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolRequirement {
+    /// Task category needing the tool.
+    pub category: String,
+    /// Interchangeable tools (resource IDs).
+    pub tools: Vec<String>,
+}
+
+/// Tool pools per category, with transfer delays.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolingRules {
+    /// Tool pools by category.
+    #[serde(default)]
+    pub requirements: Vec<ToolRequirement>,
+    /// Time to move a tool to another machine (ms).
+    #[serde(default)]
+    pub transfer_ms: i64,
+    /// Transfer times of individual tools, overriding `transfer_ms`.
+    #[serde(default)]
+    pub tool_transfer_ms: HashMap<String, i64>,
+}
+
+impl ToolingRules {
+    /// Creates rules without tools.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requires one of `tools` for tasks of `category`.
+    pub fn with_requirement(mut self, category: impl Into<String>, tools: Vec<String>) -> Self {
+        self.requirements.push(ToolRequirement {
+            category: category.into(),
+            tools,
+        });
+        self
+    }
+
+    /// Sets the default transfer time.
+    pub fn with_transfer_ms(mut self, transfer_ms: i64) -> Self {
+        self.transfer_ms = transfer_ms;
+        self
+    }
+
+    /// Sets the transfer time of one tool.
+    pub fn with_tool_transfer(mut self, tool_id: impl Into<String>, transfer_ms: i64) -> Self {
+        self.tool_transfer_ms.insert(tool_id.into(), transfer_ms);
+        self
+    }
+
+    /// Whether no category needs a tool.
+    pub fn is_empty(&self) -> bool {
+        self.requirements.is_empty()
+    }
+
+    /// Tool pool of a category (`None` if it needs no tool).
+    pub fn tools_for(&self, category: &str) -> Option<&[String]> {
+        self.requirements
+            .iter()
+            .find(|r| r.category == category)
+            .map(|r| r.tools.as_slice())
+    }
+
+    /// Time to move a tool to another machine (ms).
+    pub fn transfer_time(&self, tool_id: &str) -> i64 {
+        self.tool_transfer_ms
+            .get(tool_id)
+            .copied()
+            .unwrap_or(self.transfer_ms)
+    }
+}
@@ -0,0 +1,62 @@
+//! Time units and [`Duration`] conversions.
+//!
+//! The models keep every time as `i64` milliseconds relative to the
+//! scheduling epoch. This module names the common units, so that call
+//! sites read `90 * MINUTE_MS` rather than `90 * 60_000`, and converts
+//! from and to [`core::time::Duration`] for builders and getters that
+//! take or return one (e.g. [`Activity::with_process_duration`],
+//! [`Assignment::duration`]).
+//!
+//! Conversions saturate: durations beyond `i64::MAX` ms become
+//! `i64::MAX`, and negative milliseconds become a zero `Duration`.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use u_schedule::models::units::{self, HOUR_MS, MINUTE_MS};
+//! use u_schedule::models::Activity;
+//!
+//! let op = Activity::new("O1", "J1", 0).with_process_duration(Duration::from_secs(90 * 60));
+//! assert_eq!(op.duration.process_ms, 90 * MINUTE_MS);
+//! assert_eq!(op.duration.process_duration(), Duration::from_secs(5400));
+//! assert_eq!(units::to_ms(Duration::from_secs(7200)), 2 * HOUR_MS);
+//! ```
+//!
+//! [`Activity::with_process_duration`]: super::Activity::with_process_duration
+//! [`Assignment::duration`]: super::Assignment::duration
+
+use core::time::Duration;
+
+/// One second (ms).
+pub const SECOND_MS: i64 = 1_000;
+/// One minute (ms).
+pub const MINUTE_MS: i64 = 60 * SECOND_MS;
+/// One hour (ms).
+pub const HOUR_MS: i64 = 60 * MINUTE_MS;
+/// One day (ms).
+pub const DAY_MS: i64 = 24 * HOUR_MS;
+
+/// Whole milliseconds of `duration`, saturating at `i64::MAX`.
+pub fn to_ms(duration: Duration) -> i64 {
+    i64::try_from(duration.as_millis()).unwrap_or(i64::MAX)
+}
+
+/// `ms` milliseconds as a [`Duration`] (zero if negative).
+pub fn from_ms(ms: i64) -> Duration {
+    Duration::from_millis(ms.max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(DAY_MS, 86_400_000);
+        assert_eq!(to_ms(Duration::from_micros(2_500)), 2);
+        assert_eq!(to_ms(Duration::MAX), i64::MAX);
+        assert_eq!(from_ms(MINUTE_MS), Duration::from_secs(60));
+        assert_eq!(from_ms(-5), Duration::ZERO);
+    }
+}
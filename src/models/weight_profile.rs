@@ -0,0 +1,156 @@
+//! Per-task objective weights.
+//!
+//! Late orders rarely cost the same: a key account's penalty clause is
+//! worth more than a stock replenishment. A [`WeightProfile`] prices
+//! tardiness per task, as a cost per hour late, by customer and category.
+//! The KPI, the GA fitness and the CP objective use these rates instead of
+//! a single global tardiness weight.
+//!
+//! # Resolution
+//!
+//! The rate of a task is the first of:
+//! 1. The task's own [`tardiness_cost_per_hour`](Task::tardiness_cost_per_hour)
+//! 2. The rate of its customer (the task attribute named by
+//!    [`customer_attribute`](WeightProfile::customer_attribute))
+//! 3. The rate of its category
+//! 4. The default rate
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+use super::Task;
+
+/// Milliseconds per hour.
+const HOUR_MS: f64 = 3_600_000.0;
+
+/// Tardiness cost rates by customer and category.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightProfile {
+    /// Rate when nothing more specific applies (default: 1.0 per hour).
+    pub default_cost_per_hour: f64,
+    /// Task attribute holding the customer (default: `"customer"`).
+    pub customer_attribute: String,
+    /// Rates per customer.
+    #[serde(default)]
+    pub by_customer: HashMap<String, f64>,
+    /// Rates per task category.
+    #[serde(default)]
+    pub by_category: HashMap<String, f64>,
+}
+
+impl Default for WeightProfile {
+    fn default() -> Self {
+        Self {
+            default_cost_per_hour: 1.0,
+            customer_attribute: "customer".to_string(),
+            by_customer: HashMap::new(),
+            by_category: HashMap::new(),
+        }
+    }
+}
+
+impl WeightProfile {
+    /// Creates a profile with a rate of 1.0 per hour for every task.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the default rate.
+    pub fn with_default(mut self, cost_per_hour: f64) -> Self {
+        self.default_cost_per_hour = cost_per_hour.max(0.0);
+        self
+    }
+
+    /// Sets the task attribute holding the customer.
+    pub fn with_customer_attribute(mut self, key: impl Into<String>) -> Self {
+        self.customer_attribute = key.into();
+        self
+    }
+
+    /// Sets the rate of a customer.
+    pub fn with_customer(mut self, customer: impl Into<String>, cost_per_hour: f64) -> Self {
+        self.by_customer
+            .insert(customer.into(), cost_per_hour.max(0.0));
+        self
+    }
+
+    /// Sets the rate of a category.
+    pub fn with_category(mut self, category: impl Into<String>, cost_per_hour: f64) -> Self {
+        self.by_category
+            .insert(category.into(), cost_per_hour.max(0.0));
+        self
+    }
+
+    /// Tardiness cost per hour late of a task.
+    pub fn cost_per_hour(&self, task: &Task) -> f64 {
+        task.tardiness_cost_per_hour
+            .or_else(|| {
+                task.attributes
+                    .get(&self.customer_attribute)
+                    .and_then(|c| self.by_customer.get(c))
+                    .copied()
+            })
+            .or_else(|| self.by_category.get(&task.category).copied())
+            .unwrap_or(self.default_cost_per_hour)
+    }
+
+    /// Rate of a task relative to the default rate.
+    ///
+    /// Scales tardiness in formulations whose global tardiness weight is
+    /// calibrated for the default rate. 1.0 if the default rate is zero.
+    pub fn relative_weight(&self, task: &Task) -> f64 {
+        if self.default_cost_per_hour > 0.0 {
+            self.cost_per_hour(task) / self.default_cost_per_hour
+        } else {
+            1.0
+        }
+    }
+
+    /// Cost of a task finishing `tardiness_ms` late.
+    pub fn tardiness_cost(&self, task: &Task, tardiness_ms: i64) -> f64 {
+        self.cost_per_hour(task) * tardiness_ms.max(0) as f64 / HOUR_MS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_resolution() {
+        let profile = WeightProfile::new()
+            .with_default(10.0)
+            .with_customer("ACME", 500.0)
+            .with_category("stock", 2.0);
+
+        let plain = Task::new("T1");
+        assert_eq!(profile.cost_per_hour(&plain), 10.0);
+        let stock = Task::new("T2").with_category("stock");
+        assert_eq!(profile.cost_per_hour(&stock), 2.0);
+        let acme = Task::new("T3")
+            .with_category("stock")
+            .with_attribute("customer", "ACME");
+        assert_eq!(profile.cost_per_hour(&acme), 500.0);
+        assert_eq!(profile.relative_weight(&acme), 50.0);
+        let own = acme.clone().with_tardiness_cost(75.0);
+        assert_eq!(profile.cost_per_hour(&own), 75.0);
+
+        // Two hours late at 500 per hour.
+        assert_eq!(profile.tardiness_cost(&acme, 7_200_000), 1000.0);
+        assert_eq!(profile.tardiness_cost(&acme, -5), 0.0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_customer_attribute_and_json() {
+        let profile = WeightProfile::new()
+            .with_customer_attribute("account")
+            .with_customer("ACME", 40.0);
+        let task = Task::new("T1").with_attribute("account", "ACME");
+        assert_eq!(profile.cost_per_hour(&task), 40.0);
+
+        let parsed: WeightProfile =
+            serde_json::from_str(&serde_json::to_string(&profile).unwrap()).unwrap();
+        assert_eq!(parsed, profile);
+    }
+}
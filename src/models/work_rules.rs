@@ -0,0 +1,330 @@
+//! Working-time rules for human resources.
+//!
+//! Limits continuous working time, enforces rest between assignments,
+//! and caps daily hours. Rules are evaluated on the sequence of busy
+//! intervals of one resource.
+//!
+//! # Definitions
+//!
+//! | Rule | Meaning |
+//! |------|---------|
+//! | `min_rest_ms` | Gap required between consecutive assignments |
+//! | `max_continuous_ms` | Longest working stretch; gaps ≥ `break_ms` end a stretch |
+//! | `max_daily_ms` | Busy time per day window `[k·day, (k+1)·day)` |
+//!
+//! Day windows are aligned to the scheduling epoch (t=0).
+//!
+//! # Reference
+//! Council Directive 2003/88/EC (EU Working Time Directive)
+
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::prelude::*;
+use super::units::{self, HOUR_MS};
+
+/// Maximum placement attempts before a request is declared infeasible.
+const MAX_ATTEMPTS: usize = 10_000;
+
+/// Working-time rules of one resource.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkRules {
+    /// Maximum working stretch (ms). `None` = unlimited.
+    pub max_continuous_ms: Option<i64>,
+    /// Minimum gap that ends a working stretch (default: 30 min).
+    pub break_ms: i64,
+    /// Minimum gap between consecutive assignments (default: 0).
+    pub min_rest_ms: i64,
+    /// Maximum busy time per day (ms). `None` = unlimited.
+    pub max_daily_ms: Option<i64>,
+    /// Day length for `max_daily_ms` (default: 24 h).
+    pub day_length_ms: i64,
+}
+
+impl Default for WorkRules {
+    fn default() -> Self {
+        Self {
+            max_continuous_ms: None,
+            break_ms: HOUR_MS / 2,
+            min_rest_ms: 0,
+            max_daily_ms: None,
+            day_length_ms: 24 * HOUR_MS,
+        }
+    }
+}
+
+impl WorkRules {
+    /// Creates rules without limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum working stretch.
+    pub fn with_max_continuous_ms(mut self, ms: i64) -> Self {
+        self.max_continuous_ms = Some(ms);
+        self
+    }
+
+    /// Sets the gap that counts as a break.
+    pub fn with_break_ms(mut self, ms: i64) -> Self {
+        self.break_ms = ms.max(1);
+        self
+    }
+
+    /// Sets the minimum rest between consecutive assignments.
+    pub fn with_min_rest_ms(mut self, ms: i64) -> Self {
+        self.min_rest_ms = ms.max(0);
+        self
+    }
+
+    /// Sets the maximum busy time per day.
+    pub fn with_max_daily_ms(mut self, ms: i64) -> Self {
+        self.max_daily_ms = Some(ms);
+        self
+    }
+
+    /// Sets the day length.
+    pub fn with_day_length_ms(mut self, ms: i64) -> Self {
+        self.day_length_ms = ms.max(1);
+        self
+    }
+
+    /// [`with_max_continuous_ms`](Self::with_max_continuous_ms) from a [`Duration`].
+    pub fn with_max_continuous(self, stretch: Duration) -> Self {
+        self.with_max_continuous_ms(units::to_ms(stretch))
+    }
+
+    /// [`with_break_ms`](Self::with_break_ms) from a [`Duration`].
+    pub fn with_break(self, gap: Duration) -> Self {
+        self.with_break_ms(units::to_ms(gap))
+    }
+
+    /// [`with_min_rest_ms`](Self::with_min_rest_ms) from a [`Duration`].
+    pub fn with_min_rest(self, rest: Duration) -> Self {
+        self.with_min_rest_ms(units::to_ms(rest))
+    }
+
+    /// [`with_max_daily_ms`](Self::with_max_daily_ms) from a [`Duration`].
+    pub fn with_max_daily(self, busy: Duration) -> Self {
+        self.with_max_daily_ms(units::to_ms(busy))
+    }
+
+    /// [`with_day_length_ms`](Self::with_day_length_ms) from a [`Duration`].
+    pub fn with_day_length(self, day: Duration) -> Self {
+        self.with_day_length_ms(units::to_ms(day))
+    }
+
+    /// Earliest start `≥ ready_ms` for work of `duration_ms` appended after
+    /// `busy` (sorted, non-overlapping intervals), or `None` if no start
+    /// satisfies the rules (e.g., a single block longer than the stretch
+    /// limit).
+    pub fn earliest_start(
+        &self,
+        busy: &[(i64, i64)],
+        ready_ms: i64,
+        duration_ms: i64,
+    ) -> Option<i64> {
+        if self.max_continuous_ms.is_some_and(|max| duration_ms > max) {
+            return None;
+        }
+        let last_end = busy.last().map(|&(_, e)| e);
+        let mut t = match last_end {
+            Some(end) => ready_ms.max(end + self.min_rest_ms),
+            None => ready_ms,
+        };
+
+        for _ in 0..MAX_ATTEMPTS {
+            if let Some(max) = self.max_continuous_ms {
+                let stretch_start = self.stretch_start(busy, t);
+                if t + duration_ms - stretch_start > max {
+                    // Wait for a full break after the last assignment.
+                    t = t.max(last_end.unwrap_or(t) + self.break_ms);
+                    continue;
+                }
+            }
+            if let Some(max) = self.max_daily_ms {
+                if let Some(day) = self.first_overloaded_day(busy, t, t + duration_ms, max) {
+                    let next_day = (day + 1) * self.day_length_ms;
+                    if next_day <= t {
+                        return None;
+                    }
+                    t = next_day;
+                    continue;
+                }
+            }
+            return Some(t);
+        }
+        None
+    }
+
+    /// Rule violations in a resource's busy intervals (sorted by start).
+    ///
+    /// Each message names the offending interval or day.
+    pub fn check(&self, busy: &[(i64, i64)]) -> Vec<String> {
+        let mut issues = Vec::new();
+
+        for pair in busy.windows(2) {
+            let gap = pair[1].0 - pair[0].1;
+            if gap < self.min_rest_ms {
+                issues.push(format!(
+                    "rest of {gap} ms before [{}, {}) is below {} ms",
+                    pair[1].0, pair[1].1, self.min_rest_ms
+                ));
+            }
+        }
+
+        if let Some(max) = self.max_continuous_ms {
+            let mut stretch_start = None;
+            let mut prev_end = i64::MIN;
+            for &(s, e) in busy {
+                if stretch_start.is_none() || s - prev_end >= self.break_ms {
+                    stretch_start = Some(s);
+                }
+                let start = stretch_start.unwrap_or(s);
+                if e - start > max {
+                    issues.push(format!(
+                        "working stretch [{start}, {e}) exceeds {max} ms without a break"
+                    ));
+                    // Report each stretch once.
+                    stretch_start = Some(e);
+                }
+                prev_end = e;
+            }
+        }
+
+        if let Some(max) = self.max_daily_ms {
+            let mut days: Vec<i64> = busy
+                .iter()
+                .flat_map(|&(s, e)| self.day_of(s)..=self.day_of(e - 1))
+                .collect();
+            days.dedup();
+            for day in days {
+                let worked = self.worked_in_day(busy, day);
+                if worked > max {
+                    issues.push(format!("day {day}: {worked} ms worked exceeds {max} ms"));
+                }
+            }
+        }
+
+        issues
+    }
+
+    fn day_of(&self, t: i64) -> i64 {
+        t.div_euclid(self.day_length_ms)
+    }
+
+    /// Start of the working stretch that work starting at `t` would join.
+    fn stretch_start(&self, busy: &[(i64, i64)], t: i64) -> i64 {
+        let mut start = t;
+        let mut next_start = t;
+        for &(s, e) in busy.iter().rev() {
+            if next_start - e >= self.break_ms {
+                break;
+            }
+            start = s;
+            next_start = s;
+        }
+        start
+    }
+
+    fn worked_in_day(&self, busy: &[(i64, i64)], day: i64) -> i64 {
+        let (lo, hi) = (day * self.day_length_ms, (day + 1) * self.day_length_ms);
+        busy.iter()
+            .map(|&(s, e)| (e.min(hi) - s.max(lo)).max(0))
+            .sum()
+    }
+
+    /// First day where adding `[start, end)` exceeds `max`.
+    fn first_overloaded_day(
+        &self,
+        busy: &[(i64, i64)],
+        start: i64,
+        end: i64,
+        max: i64,
+    ) -> Option<i64> {
+        (self.day_of(start)..=self.day_of(end - 1).max(self.day_of(start))).find(|&day| {
+            let (lo, hi) = (day * self.day_length_ms, (day + 1) * self.day_length_ms);
+            let added = (end.min(hi) - start.max(lo)).max(0);
+            self.worked_in_day(busy, day) + added > max
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_rules_starts_at_ready() {
+        let rules = WorkRules::new();
+        assert_eq!(rules.earliest_start(&[(0, 100)], 100, 50), Some(100));
+    }
+
+    #[test]
+    fn test_min_rest() {
+        let rules = WorkRules::new().with_min_rest_ms(30);
+        assert_eq!(rules.earliest_start(&[(0, 100)], 100, 50), Some(130));
+        assert_eq!(rules.earliest_start(&[(0, 100)], 200, 50), Some(200));
+        assert_eq!(rules.check(&[(0, 100), (110, 150)]).len(), 1);
+        assert!(rules.check(&[(0, 100), (130, 150)]).is_empty());
+    }
+
+    #[test]
+    fn test_max_continuous() {
+        let rules = WorkRules::new()
+            .with_max_continuous_ms(4 * HOUR_MS)
+            .with_break_ms(HOUR_MS / 2);
+        let busy = [(0, 3 * HOUR_MS)];
+        // 2h more would make a 5h stretch: wait for a 30 min break.
+        assert_eq!(
+            rules.earliest_start(&busy, 3 * HOUR_MS, 2 * HOUR_MS),
+            Some(3 * HOUR_MS + HOUR_MS / 2)
+        );
+        // 1h fits.
+        assert_eq!(
+            rules.earliest_start(&busy, 3 * HOUR_MS, HOUR_MS),
+            Some(3 * HOUR_MS)
+        );
+        // A single block over the limit is infeasible.
+        assert_eq!(rules.earliest_start(&[], 0, 5 * HOUR_MS), None);
+
+        let issues = rules.check(&[(0, 3 * HOUR_MS), (3 * HOUR_MS + 60_000, 5 * HOUR_MS)]);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("without a break"));
+    }
+
+    #[test]
+    fn test_max_daily() {
+        let rules = WorkRules::new().with_max_daily_ms(8 * HOUR_MS);
+        let busy = [(0, 7 * HOUR_MS)];
+        // 2h more today would be 9h: move to the next day.
+        assert_eq!(
+            rules.earliest_start(&busy, 7 * HOUR_MS, 2 * HOUR_MS),
+            Some(24 * HOUR_MS)
+        );
+        assert_eq!(
+            rules.earliest_start(&busy, 7 * HOUR_MS, HOUR_MS),
+            Some(7 * HOUR_MS)
+        );
+
+        let issues = rules.check(&[(0, 7 * HOUR_MS), (8 * HOUR_MS, 10 * HOUR_MS)]);
+        assert_eq!(
+            issues,
+            vec![format!(
+                "day 0: {} ms worked exceeds {} ms",
+                9 * HOUR_MS,
+                8 * HOUR_MS
+            )]
+        );
+    }
+
+    #[test]
+    fn test_work_spanning_midnight_counts_per_day() {
+        let rules = WorkRules::new().with_max_daily_ms(8 * HOUR_MS);
+        // 20:00–04:00 is 4h on each day.
+        let busy = [(20 * HOUR_MS, 28 * HOUR_MS)];
+        assert!(rules.check(&busy).is_empty());
+    }
+}
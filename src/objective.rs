@@ -0,0 +1,337 @@
+//! Scheduling objectives shared by the solvers and KPIs.
+//!
+//! Each objective term is defined once here and evaluated over a
+//! [`Schedule`] and its input [`Task`]s. [`ScheduleKpi`], the GA
+//! fitness, the LNS cost and the CP objective are all built from these
+//! definitions, so what a solver minimizes is what the KPIs report.
+//!
+//! # Terms
+//!
+//! | Term | Value (ms unless noted) | CP form |
+//! |------|-------------------------|---------|
+//! | [`Makespan`](ObjectiveTerm::Makespan) | Latest end | `MinimizeMaxEnd` |
+//! | [`TotalTardiness`](ObjectiveTerm::TotalTardiness) | Σ max(0, C − d) | Σ C over deadline tasks |
+//! | [`WeightedTardiness`](ObjectiveTerm::WeightedTardiness) | Σ [`tardiness_factor`] × max(0, C − d) | Σ factor × C over deadline tasks |
+//! | [`TardinessCost`](ObjectiveTerm::TardinessCost) | Σ rate × hours late (currency) | Σ rate × C over deadline tasks |
+//! | [`ClassCompletion`](ObjectiveTerm::ClassCompletion) | Σ C over one priority class | Σ C over the class |
+//!
+//! `C` is a task's completion time and `d` its deadline. CP models can
+//! only minimize linear combinations of end times, so tardiness terms
+//! are lowered to the weighted completion of the deadline tasks.
+//!
+//! # Usage
+//!
+//! ```
+//! use u_schedule::models::{Assignment, Schedule, Task};
+//! use u_schedule::objective::{Objective, ObjectiveTerm};
+//!
+//! let tasks = vec![Task::new("J1").with_deadline(500)];
+//! let mut schedule = Schedule::new();
+//! schedule.add_assignment(Assignment::new("O1", "J1", "M1", 0, 1000));
+//!
+//! let objective = Objective::makespan_tardiness(0.5);
+//! assert_eq!(objective.evaluate(&schedule, &tasks), 0.5 * 1000.0 + 0.5 * 500.0);
+//! assert_eq!(ObjectiveTerm::TotalTardiness.evaluate(&schedule, &tasks, objective.weights()), 500.0);
+//! ```
+//!
+//! [`ScheduleKpi`]: crate::scheduler::ScheduleKpi
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{PriorityClass, Schedule, Task, WeightProfile};
+
+/// Tardiness of a task completing at `completion_ms` (0 if on time).
+pub fn tardiness_ms(completion_ms: i64, deadline_ms: i64) -> i64 {
+    (completion_ms - deadline_ms).max(0)
+}
+
+/// Tardiness of `task` in `schedule`, if it has a deadline and is
+/// scheduled.
+pub fn task_tardiness_ms(schedule: &Schedule, task: &Task) -> Option<i64> {
+    let deadline = task.deadline?;
+    let completion = schedule.task_completion_time(&task.id)?;
+    Some(tardiness_ms(completion, deadline))
+}
+
+/// Weight of one ms of tardiness: the class weight relative to
+/// [`PriorityClass::Normal`] times the task's rate relative to the
+/// profile default ([`WeightProfile::relative_weight`]).
+pub fn tardiness_factor(class: PriorityClass, relative_rate: f64) -> f64 {
+    class.weight() / PriorityClass::Normal.weight() * relative_rate
+}
+
+/// Σ `factor` × tardiness over `(task_id, deadline_ms, factor)` entries.
+///
+/// Unscheduled tasks contribute nothing; solvers penalize them
+/// separately.
+pub fn weighted_tardiness<'a>(
+    schedule: &Schedule,
+    entries: impl IntoIterator<Item = (&'a str, i64, f64)>,
+) -> f64 {
+    entries
+        .into_iter()
+        .filter_map(|(task_id, deadline, factor)| {
+            schedule
+                .task_completion_time(task_id)
+                .map(|c| factor * tardiness_ms(c, deadline) as f64)
+        })
+        .sum()
+}
+
+/// Mean completion time of the scheduled `(task_id, class)` entries,
+/// weighted by [`PriorityClass::weight`] (0 if none is scheduled).
+pub fn class_weighted_completion<'a>(
+    schedule: &Schedule,
+    entries: impl IntoIterator<Item = (&'a str, PriorityClass)>,
+) -> f64 {
+    let (weighted, total) =
+        entries
+            .into_iter()
+            .fold((0.0, 0.0), |(sum, total), (task_id, class)| match schedule
+                .task_completion_time(task_id)
+            {
+                Some(c) => (sum + class.weight() * c as f64, total + class.weight()),
+                None => (sum, total),
+            });
+    if total > 0.0 {
+        weighted / total
+    } else {
+        0.0
+    }
+}
+
+/// One term of a scheduling objective (all minimized).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ObjectiveTerm {
+    /// Latest end time.
+    Makespan,
+    /// Sum of tardiness.
+    TotalTardiness,
+    /// Sum of tardiness scaled by [`tardiness_factor`].
+    WeightedTardiness,
+    /// Sum of [`WeightProfile::tardiness_cost`].
+    TardinessCost,
+    /// Sum of completion times of one priority class.
+    ClassCompletion(PriorityClass),
+}
+
+impl ObjectiveTerm {
+    /// Value of the term for `schedule`.
+    ///
+    /// Unscheduled tasks contribute nothing.
+    pub fn evaluate(&self, schedule: &Schedule, tasks: &[Task], weights: &WeightProfile) -> f64 {
+        match self {
+            Self::Makespan => schedule.makespan_ms() as f64,
+            Self::TotalTardiness => tasks
+                .iter()
+                .filter_map(|t| task_tardiness_ms(schedule, t))
+                .sum::<i64>() as f64,
+            Self::WeightedTardiness => weighted_tardiness(
+                schedule,
+                tasks.iter().filter_map(|t| {
+                    t.deadline.map(|d| {
+                        let factor = tardiness_factor(t.priority_class, weights.relative_weight(t));
+                        (t.id.as_str(), d, factor)
+                    })
+                }),
+            ),
+            Self::TardinessCost => tasks
+                .iter()
+                .filter_map(|t| {
+                    task_tardiness_ms(schedule, t).map(|late| weights.tardiness_cost(t, late))
+                })
+                .sum(),
+            Self::ClassCompletion(class) => tasks
+                .iter()
+                .filter(|t| t.priority_class == *class)
+                .filter_map(|t| schedule.task_completion_time(&t.id))
+                .sum::<i64>() as f64,
+        }
+    }
+
+    /// The term as a weighted sum of final-activity end times, for CP
+    /// models (`None` for [`Makespan`](Self::Makespan), which CP
+    /// expresses as `MinimizeMaxEnd`).
+    ///
+    /// Tardiness terms keep the tasks with a deadline and a positive
+    /// weight; the surrogate pushes their completions forward without
+    /// rewarding early finishes beyond what the weights imply.
+    pub fn completion_terms<'a>(
+        &self,
+        tasks: impl IntoIterator<Item = &'a Task>,
+        weights: &WeightProfile,
+    ) -> Option<Vec<(String, f64)>> {
+        let coefficient: &dyn Fn(&Task) -> Option<f64> = match self {
+            Self::Makespan => return None,
+            Self::TotalTardiness => &|t: &Task| t.deadline.map(|_| 1.0),
+            Self::WeightedTardiness => &|t: &Task| {
+                t.deadline
+                    .map(|_| tardiness_factor(t.priority_class, weights.relative_weight(t)))
+            },
+            Self::TardinessCost => &|t: &Task| t.deadline.map(|_| weights.cost_per_hour(t)),
+            Self::ClassCompletion(class) => &|t: &Task| (t.priority_class == *class).then_some(1.0),
+        };
+        Some(
+            tasks
+                .into_iter()
+                .filter_map(|t| {
+                    let weight = coefficient(t).filter(|&w| w > 0.0)?;
+                    t.activities.last().map(|a| (a.id.clone(), weight))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// A weighted sum of [`ObjectiveTerm`]s, with the [`WeightProfile`]
+/// that prices tardiness.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Objective {
+    terms: Vec<(ObjectiveTerm, f64)>,
+    weights: WeightProfile,
+}
+
+impl Objective {
+    /// An objective with no terms (evaluates to 0).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `(1 − w) × makespan + w × weighted tardiness`, the cost used by
+    /// the GA and LNS (`w` clamped to `[0, 1]`).
+    pub fn makespan_tardiness(tardiness_weight: f64) -> Self {
+        let w = tardiness_weight.clamp(0.0, 1.0);
+        Self::new()
+            .with_term(ObjectiveTerm::Makespan, 1.0 - w)
+            .with_term(ObjectiveTerm::WeightedTardiness, w)
+    }
+
+    /// Adds `weight × term`.
+    pub fn with_term(mut self, term: ObjectiveTerm, weight: f64) -> Self {
+        self.terms.push((term, weight));
+        self
+    }
+
+    /// Sets the profile pricing tardiness.
+    pub fn with_weight_profile(mut self, weights: WeightProfile) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// The weighted terms.
+    pub fn terms(&self) -> &[(ObjectiveTerm, f64)] {
+        &self.terms
+    }
+
+    /// The profile pricing tardiness.
+    pub fn weights(&self) -> &WeightProfile {
+        &self.weights
+    }
+
+    /// Value of the objective for `schedule` (lower is better).
+    pub fn evaluate(&self, schedule: &Schedule, tasks: &[Task]) -> f64 {
+        self.terms
+            .iter()
+            .filter(|(_, weight)| *weight != 0.0)
+            .map(|(term, weight)| weight * term.evaluate(schedule, tasks, &self.weights))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, Assignment};
+
+    fn setup() -> (Schedule, Vec<Task>) {
+        let tasks = vec![
+            Task::new("J1")
+                .with_deadline(1000)
+                .with_priority_class(PriorityClass::Rush)
+                .with_activity(Activity::new("J1_O1", "J1", 1).with_process_time(1500)),
+            Task::new("J2")
+                .with_deadline(5000)
+                .with_tardiness_cost(20.0)
+                .with_activity(Activity::new("J2_O1", "J2", 1).with_process_time(2000)),
+            Task::new("J3")
+                .with_deadline(3_600_000)
+                .with_tardiness_cost(2.0)
+                .with_activity(Activity::new("J3_O1", "J3", 1).with_process_time(3_600_000)),
+            Task::new("J4").with_activity(Activity::new("J4_O1", "J4", 1).with_process_time(100)),
+        ];
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1500));
+        schedule.add_assignment(Assignment::new("J2_O1", "J2", "M1", 1500, 3500));
+        schedule.add_assignment(Assignment::new("J3_O1", "J3", "M2", 3_600_000, 7_200_000));
+        (schedule, tasks)
+    }
+
+    #[test]
+    fn test_terms() {
+        let (schedule, tasks) = setup();
+        let weights = WeightProfile::default();
+        let value = |term: ObjectiveTerm| term.evaluate(&schedule, &tasks, &weights);
+
+        assert_eq!(value(ObjectiveTerm::Makespan), 7_200_000.0);
+        // J1 500 ms late, J3 one hour late; J4 has no deadline.
+        assert_eq!(value(ObjectiveTerm::TotalTardiness), 500.0 + 3_600_000.0);
+        // Rush ×10 on J1, rate ×2 on J3.
+        assert_eq!(
+            value(ObjectiveTerm::WeightedTardiness),
+            10.0 * 500.0 + 2.0 * 3_600_000.0
+        );
+        assert!((value(ObjectiveTerm::TardinessCost) - (500.0 / 3_600_000.0 + 2.0)).abs() < 1e-9);
+        assert_eq!(
+            value(ObjectiveTerm::ClassCompletion(PriorityClass::Rush)),
+            1500.0
+        );
+        // J4 is unscheduled.
+        assert_eq!(
+            value(ObjectiveTerm::ClassCompletion(PriorityClass::Normal)),
+            3500.0 + 7_200_000.0
+        );
+        assert_eq!(
+            class_weighted_completion(
+                &schedule,
+                [("J1", PriorityClass::Rush), ("J4", PriorityClass::Normal)]
+            ),
+            1500.0
+        );
+
+        let objective = Objective::makespan_tardiness(0.25);
+        assert_eq!(
+            objective.evaluate(&schedule, &tasks),
+            0.75 * value(ObjectiveTerm::Makespan) + 0.25 * value(ObjectiveTerm::WeightedTardiness)
+        );
+        assert_eq!(Objective::new().evaluate(&schedule, &tasks), 0.0);
+    }
+
+    #[test]
+    fn test_completion_terms() {
+        let (_, tasks) = setup();
+        let weights = WeightProfile::default();
+
+        assert!(ObjectiveTerm::Makespan
+            .completion_terms(&tasks, &weights)
+            .is_none());
+        assert_eq!(
+            ObjectiveTerm::WeightedTardiness.completion_terms(&tasks, &weights),
+            Some(vec![
+                ("J1_O1".to_string(), 10.0),
+                ("J2_O1".to_string(), 20.0),
+                ("J3_O1".to_string(), 2.0),
+            ])
+        );
+        assert_eq!(
+            ObjectiveTerm::ClassCompletion(PriorityClass::Rush).completion_terms(&tasks, &weights),
+            Some(vec![("J1_O1".to_string(), 1.0)])
+        );
+        let free = WeightProfile::new().with_default(0.0);
+        assert_eq!(
+            ObjectiveTerm::TardinessCost
+                .completion_terms(&tasks, &free)
+                .map(|terms| terms.len()),
+            Some(2)
+        );
+    }
+}
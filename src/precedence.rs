@@ -0,0 +1,401 @@
+//! Precedence closure: earliest and latest starts of activities.
+//!
+//! Before any resource is assigned, the precedence graph alone bounds
+//! when each activity can run. [`PrecedenceBounds`] propagates release
+//! times forward through the graph to each activity's earliest start
+//! (its *head*) and the horizon and deadlines backward to its latest
+//! start (its *tail*). Their difference is the activity's slack along
+//! its longest path, the "true" slack of the whole graph rather than of
+//! its task alone.
+//!
+//! The bounds serve three uses:
+//! - dispatching rules read the latest start of each task's next
+//!   activity (see
+//!   [`SchedulingContext::with_precedence_bounds`](crate::dispatching::SchedulingContext::with_precedence_bounds)),
+//! - the CP model narrows its start domains (see
+//!   [`ScheduleCpBuilder::with_bound_tightening`](crate::cp::ScheduleCpBuilder::with_bound_tightening)),
+//! - [`unattainable_deadlines`](PrecedenceBounds::unattainable_deadlines)
+//!   lists deadlines no schedule can meet.
+//!
+//! # Graph
+//!
+//! Activities of a chain follow each other through their
+//! [precedence mode](crate::models::PrecedenceMode) and lag; explicit
+//! [predecessors](crate::models::Activity::predecessors), also across
+//! tasks, must finish first. Durations are processing times: setups
+//! depend on the sequence on a machine and are not known in advance, so
+//! heads are lower and tails upper bounds of every schedule's times.
+//!
+//! # Reference
+//! Kelley & Walker (1959), "Critical-path planning and scheduling";
+//! Brucker et al. (1994), "A branch and bound algorithm for the job-shop
+//! scheduling problem" (heads and tails)
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Task;
+use crate::validation::{ValidationError, ValidationErrorKind};
+
+/// Time bounds of one activity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityBounds {
+    /// Earliest start (head, ms).
+    pub earliest_start_ms: i64,
+    /// Latest start that keeps every successor within the horizon and its
+    /// deadline (tail, ms).
+    pub latest_start_ms: i64,
+    /// Processing time (ms).
+    pub duration_ms: i64,
+}
+
+impl ActivityBounds {
+    /// Latest minus earliest start (ms); negative when the horizon or a
+    /// deadline cannot be met.
+    pub fn slack_ms(&self) -> i64 {
+        self.latest_start_ms - self.earliest_start_ms
+    }
+
+    /// Earliest end (ms).
+    pub fn earliest_end_ms(&self) -> i64 {
+        self.earliest_start_ms + self.duration_ms
+    }
+}
+
+/// A deadline the precedence graph alone makes impossible to meet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadlineConflict {
+    /// Task ID.
+    pub task_id: String,
+    /// The task's deadline (ms).
+    pub deadline_ms: i64,
+    /// Earliest possible completion of the task (ms).
+    pub earliest_completion_ms: i64,
+}
+
+/// Earliest and latest starts of all activities of a problem.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, Task};
+/// use u_schedule::precedence::PrecedenceBounds;
+///
+/// let task = Task::new("J1")
+///     .with_deadline(5000)
+///     .with_activity(Activity::new("O1", "J1", 0).with_process_time(1000))
+///     .with_activity(Activity::new("O2", "J1", 1).with_process_time(2000));
+/// let bounds = PrecedenceBounds::compute(&[task], 0, Some(10_000)).unwrap();
+///
+/// assert_eq!(bounds.earliest_start("O2"), Some(1000));
+/// // O2 must start by 3000 to meet the deadline, O1 by 2000.
+/// assert_eq!(bounds.latest_start("O1"), Some(2000));
+/// assert_eq!(bounds.slack("O2"), Some(2000));
+/// assert!(bounds.unattainable_deadlines().is_empty());
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrecedenceBounds {
+    /// Horizon the tails are computed against (ms).
+    pub horizon_ms: i64,
+    bounds: HashMap<String, ActivityBounds>,
+    /// Activity IDs, predecessors first.
+    order: Vec<String>,
+    /// Deadline and last activities of each task with a deadline.
+    deadlines: Vec<(String, i64, Vec<String>)>,
+}
+
+impl PrecedenceBounds {
+    /// Computes the bounds of `tasks` from `start_time_ms`.
+    ///
+    /// Every activity must end by `horizon_ms` and by its task's
+    /// deadline. Without a horizon, the earliest end of the whole graph
+    /// (the critical path) is used, so activities on it have zero slack
+    /// unless a deadline is tighter.
+    ///
+    /// # Errors
+    /// Returns a [`CyclicDependency`](ValidationErrorKind::CyclicDependency)
+    /// error if the precedences form a cycle.
+    pub fn compute(
+        tasks: &[Task],
+        start_time_ms: i64,
+        horizon_ms: Option<i64>,
+    ) -> Result<Self, ValidationError> {
+        Self::compute_with(tasks, start_time_ms, horizon_ms, true)
+    }
+
+    /// Like [`compute`](Self::compute); deadlines bound the tails only if
+    /// `deadlines` is set.
+    pub(crate) fn compute_with(
+        tasks: &[Task],
+        start_time_ms: i64,
+        horizon_ms: Option<i64>,
+        deadlines: bool,
+    ) -> Result<Self, ValidationError> {
+        // Nodes: (task, activity) per ID; the first of duplicate IDs wins
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut nodes = Vec::new();
+        for task in tasks {
+            for activity in &task.activities {
+                if !index.contains_key(activity.id.as_str()) {
+                    index.insert(&activity.id, nodes.len());
+                    nodes.push((task, activity));
+                }
+            }
+        }
+
+        // Edges: start(to) ≥ start(from) + offset
+        let mut successors: Vec<Vec<(usize, i64)>> = vec![Vec::new(); nodes.len()];
+        let mut indegree = vec![0usize; nodes.len()];
+        for task in tasks {
+            for (k, activity) in task.activities.iter().enumerate() {
+                let Some(&to) = index.get(activity.id.as_str()) else {
+                    continue;
+                };
+                if let Some(prev) = task.chained_predecessor(k) {
+                    let prev = &task.activities[prev];
+                    let offset = activity.precedence_mode.earliest_start(
+                        0,
+                        prev.duration.process_ms.max(0),
+                        activity.precedence_lag_ms,
+                        activity.duration.process_ms.max(0),
+                    );
+                    successors[index[prev.id.as_str()]].push((to, offset));
+                    indegree[to] += 1;
+                }
+                for pred in &activity.predecessors {
+                    if let Some(&from) = index.get(pred.as_str()) {
+                        let offset = nodes[from].1.duration.process_ms.max(0);
+                        successors[from].push((to, offset));
+                        indegree[to] += 1;
+                    }
+                }
+            }
+        }
+
+        // Topological order (Kahn), in input order among ready nodes
+        let mut order = Vec::with_capacity(nodes.len());
+        let mut ready: Vec<usize> = (0..nodes.len())
+            .rev()
+            .filter(|&n| indegree[n] == 0)
+            .collect();
+        while let Some(n) = ready.pop() {
+            order.push(n);
+            for &(to, _) in successors[n].iter().rev() {
+                indegree[to] -= 1;
+                if indegree[to] == 0 {
+                    ready.push(to);
+                }
+            }
+        }
+        if order.len() < nodes.len() {
+            let on_cycle = (0..nodes.len())
+                .find(|&n| indegree[n] > 0)
+                .map_or("", |n| nodes[n].1.id.as_str());
+            return Err(ValidationError::new(
+                ValidationErrorKind::CyclicDependency,
+                format!("Precedence cycle through activity '{on_cycle}'"),
+            ));
+        }
+
+        let duration = |n: usize| nodes[n].1.duration.process_ms.max(0);
+        let mut heads: Vec<i64> = nodes
+            .iter()
+            .map(|(task, _)| {
+                task.release_time
+                    .unwrap_or(start_time_ms)
+                    .max(start_time_ms)
+            })
+            .collect();
+        for &n in &order {
+            for &(to, offset) in &successors[n] {
+                heads[to] = heads[to].max(heads[n] + offset);
+            }
+        }
+
+        let horizon_ms = horizon_ms.unwrap_or_else(|| {
+            (0..nodes.len())
+                .map(|n| heads[n] + duration(n))
+                .max()
+                .unwrap_or(start_time_ms)
+        });
+        let mut tails: Vec<i64> = nodes
+            .iter()
+            .enumerate()
+            .map(|(n, (task, _))| {
+                let due = match task.deadline.filter(|_| deadlines) {
+                    Some(deadline) => deadline.min(horizon_ms),
+                    None => horizon_ms,
+                };
+                due - duration(n)
+            })
+            .collect();
+        for &n in order.iter().rev() {
+            for &(to, offset) in &successors[n] {
+                tails[n] = tails[n].min(tails[to] - offset);
+            }
+        }
+
+        let bounds = (0..nodes.len())
+            .map(|n| {
+                let bounds = ActivityBounds {
+                    earliest_start_ms: heads[n],
+                    latest_start_ms: tails[n],
+                    duration_ms: duration(n),
+                };
+                (nodes[n].1.id.clone(), bounds)
+            })
+            .collect();
+        let deadlines = tasks
+            .iter()
+            .filter_map(|t| {
+                let ids = t.activities.iter().map(|a| a.id.clone()).collect();
+                t.deadline.map(|d| (t.id.clone(), d, ids))
+            })
+            .collect();
+        Ok(Self {
+            horizon_ms,
+            bounds,
+            order: order.into_iter().map(|n| nodes[n].1.id.clone()).collect(),
+            deadlines,
+        })
+    }
+
+    /// Bounds of an activity.
+    pub fn get(&self, activity_id: &str) -> Option<&ActivityBounds> {
+        self.bounds.get(activity_id)
+    }
+
+    /// Earliest start of an activity (ms).
+    pub fn earliest_start(&self, activity_id: &str) -> Option<i64> {
+        self.get(activity_id).map(|b| b.earliest_start_ms)
+    }
+
+    /// Latest start of an activity (ms).
+    pub fn latest_start(&self, activity_id: &str) -> Option<i64> {
+        self.get(activity_id).map(|b| b.latest_start_ms)
+    }
+
+    /// Slack of an activity (ms).
+    pub fn slack(&self, activity_id: &str) -> Option<i64> {
+        self.get(activity_id).map(ActivityBounds::slack_ms)
+    }
+
+    /// Activity IDs, every activity after its predecessors.
+    pub fn topological_order(&self) -> &[String] {
+        &self.order
+    }
+
+    /// Activities with the least slack, in topological order: the
+    /// critical path (or paths) against the horizon and deadlines.
+    pub fn critical_activities(&self) -> Vec<&str> {
+        let least = self.bounds.values().map(ActivityBounds::slack_ms).min();
+        self.order
+            .iter()
+            .filter(|id| Some(self.bounds[id.as_str()].slack_ms()) == least)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Earliest end of the whole graph (ms): a lower bound of the
+    /// makespan of every schedule.
+    pub fn earliest_end_ms(&self) -> Option<i64> {
+        self.bounds
+            .values()
+            .map(ActivityBounds::earliest_end_ms)
+            .max()
+    }
+
+    /// Tasks whose deadline precedes their earliest completion, so no
+    /// schedule meets it.
+    pub fn unattainable_deadlines(&self) -> Vec<DeadlineConflict> {
+        self.deadlines
+            .iter()
+            .filter_map(|(task_id, deadline_ms, ids)| {
+                let earliest_completion_ms = ids
+                    .iter()
+                    .filter_map(|id| self.bounds.get(id))
+                    .map(ActivityBounds::earliest_end_ms)
+                    .max()?;
+                (earliest_completion_ms > *deadline_ms).then(|| DeadlineConflict {
+                    task_id: task_id.clone(),
+                    deadline_ms: *deadline_ms,
+                    earliest_completion_ms,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, PrecedenceMode};
+
+    fn op(id: &str, task: &str, seq: i32, ms: i64) -> Activity {
+        Activity::new(id, task, seq).with_process_time(ms)
+    }
+
+    #[test]
+    fn test_heads_tails_across_tasks() {
+        // J1: A (1000) → B (2000); J2: C (500, released at 300) waits for
+        // A, and D (1000) starts with C.
+        let tasks = vec![
+            Task::new("J1")
+                .with_activity(op("A", "J1", 0, 1000))
+                .with_activity(op("B", "J1", 1, 2000)),
+            Task::new("J2")
+                .with_release_time(300)
+                .with_deadline(2500)
+                .with_activity(op("C", "J2", 0, 500).with_predecessor("A"))
+                .with_activity(
+                    op("D", "J2", 1, 1000).with_precedence_mode(PrecedenceMode::StartToStart, 0),
+                ),
+        ];
+        let bounds = PrecedenceBounds::compute(&tasks, 0, None).unwrap();
+        let heads: Vec<i64> = ["A", "B", "C", "D"]
+            .iter()
+            .map(|id| bounds.earliest_start(id).unwrap())
+            .collect();
+        assert_eq!(heads, [0, 1000, 1000, 1000]);
+        // Critical path A → B ends at 3000; J2's deadline gives D 1500.
+        assert_eq!(bounds.horizon_ms, 3000);
+        assert_eq!(bounds.earliest_end_ms(), Some(3000));
+        assert_eq!(bounds.latest_start("D"), Some(1500));
+        assert_eq!(bounds.latest_start("C"), Some(1500));
+        // A's tail: min(1000 − 1000 via B, 1500 − 1000 via C).
+        assert_eq!(bounds.latest_start("A"), Some(0));
+        assert_eq!(bounds.critical_activities(), ["A", "B"]);
+        assert_eq!(bounds.slack("D"), Some(500));
+        assert!(bounds.unattainable_deadlines().is_empty());
+        assert_eq!(bounds.topological_order().len(), 4);
+
+        // A deadline the chain cannot meet.
+        let mut late = tasks.clone();
+        late[1].deadline = Some(1200);
+        let bounds = PrecedenceBounds::compute(&late, 0, Some(10_000)).unwrap();
+        assert_eq!(
+            bounds.unattainable_deadlines(),
+            [DeadlineConflict {
+                task_id: "J2".into(),
+                deadline_ms: 1200,
+                earliest_completion_ms: 2000,
+            }]
+        );
+        assert!(bounds.slack("C").unwrap() < 0);
+        assert_eq!(bounds.critical_activities(), ["A", "C", "D"]);
+        // Without deadlines, tails only follow the horizon.
+        let relaxed = PrecedenceBounds::compute_with(&late, 0, Some(10_000), false).unwrap();
+        assert_eq!(relaxed.latest_start("D"), Some(9000));
+    }
+
+    #[test]
+    fn test_cycle_rejected() {
+        let tasks = vec![
+            Task::new("J1").with_activity(op("A", "J1", 0, 100).with_predecessor("B")),
+            Task::new("J2").with_activity(op("B", "J2", 0, 100).with_predecessor("A")),
+        ];
+        let err = PrecedenceBounds::compute(&tasks, 0, None).unwrap_err();
+        assert_eq!(err.kind, ValidationErrorKind::CyclicDependency);
+    }
+}
@@ -0,0 +1,299 @@
+//! Python bindings (feature `python`).
+//!
+//! Exposes problem construction, the greedy scheduler, rule engines,
+//! validation and KPIs as the `u_schedule` Python module, so that
+//! experiments can run from Python without a Rust toolchain.
+//!
+//! # Build
+//!
+//! ```text
+//! maturin develop
+//! ```
+//!
+//! `pyproject.toml` enables the `python` and `extension-module` features;
+//! maturin passes `--crate-type cdylib` itself, as `Cargo.toml` declares
+//! no `cdylib` (it would not link without `std`).
+//!
+//! # Example (Python)
+//!
+//! ```text
+//! import u_schedule as us
+//!
+//! p = us.Problem()
+//! p.add_resource("M1")
+//! p.add_task("J1", [(1000, ["M1"])], deadline_ms=5000)
+//! s = us.solve_greedy(p, us.RuleEngine(["EDD", "SPT"]))
+//! print(s.makespan_ms, us.kpi(s, p)["total_tardiness_ms"])
+//! ```
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::dispatching::{
+    EvaluationMode, RuleEngine, RuleEngineSpec, RuleSpec, TieBreaker, WeightedRuleSpec,
+};
+use crate::models::{
+    Activity, ActivityDuration, Resource, ResourceRequirement, Schedule, Task, TransitionMatrix,
+};
+use crate::scheduler::{ScheduleKpi, ScheduleRequest, SimpleScheduler};
+use crate::validation::validate_input;
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+/// A scheduling problem (tasks, resources, setups).
+#[pyclass(name = "Problem")]
+#[derive(Clone)]
+pub struct PyProblem {
+    request: ScheduleRequest,
+}
+
+#[pymethods]
+impl PyProblem {
+    #[new]
+    #[pyo3(signature = (start_time_ms = 0))]
+    fn new(start_time_ms: i64) -> Self {
+        Self {
+            request: ScheduleRequest::new(Vec::new(), Vec::new()).with_start_time(start_time_ms),
+        }
+    }
+
+    /// Parses a problem from the JSON instance format.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        let request = serde_json::from_str(json).map_err(value_error)?;
+        Ok(Self { request })
+    }
+
+    /// Serializes the problem to the JSON instance format.
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.request).map_err(value_error)
+    }
+
+    /// Adds a primary resource.
+    #[pyo3(signature = (id, capacity = 1))]
+    fn add_resource(&mut self, id: &str, capacity: i32) {
+        self.request
+            .resources
+            .push(Resource::primary(id).with_capacity(capacity));
+    }
+
+    /// Adds a task with operations given as `(process_ms, [candidate ids])`.
+    #[pyo3(signature = (id, operations, priority = 0, deadline_ms = None, release_ms = None, category = None))]
+    fn add_task(
+        &mut self,
+        id: &str,
+        operations: Vec<(i64, Vec<String>)>,
+        priority: i32,
+        deadline_ms: Option<i64>,
+        release_ms: Option<i64>,
+        category: Option<String>,
+    ) {
+        let mut task = Task::new(id).with_priority(priority);
+        task.deadline = deadline_ms;
+        task.release_time = release_ms;
+        if let Some(category) = category {
+            task.category = category;
+        }
+        for (seq, (process_ms, candidates)) in operations.into_iter().enumerate() {
+            task = task.with_activity(
+                Activity::new(format!("{id}_O{}", seq + 1), id, seq as i32)
+                    .with_duration(ActivityDuration::fixed(process_ms))
+                    .with_requirement(
+                        ResourceRequirement::new("Machine").with_candidates(candidates),
+                    ),
+            );
+        }
+        self.request.tasks.push(task);
+    }
+
+    /// Sets a sequence-dependent setup time between task categories.
+    fn add_setup(
+        &mut self,
+        resource_id: &str,
+        from_category: &str,
+        to_category: &str,
+        time_ms: i64,
+    ) {
+        let mut matrix = self
+            .request
+            .transition_matrices
+            .get(resource_id)
+            .cloned()
+            .unwrap_or_else(|| TransitionMatrix::new(resource_id, resource_id));
+        matrix.set_transition(from_category, to_category, time_ms);
+        self.request.transition_matrices.add(matrix);
+    }
+
+    #[getter]
+    fn task_count(&self) -> usize {
+        self.request.tasks.len()
+    }
+
+    #[getter]
+    fn resource_count(&self) -> usize {
+        self.request.resources.len()
+    }
+}
+
+/// A dispatching rule engine.
+#[pyclass(name = "RuleEngine")]
+#[derive(Clone)]
+pub struct PyRuleEngine {
+    spec: RuleEngineSpec,
+}
+
+#[pymethods]
+impl PyRuleEngine {
+    /// Creates an engine from rule names (e.g. `["EDD", "SPT"]`).
+    ///
+    /// `mode` is `"sequential"` or `"weighted"`; `weights` apply in
+    /// weighted mode and default to 1.0.
+    #[new]
+    #[pyo3(signature = (rules, mode = "sequential", weights = None))]
+    fn new(rules: Vec<String>, mode: &str, weights: Option<Vec<f64>>) -> PyResult<Self> {
+        let mode = match mode.to_ascii_lowercase().as_str() {
+            "sequential" => EvaluationMode::Sequential,
+            "weighted" => EvaluationMode::Weighted,
+            other => return Err(value_error(format!("unknown mode `{other}`"))),
+        };
+        let weights = weights.unwrap_or_else(|| vec![1.0; rules.len()]);
+        if weights.len() != rules.len() {
+            return Err(value_error("weights must match rules in length"));
+        }
+        let rules = rules
+            .iter()
+            .zip(weights)
+            .map(|(name, weight)| Ok(WeightedRuleSpec::new(parse_rule(name)?).with_weight(weight)))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Self {
+            spec: RuleEngineSpec {
+                mode,
+                rules,
+                tie_breakers: Vec::new(),
+                final_tie_breaker: TieBreaker::default(),
+                epsilon: None,
+            },
+        })
+    }
+
+    /// Parses an engine from its JSON spec.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        let spec = serde_json::from_str(json).map_err(value_error)?;
+        Ok(Self { spec })
+    }
+
+    /// Serializes the engine spec to JSON.
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.spec).map_err(value_error)
+    }
+}
+
+fn parse_rule(name: &str) -> PyResult<RuleSpec> {
+    let tagged = serde_json::json!({ "rule": name.to_ascii_uppercase() });
+    serde_json::from_value(tagged).map_err(|_| value_error(format!("unknown rule `{name}`")))
+}
+
+/// A computed schedule.
+#[pyclass(name = "Schedule")]
+#[derive(Clone)]
+pub struct PySchedule {
+    schedule: Schedule,
+}
+
+#[pymethods]
+impl PySchedule {
+    #[getter]
+    fn makespan_ms(&self) -> i64 {
+        self.schedule.makespan_ms()
+    }
+
+    /// Assignments as a list of dicts (one row per activity).
+    #[getter]
+    fn assignments<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.schedule
+            .assignments
+            .iter()
+            .map(|a| {
+                let row = PyDict::new(py);
+                row.set_item("activity_id", &a.activity_id)?;
+                row.set_item("task_id", &a.task_id)?;
+                row.set_item("resource_id", &a.resource_id)?;
+                row.set_item("start_ms", a.start_ms)?;
+                row.set_item("end_ms", a.end_ms)?;
+                row.set_item("setup_ms", a.setup_ms)?;
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// Stable fingerprint of the assignments.
+    fn fingerprint(&self) -> u64 {
+        self.schedule.fingerprint()
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.schedule).map_err(value_error)
+    }
+
+    fn __len__(&self) -> usize {
+        self.schedule.assignment_count()
+    }
+}
+
+/// Runs the greedy scheduler, optionally ordering tasks with a rule engine.
+#[pyfunction]
+#[pyo3(signature = (problem, engine = None))]
+fn solve_greedy(problem: &PyProblem, engine: Option<&PyRuleEngine>) -> PySchedule {
+    let mut scheduler = SimpleScheduler::new();
+    if let Some(engine) = engine {
+        scheduler = scheduler.with_rule_engine(RuleEngine::from_spec(&engine.spec));
+    }
+    PySchedule {
+        schedule: scheduler.schedule_request(&problem.request),
+    }
+}
+
+/// Validates the problem; returns a list of error messages (empty = valid).
+#[pyfunction]
+fn validate(problem: &PyProblem) -> Vec<String> {
+    match validate_input(&problem.request.tasks, &problem.request.resources) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.into_iter().map(|e| e.message).collect(),
+    }
+}
+
+/// Computes schedule KPIs as a dict.
+#[pyfunction]
+fn kpi<'py>(
+    py: Python<'py>,
+    schedule: &PySchedule,
+    problem: &PyProblem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let kpi = ScheduleKpi::calculate(&schedule.schedule, &problem.request.tasks);
+    let dict = PyDict::new(py);
+    dict.set_item("makespan_ms", kpi.makespan_ms)?;
+    dict.set_item("total_tardiness_ms", kpi.total_tardiness_ms)?;
+    dict.set_item("max_tardiness_ms", kpi.max_tardiness_ms)?;
+    dict.set_item("on_time_rate", kpi.on_time_rate)?;
+    dict.set_item("avg_utilization", kpi.avg_utilization)?;
+    dict.set_item("avg_flow_time_ms", kpi.avg_flow_time_ms)?;
+    dict.set_item("utilization_by_resource", kpi.utilization_by_resource)?;
+    Ok(dict)
+}
+
+/// The `u_schedule` Python module.
+#[pymodule]
+#[pyo3(name = "u_schedule")]
+fn u_schedule_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyProblem>()?;
+    m.add_class::<PyRuleEngine>()?;
+    m.add_class::<PySchedule>()?;
+    m.add_function(wrap_pyfunction!(solve_greedy, m)?)?;
+    m.add_function(wrap_pyfunction!(validate, m)?)?;
+    m.add_function(wrap_pyfunction!(kpi, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,432 @@
+//! Time-bucketed aggregate planning.
+//!
+//! Before sequencing anything, a planner wants to know whether the order
+//! book fits: which weeks are full, which groups are the bottleneck, and
+//! which orders cannot make their due date. [`AggregatePlanner`] answers
+//! that in one pass by pouring each task's workload into capacity
+//! buckets per resource group, without sequencing, setups or
+//! single-resource conflicts.
+//!
+//! # Model
+//!
+//! | Term | Definition |
+//! |------|-----------|
+//! | Group | Resources an activity may use, keyed by its first requirement's `resource_type`; members are the resolved candidates of all activities of the group |
+//! | Capacity | `Σ available calendar time × capacity × efficiency` of the members, per bucket |
+//! | Load | Activity workload ([`ActivityDuration::total_ms`](crate::models::ActivityDuration::total_ms)) allocated to the bucket |
+//!
+//! Tasks are taken in greedy dispatch order (priority class, then flow
+//! deadline, then priority). An activity fills free group capacity
+//! forward from the later of its task's release bucket and the bucket in
+//! which its previous activity finished; work may spill over several
+//! buckets. Precedences across tasks are ignored. Work that does not fit
+//! within the horizon is reported as overflow.
+//!
+//! # Seeding the Detailed Scheduler
+//!
+//! [`AggregatePlan::seed_release_dates`] releases each task no earlier
+//! than the start of its first bucket, so the detailed scheduler spreads
+//! work as the aggregate plan did instead of front-loading the horizon.
+//!
+//! # Reference
+//! Vollmann et al. (2005), "Manufacturing Planning and Control for Supply
+//! Chain Management", Ch. 6: Rough-cut capacity planning
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::SimpleScheduler;
+use crate::models::{Resource, Task};
+
+const WEEK_MS: i64 = 7 * 86_400_000;
+
+/// Workload and capacity of one group in one bucket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupLoad {
+    /// Resource group (requirement type).
+    pub group: String,
+    /// Bucket index.
+    pub bucket: usize,
+    /// Bucket start (ms).
+    pub start_ms: i64,
+    /// Effective capacity (ms).
+    pub capacity_ms: i64,
+    /// Allocated workload (ms).
+    pub load_ms: i64,
+}
+
+impl GroupLoad {
+    /// Load over capacity (0.0 for a bucket without capacity).
+    pub fn utilization(&self) -> f64 {
+        if self.capacity_ms > 0 {
+            self.load_ms as f64 / self.capacity_ms as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Buckets a task's workload was allocated to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskBuckets {
+    /// Task ID.
+    pub task_id: String,
+    /// First bucket with work of the task (`None` for tasks without work).
+    pub start_bucket: Option<usize>,
+    /// Bucket in which the last work finishes (`None` if it overflows the
+    /// horizon or there is no work).
+    pub finish_bucket: Option<usize>,
+    /// Whether the finish bucket ends after the deadline, or the work
+    /// overflows the horizon of a task with a deadline.
+    pub late: bool,
+}
+
+/// Result of [`AggregatePlanner::plan`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregatePlan {
+    /// Bucket length (ms).
+    pub bucket_ms: i64,
+    /// Start of bucket 0 (ms).
+    pub origin_ms: i64,
+    /// Load per group and bucket, by group then bucket.
+    pub loads: Vec<GroupLoad>,
+    /// Allocation per task, in input order.
+    pub tasks: Vec<TaskBuckets>,
+    /// Workload beyond the horizon per group (ms); groups without
+    /// overflow are omitted.
+    pub overflow_ms: BTreeMap<String, i64>,
+}
+
+impl AggregatePlan {
+    /// Start of bucket `index` (ms).
+    pub fn bucket_start(&self, index: usize) -> i64 {
+        self.origin_ms + index as i64 * self.bucket_ms
+    }
+
+    /// Allocation of a task.
+    pub fn task(&self, task_id: &str) -> Option<&TaskBuckets> {
+        self.tasks.iter().find(|t| t.task_id == task_id)
+    }
+
+    /// Loads of one group, by bucket.
+    pub fn group(&self, group: &str) -> Vec<&GroupLoad> {
+        self.loads.iter().filter(|l| l.group == group).collect()
+    }
+
+    /// IDs of tasks that miss their deadline at bucket granularity.
+    pub fn late_tasks(&self) -> Vec<&str> {
+        self.tasks
+            .iter()
+            .filter(|t| t.late)
+            .map(|t| t.task_id.as_str())
+            .collect()
+    }
+
+    /// Whether all work fits within the horizon and no task is late.
+    pub fn is_feasible(&self) -> bool {
+        self.overflow_ms.is_empty() && self.tasks.iter().all(|t| !t.late)
+    }
+
+    /// Releases each planned task no earlier than its start bucket.
+    ///
+    /// Tasks not in the plan or without work keep their release time.
+    pub fn seed_release_dates(&self, tasks: &mut [Task]) {
+        let starts: HashMap<&str, usize> = self
+            .tasks
+            .iter()
+            .filter_map(|t| Some((t.task_id.as_str(), t.start_bucket?)))
+            .collect();
+        for task in tasks {
+            if let Some(&bucket) = starts.get(task.id.as_str()) {
+                let start = self.bucket_start(bucket);
+                task.release_time = Some(task.release_time.unwrap_or(start).max(start));
+            }
+        }
+    }
+}
+
+/// Rough-cut capacity planner over fixed time buckets.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, Resource, ResourceRequirement, Task};
+/// use u_schedule::scheduler::AggregatePlanner;
+///
+/// const HOUR: i64 = 3_600_000;
+/// let mut tasks: Vec<Task> = (0..3)
+///     .map(|i| {
+///         let id = format!("J{i}");
+///         Task::new(&id).with_activity(
+///             Activity::new(format!("{id}_O1"), &id, 0)
+///                 .with_process_time(100 * HOUR)
+///                 .with_requirement(ResourceRequirement::new("Primary")),
+///         )
+///     })
+///     .collect();
+/// let resources = vec![Resource::primary("M1")];
+///
+/// // One machine offers 168 h a week: the third job starts in week 2.
+/// let plan = AggregatePlanner::weekly().with_buckets(4).plan(&tasks, &resources);
+/// assert_eq!(plan.task("J2").unwrap().start_bucket, Some(1));
+/// plan.seed_release_dates(&mut tasks);
+/// assert_eq!(tasks[2].release_time, Some(plan.bucket_start(1)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AggregatePlanner {
+    /// Bucket length (ms).
+    pub bucket_ms: i64,
+    /// Start of bucket 0 (ms).
+    pub origin_ms: i64,
+    /// Number of buckets in the horizon.
+    pub buckets: usize,
+}
+
+impl AggregatePlanner {
+    /// Creates a planner with `bucket_ms` buckets from t=0 over 52 buckets.
+    pub fn new(bucket_ms: i64) -> Self {
+        Self {
+            bucket_ms: bucket_ms.max(1),
+            origin_ms: 0,
+            buckets: 52,
+        }
+    }
+
+    /// Creates a planner with weekly buckets from t=0 over one year.
+    pub fn weekly() -> Self {
+        Self::new(WEEK_MS)
+    }
+
+    /// Sets the start of the first bucket.
+    pub fn with_origin(mut self, origin_ms: i64) -> Self {
+        self.origin_ms = origin_ms;
+        self
+    }
+
+    /// Sets the number of buckets in the horizon.
+    pub fn with_buckets(mut self, buckets: usize) -> Self {
+        self.buckets = buckets;
+        self
+    }
+
+    /// Allocates the workload of `tasks` to group capacity buckets.
+    pub fn plan(&self, tasks: &[Task], resources: &[Resource]) -> AggregatePlan {
+        // Group of each activity, and the members of each group.
+        let mut members: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for activity in tasks.iter().flat_map(|t| &t.activities) {
+            if let Some(req) = activity.resource_requirements.first() {
+                members
+                    .entry(req.resource_type.as_str())
+                    .or_default()
+                    .extend(req.resolve(resources));
+            }
+        }
+        let by_id: HashMap<&str, &Resource> =
+            resources.iter().map(|r| (r.id.as_str(), r)).collect();
+        let mut capacity: BTreeMap<&str, Vec<i64>> = BTreeMap::new();
+        for (&group, ids) in &members {
+            let buckets = (0..self.buckets)
+                .map(|b| {
+                    ids.iter()
+                        .filter_map(|id| by_id.get(id))
+                        .map(|r| self.resource_capacity(r, b))
+                        .sum()
+                })
+                .collect();
+            capacity.insert(group, buckets);
+        }
+
+        let mut load: BTreeMap<&str, Vec<i64>> = capacity
+            .keys()
+            .map(|&g| (g, vec![0; self.buckets]))
+            .collect();
+        let mut overflow: BTreeMap<String, i64> = BTreeMap::new();
+        let mut allocations: Vec<TaskBuckets> = tasks
+            .iter()
+            .map(|t| TaskBuckets {
+                task_id: t.id.clone(),
+                start_bucket: None,
+                finish_bucket: None,
+                late: false,
+            })
+            .collect();
+
+        for i in SimpleScheduler::new().sort_tasks(tasks, self.origin_ms) {
+            let task = &tasks[i];
+            let mut activities: Vec<_> = task.activities.iter().collect();
+            activities.sort_by_key(|a| a.sequence);
+
+            let mut earliest = task.release_time.map_or(0, |r| self.bucket_index(r));
+            let mut start = None;
+            let mut overflowed = false;
+            for activity in activities {
+                let Some(req) = activity.resource_requirements.first() else {
+                    continue;
+                };
+                let group = req.resource_type.as_str();
+                let mut remaining = activity.duration.total_ms();
+                if remaining <= 0 {
+                    continue;
+                }
+                let (cap, used) = (&capacity[group], load.get_mut(group).expect("group"));
+                let mut bucket = earliest;
+                while bucket < self.buckets {
+                    let take = (cap[bucket] - used[bucket]).min(remaining);
+                    if take > 0 {
+                        used[bucket] += take;
+                        remaining -= take;
+                        start.get_or_insert(bucket);
+                    }
+                    if remaining == 0 {
+                        break;
+                    }
+                    bucket += 1;
+                }
+                if remaining > 0 {
+                    *overflow.entry(group.to_string()).or_default() += remaining;
+                    overflowed = true;
+                }
+                earliest = bucket;
+            }
+
+            let allocation = &mut allocations[i];
+            allocation.start_bucket = start;
+            allocation.finish_bucket = (start.is_some() && !overflowed).then_some(earliest);
+            allocation.late = match (task.deadline, allocation.finish_bucket) {
+                (Some(deadline), Some(b)) => self.bucket_start(b + 1) > deadline,
+                (Some(_), None) => overflowed,
+                (None, _) => false,
+            };
+        }
+
+        let loads = capacity
+            .iter()
+            .flat_map(|(&group, cap)| {
+                let used = &load[group];
+                (0..self.buckets).map(move |b| GroupLoad {
+                    group: group.to_string(),
+                    bucket: b,
+                    start_ms: self.bucket_start(b),
+                    capacity_ms: cap[b],
+                    load_ms: used[b],
+                })
+            })
+            .collect();
+
+        AggregatePlan {
+            bucket_ms: self.bucket_ms,
+            origin_ms: self.origin_ms,
+            loads,
+            tasks: allocations,
+            overflow_ms: overflow,
+        }
+    }
+
+    fn bucket_start(&self, index: usize) -> i64 {
+        self.origin_ms + index as i64 * self.bucket_ms
+    }
+
+    /// Bucket containing `time_ms` (0 for times before the origin).
+    fn bucket_index(&self, time_ms: i64) -> usize {
+        (time_ms - self.origin_ms).div_euclid(self.bucket_ms).max(0) as usize
+    }
+
+    /// Effective capacity of one resource in one bucket (ms).
+    fn resource_capacity(&self, resource: &Resource, bucket: usize) -> i64 {
+        let start = self.bucket_start(bucket);
+        let end = start + self.bucket_ms;
+        let available = resource
+            .calendar
+            .as_ref()
+            .map_or(self.bucket_ms, |c| c.available_time_in_range(start, end));
+        (available as f64 * resource.capacity.max(0) as f64 * resource.efficiency.max(0.0)) as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, Calendar, ResourceRequirement};
+
+    const HOUR: i64 = 3_600_000;
+
+    fn job(id: &str, steps: &[(&str, i64)]) -> Task {
+        steps
+            .iter()
+            .enumerate()
+            .fold(Task::new(id), |task, (i, &(group, hours))| {
+                task.with_activity(
+                    Activity::new(format!("{id}_O{i}"), id, i as i32)
+                        .with_process_time(hours * HOUR)
+                        .with_requirement(ResourceRequirement::new(group)),
+                )
+            })
+    }
+
+    #[test]
+    fn test_buckets_fill_forward() {
+        let tasks = vec![
+            job("J1", &[("Primary", 30), ("Human", 10)]),
+            job("J2", &[("Primary", 30), ("Human", 10)]).with_deadline(48 * HOUR),
+            job("J3", &[("Human", 10)]).with_release_time(30 * HOUR),
+        ];
+        // Two machines, one operator; daily buckets.
+        let resources = vec![
+            Resource::primary("M1"),
+            Resource::primary("M2").with_efficiency(0.5),
+            Resource::human("W1"),
+        ];
+        let plan = AggregatePlanner::new(24 * HOUR)
+            .with_buckets(3)
+            .plan(&tasks, &resources);
+
+        // Machines offer 24 h + 12 h a day.
+        let primary = plan.group("Primary");
+        assert_eq!(primary[0].capacity_ms, 36 * HOUR);
+        let load: Vec<i64> = primary.iter().map(|l| l.load_ms / HOUR).collect();
+        assert_eq!(load, vec![36, 24, 0]);
+        assert!((primary[0].utilization() - 1.0).abs() < 1e-9);
+
+        // J1 fits in day 0; J2 spills into day 1, where its second step
+        // and the released J3 follow, and still meets its deadline.
+        let buckets = |id| {
+            let t = plan.task(id).unwrap();
+            (t.start_bucket, t.finish_bucket, t.late)
+        };
+        assert_eq!(buckets("J1"), (Some(0), Some(0), false));
+        assert_eq!(buckets("J2"), (Some(0), Some(1), false));
+        assert_eq!(buckets("J3"), (Some(1), Some(1), false));
+        let human: Vec<i64> = plan
+            .group("Human")
+            .iter()
+            .map(|l| l.load_ms / HOUR)
+            .collect();
+        assert_eq!(human, vec![10, 20, 0]);
+        assert!(plan.is_feasible());
+    }
+
+    #[test]
+    fn test_overflow_and_lateness() {
+        let tasks = vec![
+            job("J1", &[("Primary", 40)]).with_deadline(24 * HOUR),
+            job("J2", &[("Primary", 40)]),
+        ];
+        let calendar = Calendar::new("day").with_window(0, 8 * HOUR);
+        let resources = vec![Resource::primary("M1").with_calendar(calendar)];
+        let plan = AggregatePlanner::new(24 * HOUR)
+            .with_buckets(2)
+            .plan(&tasks, &resources);
+
+        // 8 h of capacity in total: J1 overflows and is late; J2 gets nothing.
+        assert_eq!(plan.overflow_ms["Primary"], 72 * HOUR);
+        assert_eq!(plan.late_tasks(), vec!["J1"]);
+        assert_eq!(plan.task("J2").unwrap().start_bucket, None);
+        assert!(!plan.is_feasible());
+
+        let mut seeded = tasks.clone();
+        plan.seed_release_dates(&mut seeded);
+        assert_eq!(seeded[0].release_time, Some(0));
+        assert_eq!(seeded[1].release_time, None);
+    }
+}
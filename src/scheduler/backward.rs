@@ -0,0 +1,298 @@
+//! Backward (due-date driven) scheduling.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use super::ScheduleRequest;
+use crate::models::{subtract_windows, Assignment, Schedule, Task, TimeWindow};
+use crate::validation::check_schedule;
+
+/// Result of a backward pass.
+#[derive(Debug, Clone)]
+pub struct BackwardPlan {
+    /// The as-late-as-possible schedule.
+    pub schedule: Schedule,
+    /// Latest start of each scheduled activity (ms): its start in the
+    /// backward schedule.
+    pub latest_starts: HashMap<String, i64>,
+    /// Tasks whose latest start is before their release time (or the
+    /// request start), or that could not be placed: their deadline
+    /// cannot be met.
+    pub infeasible: Vec<String>,
+}
+
+impl BackwardPlan {
+    /// Latest start of an activity, if it was scheduled.
+    pub fn latest_start(&self, activity_id: &str) -> Option<i64> {
+        self.latest_starts.get(activity_id).copied()
+    }
+
+    /// Whether every task can start within its release time.
+    pub fn is_feasible(&self) -> bool {
+        self.infeasible.is_empty()
+    }
+}
+
+/// Schedules activities as late as possible, backwards from deadlines.
+///
+/// The mirror image of [`SimpleScheduler`](super::SimpleScheduler) for
+/// just-in-time plans: work finishes right at its due date instead of as
+/// early as possible, and the start of each activity is its latest start.
+///
+/// # Algorithm
+/// 1. Order tasks by priority class (highest first), then by deadline,
+///    latest first, then by priority.
+/// 2. For each task, place activities from last to first. The last ends
+///    by the task's deadline (the horizon for undated tasks); each
+///    earlier one ends early enough for its successor's precedence mode
+///    and lag.
+/// 3. Each activity takes the candidate resource on which it can start
+///    latest, in the latest free stretch of the resource's calendar that
+///    fits its processing time before that bound.
+///
+/// Resources are unary and processing times are
+/// [`process_ms`](crate::models::ActivityDuration::process_ms). Unlike
+/// the forward [`SimpleScheduler`](super::SimpleScheduler), the backward
+/// pass ignores sequence-dependent setups and locks. Explicit
+/// predecessors in other tasks bound an activity only if the successor's
+/// task was placed first.
+#[derive(Debug, Clone, Default)]
+pub struct BackwardScheduler {
+    horizon_ms: Option<i64>,
+}
+
+impl BackwardScheduler {
+    /// Creates a backward scheduler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the due time of undated tasks (default: the latest deadline,
+    /// or the request start plus all processing time if no task has one).
+    pub fn with_horizon(mut self, horizon_ms: i64) -> Self {
+        self.horizon_ms = Some(horizon_ms);
+        self
+    }
+
+    /// Runs the backward pass.
+    pub fn schedule_request(&self, request: &ScheduleRequest) -> BackwardPlan {
+        let (tasks, resources) = (&request.tasks, &request.resources);
+        let horizon = self.horizon_ms.unwrap_or_else(|| {
+            tasks
+                .iter()
+                .filter_map(|t| t.deadline)
+                .max()
+                .unwrap_or_else(|| {
+                    request.start_time_ms
+                        + tasks
+                            .iter()
+                            .flat_map(|t| &t.activities)
+                            .map(|a| a.duration.process_ms.max(0))
+                            .sum::<i64>()
+                })
+        });
+        let available: HashMap<&str, Vec<TimeWindow>> = resources
+            .iter()
+            .map(|r| {
+                let windows = match &r.calendar {
+                    Some(calendar) => calendar.available_intervals(),
+                    None => vec![TimeWindow::new(i64::MIN, i64::MAX)],
+                };
+                (r.id.as_str(), windows)
+            })
+            .collect();
+
+        let mut order: Vec<&Task> = tasks.iter().collect();
+        order.sort_by_key(|t| {
+            (
+                t.priority_class,
+                Reverse(t.deadline.unwrap_or(horizon)),
+                Reverse(t.priority),
+            )
+        });
+
+        let mut busy: HashMap<&str, Vec<TimeWindow>> = HashMap::new();
+        // Placed activity → start, for explicit predecessors.
+        let mut placed: HashMap<&str, i64> = HashMap::new();
+        let mut schedule = Schedule::new();
+        let mut infeasible = Vec::new();
+
+        for task in order {
+            let release = task
+                .release_time
+                .unwrap_or(request.start_time_ms)
+                .max(request.start_time_ms);
+            let mut activities: Vec<_> = task.activities.iter().collect();
+            activities.sort_by_key(|a| a.sequence);
+
+            let mut bound = task.deadline.unwrap_or(horizon);
+            let mut feasible = true;
+            for (k, activity) in activities.iter().enumerate().rev() {
+                let duration = activity.duration.process_ms.max(0);
+                let mut latest_end = bound;
+                for (succ, start) in tasks
+                    .iter()
+                    .flat_map(|t| &t.activities)
+                    .filter(|s| s.predecessors.contains(&activity.id))
+                    .filter_map(|s| placed.get(s.id.as_str()).map(|&start| (s, start)))
+                {
+                    let delay = succ.precedence_mode.end_to_start_delay(
+                        succ.precedence_lag_ms,
+                        duration,
+                        succ.duration.process_ms,
+                    );
+                    latest_end = latest_end.min(start - delay);
+                }
+
+                let best = activity
+                    .resolved_candidates(resources)
+                    .into_iter()
+                    .filter_map(|id| {
+                        let taken = busy.get(id).map(Vec::as_slice).unwrap_or(&[]);
+                        let free = subtract_windows(&available[id], taken);
+                        latest_fit(&free, latest_end, duration).map(|start| (start, id))
+                    })
+                    .max_by_key(|&(start, _)| start);
+                let Some((start, resource_id)) = best else {
+                    feasible = false;
+                    break;
+                };
+
+                let end = start + duration;
+                schedule.add_assignment(Assignment::new(
+                    &activity.id,
+                    &task.id,
+                    resource_id,
+                    start,
+                    end,
+                ));
+                let taken = busy.entry(resource_id).or_default();
+                let at = taken.partition_point(|w| w.start_ms < start);
+                taken.insert(at, TimeWindow::new(start, end));
+                placed.insert(&activity.id, start);
+                if start < release {
+                    feasible = false;
+                }
+
+                bound = match k.checked_sub(1).map(|p| activities[p]) {
+                    Some(previous) => {
+                        start
+                            - activity.precedence_mode.end_to_start_delay(
+                                activity.precedence_lag_ms,
+                                previous.duration.process_ms.max(0),
+                                duration,
+                            )
+                    }
+                    None => start,
+                };
+            }
+            if !feasible {
+                infeasible.push(task.id.clone());
+            }
+        }
+
+        let latest_starts = schedule
+            .assignments
+            .iter()
+            .map(|a| (a.activity_id.clone(), a.start_ms))
+            .collect();
+        schedule.insert_conditioning(resources);
+        schedule.violations = check_schedule(&schedule, tasks, resources);
+        BackwardPlan {
+            schedule,
+            latest_starts,
+            infeasible,
+        }
+    }
+}
+
+/// Latest start of `duration` in `free` (sorted, disjoint) ending by
+/// `latest_end`.
+fn latest_fit(free: &[TimeWindow], latest_end: i64, duration: i64) -> Option<i64> {
+    free.iter().rev().find_map(|w| {
+        let end = w.end_ms.min(latest_end);
+        (end.saturating_sub(w.start_ms) >= duration).then(|| end - duration)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        Activity, ActivityDuration, Calendar, PrecedenceMode, Resource, ResourceRequirement,
+    };
+
+    fn op(id: &str, task: &str, seq: i32, ms: i64, machine: &str) -> Activity {
+        Activity::new(id, task, seq)
+            .with_duration(ActivityDuration::fixed(ms))
+            .with_requirement(
+                ResourceRequirement::new("Machine").with_candidates(vec![machine.into()]),
+            )
+    }
+
+    #[test]
+    fn test_just_in_time_chain() {
+        let tasks = vec![
+            Task::new("J1")
+                .with_deadline(10_000)
+                .with_activity(op("J1_O1", "J1", 0, 1000, "M1"))
+                .with_activity(op("J1_O2", "J1", 1, 2000, "M2")),
+            Task::new("J2")
+                .with_deadline(10_000)
+                .with_priority(5)
+                .with_activity(op("J2_O1", "J2", 0, 1000, "M2")),
+        ];
+        let request = ScheduleRequest::new(
+            tasks,
+            vec![Resource::primary("M1"), Resource::primary("M2")],
+        );
+        let plan = BackwardScheduler::new().schedule_request(&request);
+
+        // J2 (higher priority) takes M2 right before the deadline; J1's
+        // last operation ends where J2 starts.
+        assert_eq!(plan.latest_start("J2_O1"), Some(9000));
+        assert_eq!(plan.latest_start("J1_O2"), Some(7000));
+        assert_eq!(plan.latest_start("J1_O1"), Some(6000));
+        assert!(plan.is_feasible());
+        assert!(plan.schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_calendar_lag_and_infeasible_release() {
+        let tasks = vec![Task::new("J1")
+            .with_deadline(10_000)
+            .with_release_time(5000)
+            .with_activity(op("J1_O1", "J1", 0, 2000, "M1"))
+            .with_activity(
+                op("J1_O2", "J1", 1, 1000, "M2")
+                    .with_precedence_mode(PrecedenceMode::FinishToStart, 500),
+            )];
+        let resources = vec![
+            Resource::primary("M1").with_calendar(Calendar::new("c").with_blocked(7000, 9000)),
+            Resource::primary("M2"),
+        ];
+        let request = ScheduleRequest::new(tasks, resources);
+        let plan = BackwardScheduler::new().schedule_request(&request);
+
+        // O2 at 9000; O1 must end by 8500 but M1 is blocked from 7000.
+        assert_eq!(plan.latest_start("J1_O2"), Some(9000));
+        assert_eq!(plan.latest_start("J1_O1"), Some(5000));
+        assert!(plan.is_feasible());
+
+        let tight = BackwardScheduler::new().schedule_request(&ScheduleRequest::new(
+            vec![request.tasks[0].clone().with_release_time(5500)],
+            request.resources.clone(),
+        ));
+        assert_eq!(tight.infeasible, vec!["J1"]);
+    }
+
+    #[test]
+    fn test_latest_fit() {
+        let free = [TimeWindow::new(0, 1000), TimeWindow::new(2000, 2500)];
+        assert_eq!(latest_fit(&free, 3000, 400), Some(2100));
+        assert_eq!(latest_fit(&free, 3000, 800), Some(200));
+        assert_eq!(latest_fit(&free, 500, 800), None);
+        let open = [TimeWindow::new(i64::MIN, i64::MAX)];
+        assert_eq!(latest_fit(&open, 100, 300), Some(-200));
+    }
+}
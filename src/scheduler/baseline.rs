@@ -0,0 +1,256 @@
+//! Random-schedule baselines.
+//!
+//! "The GA plan has a 3 h makespan" means little on its own; "it beats
+//! 95 % of random plans by 20 %" does. [`RandomBaseline`] samples random
+//! feasible schedules of a request and reports their KPI distribution as
+//! a [`BaselineReport`], against which any solver's result can be ranked.
+//!
+//! # Method
+//!
+//! Each sample is a random [`ScheduleChromosome`] (a random operation
+//! sequence and a random eligible resource per activity) decoded by the
+//! GA's [`SchedulingGaProblem`], so samples honor precedences, release
+//! times, calendars, setups, tooling and locks exactly like GA plans do.
+//! The operation sequence draws the next task with probability
+//! proportional to its remaining activities. Tardiness is priced with an
+//! optional [`WeightProfile`].
+//!
+//! Quantiles use the nearest-rank method over the samples, in ascending
+//! order: `makespan_at(0.05)` is the makespan that 95 % of random plans
+//! exceed or match.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use super::promise::quantile;
+use super::{ScheduleKpi, ScheduleRequest};
+use crate::ga::{ScheduleChromosome, SchedulingGaProblem};
+use crate::models::WeightProfile;
+use crate::validation::ValidationError;
+
+/// KPI distribution of random schedules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineReport {
+    /// KPIs of each sample, in sampling order.
+    pub samples: Vec<ScheduleKpi>,
+    /// Sample makespans, ascending (ms).
+    pub makespans_ms: Vec<i64>,
+    /// Sample total tardiness, ascending (ms).
+    pub tardiness_ms: Vec<i64>,
+    /// Sample tardiness costs, ascending.
+    pub tardiness_costs: Vec<f64>,
+    /// Samples with [`check_schedule`](crate::validation::check_schedule)
+    /// violations (e.g. missed deadlines).
+    pub violating_samples: usize,
+}
+
+impl BaselineReport {
+    /// Makespan at quantile `q` of the samples (clamped to `[0, 1]`).
+    pub fn makespan_at(&self, q: f64) -> i64 {
+        quantile(&self.makespans_ms, q)
+    }
+
+    /// Total tardiness at quantile `q` of the samples.
+    pub fn tardiness_at(&self, q: f64) -> i64 {
+        quantile(&self.tardiness_ms, q)
+    }
+
+    /// Relative makespan improvement of `makespan_ms` over the random
+    /// plan better than `percentile` of the samples: 0.2 means 20 %
+    /// shorter than the 95th-percentile random plan for `percentile`
+    /// 0.95. Negative when worse, 0 for an empty report.
+    pub fn makespan_improvement(&self, makespan_ms: i64, percentile: f64) -> f64 {
+        let reference = self.makespan_at(1.0 - percentile);
+        if reference <= 0 {
+            return 0.0;
+        }
+        (reference - makespan_ms) as f64 / reference as f64
+    }
+
+    /// Share of samples with a longer makespan than `makespan_ms`.
+    pub fn makespan_rank(&self, makespan_ms: i64) -> f64 {
+        if self.makespans_ms.is_empty() {
+            return 0.0;
+        }
+        let not_longer = self.makespans_ms.partition_point(|&m| m <= makespan_ms);
+        (self.makespans_ms.len() - not_longer) as f64 / self.makespans_ms.len() as f64
+    }
+}
+
+/// Samples random feasible schedules as a statistical baseline.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, ActivityDuration, Resource, ResourceRequirement, Task};
+/// use u_schedule::scheduler::{RandomBaseline, ScheduleRequest, SimpleScheduler};
+///
+/// let job = |id: &str, ms: i64| {
+///     Task::new(id).with_activity(
+///         Activity::new(format!("{id}_O1"), id, 0)
+///             .with_duration(ActivityDuration::fixed(ms))
+///             .with_requirement(
+///                 ResourceRequirement::new("Machine")
+///                     .with_candidates(vec!["FAST".into(), "SLOW".into()]),
+///             ),
+///     )
+/// };
+/// let tasks = vec![job("J1", 1000), job("J2", 1000), job("J3", 1000)];
+/// let resources = vec![Resource::primary("FAST"), Resource::primary("SLOW")];
+/// let request = ScheduleRequest::new(tasks, resources);
+///
+/// let baseline = RandomBaseline::new().with_samples(50).sample(&request).unwrap();
+/// assert_eq!(baseline.samples.len(), 50);
+/// // Random plans range from a balanced split to everything on one machine.
+/// assert_eq!(baseline.makespan_at(0.0), 2000);
+/// assert_eq!(baseline.makespan_at(1.0), 3000);
+///
+/// let greedy = SimpleScheduler::new().schedule_request(&request);
+/// assert!(baseline.makespan_improvement(greedy.makespan_ms(), 0.5) >= 0.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RandomBaseline {
+    samples: usize,
+    seed: u64,
+    weights: WeightProfile,
+}
+
+impl Default for RandomBaseline {
+    fn default() -> Self {
+        Self {
+            samples: 100,
+            seed: 0,
+            weights: WeightProfile::default(),
+        }
+    }
+}
+
+impl RandomBaseline {
+    /// Creates a baseline of 100 samples with seed 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of samples (at least 1).
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    /// Sets the random seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Prices tardiness with `weights` (see
+    /// [`ScheduleKpi::calculate_weighted`]).
+    pub fn with_weights(mut self, weights: WeightProfile) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Samples random schedules of `request`.
+    ///
+    /// # Errors
+    /// Returns an error if the request's tasks fail validation (see
+    /// [`SchedulingGaProblem::try_new`]).
+    pub fn sample(&self, request: &ScheduleRequest) -> Result<BaselineReport, ValidationError> {
+        let problem = SchedulingGaProblem::try_new(&request.tasks, &request.resources)?
+            .with_transition_matrices(request.transition_matrices.clone())
+            .with_tooling(request.tooling.clone())
+            .with_locks(&request.tasks, &request.locks);
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let mut samples = Vec::with_capacity(self.samples);
+        let mut violating_samples = 0;
+        for _ in 0..self.samples {
+            let chromosome = ScheduleChromosome::random(&problem.activities, &mut rng);
+            let schedule = problem.decode(&chromosome);
+            if !schedule.violations.is_empty() {
+                violating_samples += 1;
+            }
+            samples.push(ScheduleKpi::calculate_weighted(
+                &schedule,
+                &request.tasks,
+                &self.weights,
+            ));
+        }
+
+        let mut makespans_ms: Vec<i64> = samples.iter().map(|k| k.makespan_ms).collect();
+        let mut tardiness_ms: Vec<i64> = samples.iter().map(|k| k.total_tardiness_ms).collect();
+        let mut tardiness_costs: Vec<f64> = samples.iter().map(|k| k.tardiness_cost).collect();
+        makespans_ms.sort_unstable();
+        tardiness_ms.sort_unstable();
+        tardiness_costs.sort_by(f64::total_cmp);
+        Ok(BaselineReport {
+            samples,
+            makespans_ms,
+            tardiness_ms,
+            tardiness_costs,
+            violating_samples,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, ActivityDuration, Resource, ResourceRequirement, Task};
+
+    fn job(id: &str, ms: i64, deadline: i64) -> Task {
+        Task::new(id).with_deadline(deadline).with_activity(
+            Activity::new(format!("{id}_O1"), id, 0)
+                .with_duration(ActivityDuration::fixed(ms))
+                .with_requirement(
+                    ResourceRequirement::new("Machine")
+                        .with_candidates(vec!["M1".into(), "M2".into()]),
+                ),
+        )
+    }
+
+    #[test]
+    fn test_distribution_and_ranking() {
+        let tasks = vec![
+            job("J1", 1000, 1000),
+            job("J2", 2000, 2000),
+            job("J3", 3000, 10_000),
+            job("J4", 1000, 10_000),
+        ];
+        let resources = vec![Resource::primary("M1"), Resource::primary("M2")];
+        let request = ScheduleRequest::new(tasks, resources);
+
+        let report = RandomBaseline::new()
+            .with_samples(200)
+            .with_seed(5)
+            .sample(&request)
+            .unwrap();
+        assert_eq!(report.samples.len(), 200);
+        assert!(report.makespans_ms.windows(2).all(|w| w[0] <= w[1]));
+        // From the best split (3000 + 1000 against 2000 + 1000 + 1000) to
+        // all work on one machine.
+        assert_eq!(report.makespan_at(0.0), 4000);
+        assert_eq!(report.makespan_at(1.0), 7000);
+        assert!(report.violating_samples > 0);
+        assert!(report.tardiness_at(1.0) > 0);
+
+        // Same seed, same distribution.
+        let again = RandomBaseline::new()
+            .with_samples(200)
+            .with_seed(5)
+            .sample(&request)
+            .unwrap();
+        assert_eq!(again.makespans_ms, report.makespans_ms);
+
+        // The optimum beats every longer random plan.
+        let best = 4000;
+        assert_eq!(report.makespan_rank(7000), 0.0);
+        assert!(report.makespan_rank(best) > 0.5);
+        let worst = report.makespan_at(1.0);
+        assert!(
+            (report.makespan_improvement(best, 0.0) - (worst - best) as f64 / worst as f64).abs()
+                < 1e-9
+        );
+    }
+}
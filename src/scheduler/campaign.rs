@@ -0,0 +1,325 @@
+//! Campaign (batching) optimizer for setup reduction.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ScheduleRequest, SimpleScheduler};
+use crate::dispatching::RuleEngine;
+use crate::models::{Schedule, Task};
+
+/// A run of same-category tasks on one resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Campaign {
+    /// Resource ID.
+    pub resource_id: String,
+    /// Shared task category.
+    pub category: String,
+    /// Task IDs in processing order.
+    pub task_ids: Vec<String>,
+    /// Start of the first assignment (ms).
+    pub start_ms: i64,
+    /// End of the last assignment (ms).
+    pub end_ms: i64,
+}
+
+/// Setup comparison between the ungrouped and the campaign plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignReport {
+    /// Campaigns of the campaign plan, by resource then start time.
+    pub campaigns: Vec<Campaign>,
+    /// Total setup time of the ungrouped plan (ms).
+    pub baseline_setup_ms: i64,
+    /// Total setup time of the campaign plan (ms).
+    pub campaign_setup_ms: i64,
+    /// Makespan of the ungrouped plan (ms).
+    pub baseline_makespan_ms: i64,
+    /// Makespan of the campaign plan (ms).
+    pub campaign_makespan_ms: i64,
+}
+
+impl CampaignReport {
+    /// Setup time saved by campaigning (negative if it got worse).
+    pub fn setup_savings_ms(&self) -> i64 {
+        self.baseline_setup_ms - self.campaign_setup_ms
+    }
+}
+
+/// Groups same-category tasks into campaigns to reduce setup time.
+///
+/// # Algorithm
+/// 1. Order tasks as [`SimpleScheduler`] would (rule engine or priority);
+///    this is the ungrouped baseline.
+/// 2. Walk the baseline order. The first unplaced task opens a campaign;
+///    later tasks of the same category and priority class are pulled
+///    forward into it while the campaign is below its size limit and the
+///    candidate's deadline is at most `max_earliness_ms` after the opening task's deadline
+///    (undated candidates always qualify).
+/// 3. Schedule both orders greedily and compare total setup.
+///
+/// The earliness limit keeps far-future orders from being produced
+/// (and held in inventory) just to share a setup.
+///
+/// # Reference
+/// Potts & Kovalyov (2000), "Scheduling with batching: A review"
+#[derive(Debug, Clone, Default)]
+pub struct CampaignOptimizer {
+    max_campaign_size: Option<usize>,
+    category_limits: HashMap<String, usize>,
+    max_earliness_ms: Option<i64>,
+    rule_engine: Option<RuleEngine>,
+}
+
+impl CampaignOptimizer {
+    /// Creates an optimizer without size or earliness limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits every campaign to `size` tasks.
+    pub fn with_max_campaign_size(mut self, size: usize) -> Self {
+        self.max_campaign_size = Some(size.max(1));
+        self
+    }
+
+    /// Limits campaigns of one category to `size` tasks (overrides the
+    /// global limit).
+    pub fn with_category_limit(mut self, category: impl Into<String>, size: usize) -> Self {
+        self.category_limits.insert(category.into(), size.max(1));
+        self
+    }
+
+    /// Limits how far (by deadline) a task may be pulled forward.
+    pub fn with_max_earliness_ms(mut self, max_earliness_ms: i64) -> Self {
+        self.max_earliness_ms = Some(max_earliness_ms);
+        self
+    }
+
+    /// Sets the rule engine that defines the baseline order.
+    pub fn with_rule_engine(mut self, engine: RuleEngine) -> Self {
+        self.rule_engine = Some(engine);
+        self
+    }
+
+    /// Task indices in campaign order.
+    pub fn campaign_order(&self, tasks: &[Task], start_time_ms: i64) -> Vec<usize> {
+        let baseline = self.scheduler().sort_tasks(tasks, start_time_ms);
+        let mut placed = vec![false; tasks.len()];
+        let mut order = Vec::with_capacity(tasks.len());
+
+        for (pos, &seed) in baseline.iter().enumerate() {
+            if placed[seed] {
+                continue;
+            }
+            let head = &tasks[seed];
+            let limit = self.limit_for(&head.category);
+            placed[seed] = true;
+            order.push(seed);
+            let mut size = 1;
+
+            for &candidate in &baseline[pos + 1..] {
+                if size >= limit {
+                    break;
+                }
+                let task = &tasks[candidate];
+                if placed[candidate]
+                    || task.category != head.category
+                    || task.priority_class != head.priority_class
+                {
+                    continue;
+                }
+                if !self.within_earliness(head, task) {
+                    continue;
+                }
+                placed[candidate] = true;
+                order.push(candidate);
+                size += 1;
+            }
+        }
+        order
+    }
+
+    /// Builds the campaign plan and its report.
+    pub fn optimize(&self, request: &ScheduleRequest) -> (Schedule, CampaignReport) {
+        let scheduler = self
+            .scheduler()
+            .with_transition_matrices(request.transition_matrices.clone())
+            .with_affinity(request.affinity.clone())
+            .with_locks(request.locks.clone())
+            .with_tooling(request.tooling.clone());
+        let (tasks, resources, start) = (&request.tasks, &request.resources, request.start_time_ms);
+
+        let baseline_order = scheduler.sort_tasks(tasks, start);
+        let baseline = scheduler.schedule_ordered(tasks, resources, start, &baseline_order);
+        let order = self.campaign_order(tasks, start);
+        let schedule = scheduler.schedule_ordered(tasks, resources, start, &order);
+
+        let report = CampaignReport {
+            campaigns: campaigns(&schedule, tasks),
+            baseline_setup_ms: total_setup(&baseline),
+            campaign_setup_ms: total_setup(&schedule),
+            baseline_makespan_ms: baseline.makespan_ms(),
+            campaign_makespan_ms: schedule.makespan_ms(),
+        };
+        (schedule, report)
+    }
+
+    fn scheduler(&self) -> SimpleScheduler {
+        match &self.rule_engine {
+            Some(engine) => SimpleScheduler::new().with_rule_engine(engine.clone()),
+            None => SimpleScheduler::new(),
+        }
+    }
+
+    fn limit_for(&self, category: &str) -> usize {
+        self.category_limits
+            .get(category)
+            .copied()
+            .or(self.max_campaign_size)
+            .unwrap_or(usize::MAX)
+    }
+
+    fn within_earliness(&self, head: &Task, candidate: &Task) -> bool {
+        match (self.max_earliness_ms, head.deadline, candidate.deadline) {
+            (Some(max), Some(head_due), Some(due)) => due - head_due <= max,
+            // An undated head has no reference point; keep dated tasks out.
+            (Some(_), None, Some(_)) => false,
+            _ => true,
+        }
+    }
+}
+
+fn total_setup(schedule: &Schedule) -> i64 {
+    schedule.assignments.iter().map(|a| a.setup_ms).sum()
+}
+
+/// Consecutive same-category assignment runs per resource.
+fn campaigns(schedule: &Schedule, tasks: &[Task]) -> Vec<Campaign> {
+    let category: HashMap<&str, &str> = tasks
+        .iter()
+        .map(|t| (t.id.as_str(), t.category.as_str()))
+        .collect();
+
+    let mut by_resource: Vec<_> = schedule.assignments.iter().collect();
+    by_resource.sort_by(|a, b| {
+        (a.resource_id.as_str(), a.start_ms).cmp(&(b.resource_id.as_str(), b.start_ms))
+    });
+
+    let mut result: Vec<Campaign> = Vec::new();
+    for a in by_resource {
+        let cat = category.get(a.task_id.as_str()).copied().unwrap_or("");
+        match result.last_mut() {
+            Some(c) if c.resource_id == a.resource_id && c.category == cat => {
+                if c.task_ids.last() != Some(&a.task_id) {
+                    c.task_ids.push(a.task_id.clone());
+                }
+                c.end_ms = a.end_ms;
+            }
+            _ => result.push(Campaign {
+                resource_id: a.resource_id.clone(),
+                category: cat.to_string(),
+                task_ids: vec![a.task_id.clone()],
+                start_ms: a.start_ms,
+                end_ms: a.end_ms,
+            }),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        Activity, ActivityDuration, Resource, ResourceRequirement, TransitionMatrix,
+        TransitionMatrixCollection,
+    };
+
+    fn make_task(id: &str, category: &str, priority: i32, deadline: Option<i64>) -> Task {
+        let mut task = Task::new(id)
+            .with_category(category)
+            .with_priority(priority)
+            .with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_duration(ActivityDuration::fixed(100))
+                    .with_requirement(
+                        ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                    ),
+            );
+        task.deadline = deadline;
+        task
+    }
+
+    /// A,B,A,B,A,B by priority; every category change costs 50.
+    fn request(deadlines: [Option<i64>; 6]) -> ScheduleRequest {
+        let tasks = (0..6)
+            .map(|i| {
+                let cat = if i % 2 == 0 { "A" } else { "B" };
+                make_task(&format!("T{i}"), cat, 10 - i as i32, deadlines[i])
+            })
+            .collect();
+        let matrices = TransitionMatrixCollection::new()
+            .with_matrix(TransitionMatrix::new("m", "M1").with_default(50));
+        ScheduleRequest::new(tasks, vec![Resource::primary("M1")])
+            .with_transition_matrices(matrices)
+    }
+
+    fn ids(tasks: &[Task], order: &[usize]) -> Vec<String> {
+        order.iter().map(|&i| tasks[i].id.clone()).collect()
+    }
+
+    #[test]
+    fn test_groups_categories() {
+        let request = request([None; 6]);
+        let (schedule, report) = CampaignOptimizer::new().optimize(&request);
+
+        assert_eq!(schedule.assignment_count(), 6);
+        assert_eq!(report.baseline_setup_ms, 250);
+        assert_eq!(report.campaign_setup_ms, 50);
+        assert_eq!(report.setup_savings_ms(), 200);
+        assert_eq!(report.campaign_makespan_ms, 650);
+        assert_eq!(report.campaigns.len(), 2);
+        assert_eq!(report.campaigns[0].task_ids, vec!["T0", "T2", "T4"]);
+        assert_eq!(report.campaigns[1].category, "B");
+    }
+
+    #[test]
+    fn test_campaign_size_limit() {
+        let request = request([None; 6]);
+        let optimizer = CampaignOptimizer::new().with_max_campaign_size(2);
+        let order = optimizer.campaign_order(&request.tasks, 0);
+        assert_eq!(
+            ids(&request.tasks, &order),
+            vec!["T0", "T2", "T1", "T3", "T4", "T5"]
+        );
+
+        let per_category = CampaignOptimizer::new()
+            .with_max_campaign_size(2)
+            .with_category_limit("B", 3);
+        let order = per_category.campaign_order(&request.tasks, 0);
+        assert_eq!(
+            ids(&request.tasks, &order),
+            vec!["T0", "T2", "T1", "T3", "T5", "T4"]
+        );
+    }
+
+    #[test]
+    fn test_max_earliness() {
+        let due = |d| Some(d);
+        let request = request([
+            due(1000),
+            due(1000),
+            due(1500),
+            due(1000),
+            due(5000),
+            due(5000),
+        ]);
+        let optimizer = CampaignOptimizer::new().with_max_earliness_ms(1000);
+        let order = optimizer.campaign_order(&request.tasks, 0);
+        // T4 is due 4000 after T0: not pulled forward.
+        assert_eq!(
+            ids(&request.tasks, &order),
+            vec!["T0", "T2", "T1", "T3", "T4", "T5"]
+        );
+    }
+}
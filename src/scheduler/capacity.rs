@@ -0,0 +1,387 @@
+//! Makespan vs. resource-count trade-off analysis.
+//!
+//! Capacity investments are justified by what an extra machine buys (or
+//! what losing one costs). [`CapacityAnalyzer`] re-solves a request with
+//! fewer or more resources and reports makespan, tardiness and
+//! utilization per resource count as a [`CapacityCurve`].
+//!
+//! # Modes
+//!
+//! | Mode | Resources per point |
+//! |------|---------------------|
+//! | [`reduce`](CapacityAnalyzer::reduce) | Pool minus the resources whose removal hurts least, one at a time |
+//! | [`expand`](CapacityAnalyzer::expand) | All resources plus 1..n clones of a template machine |
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::cost::csv_field;
+use super::{ScheduleKpi, ScheduleRequest, SimpleScheduler};
+use crate::models::{Resource, ResourceType, Schedule};
+
+/// Outcome of one resource configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapacityPoint {
+    /// Number of pool resources available.
+    pub resource_count: usize,
+    /// IDs of the pool resources available.
+    pub resource_ids: Vec<String>,
+    /// Makespan (ms).
+    pub makespan_ms: i64,
+    /// Sum of tardiness across all tasks (ms).
+    pub total_tardiness_ms: i64,
+    /// Mean utilization of the pool resources (0.0..1.0).
+    pub avg_utilization: f64,
+    /// Activities that could not be scheduled (no candidate left).
+    pub unscheduled_activities: usize,
+}
+
+/// Makespan and tardiness by resource count, ascending.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CapacityCurve {
+    /// One point per resource count.
+    pub points: Vec<CapacityPoint>,
+}
+
+impl CapacityCurve {
+    /// Fewest resources that schedule everything within `max_makespan_ms`.
+    pub fn min_resources_for(&self, max_makespan_ms: i64) -> Option<&CapacityPoint> {
+        self.points
+            .iter()
+            .find(|p| p.unscheduled_activities == 0 && p.makespan_ms <= max_makespan_ms)
+    }
+
+    /// Makespan saved by each point over the previous one (ms; first = 0).
+    pub fn marginal_gains_ms(&self) -> Vec<i64> {
+        let mut gains = vec![0; self.points.len().min(1)];
+        gains.extend(
+            self.points
+                .windows(2)
+                .map(|w| w[0].makespan_ms - w[1].makespan_ms),
+        );
+        gains
+    }
+
+    /// The curve as CSV (header row included).
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "resource_count,resource_ids,makespan_ms,total_tardiness_ms,avg_utilization,unscheduled_activities\n",
+        );
+        for p in &self.points {
+            csv.push_str(&format!(
+                "{},{},{},{},{:.4},{}\n",
+                p.resource_count,
+                csv_field(&p.resource_ids.join(";")),
+                p.makespan_ms,
+                p.total_tardiness_ms,
+                p.avg_utilization,
+                p.unscheduled_activities,
+            ));
+        }
+        csv
+    }
+}
+
+/// Adds clone `n` of `template` to `request` as `<template_id>+<n>`, with
+/// the template's transition matrix and candidacies; returns its ID.
+pub(super) fn add_clone(request: &mut ScheduleRequest, template: &Resource, n: usize) -> String {
+    let clone_id = format!("{}+{n}", template.id);
+    let mut clone = template.clone();
+    clone.id = clone_id.clone();
+    request.resources.push(clone);
+    if let Some(matrix) = request.transition_matrices.get(&template.id) {
+        let mut matrix = matrix.clone();
+        matrix.resource_id = clone_id.clone();
+        request.transition_matrices.add(matrix);
+    }
+    for req in request
+        .tasks
+        .iter_mut()
+        .flat_map(|t| t.activities.iter_mut())
+        .flat_map(|a| a.resource_requirements.iter_mut())
+    {
+        if req.candidates.contains(&template.id) {
+            req.candidates.push(clone_id.clone());
+        }
+    }
+    clone_id
+}
+
+/// Re-solves a request with varying resource counts.
+///
+/// Each configuration is scheduled with the greedy scheduler; transition
+/// matrices, affinity, locks and tooling are taken from the request.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, ActivityDuration, Resource, ResourceRequirement, Task};
+/// use u_schedule::scheduler::{CapacityAnalyzer, ScheduleRequest};
+///
+/// let tasks = (0..4)
+///     .map(|i| {
+///         let id = format!("J{i}");
+///         Task::new(&id).with_activity(
+///             Activity::new(format!("{id}_O1"), &id, 0)
+///                 .with_duration(ActivityDuration::fixed(1000))
+///                 .with_requirement(
+///                     ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+///                 ),
+///         )
+///     })
+///     .collect();
+/// let request = ScheduleRequest::new(tasks, vec![Resource::primary("M1")]);
+///
+/// let curve = CapacityAnalyzer::new().expand(&request, "M1", 3);
+/// let makespans: Vec<i64> = curve.points.iter().map(|p| p.makespan_ms).collect();
+/// assert_eq!(makespans, vec![4000, 2000, 2000, 1000]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CapacityAnalyzer {
+    scheduler: SimpleScheduler,
+    pool: Option<Vec<String>>,
+}
+
+impl CapacityAnalyzer {
+    /// Creates an analyzer over all primary resources.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the scheduler (e.g., with a rule engine).
+    pub fn with_scheduler(mut self, scheduler: SimpleScheduler) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Restricts the analysis to these resources (default: all primary
+    /// resources). Other resources are always available.
+    pub fn with_pool(mut self, resource_ids: Vec<String>) -> Self {
+        self.pool = Some(resource_ids);
+        self
+    }
+
+    /// Removes pool resources one at a time, down to one.
+    ///
+    /// Backward elimination: at each step every remaining pool resource is
+    /// tried for removal and the one whose removal gives the best result
+    /// (fewest unscheduled activities, then makespan, then tardiness) is
+    /// dropped. Points are returned from one resource up to the full pool.
+    pub fn reduce(&self, request: &ScheduleRequest) -> CapacityCurve {
+        let pool = self.pool_ids(request);
+        let mut active = pool.clone();
+        let mut points = vec![self.evaluate(request, &request.resources, &active)];
+
+        while active.len() > 1 {
+            let best = (0..active.len())
+                .map(|i| {
+                    let mut remaining = active.clone();
+                    remaining.remove(i);
+                    let removed: Vec<&str> = pool
+                        .iter()
+                        .filter(|id| !remaining.contains(id))
+                        .map(String::as_str)
+                        .collect();
+                    let resources: Vec<Resource> = request
+                        .resources
+                        .iter()
+                        .filter(|r| !removed.contains(&r.id.as_str()))
+                        .cloned()
+                        .collect();
+                    let point = self.evaluate(request, &resources, &remaining);
+                    (remaining, point)
+                })
+                .min_by_key(|(_, p)| {
+                    (
+                        p.unscheduled_activities,
+                        p.makespan_ms,
+                        p.total_tardiness_ms,
+                    )
+                });
+            let Some((remaining, point)) = best else {
+                break;
+            };
+            active = remaining;
+            points.push(point);
+        }
+
+        points.reverse();
+        CapacityCurve { points }
+    }
+
+    /// Adds up to `max_added` clones of `template_id`.
+    ///
+    /// Clone `n` is named `<template_id>+<n>`, copies the template
+    /// (calendar, efficiency, rates), its transition matrix, and becomes a
+    /// candidate wherever the template is. Points are returned from the
+    /// current resources up to `max_added` clones. Empty if the template
+    /// does not exist.
+    pub fn expand(
+        &self,
+        request: &ScheduleRequest,
+        template_id: &str,
+        max_added: usize,
+    ) -> CapacityCurve {
+        let Some(template) = request.resources.iter().find(|r| r.id == template_id) else {
+            return CapacityCurve::default();
+        };
+        let mut active = self.pool_ids(request);
+        if !active.iter().any(|id| id == template_id) {
+            active.push(template_id.to_string());
+        }
+        let mut points = vec![self.evaluate(request, &request.resources, &active)];
+
+        let mut expanded = request.clone();
+        for n in 1..=max_added {
+            let clone_id = add_clone(&mut expanded, template, n);
+            active.push(clone_id);
+            points.push(self.evaluate(&expanded, &expanded.resources, &active));
+        }
+
+        CapacityCurve { points }
+    }
+
+    /// Pool resource IDs present in the request, in request order.
+    fn pool_ids(&self, request: &ScheduleRequest) -> Vec<String> {
+        request
+            .resources
+            .iter()
+            .filter(|r| match &self.pool {
+                Some(pool) => pool.contains(&r.id),
+                None => r.resource_type == ResourceType::Primary,
+            })
+            .map(|r| r.id.clone())
+            .collect()
+    }
+
+    /// Schedules `request` on `resources` and measures the pool.
+    fn evaluate(
+        &self,
+        request: &ScheduleRequest,
+        resources: &[Resource],
+        active: &[String],
+    ) -> CapacityPoint {
+        let schedule = self
+            .scheduler
+            .clone()
+            .with_transition_matrices(request.transition_matrices.clone())
+            .with_affinity(request.affinity.clone())
+            .with_locks(request.locks.clone())
+            .with_tooling(request.tooling.clone())
+            .schedule(&request.tasks, resources, request.start_time_ms);
+        let kpi = ScheduleKpi::calculate(&schedule, &request.tasks);
+        CapacityPoint {
+            resource_count: active.len(),
+            resource_ids: active.to_vec(),
+            makespan_ms: kpi.makespan_ms,
+            total_tardiness_ms: kpi.total_tardiness_ms,
+            avg_utilization: Self::pool_utilization(&schedule, active),
+            unscheduled_activities: Self::unscheduled(request, &schedule),
+        }
+    }
+
+    /// Mean utilization over the active pool resources (makespan horizon).
+    fn pool_utilization(schedule: &Schedule, active: &[String]) -> f64 {
+        if active.is_empty() {
+            return 0.0;
+        }
+        let horizon = schedule.makespan_ms();
+        let sum: f64 = active
+            .iter()
+            .filter_map(|id| schedule.resource_utilization(id, horizon))
+            .sum();
+        sum / active.len() as f64
+    }
+
+    /// Activities of the request without an assignment.
+    fn unscheduled(request: &ScheduleRequest, schedule: &Schedule) -> usize {
+        let scheduled: HashSet<&str> = schedule
+            .assignments
+            .iter()
+            .map(|a| a.activity_id.as_str())
+            .collect();
+        request
+            .tasks
+            .iter()
+            .flat_map(|t| &t.activities)
+            .filter(|a| !scheduled.contains(a.id.as_str()))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, ActivityDuration, ResourceRequirement, Task};
+
+    fn make_request(candidates: &[&str], jobs: usize) -> ScheduleRequest {
+        let tasks =
+            (0..jobs)
+                .map(|i| {
+                    let id = format!("J{i}");
+                    Task::new(&id).with_deadline(2000).with_activity(
+                        Activity::new(format!("{id}_O1"), &id, 0)
+                            .with_duration(ActivityDuration::fixed(1000))
+                            .with_requirement(ResourceRequirement::new("Machine").with_candidates(
+                                candidates.iter().map(|c| c.to_string()).collect(),
+                            )),
+                    )
+                })
+                .collect();
+        let resources = candidates.iter().map(|&id| Resource::primary(id)).collect();
+        ScheduleRequest::new(tasks, resources)
+    }
+
+    #[test]
+    fn test_reduce() {
+        let mut request = make_request(&["M1", "M2", "M3"], 6);
+        // J0 can only run on M3, so M3 is never the one dropped.
+        request.tasks[0].activities[0].resource_requirements[0].candidates = vec!["M3".into()];
+
+        let curve = CapacityAnalyzer::new().reduce(&request);
+        let counts: Vec<usize> = curve.points.iter().map(|p| p.resource_count).collect();
+        assert_eq!(counts, vec![1, 2, 3]);
+        let makespans: Vec<i64> = curve.points.iter().map(|p| p.makespan_ms).collect();
+        assert_eq!(makespans, vec![6000, 3000, 2000]);
+        assert_eq!(curve.points[0].resource_ids, vec!["M3".to_string()]);
+        assert!(curve.points.iter().all(|p| p.unscheduled_activities == 0));
+        assert_eq!(
+            curve.points[0].total_tardiness_ms,
+            4000 + 3000 + 2000 + 1000
+        );
+        assert!((curve.points[2].avg_utilization - 1.0).abs() < 1e-10);
+
+        assert_eq!(curve.marginal_gains_ms(), vec![0, 3000, 1000]);
+        assert_eq!(curve.min_resources_for(3000).unwrap().resource_count, 2);
+        assert!(curve.min_resources_for(1000).is_none());
+
+        // A pool of one leaves the others in place.
+        let curve = CapacityAnalyzer::new()
+            .with_pool(vec!["M1".into()])
+            .reduce(&request);
+        assert_eq!(curve.points.len(), 1);
+        assert_eq!(curve.points[0].makespan_ms, 2000);
+    }
+
+    #[test]
+    fn test_expand() {
+        let request = make_request(&["M1"], 4);
+        let curve = CapacityAnalyzer::new().expand(&request, "M1", 2);
+        let makespans: Vec<i64> = curve.points.iter().map(|p| p.makespan_ms).collect();
+        assert_eq!(makespans, vec![4000, 2000, 2000]);
+        assert_eq!(
+            curve.points[2].resource_ids,
+            vec!["M1".to_string(), "M1+1".into(), "M1+2".into()]
+        );
+
+        let csv = curve.to_csv();
+        assert!(csv.starts_with("resource_count,resource_ids,"));
+        assert!(csv.contains("\n2,M1;M1+1,2000,0,1.0000,0\n"));
+
+        assert!(CapacityAnalyzer::new()
+            .expand(&request, "missing", 2)
+            .points
+            .is_empty());
+    }
+}
@@ -0,0 +1,312 @@
+//! Per-assignment confidence under duration uncertainty.
+//!
+//! A plan is as robust as its tightest spots: an assignment behind a
+//! long, uncertain operation with no slack in between will likely start
+//! late, one after a generous gap will not. [`ConfidenceEstimator`]
+//! replays the plan with sampled processing times and reports, for each
+//! assignment, the probability of starting and finishing as planned.
+//!
+//! # Method
+//!
+//! Per sample, each activity with a [`DurationDistribution`] gets a
+//! processing time drawn from it (inverse-CDF sampling, as in
+//! [`OrderPromiser`](super::OrderPromiser)); its assignments' processing
+//! parts are scaled by the ratio to the planned processing time. The plan
+//! is then executed in order, never earlier than planned:
+//!
+//! ```text
+//! start = max(planned start, end of every predecessor)
+//! end   = start + setup + sampled processing
+//! ```
+//!
+//! Predecessors are the previous activity of the task (and explicit
+//! predecessors), and the latest-ending earlier assignment on the same
+//! resource, where they finish before the assignment starts in the plan.
+//! Slack between them absorbs delays. Precedence lags are not re-applied.
+//! An assignment runs as planned when its sampled start (end) is at most
+//! the tolerance after the planned one.
+
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::models::{
+    Assignment, AssignmentConfidence, DurationDistribution, PertEstimate, Schedule, Task,
+};
+
+/// Estimates per-assignment probabilities of running as planned.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, Assignment, DurationDistribution, Schedule, Task};
+/// use u_schedule::scheduler::ConfidenceEstimator;
+///
+/// let task = Task::new("J1")
+///     .with_activity(Activity::new("O1", "J1", 0).with_process_time(1000))
+///     .with_activity(Activity::new("O2", "J1", 1).with_process_time(1000));
+/// let mut plan = Schedule::new();
+/// plan.add_assignment(Assignment::new("O1", "J1", "M1", 0, 1000));
+/// plan.add_assignment(Assignment::new("O2", "J1", "M2", 1500, 2500));
+///
+/// ConfidenceEstimator::new()
+///     .with_distribution("O1", DurationDistribution::Uniform { min_ms: 500, max_ms: 2500 })
+///     .annotate(&mut plan, &[task]);
+/// let o2 = plan.confidence_of(&plan.assignments[1]).unwrap();
+/// // O2 starts on time when O1 takes at most 1500 ms: half the time.
+/// assert!((o2.start_probability - 0.5).abs() < 0.1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfidenceEstimator {
+    samples: usize,
+    seed: u64,
+    tolerance_ms: i64,
+    default_variance: Option<f64>,
+    distributions: HashMap<String, DurationDistribution>,
+}
+
+impl Default for ConfidenceEstimator {
+    fn default() -> Self {
+        Self {
+            samples: 200,
+            seed: 0,
+            tolerance_ms: 0,
+            default_variance: None,
+            distributions: HashMap::new(),
+        }
+    }
+}
+
+impl ConfidenceEstimator {
+    /// Creates an estimator with 200 samples, seed 0 and no tolerance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of samples (at least 1).
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    /// Sets the random seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Counts starts and ends up to `tolerance_ms` late as on plan.
+    pub fn with_tolerance_ms(mut self, tolerance_ms: i64) -> Self {
+        self.tolerance_ms = tolerance_ms.max(0);
+        self
+    }
+
+    /// Gives activities without a distribution a symmetric PERT estimate
+    /// of ± `ratio` × their processing time
+    /// (see [`PertEstimate::from_variance`]).
+    pub fn with_default_variance(mut self, ratio: f64) -> Self {
+        self.default_variance = Some(ratio);
+        self
+    }
+
+    /// Sets the processing-time distribution of an activity.
+    pub fn with_distribution(
+        mut self,
+        activity_id: impl Into<String>,
+        distribution: DurationDistribution,
+    ) -> Self {
+        self.distributions.insert(activity_id.into(), distribution);
+        self
+    }
+
+    /// Confidence of every assignment of `schedule`, in assignment order.
+    pub fn estimate(&self, schedule: &Schedule, tasks: &[Task]) -> Vec<AssignmentConfidence> {
+        let assignments = &schedule.assignments;
+        let mut order: Vec<usize> = (0..assignments.len()).collect();
+        order.sort_by_key(|&i| (assignments[i].start_ms, assignments[i].end_ms));
+        let predecessors = predecessors(assignments, &order, tasks);
+
+        // Planned processing time and distribution per sampled activity.
+        let mut uncertain: Vec<(&str, i64, DurationDistribution)> = Vec::new();
+        for activity in tasks.iter().flat_map(|t| &t.activities) {
+            let planned = activity.duration.process_ms;
+            let distribution = match (self.distributions.get(&activity.id), self.default_variance) {
+                (Some(d), _) => d.clone(),
+                (None, Some(ratio)) => {
+                    DurationDistribution::Pert(PertEstimate::from_variance(planned, ratio))
+                }
+                (None, None) => continue,
+            };
+            if planned > 0 {
+                uncertain.push((&activity.id, planned, distribution));
+            }
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut on_time = vec![(0usize, 0usize); assignments.len()];
+        let mut ends = vec![0i64; assignments.len()];
+        let mut factors: HashMap<&str, f64> = HashMap::new();
+        for _ in 0..self.samples {
+            factors.clear();
+            for (id, planned, distribution) in &uncertain {
+                let u: f64 = rng.random_range(1e-6..1.0 - 1e-6);
+                let sampled = distribution.duration_at_confidence(u).max(0);
+                factors.insert(id, sampled as f64 / *planned as f64);
+            }
+            for &i in &order {
+                let a = &assignments[i];
+                let start = predecessors[i]
+                    .iter()
+                    .map(|&p| ends[p])
+                    .fold(a.start_ms, i64::max);
+                let process = (a.end_ms - a.start_ms - a.setup_ms).max(0);
+                let process = match factors.get(a.activity_id.as_str()) {
+                    Some(factor) => (process as f64 * factor).round() as i64,
+                    None => process,
+                };
+                ends[i] = start + a.setup_ms + process;
+                if start <= a.start_ms + self.tolerance_ms {
+                    on_time[i].0 += 1;
+                }
+                if ends[i] <= a.end_ms + self.tolerance_ms {
+                    on_time[i].1 += 1;
+                }
+            }
+        }
+
+        let samples = self.samples as f64;
+        assignments
+            .iter()
+            .zip(on_time)
+            .map(|(a, (starts, finishes))| AssignmentConfidence {
+                activity_id: a.activity_id.clone(),
+                resource_id: a.resource_id.clone(),
+                start_ms: a.start_ms,
+                start_probability: starts as f64 / samples,
+                finish_probability: finishes as f64 / samples,
+            })
+            .collect()
+    }
+
+    /// Stores [`estimate`](Self::estimate) in the schedule's
+    /// [`confidence`](Schedule::confidence).
+    pub fn annotate(&self, schedule: &mut Schedule, tasks: &[Task]) {
+        schedule.confidence = self.estimate(schedule, tasks);
+    }
+}
+
+/// Indices of the assignments each assignment waits for.
+fn predecessors(assignments: &[Assignment], order: &[usize], tasks: &[Task]) -> Vec<Vec<usize>> {
+    // Activity → activities it follows (previous in its task, explicit).
+    let mut follows: HashMap<&str, Vec<&str>> = HashMap::new();
+    for task in tasks {
+        let mut activities: Vec<_> = task.activities.iter().collect();
+        activities.sort_by_key(|a| a.sequence);
+        let dag = task.is_dag();
+        for (k, activity) in activities.iter().enumerate() {
+            let entry = follows.entry(activity.id.as_str()).or_default();
+            if k > 0 && !dag {
+                entry.push(&activities[k - 1].id);
+            }
+            entry.extend(activity.predecessors.iter().map(String::as_str));
+        }
+    }
+    let mut by_activity: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, a) in assignments.iter().enumerate() {
+        by_activity.entry(&a.activity_id).or_default().push(i);
+    }
+
+    let mut result = vec![Vec::new(); assignments.len()];
+    let mut last_on_resource: HashMap<&str, Vec<usize>> = HashMap::new();
+    for &i in order {
+        let a = &assignments[i];
+        let earlier = last_on_resource.entry(&a.resource_id).or_default();
+        if let Some(&p) = earlier
+            .iter()
+            .filter(|&&p| assignments[p].end_ms <= a.start_ms)
+            .max_by_key(|&&p| assignments[p].end_ms)
+        {
+            result[i].push(p);
+        }
+        earlier.push(i);
+
+        for before in follows.get(a.activity_id.as_str()).into_iter().flatten() {
+            result[i].extend(
+                by_activity
+                    .get(before)
+                    .into_iter()
+                    .flatten()
+                    .filter(|&&p| assignments[p].end_ms <= a.start_ms),
+            );
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Activity;
+
+    fn job(id: &str, ms: i64) -> Task {
+        Task::new(id).with_activity(Activity::new(format!("{id}_O1"), id, 0).with_process_time(ms))
+    }
+
+    #[test]
+    fn test_slack_absorbs_delays() {
+        let tasks = vec![job("J1", 1000), job("J2", 1000), job("J3", 1000)];
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+        plan.add_assignment(Assignment::new("J2_O1", "J2", "M1", 1000, 2000));
+        plan.add_assignment(Assignment::new("J3_O1", "J3", "M1", 5000, 6000));
+
+        let estimator = ConfidenceEstimator::new().with_distribution(
+            "J1_O1",
+            DurationDistribution::Uniform {
+                min_ms: 500,
+                max_ms: 3000,
+            },
+        );
+        estimator.annotate(&mut plan, &tasks);
+        let confidence = |i: usize| plan.confidence_of(&plan.assignments[i]).unwrap();
+
+        assert_eq!(confidence(0).start_probability, 1.0);
+        // J1 finishes in time in 1 case out of 5, and so J2 starts.
+        assert!((confidence(0).finish_probability - 0.2).abs() < 0.08);
+        assert!((confidence(1).start_probability - 0.2).abs() < 0.08);
+        assert!(confidence(1).finish_probability < 0.3);
+        // The gap before J3 absorbs every delay.
+        assert_eq!(confidence(2).start_probability, 1.0);
+        assert_eq!(confidence(2).finish_probability, 1.0);
+
+        let tolerant = estimator.with_tolerance_ms(2000).estimate(&plan, &tasks);
+        assert_eq!(tolerant[1].start_probability, 1.0);
+    }
+
+    #[test]
+    fn test_task_precedence_and_default_variance() {
+        let task = Task::new("J1")
+            .with_activity(Activity::new("O1", "J1", 0).with_process_time(1000))
+            .with_activity(Activity::new("O2", "J1", 1).with_process_time(1000));
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("O1", "J1", "M1", 0, 1000));
+        plan.add_assignment(Assignment::new("O2", "J1", "M2", 1000, 2000));
+
+        let confidence = ConfidenceEstimator::new()
+            .with_default_variance(0.5)
+            .with_seed(7)
+            .estimate(&plan, &[task]);
+        assert_eq!(confidence.len(), 2);
+        // A symmetric estimate finishes on time about half the time.
+        assert!((confidence[0].finish_probability - 0.5).abs() < 0.1);
+        assert!((confidence[1].start_probability - 0.5).abs() < 0.1);
+        assert!(confidence[1].finish_probability < confidence[1].start_probability);
+
+        // Without distributions, everything runs as planned.
+        let certain = ConfidenceEstimator::new().estimate(&plan, &[]);
+        assert!(certain
+            .iter()
+            .all(|c| c.start_probability == 1.0 && c.finish_probability == 1.0));
+    }
+}
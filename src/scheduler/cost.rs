@@ -0,0 +1,342 @@
+//! Schedule cost evaluation.
+//!
+//! Prices a schedule from `Resource::cost_per_hour` and splits the cost
+//! per assignment and per task into categories that finance can book
+//! separately.
+//!
+//! # Categories
+//!
+//! | Category | Definition |
+//! |----------|-----------|
+//! | Machine | Processing time on non-human resources × rate |
+//! | Labor | Processing time on human resources × rate |
+//! | Setup | Setup time on any resource × rate |
+//! | Overtime | Premium on human time outside regular hours |
+//!
+//! Resources without a rate cost nothing. Regular hours are a daily
+//! window aligned to the scheduling epoch (t=0), like
+//! [`WorkRules`](crate::models::WorkRules) day windows.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::units::{DAY_MS, HOUR_MS};
+use crate::models::{Assignment, Resource, ResourceType, Schedule, TimeWindow};
+
+/// Cost of one assignment, by category.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssignmentCost {
+    /// Activity ID.
+    pub activity_id: String,
+    /// Task ID.
+    pub task_id: String,
+    /// Resource ID.
+    pub resource_id: String,
+    /// Machine processing cost.
+    pub machine: f64,
+    /// Labor processing cost.
+    pub labor: f64,
+    /// Setup cost.
+    pub setup: f64,
+    /// Overtime premium.
+    pub overtime: f64,
+}
+
+impl AssignmentCost {
+    /// Sum of all categories.
+    pub fn total(&self) -> f64 {
+        self.machine + self.labor + self.setup + self.overtime
+    }
+}
+
+/// Cost of one task, summed over its assignments.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskCost {
+    /// Task ID.
+    pub task_id: String,
+    /// Machine processing cost.
+    pub machine: f64,
+    /// Labor processing cost.
+    pub labor: f64,
+    /// Setup cost.
+    pub setup: f64,
+    /// Overtime premium.
+    pub overtime: f64,
+}
+
+impl TaskCost {
+    /// Sum of all categories.
+    pub fn total(&self) -> f64 {
+        self.machine + self.labor + self.setup + self.overtime
+    }
+}
+
+/// Cost breakdown of a schedule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostReport {
+    /// Per-assignment costs, in schedule order.
+    pub assignments: Vec<AssignmentCost>,
+    /// Per-task costs, ordered by task ID.
+    pub tasks: Vec<TaskCost>,
+}
+
+impl CostReport {
+    /// Total schedule cost.
+    pub fn total(&self) -> f64 {
+        self.assignments.iter().map(AssignmentCost::total).sum()
+    }
+
+    /// Per-assignment breakdown as CSV (header row included).
+    pub fn assignments_csv(&self) -> String {
+        let mut out =
+            String::from("activity_id,task_id,resource_id,machine,labor,setup,overtime,total\n");
+        for a in &self.assignments {
+            let _ = writeln!(
+                out,
+                "{},{},{},{:.2},{:.2},{:.2},{:.2},{:.2}",
+                csv_field(&a.activity_id),
+                csv_field(&a.task_id),
+                csv_field(&a.resource_id),
+                a.machine,
+                a.labor,
+                a.setup,
+                a.overtime,
+                a.total()
+            );
+        }
+        out
+    }
+
+    /// Per-task breakdown as CSV (header row included).
+    pub fn tasks_csv(&self) -> String {
+        let mut out = String::from("task_id,machine,labor,setup,overtime,total\n");
+        for t in &self.tasks {
+            let _ = writeln!(
+                out,
+                "{},{:.2},{:.2},{:.2},{:.2},{:.2}",
+                csv_field(&t.task_id),
+                t.machine,
+                t.labor,
+                t.setup,
+                t.overtime,
+                t.total()
+            );
+        }
+        out
+    }
+}
+
+/// Quotes a CSV field if it contains a delimiter, quote, or line break.
+pub(super) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Prices schedules from resource hourly rates.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Assignment, Resource, Schedule};
+/// use u_schedule::scheduler::CostModel;
+///
+/// let resources = vec![Resource::primary("M1").with_cost(60.0)];
+/// let mut schedule = Schedule::new();
+/// // 30 min setup + 1 h processing
+/// schedule.add_assignment(Assignment::new("O1", "J1", "M1", 0, 5_400_000).with_setup(1_800_000));
+///
+/// let report = CostModel::new().evaluate(&schedule, &resources);
+/// assert_eq!(report.tasks[0].machine, 60.0);
+/// assert_eq!(report.tasks[0].setup, 30.0);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostModel {
+    /// Start of regular hours within a day (ms, default: 0).
+    pub regular_start_ms: i64,
+    /// End of regular hours within a day (ms, default: 24 h = no overtime).
+    pub regular_end_ms: i64,
+    /// Overtime premium as a fraction of the base rate (default: 0.5).
+    pub overtime_premium: f64,
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        Self {
+            regular_start_ms: 0,
+            regular_end_ms: DAY_MS,
+            overtime_premium: 0.5,
+        }
+    }
+}
+
+impl CostModel {
+    /// Creates a model without overtime.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the daily regular-hours window `[start, end)` (ms of day).
+    pub fn with_regular_hours(mut self, start_ms: i64, end_ms: i64) -> Self {
+        self.regular_start_ms = start_ms.clamp(0, DAY_MS);
+        self.regular_end_ms = end_ms.clamp(self.regular_start_ms, DAY_MS);
+        self
+    }
+
+    /// Sets the overtime premium (0.5 = time and a half).
+    pub fn with_overtime_premium(mut self, premium: f64) -> Self {
+        self.overtime_premium = premium.max(0.0);
+        self
+    }
+
+    /// Prices every assignment of `schedule`.
+    ///
+    /// Assignments on unknown resources are priced at zero.
+    pub fn evaluate(&self, schedule: &Schedule, resources: &[Resource]) -> CostReport {
+        let by_id: HashMap<&str, &Resource> =
+            resources.iter().map(|r| (r.id.as_str(), r)).collect();
+
+        let assignments: Vec<AssignmentCost> = schedule
+            .assignments
+            .iter()
+            .map(|a| self.price(a, by_id.get(a.resource_id.as_str()).copied()))
+            .collect();
+
+        let mut tasks: BTreeMap<&str, TaskCost> = BTreeMap::new();
+        for a in &assignments {
+            let t = tasks.entry(a.task_id.as_str()).or_insert_with(|| TaskCost {
+                task_id: a.task_id.clone(),
+                ..TaskCost::default()
+            });
+            t.machine += a.machine;
+            t.labor += a.labor;
+            t.setup += a.setup;
+            t.overtime += a.overtime;
+        }
+        let tasks = tasks.into_values().collect();
+
+        CostReport { assignments, tasks }
+    }
+
+    fn price(&self, a: &Assignment, resource: Option<&Resource>) -> AssignmentCost {
+        let rate = resource.and_then(|r| r.cost_per_hour).unwrap_or(0.0);
+        let human = resource.is_some_and(|r| r.resource_type == ResourceType::Human);
+        let cost = |ms: i64| rate * ms as f64 / HOUR_MS as f64;
+
+        let process = cost(a.process_ms());
+        let overtime = if human {
+            self.overtime_premium * cost(a.duration_ms() - self.regular_ms(a.start_ms, a.end_ms))
+        } else {
+            0.0
+        };
+        AssignmentCost {
+            activity_id: a.activity_id.clone(),
+            task_id: a.task_id.clone(),
+            resource_id: a.resource_id.clone(),
+            machine: if human { 0.0 } else { process },
+            labor: if human { process } else { 0.0 },
+            setup: cost(a.setup_ms),
+            overtime,
+        }
+    }
+
+    /// Portion of `[start, end)` inside regular hours (ms).
+    fn regular_ms(&self, start: i64, end: i64) -> i64 {
+        if end <= start {
+            return 0;
+        }
+        let span = TimeWindow::new(start, end);
+        (start.div_euclid(DAY_MS)..=(end - 1).div_euclid(DAY_MS))
+            .filter_map(|day| {
+                let regular = TimeWindow::new(
+                    day * DAY_MS + self.regular_start_ms,
+                    day * DAY_MS + self.regular_end_ms,
+                );
+                span.intersection(&regular)
+            })
+            .map(|w| w.duration_ms())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resources() -> Vec<Resource> {
+        vec![
+            Resource::primary("M1").with_cost(60.0),
+            Resource::human("W1").with_cost(30.0),
+            Resource::primary("M2"),
+        ]
+    }
+
+    #[test]
+    fn test_machine_labor_and_setup() {
+        let mut s = Schedule::new();
+        s.add_assignment(Assignment::new("O1", "J1", "M1", 0, 2 * HOUR_MS).with_setup(HOUR_MS));
+        s.add_assignment(Assignment::new("O1", "J1", "W1", 0, 2 * HOUR_MS));
+        s.add_assignment(Assignment::new("O2", "J2", "M2", 0, HOUR_MS));
+
+        let report = CostModel::new().evaluate(&s, &resources());
+        assert_eq!(report.assignments[0].machine, 60.0);
+        assert_eq!(report.assignments[0].setup, 60.0);
+        assert_eq!(report.assignments[1].labor, 60.0);
+        assert_eq!(report.assignments[2].total(), 0.0);
+        assert_eq!(report.tasks.len(), 2);
+        assert_eq!(report.tasks[0].total(), 180.0);
+        assert_eq!(report.total(), 180.0);
+    }
+
+    #[test]
+    fn test_overtime_premium_for_humans_only() {
+        // Regular hours 08:00–16:00; work 14:00–18:00 has 2 h overtime.
+        let model = CostModel::new().with_regular_hours(8 * HOUR_MS, 16 * HOUR_MS);
+        let mut s = Schedule::new();
+        s.add_assignment(Assignment::new(
+            "O1",
+            "J1",
+            "W1",
+            14 * HOUR_MS,
+            18 * HOUR_MS,
+        ));
+        s.add_assignment(Assignment::new(
+            "O1",
+            "J1",
+            "M1",
+            14 * HOUR_MS,
+            18 * HOUR_MS,
+        ));
+
+        let report = model.evaluate(&s, &resources());
+        assert_eq!(report.assignments[0].labor, 120.0);
+        assert_eq!(report.assignments[0].overtime, 30.0);
+        assert_eq!(report.assignments[1].overtime, 0.0);
+
+        // Overnight 22:00–10:00 next day: only 08:00–10:00 is regular.
+        assert_eq!(model.regular_ms(22 * HOUR_MS, 34 * HOUR_MS), 2 * HOUR_MS);
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let mut s = Schedule::new();
+        s.add_assignment(Assignment::new("O1", "J,1", "M1", 0, HOUR_MS / 2));
+        let report = CostModel::new().evaluate(&s, &resources());
+
+        let csv = report.assignments_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "activity_id,task_id,resource_id,machine,labor,setup,overtime,total"
+        );
+        assert_eq!(lines[1], "O1,\"J,1\",M1,30.00,0.00,0.00,0.00,30.00");
+        assert_eq!(
+            report.tasks_csv(),
+            "task_id,machine,labor,setup,overtime,total\n\"J,1\",30.00,0.00,0.00,0.00,30.00\n"
+        );
+    }
+}
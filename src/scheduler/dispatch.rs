@@ -0,0 +1,800 @@
+//! Event-driven dispatching simulation.
+//!
+//! [`SimpleScheduler`](super::SimpleScheduler) ranks tasks once, before
+//! anything is placed, so rules that read the shop state (WINQ, CR, MST,
+//! LWKR, LPUL) only ever see the empty plan. [`DispatchScheduler`] instead
+//! simulates the shop: whenever a machine is free and work waits for it,
+//! the [`SchedulingContext`] is rebuilt from the plan so far and the rule
+//! engine picks the next job from that machine's queue.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::ScheduleRequest;
+use crate::dispatching::{RuleEngine, SchedulingContext};
+use crate::models::{Assignment, Resource, Schedule, Task, TransitionMatrixCollection};
+use crate::validation::check_schedule;
+
+/// How a machine picks the next job from its queue.
+///
+/// Queued tasks of the highest [priority class](crate::models::PriorityClass)
+/// always go first; the discipline orders them.
+#[derive(Clone)]
+pub enum QueueDiscipline {
+    /// First come, first served: the task that joined the queue earliest.
+    Fifo,
+    /// The best task by this rule engine (instead of the scheduler's).
+    Rules(RuleEngine),
+    /// The highest [`Task::priority`], first come first served among
+    /// equals. A ready task of a higher class or priority interrupts the
+    /// job running on the machine when no other machine can take it; the
+    /// interrupted job rejoins the queue and later resumes its remaining
+    /// processing.
+    PreemptivePriority,
+}
+
+/// Queue statistics of one machine over a simulation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueueStats {
+    /// Resource ID.
+    pub resource_id: String,
+    /// Jobs started (resumptions after a preemption count again).
+    pub dispatched: usize,
+    /// Longest queue at a dispatch, the started job included.
+    pub max_length: usize,
+    /// Sum over started jobs of the time spent queued (ms).
+    pub total_wait_ms: i64,
+    /// Longest time a job spent queued (ms).
+    pub max_wait_ms: i64,
+    /// Jobs interrupted by a more important one.
+    pub preemptions: usize,
+}
+
+impl QueueStats {
+    /// Mean time a started job spent queued (ms).
+    pub fn mean_wait_ms(&self) -> f64 {
+        if self.dispatched == 0 {
+            0.0
+        } else {
+            self.total_wait_ms as f64 / self.dispatched as f64
+        }
+    }
+}
+
+/// A job left waiting when a simulation stalled.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlockedJob {
+    /// Task ID.
+    pub task_id: String,
+    /// The task's next activity.
+    pub activity_id: String,
+    /// Explicit predecessors of the activity that never finished.
+    pub waiting_for: Vec<String>,
+    /// Candidate machines that could not start it (empty while it waits
+    /// for predecessors).
+    pub machines: Vec<String>,
+}
+
+/// Why a simulation stopped with work left.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stall {
+    /// Simulation time of the last event (ms).
+    pub at_ms: i64,
+    /// Jobs left waiting, in task order.
+    pub blocked_jobs: Vec<BlockedJob>,
+    /// Machines with waiting jobs whose calendars never open again.
+    pub closed_machines: Vec<String>,
+}
+
+/// Result of a dispatching simulation.
+#[derive(Debug, Clone)]
+pub struct SimulationReport {
+    /// The simulated schedule.
+    pub schedule: Schedule,
+    /// Queue statistics per resource, in resource order.
+    pub queues: Vec<QueueStats>,
+    /// Set if the simulation stopped making progress with jobs waiting.
+    pub stall: Option<Stall>,
+}
+
+/// Non-delay scheduler that dispatches per machine queue as a simulation
+/// clock advances.
+///
+/// # Algorithm
+/// 1. A task's next activity joins the queue of each of its candidate
+///    machines once it is ready: its task is released, the previous
+///    activity allows it (through its precedence mode and lag), and its
+///    explicit predecessors are placed and finished.
+/// 2. At the current time, the first machine (in resource order) that is
+///    free — and in working time, if it has a calendar — with a nonempty
+///    queue takes a job. Among the queued tasks of the highest
+///    [priority class](crate::models::PriorityClass), the machine's
+///    [`QueueDiscipline`] picks the one that starts now. By default that
+///    is the rule engine's best, with the context derived by
+///    [`SchedulingContext::from_schedule`] and each queued task's arrival
+///    set to when it joined the queue.
+/// 3. When no machine can start anything, a machine with the
+///    [`PreemptivePriority`](QueueDiscipline::PreemptivePriority)
+///    discipline may interrupt its job for a more important waiting one;
+///    otherwise the clock advances to the next event: a machine becoming
+///    free or an activity becoming ready.
+///
+/// A machine never idles while a job waits for it (a non-delay schedule).
+/// Setups come from the transition matrices. Resources are unary, and
+/// only the first [requirement](crate::models::Activity::resource_requirements)
+/// is dispatched; locks, tooling, splitting and working-time rules are
+/// left to [`SimpleScheduler`](super::SimpleScheduler). Activities
+/// without a candidate are skipped.
+///
+/// # Stalls
+/// The simulation stops without progress when no event is left (jobs
+/// wait for predecessors that never finish, or for machines that never
+/// open), or, with [`with_stall_limit`](Self::with_stall_limit), when the
+/// next event lies more than the limit after the last start or finish.
+/// The waiting jobs stay unscheduled and
+/// [`SimulationReport::stall`] names them, what they wait for, and the
+/// machines that never open.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::dispatching::{rules, RuleEngine};
+/// use u_schedule::models::{Activity, Resource, ResourceRequirement, Task};
+/// use u_schedule::scheduler::DispatchScheduler;
+///
+/// let job = |id: &str, ms: i64| {
+///     Task::new(id).with_activity(
+///         Activity::new(format!("{id}_O1"), id, 0)
+///             .with_process_time(ms)
+///             .with_requirement(ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()])),
+///     )
+/// };
+/// let tasks = vec![job("J1", 3000), job("J2", 1000)];
+/// let scheduler = DispatchScheduler::new(RuleEngine::new().with_rule(rules::Spt));
+/// let schedule = scheduler.schedule(&tasks, &[Resource::primary("M1")], 0);
+/// assert_eq!(schedule.assignment_for_activity("J2_O1").unwrap().start_ms, 0);
+/// assert_eq!(schedule.makespan_ms(), 4000);
+/// ```
+///
+/// # Reference
+/// Giffler & Thompson (1960), "Algorithms for Solving Production-Scheduling
+/// Problems"; Pinedo (2016), "Scheduling", Ch. 4
+#[derive(Clone)]
+pub struct DispatchScheduler {
+    engine: RuleEngine,
+    transition_matrices: TransitionMatrixCollection,
+    disciplines: HashMap<String, QueueDiscipline>,
+    stall_limit_ms: Option<i64>,
+}
+
+impl DispatchScheduler {
+    /// Creates a scheduler dispatching by `engine`.
+    pub fn new(engine: RuleEngine) -> Self {
+        Self {
+            engine,
+            transition_matrices: TransitionMatrixCollection::new(),
+            disciplines: HashMap::new(),
+            stall_limit_ms: None,
+        }
+    }
+
+    /// Sets sequence-dependent setup times.
+    pub fn with_transition_matrices(mut self, matrices: TransitionMatrixCollection) -> Self {
+        self.transition_matrices = matrices;
+        self
+    }
+
+    /// Sets the queue discipline of one resource (others dispatch by the
+    /// scheduler's rule engine).
+    pub fn with_discipline(
+        mut self,
+        resource_id: impl Into<String>,
+        discipline: QueueDiscipline,
+    ) -> Self {
+        self.disciplines.insert(resource_id.into(), discipline);
+        self
+    }
+
+    /// Stops the simulation once the next event lies more than `limit_ms`
+    /// after the last start or finish (default: only when no event is
+    /// left).
+    pub fn with_stall_limit(mut self, limit_ms: i64) -> Self {
+        self.stall_limit_ms = Some(limit_ms);
+        self
+    }
+
+    /// Schedules from a request (its transition matrices replace the
+    /// configured ones).
+    pub fn schedule_request(&self, request: &ScheduleRequest) -> Schedule {
+        let scheduler = Self {
+            transition_matrices: request.transition_matrices.clone(),
+            ..self.clone()
+        };
+        scheduler.schedule(&request.tasks, &request.resources, request.start_time_ms)
+    }
+
+    /// Runs the simulation from `start_time_ms`.
+    pub fn schedule(&self, tasks: &[Task], resources: &[Resource], start_time_ms: i64) -> Schedule {
+        self.simulate(tasks, resources, start_time_ms).schedule
+    }
+
+    /// Runs the simulation from `start_time_ms`, with queue statistics.
+    pub fn simulate(
+        &self,
+        tasks: &[Task],
+        resources: &[Resource],
+        start_time_ms: i64,
+    ) -> SimulationReport {
+        let known: HashSet<&str> = tasks
+            .iter()
+            .flat_map(|t| &t.activities)
+            .map(|a| a.id.as_str())
+            .collect();
+        let mut free: HashMap<&str, i64> = resources
+            .iter()
+            .map(|r| (r.id.as_str(), start_time_ms))
+            .collect();
+        let mut last_category: HashMap<&str, &str> = HashMap::new();
+        // Processing interval of each placed activity
+        let mut placed: HashMap<&str, (i64, i64)> = HashMap::new();
+        // Processing left of interrupted activities
+        let mut remaining: HashMap<&str, i64> = HashMap::new();
+        // Task of the job last started on each machine
+        let mut running: HashMap<&str, usize> = HashMap::new();
+        let mut stats: HashMap<&str, QueueStats> = resources
+            .iter()
+            .map(|r| {
+                let queue = QueueStats {
+                    resource_id: r.id.clone(),
+                    ..QueueStats::default()
+                };
+                (r.id.as_str(), queue)
+            })
+            .collect();
+        let mut next = vec![0usize; tasks.len()];
+        let mut joined: Vec<Option<i64>> = vec![None; tasks.len()];
+        let mut schedule = Schedule::new();
+        let mut clock = start_time_ms;
+        let mut last_start = start_time_ms;
+        let mut stall = None;
+
+        loop {
+            // Ready time (if bounded yet) and candidates of each task's
+            // next activity
+            let mut pending = Vec::new();
+            for (i, task) in tasks.iter().enumerate() {
+                while let Some(activity) = task.activities.get(next[i]) {
+                    let candidates: Vec<&str> = activity
+                        .resource_requirements
+                        .first()
+                        .map_or_else(Vec::new, |req| req.resolve(resources))
+                        .into_iter()
+                        .filter(|id| free.contains_key(id))
+                        .collect();
+                    if !candidates.is_empty() {
+                        let ready = ready_time(task, next[i], &placed, &known, start_time_ms);
+                        pending.push((i, activity, ready, candidates));
+                        break;
+                    }
+                    next[i] += 1;
+                }
+            }
+            if pending.is_empty() {
+                break;
+            }
+
+            // The first free machine with a queue dispatches
+            let opens = |id: &str| opening(resources, id, free[id].max(clock));
+            let queue_of = |machine: &str| -> Vec<usize> {
+                pending
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, _, ready, candidates))| {
+                        ready.is_some_and(|t| t <= clock) && candidates.contains(&machine)
+                    })
+                    .map(|(k, _)| k)
+                    .collect()
+            };
+            let machine = resources
+                .iter()
+                .map(|r| r.id.as_str())
+                .filter(|id| opens(id) == Some(clock))
+                .map(|id| (id, queue_of(id)))
+                .find(|(_, queue)| !queue.is_empty());
+            let Some((machine, queue)) = machine else {
+                // Interrupt a job for a more important waiting one
+                let urgency = |i: usize| (tasks[i].priority_class, -tasks[i].priority);
+                let preempted = resources
+                    .iter()
+                    .map(|r| r.id.as_str())
+                    .filter(|id| {
+                        matches!(
+                            self.disciplines.get(*id),
+                            Some(QueueDiscipline::PreemptivePriority)
+                        )
+                    })
+                    .find_map(|id| {
+                        let &i = running.get(id)?;
+                        // The task's latest activity, still on the machine
+                        let current = &tasks[i].activities.get(next[i].checked_sub(1)?)?.id;
+                        let index = schedule.assignments.iter().rposition(|a| {
+                            a.activity_id == *current && a.resource_id == id && a.end_ms > clock
+                        })?;
+                        queue_of(id)
+                            .iter()
+                            .any(|&k| urgency(pending[k].0) < urgency(i))
+                            .then_some((id, i, index))
+                    });
+                if let Some((machine, i, index)) = preempted {
+                    let a = &mut schedule.assignments[index];
+                    let activity_id = tasks[i].activities[next[i] - 1].id.as_str();
+                    let done = (clock - (a.start_ms + a.setup_ms)).max(0);
+                    let left = a.end_ms - a.start_ms - a.setup_ms - done;
+                    if done > 0 {
+                        a.end_ms = clock;
+                    } else {
+                        schedule.assignments.remove(index);
+                    }
+                    remaining.insert(activity_id, left);
+                    placed.remove(activity_id);
+                    running.remove(machine);
+                    free.insert(machine, clock);
+                    next[i] -= 1;
+                    joined[i] = Some(clock);
+                    stats.get_mut(machine).expect("known machine").preemptions += 1;
+                    continue;
+                }
+
+                // Advance to the next machine release or ready time
+                let upcoming = pending
+                    .iter()
+                    .filter_map(|(_, _, ready, _)| *ready)
+                    .chain(pending.iter().flat_map(|(_, _, ready, candidates)| {
+                        candidates
+                            .iter()
+                            .filter(|_| ready.is_some())
+                            .filter_map(|&id| opens(id))
+                    }))
+                    .filter(|&t| t > clock)
+                    .min();
+                let last_event = placed
+                    .values()
+                    .map(|&(_, end)| end)
+                    .filter(|&end| end <= clock)
+                    .fold(last_start, i64::max);
+                match upcoming {
+                    Some(t) if self.stall_limit_ms.is_none_or(|l| t - last_event <= l) => {
+                        clock = t;
+                        continue;
+                    }
+                    _ => {
+                        let blocked_jobs = pending
+                            .iter()
+                            .map(|&(i, activity, ready, ref candidates)| BlockedJob {
+                                task_id: tasks[i].id.clone(),
+                                activity_id: activity.id.clone(),
+                                waiting_for: activity
+                                    .predecessors
+                                    .iter()
+                                    .filter(|p| {
+                                        known.contains(p.as_str())
+                                            && !placed.contains_key(p.as_str())
+                                    })
+                                    .cloned()
+                                    .collect(),
+                                machines: match ready {
+                                    Some(_) => candidates.iter().map(|id| id.to_string()).collect(),
+                                    None => Vec::new(),
+                                },
+                            })
+                            .collect();
+                        let closed_machines = resources
+                            .iter()
+                            .map(|r| r.id.as_str())
+                            .filter(|&id| {
+                                opens(id).is_none()
+                                    && pending.iter().any(|(_, _, ready, candidates)| {
+                                        ready.is_some() && candidates.contains(&id)
+                                    })
+                            })
+                            .map(String::from)
+                            .collect();
+                        stall = Some(Stall {
+                            at_ms: last_event,
+                            blocked_jobs,
+                            closed_machines,
+                        });
+                        break;
+                    }
+                }
+            };
+
+            for &k in &queue {
+                let i = pending[k].0;
+                joined[i].get_or_insert(pending[k].2.unwrap_or(clock));
+            }
+            let queue_length = queue.len();
+            let top = queue
+                .iter()
+                .map(|&k| tasks[pending[k].0].priority_class)
+                .min();
+            let contenders: Vec<usize> = queue
+                .into_iter()
+                .filter(|&k| Some(tasks[pending[k].0].priority_class) == top)
+                .collect();
+            let arrival = |k: usize| joined[pending[k].0].unwrap_or(clock);
+            let pick = match self.disciplines.get(machine) {
+                Some(QueueDiscipline::Fifo) => (0..contenders.len())
+                    .min_by_key(|&c| arrival(contenders[c]))
+                    .unwrap_or(0),
+                Some(QueueDiscipline::PreemptivePriority) => (0..contenders.len())
+                    .min_by_key(|&c| {
+                        let k = contenders[c];
+                        (-tasks[pending[k].0].priority, arrival(k))
+                    })
+                    .unwrap_or(0),
+                discipline => {
+                    let engine = match discipline {
+                        Some(QueueDiscipline::Rules(engine)) => engine,
+                        _ => &self.engine,
+                    };
+                    let queued: Vec<&Task> =
+                        contenders.iter().map(|&k| &tasks[pending[k].0]).collect();
+                    let mut context = SchedulingContext::from_schedule(clock, &schedule, tasks);
+                    for &k in &contenders {
+                        context = context.with_arrival_time(&tasks[pending[k].0].id, arrival(k));
+                    }
+                    engine.rank(&queued, &context)[0]
+                }
+            };
+            let (i, activity) = (pending[contenders[pick]].0, pending[contenders[pick]].1);
+
+            let queue = stats.get_mut(machine).expect("known machine");
+            let wait = clock - joined[i].unwrap_or(clock);
+            queue.dispatched += 1;
+            queue.max_length = queue.max_length.max(queue_length);
+            queue.total_wait_ms += wait;
+            queue.max_wait_ms = queue.max_wait_ms.max(wait);
+
+            let task = &tasks[i];
+            let setup = last_category.get(machine).map_or(0, |prev| {
+                self.transition_matrices
+                    .get_transition_time(machine, prev, &task.category)
+            });
+            let process = remaining
+                .remove(activity.id.as_str())
+                .unwrap_or(activity.duration.process_ms.max(0));
+            let end = clock + setup + process;
+            last_start = clock;
+            running.insert(machine, i);
+            schedule.add_assignment(
+                Assignment::new(&activity.id, &task.id, machine, clock, end).with_setup(setup),
+            );
+            free.insert(machine, end);
+            last_category.insert(machine, &task.category);
+            placed.insert(&activity.id, (clock + setup, end));
+            next[i] += 1;
+            joined[i] = None;
+        }
+
+        schedule.insert_conditioning(resources);
+        schedule.violations = check_schedule(&schedule, tasks, resources);
+        let queues = resources
+            .iter()
+            .filter_map(|r| stats.remove(r.id.as_str()))
+            .collect();
+        SimulationReport {
+            schedule,
+            queues,
+            stall,
+        }
+    }
+}
+
+/// Earliest start of the `k`-th activity of `task`, or `None` while its
+/// previous activity or an explicit predecessor is unplaced.
+fn ready_time(
+    task: &Task,
+    k: usize,
+    placed: &HashMap<&str, (i64, i64)>,
+    known: &HashSet<&str>,
+    start_time_ms: i64,
+) -> Option<i64> {
+    let activity = &task.activities[k];
+    let mut ready = task
+        .release_time
+        .unwrap_or(start_time_ms)
+        .max(start_time_ms);
+    // The nearest earlier activity that was placed (skipped ones have no
+    // candidates)
+    if let Some(&(prev_start, prev_end)) = task.activities[..k]
+        .iter()
+        .rev()
+        .find_map(|a| placed.get(a.id.as_str()))
+    {
+        ready = ready.max(activity.precedence_mode.earliest_start(
+            prev_start,
+            prev_end,
+            activity.precedence_lag_ms,
+            activity.duration.process_ms,
+        ));
+    }
+    for pred in activity
+        .predecessors
+        .iter()
+        .filter(|p| known.contains(p.as_str()))
+    {
+        ready = ready.max(placed.get(pred.as_str())?.1);
+    }
+    Some(ready)
+}
+
+/// When `resource_id` can next start work at or after `from_ms`: `from_ms`
+/// itself, or the next working time of its calendar (`None` if there is
+/// none).
+fn opening(resources: &[Resource], resource_id: &str, from_ms: i64) -> Option<i64> {
+    match resources
+        .iter()
+        .find(|r| r.id == resource_id)
+        .and_then(|r| r.calendar.as_ref())
+    {
+        Some(calendar) => calendar.next_available_time(from_ms),
+        None => Some(from_ms),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dispatching::rules;
+    use crate::models::{Activity, ActivityDuration, Calendar, PriorityClass, ResourceRequirement};
+
+    fn op(id: &str, task: &str, seq: i32, ms: i64, machine: &str) -> Activity {
+        Activity::new(id, task, seq)
+            .with_duration(ActivityDuration::fixed(ms))
+            .with_requirement(
+                ResourceRequirement::new("Machine").with_candidates(vec![machine.into()]),
+            )
+    }
+
+    fn start(schedule: &Schedule, activity_id: &str) -> i64 {
+        schedule
+            .assignment_for_activity(activity_id)
+            .unwrap()
+            .start_ms
+    }
+
+    #[test]
+    fn test_rules_see_the_shop_state() {
+        let tasks = vec![
+            Task::new("J1")
+                .with_activity(op("J1_O1", "J1", 0, 1000, "M1"))
+                .with_activity(op("J1_O2", "J1", 1, 500, "M2")),
+            Task::new("J2").with_activity(op("J2_O1", "J2", 0, 3000, "M2")),
+            Task::new("J3").with_activity(op("J3_O1", "J3", 0, 2000, "M2")),
+            Task::new("J4").with_activity(op("J4_O1", "J4", 0, 2500, "M2")),
+        ];
+        let resources = vec![Resource::primary("M1"), Resource::primary("M2")];
+        let scheduler = DispatchScheduler::new(RuleEngine::new().with_rule(rules::Lwkr));
+        let schedule = scheduler.schedule(&tasks, &resources, 0);
+
+        // M2 starts the least work waiting at 0 (J1 is still on M1); at
+        // 2000, J1 has only 500 ms left and jumps the queue.
+        assert_eq!(start(&schedule, "J1_O1"), 0);
+        assert_eq!(start(&schedule, "J3_O1"), 0);
+        assert_eq!(start(&schedule, "J1_O2"), 2000);
+        assert_eq!(start(&schedule, "J4_O1"), 2500);
+        assert_eq!(start(&schedule, "J2_O1"), 5000);
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_clock_waits_for_release_calendar_and_predecessors() {
+        let tasks = vec![
+            Task::new("J1")
+                .with_release_time(500)
+                .with_activity(op("J1_O1", "J1", 0, 1000, "M1")),
+            Task::new("J2")
+                .with_activity(op("J2_O1", "J2", 0, 1000, "M2").with_predecessor("J1_O1")),
+            Task::new("J3")
+                .with_priority_class(PriorityClass::Filler)
+                .with_activity(op("J3_O1", "J3", 0, 100, "M1")),
+            Task::new("J4")
+                .with_release_time(100)
+                .with_activity(op("J4_O1", "J4", 0, 100, "M1")),
+        ];
+        let resources = vec![
+            Resource::primary("M1").with_calendar(Calendar::new("c").with_blocked(0, 200)),
+            Resource::primary("M2"),
+        ];
+        let scheduler = DispatchScheduler::new(RuleEngine::new().with_rule(rules::Spt));
+        let schedule = scheduler.schedule(&tasks, &resources, 0);
+
+        // M1 opens at 200 with J3 and J4 queued: J4's class wins.
+        assert_eq!(start(&schedule, "J4_O1"), 200);
+        assert_eq!(start(&schedule, "J3_O1"), 300);
+        assert_eq!(start(&schedule, "J1_O1"), 500);
+        assert_eq!(start(&schedule, "J2_O1"), 1500);
+        assert_eq!(schedule.assignments.len(), 4);
+    }
+
+    #[test]
+    fn test_per_resource_disciplines_and_queue_stats() {
+        // The same arrivals on M1 (FIFO) and M2 (the engine's SPT)
+        let job = |id: &str, release: i64, ms: i64, machine: &str| {
+            Task::new(id).with_release_time(release).with_activity(op(
+                &format!("{id}_O1"),
+                id,
+                0,
+                ms,
+                machine,
+            ))
+        };
+        let tasks = vec![
+            job("A", 0, 2000, "M1"),
+            job("B", 500, 3000, "M1"),
+            job("C", 1000, 1000, "M1"),
+            job("D", 0, 2000, "M2"),
+            job("E", 500, 3000, "M2"),
+            job("F", 1000, 1000, "M2"),
+        ];
+        let resources = vec![Resource::primary("M1"), Resource::primary("M2")];
+        let report = DispatchScheduler::new(RuleEngine::new().with_rule(rules::Spt))
+            .with_discipline("M1", QueueDiscipline::Fifo)
+            .simulate(&tasks, &resources, 0);
+        let schedule = &report.schedule;
+        assert_eq!(
+            (start(schedule, "B_O1"), start(schedule, "C_O1")),
+            (2000, 5000)
+        );
+        assert_eq!(
+            (start(schedule, "F_O1"), start(schedule, "E_O1")),
+            (2000, 3000)
+        );
+
+        let [m1, m2] = &report.queues[..] else {
+            panic!("one entry per resource")
+        };
+        assert_eq!((m1.resource_id.as_str(), m1.dispatched), ("M1", 3));
+        assert_eq!(m1.max_length, 2);
+        assert_eq!((m1.total_wait_ms, m1.max_wait_ms), (1500 + 4000, 4000));
+        assert_eq!((m2.total_wait_ms, m2.max_wait_ms), (1000 + 2500, 2500));
+        assert!((m2.mean_wait_ms() - 3500.0 / 3.0).abs() < 1e-9);
+        assert_eq!(m1.preemptions + m2.preemptions, 0);
+
+        // A rule-based discipline overrides the scheduler's engine.
+        let lpt = DispatchScheduler::new(RuleEngine::new().with_rule(rules::Spt))
+            .with_discipline(
+                "M2",
+                QueueDiscipline::Rules(RuleEngine::new().with_rule(rules::Lpt)),
+            )
+            .schedule(&tasks, &resources, 0);
+        assert_eq!(start(&lpt, "E_O1"), 2000);
+    }
+
+    #[test]
+    fn test_preemptive_priority() {
+        let tasks = vec![
+            Task::new("J1").with_activity(op("J1_O1", "J1", 0, 3000, "M1")),
+            Task::new("J2")
+                .with_priority(5)
+                .with_release_time(1000)
+                .with_activity(op("J2_O1", "J2", 0, 1000, "M1")),
+        ];
+        let resources = vec![Resource::primary("M1")];
+        let engine = RuleEngine::new().with_rule(rules::Spt);
+
+        // J2 interrupts J1, which resumes its remaining 2000 ms after it.
+        let report = DispatchScheduler::new(engine.clone())
+            .with_discipline("M1", QueueDiscipline::PreemptivePriority)
+            .simulate(&tasks, &resources, 0);
+        let spans: Vec<(&str, i64, i64)> = report
+            .schedule
+            .assignments
+            .iter()
+            .map(|a| (a.activity_id.as_str(), a.start_ms, a.end_ms))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                ("J1_O1", 0, 1000),
+                ("J2_O1", 1000, 2000),
+                ("J1_O1", 2000, 4000)
+            ]
+        );
+        let queue = &report.queues[0];
+        assert_eq!((queue.preemptions, queue.dispatched), (1, 3));
+        assert_eq!(queue.total_wait_ms, 1000);
+        assert!(report.schedule.violations.is_empty());
+
+        // Without preemption J2 waits; at equal priority nobody is interrupted.
+        let plain = DispatchScheduler::new(engine.clone()).schedule(&tasks, &resources, 0);
+        assert_eq!(start(&plain, "J2_O1"), 3000);
+        let mut equal = tasks.clone();
+        equal[1].priority = 0;
+        let schedule = DispatchScheduler::new(engine)
+            .with_discipline("M1", QueueDiscipline::PreemptivePriority)
+            .schedule(&equal, &resources, 0);
+        assert_eq!(start(&schedule, "J2_O1"), 3000);
+        assert_eq!(schedule.assignments.len(), 2);
+    }
+
+    #[test]
+    fn test_stall_diagnostics() {
+        let scheduler = DispatchScheduler::new(RuleEngine::new().with_rule(rules::Spt));
+        let resources = vec![
+            Resource::primary("M1").with_calendar(Calendar::new("c").with_window(0, 1000)),
+            Resource::primary("M2"),
+        ];
+
+        // J1 and J2 wait for each other; J3 arrives after M1 has closed.
+        let tasks = vec![
+            Task::new("J1")
+                .with_activity(op("J1_O1", "J1", 0, 100, "M2").with_predecessor("J2_O1")),
+            Task::new("J2")
+                .with_activity(op("J2_O1", "J2", 0, 100, "M2").with_predecessor("J1_O1")),
+            Task::new("J3")
+                .with_release_time(1500)
+                .with_activity(op("J3_O1", "J3", 0, 100, "M1")),
+            Task::new("J4").with_activity(op("J4_O1", "J4", 0, 800, "M1")),
+        ];
+        let report = scheduler.simulate(&tasks, &resources, 0);
+        assert_eq!(report.schedule.assignments.len(), 1);
+        let stall = report.stall.expect("stalled");
+        assert_eq!(stall.at_ms, 800);
+        let jobs: Vec<(&str, Vec<String>, Vec<String>)> = stall
+            .blocked_jobs
+            .iter()
+            .map(|j| {
+                (
+                    j.task_id.as_str(),
+                    j.waiting_for.clone(),
+                    j.machines.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            jobs,
+            [
+                ("J1", vec!["J2_O1".to_string()], vec![]),
+                ("J2", vec!["J1_O1".to_string()], vec![]),
+                ("J3", vec![], vec!["M1".to_string()]),
+            ]
+        );
+        assert_eq!(stall.closed_machines, ["M1"]);
+
+        // A run that finishes reports no stall.
+        let report = scheduler.simulate(&tasks[3..], &resources, 0);
+        assert!(report.stall.is_none());
+    }
+
+    #[test]
+    fn test_stall_limit() {
+        let tasks = vec![
+            Task::new("J1").with_activity(op("J1_O1", "J1", 0, 1000, "M1")),
+            Task::new("J2")
+                .with_release_time(10_000)
+                .with_activity(op("J2_O1", "J2", 0, 1000, "M1")),
+        ];
+        let resources = vec![Resource::primary("M1")];
+        let scheduler = DispatchScheduler::new(RuleEngine::new().with_rule(rules::Spt));
+
+        // The 9 s gap after J1 exceeds a 5 s limit but not a 10 s one.
+        let report = scheduler
+            .clone()
+            .with_stall_limit(5000)
+            .simulate(&tasks, &resources, 0);
+        let stall = report.stall.expect("stalled");
+        assert_eq!(stall.at_ms, 1000);
+        assert_eq!(stall.blocked_jobs.len(), 1);
+        assert_eq!(stall.blocked_jobs[0].activity_id, "J2_O1");
+        assert!(stall.closed_machines.is_empty());
+
+        let report = scheduler
+            .with_stall_limit(10_000)
+            .simulate(&tasks, &resources, 0);
+        assert!(report.stall.is_none());
+        assert_eq!(start(&report.schedule, "J2_O1"), 10_000);
+    }
+}
@@ -0,0 +1,896 @@
+//! Manual schedule editing.
+//!
+//! The building block for drag-and-drop Gantt editing: apply a planner's
+//! edit (move an assignment in time, to another resource, or both),
+//! optionally push dependent work later to make room, re-validate with
+//! [`check_schedule`], and report which violations the edit introduced
+//! or resolved.
+//!
+//! # Right Shift
+//!
+//! With right shifting enabled, activities pushed by the edit — through
+//! their task sequence, explicit predecessors, or the order of work on a
+//! single-capacity resource — move later just enough to respect those
+//! constraints, transitively. The edited activity itself never moves, and
+//! nothing moves earlier.
+//!
+//! # Sequence Edits
+//!
+//! Resource-centric operations — swap neighbors on a resource, move an
+//! activity earlier or later in a resource's sequence, reassign it to
+//! another candidate — rebuild the times of the changed stretch of the
+//! sequence, with setups recomputed from the transition matrices, push
+//! dependent work later and re-validate, in one call. They are the
+//! building blocks for local search as well as Gantt editing.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::ScheduleRequest;
+use crate::models::{Activity, Schedule, Task, Violation};
+use crate::validation::check_schedule;
+
+/// A manual change to one assignment.
+///
+/// The assignment is identified by its activity and current resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManualEdit {
+    /// Activity of the edited assignment.
+    pub activity_id: String,
+    /// Current resource of the edited assignment.
+    pub resource_id: String,
+    /// New start time (ms), if moved in time.
+    pub start_ms: Option<i64>,
+    /// New resource, if moved to another resource.
+    pub new_resource_id: Option<String>,
+}
+
+impl ManualEdit {
+    /// Creates an edit of the assignment of `activity_id` on `resource_id`
+    /// that changes nothing yet.
+    pub fn new(activity_id: impl Into<String>, resource_id: impl Into<String>) -> Self {
+        Self {
+            activity_id: activity_id.into(),
+            resource_id: resource_id.into(),
+            start_ms: None,
+            new_resource_id: None,
+        }
+    }
+
+    /// Moves the assignment to a new start time.
+    pub fn with_start(mut self, start_ms: i64) -> Self {
+        self.start_ms = Some(start_ms);
+        self
+    }
+
+    /// Moves the assignment to another resource.
+    pub fn with_resource(mut self, resource_id: impl Into<String>) -> Self {
+        self.new_resource_id = Some(resource_id.into());
+        self
+    }
+}
+
+/// Result of applying a [`ManualEdit`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EditOutcome {
+    /// Other activities whose times the edit changed, in the order
+    /// moved (right shifting only moves them later).
+    pub shifted: Vec<String>,
+    /// Violations present after the edit but not before.
+    pub introduced: Vec<Violation>,
+    /// Violations present before the edit but not after.
+    pub resolved: Vec<Violation>,
+}
+
+impl EditOutcome {
+    /// Whether the edit introduced no violation.
+    pub fn is_clean(&self) -> bool {
+        self.introduced.is_empty()
+    }
+}
+
+/// Applies manual edits to a schedule, keeping its violations current.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, Resource, ResourceRequirement, Task};
+/// use u_schedule::scheduler::{ManualEdit, ScheduleEditor, ScheduleRequest, SimpleScheduler};
+///
+/// let task = |id: &str| {
+///     Task::new(id).with_activity(
+///         Activity::new(format!("{id}_O1"), id, 0)
+///             .with_process_time(1000)
+///             .with_requirement(ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()])),
+///     )
+/// };
+/// let request = ScheduleRequest::new(vec![task("J1"), task("J2")], vec![Resource::primary("M1")]);
+/// let schedule = SimpleScheduler::new().schedule_request(&request);
+///
+/// // Drag J2 to the front: J1 is pushed behind it.
+/// let mut editor = ScheduleEditor::new(&request, schedule).with_right_shift(true);
+/// let outcome = editor.apply(&ManualEdit::new("J2_O1", "M1").with_start(0)).unwrap();
+/// assert_eq!(outcome.shifted, vec!["J1_O1"]);
+/// assert!(outcome.is_clean());
+/// assert_eq!(editor.schedule().makespan_ms(), 2000);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScheduleEditor<'a> {
+    request: &'a ScheduleRequest,
+    schedule: Schedule,
+    right_shift: bool,
+}
+
+impl<'a> ScheduleEditor<'a> {
+    /// Creates an editor for `schedule`, a solution of `request`.
+    ///
+    /// The schedule's violations are replaced by a fresh
+    /// [`check_schedule`].
+    pub fn new(request: &'a ScheduleRequest, mut schedule: Schedule) -> Self {
+        schedule.violations = check_schedule(&schedule, &request.tasks, &request.resources);
+        Self {
+            request,
+            schedule,
+            right_shift: false,
+        }
+    }
+
+    /// Enables or disables right shifting of dependent activities
+    /// (default: disabled).
+    pub fn with_right_shift(mut self, enabled: bool) -> Self {
+        self.right_shift = enabled;
+        self
+    }
+
+    /// The current schedule.
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Consumes the editor, returning the current schedule.
+    pub fn into_schedule(self) -> Schedule {
+        self.schedule
+    }
+
+    /// Applies an edit.
+    ///
+    /// Moving in time shifts all assignments of the activity (e.g., its
+    /// setup crew) by the same amount; moving to another resource changes
+    /// only the edited assignment. Setup times are kept as they are.
+    ///
+    /// # Errors
+    /// If the assignment does not exist or the new resource is not in the
+    /// request; the schedule is then unchanged.
+    pub fn apply(&mut self, edit: &ManualEdit) -> Result<EditOutcome, String> {
+        let target = self
+            .schedule
+            .assignments
+            .iter()
+            .position(|a| a.activity_id == edit.activity_id && a.resource_id == edit.resource_id)
+            .ok_or_else(|| {
+                format!(
+                    "no assignment of `{}` on `{}`",
+                    edit.activity_id, edit.resource_id
+                )
+            })?;
+        if let Some(resource_id) = &edit.new_resource_id {
+            if !self.request.resources.iter().any(|r| &r.id == resource_id) {
+                return Err(format!("unknown resource `{resource_id}`"));
+            }
+        }
+
+        if let Some(start_ms) = edit.start_ms {
+            let delta = start_ms - self.schedule.assignments[target].start_ms;
+            shift(&mut self.schedule, &edit.activity_id, delta);
+        }
+        if let Some(resource_id) = &edit.new_resource_id {
+            self.schedule.assignments[target].resource_id = resource_id.clone();
+        }
+        let shifted = if self.right_shift {
+            self.right_shift_from(&edit.activity_id)
+        } else {
+            Vec::new()
+        };
+        Ok(self.revalidate(shifted))
+    }
+
+    /// Activities on a resource, in order of work.
+    pub fn sequence(&self, resource_id: &str) -> Vec<&str> {
+        let mut on_resource: Vec<_> = self
+            .schedule
+            .assignments
+            .iter()
+            .filter(|a| a.resource_id == resource_id && !a.is_conditioning())
+            .collect();
+        on_resource.sort_by_key(|a| (a.start_ms, a.end_ms));
+        let mut order: Vec<&str> = Vec::new();
+        for a in on_resource {
+            if !order.contains(&a.activity_id.as_str()) {
+                order.push(&a.activity_id);
+            }
+        }
+        order
+    }
+
+    /// Swaps an activity with the next one on a resource.
+    ///
+    /// See [`move_in_sequence`](Self::move_in_sequence).
+    ///
+    /// # Errors
+    /// If the activity is not on the resource or is its last.
+    pub fn swap_with_next(
+        &mut self,
+        resource_id: &str,
+        activity_id: &str,
+    ) -> Result<EditOutcome, String> {
+        let order = self.sequence(resource_id);
+        let position = position_on(&order, activity_id, resource_id)?;
+        if position + 1 == order.len() {
+            return Err(format!("`{activity_id}` is the last on `{resource_id}`"));
+        }
+        self.move_in_sequence(resource_id, activity_id, 1)
+    }
+
+    /// Moves an activity `offset` places later (negative: earlier) in a
+    /// resource's sequence, clamped to the sequence.
+    ///
+    /// The reordered stretch of the sequence is re-timed: each activity
+    /// starts as soon as the one before it on the resource, its task
+    /// predecessors and its release allow, with the setup recomputed for
+    /// its new predecessor. Later work on the resource keeps its times
+    /// unless pushed, and dependent work is right-shifted, whatever
+    /// [`with_right_shift`](Self::with_right_shift) says.
+    ///
+    /// # Errors
+    /// If the activity is not on the resource.
+    pub fn move_in_sequence(
+        &mut self,
+        resource_id: &str,
+        activity_id: &str,
+        offset: isize,
+    ) -> Result<EditOutcome, String> {
+        let mut order: Vec<String> = self
+            .sequence(resource_id)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let from = position_on(&order, activity_id, resource_id)?;
+        let to = from.saturating_add_signed(offset).min(order.len() - 1);
+        // The reordered stretch starts no earlier than it did.
+        let floor = self
+            .schedule
+            .assignments
+            .iter()
+            .filter(|a| a.resource_id == resource_id && a.activity_id == order[from.min(to)])
+            .map(|a| a.start_ms)
+            .min()
+            .unwrap_or(i64::MIN);
+        let moved = order.remove(from);
+        order.insert(to, moved);
+
+        let mut changed = self.retime(resource_id, &order, from.min(to), from.max(to) + 1, floor);
+        changed.extend(self.right_shift_after(activity_id, &changed));
+        changed.retain(|id| id != activity_id);
+        Ok(self.revalidate(changed))
+    }
+
+    /// Moves an activity to another candidate resource of the same
+    /// requirement, where its current start falls in that resource's
+    /// sequence.
+    ///
+    /// Both sequences are re-timed from the change as in
+    /// [`move_in_sequence`](Self::move_in_sequence): the activity starts
+    /// as early as the new resource's sequence allows, and keeps its
+    /// processing time.
+    ///
+    /// # Errors
+    /// If the activity is not on `resource_id` or `new_resource_id` is not
+    /// a candidate for it.
+    pub fn reassign(
+        &mut self,
+        activity_id: &str,
+        resource_id: &str,
+        new_resource_id: &str,
+    ) -> Result<EditOutcome, String> {
+        let old_order = self.sequence(resource_id);
+        let from = position_on(&old_order, activity_id, resource_id)?;
+        let eligible = self
+            .request
+            .tasks
+            .iter()
+            .flat_map(|t| &t.activities)
+            .find(|a| a.id == activity_id)
+            .is_some_and(|activity| {
+                activity.resource_requirements.iter().any(|req| {
+                    let candidates = req.resolve(&self.request.resources);
+                    candidates.contains(&resource_id) && candidates.contains(&new_resource_id)
+                })
+            });
+        if !eligible || new_resource_id == resource_id {
+            return Err(format!(
+                "`{new_resource_id}` is not another candidate for `{activity_id}`"
+            ));
+        }
+
+        let start = self
+            .schedule
+            .assignments
+            .iter()
+            .filter(|a| a.activity_id == activity_id && a.resource_id == resource_id)
+            .map(|a| a.start_ms)
+            .min()
+            .unwrap_or(0);
+        let mut new_order: Vec<String> = self
+            .sequence(new_resource_id)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let to = new_order
+            .iter()
+            .position(|id| {
+                self.schedule.assignments.iter().any(|a| {
+                    &a.activity_id == id && a.resource_id == new_resource_id && a.start_ms > start
+                })
+            })
+            .unwrap_or(new_order.len());
+        new_order.insert(to, activity_id.to_string());
+        let old_order: Vec<String> = old_order
+            .into_iter()
+            .filter(|id| *id != activity_id)
+            .map(String::from)
+            .collect();
+        for a in self
+            .schedule
+            .assignments
+            .iter_mut()
+            .filter(|a| a.activity_id == activity_id && a.resource_id == resource_id)
+        {
+            a.resource_id = new_resource_id.to_string();
+        }
+
+        let mut changed = self.retime(new_resource_id, &new_order, to, to + 1, i64::MIN);
+        for id in self.retime(resource_id, &old_order, from, from, i64::MIN) {
+            if !changed.contains(&id) {
+                changed.push(id);
+            }
+        }
+        changed.extend(self.right_shift_after(activity_id, &changed));
+        changed.retain(|id| id != activity_id);
+        Ok(self.revalidate(changed))
+    }
+
+    /// Re-times the work on a resource in `order` (activity IDs) from
+    /// position `from`, starting no earlier than `floor`; activities from
+    /// position `keep` on never start earlier than they do. Returns the
+    /// activities whose times changed.
+    fn retime(
+        &mut self,
+        resource_id: &str,
+        order: &[String],
+        from: usize,
+        keep: usize,
+        floor: i64,
+    ) -> Vec<String> {
+        let mut tasks: HashMap<&str, (&Task, Option<&Activity>, &Activity)> = HashMap::new();
+        for task in &self.request.tasks {
+            for (i, activity) in task.activities.iter().enumerate() {
+                let previous = i.checked_sub(1).map(|p| &task.activities[p]);
+                tasks.insert(&activity.id, (task, previous, activity));
+            }
+        }
+        let on_resource = |schedule: &Schedule, activity_id: &str| {
+            schedule
+                .assignments
+                .iter()
+                .enumerate()
+                .filter(|(_, a)| a.activity_id == activity_id && a.resource_id == resource_id)
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>()
+        };
+
+        let mut changed = Vec::new();
+        for position in from..order.len() {
+            let activity_id = order[position].as_str();
+            let Some(&(task, previous, activity)) = tasks.get(activity_id) else {
+                continue;
+            };
+            let own = on_resource(&self.schedule, activity_id);
+            let Some(&first) = own
+                .iter()
+                .min_by_key(|&&i| self.schedule.assignments[i].start_ms)
+            else {
+                continue;
+            };
+            let old = self.schedule.assignments[first].clone();
+
+            // Earliest start after the previous work on the resource, the
+            // task's predecessors and its release.
+            let mut earliest = floor
+                .max(self.request.start_time_ms)
+                .max(task.release_time.unwrap_or(i64::MIN));
+            let mut setup = activity.duration.setup_ms;
+            if let Some(before) = position.checked_sub(1).map(|p| order[p].as_str()) {
+                let end = on_resource(&self.schedule, before)
+                    .iter()
+                    .map(|&i| self.schedule.assignments[i].end_ms)
+                    .max();
+                earliest = earliest.max(end.unwrap_or(i64::MIN));
+                if let Some(&(before_task, _, _)) = tasks.get(before) {
+                    setup += self.request.transition_matrices.get_transition_time(
+                        resource_id,
+                        &before_task.category,
+                        &task.category,
+                    );
+                }
+            }
+            if let Some((prev_start, prev_end)) =
+                previous.and_then(|p| processing_span(&self.schedule, &p.id))
+            {
+                earliest = earliest.max(activity.precedence_mode.earliest_start(
+                    prev_start,
+                    prev_end,
+                    activity.precedence_lag_ms,
+                    old.process_ms(),
+                ));
+            }
+            for pred in &activity.predecessors {
+                if let Some((_, end)) = processing_span(&self.schedule, pred) {
+                    earliest = earliest.max(end);
+                }
+            }
+            if position >= keep {
+                earliest = earliest.max(old.start_ms);
+            }
+
+            shift(&mut self.schedule, activity_id, earliest - old.start_ms);
+            if own.len() == 1 {
+                // Recompute the setup; work held alongside ends with it.
+                let shifted_end = old.end_ms + earliest - old.start_ms;
+                let end = earliest + setup + old.process_ms() + old.teardown_ms;
+                for a in self
+                    .schedule
+                    .assignments
+                    .iter_mut()
+                    .filter(|a| a.activity_id == activity_id && a.end_ms == shifted_end)
+                {
+                    a.end_ms = end;
+                }
+                self.schedule.assignments[first].setup_ms = setup;
+            }
+            let new = &self.schedule.assignments[first];
+            if (new.start_ms, new.end_ms) != (old.start_ms, old.end_ms) {
+                changed.push(activity_id.to_string());
+            }
+        }
+        changed
+    }
+
+    /// Right-shifts work constrained by the edited and re-timed
+    /// activities; returns the pushed activities.
+    fn right_shift_after(&mut self, edited: &str, retimed: &[String]) -> Vec<String> {
+        let sequence = work_sequence(self.request, &self.schedule, Some(edited));
+        let mut moved: HashSet<String> = retimed.iter().cloned().collect();
+        moved.insert(edited.to_string());
+        right_shift(
+            self.request,
+            &mut self.schedule,
+            &sequence,
+            moved,
+            None,
+            &[],
+        )
+        .into_iter()
+        .filter(|id| !retimed.contains(id))
+        .collect()
+    }
+
+    /// Replaces the violations with a fresh audit and reports the change.
+    fn revalidate(&mut self, shifted: Vec<String>) -> EditOutcome {
+        let before = std::mem::take(&mut self.schedule.violations);
+        let after = check_schedule(&self.schedule, &self.request.tasks, &self.request.resources);
+        let outcome = EditOutcome {
+            shifted,
+            introduced: after
+                .iter()
+                .filter(|v| !before.contains(v))
+                .cloned()
+                .collect(),
+            resolved: before
+                .iter()
+                .filter(|v| !after.contains(v))
+                .cloned()
+                .collect(),
+        };
+        self.schedule.violations = after;
+        outcome
+    }
+
+    /// Pushes activities constrained by the edited one; returns the
+    /// pushed activities.
+    fn right_shift_from(&mut self, edited: &str) -> Vec<String> {
+        // Order of work fixed after the edit (the edited activity goes
+        // first among equal starts).
+        let sequence = work_sequence(self.request, &self.schedule, Some(edited));
+        right_shift(
+            self.request,
+            &mut self.schedule,
+            &sequence,
+            HashSet::from([edited.to_string()]),
+            Some(edited),
+            &[],
+        )
+    }
+}
+
+/// Position of an activity in a resource's sequence.
+fn position_on<S: AsRef<str>>(
+    order: &[S],
+    activity_id: &str,
+    resource_id: &str,
+) -> Result<usize, String> {
+    order
+        .iter()
+        .position(|id| id.as_ref() == activity_id)
+        .ok_or_else(|| format!("no assignment of `{activity_id}` on `{resource_id}`"))
+}
+
+/// Assignment indices per single-capacity resource, in order of work
+/// (`first` goes first among equal starts).
+pub(super) fn work_sequence(
+    request: &ScheduleRequest,
+    schedule: &Schedule,
+    first: Option<&str>,
+) -> Vec<(usize, usize)> {
+    let single_capacity: HashSet<&str> = request
+        .resources
+        .iter()
+        .filter(|r| r.capacity <= 1)
+        .map(|r| r.id.as_str())
+        .collect();
+    let mut sequence: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, a) in schedule.assignments.iter().enumerate() {
+        if single_capacity.contains(a.resource_id.as_str()) {
+            sequence.entry(a.resource_id.as_str()).or_default().push(i);
+        }
+    }
+    let assignments = &schedule.assignments;
+    // Assignment index → previous assignment of another activity
+    let mut resource_prev: HashMap<usize, usize> = HashMap::new();
+    for order in sequence.values_mut() {
+        order.sort_by_key(|&i| {
+            (
+                assignments[i].start_ms,
+                Some(assignments[i].activity_id.as_str()) != first,
+                i,
+            )
+        });
+        for pair in order.windows(2) {
+            if assignments[pair[0]].activity_id != assignments[pair[1]].activity_id {
+                resource_prev.insert(pair[1], pair[0]);
+            }
+        }
+    }
+    resource_prev.into_iter().collect()
+}
+
+/// Pushes activities later, transitively, until the constraints from
+/// `moved` activities hold — task sequences, explicit predecessors and
+/// the order of work `sequence` (pairs of assignment and previous
+/// assignment, see [`work_sequence`]) — and no assignment overlaps a
+/// `blocked` window `(resource, start, end)`. The `pinned` activity never
+/// moves. Returns the pushed activities, in the order first pushed.
+pub(super) fn right_shift(
+    request: &ScheduleRequest,
+    schedule: &mut Schedule,
+    sequence: &[(usize, usize)],
+    mut moved: HashSet<String>,
+    pinned: Option<&str>,
+    blocked: &[(String, i64, i64)],
+) -> Vec<String> {
+    let activities: Vec<(Option<&Activity>, &Activity)> = request
+        .tasks
+        .iter()
+        .flat_map(|t| {
+            t.activities
+                .iter()
+                .enumerate()
+                .map(|(i, act)| (i.checked_sub(1).map(|p| &t.activities[p]), act))
+        })
+        .collect();
+
+    let mut shifted = Vec::new();
+    for _ in 0..=activities.len() + blocked.len() {
+        let mut changed = false;
+        for &(previous, act) in &activities {
+            if Some(act.id.as_str()) == pinned {
+                continue;
+            }
+            let Some((start, process_ms)) = start_and_process(schedule, &act.id) else {
+                continue;
+            };
+            let mut bound = i64::MIN;
+            if let Some(prev) = previous.filter(|p| moved.contains(&p.id)) {
+                if let Some((prev_start, prev_end)) = processing_span(schedule, &prev.id) {
+                    bound = bound.max(act.precedence_mode.earliest_start(
+                        prev_start,
+                        prev_end,
+                        act.precedence_lag_ms,
+                        process_ms,
+                    ));
+                }
+            }
+            for pred in act.predecessors.iter().filter(|p| moved.contains(*p)) {
+                if let Some((_, end)) = processing_span(schedule, pred) {
+                    bound = bound.max(end);
+                }
+            }
+            for &(i, prev) in sequence {
+                let (a, p) = (&schedule.assignments[i], &schedule.assignments[prev]);
+                if a.activity_id == act.id && moved.contains(&p.activity_id) {
+                    // Keep the assignment's offset within its activity.
+                    bound = bound.max(p.end_ms - (a.start_ms - start));
+                }
+            }
+            for a in schedule
+                .assignments
+                .iter()
+                .filter(|a| a.activity_id == act.id)
+            {
+                for (_, _, to) in blocked.iter().filter(|(resource_id, from, to)| {
+                    *resource_id == a.resource_id && a.start_ms < *to && *from < a.end_ms
+                }) {
+                    bound = bound.max(to - (a.start_ms - start));
+                }
+            }
+            if bound > start {
+                shift(schedule, &act.id, bound - start);
+                if moved.insert(act.id.clone()) {
+                    shifted.push(act.id.clone());
+                }
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    shifted
+}
+
+/// Moves all assignments of an activity by `delta` ms.
+pub(super) fn shift(schedule: &mut Schedule, activity_id: &str, delta: i64) {
+    for a in schedule
+        .assignments
+        .iter_mut()
+        .filter(|a| a.activity_id == activity_id)
+    {
+        a.start_ms += delta;
+        a.end_ms += delta;
+    }
+}
+
+/// Start and processing time of an activity's processing assignments.
+fn start_and_process(schedule: &Schedule, activity_id: &str) -> Option<(i64, i64)> {
+    schedule
+        .assignments
+        .iter()
+        .filter(|a| a.activity_id == activity_id && a.process_ms() > 0)
+        .map(|a| (a.start_ms, a.process_ms()))
+        .reduce(|(s1, p1), (s2, p2)| (s1.min(s2), p1.max(p2)))
+}
+
+/// Processing interval of an activity: (start after setup, end).
+fn processing_span(schedule: &Schedule, activity_id: &str) -> Option<(i64, i64)> {
+    schedule
+        .assignments
+        .iter()
+        .filter(|a| a.activity_id == activity_id && a.process_ms() > 0)
+        .map(|a| (a.start_ms + a.setup_ms, a.end_ms))
+        .reduce(|(s1, e1), (s2, e2)| (s1.min(s2), e1.max(e2)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        Assignment, Resource, ResourceRequirement, TransitionMatrix, TransitionMatrixCollection,
+        ViolationType,
+    };
+    use crate::scheduler::SimpleScheduler;
+
+    fn on(ids: &[&str]) -> ResourceRequirement {
+        ResourceRequirement::new("Machine")
+            .with_candidates(ids.iter().map(|s| s.to_string()).collect())
+    }
+
+    /// J1: O1 (M1) → O2 (M2); J2: O3 (M2). Greedy: O1 [0,1000),
+    /// O2 [1000,2000), O3 [2000,3000).
+    fn request() -> ScheduleRequest {
+        ScheduleRequest::new(
+            vec![
+                Task::new("J1")
+                    .with_activity(
+                        Activity::new("O1", "J1", 0)
+                            .with_process_time(1000)
+                            .with_requirement(on(&["M1"])),
+                    )
+                    .with_activity(
+                        Activity::new("O2", "J1", 1)
+                            .with_process_time(1000)
+                            .with_requirement(on(&["M2"])),
+                    ),
+                Task::new("J2").with_deadline(3500).with_activity(
+                    Activity::new("O3", "J2", 0)
+                        .with_process_time(1000)
+                        .with_requirement(on(&["M2"])),
+                ),
+            ],
+            vec![
+                Resource::primary("M1"),
+                Resource::primary("M2"),
+                Resource::primary("M3"),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_move_reports_violation_delta() {
+        let request = request();
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+        let mut editor = ScheduleEditor::new(&request, schedule);
+        assert!(editor.schedule().is_valid());
+
+        // Moving O1 later breaks the task sequence with O2.
+        let outcome = editor
+            .apply(&ManualEdit::new("O1", "M1").with_start(500))
+            .unwrap();
+        assert!(outcome.shifted.is_empty());
+        assert_eq!(outcome.introduced.len(), 1);
+        assert_eq!(
+            outcome.introduced[0].violation_type,
+            ViolationType::PrecedenceViolation
+        );
+        assert!(outcome.resolved.is_empty());
+
+        // Moving it back resolves the violation.
+        let outcome = editor
+            .apply(&ManualEdit::new("O1", "M1").with_start(0))
+            .unwrap();
+        assert!(outcome.is_clean());
+        assert_eq!(outcome.resolved.len(), 1);
+        assert!(editor.schedule().is_valid());
+    }
+
+    #[test]
+    fn test_right_shift_pushes_dependents_transitively() {
+        let request = request();
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+        let mut editor = ScheduleEditor::new(&request, schedule).with_right_shift(true);
+
+        // O1 → 1500 pushes O2 (task sequence) to 2500, which pushes O3
+        // (M2 order) to 3500 and misses J2's deadline.
+        let outcome = editor
+            .apply(&ManualEdit::new("O1", "M1").with_start(1500))
+            .unwrap();
+        assert_eq!(outcome.shifted, vec!["O2", "O3"]);
+        let s = editor.schedule();
+        assert_eq!(s.assignment_for_activity("O2").unwrap().start_ms, 2500);
+        assert_eq!(s.assignment_for_activity("O3").unwrap().start_ms, 3500);
+        assert_eq!(outcome.introduced.len(), 1);
+        assert_eq!(
+            outcome.introduced[0].violation_type,
+            ViolationType::DeadlineMiss
+        );
+    }
+
+    #[test]
+    fn test_reassign_and_errors() {
+        let request = request();
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+        let mut editor = ScheduleEditor::new(&request, schedule);
+
+        // O3 onto idle M3 at 1000: no conflicts.
+        let outcome = editor
+            .apply(
+                &ManualEdit::new("O3", "M2")
+                    .with_resource("M3")
+                    .with_start(1000),
+            )
+            .unwrap();
+        assert!(outcome.is_clean());
+        let o3 = editor.schedule().assignment_for_activity("O3").unwrap();
+        assert_eq!((o3.resource_id.as_str(), o3.start_ms), ("M3", 1000));
+
+        // Onto M1 at 500 overlaps O1.
+        let outcome = editor
+            .apply(
+                &ManualEdit::new("O3", "M3")
+                    .with_resource("M1")
+                    .with_start(500),
+            )
+            .unwrap();
+        assert_eq!(
+            outcome.introduced[0].violation_type,
+            ViolationType::CapacityExceeded
+        );
+
+        let before = editor.schedule().fingerprint();
+        assert!(editor
+            .apply(&ManualEdit::new("O3", "M2").with_start(0))
+            .is_err());
+        assert!(editor
+            .apply(&ManualEdit::new("O3", "M1").with_resource("M9"))
+            .is_err());
+        assert_eq!(editor.schedule().fingerprint(), before);
+    }
+
+    #[test]
+    fn test_sequence_moves_retime_the_resource() {
+        let request = request();
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+        let mut editor = ScheduleEditor::new(&request, schedule);
+        assert_eq!(editor.sequence("M2"), ["O2", "O3"]);
+
+        // O3 goes first on M2 and starts when M2 frees up; O2 follows.
+        let outcome = editor.swap_with_next("M2", "O2").unwrap();
+        assert_eq!(outcome.shifted, ["O3"]);
+        assert!(outcome.is_clean());
+        let s = editor.schedule();
+        assert_eq!(editor.sequence("M2"), ["O3", "O2"]);
+        assert_eq!(s.assignment_for_activity("O3").unwrap().start_ms, 1000);
+        assert_eq!(s.assignment_for_activity("O2").unwrap().start_ms, 2000);
+
+        // Moving O2 back to the front (clamped) restores the plan.
+        editor.move_in_sequence("M2", "O2", -5).unwrap();
+        assert_eq!(editor.sequence("M2"), ["O2", "O3"]);
+        assert_eq!(editor.schedule().makespan_ms(), 3000);
+
+        assert!(editor.swap_with_next("M2", "O3").is_err());
+        assert!(editor.move_in_sequence("M1", "O3", 1).is_err());
+    }
+
+    #[test]
+    fn test_setups_follow_the_sequence() {
+        let job = |id: &str, category: &str| {
+            Task::new(id).with_category(category).with_activity(
+                Activity::new(id, id, 0)
+                    .with_process_time(1000)
+                    .with_requirement(on(&["M1", "M2"])),
+            )
+        };
+        let mut matrix = TransitionMatrix::new("changeover", "M1").with_default(300);
+        matrix.set_transition("A", "A", 0);
+        let mut request = ScheduleRequest::new(
+            vec![job("A1", "A"), job("B1", "B"), job("A2", "A")],
+            vec![Resource::primary("M1"), Resource::primary("M2")],
+        );
+        request.transition_matrices = TransitionMatrixCollection::new().with_matrix(matrix);
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("A1", "A1", "M1", 0, 1000));
+        plan.add_assignment(Assignment::new("B1", "B1", "M1", 1000, 2300).with_setup(300));
+        plan.add_assignment(Assignment::new("A2", "A2", "M1", 2300, 3600).with_setup(300));
+        let mut editor = ScheduleEditor::new(&request, plan);
+
+        // Grouping the A jobs saves one changeover.
+        let outcome = editor.swap_with_next("M1", "B1").unwrap();
+        assert!(outcome.is_clean());
+        let s = editor.schedule();
+        let a2 = s.assignment_for_activity("A2").unwrap();
+        assert_eq!((a2.start_ms, a2.end_ms, a2.setup_ms), (1000, 2000, 0));
+        let b1 = s.assignment_for_activity("B1").unwrap();
+        assert_eq!((b1.start_ms, b1.end_ms, b1.setup_ms), (2000, 3300, 300));
+
+        // Reassigning B1 to the idle M2 starts it at once, without setup.
+        let outcome = editor.reassign("B1", "M1", "M2").unwrap();
+        assert!(outcome.is_clean());
+        let b1 = editor.schedule().assignment_for_activity("B1").unwrap();
+        assert_eq!(
+            (b1.resource_id.as_str(), b1.start_ms, b1.end_ms),
+            ("M2", 0, 1000)
+        );
+        assert_eq!(editor.schedule().makespan_ms(), 2000);
+        assert!(editor.reassign("A1", "M1", "M3").is_err());
+        assert!(editor.reassign("A1", "M2", "M1").is_err());
+    }
+}
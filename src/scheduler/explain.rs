@@ -0,0 +1,451 @@
+//! Schedule explanations.
+//!
+//! Answers "why does task X start at t?" by reconstructing, for each
+//! assignment of the task, the constraints that prevent an earlier start.
+//! Works on any schedule (greedy, GA, CP, or hand-edited), using only the
+//! request and the schedule itself.
+//!
+//! # Reasons
+//!
+//! | Reason | Binding when the assignment starts at |
+//! |--------|---------------------------------------|
+//! | Schedule start | The request start time |
+//! | Release time | The task's release time |
+//! | Predecessor | The earliest start allowed by a predecessor |
+//! | Resource busy | The end of another assignment on the resource |
+//! | Calendar | The start of a working period of the resource |
+//!
+//! Setup time is reported alongside: processing begins after it.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::ScheduleRequest;
+use crate::models::{Assignment, PrecedenceMode, Resource, Schedule, Task};
+
+/// A constraint that binds the start of an assignment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StartReason {
+    /// Nothing may start before the request start time.
+    ScheduleStart {
+        /// Request start (ms).
+        start_ms: i64,
+    },
+    /// The task is not released earlier.
+    ReleaseTime {
+        /// Release time (ms).
+        release_ms: i64,
+    },
+    /// Waited for a predecessor activity.
+    Predecessor {
+        /// Predecessor activity ID.
+        activity_id: String,
+        /// Relation to the predecessor.
+        mode: PrecedenceMode,
+        /// Earliest start allowed by the predecessor (ms).
+        until_ms: i64,
+    },
+    /// The resource was busy with another activity.
+    ResourceBusy {
+        /// Resource ID.
+        resource_id: String,
+        /// Activity occupying the resource.
+        activity_id: String,
+        /// End of that activity (ms).
+        until_ms: i64,
+    },
+    /// The resource was not working earlier.
+    Calendar {
+        /// Resource ID.
+        resource_id: String,
+        /// Start of the working period (ms).
+        available_from_ms: i64,
+    },
+}
+
+impl fmt::Display for StartReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ScheduleStart { start_ms } => write!(f, "schedule starts at {start_ms}"),
+            Self::ReleaseTime { release_ms } => write!(f, "released at {release_ms}"),
+            Self::Predecessor {
+                activity_id,
+                mode,
+                until_ms,
+            } => {
+                let relation = match mode {
+                    PrecedenceMode::FinishToStart => "to finish".to_string(),
+                    PrecedenceMode::StartToStart => "to start".to_string(),
+                    PrecedenceMode::FinishToFinish => "(finish-to-finish)".to_string(),
+                    PrecedenceMode::Overlap { percent } => format!("({percent}% overlap)"),
+                };
+                write!(
+                    f,
+                    "waited for predecessor {activity_id} {relation} until {until_ms}"
+                )
+            }
+            Self::ResourceBusy {
+                resource_id,
+                activity_id,
+                until_ms,
+            } => write!(f, "{resource_id} busy with {activity_id} until {until_ms}"),
+            Self::Calendar {
+                resource_id,
+                available_from_ms,
+            } => write!(f, "{resource_id} not working until {available_from_ms}"),
+        }
+    }
+}
+
+/// Why one assignment starts when it does.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssignmentExplanation {
+    /// Activity ID.
+    pub activity_id: String,
+    /// Resource ID.
+    pub resource_id: String,
+    /// Start time (ms).
+    pub start_ms: i64,
+    /// Setup time before processing (ms).
+    pub setup_ms: i64,
+    /// Binding constraints.
+    ///
+    /// Empty when nothing binds the start: it could move earlier, as far
+    /// as the request, the calendar and other assignments are concerned
+    /// (e.g., after a manual edit, or when working-time rules apply).
+    pub reasons: Vec<StartReason>,
+}
+
+impl fmt::Display for AssignmentExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} on {} starts at {}",
+            self.activity_id, self.resource_id, self.start_ms
+        )?;
+        if self.reasons.is_empty() {
+            write!(f, ": no binding constraint")?;
+        } else {
+            let reasons: Vec<String> = self.reasons.iter().map(|r| r.to_string()).collect();
+            write!(f, ": {}", reasons.join("; "))?;
+        }
+        if self.setup_ms > 0 {
+            write!(f, " (setup of {} ms first)", self.setup_ms)?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a task starts when it does, assignment by assignment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskExplanation {
+    /// Task ID.
+    pub task_id: String,
+    /// Earliest start of the task's assignments (ms).
+    pub start_ms: i64,
+    /// Assignments in activity order, then by start.
+    pub assignments: Vec<AssignmentExplanation>,
+}
+
+impl fmt::Display for TaskExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} starts at {}", self.task_id, self.start_ms)?;
+        for a in &self.assignments {
+            write!(f, "\n- {a}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Explains start times of a schedule.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, Resource, ResourceRequirement, Task};
+/// use u_schedule::scheduler::{ScheduleExplainer, ScheduleRequest, SimpleScheduler, StartReason};
+///
+/// let task = |id: &str| {
+///     Task::new(id).with_activity(
+///         Activity::new(format!("{id}_O1"), id, 0)
+///             .with_process_time(1000)
+///             .with_requirement(ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()])),
+///     )
+/// };
+/// let request = ScheduleRequest::new(vec![task("J1"), task("J2")], vec![Resource::primary("M1")]);
+/// let schedule = SimpleScheduler::new().schedule_request(&request);
+///
+/// let explanation = ScheduleExplainer::new(&request, &schedule).explain("J2").unwrap();
+/// assert_eq!(explanation.start_ms, 1000);
+/// assert!(matches!(
+///     &explanation.assignments[0].reasons[0],
+///     StartReason::ResourceBusy { activity_id, .. } if activity_id == "J1_O1"
+/// ));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScheduleExplainer<'a> {
+    request: &'a ScheduleRequest,
+    schedule: &'a Schedule,
+    resources: HashMap<&'a str, &'a Resource>,
+}
+
+impl<'a> ScheduleExplainer<'a> {
+    /// Creates an explainer for `schedule`, a solution of `request`.
+    pub fn new(request: &'a ScheduleRequest, schedule: &'a Schedule) -> Self {
+        Self {
+            request,
+            schedule,
+            resources: request
+                .resources
+                .iter()
+                .map(|r| (r.id.as_str(), r))
+                .collect(),
+        }
+    }
+
+    /// Explains the start of a task.
+    ///
+    /// Returns `None` if the task is unknown or has no assignments.
+    pub fn explain(&self, task_id: &str) -> Option<TaskExplanation> {
+        let task = self.request.tasks.iter().find(|t| t.id == task_id)?;
+        let mut assignments = Vec::new();
+        for (index, activity) in task.activities.iter().enumerate() {
+            let mut own: Vec<&Assignment> = self
+                .schedule
+                .assignments
+                .iter()
+                .filter(|a| a.activity_id == activity.id)
+                .collect();
+            own.sort_by_key(|a| a.start_ms);
+            for a in own {
+                assignments.push(AssignmentExplanation {
+                    activity_id: a.activity_id.clone(),
+                    resource_id: a.resource_id.clone(),
+                    start_ms: a.start_ms,
+                    setup_ms: a.setup_ms,
+                    reasons: self.reasons(task, index, a),
+                });
+            }
+        }
+        let start_ms = assignments.iter().map(|a| a.start_ms).min()?;
+        Some(TaskExplanation {
+            task_id: task.id.clone(),
+            start_ms,
+            assignments,
+        })
+    }
+
+    fn reasons(&self, task: &Task, index: usize, assignment: &Assignment) -> Vec<StartReason> {
+        let start = assignment.start_ms;
+        let activity = &task.activities[index];
+        let mut reasons = Vec::new();
+
+        if start == self.request.start_time_ms {
+            reasons.push(StartReason::ScheduleStart { start_ms: start });
+        }
+        if task.release_time == Some(start) && start > self.request.start_time_ms {
+            reasons.push(StartReason::ReleaseTime { release_ms: start });
+        }
+
+        // The previous activity of the task, through the precedence mode.
+        if let Some(prev) = task.chained_predecessor(index).map(|i| &task.activities[i]) {
+            if let Some((prev_start, prev_end)) = self.processing_span(&prev.id) {
+                let bound = activity.precedence_mode.earliest_start(
+                    prev_start,
+                    prev_end,
+                    activity.precedence_lag_ms,
+                    assignment.process_ms(),
+                );
+                if bound == start {
+                    reasons.push(StartReason::Predecessor {
+                        activity_id: prev.id.clone(),
+                        mode: activity.precedence_mode,
+                        until_ms: bound,
+                    });
+                }
+            }
+        }
+        // Explicit predecessors: finish-to-start.
+        for pred in &activity.predecessors {
+            if let Some((_, end)) = self.processing_span(pred) {
+                if end == start {
+                    reasons.push(StartReason::Predecessor {
+                        activity_id: pred.clone(),
+                        mode: PrecedenceMode::FinishToStart,
+                        until_ms: end,
+                    });
+                }
+            }
+        }
+
+        for other in self.schedule.assignments.iter().filter(|o| {
+            o.resource_id == assignment.resource_id
+                && o.activity_id != assignment.activity_id
+                && o.end_ms == start
+        }) {
+            reasons.push(StartReason::ResourceBusy {
+                resource_id: other.resource_id.clone(),
+                activity_id: other.activity_id.clone(),
+                until_ms: start,
+            });
+        }
+
+        let calendar = self
+            .resources
+            .get(assignment.resource_id.as_str())
+            .and_then(|r| r.calendar.as_ref());
+        if let Some(calendar) = calendar {
+            if start > i64::MIN
+                && calendar.is_working_time(start)
+                && !calendar.is_working_time(start - 1)
+            {
+                reasons.push(StartReason::Calendar {
+                    resource_id: assignment.resource_id.clone(),
+                    available_from_ms: start,
+                });
+            }
+        }
+        reasons
+    }
+
+    /// Processing interval of an activity: (start after setup, end),
+    /// spanning all of its assignments.
+    fn processing_span(&self, activity_id: &str) -> Option<(i64, i64)> {
+        self.schedule
+            .assignments
+            .iter()
+            .filter(|a| a.activity_id == activity_id && a.process_ms() > 0)
+            .map(|a| (a.start_ms + a.setup_ms, a.end_ms))
+            .reduce(|(s1, e1), (s2, e2)| (s1.min(s2), e1.max(e2)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        Activity, ActivityDuration, Calendar, ResourceRequirement, TransitionMatrix,
+        TransitionMatrixCollection,
+    };
+    use crate::scheduler::SimpleScheduler;
+
+    fn machine(ids: &[&str]) -> ResourceRequirement {
+        ResourceRequirement::new("Machine")
+            .with_candidates(ids.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[test]
+    fn test_explains_predecessor_and_busy_resource() {
+        let j1 = Task::new("J1")
+            .with_activity(
+                Activity::new("J1_O1", "J1", 0)
+                    .with_process_time(1000)
+                    .with_requirement(machine(&["M1"])),
+            )
+            .with_activity(
+                Activity::new("J1_O2", "J1", 1)
+                    .with_process_time(1000)
+                    .with_requirement(machine(&["M2"])),
+            );
+        let j2 = Task::new("J2").with_release_time(500).with_activity(
+            Activity::new("J2_O1", "J2", 0)
+                .with_process_time(1000)
+                .with_requirement(machine(&["M2"])),
+        );
+        let request = ScheduleRequest::new(
+            vec![j1, j2],
+            vec![Resource::primary("M1"), Resource::primary("M2")],
+        );
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+        let explainer = ScheduleExplainer::new(&request, &schedule);
+
+        let j1 = explainer.explain("J1").unwrap();
+        assert_eq!(
+            j1.assignments[0].reasons,
+            vec![StartReason::ScheduleStart { start_ms: 0 }]
+        );
+        assert_eq!(
+            j1.assignments[1].reasons,
+            vec![StartReason::Predecessor {
+                activity_id: "J1_O1".into(),
+                mode: PrecedenceMode::FinishToStart,
+                until_ms: 1000,
+            }]
+        );
+
+        // J2 is released at 500 but M2 is busy with J1_O2 until 2000.
+        let j2 = explainer.explain("J2").unwrap();
+        assert_eq!(j2.start_ms, 2000);
+        assert_eq!(
+            j2.assignments[0].reasons,
+            vec![StartReason::ResourceBusy {
+                resource_id: "M2".into(),
+                activity_id: "J1_O2".into(),
+                until_ms: 2000,
+            }]
+        );
+        assert!(j2.to_string().contains("M2 busy with J1_O2 until 2000"));
+
+        assert!(explainer.explain("J9").is_none());
+    }
+
+    #[test]
+    fn test_explains_release_setup_and_calendar() {
+        let mut tm = TransitionMatrix::new("changeover", "M1");
+        tm.set_transition("A", "B", 300);
+        let request = ScheduleRequest::new(
+            vec![
+                Task::new("J1").with_category("A").with_activity(
+                    Activity::new("J1_O1", "J1", 0)
+                        .with_process_time(1000)
+                        .with_requirement(machine(&["M1"])),
+                ),
+                Task::new("J2")
+                    .with_category("B")
+                    .with_release_time(1000)
+                    .with_activity(
+                        Activity::new("J2_O1", "J2", 0)
+                            .with_duration(ActivityDuration::fixed(500))
+                            .with_requirement(machine(&["M1"])),
+                    ),
+            ],
+            vec![Resource::primary("M1")],
+        )
+        .with_transition_matrices(TransitionMatrixCollection::new().with_matrix(tm));
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+
+        let j2 = ScheduleExplainer::new(&request, &schedule)
+            .explain("J2")
+            .unwrap();
+        let a = &j2.assignments[0];
+        assert_eq!(a.setup_ms, 300);
+        assert!(a
+            .reasons
+            .contains(&StartReason::ReleaseTime { release_ms: 1000 }));
+        assert!(a.to_string().contains("setup of 300 ms"));
+
+        // A hand-placed assignment at the start of a shift.
+        let shifted = ScheduleRequest::new(
+            request.tasks.clone(),
+            vec![
+                Resource::primary("M1").with_calendar(Calendar::new("day").with_window(5000, 9000))
+            ],
+        );
+        let mut manual = Schedule::new();
+        manual.add_assignment(Assignment::new("J1_O1", "J1", "M1", 5000, 6000));
+        manual.add_assignment(Assignment::new("J2_O1", "J2", "M1", 7000, 7500));
+        let explainer = ScheduleExplainer::new(&shifted, &manual);
+        assert_eq!(
+            explainer.explain("J1").unwrap().assignments[0].reasons,
+            vec![StartReason::Calendar {
+                resource_id: "M1".into(),
+                available_from_ms: 5000,
+            }]
+        );
+        let j2 = explainer.explain("J2").unwrap();
+        assert!(j2.assignments[0].reasons.is_empty());
+        assert!(j2.to_string().contains("no binding constraint"));
+    }
+}
@@ -0,0 +1,148 @@
+//! Workload fairness metrics.
+//!
+//! Pure makespan objectives tend to concentrate work on a few people.
+//! These metrics measure how evenly assigned time is spread over a set
+//! of resources, by default the human ones.
+//!
+//! # Metrics
+//!
+//! | Metric | Definition |
+//! |--------|-----------|
+//! | Spread | max − min assigned time |
+//! | Gini | `Σᵢ Σⱼ |xᵢ − xⱼ| / (2 n² x̄)`, 0 = equal, → 1 = concentrated |
+//!
+//! # Reference
+//! Gini (1912), "Variabilità e mutabilità"
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Resource, ResourceType, Schedule};
+
+/// Workload distribution over a set of resources.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadFairness {
+    /// Assigned time per resource (ms), including setup.
+    pub workload_by_resource: BTreeMap<String, i64>,
+    /// Largest workload (ms).
+    pub max_workload_ms: i64,
+    /// Smallest workload (ms).
+    pub min_workload_ms: i64,
+    /// Max − min workload (ms).
+    pub spread_ms: i64,
+    /// Gini coefficient of workloads (0.0 = perfectly even).
+    pub gini: f64,
+}
+
+impl WorkloadFairness {
+    /// Fairness over the human resources in `resources`.
+    ///
+    /// Resources without assignments count with zero workload.
+    pub fn for_humans(schedule: &Schedule, resources: &[Resource]) -> Self {
+        let ids: Vec<&str> = resources
+            .iter()
+            .filter(|r| r.resource_type == ResourceType::Human)
+            .map(|r| r.id.as_str())
+            .collect();
+        Self::calculate(schedule, &ids)
+    }
+
+    /// Fairness over the given resource IDs.
+    pub fn calculate(schedule: &Schedule, resource_ids: &[&str]) -> Self {
+        let mut workload: BTreeMap<String, i64> =
+            resource_ids.iter().map(|id| (id.to_string(), 0)).collect();
+        for a in &schedule.assignments {
+            if let Some(w) = workload.get_mut(&a.resource_id) {
+                *w += a.duration_ms();
+            }
+        }
+
+        let values: Vec<i64> = workload.values().copied().collect();
+        let max = values.iter().copied().max().unwrap_or(0);
+        let min = values.iter().copied().min().unwrap_or(0);
+        Self {
+            max_workload_ms: max,
+            min_workload_ms: min,
+            spread_ms: max - min,
+            gini: gini(&values),
+            workload_by_resource: workload,
+        }
+    }
+}
+
+/// Gini coefficient of non-negative values (0.0 for empty or all-zero).
+fn gini(values: &[i64]) -> f64 {
+    let n = values.len();
+    let total: i64 = values.iter().sum();
+    if n == 0 || total <= 0 {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    // Σᵢ (2i − n − 1)·xᵢ over ascending values (1-based i).
+    let weighted: i64 = sorted
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| (2 * (i as i64 + 1) - n as i64 - 1) * x)
+        .sum();
+    weighted as f64 / (n as f64 * total as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Assignment;
+
+    fn schedule(loads: &[(&str, i64)]) -> Schedule {
+        let mut s = Schedule::new();
+        for (i, &(r, ms)) in loads.iter().enumerate() {
+            s.add_assignment(Assignment::new(format!("A{i}"), "T", r, 0, ms));
+        }
+        s
+    }
+
+    #[test]
+    fn test_even_workload() {
+        let s = schedule(&[("W1", 100), ("W2", 100)]);
+        let f = WorkloadFairness::calculate(&s, &["W1", "W2"]);
+        assert_eq!(f.spread_ms, 0);
+        assert_eq!(f.gini, 0.0);
+    }
+
+    #[test]
+    fn test_concentrated_workload() {
+        let s = schedule(&[("W1", 300), ("W1", 100)]);
+        let f = WorkloadFairness::calculate(&s, &["W1", "W2"]);
+        assert_eq!(f.workload_by_resource["W1"], 400);
+        assert_eq!(f.workload_by_resource["W2"], 0);
+        assert_eq!(
+            (f.max_workload_ms, f.min_workload_ms, f.spread_ms),
+            (400, 0, 400)
+        );
+        // Two people, one does everything: G = (n − 1) / n = 0.5.
+        assert!((f.gini - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_for_humans_filters_resource_type() {
+        let resources = vec![
+            Resource::new("W1", ResourceType::Human),
+            Resource::new("W2", ResourceType::Human),
+            Resource::primary("M1"),
+        ];
+        let s = schedule(&[("W1", 100), ("W2", 300), ("M1", 1000)]);
+        let f = WorkloadFairness::for_humans(&s, &resources);
+        assert_eq!(f.workload_by_resource.len(), 2);
+        assert_eq!(f.spread_ms, 200);
+        // |100 − 300|·2 / (2·4·200) = 0.25
+        assert!((f.gini - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gini_edge_cases() {
+        assert_eq!(gini(&[]), 0.0);
+        assert_eq!(gini(&[0, 0]), 0.0);
+        assert_eq!(gini(&[5]), 0.0);
+    }
+}
@@ -0,0 +1,495 @@
+//! Order aggregation: merging many small identical tasks before solving.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{ScheduleRequest, SimpleScheduler};
+use crate::models::{Activity, Assignment, PriorityClass, Schedule, Task};
+use crate::validation::check_schedule;
+
+/// Tasks merged into one aggregated task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderGroup {
+    /// ID of the aggregated task.
+    pub task_id: String,
+    /// Member task IDs, in processing order inside the batch.
+    pub member_ids: Vec<String>,
+}
+
+impl OrderGroup {
+    /// Number of merged tasks.
+    pub fn quantity(&self) -> usize {
+        self.member_ids.len()
+    }
+}
+
+/// A request with small identical tasks merged, and the way back.
+#[derive(Debug, Clone)]
+pub struct AggregatedRequest {
+    /// The reduced request to solve.
+    pub request: ScheduleRequest,
+    /// Aggregated tasks (groups of two or more tasks).
+    pub groups: Vec<OrderGroup>,
+    original: ScheduleRequest,
+}
+
+impl AggregatedRequest {
+    /// Task count before and after aggregation.
+    pub fn reduction(&self) -> (usize, usize) {
+        (self.original.tasks.len(), self.request.tasks.len())
+    }
+
+    /// Expands a schedule of the reduced request to the original tasks.
+    ///
+    /// Each assignment of an aggregated activity is cut into consecutive
+    /// slices, one per member in batch order: the first member carries
+    /// the setup, and the remaining worked time is shared in proportion
+    /// to the members' processing times. Assignments split into segments
+    /// are cut along their worked time, so a member may get several
+    /// segments. Violations are those of
+    /// [`check_schedule`] against the original tasks.
+    pub fn expand(&self, schedule: &Schedule) -> Schedule {
+        let groups: HashMap<&str, &OrderGroup> = self
+            .groups
+            .iter()
+            .map(|g| (g.task_id.as_str(), g))
+            .collect();
+        let originals: HashMap<&str, &Task> = self
+            .original
+            .tasks
+            .iter()
+            .map(|t| (t.id.as_str(), t))
+            .collect();
+
+        let mut expanded = Schedule::new();
+        // (activity, resource) → assignments, in first-seen order.
+        let mut pieces: Vec<((&str, &str), Vec<&Assignment>)> = Vec::new();
+        for a in &schedule.assignments {
+            if !groups.contains_key(a.task_id.as_str()) {
+                expanded.add_assignment(a.clone());
+                continue;
+            }
+            let key = (a.activity_id.as_str(), a.resource_id.as_str());
+            match pieces.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, list)) => list.push(a),
+                None => pieces.push((key, vec![a])),
+            }
+        }
+
+        for ((activity_id, _), mut list) in pieces {
+            let group = groups[list[0].task_id.as_str()];
+            let Some(position) = self
+                .request
+                .tasks
+                .iter()
+                .find(|t| t.id == group.task_id)
+                .and_then(|t| t.activities.iter().position(|a| a.id == activity_id))
+            else {
+                continue;
+            };
+            let members: Vec<(&Task, &Activity)> = group
+                .member_ids
+                .iter()
+                .filter_map(|id| originals.get(id.as_str()))
+                .filter_map(|t| sorted_activities(t).get(position).map(|&a| (*t, a)))
+                .collect();
+            list.sort_by_key(|a| a.start_ms);
+            for (task, activity, start, end) in slice_members(&list, &members) {
+                for (from, to, setup) in map_slice(&list, start, end) {
+                    expanded.add_assignment(
+                        Assignment::new(&activity.id, &task.id, &list[0].resource_id, from, to)
+                            .with_setup(setup),
+                    );
+                }
+            }
+        }
+
+        expanded.violations =
+            check_schedule(&expanded, &self.original.tasks, &self.original.resources);
+        expanded
+    }
+}
+
+/// Merges small identical tasks into aggregated tasks with a quantity.
+///
+/// Planning systems (MRP) often emit thousands of tiny orders for the
+/// same product. Solving them one by one blows up the problem size
+/// without changing the plan much, since identical orders end up
+/// back-to-back anyway. The aggregator merges them, a solver schedules
+/// the reduced request, and [`AggregatedRequest::expand`] maps the result
+/// back to the original orders.
+///
+/// # Grouping
+/// Tasks merge when they share:
+/// - category and priority class,
+/// - routing: the same activities (by sequence) with the same resource
+///   requirements, setup, teardown, splitting, precedence mode and
+///   attributes (processing times may differ),
+/// - due window: deadlines in the same window of `due_window_ms` (or
+///   equal deadlines without a window; undated tasks group together),
+///   and release times likewise.
+///
+/// Tasks that are locked, have explicit predecessors or are explicit
+/// predecessors, or are synchronized with other work stay as they are.
+///
+/// # Aggregated Task
+/// An aggregated task runs its members as one batch: processing times
+/// add up, setup and teardown are paid once. It takes the earliest
+/// deadline, the latest release time, the tightest maximum flow time,
+/// the highest priority, the summed tardiness cost, and the attributes
+/// all members agree on. Members run in deadline order inside the batch.
+#[derive(Debug, Clone, Default)]
+pub struct OrderAggregator {
+    due_window_ms: Option<i64>,
+    max_group_size: Option<usize>,
+}
+
+impl OrderAggregator {
+    /// Creates an aggregator that merges equal deadlines only, without a
+    /// size limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merges tasks whose deadlines (and release times) fall in the same
+    /// window of `window_ms`, counted from the request start.
+    pub fn with_due_window(mut self, window_ms: i64) -> Self {
+        self.due_window_ms = Some(window_ms.max(1));
+        self
+    }
+
+    /// Limits an aggregated task to `size` members.
+    pub fn with_max_group_size(mut self, size: usize) -> Self {
+        self.max_group_size = Some(size.max(1));
+        self
+    }
+
+    /// Builds the reduced request.
+    pub fn aggregate(&self, request: &ScheduleRequest) -> AggregatedRequest {
+        let tasks = &request.tasks;
+        let fixed = self.fixed_tasks(request);
+        let limit = self.max_group_size.unwrap_or(usize::MAX);
+
+        // Groups of task indices, in order of their first member.
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut open: HashMap<GroupKey, Vec<usize>> = HashMap::new();
+        for (i, task) in tasks.iter().enumerate() {
+            if fixed.contains(task.id.as_str()) {
+                groups.push(vec![i]);
+                continue;
+            }
+            let key = GroupKey {
+                category: task.category.clone(),
+                class: task.priority_class,
+                due: self.window(task.deadline, request.start_time_ms),
+                release: self.window(task.release_time, request.start_time_ms),
+            };
+            let candidates = open.entry(key).or_default();
+            let joined = candidates
+                .iter()
+                .copied()
+                .find(|&g| groups[g].len() < limit && same_routing(&tasks[groups[g][0]], task));
+            match joined {
+                Some(g) => groups[g].push(i),
+                None => {
+                    candidates.push(groups.len());
+                    groups.push(vec![i]);
+                }
+            }
+        }
+
+        let mut reduced = Vec::with_capacity(groups.len());
+        let mut order_groups = Vec::new();
+        for mut members in groups {
+            if members.len() == 1 {
+                reduced.push(tasks[members[0]].clone());
+                continue;
+            }
+            members.sort_by_key(|&i| (tasks[i].deadline.unwrap_or(i64::MAX), i));
+            let members: Vec<&Task> = members.iter().map(|&i| &tasks[i]).collect();
+            let task = merge(&members);
+            order_groups.push(OrderGroup {
+                task_id: task.id.clone(),
+                member_ids: members.iter().map(|t| t.id.clone()).collect(),
+            });
+            reduced.push(task);
+        }
+
+        let mut reduced_request = request.clone();
+        reduced_request.tasks = reduced;
+        AggregatedRequest {
+            request: reduced_request,
+            groups: order_groups,
+            original: request.clone(),
+        }
+    }
+
+    /// Aggregates, schedules with `scheduler` and expands the result.
+    pub fn schedule(&self, request: &ScheduleRequest, scheduler: &SimpleScheduler) -> Schedule {
+        let aggregated = self.aggregate(request);
+        let schedule = scheduler.schedule_request(&aggregated.request);
+        aggregated.expand(&schedule)
+    }
+
+    fn window(&self, time_ms: Option<i64>, origin_ms: i64) -> Option<i64> {
+        match self.due_window_ms {
+            Some(window) => time_ms.map(|t| (t - origin_ms).div_euclid(window)),
+            None => time_ms,
+        }
+    }
+
+    /// Tasks that must stay on their own.
+    fn fixed_tasks<'a>(&self, request: &'a ScheduleRequest) -> HashSet<&'a str> {
+        let mut fixed: HashSet<&str> = request.locks.locked_tasks();
+        let owner: HashMap<&str, &str> = request
+            .tasks
+            .iter()
+            .flat_map(|t| t.activities.iter().map(|a| (a.id.as_str(), t.id.as_str())))
+            .collect();
+        for task in &request.tasks {
+            for activity in &task.activities {
+                if request.locks.resource_for(&activity.id).is_some() {
+                    fixed.insert(&task.id);
+                }
+                let linked = activity
+                    .predecessors
+                    .iter()
+                    .chain(&activity.synchronized_with);
+                for other in linked {
+                    fixed.insert(&task.id);
+                    fixed.extend(owner.get(other.as_str()).copied());
+                }
+            }
+        }
+        fixed
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GroupKey {
+    category: String,
+    class: PriorityClass,
+    due: Option<i64>,
+    release: Option<i64>,
+}
+
+fn sorted_activities(task: &Task) -> Vec<&Activity> {
+    let mut activities: Vec<&Activity> = task.activities.iter().collect();
+    activities.sort_by_key(|a| a.sequence);
+    activities
+}
+
+/// Whether two tasks run the same activities (up to processing times).
+fn same_routing(a: &Task, b: &Task) -> bool {
+    let (xs, ys) = (sorted_activities(a), sorted_activities(b));
+    !xs.is_empty()
+        && xs.len() == ys.len()
+        && xs.iter().zip(&ys).all(|(x, y)| {
+            x.sequence == y.sequence
+                && x.duration.setup_ms == y.duration.setup_ms
+                && x.duration.teardown_ms == y.duration.teardown_ms
+                && x.resource_requirements == y.resource_requirements
+                && x.splittable == y.splittable
+                && x.min_split_ms == y.min_split_ms
+                && x.setup_requirement == y.setup_requirement
+                && x.precedence_mode == y.precedence_mode
+                && x.precedence_lag_ms == y.precedence_lag_ms
+                && x.attributes == y.attributes
+        })
+}
+
+/// The aggregated task of `members` (two or more, same routing).
+fn merge(members: &[&Task]) -> Task {
+    let head = members[0];
+    let id = format!("AGG:{}", head.id);
+    let mut task = Task::new(&id)
+        .with_name(format!("{} ×{}", head.id, members.len()))
+        .with_category(&head.category)
+        .with_priority(members.iter().map(|t| t.priority).max().unwrap_or(0))
+        .with_priority_class(head.priority_class);
+    task.deadline = members.iter().filter_map(|t| t.deadline).min();
+    task.release_time = members.iter().filter_map(|t| t.release_time).max();
+    task.max_flow_ms = members.iter().filter_map(|t| t.max_flow_ms).min();
+    let costs: Vec<f64> = members
+        .iter()
+        .filter_map(|t| t.tardiness_cost_per_hour)
+        .collect();
+    task.tardiness_cost_per_hour = (!costs.is_empty()).then(|| costs.iter().sum());
+    task.attributes = head
+        .attributes
+        .iter()
+        .filter(|(k, v)| members.iter().all(|t| t.attributes.get(*k) == Some(*v)))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    let routings: Vec<Vec<&Activity>> = members.iter().map(|t| sorted_activities(t)).collect();
+    for (pos, template) in routings[0].iter().enumerate() {
+        let mut activity = (*template).clone();
+        activity.id = format!("AGG:{}", template.id);
+        activity.task_id = id.clone();
+        activity.duration.process_ms = routings.iter().map(|r| r[pos].duration.process_ms).sum();
+        task.activities.push(activity);
+    }
+    task
+}
+
+/// Worked-time slices `[start, end)` of each member in one resource's
+/// assignments of an aggregated activity.
+fn slice_members<'a>(
+    list: &[&Assignment],
+    members: &[(&'a Task, &'a Activity)],
+) -> Vec<(&'a Task, &'a Activity, i64, i64)> {
+    let worked: i64 = list.iter().map(|a| a.end_ms - a.start_ms).sum();
+    let setup: i64 = list.iter().map(|a| a.setup_ms).sum::<i64>().min(worked);
+    let shared = worked - setup;
+    let total: i64 = members
+        .iter()
+        .map(|(_, a)| a.duration.process_ms.max(0))
+        .sum();
+    let n = members.len() as i64;
+
+    let mut slices = Vec::with_capacity(members.len());
+    let mut cumulative = 0;
+    let mut start = 0;
+    for (i, &(task, activity)) in members.iter().enumerate() {
+        cumulative += activity.duration.process_ms.max(0);
+        let end = if i + 1 == members.len() {
+            worked
+        } else if total > 0 {
+            setup + (shared as i128 * cumulative as i128 / total as i128) as i64
+        } else {
+            setup + shared * (i as i64 + 1) / n
+        };
+        if end > start {
+            slices.push((task, activity, start, end));
+        }
+        start = end;
+    }
+    slices
+}
+
+/// Maps a worked-time slice onto the assignments, as `(start, end, setup)`.
+fn map_slice(list: &[&Assignment], start: i64, end: i64) -> Vec<(i64, i64, i64)> {
+    let setup: i64 = list.iter().map(|a| a.setup_ms).sum();
+    let mut segments = Vec::new();
+    let mut offset = 0;
+    for a in list {
+        let length = a.end_ms - a.start_ms;
+        let (from, to) = (start.max(offset), end.min(offset + length));
+        if from < to {
+            let in_setup = (to.min(setup) - from).max(0);
+            segments.push((
+                a.start_ms + from - offset,
+                a.start_ms + to - offset,
+                in_setup,
+            ));
+        }
+        offset += length;
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ActivityDuration, Lock, LockSet, Resource, ResourceRequirement};
+
+    fn make_task(id: &str, category: &str, process_ms: i64, deadline: i64) -> Task {
+        Task::new(id)
+            .with_category(category)
+            .with_deadline(deadline)
+            .with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_duration(ActivityDuration::new(100, process_ms, 0))
+                    .with_requirement(
+                        ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                    ),
+            )
+    }
+
+    fn request() -> ScheduleRequest {
+        let tasks = vec![
+            make_task("A1", "A", 200, 5000),
+            make_task("B1", "B", 300, 5000),
+            make_task("A2", "A", 400, 4000),
+            make_task("A3", "A", 200, 9000),
+            make_task("A4", "A", 100, 5500),
+        ];
+        ScheduleRequest::new(tasks, vec![Resource::primary("M1")])
+    }
+
+    #[test]
+    fn test_merges_by_routing_and_due_window() {
+        let request = request();
+        let aggregated = OrderAggregator::new()
+            .with_due_window(2000)
+            .aggregate(&request);
+
+        // A1, A2 and A4 are due in [4000, 6000); A3 and B1 stay alone.
+        assert_eq!(aggregated.reduction(), (5, 3));
+        assert_eq!(aggregated.groups.len(), 1);
+        let group = &aggregated.groups[0];
+        assert_eq!(group.member_ids, vec!["A2", "A1", "A4"]);
+        assert_eq!(group.quantity(), 3);
+
+        let task = &aggregated.request.tasks[0];
+        assert_eq!(task.id, group.task_id);
+        assert_eq!(task.deadline, Some(4000));
+        assert_eq!(task.activities[0].duration.total_ms(), 800);
+
+        // Without a window only equal deadlines merge.
+        let exact = OrderAggregator::new().aggregate(&request);
+        assert_eq!(exact.reduction(), (5, 5));
+    }
+
+    #[test]
+    fn test_expand_restores_orders() {
+        let request = request();
+        let aggregator = OrderAggregator::new().with_due_window(2000);
+        let schedule = aggregator.schedule(&request, &SimpleScheduler::new());
+
+        assert_eq!(schedule.assignment_count(), 5);
+        // The merged orders share one setup, carried by the first.
+        let a2 = schedule.assignment_for_activity("A2_O1").unwrap();
+        assert_eq!((a2.start_ms, a2.end_ms, a2.setup_ms), (0, 500, 100));
+        let a1 = schedule.assignment_for_activity("A1_O1").unwrap();
+        assert_eq!((a1.start_ms, a1.end_ms, a1.setup_ms), (500, 700, 0));
+        let a4 = schedule.assignment_for_activity("A4_O1").unwrap();
+        assert_eq!((a4.task_id.as_str(), a4.end_ms), ("A4", 800));
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_expand_cuts_segments() {
+        let request = ScheduleRequest::new(
+            vec![make_task("A1", "A", 300, 0), make_task("A2", "A", 300, 0)],
+            vec![Resource::primary("M1")],
+        );
+        let aggregated = OrderAggregator::new().aggregate(&request);
+        let id = &aggregated.groups[0].task_id;
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("AGG:A1_O1", id, "M1", 0, 400).with_setup(100));
+        schedule.add_assignment(Assignment::new("AGG:A1_O1", id, "M1", 1000, 1300));
+
+        let expanded = aggregated.expand(&schedule);
+        let a2: Vec<(i64, i64)> = expanded
+            .assignments_for_task("A2")
+            .iter()
+            .map(|a| (a.start_ms, a.end_ms))
+            .collect();
+        assert_eq!(a2, vec![(1000, 1300)]);
+        assert_eq!(expanded.task_completion_time("A1"), Some(400));
+    }
+
+    #[test]
+    fn test_locked_tasks_stay_alone() {
+        let mut manual = Schedule::new();
+        manual.add_assignment(Assignment::new("A1_O1", "A1", "M1", 0, 300));
+        let request = ScheduleRequest::new(
+            vec![make_task("A1", "A", 200, 0), make_task("A2", "A", 200, 0)],
+            vec![Resource::primary("M1")],
+        )
+        .with_locks(LockSet::new().with_lock(Lock::task(&manual, "A1")));
+        let aggregated = OrderAggregator::new().aggregate(&request);
+        assert!(aggregated.groups.is_empty());
+    }
+}
@@ -10,21 +10,37 @@
 //! | Makespan (C_max) | Latest completion time |
 //! | Total Tardiness | Sum of max(0, completion - deadline) |
 //! | Maximum Tardiness | Largest single delay |
+//! | Tardiness Cost | Sum of hours late × the task's [`WeightProfile`] rate |
 //! | On-Time Rate | Fraction meeting deadlines |
 //! | Avg Utilization | Mean resource busyness |
 //! | Avg Flow Time | Mean time from release to completion |
+//! | Flow Overruns | Tasks exceeding their maximum flow time, and the largest excess |
+//! | Split Activities | Activities worked in more than one segment on a resource |
+//! | Interruption | Unworked time inside split activities |
+//! | Throughput | Planned against target completions per category, with shortfalls (see [`ScheduleKpi::with_throughput`]) |
+//! | Setups per Shift | Category changeovers per resource and calendar window (see [`ScheduleKpi::with_changeovers`]) |
+//! | Resource Cost | Booked resource time at each resource's hourly rate (see [`ScheduleKpi::with_resource_cost`]) |
+//!
+//! Tardiness follows the shared definitions in [`crate::objective`].
 //!
 //! # Reference
 //! Pinedo (2016), "Scheduling", Ch. 1.2: Performance Measures
 
 use std::collections::HashMap;
 
-use crate::models::{Schedule, Task};
+use serde::{Deserialize, Serialize};
+
+use super::CostModel;
+use crate::models::{
+    CategoryThroughput, Resource, Schedule, SegmentedAssignment, Task, ThroughputTargets,
+    WeightProfile,
+};
+use crate::objective::tardiness_ms;
 
 /// Schedule performance indicators.
 ///
 /// All time values are in milliseconds.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduleKpi {
     /// Makespan: latest completion time (ms).
     pub makespan_ms: i64,
@@ -32,6 +48,9 @@ pub struct ScheduleKpi {
     pub total_tardiness_ms: i64,
     /// Maximum tardiness of any single task (ms).
     pub max_tardiness_ms: i64,
+    /// Sum of tardiness weighted by each task's cost per hour late.
+    #[serde(default)]
+    pub tardiness_cost: f64,
     /// Fraction of tasks completing on time (0.0..1.0).
     pub on_time_rate: f64,
     /// Average resource utilization (0.0..1.0).
@@ -40,6 +59,34 @@ pub struct ScheduleKpi {
     pub utilization_by_resource: HashMap<String, f64>,
     /// Average flow time: mean(completion - release) in ms.
     pub avg_flow_time_ms: f64,
+    /// Tasks completing after their [`Task::flow_deadline`].
+    #[serde(default)]
+    pub flow_overruns: usize,
+    /// Largest flow-time excess of any single task (ms).
+    #[serde(default)]
+    pub max_flow_overrun_ms: i64,
+    /// Activities worked in more than one segment on a resource
+    /// (see [`SegmentedAssignment`]).
+    #[serde(default)]
+    pub split_activities: usize,
+    /// Time between the first start and last end of split activities
+    /// not spent working on them (ms).
+    #[serde(default)]
+    pub interruption_ms: i64,
+    /// Planned against target completions per category (empty unless
+    /// [`with_throughput`](Self::with_throughput) was applied).
+    #[serde(default)]
+    pub throughput: Vec<CategoryThroughput>,
+    /// Category changeovers per resource, per calendar window with work in
+    /// time order (empty unless
+    /// [`with_changeovers`](Self::with_changeovers) was applied).
+    #[serde(default)]
+    pub changeovers_per_window: HashMap<String, Vec<usize>>,
+    /// Cost of the resources' time at their
+    /// [`cost_per_hour`](Resource::cost_per_hour) (0 unless
+    /// [`with_resource_cost`](Self::with_resource_cost) was applied).
+    #[serde(default)]
+    pub resource_cost: f64,
 }
 
 impl ScheduleKpi {
@@ -49,12 +96,24 @@ impl ScheduleKpi {
     /// * `schedule` - The completed schedule with assignments.
     /// * `tasks` - The input tasks (for deadlines and release times).
     pub fn calculate(schedule: &Schedule, tasks: &[Task]) -> Self {
+        Self::calculate_weighted(schedule, tasks, &WeightProfile::default())
+    }
+
+    /// Computes KPIs, pricing tardiness with `weights`.
+    pub fn calculate_weighted(
+        schedule: &Schedule,
+        tasks: &[Task],
+        weights: &WeightProfile,
+    ) -> Self {
         let makespan = schedule.makespan_ms();
         let mut total_tardiness: i64 = 0;
         let mut max_tardiness: i64 = 0;
+        let mut tardiness_cost: f64 = 0.0;
         let mut on_time_count: usize = 0;
         let mut total_flow_time: f64 = 0.0;
         let mut counted_tasks: usize = 0;
+        let mut flow_overruns: usize = 0;
+        let mut max_flow_overrun: i64 = 0;
 
         for task in tasks {
             if let Some(completion) = schedule.task_completion_time(&task.id) {
@@ -63,13 +122,21 @@ impl ScheduleKpi {
                 // Flow time
                 let release = task.release_time.unwrap_or(0);
                 total_flow_time += (completion - release) as f64;
+                if let Some(limit) = task.flow_deadline() {
+                    let overrun = tardiness_ms(completion, limit);
+                    if overrun > 0 {
+                        flow_overruns += 1;
+                        max_flow_overrun = max_flow_overrun.max(overrun);
+                    }
+                }
 
                 // Tardiness
                 if let Some(deadline) = task.deadline {
-                    if completion > deadline {
-                        let tardiness = completion - deadline;
+                    let tardiness = tardiness_ms(completion, deadline);
+                    if tardiness > 0 {
                         total_tardiness += tardiness;
                         max_tardiness = max_tardiness.max(tardiness);
+                        tardiness_cost += weights.tardiness_cost(task, tardiness);
                     } else {
                         on_time_count += 1;
                     }
@@ -101,17 +168,76 @@ impl ScheduleKpi {
             total_flow_time / counted_tasks as f64
         };
 
+        let split: Vec<SegmentedAssignment> = schedule
+            .segmented_assignments()
+            .into_iter()
+            .filter(SegmentedAssignment::is_split)
+            .collect();
+
         Self {
             makespan_ms: makespan,
             total_tardiness_ms: total_tardiness,
             max_tardiness_ms: max_tardiness,
+            tardiness_cost,
             on_time_rate,
             avg_utilization,
             utilization_by_resource,
             avg_flow_time_ms,
+            flow_overruns,
+            max_flow_overrun_ms: max_flow_overrun,
+            split_activities: split.len(),
+            interruption_ms: split.iter().map(SegmentedAssignment::interruption_ms).sum(),
+            throughput: Vec::new(),
+            changeovers_per_window: HashMap::new(),
+            resource_cost: 0.0,
         }
     }
 
+    /// Adds planned against target completions per category of `targets`.
+    pub fn with_throughput(
+        mut self,
+        schedule: &Schedule,
+        tasks: &[Task],
+        targets: &ThroughputTargets,
+    ) -> Self {
+        self.throughput = targets.measure(schedule, tasks);
+        self
+    }
+
+    /// Categories planned below their throughput target.
+    pub fn shortfalls(&self) -> Vec<&CategoryThroughput> {
+        self.throughput.iter().filter(|c| c.shortfall > 0).collect()
+    }
+
+    /// Adds the category changeovers of each of `resources` per calendar
+    /// window (see [`Schedule::changeovers_per_window`]).
+    pub fn with_changeovers(
+        mut self,
+        schedule: &Schedule,
+        tasks: &[Task],
+        resources: &[Resource],
+    ) -> Self {
+        self.changeovers_per_window = resources
+            .iter()
+            .map(|r| {
+                let counts = schedule
+                    .changeovers_per_window(r, tasks)
+                    .into_iter()
+                    .map(|(_, count)| count)
+                    .collect();
+                (r.id.clone(), counts)
+            })
+            .collect();
+        self
+    }
+
+    /// Adds the cost of the time booked on `resources`, priced by a
+    /// [`CostModel`] without overtime.
+    pub fn with_resource_cost(mut self, schedule: &Schedule, resources: &[Resource]) -> Self {
+        self.resource_cost = CostModel::new().evaluate(schedule, resources).total();
+        self
+    }
+
     /// Whether the schedule meets the given quality thresholds.
     pub fn meets_thresholds(&self, max_tardiness: i64, min_utilization: f64) -> bool {
         self.max_tardiness_ms <= max_tardiness && self.avg_utilization >= min_utilization
@@ -154,6 +280,30 @@ mod tests {
         assert!((kpi.avg_flow_time_ms - 2000.0).abs() < 1e-10); // (1000+3000)/2
     }
 
+    #[test]
+    fn test_kpi_flow_overruns() {
+        let tasks = vec![
+            make_task("J1", 1000, None, Some(500)).with_max_flow(1000),
+            make_task("J2", 2000, None, None).with_max_flow(2000),
+            make_task("J3", 1000, None, None).with_max_flow(10_000),
+        ];
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("J1_O1", "J1", "M1", 500, 1500));
+        schedule.add_assignment(Assignment::new("J2_O1", "J2", "M1", 1500, 3500));
+        schedule.add_assignment(Assignment::new("J3_O1", "J3", "M1", 3500, 4500));
+
+        let kpi = ScheduleKpi::calculate(&schedule, &tasks);
+        assert_eq!(kpi.flow_overruns, 1);
+        assert_eq!(kpi.max_flow_overrun_ms, 1500);
+
+        let violations = crate::validation::check_schedule(&schedule, &tasks, &[]);
+        let overrun = violations
+            .iter()
+            .find(|v| v.violation_type == crate::models::ViolationType::FlowTimeExceeded)
+            .unwrap();
+        assert_eq!(overrun.entity_id, "J2");
+    }
+
     #[test]
     fn test_kpi_tardiness() {
         let tasks = vec![
@@ -170,6 +320,25 @@ mod tests {
         assert!((kpi.on_time_rate - 0.5).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_kpi_tardiness_cost() {
+        let tasks = vec![
+            make_task("J1", 3_600_000, Some(0), None).with_attribute("customer", "ACME"),
+            make_task("J2", 3_600_000, Some(3_600_000), None).with_tardiness_cost(30.0),
+        ];
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 3_600_000));
+        schedule.add_assignment(Assignment::new("J2_O1", "J2", "M1", 3_600_000, 7_200_000));
+
+        // One hour late each, at the default rate of 1.0 and an explicit 30.0.
+        let kpi = ScheduleKpi::calculate(&schedule, &tasks);
+        assert!((kpi.tardiness_cost - 31.0).abs() < 1e-10);
+
+        let weights = WeightProfile::new().with_customer("ACME", 200.0);
+        let kpi = ScheduleKpi::calculate_weighted(&schedule, &tasks, &weights);
+        assert!((kpi.tardiness_cost - 230.0).abs() < 1e-10);
+    }
+
     #[test]
     fn test_kpi_utilization() {
         let tasks = vec![
@@ -203,6 +372,21 @@ mod tests {
         assert!((kpi.avg_flow_time_ms - 1500.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_kpi_split_activities() {
+        let tasks = vec![make_task("J1", 2000, None, None)];
+        let mut schedule = Schedule::new();
+        // Interrupted from 1000 to 4000 (e.g. a shift end).
+        schedule.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+        schedule.add_assignment(Assignment::new("J1_O1", "J1", "M1", 4000, 5000));
+
+        let kpi = ScheduleKpi::calculate(&schedule, &tasks);
+        assert_eq!(kpi.split_activities, 1);
+        assert_eq!(kpi.interruption_ms, 3000);
+        assert!((kpi.avg_flow_time_ms - 5000.0).abs() < 1e-10);
+        assert!((kpi.utilization_by_resource["M1"] - 0.4).abs() < 1e-10);
+    }
+
     #[test]
     fn test_kpi_empty() {
         let kpi = ScheduleKpi::calculate(&Schedule::new(), &[]);
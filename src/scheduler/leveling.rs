@@ -0,0 +1,152 @@
+//! Window-based capacity leveling.
+//!
+//! Limits how many resources of a pool are needed within each time
+//! bucket, e.g., "no more than 6 operators in any hour". A resource counts
+//! towards a bucket's demand if any of its assignments overlaps the
+//! bucket. Buckets are aligned to the scheduling epoch (t=0).
+//!
+//! # Metrics
+//!
+//! | Metric | Definition |
+//! |--------|-----------|
+//! | Demand | Distinct pool resources busy within a bucket |
+//! | Peak | Largest bucket demand |
+//! | Excess | `Σ max(0, demand − limit)` over buckets |
+//!
+//! # Reference
+//! Neumann & Zimmermann (2000), "Procedures for resource leveling and net
+//! present value problems in project scheduling with general temporal and
+//! resource constraints"
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Resource, ResourceType, Schedule};
+
+/// A capacity limit per time bucket over a resource pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapacityLeveling {
+    /// Bucket length (ms).
+    pub bucket_ms: i64,
+    /// Maximum resources needed per bucket.
+    pub limit: usize,
+    /// Pool: resources of this type (`None` = all resources).
+    #[serde(default)]
+    pub resource_type: Option<ResourceType>,
+}
+
+/// Demand profile of a schedule against a [`CapacityLeveling`] limit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelingProfile {
+    /// Demand per bucket, keyed by bucket start (ms); empty buckets omitted.
+    pub demand_by_bucket: BTreeMap<i64, usize>,
+    /// Largest bucket demand.
+    pub peak: usize,
+    /// Total demand above the limit, summed over buckets.
+    pub excess: usize,
+    /// Number of buckets above the limit.
+    pub overloaded_buckets: usize,
+}
+
+impl CapacityLeveling {
+    /// Creates a limit over all resources.
+    pub fn new(bucket_ms: i64, limit: usize) -> Self {
+        Self {
+            bucket_ms: bucket_ms.max(1),
+            limit,
+            resource_type: None,
+        }
+    }
+
+    /// Restricts the pool to one resource type.
+    pub fn with_resource_type(mut self, resource_type: ResourceType) -> Self {
+        self.resource_type = Some(resource_type);
+        self
+    }
+
+    /// Whether a resource belongs to the pool.
+    pub fn in_pool(&self, resource: &Resource) -> bool {
+        self.resource_type
+            .as_ref()
+            .is_none_or(|t| *t == resource.resource_type)
+    }
+
+    /// Computes the demand profile of `schedule`.
+    pub fn profile(&self, schedule: &Schedule, resources: &[Resource]) -> LevelingProfile {
+        let pool: BTreeSet<&str> = resources
+            .iter()
+            .filter(|r| self.in_pool(r))
+            .map(|r| r.id.as_str())
+            .collect();
+
+        let mut busy: BTreeMap<i64, BTreeSet<&str>> = BTreeMap::new();
+        for a in &schedule.assignments {
+            if a.end_ms <= a.start_ms || !pool.contains(a.resource_id.as_str()) {
+                continue;
+            }
+            let first = a.start_ms.div_euclid(self.bucket_ms);
+            let last = (a.end_ms - 1).div_euclid(self.bucket_ms);
+            for bucket in first..=last {
+                busy.entry(bucket * self.bucket_ms)
+                    .or_default()
+                    .insert(a.resource_id.as_str());
+            }
+        }
+
+        let demand_by_bucket: BTreeMap<i64, usize> =
+            busy.into_iter().map(|(t, set)| (t, set.len())).collect();
+        let over = demand_by_bucket.values().filter(|&&d| d > self.limit);
+        LevelingProfile {
+            peak: demand_by_bucket.values().copied().max().unwrap_or(0),
+            excess: over.clone().map(|&d| d - self.limit).sum(),
+            overloaded_buckets: over.count(),
+            demand_by_bucket,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Assignment;
+
+    fn resources() -> Vec<Resource> {
+        vec![
+            Resource::human("W1"),
+            Resource::human("W2"),
+            Resource::human("W3"),
+            Resource::primary("M1"),
+        ]
+    }
+
+    #[test]
+    fn test_profile_counts_distinct_resources_per_bucket() {
+        let mut s = Schedule::new();
+        s.add_assignment(Assignment::new("A", "T", "W1", 0, 150));
+        s.add_assignment(Assignment::new("B", "T", "W1", 150, 180));
+        s.add_assignment(Assignment::new("C", "T", "W2", 50, 100));
+        s.add_assignment(Assignment::new("D", "T", "W3", 90, 110));
+        s.add_assignment(Assignment::new("E", "T", "M1", 0, 300));
+
+        let leveling = CapacityLeveling::new(100, 2).with_resource_type(ResourceType::Human);
+        let p = leveling.profile(&s, &resources());
+        assert_eq!(p.demand_by_bucket, BTreeMap::from([(0, 3), (100, 2)]));
+        assert_eq!((p.peak, p.excess, p.overloaded_buckets), (3, 1, 1));
+
+        // All resources: the machine joins buckets 0–200.
+        let p = CapacityLeveling::new(100, 2).profile(&s, &resources());
+        assert_eq!(
+            p.demand_by_bucket,
+            BTreeMap::from([(0, 4), (100, 3), (200, 1)])
+        );
+        assert_eq!((p.peak, p.excess, p.overloaded_buckets), (4, 3, 2));
+    }
+
+    #[test]
+    fn test_empty_schedule() {
+        let p = CapacityLeveling::new(100, 0).profile(&Schedule::new(), &resources());
+        assert!(p.demand_by_bucket.is_empty());
+        assert_eq!((p.peak, p.excess), (0, 0));
+    }
+}
@@ -0,0 +1,614 @@
+//! Large neighborhood search (LNS) improvement.
+//!
+//! Polishes an existing schedule from any backend (greedy, GA, CP, or a
+//! hand-edited plan): repeatedly removes part of it and rebuilds the
+//! removed tasks with the greedy decoder, keeping the result when it is
+//! better. Runs on `u-metaheur`'s ALNS, which adapts how often each
+//! destroy and repair operator is used to how often it succeeds.
+//!
+//! # Destroy Operators
+//!
+//! | Operator | Removes |
+//! |----------|---------|
+//! | Random machine | Tasks with work on one random resource |
+//! | Time window | Tasks running closest to a random point in time |
+//! | Worst tardiness | Tasks with the largest (class-weighted) tardiness |
+//!
+//! # Repair Operators
+//!
+//! | Operator | Reinserts removed tasks |
+//! |----------|-------------------------|
+//! | Due date | Into the dispatch order by priority class and deadline |
+//! | Random | At random positions in the dispatch order |
+//!
+//! # Reference
+//! Shaw (1998), "Using constraint programming and local search methods to
+//! solve vehicle routing problems"; Ropke & Pisinger (2006), "An adaptive
+//! large neighborhood search heuristic for the pickup and delivery problem
+//! with time windows"
+
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use u_metaheur::alns::{AlnsConfig, AlnsProblem, AlnsRunner, DestroyOperator, RepairOperator};
+
+use super::{ScheduleRequest, SimpleScheduler};
+use crate::models::{Schedule, Task};
+use crate::objective;
+
+/// Cost per activity left unscheduled; dominates any time-based term.
+const UNSCHEDULED_PENALTY: f64 = 1e12;
+
+/// Outcome of an LNS run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LnsReport {
+    /// Cost of the input schedule.
+    pub initial_cost: f64,
+    /// Cost of the returned schedule (never above `initial_cost`).
+    pub final_cost: f64,
+    /// Destroy–repair iterations run.
+    pub iterations: usize,
+    /// Iterations that found a new best schedule.
+    pub improvements: usize,
+}
+
+/// Improves schedules by large neighborhood search.
+///
+/// # Algorithm
+/// The input schedule is read back as a dispatch order (tasks by first
+/// start) plus the resource of each activity. Each iteration:
+/// 1. A destroy operator removes 10–40% of the tasks (see
+///    [`with_destroy_degree`](Self::with_destroy_degree)).
+/// 2. A repair operator reinserts them into the dispatch order.
+/// 3. The greedy decoder rebuilds the schedule: kept activities stay on
+///    their resources, removed ones may pick any candidate.
+/// 4. The candidate replaces the current schedule if it is not worse.
+///
+/// The cost is `(1 − w) × makespan + w × Σ tardiness`, with tardiness
+/// scaled by priority class as in the GA. The input schedule is kept
+/// as-is unless a better one is found.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, ActivityDuration, Resource, ResourceRequirement, Task};
+/// use u_schedule::scheduler::{LnsImprover, ScheduleRequest, SimpleScheduler};
+///
+/// let tasks = (0..4)
+///     .map(|i| {
+///         let id = format!("J{i}");
+///         Task::new(&id).with_activity(
+///             Activity::new(format!("{id}_O1"), &id, 0)
+///                 .with_duration(ActivityDuration::fixed(1000))
+///                 .with_requirement(
+///                     ResourceRequirement::new("Machine")
+///                         .with_candidates(vec!["M1".into(), "M2".into()]),
+///                 ),
+///         )
+///     })
+///     .collect();
+/// let request = ScheduleRequest::new(tasks, vec![Resource::primary("M1"), Resource::primary("M2")]);
+/// let schedule = SimpleScheduler::new().schedule_request(&request);
+///
+/// let (improved, report) = LnsImprover::new().with_seed(7).improve(&request, &schedule);
+/// assert!(report.final_cost <= report.initial_cost);
+/// assert_eq!(improved.assignment_count(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LnsImprover {
+    iterations: usize,
+    seed: Option<u64>,
+    min_destroy: f64,
+    max_destroy: f64,
+    tardiness_weight: f64,
+    scheduler: SimpleScheduler,
+}
+
+impl Default for LnsImprover {
+    fn default() -> Self {
+        Self {
+            iterations: 500,
+            seed: None,
+            min_destroy: 0.1,
+            max_destroy: 0.4,
+            tardiness_weight: 0.5,
+            scheduler: SimpleScheduler::new(),
+        }
+    }
+}
+
+impl LnsImprover {
+    /// Creates an improver with 500 iterations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of destroy–repair iterations.
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    /// Sets the random seed for reproducible runs.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the range of the fraction of tasks removed per iteration.
+    ///
+    /// Both bounds are clamped to `(0, 1]`.
+    pub fn with_destroy_degree(mut self, min: f64, max: f64) -> Self {
+        self.min_destroy = min.clamp(f64::EPSILON, 1.0);
+        self.max_destroy = max.clamp(self.min_destroy, 1.0);
+        self
+    }
+
+    /// Sets the tardiness weight `w` of the cost (clamped to `[0, 1]`).
+    pub fn with_tardiness_weight(mut self, weight: f64) -> Self {
+        self.tardiness_weight = weight.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the decoder (e.g., with a rule engine, which orders
+    /// unscheduled tasks of the input).
+    ///
+    /// Transition matrices, affinity, locks and tooling are taken from the request.
+    pub fn with_scheduler(mut self, scheduler: SimpleScheduler) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Improves `schedule`, a solution of `request`.
+    pub fn improve(&self, request: &ScheduleRequest, schedule: &Schedule) -> (Schedule, LnsReport) {
+        let problem = LnsProblem {
+            request,
+            scheduler: self
+                .scheduler
+                .clone()
+                .with_transition_matrices(request.transition_matrices.clone())
+                .with_affinity(request.affinity.clone())
+                .with_locks(request.locks.clone())
+                .with_tooling(request.tooling.clone()),
+            initial: schedule,
+            tardiness_weight: self.tardiness_weight,
+        };
+        let initial_cost = problem.schedule_cost(schedule);
+        if request.tasks.is_empty() {
+            let report = LnsReport {
+                initial_cost,
+                final_cost: initial_cost,
+                iterations: 0,
+                improvements: 0,
+            };
+            return (schedule.clone(), report);
+        }
+
+        let destroy_ops = [
+            DestroyKind::RandomMachine,
+            DestroyKind::TimeWindow,
+            DestroyKind::WorstTardiness,
+        ]
+        .map(|kind| LnsDestroy {
+            kind,
+            problem: &problem,
+        });
+        let repair_ops = [RepairKind::DueDate, RepairKind::Random].map(|kind| LnsRepair {
+            kind,
+            problem: &problem,
+        });
+        // A near-zero temperature turns SA acceptance into "not worse".
+        let mut config = AlnsConfig::default()
+            .with_max_iterations(self.iterations)
+            .with_segment_length(self.iterations.clamp(1, 50))
+            .with_destroy_degree(self.min_destroy, self.max_destroy + f64::EPSILON)
+            .with_temperature(1e-9, 0.5, 1e-9);
+        if let Some(seed) = self.seed {
+            config = config.with_seed(seed);
+        }
+
+        let result = AlnsRunner::run(&problem, &destroy_ops, &repair_ops, &config);
+        let report = LnsReport {
+            initial_cost,
+            final_cost: result.best_cost,
+            iterations: result.iterations,
+            improvements: result.improvements,
+        };
+        (result.best.schedule, report)
+    }
+}
+
+/// A dispatch order with pinned resources and its decoded schedule.
+#[derive(Debug, Clone)]
+struct LnsSolution {
+    /// Task indices in dispatch order (excluding `removed`).
+    order: Vec<usize>,
+    /// Task indices removed by the last destroy.
+    removed: Vec<usize>,
+    /// Resource per task and activity (`None` = free choice).
+    pins: Vec<Vec<Option<String>>>,
+    /// Decoded schedule.
+    schedule: Schedule,
+}
+
+struct LnsProblem<'a> {
+    request: &'a ScheduleRequest,
+    scheduler: SimpleScheduler,
+    initial: &'a Schedule,
+    tardiness_weight: f64,
+}
+
+impl LnsProblem<'_> {
+    fn tasks(&self) -> &[Task] {
+        &self.request.tasks
+    }
+
+    /// Class-weighted tardiness of task `i` (ms).
+    fn tardiness(&self, schedule: &Schedule, i: usize) -> f64 {
+        let task = &self.tasks()[i];
+        task.deadline.map_or(0.0, |deadline| {
+            let factor = objective::tardiness_factor(task.priority_class, 1.0);
+            objective::weighted_tardiness(schedule, [(task.id.as_str(), deadline, factor)])
+        })
+    }
+
+    fn schedule_cost(&self, schedule: &Schedule) -> f64 {
+        let tardiness: f64 = (0..self.tasks().len())
+            .map(|i| self.tardiness(schedule, i))
+            .sum();
+        // Setup-crew assignments are setup-only; don't count them as activities.
+        let scheduled = schedule
+            .assignments
+            .iter()
+            .filter(|a| a.setup_ms == 0 || a.process_ms() > 0)
+            .count();
+        let expected = self
+            .tasks()
+            .iter()
+            .flat_map(|t| &t.activities)
+            .filter(|a| !a.resolved_candidates(&self.request.resources).is_empty())
+            .count();
+        let unscheduled = expected.saturating_sub(scheduled) as f64;
+
+        (1.0 - self.tardiness_weight) * schedule.makespan_ms() as f64
+            + self.tardiness_weight * tardiness
+            + UNSCHEDULED_PENALTY * unscheduled
+    }
+
+    /// Reads the dispatch order and resource pins back from a schedule.
+    fn solution_from(&self, schedule: Schedule) -> LnsSolution {
+        let tasks = self.tasks();
+        let mut by_task: HashMap<&str, Vec<(i64, &str)>> = HashMap::new();
+        for a in &schedule.assignments {
+            if a.setup_ms == 0 || a.process_ms() > 0 {
+                by_task
+                    .entry(a.task_id.as_str())
+                    .or_default()
+                    .push((a.start_ms, a.resource_id.as_str()));
+            }
+        }
+
+        let mut first_start = vec![i64::MAX; tasks.len()];
+        let mut pins = Vec::with_capacity(tasks.len());
+        for (i, task) in tasks.iter().enumerate() {
+            let mut placed = by_task.remove(task.id.as_str()).unwrap_or_default();
+            placed.sort_unstable();
+            if let Some(&(start, _)) = placed.first() {
+                first_start[i] = start;
+            }
+            // Activities with candidates are scheduled in sequence order.
+            let mut placed = placed.into_iter();
+            let task_pins = task
+                .activities
+                .iter()
+                .map(|activity| {
+                    let candidates = activity.resolved_candidates(&self.request.resources);
+                    if candidates.is_empty() {
+                        return None;
+                    }
+                    let (_, resource) = placed.next()?;
+                    candidates.contains(&resource).then(|| resource.to_string())
+                })
+                .collect();
+            pins.push(task_pins);
+        }
+
+        // Scheduled tasks by first start, then the rest in dispatch order.
+        let fallback = self.scheduler.sort_tasks(tasks, self.request.start_time_ms);
+        let mut rank = vec![0; tasks.len()];
+        for (r, &i) in fallback.iter().enumerate() {
+            rank[i] = r;
+        }
+        let mut order: Vec<usize> = (0..tasks.len()).collect();
+        order.sort_by_key(|&i| (first_start[i], rank[i]));
+
+        LnsSolution {
+            order,
+            removed: Vec::new(),
+            pins,
+            schedule,
+        }
+    }
+
+    /// Rebuilds the schedule of `order` with the greedy decoder.
+    fn decode(&self, order: Vec<usize>, pins: &[Vec<Option<String>>]) -> LnsSolution {
+        let tasks: Vec<Task> = self
+            .tasks()
+            .iter()
+            .zip(pins)
+            .map(|(task, task_pins)| {
+                let mut task = task.clone();
+                for (activity, pin) in task.activities.iter_mut().zip(task_pins) {
+                    let Some(resource) = pin else { continue };
+                    for req in &mut activity.resource_requirements {
+                        req.candidates = req
+                            .resolve(&self.request.resources)
+                            .into_iter()
+                            .filter(|c| c == resource)
+                            .map(String::from)
+                            .collect();
+                    }
+                }
+                task
+            })
+            .collect();
+        let schedule = self.scheduler.schedule_ordered(
+            &tasks,
+            &self.request.resources,
+            self.request.start_time_ms,
+            &order,
+        );
+        let mut solution = self.solution_from(schedule);
+        solution.order = order;
+        solution
+    }
+}
+
+impl AlnsProblem for LnsProblem<'_> {
+    type Solution = LnsSolution;
+
+    fn initial_solution<R: Rng>(&self, _rng: &mut R) -> LnsSolution {
+        self.solution_from(self.initial.clone())
+    }
+
+    fn cost(&self, solution: &LnsSolution) -> f64 {
+        self.schedule_cost(&solution.schedule)
+    }
+}
+
+/// Destroy operator kinds.
+#[derive(Debug, Clone, Copy)]
+enum DestroyKind {
+    RandomMachine,
+    TimeWindow,
+    WorstTardiness,
+}
+
+struct LnsDestroy<'p, 'a> {
+    kind: DestroyKind,
+    problem: &'p LnsProblem<'a>,
+}
+
+impl DestroyOperator<LnsSolution> for LnsDestroy<'_, '_> {
+    fn name(&self) -> &str {
+        match self.kind {
+            DestroyKind::RandomMachine => "random_machine",
+            DestroyKind::TimeWindow => "time_window",
+            DestroyKind::WorstTardiness => "worst_tardiness",
+        }
+    }
+
+    fn destroy<R: Rng>(&self, solution: &LnsSolution, degree: f64, rng: &mut R) -> LnsSolution {
+        let n = solution.order.len();
+        let count = ((n as f64 * degree).round() as usize).clamp(1, n.max(1));
+        // Shuffle first so that ties are broken at random.
+        let mut candidates = solution.order.clone();
+        candidates.shuffle(rng);
+
+        let schedule = &solution.schedule;
+        let tasks = self.problem.tasks();
+        let span = |i: usize| {
+            let assignments = schedule.assignments_for_task(&tasks[i].id);
+            let start = assignments.iter().map(|a| a.start_ms).min();
+            let end = assignments.iter().map(|a| a.end_ms).max();
+            start.zip(end)
+        };
+
+        match self.kind {
+            DestroyKind::RandomMachine => {
+                if let Some(a) = pick(&schedule.assignments, rng) {
+                    let on_machine: Vec<&str> = schedule
+                        .assignments
+                        .iter()
+                        .filter(|b| b.resource_id == a.resource_id)
+                        .map(|b| b.task_id.as_str())
+                        .collect();
+                    candidates.retain(|&i| on_machine.contains(&tasks[i].id.as_str()));
+                }
+            }
+            DestroyKind::TimeWindow => {
+                if let Some(a) = pick(&schedule.assignments, rng) {
+                    let pivot = a.start_ms;
+                    candidates.sort_by_key(|&i| match span(i) {
+                        Some((s, e)) => (s - pivot).max(pivot - e).max(0),
+                        None => i64::MAX,
+                    });
+                }
+            }
+            DestroyKind::WorstTardiness => {
+                // Tardiest first; on ties, latest completion first.
+                let key = |i| {
+                    (
+                        self.problem.tardiness(schedule, i),
+                        span(i).map_or(i64::MAX, |s| s.1),
+                    )
+                };
+                candidates.sort_by(|&a, &b| {
+                    let ((ta, ca), (tb, cb)) = (key(a), key(b));
+                    tb.total_cmp(&ta).then(cb.cmp(&ca))
+                });
+            }
+        }
+        candidates.truncate(count);
+
+        let mut destroyed = solution.clone();
+        destroyed.order.retain(|i| !candidates.contains(i));
+        for &i in &candidates {
+            destroyed.pins[i].iter_mut().for_each(|pin| *pin = None);
+        }
+        destroyed.removed = candidates;
+        destroyed
+    }
+}
+
+/// A random element of a slice.
+fn pick<'s, T, R: Rng>(items: &'s [T], rng: &mut R) -> Option<&'s T> {
+    (!items.is_empty()).then(|| &items[rng.random_range(0..items.len())])
+}
+
+/// Repair operator kinds.
+#[derive(Debug, Clone, Copy)]
+enum RepairKind {
+    DueDate,
+    Random,
+}
+
+struct LnsRepair<'p, 'a> {
+    kind: RepairKind,
+    problem: &'p LnsProblem<'a>,
+}
+
+impl RepairOperator<LnsSolution> for LnsRepair<'_, '_> {
+    fn name(&self) -> &str {
+        match self.kind {
+            RepairKind::DueDate => "due_date",
+            RepairKind::Random => "random",
+        }
+    }
+
+    fn repair<R: Rng>(&self, solution: &LnsSolution, rng: &mut R) -> LnsSolution {
+        let mut order = solution.order.clone();
+        match self.kind {
+            RepairKind::DueDate => {
+                let tasks = self.problem.tasks();
+                let key = |i: usize| {
+                    (
+                        tasks[i].priority_class,
+                        tasks[i].deadline.unwrap_or(i64::MAX),
+                    )
+                };
+                let mut removed = solution.removed.clone();
+                removed.sort_by_key(|&i| (key(i), std::cmp::Reverse(tasks[i].priority)));
+                for i in removed {
+                    let pos = order
+                        .iter()
+                        .position(|&j| key(j) > key(i))
+                        .unwrap_or(order.len());
+                    order.insert(pos, i);
+                }
+            }
+            RepairKind::Random => {
+                for &i in &solution.removed {
+                    let pos = rng.random_range(0..=order.len());
+                    order.insert(pos, i);
+                }
+            }
+        }
+        self.problem.decode(order, &solution.pins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, ActivityDuration, Assignment, Resource, ResourceRequirement};
+
+    fn task(id: &str, duration: i64, candidates: &[&str]) -> Task {
+        Task::new(id).with_activity(
+            Activity::new(format!("{id}_O1"), id, 0)
+                .with_duration(ActivityDuration::fixed(duration))
+                .with_requirement(
+                    ResourceRequirement::new("Machine")
+                        .with_candidates(candidates.iter().map(|c| c.to_string()).collect()),
+                ),
+        )
+    }
+
+    #[test]
+    fn test_rebalances_overloaded_machine() {
+        let tasks: Vec<Task> = (0..6)
+            .map(|i| task(&format!("J{i}"), 1000, &["M1", "M2"]))
+            .collect();
+        let request = ScheduleRequest::new(
+            tasks,
+            vec![Resource::primary("M1"), Resource::primary("M2")],
+        );
+        // Everything stacked on M1: makespan 6000.
+        let mut schedule = Schedule::new();
+        for i in 0..6 {
+            let start = 1000 * i as i64;
+            schedule.add_assignment(Assignment::new(
+                format!("J{i}_O1"),
+                format!("J{i}"),
+                "M1",
+                start,
+                start + 1000,
+            ));
+        }
+
+        let (improved, report) = LnsImprover::new()
+            .with_iterations(200)
+            .with_seed(1)
+            .with_tardiness_weight(0.0)
+            .improve(&request, &schedule);
+        assert_eq!(report.initial_cost, 6000.0);
+        assert_eq!(report.final_cost, 3000.0);
+        assert!(report.improvements > 0);
+        assert_eq!(improved.makespan_ms(), 3000);
+        assert_eq!(improved.assignment_count(), 6);
+    }
+
+    #[test]
+    fn test_reduces_tardiness() {
+        // Greedy by priority runs the urgent low-priority job last.
+        let tasks = vec![
+            task("A", 1000, &["M1"]).with_priority(3),
+            task("B", 1000, &["M1"]).with_priority(2),
+            task("C", 1000, &["M1"])
+                .with_priority(1)
+                .with_deadline(1000),
+        ];
+        let request = ScheduleRequest::new(tasks, vec![Resource::primary("M1")]);
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+        assert_eq!(schedule.task_completion_time("C"), Some(3000));
+
+        let (improved, report) = LnsImprover::new()
+            .with_iterations(100)
+            .with_seed(3)
+            .improve(&request, &schedule);
+        assert_eq!(improved.task_completion_time("C"), Some(1000));
+        assert_eq!(report.final_cost, 1500.0);
+    }
+
+    #[test]
+    fn test_keeps_optimal_input() {
+        let request = ScheduleRequest::new(
+            vec![task("A", 500, &["M1"]), task("B", 700, &["M2"])],
+            vec![Resource::primary("M1"), Resource::primary("M2")],
+        );
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+        let (improved, report) = LnsImprover::new()
+            .with_iterations(50)
+            .with_seed(5)
+            .improve(&request, &schedule);
+        assert_eq!(report.improvements, 0);
+        assert_eq!(report.final_cost, report.initial_cost);
+        assert_eq!(improved.makespan_ms(), 700);
+
+        let empty = ScheduleRequest::new(Vec::new(), Vec::new());
+        let (_, report) = LnsImprover::new().improve(&empty, &Schedule::new());
+        assert_eq!(report.iterations, 0);
+    }
+}
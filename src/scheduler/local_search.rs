@@ -0,0 +1,877 @@
+//! Tabu-search local improvement.
+//!
+//! Polishes an existing schedule from any backend (greedy, GA, CP, or a
+//! hand-edited plan) without changing resource choices: only the order
+//! of work on each machine changes. The schedule is read as a
+//! disjunctive graph — activities linked by their task sequence,
+//! explicit predecessors and the order of work on each single-capacity
+//! resource — and re-timed as early as the graph allows (semi-active).
+//!
+//! # Neighborhood
+//!
+//! Each move swaps two adjacent activities on a machine whose machine arc
+//! lies on a critical path: the longest path to the last activity, and
+//! the one to the last activity of the tardiest task (N1 of van Laarhoven
+//! et al.). The swapped pair stays tabu for a number of iterations, so
+//! the search can leave local optima without cycling back, unless
+//! swapping it again would beat the best schedule (aspiration).
+//!
+//! # Limits
+//!
+//! - Resource choices, setups and split segments stay as planned; an
+//!   activity moves as a block.
+//! - Resources with a sequence-dependent setup matrix keep their order,
+//!   since a swap would change their setups.
+//! - Calendars and other schedule rules are not part of the graph: moves
+//!   that break them (see [`check_schedule`]) or overlap a reserved lock
+//!   window are priced out by a penalty, so the result never has more
+//!   violations than the input.
+//! - Locked tasks and frozen activities keep their times.
+//!
+//! # Reference
+//! Nowicki & Smutnicki (1996), "A fast taboo search algorithm for the job
+//! shop problem"; van Laarhoven, Aarts & Lenstra (1992), "Job shop
+//! scheduling by simulated annealing"
+
+use std::collections::{HashMap, HashSet};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use u_metaheur::tabu::{TabuConfig, TabuMove, TabuProblem, TabuRunner};
+
+use super::ScheduleRequest;
+use crate::models::{Lock, PrecedenceMode, Schedule, Task, ViolationType};
+use crate::objective;
+use crate::validation::check_schedule;
+
+/// Cost per rule violation; dominates any time-based term.
+const VIOLATION_PENALTY: f64 = 1e12;
+
+/// Outcome of a tabu search.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TabuReport {
+    /// Cost of the input schedule.
+    pub initial_cost: f64,
+    /// Cost of the returned schedule (never above `initial_cost`).
+    pub final_cost: f64,
+    /// Moves made.
+    pub iterations: usize,
+    /// Move after which the returned schedule was found (0 when the input
+    /// is kept).
+    pub best_iteration: usize,
+}
+
+/// Improves schedules by tabu search over machine sequences.
+///
+/// The cost is `(1 − w) × makespan + w × Σ tardiness`, with tardiness
+/// scaled by priority class as in the GA and [`LnsImprover`](super::LnsImprover).
+/// The input schedule is kept as-is unless a better one is found.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, Assignment, Resource, ResourceRequirement, Schedule, Task};
+/// use u_schedule::scheduler::{ScheduleRequest, TabuImprover};
+///
+/// // Two jobs through M1 then M2; J1 is long on M1, J2 long on M2.
+/// let op = |id: &str, task: &str, seq, m: &str, ms| {
+///     Activity::new(id, task, seq)
+///         .with_process_time(ms)
+///         .with_requirement(ResourceRequirement::new("Machine").with_candidates(vec![m.into()]))
+/// };
+/// let tasks = vec![
+///     Task::new("J1").with_activity(op("A1", "J1", 0, "M1", 3000)).with_activity(op("A2", "J1", 1, "M2", 1000)),
+///     Task::new("J2").with_activity(op("B1", "J2", 0, "M1", 1000)).with_activity(op("B2", "J2", 1, "M2", 3000)),
+/// ];
+/// let request = ScheduleRequest::new(tasks, vec![Resource::primary("M1"), Resource::primary("M2")]);
+///
+/// // J1 first on both machines: makespan 7 s.
+/// let mut plan = Schedule::new();
+/// plan.add_assignment(Assignment::new("A1", "J1", "M1", 0, 3000));
+/// plan.add_assignment(Assignment::new("B1", "J2", "M1", 3000, 4000));
+/// plan.add_assignment(Assignment::new("A2", "J1", "M2", 3000, 4000));
+/// plan.add_assignment(Assignment::new("B2", "J2", "M2", 4000, 7000));
+///
+/// let (improved, report) = TabuImprover::new().with_tardiness_weight(0.0).improve(&request, &plan);
+/// // J2 first (Johnson's rule): 5 s.
+/// assert_eq!(improved.makespan_ms(), 5000);
+/// assert!(report.final_cost < report.initial_cost);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TabuImprover {
+    iterations: usize,
+    tenure: usize,
+    max_no_improve: usize,
+    aspiration: bool,
+    tardiness_weight: f64,
+}
+
+impl Default for TabuImprover {
+    fn default() -> Self {
+        Self {
+            iterations: 200,
+            tenure: 7,
+            max_no_improve: 50,
+            aspiration: true,
+            tardiness_weight: 0.5,
+        }
+    }
+}
+
+impl TabuImprover {
+    /// Creates an improver with 200 iterations and a tabu tenure of 7.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of moves.
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    /// Sets how many moves a swapped pair stays tabu.
+    pub fn with_tenure(mut self, tenure: usize) -> Self {
+        self.tenure = tenure;
+        self
+    }
+
+    /// Stops after this many moves without a new best schedule.
+    pub fn with_max_no_improve(mut self, moves: usize) -> Self {
+        self.max_no_improve = moves.max(1);
+        self
+    }
+
+    /// Sets whether a tabu move is allowed when it beats the best schedule
+    /// found so far (default: on).
+    pub fn with_aspiration(mut self, aspiration: bool) -> Self {
+        self.aspiration = aspiration;
+        self
+    }
+
+    /// Sets the tardiness weight `w` of the cost (clamped to `[0, 1]`).
+    pub fn with_tardiness_weight(mut self, weight: f64) -> Self {
+        self.tardiness_weight = weight.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Improves `schedule`, a solution of `request`.
+    pub fn improve(
+        &self,
+        request: &ScheduleRequest,
+        schedule: &Schedule,
+    ) -> (Schedule, TabuReport) {
+        let graph = DisjunctiveGraph::new(request, schedule, self.tardiness_weight);
+        let initial_cost = graph.schedule_cost(schedule);
+        let config = TabuConfig::default()
+            .with_max_iterations(self.iterations)
+            .with_tabu_tenure(self.tenure)
+            .with_max_no_improve(self.max_no_improve)
+            .with_aspiration(self.aspiration);
+        let result = TabuRunner::run(&graph, &config);
+        let report = TabuReport {
+            initial_cost,
+            final_cost: result.best_cost,
+            iterations: result.iterations,
+            best_iteration: if result.best_cost < initial_cost {
+                result.best_iteration + 1
+            } else {
+                0
+            },
+        };
+        (result.best.schedule, report)
+    }
+}
+
+/// Machine sequences and their schedule.
+#[derive(Debug, Clone)]
+struct Sequencing {
+    /// Nodes in order of work, per sequenced resource.
+    sequences: Vec<Vec<usize>>,
+    /// Timed schedule.
+    schedule: Schedule,
+    /// Critical machine arcs: (resource, position of the first node).
+    critical: Vec<(usize, usize)>,
+}
+
+/// A scheduled activity.
+#[derive(Debug)]
+struct Node<'a> {
+    id: &'a str,
+    /// Start in the input (ms).
+    start: i64,
+    /// Earliest start: schedule start and task release (ms).
+    earliest: i64,
+    /// Processing span relative to the start: (start after setup, end).
+    span: (i64, i64),
+    process_ms: i64,
+    /// Locked or frozen, or not an activity of the request.
+    fixed: bool,
+}
+
+/// Constraint from a predecessor node.
+#[derive(Debug, Clone, Copy)]
+enum Arc {
+    /// Previous activity of the task.
+    Chain {
+        from: usize,
+        mode: PrecedenceMode,
+        lag_ms: i64,
+    },
+    /// Explicit predecessor (finish-to-start).
+    After { from: usize },
+    /// Previous work on a sequenced resource.
+    Machine { from: usize, resource: usize },
+}
+
+struct DisjunctiveGraph<'a> {
+    request: &'a ScheduleRequest,
+    initial: &'a Schedule,
+    tardiness_weight: f64,
+    nodes: Vec<Node<'a>>,
+    index: HashMap<&'a str, usize>,
+    /// Node and offset from its start, per assignment of `initial`.
+    placements: Vec<(usize, i64)>,
+    /// Task and precedence arcs into each node.
+    arcs: Vec<Vec<Arc>>,
+    /// Sequenced resources (single capacity).
+    resources: Vec<&'a str>,
+    /// Whether a sequenced resource keeps its order (it has a setup
+    /// matrix).
+    fixed_order: Vec<bool>,
+    /// Occupation of a resource by a node, relative to its start.
+    blocks: HashMap<(usize, usize), (i64, i64)>,
+    /// Reserved lock windows per resource.
+    reserved: HashMap<&'a str, Vec<(i64, i64)>>,
+}
+
+impl<'a> DisjunctiveGraph<'a> {
+    fn new(request: &'a ScheduleRequest, initial: &'a Schedule, tardiness_weight: f64) -> Self {
+        let locked_tasks = request.locks.locked_tasks();
+        let frozen = request.locks.frozen_activities();
+        let activities: HashMap<&str, (&Task, usize)> = request
+            .tasks
+            .iter()
+            .flat_map(|t| {
+                t.activities
+                    .iter()
+                    .enumerate()
+                    .map(move |(k, a)| (a.id.as_str(), (t, k)))
+            })
+            .collect();
+
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut placements = Vec::with_capacity(initial.assignments.len());
+        for a in &initial.assignments {
+            let n = *index.entry(a.activity_id.as_str()).or_insert_with(|| {
+                let (earliest, fixed) = match activities.get(a.activity_id.as_str()) {
+                    Some((task, _)) => (
+                        task.release_time
+                            .unwrap_or(request.start_time_ms)
+                            .max(request.start_time_ms),
+                        locked_tasks.contains(task.id.as_str())
+                            || frozen.contains(a.activity_id.as_str()),
+                    ),
+                    None => (a.start_ms, true),
+                };
+                nodes.push(Node {
+                    id: &a.activity_id,
+                    start: i64::MAX,
+                    earliest,
+                    span: (i64::MAX, i64::MIN),
+                    process_ms: 0,
+                    fixed,
+                });
+                nodes.len() - 1
+            });
+            nodes[n].start = nodes[n].start.min(a.start_ms);
+            placements.push((n, a.start_ms));
+        }
+        for (i, a) in initial.assignments.iter().enumerate() {
+            let node = &mut nodes[placements[i].0];
+            placements[i].1 -= node.start;
+            if a.process_ms() > 0 {
+                node.span.0 = node.span.0.min(a.start_ms + a.setup_ms - node.start);
+                node.span.1 = node.span.1.max(a.end_ms - node.start);
+                node.process_ms = node.process_ms.max(a.process_ms());
+            }
+        }
+        for node in &mut nodes {
+            if node.span.0 > node.span.1 {
+                node.span = (0, 0);
+            }
+        }
+
+        let mut arcs = vec![Vec::new(); nodes.len()];
+        for (n, node) in nodes.iter().enumerate() {
+            let Some(&(task, k)) = activities.get(node.id) else {
+                continue;
+            };
+            let activity = &task.activities[k];
+            if let Some(prev) = task.chained_predecessor(k) {
+                if let Some(&from) = index.get(task.activities[prev].id.as_str()) {
+                    arcs[n].push(Arc::Chain {
+                        from,
+                        mode: activity.precedence_mode,
+                        lag_ms: activity.precedence_lag_ms,
+                    });
+                }
+            }
+            for pred in &activity.predecessors {
+                if let Some(&from) = index.get(pred.as_str()) {
+                    arcs[n].push(Arc::After { from });
+                }
+            }
+        }
+
+        let resources: Vec<&str> = request
+            .resources
+            .iter()
+            .filter(|r| r.capacity <= 1)
+            .map(|r| r.id.as_str())
+            .collect();
+        let fixed_order = resources
+            .iter()
+            .map(|id| request.transition_matrices.get(id).is_some())
+            .collect();
+        let resource_index: HashMap<&str, usize> = resources
+            .iter()
+            .enumerate()
+            .map(|(r, &id)| (id, r))
+            .collect();
+        let mut blocks: HashMap<(usize, usize), (i64, i64)> = HashMap::new();
+        for (a, &(n, offset)) in initial.assignments.iter().zip(&placements) {
+            if let Some(&r) = resource_index.get(a.resource_id.as_str()) {
+                let block = blocks.entry((n, r)).or_insert((i64::MAX, i64::MIN));
+                block.0 = block.0.min(offset);
+                block.1 = block.1.max(offset + a.end_ms - a.start_ms);
+            }
+        }
+
+        let mut reserved: HashMap<&str, Vec<(i64, i64)>> = HashMap::new();
+        for lock in &request.locks.locks {
+            if let Lock::ResourceWindow {
+                resource_id,
+                window,
+            } = lock
+            {
+                reserved
+                    .entry(resource_id.as_str())
+                    .or_default()
+                    .push((window.start_ms, window.end_ms));
+            }
+        }
+
+        Self {
+            request,
+            initial,
+            tardiness_weight,
+            nodes,
+            index,
+            placements,
+            arcs,
+            resources,
+            fixed_order,
+            blocks,
+            reserved,
+        }
+    }
+
+    /// Nodes per sequenced resource, in the input's order of work.
+    fn initial_sequences(&self) -> Vec<Vec<usize>> {
+        let mut sequences = vec![Vec::new(); self.resources.len()];
+        for (&(n, r), &(offset, _)) in &self.blocks {
+            sequences[r].push((self.nodes[n].start + offset, n));
+        }
+        sequences
+            .into_iter()
+            .map(|mut seq| {
+                seq.sort_unstable();
+                seq.into_iter().map(|(_, n)| n).collect()
+            })
+            .collect()
+    }
+
+    /// Times the graph of `sequences` as early as possible; `None` if the
+    /// sequences make it cyclic.
+    fn decode(&self, sequences: Vec<Vec<usize>>) -> Option<Sequencing> {
+        let count = self.nodes.len();
+        let mut incoming = self.arcs.clone();
+        for (resource, seq) in sequences.iter().enumerate() {
+            for pair in seq.windows(2) {
+                incoming[pair[1]].push(Arc::Machine {
+                    from: pair[0],
+                    resource,
+                });
+            }
+        }
+        let mut outgoing = vec![Vec::new(); count];
+        let mut indegree = vec![0usize; count];
+        for (n, arcs) in incoming.iter().enumerate() {
+            for arc in arcs {
+                let (Arc::Chain { from, .. } | Arc::After { from } | Arc::Machine { from, .. }) =
+                    *arc;
+                outgoing[from].push(n);
+                indegree[n] += 1;
+            }
+        }
+
+        let mut ready: Vec<usize> = (0..count).rev().filter(|&n| indegree[n] == 0).collect();
+        let mut starts = vec![0i64; count];
+        let mut tight: Vec<Option<Arc>> = vec![None; count];
+        let mut timed = 0;
+        while let Some(n) = ready.pop() {
+            timed += 1;
+            let node = &self.nodes[n];
+            if node.fixed {
+                starts[n] = node.start;
+            } else {
+                let mut start = node.earliest;
+                for &arc in &incoming[n] {
+                    let bound = match arc {
+                        Arc::Chain { from, mode, lag_ms } => {
+                            let (s, e) = self.nodes[from].span;
+                            let prev = starts[from];
+                            mode.earliest_start(prev + s, prev + e, lag_ms, node.process_ms)
+                        }
+                        Arc::After { from } => starts[from] + self.nodes[from].span.1,
+                        Arc::Machine { from, resource } => {
+                            starts[from] + self.blocks[&(from, resource)].1
+                                - self.blocks[&(n, resource)].0
+                        }
+                    };
+                    if bound > start {
+                        start = bound;
+                        tight[n] = Some(arc);
+                    }
+                }
+                starts[n] = start;
+            }
+            for &next in &outgoing[n] {
+                indegree[next] -= 1;
+                if indegree[next] == 0 {
+                    ready.push(next);
+                }
+            }
+        }
+        if timed < count {
+            return None;
+        }
+
+        let mut schedule = self.initial.clone();
+        for (a, &(n, offset)) in schedule.assignments.iter_mut().zip(&self.placements) {
+            let length = a.end_ms - a.start_ms;
+            a.start_ms = starts[n] + offset;
+            a.end_ms = a.start_ms + length;
+        }
+        schedule.violations =
+            check_schedule(&schedule, &self.request.tasks, &self.request.resources);
+
+        // Critical paths: to the last node, and to the tardiest task's end
+        let end = |n: usize| starts[n] + self.nodes[n].span.1;
+        let mut targets: Vec<usize> = (0..count).max_by_key(|&n| end(n)).into_iter().collect();
+        let tardiest = self
+            .request
+            .tasks
+            .iter()
+            .filter_map(|t| {
+                let last = t
+                    .activities
+                    .iter()
+                    .filter_map(|a| self.index.get(a.id.as_str()).copied())
+                    .max_by_key(|&n| end(n))?;
+                Some((end(last) - t.deadline?, last))
+            })
+            .filter(|&(tardiness, _)| tardiness > 0)
+            .max();
+        targets.extend(tardiest.map(|(_, n)| n));
+
+        let position: HashMap<(usize, usize), usize> = sequences
+            .iter()
+            .enumerate()
+            .flat_map(|(r, seq)| seq.iter().enumerate().map(move |(p, &n)| ((r, n), p)))
+            .collect();
+        let mut critical = Vec::new();
+        for mut n in targets {
+            while let Some(arc) = tight[n] {
+                n = match arc {
+                    Arc::Chain { from, .. } | Arc::After { from } => from,
+                    Arc::Machine { from, resource } => {
+                        let pos = position[&(resource, from)];
+                        if !critical.contains(&(resource, pos)) {
+                            critical.push((resource, pos));
+                        }
+                        from
+                    }
+                };
+            }
+        }
+
+        Some(Sequencing {
+            sequences,
+            schedule,
+            critical,
+        })
+    }
+
+    fn schedule_cost(&self, schedule: &Schedule) -> f64 {
+        let tardiness = objective::weighted_tardiness(
+            schedule,
+            self.request.tasks.iter().filter_map(|t| {
+                let factor = objective::tardiness_factor(t.priority_class, 1.0);
+                Some((t.id.as_str(), t.deadline?, factor))
+            }),
+        );
+        let violations = check_schedule(schedule, &self.request.tasks, &self.request.resources)
+            .iter()
+            .filter(|v| v.violation_type != ViolationType::DeadlineMiss)
+            .count();
+        let reserved = schedule
+            .assignments
+            .iter()
+            .filter(|a| {
+                self.reserved
+                    .get(a.resource_id.as_str())
+                    .is_some_and(|windows| {
+                        windows
+                            .iter()
+                            .any(|&(from, to)| a.start_ms < to && from < a.end_ms)
+                    })
+            })
+            .count();
+
+        (1.0 - self.tardiness_weight) * schedule.makespan_ms() as f64
+            + self.tardiness_weight * tardiness
+            + VIOLATION_PENALTY * (violations + reserved) as f64
+    }
+}
+
+impl TabuProblem for DisjunctiveGraph<'_> {
+    type Solution = Sequencing;
+
+    fn initial_solution<R: Rng>(&self, _rng: &mut R) -> Sequencing {
+        // The input as-is, with the critical arcs of its re-timed graph.
+        let sequences = self.initial_sequences();
+        let critical = self
+            .decode(sequences.clone())
+            .map(|s| s.critical)
+            .unwrap_or_default();
+        Sequencing {
+            sequences,
+            schedule: self.initial.clone(),
+            critical,
+        }
+    }
+
+    fn cost(&self, solution: &Sequencing) -> f64 {
+        self.schedule_cost(&solution.schedule)
+    }
+
+    fn neighbors<R: Rng>(&self, solution: &Sequencing, _rng: &mut R) -> Vec<TabuMove<Sequencing>> {
+        let mut seen = HashSet::new();
+        solution
+            .critical
+            .iter()
+            .filter_map(|&(r, pos)| {
+                let (a, b) = (solution.sequences[r][pos], solution.sequences[r][pos + 1]);
+                if self.fixed_order[r]
+                    || self.nodes[a].fixed
+                    || self.nodes[b].fixed
+                    || !seen.insert((a.min(b), a.max(b)))
+                {
+                    return None;
+                }
+                let mut sequences = solution.sequences.clone();
+                sequences[r].swap(pos, pos + 1);
+                let next = self.decode(sequences)?;
+                let (first, second) = if self.nodes[a].id < self.nodes[b].id {
+                    (self.nodes[a].id, self.nodes[b].id)
+                } else {
+                    (self.nodes[b].id, self.nodes[a].id)
+                };
+                Some(TabuMove {
+                    cost: self.schedule_cost(&next.schedule),
+                    key: format!("{first}|{second}"),
+                    solution: next,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::golden::{self, GoldenInstance};
+    use crate::models::{
+        Activity, Assignment, LockSet, Resource, ResourceRequirement, TransitionMatrix,
+        TransitionMatrixCollection,
+    };
+    use crate::scheduler::SimpleScheduler;
+
+    fn op(id: &str, task: &str, seq: i32, machine: &str, ms: i64) -> Activity {
+        Activity::new(id, task, seq)
+            .with_process_time(ms)
+            .with_requirement(
+                ResourceRequirement::new("Machine").with_candidates(vec![machine.into()]),
+            )
+    }
+
+    /// 3 jobs × 3 machines, routed M1 → M2 → M3 (a flow shop).
+    fn request() -> ScheduleRequest {
+        let job = |id: &str, times: [i64; 3]| {
+            let mut task = Task::new(id);
+            for (k, ms) in times.into_iter().enumerate() {
+                let machine = format!("M{}", k + 1);
+                task = task.with_activity(op(&format!("{id}_{k}"), id, k as i32, &machine, ms));
+            }
+            task
+        };
+        ScheduleRequest::new(
+            vec![
+                job("J1", [4000, 1000, 1000]),
+                job("J2", [1000, 4000, 1000]),
+                job("J3", [1000, 1000, 4000]),
+            ],
+            vec![
+                Resource::primary("M1"),
+                Resource::primary("M2"),
+                Resource::primary("M3"),
+            ],
+        )
+    }
+
+    /// 5 jobs × 3 machines with release times (a job shop).
+    fn job_shop() -> ScheduleRequest {
+        let routes: [(i64, [(&str, i64); 3]); 5] = [
+            (1000, [("M1", 1000), ("M2", 3000), ("M3", 2000)]),
+            (2000, [("M2", 2000), ("M3", 4000), ("M1", 4000)]),
+            (2000, [("M3", 4000), ("M1", 1000), ("M2", 5000)]),
+            (1000, [("M1", 1000), ("M2", 3000), ("M3", 5000)]),
+            (1000, [("M2", 4000), ("M3", 1000), ("M1", 2000)]),
+        ];
+        let tasks = routes
+            .iter()
+            .enumerate()
+            .map(|(j, (release, route))| {
+                let id = format!("J{j}");
+                let mut task = Task::new(&id).with_release_time(*release);
+                for (k, &(machine, ms)) in route.iter().enumerate() {
+                    task = task.with_activity(op(&format!("{id}_{k}"), &id, k as i32, machine, ms));
+                }
+                task
+            })
+            .collect();
+        ScheduleRequest::new(
+            tasks,
+            vec![
+                Resource::primary("M1"),
+                Resource::primary("M2"),
+                Resource::primary("M3"),
+            ],
+        )
+    }
+
+    /// Permutation schedule of `order` (semi-active).
+    fn permutation(request: &ScheduleRequest, order: &[usize]) -> Schedule {
+        let mut schedule = Schedule::new();
+        let mut machine_free = [0i64; 3];
+        for &j in order {
+            let task = &request.tasks[j];
+            let mut ready = 0;
+            for (k, a) in task.activities.iter().enumerate() {
+                let start = ready.max(machine_free[k]);
+                let end = start + a.duration.process_ms;
+                schedule.add_assignment(Assignment::new(
+                    &a.id,
+                    &task.id,
+                    format!("M{}", k + 1),
+                    start,
+                    end,
+                ));
+                (ready, machine_free[k]) = (end, end);
+            }
+        }
+        schedule
+    }
+
+    #[test]
+    fn test_improves_makespan() {
+        let request = request();
+        let plan = permutation(&request, &[0, 1, 2]);
+        assert_eq!(plan.makespan_ms(), 14_000);
+
+        let (improved, report) = TabuImprover::new()
+            .with_tardiness_weight(0.0)
+            .improve(&request, &plan);
+        // J3, J2, J1 meets M2's bound: 1 s before it, 6 s on it, 1 s after.
+        assert_eq!(improved.makespan_ms(), 8000);
+        assert!(improved.violations.is_empty());
+        assert_eq!(report.final_cost, 8000.0);
+        assert!(report.best_iteration > 0);
+        assert_eq!(
+            improved.assignment_count(),
+            plan.assignment_count(),
+            "resource choices are kept"
+        );
+    }
+
+    #[test]
+    fn test_keeps_frozen_and_optimal_input() {
+        let mut request = request();
+        let best = permutation(&request, &[2, 1, 0]);
+        let (kept, report) = TabuImprover::new()
+            .with_tardiness_weight(0.0)
+            .improve(&request, &best);
+        assert_eq!(report.final_cost, report.initial_cost);
+        assert_eq!(report.best_iteration, 0);
+        assert_eq!(kept.fingerprint(), best.fingerprint());
+
+        // J1 frozen first on M1: the others can only reorder behind it.
+        let plan = permutation(&request, &[0, 1, 2]);
+        request.locks = LockSet::freeze(&plan, |a| a.activity_id == "J1_0");
+        let (improved, _) = TabuImprover::new()
+            .with_tardiness_weight(0.0)
+            .improve(&request, &plan);
+        let j1 = improved.assignment_for_activity("J1_0").unwrap();
+        assert_eq!((j1.start_ms, j1.end_ms), (0, 4000));
+        assert!(improved.makespan_ms() < 14_000);
+        assert!(improved.violations.is_empty());
+    }
+
+    #[test]
+    fn test_swapped_pair_is_tabu_both_ways() {
+        let request = request();
+        let plan = permutation(&request, &[0, 1, 2]);
+        let graph = DisjunctiveGraph::new(&request, &plan, 0.0);
+        let mut rng = u_numflow::random::create_rng(0);
+        let start = graph.initial_solution(&mut rng);
+        let moves = graph.neighbors(&start, &mut rng);
+        assert!(!moves.is_empty());
+
+        // Swapping a pair back is the same move key, so tenure forbids it.
+        let mut undone = 0;
+        for first in &moves {
+            let undo = graph
+                .neighbors(&first.solution, &mut rng)
+                .into_iter()
+                .find(|m| m.solution.sequences == start.sequences);
+            if let Some(undo) = undo {
+                assert_eq!(undo.key, first.key);
+                undone += 1;
+            }
+        }
+        assert!(undone > 0);
+    }
+
+    #[test]
+    fn test_tenure_escapes_local_optimum() {
+        let instance = golden::ft06();
+        let request = instance.request();
+        let plan = SimpleScheduler::new().schedule_request(&request);
+        let search = |tenure| {
+            TabuImprover::new()
+                .with_tardiness_weight(0.0)
+                .with_iterations(300)
+                .with_max_no_improve(300)
+                .with_tenure(tenure)
+                .improve(&request, &plan)
+        };
+
+        // A one-move memory cycles near a local optimum; a longer one
+        // walks on to the optimum.
+        let (short, _) = search(1);
+        let (long, report) = search(7);
+        assert!(short.makespan_ms() > long.makespan_ms());
+        assert_eq!(long.makespan_ms(), instance.reference_makespan_ms);
+        assert_eq!(report.iterations, 300);
+        golden::assert_schedule_quality(&instance, &long, 0.0);
+    }
+
+    #[test]
+    fn test_aspiration_overrides_tenure() {
+        let request = job_shop();
+        let plan = SimpleScheduler::new().schedule_request(&request);
+        let search = |aspiration| {
+            TabuImprover::new()
+                .with_tardiness_weight(0.0)
+                .with_iterations(100)
+                .with_max_no_improve(100)
+                .with_tenure(3)
+                .with_aspiration(aspiration)
+                .improve(&request, &plan)
+                .1
+        };
+
+        // Taking tabu moves that beat the best reaches it sooner.
+        let (with, without) = (search(true), search(false));
+        assert_eq!(with.final_cost, without.final_cost);
+        assert!(with.best_iteration < without.best_iteration);
+    }
+
+    #[test]
+    fn test_iteration_limits() {
+        let request = request();
+        let plan = permutation(&request, &[0, 1, 2]);
+        let (_, report) = TabuImprover::new()
+            .with_iterations(2)
+            .with_max_no_improve(100)
+            .improve(&request, &plan);
+        assert!(report.iterations <= 2);
+
+        // From the optimum nothing improves: the search stops after
+        // `max_no_improve` moves.
+        let best = permutation(&request, &[2, 1, 0]);
+        let (_, report) = TabuImprover::new()
+            .with_tardiness_weight(0.0)
+            .with_max_no_improve(4)
+            .improve(&request, &best);
+        assert_eq!(report.iterations, 4);
+        assert_eq!(report.best_iteration, 0);
+    }
+
+    #[test]
+    fn test_result_no_worse_with_release_times_and_setups() {
+        // Categories alternate on M3, which keeps its order under a matrix.
+        let mut request = job_shop();
+        for (j, task) in request.tasks.iter_mut().enumerate() {
+            task.category = ["A", "B"][j % 2].to_string();
+            task.deadline = Some(12_000 + 1000 * j as i64);
+        }
+        let mut matrix = TransitionMatrix::new("families", "M3");
+        matrix.set_transition("A", "B", 500);
+        matrix.set_transition("B", "A", 700);
+        request.transition_matrices = TransitionMatrixCollection::new().with_matrix(matrix);
+        let plan = SimpleScheduler::new().schedule_request(&request);
+        let m3_order = |s: &Schedule| {
+            let mut work: Vec<(i64, String)> = s
+                .assignments_for_resource("M3")
+                .iter()
+                .map(|a| (a.start_ms, a.activity_id.clone()))
+                .collect();
+            work.sort();
+            work.into_iter().map(|(_, id)| id).collect::<Vec<_>>()
+        };
+
+        for weight in [0.0, 0.5, 1.0] {
+            let (improved, report) = TabuImprover::new()
+                .with_tardiness_weight(weight)
+                .improve(&request, &plan);
+            assert!(report.final_cost <= report.initial_cost);
+            assert_eq!(m3_order(&improved), m3_order(&plan));
+            if weight == 0.0 {
+                assert!(improved.makespan_ms() < plan.makespan_ms());
+                let instance = GoldenInstance {
+                    name: "job_shop_setups",
+                    tasks: request.tasks.clone(),
+                    resources: request.resources.clone(),
+                    transition_matrices: request.transition_matrices.clone(),
+                    reference_makespan_ms: plan.makespan_ms(),
+                    optimal: false,
+                };
+                golden::assert_schedule_quality(&instance, &improved, 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Machine-selection rules for list scheduling.
+//!
+//! The [`SimpleScheduler`](super::SimpleScheduler) orders tasks with a
+//! [`RuleEngine`](crate::dispatching::RuleEngine) and, for each activity,
+//! picks one of the candidate resources. By default the earliest start
+//! wins (see [`SelectionPolicy`](super::SelectionPolicy)); a
+//! [`MachineSelectionRule`] set with
+//! [`with_machine_rule`](super::SimpleScheduler::with_machine_rule)
+//! ranks the candidates instead, like a dispatching rule ranks tasks.
+//!
+//! | Rule | Prefers |
+//! |------|---------|
+//! | [`EarliestFinish`] | The earliest end of the activity |
+//! | [`LeastLoaded`] | The least work booked so far |
+//! | [`LowestSetup`] | The shortest setup (own plus changeover) |
+//! | [`RandomMachine`] | A seeded pseudo-random candidate |
+//!
+//! Candidates with equal scores go to the earlier start, then to the
+//! scheduler's [`TieBreak`](super::TieBreak).
+//!
+//! # Reference
+//! Pinedo (2016), "Scheduling", Ch. 4: Priority Dispatching
+
+use std::fmt::Debug;
+
+use crate::dispatching::seeded_hash;
+use crate::models::{Activity, Resource, Task};
+
+/// A candidate resource for an activity, as placed by the scheduler.
+#[derive(Debug, Clone, Copy)]
+pub struct MachineCandidate<'a> {
+    /// The task.
+    pub task: &'a Task,
+    /// The activity being placed.
+    pub activity: &'a Activity,
+    /// The candidate resource.
+    pub resource: &'a Resource,
+    /// Start on the resource, setup included (ms).
+    pub start_ms: i64,
+    /// End on the resource, teardown included (ms).
+    pub end_ms: i64,
+    /// Setup time: the activity's own plus the changeover (ms).
+    pub setup_ms: i64,
+    /// Work already booked on the resource (ms).
+    pub booked_ms: i64,
+}
+
+/// A rule ranking the candidate resources of an activity.
+pub trait MachineSelectionRule: Send + Sync + Debug {
+    /// Rule name (e.g., "EarliestFinish").
+    fn name(&self) -> &'static str;
+
+    /// Score of a candidate; lower is preferred.
+    fn score(&self, candidate: &MachineCandidate) -> f64;
+}
+
+/// Earliest Finish: the candidate completing the activity first.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EarliestFinish;
+
+impl MachineSelectionRule for EarliestFinish {
+    fn name(&self) -> &'static str {
+        "EarliestFinish"
+    }
+
+    fn score(&self, candidate: &MachineCandidate) -> f64 {
+        candidate.end_ms as f64
+    }
+}
+
+/// Least Loaded: the candidate with the least work booked so far.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LeastLoaded;
+
+impl MachineSelectionRule for LeastLoaded {
+    fn name(&self) -> &'static str {
+        "LeastLoaded"
+    }
+
+    fn score(&self, candidate: &MachineCandidate) -> f64 {
+        candidate.booked_ms as f64
+    }
+}
+
+/// Lowest Setup: the candidate needing the shortest setup, e.g. one
+/// already set up for the task's category.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LowestSetup;
+
+impl MachineSelectionRule for LowestSetup {
+    fn name(&self) -> &'static str {
+        "LowestSetup"
+    }
+
+    fn score(&self, candidate: &MachineCandidate) -> f64 {
+        candidate.setup_ms as f64
+    }
+}
+
+/// A pseudo-random candidate, reproducible from the seed.
+///
+/// The score hashes the seed with the activity and resource IDs, so the
+/// same seed picks the same resources on every run and platform, and
+/// different seeds give different (independent) choices.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomMachine {
+    /// Seed.
+    pub seed: u64,
+}
+
+impl RandomMachine {
+    /// Random choices from `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl MachineSelectionRule for RandomMachine {
+    fn name(&self) -> &'static str {
+        "RandomMachine"
+    }
+
+    fn score(&self, candidate: &MachineCandidate) -> f64 {
+        seeded_hash(self.seed, &[&candidate.activity.id, &candidate.resource.id]) as f64
+    }
+}
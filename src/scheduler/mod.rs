@@ -6,19 +6,232 @@
 //!
 //! `SimpleScheduler` uses a greedy, priority-driven, earliest-available-resource
 //! heuristic. It is not optimal, but provides fast baseline solutions.
+//! Ties between equally early resources follow a [`TieBreak`] policy, so
+//! repeated runs on equivalent inputs give identical schedules. Resource
+//! choice is pluggable like task ordering: a `MachineSelectionRule`
+//! (earliest finish, least loaded, lowest setup, seeded random) ranks
+//! the candidates of each activity. Tasks are placed whole in dispatch
+//! order (serial scheme), or, with a parallel `GenerationScheme`,
+//! activity by activity across tasks to build active or non-delay
+//! schedules.
+//!
+//! # Dispatching Simulation
+//!
+//! `DispatchScheduler` advances a simulation clock and, whenever a machine
+//! is free, picks the next job from its queue with the rule engine and a
+//! freshly derived `SchedulingContext`, so state-dependent rules (WINQ,
+//! CR, MST) see the current shop instead of the empty plan. Each machine
+//! may use its own `QueueDiscipline` (FIFO, its own rule engine, or
+//! preemptive priority), and `simulate` reports per-machine queue
+//! statistics and, if the run stalls, the blocked jobs and machines.
+//!
+//! # Backward Scheduling
+//!
+//! `BackwardScheduler` works back from deadlines and places activities
+//! as late as possible, for just-in-time plans and per-activity latest
+//! start times.
+//!
+//! # Campaigns
+//!
+//! `CampaignOptimizer` groups same-category tasks into campaigns to cut
+//! sequence-dependent setup, bounded by campaign size and earliness.
+//!
+//! # Order Aggregation
+//!
+//! `OrderAggregator` merges many small identical tasks (same category,
+//! routing and due window) into aggregated tasks with a quantity before
+//! solving, and expands the solved schedule back to the original orders.
+//!
+//! # Parallel Clusters
+//!
+//! `ParallelScheduler` splits a problem into independent clusters (no
+//! shared resources or precedences) and schedules them on worker threads.
+//!
+//! # Rolling Horizon
+//!
+//! `RollingHorizon` plans long horizons in overlapping windows, carrying
+//! resource state from one window to the next.
+//!
+//! # Setpoint Mode
+//!
+//! `SetpointScheduler` schedules towards a target output profile (tasks
+//! of each category completed per day) instead of the shortest makespan,
+//! for level-loaded (heijunka) production; `TargetDeviation` measures the
+//! gap to the target.
+//!
+//! # Smoothing
+//!
+//! `ScheduleSmoother` shifts assignments earlier within their slack to
+//! merge short idle fragments into larger free blocks.
+//!
+//! # Randomized Variants
+//!
+//! `ScheduleShuffler` derives alternative sequences of a schedule with
+//! the same makespan and tardiness, by exchanging neighboring jobs within
+//! their slack, for A/B tests of plan acceptance.
+//!
+//! # Aggregate Planning
+//!
+//! `AggregatePlanner` allocates task workloads to weekly (or other)
+//! capacity buckets per resource group without sequencing, as a fast
+//! rough-cut feasibility view; its plan can seed release dates for the
+//! detailed scheduler.
+//!
+//! # Improvement
+//!
+//! `LnsImprover` polishes a schedule from any backend by large
+//! neighborhood search: it removes part of the schedule (one machine, a
+//! time window, or the tardiest tasks) and rebuilds it greedily, keeping
+//! improvements. `TabuImprover` keeps the resource choices and reorders
+//! work on each machine by tabu search over critical-path swaps in the
+//! disjunctive graph.
+//!
+//! # Capacity Analysis
+//!
+//! `CapacityAnalyzer` re-solves a problem with fewer resources or with
+//! added machines and reports makespan and tardiness per resource count,
+//! to justify capacity investments.
+//!
+//! # Sensitivity
+//!
+//! `SensitivityAnalyzer` re-solves a problem with perturbed durations and
+//! deadlines and reports how stable the dispatch order, resource choices
+//! and KPIs of the dispatching policy are.
+//!
+//! # Order Promising
+//!
+//! `OrderPromiser` inserts a prospective order into the current plan
+//! under sampled processing times and quotes p50/p85/p95 completion
+//! dates.
+//!
+//! # Confidence
+//!
+//! `ConfidenceEstimator` replays a plan with sampled processing times and
+//! stores, per assignment, the probability of starting and finishing as
+//! planned, so fragile parts of the plan stand out.
+//!
+//! # Random Baseline
+//!
+//! `RandomBaseline` samples random feasible schedules (random task
+//! orders and resource picks) and reports their KPI distribution, to
+//! rank a solver's result against random plans.
+//!
+//! # What-If Sweeps
+//!
+//! `ParameterSweep` re-solves a problem across the values of one
+//! parameter (a resource's capacity, the ATC look-ahead, or the
+//! tardiness weight) and returns the KPIs per value.
+//!
+//! # Explanation
+//!
+//! `ScheduleExplainer` reports why a task starts when it does: the
+//! release time, predecessors, busy resources, or calendars that bind
+//! each of its assignments.
+//!
+//! # Manual Editing
+//!
+//! `ScheduleEditor` applies drag-and-drop edits (move in time or to
+//! another resource), optionally right-shifts dependent work, and reports
+//! the violations each edit introduces or resolves.
+//!
+//! # Repair
+//!
+//! `right_shift_repair` repairs a schedule after a disruption (a machine
+//! breakdown, a delayed or overrunning activity) by pushing affected
+//! assignments later, keeping resources and the order of work, and
+//! reports how far the plan moved.
+//!
+//! # Input Changes
+//!
+//! `stale_assignments` compares the request a schedule was built from
+//! with an edited request and splits the schedule into stale, affected
+//! and valid activities, so a targeted repair can freeze the valid part
+//! and replan only the rest.
 //!
 //! # KPI
 //!
 //! `ScheduleKpi` computes standard scheduling metrics: makespan, tardiness,
-//! on-time rate, utilization, and flow time.
+//! on-time rate, utilization, and flow time. `WorkloadFairness` measures
+//! how evenly work is spread over (human) resources. `CapacityLeveling`
+//! measures peaks in the number of resources needed per time bucket.
+//!
+//! # Cost
+//!
+//! `CostModel` prices a schedule from resource hourly rates and breaks
+//! the cost down per assignment and task (machine, labor, setup,
+//! overtime), with CSV export.
+//!
+//! # Controller Export
+//!
+//! `SequenceExport` cuts a schedule into signed per-resource job queues
+//! (start, setup, category) in JSON or CSV for machine and cell
+//! controllers.
 //!
 //! # References
 //!
 //! - Pinedo (2016), "Scheduling: Theory, Algorithms, and Systems", Ch. 3-4
 //! - Baker & Trietsch (2019), "Principles of Sequencing and Scheduling"
 
+mod aggregate;
+mod backward;
+mod baseline;
+mod campaign;
+mod capacity;
+mod confidence;
+mod cost;
+mod dispatch;
+mod edit;
+mod explain;
+mod fairness;
+mod grouping;
 mod kpi;
+mod leveling;
+mod lns;
+mod local_search;
+mod machine;
+mod parallel;
+mod promise;
+mod repair;
+mod rolling;
+mod sensitivity;
+mod sequence;
+mod setpoint;
+mod shuffle;
 mod simple;
+mod smoothing;
+mod staleness;
+mod sweep;
 
+pub use aggregate::{AggregatePlan, AggregatePlanner, GroupLoad, TaskBuckets};
+pub use backward::{BackwardPlan, BackwardScheduler};
+pub use baseline::{BaselineReport, RandomBaseline};
+pub use campaign::{Campaign, CampaignOptimizer, CampaignReport};
+pub use capacity::{CapacityAnalyzer, CapacityCurve, CapacityPoint};
+pub use confidence::ConfidenceEstimator;
+pub use cost::{AssignmentCost, CostModel, CostReport, TaskCost};
+pub use dispatch::{
+    BlockedJob, DispatchScheduler, QueueDiscipline, QueueStats, SimulationReport, Stall,
+};
+pub use edit::{EditOutcome, ManualEdit, ScheduleEditor};
+pub use explain::{AssignmentExplanation, ScheduleExplainer, StartReason, TaskExplanation};
+pub use fairness::WorkloadFairness;
+pub use grouping::{AggregatedRequest, OrderAggregator, OrderGroup};
 pub use kpi::ScheduleKpi;
-pub use simple::{ScheduleRequest, SimpleScheduler};
+pub use leveling::{CapacityLeveling, LevelingProfile};
+pub use lns::{LnsImprover, LnsReport};
+pub use local_search::{TabuImprover, TabuReport};
+pub use machine::{
+    EarliestFinish, LeastLoaded, LowestSetup, MachineCandidate, MachineSelectionRule, RandomMachine,
+};
+pub use parallel::ParallelScheduler;
+pub use promise::{OrderPromise, OrderPromiser};
+pub use repair::{right_shift_repair, Disruption, RepairOutcome, StabilityDelta};
+pub use rolling::{RollingHorizon, WindowReport};
+pub use sensitivity::{PerturbationOutcome, SensitivityAnalyzer, SensitivityReport};
+pub use sequence::{QueueEntry, ResourceQueue, SequenceExport};
+pub use setpoint::{BucketOutput, OutputTarget, SetpointScheduler, TargetDeviation};
+pub use shuffle::{ScheduleShuffler, ShuffledSchedule};
+pub use simple::{GenerationScheme, ScheduleRequest, SelectionPolicy, SimpleScheduler, TieBreak};
+pub use smoothing::{ScheduleSmoother, SmoothingReport};
+pub use staleness::{stale_assignments, StaleActivity, StaleReason, StalenessReport};
+pub use sweep::{ParameterSweep, SweepCurve, SweepParameter, SweepPoint};
@@ -0,0 +1,373 @@
+//! Parallel scheduling of independent task clusters.
+//!
+//! Multi-line plants often decompose into clusters that share nothing:
+//! no candidate resource, no setup crew, no precedence. Each cluster can
+//! be scheduled on its own, and the clusters in parallel.
+//! [`ParallelScheduler`] finds the connected components of the
+//! task–resource graph, schedules them on worker threads, and merges
+//! the results.
+//!
+//! # Graph
+//!
+//! | Edge | Between |
+//! |------|---------|
+//! | Candidate | A task and each candidate (or setup crew) resource of its activities |
+//! | Precedence | Two tasks linked by an explicit activity predecessor |
+//! | Lock | A locked task and the resources of its locked assignments |
+
+use std::collections::HashMap;
+
+use super::{ScheduleRequest, SimpleScheduler};
+use crate::models::Schedule;
+
+/// Greedy scheduling of independent clusters on worker threads.
+///
+/// Clusters are dealt to threads largest first (by activity count), each
+/// to the least loaded thread. With a dispatch order that ranks tasks
+/// independently of each other (the default priority order), the result
+/// has the same assignments as [`SimpleScheduler`] on the whole request;
+/// only their order differs (grouped by cluster). Rule engines whose
+/// scores depend on the other tasks (e.g., ATC's mean processing time)
+/// see only the task's cluster.
+///
+/// On `wasm32` clusters are scheduled one after another.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, ActivityDuration, Resource, ResourceRequirement, Task};
+/// use u_schedule::scheduler::{ParallelScheduler, ScheduleRequest};
+///
+/// let tasks = ["L1", "L2"]
+///     .iter()
+///     .map(|line| {
+///         let id = format!("J_{line}");
+///         Task::new(&id).with_activity(
+///             Activity::new(format!("{id}_O1"), &id, 0)
+///                 .with_duration(ActivityDuration::fixed(1000))
+///                 .with_requirement(
+///                     ResourceRequirement::new("Machine").with_candidates(vec![line.to_string()]),
+///                 ),
+///         )
+///     })
+///     .collect();
+/// let request = ScheduleRequest::new(tasks, vec![Resource::primary("L1"), Resource::primary("L2")]);
+///
+/// assert_eq!(ParallelScheduler::decompose(&request).len(), 2);
+/// let schedule = ParallelScheduler::new().with_threads(2).schedule_request(&request);
+/// assert_eq!(schedule.makespan_ms(), 1000);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParallelScheduler {
+    scheduler: SimpleScheduler,
+    threads: usize,
+}
+
+impl Default for ParallelScheduler {
+    fn default() -> Self {
+        Self {
+            scheduler: SimpleScheduler::new(),
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+impl ParallelScheduler {
+    /// Creates a scheduler using all available cores.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the scheduler run on each cluster.
+    ///
+    /// Transition matrices, affinity, locks and tooling are taken from the request.
+    pub fn with_scheduler(mut self, scheduler: SimpleScheduler) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Sets the number of worker threads (at least 1).
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Splits a request into independent sub-requests, largest first.
+    ///
+    /// Each sub-request keeps its tasks (in request order), the resources
+    /// they can use, and the request's start time, transition matrices,
+    /// affinity, locks and tooling. Resources no task can use are
+    /// dropped; tasks sharing a tool pool stay together.
+    pub fn decompose(request: &ScheduleRequest) -> Vec<ScheduleRequest> {
+        let tasks = &request.tasks;
+        let resource_index: HashMap<&str, usize> = request
+            .resources
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (r.id.as_str(), tasks.len() + i))
+            .collect();
+        let task_of_activity: HashMap<&str, usize> = tasks
+            .iter()
+            .enumerate()
+            .flat_map(|(i, t)| t.activities.iter().map(move |a| (a.id.as_str(), i)))
+            .collect();
+
+        // Nodes: tasks, then resources.
+        let mut components = DisjointSet::new(tasks.len() + request.resources.len());
+        for (i, task) in tasks.iter().enumerate() {
+            for activity in &task.activities {
+                let crews = activity
+                    .setup_requirement
+                    .iter()
+                    .flat_map(|req| req.candidates.iter().map(String::as_str));
+                let candidates = activity.resolved_candidates(&request.resources);
+                let tools = request
+                    .tooling
+                    .tools_for(&task.category)
+                    .into_iter()
+                    .flatten()
+                    .map(String::as_str);
+                for id in candidates.into_iter().chain(crews).chain(tools) {
+                    if let Some(&r) = resource_index.get(id) {
+                        components.union(i, r);
+                    }
+                }
+                for pred in &activity.predecessors {
+                    if let Some(&j) = task_of_activity.get(pred.as_str()) {
+                        components.union(i, j);
+                    }
+                }
+            }
+        }
+        let task_index: HashMap<&str, usize> = tasks
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.id.as_str(), i))
+            .collect();
+        for a in request.locks.locked_assignments() {
+            if let (Some(&i), Some(&r)) = (
+                task_index.get(a.task_id.as_str()),
+                resource_index.get(a.resource_id.as_str()),
+            ) {
+                components.union(i, r);
+            }
+        }
+
+        // Group by root, in order of first task.
+        let mut by_root: HashMap<usize, usize> = HashMap::new();
+        let mut parts: Vec<ScheduleRequest> = Vec::new();
+        for (i, task) in tasks.iter().enumerate() {
+            let root = components.find(i);
+            let part = *by_root.entry(root).or_insert_with(|| {
+                parts.push(empty_like(request));
+                parts.len() - 1
+            });
+            parts[part].tasks.push(task.clone());
+        }
+        for (i, resource) in request.resources.iter().enumerate() {
+            if let Some(&part) = by_root.get(&components.find(tasks.len() + i)) {
+                parts[part].resources.push(resource.clone());
+            }
+        }
+
+        parts.sort_by_key(|p| std::cmp::Reverse(activity_count(p)));
+        parts
+    }
+
+    /// Schedules a request cluster by cluster on worker threads.
+    pub fn schedule_request(&self, request: &ScheduleRequest) -> Schedule {
+        let parts = Self::decompose(request);
+
+        // Deal clusters to threads, largest first, to the least loaded.
+        let threads = self.threads.min(parts.len()).max(1);
+        let mut chunks: Vec<Vec<usize>> = vec![Vec::new(); threads];
+        let mut load = vec![0usize; threads];
+        for (i, part) in parts.iter().enumerate() {
+            let t = (0..threads).min_by_key(|&t| load[t]).unwrap_or(0);
+            chunks[t].push(i);
+            load[t] += activity_count(part);
+        }
+
+        let mut results: Vec<(usize, Schedule)> = self.run_chunks(&parts, &chunks);
+        results.sort_by_key(|(i, _)| *i);
+
+        let mut schedule = Schedule::new();
+        for (_, part) in results {
+            schedule.assignments.extend(part.assignments);
+            schedule.violations.extend(part.violations);
+        }
+        schedule
+    }
+
+    /// Schedules each chunk of clusters on its own thread.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run_chunks(
+        &self,
+        parts: &[ScheduleRequest],
+        chunks: &[Vec<usize>],
+    ) -> Vec<(usize, Schedule)> {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = chunks
+                .iter()
+                .map(|chunk| scope.spawn(move || self.run_chunk(parts, chunk)))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().expect("cluster scheduling thread panicked"))
+                .collect()
+        })
+    }
+
+    /// Schedules the chunks one after another (no threads on wasm32).
+    #[cfg(target_arch = "wasm32")]
+    fn run_chunks(
+        &self,
+        parts: &[ScheduleRequest],
+        chunks: &[Vec<usize>],
+    ) -> Vec<(usize, Schedule)> {
+        chunks
+            .iter()
+            .flat_map(|chunk| self.run_chunk(parts, chunk))
+            .collect()
+    }
+
+    /// Schedules the clusters of one chunk.
+    fn run_chunk(&self, parts: &[ScheduleRequest], chunk: &[usize]) -> Vec<(usize, Schedule)> {
+        chunk
+            .iter()
+            .map(|&i| (i, self.scheduler.schedule_request(&parts[i])))
+            .collect()
+    }
+}
+
+/// A request with the settings of `request` and no tasks or resources.
+fn empty_like(request: &ScheduleRequest) -> ScheduleRequest {
+    ScheduleRequest {
+        tasks: Vec::new(),
+        resources: Vec::new(),
+        start_time_ms: request.start_time_ms,
+        transition_matrices: request.transition_matrices.clone(),
+        affinity: request.affinity.clone(),
+        locks: request.locks.clone(),
+        tooling: request.tooling.clone(),
+        throughput_targets: request.throughput_targets.clone(),
+    }
+}
+
+/// Number of activities in a request.
+fn activity_count(request: &ScheduleRequest) -> usize {
+    request.tasks.iter().map(|t| t.activities.len()).sum()
+}
+
+/// Union–find over `0..n` with path halving.
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb {
+            self.parent[rb] = ra;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, ActivityDuration, Resource, ResourceRequirement, Task};
+
+    fn make_task(id: &str, duration_ms: i64, candidates: &[&str]) -> Task {
+        Task::new(id).with_activity(
+            Activity::new(format!("{id}_O1"), id, 0)
+                .with_duration(ActivityDuration::fixed(duration_ms))
+                .with_requirement(
+                    ResourceRequirement::new("Machine")
+                        .with_candidates(candidates.iter().map(|c| c.to_string()).collect()),
+                ),
+        )
+    }
+
+    fn make_request() -> ScheduleRequest {
+        let mut tasks = vec![
+            make_task("A1", 1000, &["M1", "M2"]),
+            make_task("B1", 500, &["M3"]),
+            make_task("A2", 2000, &["M2"]),
+            make_task("C1", 700, &["M5"]),
+            make_task("C2", 300, &["M6"]),
+            make_task("B2", 800, &["M3"]),
+        ];
+        // C2 follows C1 across lines M5 and M6.
+        tasks[4].activities[0].predecessors = vec!["C1_O1".into()];
+        let resources = ["M1", "M2", "M3", "M4", "M5", "M6"]
+            .iter()
+            .map(|&id| Resource::primary(id))
+            .collect();
+        ScheduleRequest::new(tasks, resources)
+    }
+
+    #[test]
+    fn test_decompose() {
+        let parts = ParallelScheduler::decompose(&make_request());
+        let ids: Vec<Vec<&str>> = parts
+            .iter()
+            .map(|p| p.tasks.iter().map(|t| t.id.as_str()).collect())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![vec!["A1", "A2"], vec!["B1", "B2"], vec!["C1", "C2"]]
+        );
+        let resources: Vec<Vec<&str>> = parts
+            .iter()
+            .map(|p| p.resources.iter().map(|r| r.id.as_str()).collect())
+            .collect();
+        // M4 is unused and dropped.
+        assert_eq!(
+            resources,
+            vec![vec!["M1", "M2"], vec!["M3"], vec!["M5", "M6"]]
+        );
+    }
+
+    #[test]
+    fn test_matches_sequential() {
+        let request = make_request();
+        let sorted = |schedule: &Schedule| {
+            let mut rows: Vec<(String, String, i64, i64)> = schedule
+                .assignments
+                .iter()
+                .map(|a| {
+                    (
+                        a.activity_id.clone(),
+                        a.resource_id.clone(),
+                        a.start_ms,
+                        a.end_ms,
+                    )
+                })
+                .collect();
+            rows.sort();
+            rows
+        };
+        let sequential = SimpleScheduler::new().schedule_request(&request);
+        for threads in [1, 2, 8] {
+            let parallel = ParallelScheduler::new()
+                .with_threads(threads)
+                .schedule_request(&request);
+            assert_eq!(sorted(&parallel), sorted(&sequential));
+        }
+    }
+}
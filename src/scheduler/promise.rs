@@ -0,0 +1,310 @@
+//! Order promising under duration uncertainty.
+//!
+//! Quoting a delivery date from the planned durations gives a date the
+//! shop meets about half the time. [`OrderPromiser`] inserts a
+//! prospective order into the current plan many times, with processing
+//! times drawn from per-activity [`DurationDistribution`]s, and reports
+//! completion-date quantiles as an [`OrderPromise`].
+//!
+//! # Method
+//!
+//! 1. Every task of the current plan is locked to its assignments, so
+//!    the order only fills the gaps the plan leaves.
+//! 2. Per sample, each activity of the order gets a processing time
+//!    drawn from its distribution (inverse-CDF sampling through
+//!    [`DurationDistribution::duration_at_confidence`]); activities
+//!    without one keep their planned time.
+//! 3. The greedy scheduler inserts the order; its completion is recorded.
+//!
+//! Quantiles use the nearest-rank method over the samples.
+
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::{ScheduleRequest, SimpleScheduler};
+use crate::models::{DurationDistribution, Lock, Schedule, Task};
+
+/// Completion-date quantiles of a prospective order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderPromise {
+    /// Order (task) ID.
+    pub order_id: String,
+    /// Completion with the planned durations (ms).
+    pub planned_completion_ms: i64,
+    /// Median completion (ms).
+    pub p50_ms: i64,
+    /// 85th percentile completion (ms).
+    pub p85_ms: i64,
+    /// 95th percentile completion (ms).
+    pub p95_ms: i64,
+    /// Sampled completions, ascending (ms).
+    pub completions_ms: Vec<i64>,
+}
+
+impl OrderPromise {
+    /// Completion met with probability `confidence` (clamped to `[0, 1]`).
+    pub fn completion_at(&self, confidence: f64) -> i64 {
+        quantile(&self.completions_ms, confidence)
+    }
+
+    /// Share of samples completing by `due_ms`.
+    pub fn on_time_probability(&self, due_ms: i64) -> f64 {
+        if self.completions_ms.is_empty() {
+            return 0.0;
+        }
+        let on_time = self.completions_ms.partition_point(|&c| c <= due_ms);
+        on_time as f64 / self.completions_ms.len() as f64
+    }
+}
+
+/// Quotes completion quantiles for orders inserted into a plan.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{
+///     Activity, ActivityDuration, Assignment, DurationDistribution, Resource,
+///     ResourceRequirement, Schedule, Task,
+/// };
+/// use u_schedule::scheduler::{OrderPromiser, ScheduleRequest};
+///
+/// let on_m1 = || ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]);
+/// let load = Task::new("J1").with_activity(
+///     Activity::new("J1_O1", "J1", 0)
+///         .with_duration(ActivityDuration::fixed(1000))
+///         .with_requirement(on_m1()),
+/// );
+/// let mut plan = Schedule::new();
+/// plan.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+/// let request = ScheduleRequest::new(vec![load], vec![Resource::primary("M1")]);
+///
+/// let order = Task::new("NEW").with_activity(
+///     Activity::new("NEW_O1", "NEW", 0)
+///         .with_duration(ActivityDuration::fixed(2000))
+///         .with_requirement(on_m1()),
+/// );
+/// let promise = OrderPromiser::new()
+///     .with_distribution("NEW_O1", DurationDistribution::Uniform { min_ms: 1000, max_ms: 3000 })
+///     .promise(&request, &plan, &order)
+///     .unwrap();
+/// assert_eq!(promise.planned_completion_ms, 3000);
+/// assert!(promise.p50_ms <= promise.p85_ms && promise.p85_ms <= promise.p95_ms);
+/// assert!(promise.p95_ms <= 4000);
+/// ```
+#[derive(Debug, Clone)]
+pub struct OrderPromiser {
+    scheduler: SimpleScheduler,
+    samples: usize,
+    seed: u64,
+    distributions: HashMap<String, DurationDistribution>,
+}
+
+impl Default for OrderPromiser {
+    fn default() -> Self {
+        Self {
+            scheduler: SimpleScheduler::new(),
+            samples: 200,
+            seed: 0,
+            distributions: HashMap::new(),
+        }
+    }
+}
+
+impl OrderPromiser {
+    /// Creates a promiser with 200 samples and seed 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the scheduler (e.g., with a rule engine).
+    ///
+    /// Transition matrices, affinity, locks and tooling are taken from the request.
+    pub fn with_scheduler(mut self, scheduler: SimpleScheduler) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Sets the number of samples (at least 1).
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    /// Sets the random seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets the processing-time distribution of an order activity.
+    pub fn with_distribution(
+        mut self,
+        activity_id: impl Into<String>,
+        distribution: DurationDistribution,
+    ) -> Self {
+        self.distributions.insert(activity_id.into(), distribution);
+        self
+    }
+
+    /// Quotes `order` against `plan`, the current schedule of `request`.
+    ///
+    /// Tasks of `request` missing from `plan` are scheduled around it like
+    /// any other unlocked work. Returns `None` if the order cannot be
+    /// scheduled (e.g., an activity has no known candidate resource).
+    pub fn promise(
+        &self,
+        request: &ScheduleRequest,
+        plan: &Schedule,
+        order: &Task,
+    ) -> Option<OrderPromise> {
+        let mut base = request.clone();
+        let mut plan_tasks: Vec<&str> = plan
+            .assignments
+            .iter()
+            .map(|a| a.task_id.as_str())
+            .collect();
+        plan_tasks.sort_unstable();
+        plan_tasks.dedup();
+        for task_id in plan_tasks {
+            base.locks = base.locks.with_lock(Lock::task(plan, task_id));
+        }
+        base.tasks.push(order.clone());
+        let order_index = base.tasks.len() - 1;
+
+        let planned_completion_ms = self
+            .scheduler
+            .schedule_request(&base)
+            .task_completion_time(&order.id)?;
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut completions_ms: Vec<i64> = Vec::with_capacity(self.samples);
+        for _ in 0..self.samples {
+            let mut sampled = base.clone();
+            for activity in &mut sampled.tasks[order_index].activities {
+                if let Some(distribution) = self.distributions.get(&activity.id) {
+                    let u: f64 = rng.random_range(1e-6..1.0 - 1e-6);
+                    activity.duration.process_ms = distribution.duration_at_confidence(u).max(0);
+                }
+            }
+            if let Some(completion) = self
+                .scheduler
+                .schedule_request(&sampled)
+                .task_completion_time(&order.id)
+            {
+                completions_ms.push(completion);
+            }
+        }
+        completions_ms.sort_unstable();
+
+        Some(OrderPromise {
+            order_id: order.id.clone(),
+            planned_completion_ms,
+            p50_ms: quantile(&completions_ms, 0.50),
+            p85_ms: quantile(&completions_ms, 0.85),
+            p95_ms: quantile(&completions_ms, 0.95),
+            completions_ms,
+        })
+    }
+}
+
+/// Nearest-rank quantile of ascending `values` (0 if empty).
+pub(super) fn quantile(values: &[i64], q: f64) -> i64 {
+    if values.is_empty() {
+        return 0;
+    }
+    let rank = (q.clamp(0.0, 1.0) * values.len() as f64).ceil() as usize;
+    values[rank.clamp(1, values.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, ActivityDuration, Assignment, Resource, ResourceRequirement};
+
+    fn job(id: &str, ms: i64) -> Task {
+        Task::new(id).with_activity(
+            Activity::new(format!("{id}_O1"), id, 0)
+                .with_duration(ActivityDuration::fixed(ms))
+                .with_requirement(
+                    ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                ),
+        )
+    }
+
+    fn load() -> (ScheduleRequest, Schedule) {
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+        plan.add_assignment(Assignment::new("J2_O1", "J2", "M1", 5000, 6000));
+        let request = ScheduleRequest::new(
+            vec![job("J1", 1000), job("J2", 1000)],
+            vec![Resource::primary("M1")],
+        );
+        (request, plan)
+    }
+
+    #[test]
+    fn test_fixed_durations_fill_gap() {
+        let (request, plan) = load();
+        let promise = OrderPromiser::new()
+            .with_samples(10)
+            .promise(&request, &plan, &job("NEW", 2000))
+            .unwrap();
+
+        // Fits in the gap between the two planned jobs.
+        assert_eq!(promise.planned_completion_ms, 3000);
+        assert_eq!((promise.p50_ms, promise.p95_ms), (3000, 3000));
+        assert_eq!(promise.completions_ms.len(), 10);
+        assert_eq!(promise.on_time_probability(2999), 0.0);
+        assert_eq!(promise.on_time_probability(3000), 1.0);
+    }
+
+    #[test]
+    fn test_uncertain_durations_spill_past_load() {
+        let (request, plan) = load();
+        let promiser = OrderPromiser::new().with_distribution(
+            "NEW_O1",
+            DurationDistribution::Uniform {
+                min_ms: 2000,
+                max_ms: 6000,
+            },
+        );
+        let promise = promiser
+            .promise(&request, &plan, &job("NEW", 3000))
+            .unwrap();
+
+        assert_eq!(promise.planned_completion_ms, 4000);
+        // Above 4000 ms of work the order no longer fits before J2 and
+        // starts after it, at 6000.
+        assert!((promise.on_time_probability(5000) - 0.5).abs() < 0.1);
+        assert!(promise.p85_ms > 10_000);
+        assert!(promise.p95_ms <= 12_000);
+        assert_eq!(
+            promise.completion_at(1.0),
+            *promise.completions_ms.last().unwrap()
+        );
+
+        // Reproducible for a fixed seed.
+        assert_eq!(
+            promiser.promise(&request, &plan, &job("NEW", 3000)),
+            Some(promise)
+        );
+    }
+
+    #[test]
+    fn test_unschedulable_order() {
+        let (request, plan) = load();
+        let order = Task::new("X").with_activity(
+            Activity::new("X_O1", "X", 0).with_duration(ActivityDuration::fixed(1000)),
+        );
+        assert!(OrderPromiser::new()
+            .with_samples(5)
+            .promise(&request, &plan, &order)
+            .is_none());
+        assert_eq!(quantile(&[], 0.5), 0);
+        assert_eq!(quantile(&[1, 2, 3, 4], 0.5), 2);
+        assert_eq!(quantile(&[1, 2, 3, 4], 0.0), 1);
+    }
+}
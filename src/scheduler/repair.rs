@@ -0,0 +1,316 @@
+//! Right-shift repair of a schedule after a disruption.
+//!
+//! When a machine breaks down or an operation runs late, re-solving the
+//! whole problem may reshuffle a plan the shop has already prepared for.
+//! [`right_shift_repair`] keeps the plan's resource choices and order of
+//! work and only pushes the affected assignments later, returning the
+//! repaired schedule with a [`StabilityDelta`] of how far the plan moved.
+//!
+//! # Method
+//!
+//! 1. The [`Disruption`] is applied: a delayed activity starts later, an
+//!    overrunning activity ends later, and a breakdown blocks its
+//!    resource for a window.
+//! 2. Every activity constrained by a moved one — through its task
+//!    sequence, explicit predecessors, or the order of work on a
+//!    single-capacity resource — moves later just enough to respect the
+//!    constraint, transitively (as in [`ScheduleEditor`]'s right shift).
+//!    Assignments overlapping a breakdown restart after it.
+//! 3. The result is re-validated with [`check_schedule`].
+//!
+//! Nothing moves earlier, and activities not reached by the disruption
+//! keep their times. Resources and the sequence on each resource are
+//! unchanged, so gaps a breakdown leaves before the window are not
+//! refilled; use the [`SimpleScheduler`](super::SimpleScheduler) with a
+//! [`LockSet`](crate::models::LockSet) for a full replan.
+//!
+//! [`ScheduleEditor`]: super::ScheduleEditor
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::edit::{right_shift, shift, work_sequence};
+use super::ScheduleRequest;
+use crate::models::{ActivityChange, Schedule};
+use crate::validation::check_schedule;
+
+/// An event invalidating part of a schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Disruption {
+    /// A resource is unavailable from `start_ms` to `end_ms`.
+    Breakdown {
+        /// Resource ID.
+        resource_id: String,
+        /// Start of the outage (ms).
+        start_ms: i64,
+        /// End of the outage (ms).
+        end_ms: i64,
+    },
+    /// An activity starts `delay_ms` later than planned.
+    Delay {
+        /// Activity ID.
+        activity_id: String,
+        /// Delay (ms).
+        delay_ms: i64,
+    },
+    /// An activity runs `extra_ms` longer than planned.
+    Overrun {
+        /// Activity ID.
+        activity_id: String,
+        /// Extra processing time (ms).
+        extra_ms: i64,
+    },
+}
+
+/// How much a repair moved the plan.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StabilityDelta {
+    /// Activities whose placement changed, by activity ID.
+    pub changes: Vec<ActivityChange>,
+    /// Activities starting later than planned.
+    pub moved_activities: usize,
+    /// Sum of start shifts over all activities (ms).
+    pub total_shift_ms: i64,
+    /// Largest start shift of any activity (ms).
+    pub max_shift_ms: i64,
+    /// Makespan after minus makespan before (ms).
+    pub makespan_delta_ms: i64,
+}
+
+/// A repaired schedule and how it differs from the original.
+#[derive(Debug, Clone)]
+pub struct RepairOutcome {
+    /// The repaired schedule, with its [`check_schedule`] violations.
+    pub schedule: Schedule,
+    /// Changes against the original schedule.
+    pub stability: StabilityDelta,
+}
+
+/// Repairs `schedule`, a solution of `request`, after `disruption` by
+/// right-shifting the affected assignments.
+///
+/// A disruption naming an unknown activity or resource changes nothing.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, Resource, ResourceRequirement, Task};
+/// use u_schedule::scheduler::{right_shift_repair, Disruption, ScheduleRequest, SimpleScheduler};
+///
+/// let on = |id: &str| ResourceRequirement::new("Machine").with_candidates(vec![id.into()]);
+/// let task = Task::new("J1")
+///     .with_activity(Activity::new("O1", "J1", 0).with_process_time(1000).with_requirement(on("M1")))
+///     .with_activity(Activity::new("O2", "J1", 1).with_process_time(1000).with_requirement(on("M2")));
+/// let request = ScheduleRequest::new(vec![task], vec![Resource::primary("M1"), Resource::primary("M2")]);
+/// let plan = SimpleScheduler::new().schedule_request(&request);
+///
+/// // M1 is down for the first half second: O1 restarts after it, O2 follows.
+/// let breakdown = Disruption::Breakdown { resource_id: "M1".into(), start_ms: 0, end_ms: 500 };
+/// let repaired = right_shift_repair(&request, &plan, &breakdown);
+/// assert_eq!(repaired.schedule.assignment_for_activity("O2").unwrap().start_ms, 1500);
+/// assert_eq!(repaired.stability.moved_activities, 2);
+/// assert_eq!(repaired.stability.makespan_delta_ms, 500);
+/// ```
+pub fn right_shift_repair(
+    request: &ScheduleRequest,
+    schedule: &Schedule,
+    disruption: &Disruption,
+) -> RepairOutcome {
+    let mut repaired = schedule.clone();
+    // Order of work before the disruption
+    let sequence = work_sequence(request, &repaired, None);
+
+    let mut moved = HashSet::new();
+    let mut blocked = Vec::new();
+    match disruption {
+        Disruption::Breakdown {
+            resource_id,
+            start_ms,
+            end_ms,
+        } => blocked.push((resource_id.clone(), *start_ms, *end_ms)),
+        Disruption::Delay {
+            activity_id,
+            delay_ms,
+        } => {
+            shift(&mut repaired, activity_id, (*delay_ms).max(0));
+            moved.insert(activity_id.clone());
+        }
+        Disruption::Overrun {
+            activity_id,
+            extra_ms,
+        } => {
+            let end = repaired
+                .assignments
+                .iter()
+                .filter(|a| &a.activity_id == activity_id)
+                .map(|a| a.end_ms)
+                .max();
+            for a in repaired
+                .assignments
+                .iter_mut()
+                .filter(|a| &a.activity_id == activity_id && Some(a.end_ms) == end)
+            {
+                a.end_ms += (*extra_ms).max(0);
+            }
+            moved.insert(activity_id.clone());
+        }
+    }
+    right_shift(request, &mut repaired, &sequence, moved, None, &blocked);
+    repaired.violations = check_schedule(&repaired, &request.tasks, &request.resources);
+
+    let changes = ActivityChange::between(schedule, &repaired);
+    let shifts: Vec<i64> = changes
+        .iter()
+        .filter_map(|c| Some(c.after.as_ref()?.start_ms - c.before.as_ref()?.start_ms))
+        .filter(|&d| d > 0)
+        .collect();
+    let stability = StabilityDelta {
+        moved_activities: shifts.len(),
+        total_shift_ms: shifts.iter().sum(),
+        max_shift_ms: shifts.iter().copied().max().unwrap_or(0),
+        makespan_delta_ms: repaired.makespan_ms() - schedule.makespan_ms(),
+        changes,
+    };
+    RepairOutcome {
+        schedule: repaired,
+        stability,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, Resource, ResourceRequirement, Task, ViolationType};
+    use crate::scheduler::SimpleScheduler;
+
+    fn on(id: &str) -> ResourceRequirement {
+        ResourceRequirement::new("Machine").with_candidates(vec![id.into()])
+    }
+
+    /// J1: O1 (M1) → O2 (M2); J2: O3 (M2); J3: O4 (M3). Greedy: O1
+    /// [0,1000), O2 [1000,2000), O3 [2000,3000), O4 [0,1000).
+    fn request() -> ScheduleRequest {
+        ScheduleRequest::new(
+            vec![
+                Task::new("J1")
+                    .with_activity(
+                        Activity::new("O1", "J1", 0)
+                            .with_process_time(1000)
+                            .with_requirement(on("M1")),
+                    )
+                    .with_activity(
+                        Activity::new("O2", "J1", 1)
+                            .with_process_time(1000)
+                            .with_requirement(on("M2")),
+                    ),
+                Task::new("J2")
+                    .with_priority(-1)
+                    .with_deadline(3200)
+                    .with_activity(
+                        Activity::new("O3", "J2", 0)
+                            .with_process_time(1000)
+                            .with_requirement(on("M2")),
+                    ),
+                Task::new("J3").with_activity(
+                    Activity::new("O4", "J3", 0)
+                        .with_process_time(1000)
+                        .with_requirement(on("M3")),
+                ),
+            ],
+            vec![
+                Resource::primary("M1"),
+                Resource::primary("M2"),
+                Resource::primary("M3"),
+            ],
+        )
+    }
+
+    fn start(schedule: &Schedule, id: &str) -> i64 {
+        schedule.assignment_for_activity(id).unwrap().start_ms
+    }
+
+    #[test]
+    fn test_delay_and_overrun_push_dependents() {
+        let request = request();
+        let plan = SimpleScheduler::new().schedule_request(&request);
+        assert_eq!((start(&plan, "O2"), start(&plan, "O3")), (1000, 2000));
+
+        // O1 starts 300 late: O2 follows, O3 behind it on M2; O4 untouched.
+        let delay = Disruption::Delay {
+            activity_id: "O1".into(),
+            delay_ms: 300,
+        };
+        let outcome = right_shift_repair(&request, &plan, &delay);
+        let s = &outcome.schedule;
+        assert_eq!(
+            (
+                start(s, "O1"),
+                start(s, "O2"),
+                start(s, "O3"),
+                start(s, "O4")
+            ),
+            (300, 1300, 2300, 0)
+        );
+        assert_eq!(outcome.stability.moved_activities, 3);
+        assert_eq!(outcome.stability.total_shift_ms, 900);
+        assert_eq!(outcome.stability.max_shift_ms, 300);
+        assert_eq!(outcome.stability.changes.len(), 3);
+        // J2 now ends at 3300, past its deadline.
+        assert_eq!(s.violations.len(), 1);
+        assert_eq!(s.violations[0].violation_type, ViolationType::DeadlineMiss);
+
+        // O2 overruns by 500: only O3 moves.
+        let overrun = Disruption::Overrun {
+            activity_id: "O2".into(),
+            extra_ms: 500,
+        };
+        let outcome = right_shift_repair(&request, &plan, &overrun);
+        let o2 = outcome.schedule.assignment_for_activity("O2").unwrap();
+        assert_eq!((o2.start_ms, o2.end_ms), (1000, 2500));
+        assert_eq!(start(&outcome.schedule, "O3"), 2500);
+        assert_eq!(outcome.stability.moved_activities, 1);
+        assert_eq!(outcome.stability.makespan_delta_ms, 500);
+    }
+
+    #[test]
+    fn test_breakdown_keeps_order_of_work() {
+        let request = request();
+        let plan = SimpleScheduler::new().schedule_request(&request);
+
+        // M2 down during [1500, 2200): O2 (running) restarts at 2200 and
+        // O3 stays behind it.
+        let breakdown = Disruption::Breakdown {
+            resource_id: "M2".into(),
+            start_ms: 1500,
+            end_ms: 2200,
+        };
+        let outcome = right_shift_repair(&request, &plan, &breakdown);
+        let s = &outcome.schedule;
+        assert_eq!((start(s, "O2"), start(s, "O3")), (2200, 3200));
+        assert_eq!((start(s, "O1"), start(s, "O4")), (0, 0));
+        assert_eq!(outcome.stability.max_shift_ms, 1200);
+        assert!(s
+            .assignments
+            .iter()
+            .filter(|a| a.resource_id == "M2")
+            .all(|a| a.end_ms <= 1500 || a.start_ms >= 2200));
+
+        // An outage on an idle stretch, or an unknown activity, moves nothing.
+        let idle = Disruption::Breakdown {
+            resource_id: "M3".into(),
+            start_ms: 1000,
+            end_ms: 5000,
+        };
+        let unknown = Disruption::Delay {
+            activity_id: "O9".into(),
+            delay_ms: 100,
+        };
+        for disruption in [idle, unknown] {
+            let outcome = right_shift_repair(&request, &plan, &disruption);
+            assert_eq!(outcome.stability, StabilityDelta::default());
+            assert_eq!(outcome.schedule.fingerprint(), plan.fingerprint());
+        }
+    }
+}
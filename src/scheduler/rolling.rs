@@ -0,0 +1,466 @@
+//! Rolling-horizon (rolling wave) planning.
+//!
+//! Splits a long-horizon problem into overlapping time windows and
+//! schedules them one after another, carrying the resource state across
+//! window boundaries.
+//!
+//! # Inner Schedulers
+//!
+//! | Method | Per window | Carried over |
+//! |--------|------------|--------------|
+//! | [`plan`](RollingHorizon::plan) | [`SimpleScheduler`] | Availability, last category (setups), busy time (working-time rules) |
+//! | [`plan_with`](RollingHorizon::plan_with) | Any solver of a [`ScheduleRequest`] | Committed assignments, as [resource window locks](crate::models::Lock::ResourceWindow) |
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::simple::ResourceState;
+use super::{ScheduleRequest, SimpleScheduler};
+use crate::models::{Lock, Schedule, Task};
+
+/// Outcome of one planning window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowReport {
+    /// Window start (ms).
+    pub start_ms: i64,
+    /// Window end (ms).
+    pub end_ms: i64,
+    /// Tasks planned in this window.
+    pub planned_tasks: usize,
+    /// Tasks whose plan was committed.
+    pub committed_tasks: usize,
+}
+
+/// Plans a request window by window with [`SimpleScheduler`].
+///
+/// # Algorithm
+/// A task belongs to the first window that contains its anchor: the
+/// deadline, else the release time, else the request start. For each
+/// window `[s, s + window)`:
+/// 1. Schedule all uncommitted tasks anchored before the window end,
+///    continuing from the committed resource state (availability, last
+///    category for setups, busy time for working-time rules).
+/// 2. Commit tasks that start before `s + window − overlap`; the rest are
+///    replanned by the next window, which starts there. Tasks are
+///    committed whole, so in-flight tasks never straddle two plans, and
+///    only while their work on every resource precedes all uncommitted
+///    work, so committed setups and starts never depend on work that is
+///    replanned.
+/// 3. The last window commits everything.
+///
+/// The overlap lets work near a boundary see the orders of the next
+/// window before it is fixed.
+///
+/// # Reference
+/// Sethi & Sorger (1991), "A theory of rolling horizon decision making"
+#[derive(Debug, Clone)]
+pub struct RollingHorizon {
+    window_ms: i64,
+    overlap_ms: i64,
+    scheduler: SimpleScheduler,
+}
+
+impl RollingHorizon {
+    /// Creates a planner with the given window length and overlap.
+    ///
+    /// The overlap is clamped to `[0, window_ms)`.
+    pub fn new(window_ms: i64, overlap_ms: i64) -> Self {
+        let window_ms = window_ms.max(1);
+        Self {
+            window_ms,
+            overlap_ms: overlap_ms.clamp(0, window_ms - 1),
+            scheduler: SimpleScheduler::new(),
+        }
+    }
+
+    /// Sets the scheduler used per window (e.g., with a rule engine).
+    ///
+    /// Transition matrices, affinity, locks and tooling are taken from the request.
+    pub fn with_scheduler(mut self, scheduler: SimpleScheduler) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Plans `request` and returns the concatenated schedule.
+    pub fn plan(&self, request: &ScheduleRequest) -> (Schedule, Vec<WindowReport>) {
+        let scheduler = self
+            .scheduler
+            .clone()
+            .with_transition_matrices(request.transition_matrices.clone())
+            .with_affinity(request.affinity.clone())
+            .with_locks(request.locks.clone())
+            .with_tooling(request.tooling.clone());
+        let (resources, start) = (&request.resources, request.start_time_ms);
+        let categories: HashMap<&str, &str> = request
+            .tasks
+            .iter()
+            .map(|t| (t.id.as_str(), t.category.as_str()))
+            .collect();
+        let mut state = ResourceState::new(resources, start);
+        let mut booked = 0;
+        self.roll(request, |window_tasks, committed| {
+            // Book the work committed since the last window, in start order
+            for a in &committed.assignments[booked..] {
+                let Some(&category) = categories.get(a.task_id.as_str()) else {
+                    continue;
+                };
+                // Setup-only assignments (setup crews) keep their resource's setup state.
+                let setup_only = a.setup_ms > 0 && a.process_ms() == 0;
+                state.book(a, (!setup_only).then_some(category));
+            }
+            booked = committed.assignments.len();
+            let order = scheduler.sort_tasks(window_tasks, start);
+            scheduler.schedule_with_state(
+                window_tasks,
+                resources,
+                start,
+                &order,
+                &mut state.clone(),
+            )
+        })
+    }
+
+    /// Plans `request` with any scheduler and returns the concatenated
+    /// schedule.
+    ///
+    /// `solve` is called once per window with the request narrowed to the
+    /// window's tasks (e.g. a [`SolverSpec`](crate::bundle::SolverSpec));
+    /// the time of every committed assignment is added to its locks as a
+    /// reserved resource window, so the solver must honor locks. Setup
+    /// and working-time state is not carried over.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use u_schedule::bundle::SolverSpec;
+    /// use u_schedule::models::{Activity, Resource, ResourceRequirement, Task};
+    /// use u_schedule::scheduler::{RollingHorizon, ScheduleRequest};
+    ///
+    /// let tasks = (1..=4)
+    ///     .map(|i| {
+    ///         let id = format!("J{i}");
+    ///         Task::new(&id).with_deadline(1000 * i).with_activity(
+    ///             Activity::new(format!("{id}_O1"), &id, 0)
+    ///                 .with_process_time(1000)
+    ///                 .with_requirement(ResourceRequirement::new("M").with_candidates(vec!["M1".into()])),
+    ///         )
+    ///     })
+    ///     .collect();
+    /// let request = ScheduleRequest::new(tasks, vec![Resource::primary("M1")]);
+    /// let solver = SolverSpec::greedy();
+    /// let (plan, windows) =
+    ///     RollingHorizon::new(2000, 0).plan_with(&request, |window| solver.solve(window).unwrap());
+    /// assert_eq!(windows.len(), 3);
+    /// assert_eq!(plan.makespan_ms(), 4000);
+    /// ```
+    pub fn plan_with(
+        &self,
+        request: &ScheduleRequest,
+        mut solve: impl FnMut(&ScheduleRequest) -> Schedule,
+    ) -> (Schedule, Vec<WindowReport>) {
+        self.roll(request, |window_tasks, committed| {
+            let mut window = request.clone();
+            window.tasks = window_tasks.to_vec();
+            for a in committed
+                .assignments
+                .iter()
+                .filter(|a| a.end_ms > a.start_ms)
+            {
+                window.locks.locks.push(Lock::resource_window(
+                    &a.resource_id,
+                    a.start_ms,
+                    a.end_ms,
+                ));
+            }
+            solve(&window)
+        })
+    }
+
+    /// The window loop: `plan_window` schedules a window's tasks given
+    /// the schedule committed so far.
+    fn roll(
+        &self,
+        request: &ScheduleRequest,
+        mut plan_window: impl FnMut(&[Task], &Schedule) -> Schedule,
+    ) -> (Schedule, Vec<WindowReport>) {
+        let (tasks, start) = (&request.tasks, request.start_time_ms);
+        let step = self.window_ms - self.overlap_ms;
+        let anchor = |t: &Task| t.deadline.or(t.release_time).unwrap_or(start);
+
+        let mut committed = vec![false; tasks.len()];
+        let mut result = Schedule::new();
+        let mut reports = Vec::new();
+        let mut window_start = start;
+
+        loop {
+            let pending: Vec<usize> = (0..tasks.len()).filter(|&i| !committed[i]).collect();
+            let Some(first_anchor) = pending.iter().map(|&i| anchor(&tasks[i])).min() else {
+                break;
+            };
+            // Skip windows without work.
+            window_start = window_start.max(first_anchor - self.window_ms + 1);
+            let window_end = window_start + self.window_ms;
+            let boundary = window_start + step;
+
+            let selected: Vec<usize> = pending
+                .iter()
+                .copied()
+                .filter(|&i| anchor(&tasks[i]) < window_end)
+                .collect();
+            let last = selected.len() == pending.len();
+
+            let window_tasks: Vec<Task> = selected.iter().map(|&i| tasks[i].clone()).collect();
+            let plan = plan_window(&window_tasks, &result);
+
+            // Committed task IDs
+            let mut commit: HashSet<&str> = HashSet::new();
+            for task in &window_tasks {
+                let first_start = plan
+                    .assignments
+                    .iter()
+                    .filter(|a| a.task_id == task.id)
+                    .map(|a| a.start_ms)
+                    .min();
+                if last || first_start.is_none_or(|s| s < boundary) {
+                    commit.insert(task.id.as_str());
+                }
+            }
+            // Commit only a prefix of each resource's sequence: drop tasks
+            // with work after uncommitted work until none is left
+            loop {
+                let mut first_open: HashMap<&str, i64> = HashMap::new();
+                for a in plan
+                    .assignments
+                    .iter()
+                    .filter(|a| !commit.contains(a.task_id.as_str()))
+                {
+                    let start = first_open.entry(&a.resource_id).or_insert(a.start_ms);
+                    *start = (*start).min(a.start_ms);
+                }
+                let behind: Vec<&str> = plan
+                    .assignments
+                    .iter()
+                    .filter(|a| {
+                        commit.contains(a.task_id.as_str())
+                            && first_open
+                                .get(a.resource_id.as_str())
+                                .is_some_and(|&open| a.start_ms > open)
+                    })
+                    .map(|a| a.task_id.as_str())
+                    .collect();
+                if behind.is_empty() {
+                    break;
+                }
+                for id in behind {
+                    commit.remove(id);
+                }
+            }
+            for (local, &i) in selected.iter().enumerate() {
+                committed[i] |= commit.contains(window_tasks[local].id.as_str());
+            }
+
+            let mut booked: Vec<_> = plan
+                .assignments
+                .iter()
+                .filter(|a| commit.contains(a.task_id.as_str()))
+                .collect();
+            booked.sort_by_key(|a| a.start_ms);
+            for a in booked {
+                result.add_assignment(a.clone());
+            }
+
+            reports.push(WindowReport {
+                start_ms: window_start,
+                end_ms: window_end,
+                planned_tasks: selected.len(),
+                committed_tasks: commit.len(),
+            });
+            window_start = boundary;
+        }
+
+        (result, reports)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        Activity, ActivityDuration, Resource, ResourceRequirement, TransitionMatrix,
+        TransitionMatrixCollection,
+    };
+    use crate::scheduler::ScheduleKpi;
+
+    /// T0..T3 on M1, 1000 ms each, due 1000..4000, later = higher priority.
+    fn request() -> ScheduleRequest {
+        let tasks = (0..4)
+            .map(|i| {
+                let id = format!("T{i}");
+                Task::new(&id)
+                    .with_priority(i)
+                    .with_category(if i % 2 == 0 { "A" } else { "B" })
+                    .with_deadline(1000 * (i as i64 + 1))
+                    .with_activity(
+                        Activity::new(format!("{id}_O1"), &id, 0)
+                            .with_duration(ActivityDuration::fixed(1000))
+                            .with_requirement(
+                                ResourceRequirement::new("Machine")
+                                    .with_candidates(vec!["M1".into()]),
+                            ),
+                    )
+            })
+            .collect();
+        ScheduleRequest::new(tasks, vec![Resource::primary("M1")])
+    }
+
+    #[test]
+    fn test_windows_follow_due_dates() {
+        let request = request();
+        let monolithic = SimpleScheduler::new().schedule_request(&request);
+        assert!(ScheduleKpi::calculate(&monolithic, &request.tasks).total_tardiness_ms > 0);
+
+        let (schedule, reports) = RollingHorizon::new(2000, 1000).plan(&request);
+        assert_eq!(schedule.assignment_count(), 4);
+        assert_eq!(
+            ScheduleKpi::calculate(&schedule, &request.tasks).total_tardiness_ms,
+            0
+        );
+        let planned: Vec<usize> = reports.iter().map(|r| r.planned_tasks).collect();
+        assert_eq!(planned, vec![1, 1, 1, 1]);
+        assert_eq!((reports[1].start_ms, reports[1].end_ms), (1000, 3000));
+    }
+
+    #[test]
+    fn test_overlap_defers_late_starts() {
+        // Window [0, 3500) plans T0–T2 by priority (T2, T1, T0); T0 would
+        // start at the 2000 boundary, so it is replanned with T3.
+        let (schedule, reports) = RollingHorizon::new(3500, 1500).plan(&request());
+        assert_eq!(reports.len(), 2);
+        assert_eq!(
+            (reports[0].planned_tasks, reports[0].committed_tasks),
+            (3, 2)
+        );
+        assert_eq!(
+            (reports[1].planned_tasks, reports[1].committed_tasks),
+            (2, 2)
+        );
+        assert_eq!(
+            schedule.assignment_for_activity("T0_O1").unwrap().start_ms,
+            3000
+        );
+        let mut intervals: Vec<(i64, i64)> = schedule
+            .assignments
+            .iter()
+            .map(|a| (a.start_ms, a.end_ms))
+            .collect();
+        intervals.sort_unstable();
+        assert!(intervals.windows(2).all(|w| w[0].1 <= w[1].0));
+    }
+
+    #[test]
+    fn test_plan_with_any_scheduler() {
+        use crate::bundle::SolverSpec;
+        use crate::ga::SchedulingGaOptions;
+
+        let request = request();
+        let solver = SolverSpec::Ga {
+            options: SchedulingGaOptions {
+                seed: Some(3),
+                ..SchedulingGaOptions::default()
+            },
+            population_size: Some(10),
+            generations: Some(5),
+            time_limit_ms: None,
+            generations_run: None,
+        };
+        let mut windows = Vec::new();
+        let (schedule, reports) = RollingHorizon::new(2000, 1000).plan_with(&request, |window| {
+            windows.push(window.tasks.len());
+            solver.solve(window).unwrap()
+        });
+        assert_eq!(windows, vec![1, 1, 1, 1]);
+        assert_eq!(reports.len(), 4);
+        // Committed work is reserved, so later windows queue behind it.
+        assert_eq!(schedule.assignment_count(), 4);
+        assert_eq!(
+            ScheduleKpi::calculate(&schedule, &request.tasks).total_tardiness_ms,
+            0
+        );
+        let mut intervals: Vec<(i64, i64)> = schedule
+            .assignments
+            .iter()
+            .map(|a| (a.start_ms, a.end_ms))
+            .collect();
+        intervals.sort_unstable();
+        assert_eq!(
+            intervals,
+            vec![(0, 1000), (1000, 2000), (2000, 3000), (3000, 4000)]
+        );
+    }
+
+    #[test]
+    fn test_setup_state_carries_over() {
+        let matrices = TransitionMatrixCollection::new()
+            .with_matrix(TransitionMatrix::new("m", "M1").with_default(100));
+        let request = request().with_transition_matrices(matrices);
+        let (schedule, _) = RollingHorizon::new(2000, 1000).plan(&request);
+        let setups: Vec<i64> = ["T0_O1", "T1_O1", "T2_O1", "T3_O1"]
+            .iter()
+            .map(|id| schedule.assignment_for_activity(id).unwrap().setup_ms)
+            .collect();
+        assert_eq!(setups, vec![0, 100, 100, 100]);
+    }
+
+    #[test]
+    fn test_committed_setups_follow_committed_work() {
+        let op = |id: &str, task: &str, seq: i32, machine: &str| {
+            Activity::new(id, task, seq)
+                .with_duration(ActivityDuration::fixed(1000))
+                .with_requirement(
+                    ResourceRequirement::new("Machine").with_candidates(vec![machine.into()]),
+                )
+        };
+        // X starts on M1 before the first boundary (1500), but its M0 step
+        // is planned behind Y (category B), which starts after it.
+        let tasks = vec![
+            Task::new("X")
+                .with_category("A")
+                .with_deadline(2800)
+                .with_activity(op("X_O1", "X", 0, "M1"))
+                .with_activity(op("X_O2", "X", 1, "M0")),
+            Task::new("Y")
+                .with_priority(10)
+                .with_category("B")
+                .with_release_time(2000)
+                .with_deadline(2900)
+                .with_activity(op("Y_O1", "Y", 0, "M0")),
+            Task::new("Z")
+                .with_category("A")
+                .with_deadline(10_000)
+                .with_activity(op("Z_O1", "Z", 0, "M1")),
+        ];
+        let matrices = TransitionMatrixCollection::new()
+            .with_matrix(TransitionMatrix::new("m0", "M0").with_default(500));
+        let request = ScheduleRequest::new(
+            tasks,
+            vec![Resource::primary("M0"), Resource::primary("M1")],
+        )
+        .with_transition_matrices(matrices.clone());
+
+        let (schedule, reports) = RollingHorizon::new(3000, 1500).plan(&request);
+        assert_eq!(reports[0].committed_tasks, 0);
+        assert_eq!(schedule.assignment_count(), 4);
+        let category = |task: &str| if task == "Y" { "B" } else { "A" };
+        let mut on_m0 = schedule.assignments_for_resource("M0");
+        on_m0.sort_by_key(|a| a.start_ms);
+        let mut previous: Option<&str> = None;
+        for a in on_m0 {
+            let required = previous.map_or(0, |p| {
+                matrices.get_transition_time("M0", p, category(&a.task_id))
+            });
+            assert_eq!(a.setup_ms, required, "{}", a.activity_id);
+            previous = Some(category(&a.task_id));
+        }
+    }
+}
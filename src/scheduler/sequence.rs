@@ -0,0 +1,290 @@
+//! Per-resource job queues for machine and cell controllers.
+//!
+//! Controllers on the shop floor do not need the whole plan, only the
+//! ordered list of jobs for their resource. [`SequenceExport`] cuts a
+//! schedule into one [`ResourceQueue`] per resource, with the planned
+//! start, setup and category of each entry, in JSON or CSV.
+//!
+//! # Integrity
+//!
+//! Each queue carries a signature: an FNV-1a hash of its resource ID and
+//! entries, as 16 hex digits. A controller recomputes it with
+//! [`ResourceQueue::verify`] (or any FNV-1a implementation over the same
+//! fields) to detect truncated or edited queues. The export as a whole
+//! carries the [`Schedule::fingerprint`] of its source plan.
+//!
+//! # CSV
+//!
+//! One row per entry. A leading `#` line holds the generation metadata:
+//!
+//! ```text
+//! # generator=u-schedule 0.1.0;generated_at_ms=0;schedule_fingerprint=...
+//! resource_id,position,activity_id,task_id,category,start_ms,setup_ms,end_ms,signature
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::cost::csv_field;
+use crate::models::{Assignment, Fnv1a, Schedule, Task};
+
+/// A job in a resource's queue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueEntry {
+    /// Position in the queue (1-based).
+    pub position: usize,
+    /// Activity ID.
+    pub activity_id: String,
+    /// Task ID.
+    pub task_id: String,
+    /// Task category (empty if the task is unknown).
+    pub category: String,
+    /// Planned start, setup included (ms).
+    pub start_ms: i64,
+    /// Setup at the start of the entry (ms).
+    pub setup_ms: i64,
+    /// Planned end (ms).
+    pub end_ms: i64,
+}
+
+/// The ordered jobs of one resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceQueue {
+    /// Resource ID.
+    pub resource_id: String,
+    /// FNV-1a hash of the resource ID and entries (16 hex digits).
+    pub signature: String,
+    /// Jobs in start order.
+    pub entries: Vec<QueueEntry>,
+}
+
+impl ResourceQueue {
+    fn new(resource_id: &str, entries: Vec<QueueEntry>) -> Self {
+        Self {
+            resource_id: resource_id.to_string(),
+            signature: signature(resource_id, &entries),
+            entries,
+        }
+    }
+
+    /// Whether the signature matches the resource ID and entries.
+    pub fn verify(&self) -> bool {
+        self.signature == signature(&self.resource_id, &self.entries)
+    }
+}
+
+/// Queue signature over the resource ID and every entry field.
+fn signature(resource_id: &str, entries: &[QueueEntry]) -> String {
+    let mut hasher = Fnv1a::new();
+    hasher.write_str(resource_id);
+    hasher.write_u64(entries.len() as u64);
+    for e in entries {
+        hasher.write_u64(e.position as u64);
+        hasher.write_str(&e.activity_id);
+        hasher.write_str(&e.task_id);
+        hasher.write_str(&e.category);
+        hasher.write_u64(e.start_ms as u64);
+        hasher.write_u64(e.setup_ms as u64);
+        hasher.write_u64(e.end_ms as u64);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// Per-resource job queues of a schedule, with generation metadata.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Assignment, Schedule, Task};
+/// use u_schedule::scheduler::SequenceExport;
+///
+/// let mut plan = Schedule::new();
+/// plan.add_assignment(Assignment::new("J2_O1", "J2", "M1", 1000, 2500).with_setup(500));
+/// plan.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+/// let tasks = [Task::new("J1").with_category("A"), Task::new("J2").with_category("B")];
+///
+/// let export = SequenceExport::new(&plan, &tasks, 1_700_000_000_000);
+/// let m1 = export.queue("M1").unwrap();
+/// assert_eq!(m1.entries[0].activity_id, "J1_O1");
+/// assert_eq!((m1.entries[1].category.as_str(), m1.entries[1].setup_ms), ("B", 500));
+/// assert!(m1.verify());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SequenceExport {
+    /// Producing software and version.
+    pub generator: String,
+    /// Generation time (ms, caller's clock).
+    pub generated_at_ms: i64,
+    /// [`Schedule::fingerprint`] of the source plan (16 hex digits).
+    pub schedule_fingerprint: String,
+    /// Queues by resource ID (ascending).
+    pub queues: Vec<ResourceQueue>,
+}
+
+impl SequenceExport {
+    /// Cuts `schedule` into per-resource queues.
+    ///
+    /// Categories come from `tasks`. Entries are ordered by start, then
+    /// end and activity ID; split activities appear once per segment.
+    pub fn new(schedule: &Schedule, tasks: &[Task], generated_at_ms: i64) -> Self {
+        let categories: HashMap<&str, &str> = tasks
+            .iter()
+            .map(|t| (t.id.as_str(), t.category.as_str()))
+            .collect();
+        let mut by_resource: BTreeMap<&str, Vec<&Assignment>> = BTreeMap::new();
+        for a in &schedule.assignments {
+            by_resource
+                .entry(a.resource_id.as_str())
+                .or_default()
+                .push(a);
+        }
+
+        let queues = by_resource
+            .into_iter()
+            .map(|(resource_id, mut assignments)| {
+                assignments.sort_by(|a, b| {
+                    (a.start_ms, a.end_ms, &a.activity_id).cmp(&(
+                        b.start_ms,
+                        b.end_ms,
+                        &b.activity_id,
+                    ))
+                });
+                let entries = assignments
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, a)| QueueEntry {
+                        position: i + 1,
+                        activity_id: a.activity_id.clone(),
+                        task_id: a.task_id.clone(),
+                        category: categories
+                            .get(a.task_id.as_str())
+                            .unwrap_or(&"")
+                            .to_string(),
+                        start_ms: a.start_ms,
+                        setup_ms: a.setup_ms,
+                        end_ms: a.end_ms,
+                    })
+                    .collect();
+                ResourceQueue::new(resource_id, entries)
+            })
+            .collect();
+
+        Self {
+            generator: concat!("u-schedule ", env!("CARGO_PKG_VERSION")).to_string(),
+            generated_at_ms,
+            schedule_fingerprint: format!("{:016x}", schedule.fingerprint()),
+            queues,
+        }
+    }
+
+    /// The queue of a resource.
+    pub fn queue(&self, resource_id: &str) -> Option<&ResourceQueue> {
+        self.queues.iter().find(|q| q.resource_id == resource_id)
+    }
+
+    /// Serializes the export to compact JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("export contains only serializable data")
+    }
+
+    /// The export as CSV: a `#` metadata line, a header row, and one row
+    /// per entry.
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!(
+            "# generator={};generated_at_ms={};schedule_fingerprint={}\n\
+             resource_id,position,activity_id,task_id,category,start_ms,setup_ms,end_ms,signature\n",
+            self.generator, self.generated_at_ms, self.schedule_fingerprint
+        );
+        for q in &self.queues {
+            for e in &q.entries {
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{},{},{},{}\n",
+                    csv_field(&q.resource_id),
+                    e.position,
+                    csv_field(&e.activity_id),
+                    csv_field(&e.task_id),
+                    csv_field(&e.category),
+                    e.start_ms,
+                    e.setup_ms,
+                    e.end_ms,
+                    q.signature,
+                ));
+            }
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan() -> Schedule {
+        let mut s = Schedule::new();
+        s.add_assignment(Assignment::new("J1_O2", "J1", "M2", 1000, 3000).with_setup(200));
+        s.add_assignment(Assignment::new("J2_O1", "J2", "M1", 1000, 2500));
+        s.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+        s
+    }
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            Task::new("J1").with_category("steel, cold"),
+            Task::new("J2").with_category("alu"),
+        ]
+    }
+
+    #[test]
+    fn test_queues_and_signatures() {
+        let export = SequenceExport::new(&plan(), &tasks(), 42);
+        assert_eq!(export.generated_at_ms, 42);
+        assert!(export.generator.starts_with("u-schedule "));
+        assert_eq!(
+            export.schedule_fingerprint,
+            format!("{:016x}", plan().fingerprint())
+        );
+        let ids: Vec<&str> = export
+            .queues
+            .iter()
+            .map(|q| q.resource_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["M1", "M2"]);
+
+        let m1 = export.queue("M1").unwrap();
+        let order: Vec<(usize, &str)> = m1
+            .entries
+            .iter()
+            .map(|e| (e.position, e.activity_id.as_str()))
+            .collect();
+        assert_eq!(order, vec![(1, "J1_O1"), (2, "J2_O1")]);
+        assert!(export.queues.iter().all(ResourceQueue::verify));
+        assert_ne!(m1.signature, export.queue("M2").unwrap().signature);
+
+        // Any edit breaks the signature.
+        let mut edited = m1.clone();
+        edited.entries[1].start_ms += 1;
+        assert!(!edited.verify());
+
+        let parsed: SequenceExport = serde_json::from_str(&export.to_json()).unwrap();
+        assert_eq!(parsed, export);
+    }
+
+    #[test]
+    fn test_csv() {
+        let export = SequenceExport::new(&plan(), &tasks(), 42);
+        let csv = export.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("# generator=u-schedule "));
+        assert!(lines[0].ends_with(&format!(
+            "generated_at_ms=42;schedule_fingerprint={}",
+            export.schedule_fingerprint
+        )));
+        assert_eq!(lines.len(), 5);
+        let signature = &export.queue("M2").unwrap().signature;
+        assert_eq!(
+            lines[4],
+            format!("M2,1,J1_O2,J1,\"steel, cold\",1000,200,3000,{signature}")
+        );
+    }
+}
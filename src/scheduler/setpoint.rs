@@ -0,0 +1,381 @@
+//! Setpoint scheduling: matching a target output profile.
+//!
+//! Level-loaded (heijunka) production does not finish work as early as
+//! possible; it completes a steady mix, e.g., "2 of A and 1 of B per
+//! day". An [`OutputTarget`] states how many tasks of each category should
+//! complete per time bucket, [`TargetDeviation`] measures how far a
+//! schedule is from it, and [`SetpointScheduler`] schedules towards it
+//! instead of towards the shortest makespan.
+//!
+//! # Metrics
+//!
+//! | Metric | Definition |
+//! |--------|-----------|
+//! | Shortfall | `Σ max(0, target − actual)` over buckets and categories |
+//! | Surplus | `Σ max(0, actual − target)` over buckets and categories |
+//! | Total Deviation | Shortfall + surplus |
+//!
+//! Buckets are aligned to `origin_ms`; a task counts in the bucket that
+//! contains its completion (a completion on a bucket boundary counts in
+//! the earlier bucket).
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::{ScheduleRequest, SimpleScheduler};
+use crate::models::units::DAY_MS;
+use crate::models::{Schedule, Task};
+
+/// Target completions per category and time bucket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputTarget {
+    /// Bucket length (ms).
+    pub bucket_ms: i64,
+    /// Start of the first bucket (ms).
+    #[serde(default)]
+    pub origin_ms: i64,
+    /// Target completions per bucket (index 0 = first bucket), by category.
+    pub targets: BTreeMap<String, Vec<usize>>,
+}
+
+/// Completions of one category in one bucket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BucketOutput {
+    /// Bucket start (ms).
+    pub bucket_start_ms: i64,
+    /// Task category.
+    pub category: String,
+    /// Target completions.
+    pub target: usize,
+    /// Actual completions.
+    pub actual: usize,
+}
+
+/// Deviation of a schedule from an [`OutputTarget`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TargetDeviation {
+    /// Target and actual per category and bucket, by category then time.
+    pub buckets: Vec<BucketOutput>,
+    /// Completions missing from their bucket.
+    pub shortfall: usize,
+    /// Completions beyond their bucket's target.
+    pub surplus: usize,
+    /// Shortfall + surplus.
+    pub total_deviation: usize,
+    /// Largest deviation in any single bucket.
+    pub max_deviation: usize,
+}
+
+impl OutputTarget {
+    /// Creates an empty target with `bucket_ms` buckets from t=0.
+    pub fn new(bucket_ms: i64) -> Self {
+        Self {
+            bucket_ms: bucket_ms.max(1),
+            origin_ms: 0,
+            targets: BTreeMap::new(),
+        }
+    }
+
+    /// Creates an empty target with daily buckets from t=0.
+    pub fn daily() -> Self {
+        Self::new(DAY_MS)
+    }
+
+    /// Sets the start of the first bucket.
+    pub fn with_origin(mut self, origin_ms: i64) -> Self {
+        self.origin_ms = origin_ms;
+        self
+    }
+
+    /// Targets `count` completions of `category` in each of the first
+    /// `buckets` buckets.
+    pub fn with_rate(self, category: impl Into<String>, count: usize, buckets: usize) -> Self {
+        self.with_profile(category, vec![count; buckets])
+    }
+
+    /// Sets the target completions of `category` per bucket.
+    pub fn with_profile(mut self, category: impl Into<String>, counts: Vec<usize>) -> Self {
+        self.targets.insert(category.into(), counts);
+        self
+    }
+
+    /// Target completions of `category` in bucket `index` (0 if none).
+    pub fn target(&self, category: &str, index: usize) -> usize {
+        self.targets
+            .get(category)
+            .and_then(|counts| counts.get(index))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Start of bucket `index` (ms).
+    pub fn bucket_start(&self, index: usize) -> i64 {
+        self.origin_ms + index as i64 * self.bucket_ms
+    }
+
+    /// Bucket containing a completion at `end_ms` (0 for completions at
+    /// or before the origin).
+    pub fn bucket_of(&self, end_ms: i64) -> usize {
+        (end_ms - self.origin_ms - 1)
+            .div_euclid(self.bucket_ms)
+            .max(0) as usize
+    }
+
+    /// Measures how far `schedule` is from the target.
+    ///
+    /// Only categories with a target are counted; tasks without
+    /// assignments count as not completed.
+    pub fn deviation(&self, schedule: &Schedule, tasks: &[Task]) -> TargetDeviation {
+        let mut actual: HashMap<(&str, usize), usize> = HashMap::new();
+        let mut last_bucket: HashMap<&str, usize> = HashMap::new();
+        for task in tasks {
+            if !self.targets.contains_key(&task.category) {
+                continue;
+            }
+            if let Some(end) = schedule.task_completion_time(&task.id) {
+                let bucket = self.bucket_of(end);
+                *actual.entry((task.category.as_str(), bucket)).or_default() += 1;
+                let last = last_bucket.entry(task.category.as_str()).or_default();
+                *last = (*last).max(bucket);
+            }
+        }
+
+        let mut deviation = TargetDeviation::default();
+        for (category, counts) in &self.targets {
+            let buckets = last_bucket
+                .get(category.as_str())
+                .map_or(0, |&b| b + 1)
+                .max(counts.len());
+            for index in 0..buckets {
+                let target = self.target(category, index);
+                let done = actual
+                    .get(&(category.as_str(), index))
+                    .copied()
+                    .unwrap_or(0);
+                deviation.shortfall += target.saturating_sub(done);
+                deviation.surplus += done.saturating_sub(target);
+                deviation.max_deviation = deviation.max_deviation.max(target.abs_diff(done));
+                deviation.buckets.push(BucketOutput {
+                    bucket_start_ms: self.bucket_start(index),
+                    category: category.clone(),
+                    target,
+                    actual: done,
+                });
+            }
+        }
+        deviation.total_deviation = deviation.shortfall + deviation.surplus;
+        deviation
+    }
+}
+
+/// Schedules towards an [`OutputTarget`] rather than the shortest makespan.
+///
+/// # Algorithm
+/// 1. Tasks of each targeted category are taken in the scheduler's
+///    dispatch order and dealt into bucket slots: the first `target[0]`
+///    go to bucket 0, the next `target[1]` to bucket 1, and so on; tasks
+///    beyond the last target go to the bucket after it.
+/// 2. Within a bucket, categories are interleaved by goal chasing: the
+///    `k`-th of `n` tasks of a category is placed at `(k + ½) / n`, so a
+///    2 A : 1 B mix runs A, B, A rather than A, A, B.
+/// 3. Unless holding is disabled, each task is released no earlier than
+///    its bucket start, so work is not finished ahead of its bucket.
+/// 4. The greedy scheduler builds the schedule in this order.
+///
+/// Tasks of categories without a target are dispatched last, unheld.
+///
+/// # Reference
+/// Monden (2011), "Toyota Production System", Ch. 5: Leveling the
+/// production quantity and the model mix
+#[derive(Debug, Clone)]
+pub struct SetpointScheduler {
+    target: OutputTarget,
+    scheduler: SimpleScheduler,
+    hold: bool,
+}
+
+impl SetpointScheduler {
+    /// Creates a scheduler towards `target`.
+    pub fn new(target: OutputTarget) -> Self {
+        Self {
+            target,
+            scheduler: SimpleScheduler::new(),
+            hold: true,
+        }
+    }
+
+    /// Sets the scheduler whose dispatch order ranks tasks within a
+    /// category (e.g., with a rule engine).
+    ///
+    /// Transition matrices, affinity, locks and tooling are taken from the request.
+    pub fn with_scheduler(mut self, scheduler: SimpleScheduler) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Whether tasks are held until their bucket starts (default: true).
+    pub fn with_hold(mut self, hold: bool) -> Self {
+        self.hold = hold;
+        self
+    }
+
+    /// Schedules `request` and measures the result against the target.
+    pub fn schedule(&self, request: &ScheduleRequest) -> (Schedule, TargetDeviation) {
+        let scheduler = self
+            .scheduler
+            .clone()
+            .with_transition_matrices(request.transition_matrices.clone())
+            .with_affinity(request.affinity.clone())
+            .with_locks(request.locks.clone())
+            .with_tooling(request.tooling.clone());
+        let dispatch = scheduler.sort_tasks(&request.tasks, request.start_time_ms);
+        let slots = self.assign_buckets(&request.tasks, &dispatch);
+
+        let mut tasks = request.tasks.clone();
+        if self.hold {
+            for (i, slot) in slots.iter().enumerate() {
+                if let Some((bucket, _)) = slot {
+                    let start = self.target.bucket_start(*bucket);
+                    let release = tasks[i].release_time.unwrap_or(start).max(start);
+                    tasks[i].release_time = Some(release);
+                }
+            }
+        }
+
+        let mut order = dispatch;
+        let rank: HashMap<usize, usize> = order.iter().enumerate().map(|(r, &i)| (i, r)).collect();
+        order.sort_by(|&a, &b| match (slots[a], slots[b]) {
+            (Some((ba, fa)), Some((bb, fb))) => ba.cmp(&bb).then(fa.total_cmp(&fb)),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => rank[&a].cmp(&rank[&b]),
+        });
+
+        let schedule =
+            scheduler.schedule_ordered(&tasks, &request.resources, request.start_time_ms, &order);
+        let deviation = self.target.deviation(&schedule, &request.tasks);
+        (schedule, deviation)
+    }
+
+    /// Bucket and goal-chasing position of each task (`None` = untargeted).
+    fn assign_buckets(&self, tasks: &[Task], dispatch: &[usize]) -> Vec<Option<(usize, f64)>> {
+        // Bucket of each task, dealt per category in dispatch order.
+        let mut dealt: HashMap<&str, usize> = HashMap::new();
+        let mut buckets: Vec<Option<usize>> = vec![None; tasks.len()];
+        for &i in dispatch {
+            let Some(counts) = self.target.targets.get(&tasks[i].category) else {
+                continue;
+            };
+            let k = dealt.entry(tasks[i].category.as_str()).or_default();
+            let mut remaining = *k;
+            let mut bucket = 0;
+            while bucket < counts.len() && remaining >= counts[bucket] {
+                remaining -= counts[bucket];
+                bucket += 1;
+            }
+            buckets[i] = Some(bucket);
+            *k += 1;
+        }
+
+        // Goal-chasing position within each (bucket, category) group.
+        let mut group_size: HashMap<(usize, &str), usize> = HashMap::new();
+        for (i, bucket) in buckets.iter().enumerate() {
+            if let Some(b) = bucket {
+                *group_size
+                    .entry((*b, tasks[i].category.as_str()))
+                    .or_default() += 1;
+            }
+        }
+        let mut seen: HashMap<(usize, &str), usize> = HashMap::new();
+        let mut slots = vec![None; tasks.len()];
+        for &i in dispatch {
+            let Some(b) = buckets[i] else {
+                continue;
+            };
+            let key = (b, tasks[i].category.as_str());
+            let k = seen.entry(key).or_default();
+            slots[i] = Some((b, (*k as f64 + 0.5) / group_size[&key] as f64));
+            *k += 1;
+        }
+        slots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, ActivityDuration, Assignment, Resource, ResourceRequirement};
+
+    const HOUR: i64 = 3_600_000;
+
+    fn make_request(categories: &[&str]) -> ScheduleRequest {
+        let tasks = categories
+            .iter()
+            .enumerate()
+            .map(|(i, cat)| {
+                let id = format!("J{i}");
+                Task::new(&id).with_category(*cat).with_activity(
+                    Activity::new(format!("{id}_O1"), &id, 0)
+                        .with_duration(ActivityDuration::fixed(4 * HOUR))
+                        .with_requirement(
+                            ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                        ),
+                )
+            })
+            .collect();
+        ScheduleRequest::new(tasks, vec![Resource::primary("M1")])
+    }
+
+    #[test]
+    fn test_deviation() {
+        let target = OutputTarget::daily().with_rate("A", 2, 2);
+        let tasks = make_request(&["A", "A", "A", "B"]).tasks;
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("J0_O1", "J0", "M1", 0, 4 * HOUR));
+        schedule.add_assignment(Assignment::new("J1_O1", "J1", "M1", 20 * HOUR, 24 * HOUR));
+        schedule.add_assignment(Assignment::new("J2_O1", "J2", "M1", 24 * HOUR, 28 * HOUR));
+        schedule.add_assignment(Assignment::new("J3_O1", "J3", "M1", 0, 4 * HOUR));
+
+        // Day 0: 2 of 2 (the boundary completion counts); day 1: 1 of 2.
+        let deviation = target.deviation(&schedule, &tasks);
+        assert_eq!(deviation.buckets.len(), 2);
+        assert_eq!(deviation.buckets[0].actual, 2);
+        assert_eq!(deviation.buckets[1].actual, 1);
+        assert_eq!(deviation.buckets[1].bucket_start_ms, 24 * HOUR);
+        assert_eq!(deviation.shortfall, 1);
+        assert_eq!(deviation.surplus, 0);
+        assert_eq!(deviation.total_deviation, 1);
+        assert_eq!(deviation.max_deviation, 1);
+    }
+
+    #[test]
+    fn test_setpoint_schedule() {
+        let request = make_request(&["A", "A", "A", "A", "B", "B"]);
+        let target = OutputTarget::daily()
+            .with_rate("A", 2, 2)
+            .with_rate("B", 1, 2);
+
+        // As fast as possible, all six complete on day 0.
+        let (_, unheld) = SetpointScheduler::new(target.clone())
+            .with_hold(false)
+            .schedule(&request);
+        assert_eq!(unheld.surplus, 3);
+        assert_eq!(unheld.shortfall, 3);
+
+        let (schedule, deviation) = SetpointScheduler::new(target).schedule(&request);
+        assert_eq!(deviation.total_deviation, 0);
+        // Day 1 work waits for day 1.
+        assert_eq!(schedule.makespan_ms(), 36 * HOUR);
+
+        // Mixed A, B, A within each day.
+        let mut day0: Vec<&Assignment> = schedule
+            .assignments
+            .iter()
+            .filter(|a| a.end_ms <= 24 * HOUR)
+            .collect();
+        day0.sort_by_key(|a| a.start_ms);
+        let ids: Vec<&str> = day0.iter().map(|a| a.task_id.as_str()).collect();
+        assert_eq!(ids, vec!["J0", "J4", "J1"]);
+    }
+}
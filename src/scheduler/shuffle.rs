@@ -0,0 +1,397 @@
+//! KPI-preserving schedule randomization.
+//!
+//! To A/B test how operators accept machine-generated plans, the plans
+//! shown to each group must differ in sequence but not in performance.
+//! [`ScheduleShuffler`] derives such variants from a solved schedule by
+//! exchanging neighboring jobs on a resource within the time they
+//! already occupy, so makespan and tardiness stay (near) equal.
+//!
+//! # Method
+//!
+//! A move picks a random resource and two consecutive assignments on it,
+//! `a` then `b`, and runs them in the opposite order inside the span they
+//! cover: `b` starts where `a` started and `a` follows right after it.
+//! Since `a` now ends no later than `b` did, the resource finishes no
+//! later. A move is kept only if
+//! - `b` does not start before its task's release time,
+//! - the sequence-dependent setups of the
+//!   [transition matrices](ScheduleShuffler::with_transition_matrices)
+//!   stay as recorded: none before `b` or between `b` and `a`, and the
+//!   same after `a` as there was after `b`,
+//! - compared with the original schedule, [`check_schedule`] reports no
+//!   more violations other than deadline misses, with no higher penalty
+//!   (precedences, calendars and synchronization still hold),
+//! - the makespan does not grow, and
+//! - total tardiness grows by at most the tolerance (0 by default).
+//!
+//! Only activities with a single, setup-free assignment move: their own
+//! setup would have to move with them, and multi-resource or split work
+//! would need all its parts moved together. Resources with enforced
+//! working-time rules keep their sequence.
+
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::ScheduleKpi;
+use crate::models::{
+    Resource, Schedule, Task, TransitionMatrixCollection, Violation, ViolationType,
+};
+use crate::validation::check_schedule;
+
+/// A schedule variant and the exchanges that produced it.
+#[derive(Debug, Clone)]
+pub struct ShuffledSchedule {
+    /// The variant, with its [`check_schedule`] violations.
+    pub schedule: Schedule,
+    /// Exchanged activity pairs (resource, first, second), in the order
+    /// applied; `first` ran before `second` before the exchange.
+    pub swaps: Vec<(String, String, String)>,
+}
+
+/// Generates alternative sequences of a schedule with equal KPIs.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, Assignment, Resource, Schedule, Task};
+/// use u_schedule::scheduler::ScheduleShuffler;
+///
+/// let job = |id: &str| {
+///     Task::new(id).with_activity(Activity::new(format!("{id}_O1"), id, 0).with_process_time(1000))
+/// };
+/// let tasks = vec![job("J1"), job("J2")];
+/// let mut plan = Schedule::new();
+/// plan.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+/// plan.add_assignment(Assignment::new("J2_O1", "J2", "M1", 1000, 2000));
+///
+/// let resources = [Resource::primary("M1")];
+/// let variant = ScheduleShuffler::new().shuffle(&plan, &tasks, &resources);
+/// assert_eq!(variant.schedule.makespan_ms(), 2000);
+/// assert!(variant.schedule.violations.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScheduleShuffler {
+    moves: usize,
+    seed: u64,
+    tardiness_tolerance_ms: i64,
+    transition_matrices: TransitionMatrixCollection,
+}
+
+impl Default for ScheduleShuffler {
+    fn default() -> Self {
+        Self {
+            moves: 25,
+            seed: 0,
+            tardiness_tolerance_ms: 0,
+            transition_matrices: TransitionMatrixCollection::new(),
+        }
+    }
+}
+
+impl ScheduleShuffler {
+    /// Creates a shuffler attempting 25 moves, with seed 0 and no
+    /// tardiness tolerance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of attempted moves per variant.
+    pub fn with_moves(mut self, moves: usize) -> Self {
+        self.moves = moves;
+        self
+    }
+
+    /// Sets the random seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Accepts variants whose total tardiness exceeds the original's by
+    /// up to `tolerance_ms`.
+    pub fn with_tardiness_tolerance_ms(mut self, tolerance_ms: i64) -> Self {
+        self.tardiness_tolerance_ms = tolerance_ms.max(0);
+        self
+    }
+
+    /// Sets the transition matrices the schedule's setups follow; moves
+    /// that would change a required setup are rejected.
+    pub fn with_transition_matrices(mut self, matrices: TransitionMatrixCollection) -> Self {
+        self.transition_matrices = matrices;
+        self
+    }
+
+    /// `count` variants of `schedule`, from consecutive seeds starting at
+    /// the configured one.
+    pub fn variants(
+        &self,
+        schedule: &Schedule,
+        tasks: &[Task],
+        resources: &[Resource],
+        count: usize,
+    ) -> Vec<ShuffledSchedule> {
+        (0..count as u64)
+            .map(|k| {
+                self.clone()
+                    .with_seed(self.seed.wrapping_add(k))
+                    .shuffle(schedule, tasks, resources)
+            })
+            .collect()
+    }
+
+    /// One variant of `schedule`; `tasks` and `resources` are its inputs.
+    ///
+    /// Confidence estimates refer to planned starts and are dropped from
+    /// the variant.
+    pub fn shuffle(
+        &self,
+        schedule: &Schedule,
+        tasks: &[Task],
+        resources: &[Resource],
+    ) -> ShuffledSchedule {
+        let mut result = schedule.clone();
+        result.confidence.clear();
+        result.violations = check_schedule(&result, tasks, resources);
+        let (baseline_count, baseline_penalty) = audit(&result.violations);
+        let baseline = ScheduleKpi::calculate(schedule, tasks);
+        let max_tardiness = baseline.total_tardiness_ms + self.tardiness_tolerance_ms;
+
+        let task_by_id: HashMap<&str, &Task> = tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+        let mut parts: HashMap<&str, usize> = HashMap::new();
+        for a in &schedule.assignments {
+            *parts.entry(a.activity_id.as_str()).or_default() += 1;
+        }
+        let movable: Vec<bool> = schedule
+            .assignments
+            .iter()
+            .map(|a| a.setup_ms == 0 && parts[a.activity_id.as_str()] == 1)
+            .collect();
+        let mut lanes: Vec<&str> = schedule
+            .assignments
+            .iter()
+            .map(|a| a.resource_id.as_str())
+            .filter(|id| {
+                !resources
+                    .iter()
+                    .any(|r| r.id == *id && r.enforced_work_rules().is_some())
+            })
+            .collect();
+        lanes.sort_unstable();
+        lanes.dedup();
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut swaps = Vec::new();
+        if lanes.is_empty() {
+            return ShuffledSchedule {
+                schedule: result,
+                swaps,
+            };
+        }
+        for _ in 0..self.moves {
+            let lane = lanes[rng.random_range(0..lanes.len())];
+            let mut sequence: Vec<usize> = (0..result.assignments.len())
+                .filter(|&i| result.assignments[i].resource_id == lane)
+                .collect();
+            if sequence.len() < 2 {
+                continue;
+            }
+            sequence.sort_by_key(|&i| result.assignments[i].start_ms);
+            let k = rng.random_range(0..sequence.len() - 1);
+            let (i, j) = (sequence[k], sequence[k + 1]);
+            let (a, b) = (&result.assignments[i], &result.assignments[j]);
+            if !movable[i] || !movable[j] || a.task_id == b.task_id || a.end_ms > b.start_ms {
+                continue;
+            }
+            let release = |id: &str| task_by_id.get(id).and_then(|t| t.release_time);
+            if release(&b.task_id).is_some_and(|release| release > a.start_ms) {
+                continue;
+            }
+            // Setups: none before b or between b and a, and the one after
+            // a as recorded after b
+            let category = |k: usize| {
+                let id = result.assignments[sequence[k]].task_id.as_str();
+                task_by_id.get(id).map_or("", |t| t.category.as_str())
+            };
+            let setup =
+                |from: &str, to: &str| self.transition_matrices.get_transition_time(lane, from, to);
+            let (cat_a, cat_b) = (category(k), category(k + 1));
+            if k.checked_sub(1)
+                .is_some_and(|p| setup(category(p), cat_b) != 0)
+                || setup(cat_b, cat_a) != 0
+                || (k + 2 < sequence.len()
+                    && setup(cat_a, category(k + 2)) != setup(cat_b, category(k + 2)))
+            {
+                continue;
+            }
+
+            let mut candidate = result.clone();
+            let start = a.start_ms;
+            let (first_ms, second_ms) = (a.duration_ms(), b.duration_ms());
+            let second = &mut candidate.assignments[j];
+            second.start_ms = start;
+            second.end_ms = start + second_ms;
+            let first = &mut candidate.assignments[i];
+            first.start_ms = start + second_ms;
+            first.end_ms = start + second_ms + first_ms;
+
+            candidate.violations = check_schedule(&candidate, tasks, resources);
+            let kpi = ScheduleKpi::calculate(&candidate, tasks);
+            let (count, penalty) = audit(&candidate.violations);
+            if count <= baseline_count
+                && penalty <= baseline_penalty
+                && kpi.makespan_ms <= baseline.makespan_ms
+                && kpi.total_tardiness_ms <= max_tardiness
+            {
+                swaps.push((
+                    lane.to_string(),
+                    result.assignments[i].activity_id.clone(),
+                    result.assignments[j].activity_id.clone(),
+                ));
+                result = candidate;
+            }
+        }
+        ShuffledSchedule {
+            schedule: result,
+            swaps,
+        }
+    }
+}
+
+/// Number and weighted penalty of violations other than deadline misses
+/// (tardiness is bounded separately).
+fn audit(violations: &[Violation]) -> (usize, f64) {
+    violations
+        .iter()
+        .filter(|v| v.violation_type != ViolationType::DeadlineMiss)
+        .fold((0, 0.0), |(n, p), v| (n + 1, p + v.weighted_penalty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, Assignment, TransitionMatrix};
+
+    fn machines() -> Vec<Resource> {
+        ["M1", "M2", "M3"].map(Resource::primary).to_vec()
+    }
+
+    fn job(id: &str, ms: i64) -> Task {
+        Task::new(id).with_activity(Activity::new(format!("{id}_O1"), id, 0).with_process_time(ms))
+    }
+
+    fn order(schedule: &Schedule, resource: &str) -> Vec<String> {
+        let mut on: Vec<_> = schedule.assignments_for_resource(resource);
+        on.sort_by_key(|a| a.start_ms);
+        on.iter().map(|a| a.activity_id.clone()).collect()
+    }
+
+    #[test]
+    fn test_variants_keep_kpis() {
+        let tasks = vec![
+            job("J1", 1000),
+            job("J2", 500),
+            job("J3", 1500),
+            job("J4", 700),
+        ];
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+        plan.add_assignment(Assignment::new("J2_O1", "J2", "M1", 1000, 1500));
+        plan.add_assignment(Assignment::new("J3_O1", "J3", "M2", 0, 1500));
+        plan.add_assignment(Assignment::new("J4_O1", "J4", "M2", 1500, 2200));
+
+        let variants = ScheduleShuffler::new().variants(&plan, &tasks, &machines(), 8);
+        assert_eq!(variants.len(), 8);
+        for v in &variants {
+            assert_eq!(v.schedule.makespan_ms(), 2200);
+            assert!(v.schedule.violations.is_empty());
+            assert_eq!(v.schedule.assignments.len(), 4);
+        }
+        let orders: Vec<_> = variants
+            .iter()
+            .map(|v| (order(&v.schedule, "M1"), order(&v.schedule, "M2")))
+            .collect();
+        assert!(orders.iter().any(|o| o != &orders[0]));
+    }
+
+    #[test]
+    fn test_deadlines_and_precedence_pin_the_sequence() {
+        let tasks = vec![
+            job("J1", 1000).with_deadline(1000),
+            job("J2", 1000),
+            Task::new("J3")
+                .with_activity(Activity::new("J3_O1", "J3", 0).with_process_time(1000))
+                .with_activity(Activity::new("J3_O2", "J3", 1).with_process_time(1000)),
+        ];
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+        plan.add_assignment(Assignment::new("J2_O1", "J2", "M1", 1000, 2000));
+        plan.add_assignment(Assignment::new("J3_O1", "J3", "M2", 0, 1000));
+        plan.add_assignment(Assignment::new("J3_O2", "J3", "M3", 1000, 2000));
+
+        // J1 would turn late, and J3's operations are tied by precedence.
+        let strict = ScheduleShuffler::new().shuffle(&plan, &tasks, &machines());
+        assert!(strict.swaps.is_empty());
+        assert_eq!(order(&strict.schedule, "M1"), ["J1_O1", "J2_O1"]);
+
+        // A tardiness allowance frees M1.
+        let loose = ScheduleShuffler::new()
+            .with_tardiness_tolerance_ms(1000)
+            .shuffle(&plan, &tasks, &machines());
+        assert!(!loose.swaps.is_empty());
+        assert!(loose.swaps.iter().all(|(lane, _, _)| lane == "M1"));
+    }
+
+    #[test]
+    fn test_release_times_hold() {
+        let tasks = vec![job("J1", 1000), job("J2", 1000).with_release_time(1000)];
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+        plan.add_assignment(Assignment::new("J2_O1", "J2", "M1", 1000, 2000));
+
+        for seed in 0..8 {
+            let variant =
+                ScheduleShuffler::new()
+                    .with_seed(seed)
+                    .shuffle(&plan, &tasks, &machines());
+            assert!(variant.swaps.is_empty());
+            assert_eq!(order(&variant.schedule, "M1"), ["J1_O1", "J2_O1"]);
+        }
+    }
+
+    #[test]
+    fn test_required_setups_hold() {
+        let job = |id: &str, category: &str| job(id, 1000).with_category(category);
+        // A -> B needs no setup, B -> A does
+        let mut matrix = TransitionMatrix::new("m1", "M1");
+        matrix.set_transition("B", "A", 500);
+        let matrices = TransitionMatrixCollection::new().with_matrix(matrix);
+        let tasks = vec![job("J1", "A"), job("J2", "B"), job("J3", "B")];
+        let mut plan = Schedule::new();
+        plan.add_assignment(Assignment::new("J1_O1", "J1", "M1", 0, 1000));
+        plan.add_assignment(Assignment::new("J2_O1", "J2", "M1", 1000, 2000));
+        plan.add_assignment(Assignment::new("J3_O1", "J3", "M1", 2000, 3000));
+
+        let shuffler = ScheduleShuffler::new().with_transition_matrices(matrices);
+        for seed in 0..8 {
+            let variant = shuffler
+                .clone()
+                .with_seed(seed)
+                .shuffle(&plan, &tasks, &machines());
+            // Only the two B jobs may trade places
+            assert_eq!(order(&variant.schedule, "M1")[0], "J1_O1");
+            assert!(variant.swaps.iter().all(|(_, a, _)| a != "J1_O1"));
+        }
+        // Without the matrix J1 moves freely
+        let free = (0..8).any(|seed| {
+            ScheduleShuffler::new()
+                .with_seed(seed)
+                .shuffle(&plan, &tasks, &machines())
+                .swaps
+                .iter()
+                .any(|(_, a, _)| a == "J1_O1")
+        });
+        assert!(free);
+    }
+}
@@ -3,9 +3,54 @@
 //! # Algorithm
 //!
 //! 1. Sort tasks by dispatching rule (or priority if no rule engine).
-//! 2. For each task, process activities sequentially.
-//! 3. For each activity, select the earliest-available candidate resource.
-//! 4. Apply sequence-dependent setup times from transition matrices.
+//! 2. For each task, process activities sequentially, or in topological
+//!    order if its activities form a [DAG](crate::models::Task::is_dag):
+//!    then each waits only for its predecessors, and independent branches
+//!    may run in parallel on different resources. A parallel
+//!    [`GenerationScheme`] (active or non-delay) instead interleaves
+//!    tasks, placing one activity at a time among the next activities of
+//!    all tasks.
+//! 3. For each activity, select the earliest-available candidate resource
+//!    (delayed as needed by working-time rules of human resources), with
+//!    ties broken by the scheduler's [`TieBreak`] policy; a
+//!    [`SelectionPolicy`] may trade start time for a cheaper resource,
+//!    and a [`MachineSelectionRule`] may rank candidates instead. An
+//!    activity with several requirements (e.g. machine + operator) takes
+//!    a machine from the first and, per requirement, as many distinct
+//!    earliest-free resources as its `quantity` (the machine counts
+//!    toward the first), and starts when all of them are free.
+//! 4. Open each activity with its own setup plus the sequence-dependent
+//!    setup time from transition matrices, and close it with its
+//!    teardown (recorded as the assignment's `teardown_ms`, which
+//!    successors wait for); a setup that needs a setter waits for the
+//!    earliest-available one. A
+//!    category changeover on a resource that has used up its
+//!    [`max_setups_per_window`](crate::models::Resource::max_setups_per_window)
+//!    is deferred to the next calendar window.
+//! 5. Split [`splittable`](crate::models::Activity::splittable)
+//!    activities around the resource's calendar and around booked or
+//!    locked work (e.g. a higher-priority task released later): the work
+//!    fills free time from the task's start as assignment segments of at
+//!    least `min_split_ms`, and resumes after each interruption.
+//!    Other activities (and any on resources with working-time rules)
+//!    run contiguously after the resource's last booking; they are placed
+//!    without regard to calendars.
+//! 6. Stretch processing times by the resource's
+//!    [`LearningCurve`](crate::models::LearningCurve), if any, from the
+//!    start time and the units of the task's category the resource has
+//!    already processed.
+//! 7. Respect skill certifications: a resource takes an activity no
+//!    earlier than its required skills become valid, and not if one
+//!    lapses before the work ends.
+//! 8. Start [synchronized](crate::models::SyncGroups) activities
+//!    together: the pass is repeated with each group's members held back
+//!    to the group's latest start until they align.
+//! 9. On [batching](crate::models::Resource::batching) resources, an
+//!    activity joins the earliest batch it is ready for: one of
+//!    compatible categories with room left, not yet started, and whose
+//!    cycle covers the activity's processing. Members share the batch's
+//!    start, end, setup and teardown. Activities needing further
+//!    resources, a setter or a tool, and split activities, run alone.
 //!
 //! # Complexity
 //! O(n * m * c) where n=tasks, m=activities/task, c=candidate resources.
@@ -13,22 +58,48 @@
 //! # Reference
 //! Pinedo (2016), "Scheduling", Ch. 4: Priority Dispatching
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+
+use super::{MachineCandidate, MachineSelectionRule};
 use crate::dispatching::{RuleEngine, SchedulingContext};
-use crate::models::{Assignment, Resource, Schedule, Task, TransitionMatrixCollection};
+use crate::models::units::HOUR_MS;
+use crate::models::{
+    earliest_fit, intersect_windows, merge_windows, subtract_windows, Activity, AffinityTable,
+    Assignment, LockSet, Resource, ResourceRequirement, Schedule, SyncGroups, Task,
+    ThroughputTargets, TimeWindow, ToolingRules, TransitionMatrixCollection, WorkRules,
+};
+use crate::validation::check_schedule;
 
 /// Input container for scheduling.
-#[derive(Debug, Clone)]
+///
+/// Serializes to the JSON instance format shared by the bindings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduleRequest {
     /// Tasks to schedule.
     pub tasks: Vec<Task>,
     /// Available resources.
     pub resources: Vec<Resource>,
     /// Schedule start time (ms).
+    #[serde(default)]
     pub start_time_ms: i64,
     /// Sequence-dependent setup time matrices.
+    #[serde(default)]
     pub transition_matrices: TransitionMatrixCollection,
+    /// Soft task–resource preferences.
+    #[serde(default)]
+    pub affinity: AffinityTable,
+    /// Pinned decisions the scheduler must keep.
+    #[serde(default)]
+    pub locks: LockSet,
+    /// Tools that categories must mount, with transfer delays.
+    #[serde(default)]
+    pub tooling: ToolingRules,
+    /// Target completions per category within the horizon.
+    #[serde(default)]
+    pub throughput_targets: ThroughputTargets,
 }
 
 impl ScheduleRequest {
@@ -39,6 +110,10 @@ impl ScheduleRequest {
             resources,
             start_time_ms: 0,
             transition_matrices: TransitionMatrixCollection::new(),
+            affinity: AffinityTable::new(),
+            locks: LockSet::new(),
+            tooling: ToolingRules::new(),
+            throughput_targets: ThroughputTargets::default(),
         }
     }
 
@@ -53,6 +128,228 @@ impl ScheduleRequest {
         self.transition_matrices = matrices;
         self
     }
+
+    /// Sets affinity rules.
+    pub fn with_affinity(mut self, affinity: AffinityTable) -> Self {
+        self.affinity = affinity;
+        self
+    }
+
+    /// Sets locks.
+    pub fn with_locks(mut self, locks: LockSet) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Sets tooling rules.
+    pub fn with_tooling(mut self, tooling: ToolingRules) -> Self {
+        self.tooling = tooling;
+        self
+    }
+
+    /// Sets throughput targets per category.
+    pub fn with_throughput_targets(mut self, targets: ThroughputTargets) -> Self {
+        self.throughput_targets = targets;
+        self
+    }
+}
+
+/// Per-resource state of a greedy run.
+///
+/// Lets a run continue where an earlier one stopped, as in
+/// [`RollingHorizon`](super::RollingHorizon).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ResourceState {
+    /// Earliest free time per resource.
+    available: HashMap<String, i64>,
+    /// Category of the last activity per resource (for setups).
+    last_category: HashMap<String, String>,
+    /// Busy intervals per resource (for working-time rules).
+    busy: HashMap<String, Vec<(i64, i64)>>,
+    /// Units processed per resource and category (for learning curves).
+    produced: HashMap<String, HashMap<String, u32>>,
+    /// Machine each tool was last mounted on (for transfers).
+    tool_location: HashMap<String, String>,
+    /// Start times of category changeovers per resource (for setup limits).
+    changeovers: HashMap<String, Vec<i64>>,
+}
+
+impl ResourceState {
+    /// All resources free from `start_time_ms`.
+    pub(crate) fn new(resources: &[Resource], start_time_ms: i64) -> Self {
+        Self {
+            available: resources
+                .iter()
+                .map(|r| (r.id.clone(), start_time_ms))
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Books an existing assignment. `category` is `None` for setup-only
+    /// assignments (setup crews), which do not change the resource's
+    /// setup state.
+    pub(crate) fn book(&mut self, assignment: &Assignment, category: Option<&str>) {
+        let id = &assignment.resource_id;
+        if let Some(available) = self.available.get_mut(id) {
+            *available = (*available).max(assignment.end_ms);
+        }
+        if let Some(category) = category {
+            let previous = self.last_category.insert(id.clone(), category.to_string());
+            if previous.is_some_and(|p| p != category) {
+                self.changeovers
+                    .entry(id.clone())
+                    .or_default()
+                    .push(assignment.start_ms);
+            }
+            *self
+                .produced
+                .entry(id.clone())
+                .or_default()
+                .entry(category.to_string())
+                .or_default() += 1;
+        }
+        self.busy
+            .entry(id.clone())
+            .or_default()
+            .push((assignment.start_ms, assignment.end_ms));
+    }
+}
+
+/// How [`SimpleScheduler`] weighs availability against the
+/// [`cost_per_hour`](Resource::cost_per_hour) of candidate resources.
+///
+/// A booking's cost is the resource's rate times the time it holds the
+/// resource, setup included; resources without a rate cost nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SelectionPolicy {
+    /// The earliest start (completion for splittable activities).
+    #[default]
+    EarliestStart,
+    /// The cheapest candidate on which the task can still meet its
+    /// deadline, assuming its later activities follow without waiting;
+    /// the earliest if none can. Tasks without a deadline always take
+    /// the cheapest.
+    CheapestFeasible,
+    /// The lowest start (ms) plus `ms_per_cost` times the booking cost:
+    /// a resource one cost unit dearer must start `ms_per_cost` earlier
+    /// to win.
+    Weighted {
+        /// Milliseconds of delay worth one cost unit.
+        ms_per_cost: f64,
+    },
+}
+
+/// How [`SimpleScheduler`] chooses among candidate resources on which an
+/// activity would start equally early.
+///
+/// Every policy but [`CandidateOrder`](Self::CandidateOrder) falls back
+/// to the resource ID, so the choice does not depend on the order of
+/// candidates or resources in the input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreak {
+    /// The first in the requirement's candidates (or the resource list).
+    #[default]
+    CandidateOrder,
+    /// The smallest resource ID.
+    ResourceId,
+    /// The least work booked so far.
+    LowestUtilization,
+    /// The lowest [`cost_per_hour`](Resource::cost_per_hour); resources
+    /// without a rate come last.
+    LowestCost,
+}
+
+impl TieBreak {
+    /// Whether resource `a` wins a tie against `b`.
+    fn prefers(
+        self,
+        a: &str,
+        b: &str,
+        resources: &HashMap<&str, &Resource>,
+        busy: &HashMap<String, Vec<(i64, i64)>>,
+    ) -> bool {
+        let booked = |id: &str| {
+            busy.get(id)
+                .map_or(0, |b| b.iter().map(|(s, e)| e - s).sum::<i64>())
+        };
+        let rate = |id: &str| {
+            resources
+                .get(id)
+                .and_then(|r| r.cost_per_hour)
+                .unwrap_or(f64::INFINITY)
+        };
+        match self {
+            Self::CandidateOrder => false,
+            Self::ResourceId => a < b,
+            Self::LowestUtilization => (booked(a), a) < (booked(b), b),
+            Self::LowestCost => rate(a).total_cmp(&rate(b)).then(a.cmp(b)).is_lt(),
+        }
+    }
+}
+
+/// The schedule generation scheme of [`SimpleScheduler`]: the order in
+/// which activities are placed.
+///
+/// The serial scheme places each task whole in dispatch order. The
+/// parallel schemes advance in time over the next activities of all
+/// tasks of the most urgent [priority class](crate::models::PriorityClass)
+/// with work left, so a task's later activities compete with other
+/// tasks' earlier ones but never with lower classes; among the
+/// activities a scheme allows next, the first task in dispatch order
+/// wins. They often give shorter schedules for the same
+/// dispatching rule, at the cost of evaluating every eligible activity
+/// per placement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationScheme {
+    /// Serial: tasks in dispatch order, all activities of one before the
+    /// next.
+    #[default]
+    Serial,
+    /// Parallel, active (Giffler-Thompson): take the eligible activity
+    /// that would end first, and place the first in dispatch order of
+    /// those that could start on its resource before that end. A
+    /// resource may idle for a higher-ranked activity about to arrive.
+    Active,
+    /// Parallel, non-delay: place the first in dispatch order of the
+    /// eligible activities that could start earliest. No resource idles
+    /// while an activity could start on it.
+    NonDelay,
+}
+
+impl GenerationScheme {
+    /// Dispatch rank of the option to place next, given the placement of
+    /// each task's next activity by rank.
+    fn pick(self, options: &[(usize, Placement)]) -> Option<usize> {
+        match self {
+            Self::Serial => options.iter().map(|&(rank, _)| rank).min(),
+            Self::Active => {
+                // The earliest end, and the activities in conflict with it
+                let &(first, earliest) =
+                    options.iter().min_by_key(|(rank, p)| (p.end_ms, *rank))?;
+                options
+                    .iter()
+                    .filter(|(rank, p)| {
+                        *rank == first
+                            || (p.resource_id == earliest.resource_id
+                                && p.start_ms < earliest.end_ms)
+                    })
+                    .map(|&(rank, _)| rank)
+                    .min()
+            }
+            Self::NonDelay => {
+                let start = options.iter().map(|(_, p)| p.start_ms).min()?;
+                options
+                    .iter()
+                    .filter(|(_, p)| p.start_ms == start)
+                    .map(|&(rank, _)| rank)
+                    .min()
+            }
+        }
+    }
 }
 
 /// Simple priority-driven greedy scheduler.
@@ -88,6 +385,14 @@ impl ScheduleRequest {
 pub struct SimpleScheduler {
     transition_matrices: TransitionMatrixCollection,
     rule_engine: Option<RuleEngine>,
+    affinity: AffinityTable,
+    locks: LockSet,
+    tooling: ToolingRules,
+    throughput: ThroughputTargets,
+    tie_break: TieBreak,
+    selection: SelectionPolicy,
+    machine_rule: Option<Arc<dyn MachineSelectionRule>>,
+    scheme: GenerationScheme,
 }
 
 impl SimpleScheduler {
@@ -96,6 +401,14 @@ impl SimpleScheduler {
         Self {
             transition_matrices: TransitionMatrixCollection::new(),
             rule_engine: None,
+            affinity: AffinityTable::new(),
+            locks: LockSet::new(),
+            tooling: ToolingRules::new(),
+            throughput: ThroughputTargets::default(),
+            tie_break: TieBreak::default(),
+            selection: SelectionPolicy::default(),
+            machine_rule: None,
+            scheme: GenerationScheme::default(),
         }
     }
 
@@ -105,6 +418,74 @@ impl SimpleScheduler {
         self
     }
 
+    /// Sets affinity rules that bias resource selection.
+    ///
+    /// A candidate's start time is discounted by its affinity score
+    /// (ms-equivalent), so a preferred resource wins unless it starts
+    /// later than the score allows.
+    pub fn with_affinity(mut self, affinity: AffinityTable) -> Self {
+        self.affinity = affinity;
+        self
+    }
+
+    /// Sets locks: locked tasks and frozen activities keep their
+    /// assignments, locked activities their resource, and other work
+    /// avoids reserved and locked time. Use [`LockSet::freeze`] to replan
+    /// around the started part of an existing schedule.
+    pub fn with_locks(mut self, locks: LockSet) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Sets tooling rules: an activity of a category with a tool pool
+    /// waits for the earliest-ready tool (including its transfer from
+    /// another machine), which is booked for the activity's interval.
+    /// Activities of other categories draw tools from their
+    /// [`tool_requirement`](crate::models::Activity::tool_requirement),
+    /// moved with the rules' transfer times.
+    pub fn with_tooling(mut self, tooling: ToolingRules) -> Self {
+        self.tooling = tooling;
+        self
+    }
+
+    /// Sets how ties between equally early candidate resources are
+    /// broken (default: [`TieBreak::CandidateOrder`]).
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// Sets how resource cost is weighed against availability (default:
+    /// [`SelectionPolicy::EarliestStart`]).
+    pub fn with_selection_policy(mut self, policy: SelectionPolicy) -> Self {
+        self.selection = policy;
+        self
+    }
+
+    /// Ranks candidate resources by `rule` instead of the
+    /// [selection policy](Self::with_selection_policy): the lowest score
+    /// wins, equal scores go to the earlier start, then to the
+    /// [tie-break](Self::with_tie_break).
+    pub fn with_machine_rule(mut self, rule: impl MachineSelectionRule + 'static) -> Self {
+        self.machine_rule = Some(Arc::new(rule));
+        self
+    }
+
+    /// Sets the schedule generation scheme (default:
+    /// [`GenerationScheme::Serial`]).
+    pub fn with_generation_scheme(mut self, scheme: GenerationScheme) -> Self {
+        self.scheme = scheme;
+        self
+    }
+
+    /// Puts categories behind their throughput target first: within a
+    /// priority class, the first tasks of each targeted category (in
+    /// dispatch order, as many as its target) are placed before the rest.
+    pub fn with_throughput_targets(mut self, targets: ThroughputTargets) -> Self {
+        self.throughput = targets;
+        self
+    }
+
     /// Sets a rule engine for task ordering.
     ///
     /// When set, tasks are sorted by the rule engine instead of by priority.
@@ -116,81 +497,510 @@ impl SimpleScheduler {
     /// Schedules tasks on resources.
     ///
     /// # Algorithm
-    /// 1. Sort tasks by rule engine or priority (descending).
-    /// 2. For each task, schedule activities in sequence order.
-    /// 3. For each activity, find the earliest-available candidate resource.
-    /// 4. Apply setup time from transition matrices.
+    /// 1. Sort tasks by priority class, then tasks filling a
+    ///    [throughput target](Self::with_throughput_targets) before the
+    ///    rest, then tasks with a
+    ///    [`max_flow_ms`](crate::models::Task::max_flow_ms) by their
+    ///    [`flow_deadline`](crate::models::Task::flow_deadline), then the
+    ///    rest by rule engine or priority (descending).
+    /// 2. For each task, schedule activities in sequence order, each
+    ///    bounded by the previous one through its
+    ///    [`precedence_mode`](crate::models::Activity::precedence_mode).
+    /// 3. For each activity, find the earliest-available candidate resource,
+    ///    respecting working-time rules of human resources and discounting
+    ///    start times by affinity scores. Each
+    ///    [requirement](crate::models::Activity::resource_requirements)
+    ///    adds its earliest-free resources up to its
+    ///    [`quantity`](crate::models::ResourceRequirement::quantity) (the
+    ///    machine counts toward the first), held for the same interval and
+    ///    recorded as separate assignments after the machine assignment.
+    /// 4. Apply setup time from transition matrices, deferring changeovers
+    ///    beyond the resource's setup limit to its next calendar window.
+    ///    If the activity has a
+    ///    [`setup_requirement`](crate::models::Activity::setup_requirement),
+    ///    the setup also occupies the earliest-free setter, recorded as a
+    ///    separate setup-only assignment after the machine assignment.
+    ///    If the task's category needs a [tool](Self::with_tooling), or
+    ///    the activity has a
+    ///    [`tool_requirement`](crate::models::Activity::tool_requirement),
+    ///    the tool is recorded the same way for the whole activity.
+    /// 5. Add resource warm-ups and cool-downs
+    ///    ([`Schedule::insert_conditioning`]) and record the
+    ///    [`check_schedule`] violations (deadline misses, broken
+    ///    working-time rules, ...) on the schedule.
+    ///
+    /// With a parallel [generation scheme](Self::with_generation_scheme),
+    /// step 2 interleaves tasks: each placement takes the scheme's pick
+    /// among the next activities of all tasks.
+    ///
+    /// With [`locks`](Self::with_locks), locked tasks and frozen activities
+    /// are copied as-is in their turn, and other activities start no
+    /// earlier than the first gap on the resource that avoids reserved and
+    /// locked time.
     pub fn schedule(&self, tasks: &[Task], resources: &[Resource], start_time_ms: i64) -> Schedule {
-        let mut schedule = Schedule::new();
-        let mut resource_available: HashMap<String, i64> = HashMap::new();
-        let mut last_category: HashMap<String, String> = HashMap::new();
+        let task_order = self.sort_tasks(tasks, start_time_ms);
+        let mut schedule = self.schedule_ordered(tasks, resources, start_time_ms, &task_order);
+        schedule.insert_conditioning(resources);
+        schedule.violations = check_schedule(&schedule, tasks, resources);
+        schedule
+    }
+
+    /// Schedules tasks in an explicit order (indices into `tasks`).
+    pub(crate) fn schedule_ordered(
+        &self,
+        tasks: &[Task],
+        resources: &[Resource],
+        start_time_ms: i64,
+        task_order: &[usize],
+    ) -> Schedule {
+        let mut state = ResourceState::new(resources, start_time_ms);
+        self.schedule_with_state(tasks, resources, start_time_ms, task_order, &mut state)
+    }
 
-        // Initialize resource availability
-        for resource in resources {
-            resource_available.insert(resource.id.clone(), start_time_ms);
+    /// Schedules tasks in an explicit order, continuing from `state`.
+    pub(crate) fn schedule_with_state(
+        &self,
+        tasks: &[Task],
+        resources: &[Resource],
+        start_time_ms: i64,
+        task_order: &[usize],
+        state: &mut ResourceState,
+    ) -> Schedule {
+        let groups = SyncGroups::from_tasks(tasks);
+        let mut floors = HashMap::new();
+        if groups.is_empty() {
+            return self.place(tasks, resources, start_time_ms, task_order, state, &floors);
+        }
+        // Synchronized activities: rebuild from the starting state with
+        // start floors at each group's latest start until all align
+        let initial = state.clone();
+        let mut rounds = 0;
+        loop {
+            *state = initial.clone();
+            let schedule = self.place(tasks, resources, start_time_ms, task_order, state, &floors);
+            rounds += 1;
+            if rounds > SyncGroups::MAX_REPAIR_ROUNDS
+                || !groups.raise_floors(&schedule, &mut floors)
+            {
+                return schedule;
+            }
         }
+    }
 
-        // Determine task order
-        let task_order = self.sort_tasks(tasks, start_time_ms);
+    /// One greedy pass; activities start no earlier than their `floors`.
+    fn place(
+        &self,
+        tasks: &[Task],
+        resources: &[Resource],
+        start_time_ms: i64,
+        task_order: &[usize],
+        state: &mut ResourceState,
+        floors: &HashMap<String, i64>,
+    ) -> Schedule {
+        let mut pass = Pass {
+            resources,
+            resource_by_id: resources.iter().map(|r| (r.id.as_str(), r)).collect(),
+            work_rules: resources
+                .iter()
+                .filter_map(|r| r.enforced_work_rules().map(|w| (r.id.as_str(), w)))
+                .collect(),
+            blocked: self.locks.blocked_windows(),
+            floors,
+            start_time_ms,
+            state,
+            batches: HashMap::new(),
+            schedule: Schedule::new(),
+        };
+        let locked_tasks = self.locks.locked_tasks();
+        // Remaining activities per task, in dispatch order (parallel schemes)
+        let mut queues = Vec::new();
 
-        // Schedule each task
-        for &task_idx in &task_order {
+        for &task_idx in task_order {
             let task = &tasks[task_idx];
-            let mut task_start = task
-                .release_time
-                .unwrap_or(start_time_ms)
-                .max(start_time_ms);
-
-            for activity in &task.activities {
-                let candidates = activity.candidate_resources();
-                if candidates.is_empty() {
-                    continue;
+            if locked_tasks.contains(task.id.as_str()) {
+                for a in self
+                    .locks
+                    .locked_assignments()
+                    .filter(|a| a.task_id == task.id)
+                {
+                    pass.schedule.add_assignment(a.clone());
                 }
+                continue;
+            }
+            let activities = task
+                .activity_order()
+                .into_iter()
+                .map(|k| &task.activities[k]);
+            if self.scheme == GenerationScheme::Serial {
+                let mut progress = TaskProgress::default();
+                for activity in activities {
+                    self.place_activity(&mut pass, task, activity, &mut progress, true);
+                }
+            } else {
+                queues.push((
+                    task,
+                    activities.collect::<VecDeque<_>>(),
+                    TaskProgress::default(),
+                ));
+            }
+        }
 
-                // Select resource with earliest availability
-                let mut best_resource: Option<&str> = None;
-                let mut best_start = i64::MAX;
-
-                for candidate in &candidates {
-                    if let Some(&available) = resource_available.get(*candidate) {
-                        let actual_start = available.max(task_start);
-                        if actual_start < best_start {
-                            best_start = actual_start;
-                            best_resource = Some(candidate);
-                        }
+        // Parallel schemes: place the scheme's pick among the next
+        // activities of the most urgent priority class with work left
+        // (queues follow class order), skipping those that fit nowhere
+        loop {
+            let mut options = Vec::new();
+            let mut class = None;
+            for (rank, (task, queue, progress)) in queues.iter_mut().enumerate() {
+                if class.is_some_and(|c| task.priority_class > c) {
+                    break;
+                }
+                while let Some(&activity) = queue.front() {
+                    if let Some(placement) =
+                        self.place_activity(&mut pass, task, activity, progress, false)
+                    {
+                        options.push((rank, placement));
+                        class = Some(task.priority_class);
+                        break;
                     }
+                    queue.pop_front();
                 }
+            }
+            let Some(rank) = self.scheme.pick(&options) else {
+                break;
+            };
+            let (task, queue, progress) = &mut queues[rank];
+            let activity = queue.pop_front().expect("picked activity");
+            self.place_activity(&mut pass, task, activity, progress, true);
+        }
 
-                if let Some(resource_id) = best_resource {
-                    // Calculate setup time from transition matrices
-                    let setup_time = if let Some(prev_cat) = last_category.get(resource_id) {
-                        self.transition_matrices.get_transition_time(
-                            resource_id,
-                            prev_cat,
-                            &task.category,
-                        )
+        pass.schedule
+    }
+
+    /// Places `activity` of `task` in `pass` after the task's placed
+    /// activities, and returns where it goes: the chosen resource and the
+    /// span of its work. With `commit` false, only returns where it would
+    /// go. `None` if it fits on no candidate.
+    fn place_activity<'a>(
+        &'a self,
+        pass: &mut Pass<'a>,
+        task: &'a Task,
+        activity: &'a Activity,
+        progress: &mut TaskProgress<'a>,
+        commit: bool,
+    ) -> Option<Placement<'a>> {
+        // Frozen activities keep their assignments; the rest of
+        // the task follows them
+        if self.locks.frozen_assignments(&activity.id).next().is_some() {
+            return Some(self.place_frozen(pass, activity, progress, commit));
+        }
+        // The first requirement picks the machine; the remaining
+        // units add resources held alongside it
+        let mut candidates = activity
+            .resource_requirements
+            .first()
+            .map_or_else(Vec::new, |req| req.resolve(pass.resources));
+        if let Some(locked) = self.locks.resource_for(&activity.id) {
+            if candidates.contains(&locked) {
+                candidates = vec![locked];
+            }
+        }
+        if candidates.is_empty() {
+            return None;
+        }
+        let demand = self.demand(pass, task, activity, progress);
+
+        // Select resource with earliest feasible start, discounted by affinity
+        let mut best: Option<Candidate<'a>> = None;
+        for machine in candidates {
+            let Some(candidate) = self.evaluate(pass, &demand, machine) else {
+                continue;
+            };
+            if best.as_ref().is_none_or(|b| {
+                candidate.cost < b.cost
+                    || (candidate.cost == b.cost
+                        && self.tie_break.prefers(
+                            machine,
+                            b.resource_id,
+                            &pass.resource_by_id,
+                            &pass.state.busy,
+                        ))
+            }) {
+                best = Some(candidate);
+            }
+        }
+
+        let best = best?;
+        let placement = best.placement();
+        if commit {
+            pass.commit(&demand, progress, best);
+        }
+        Some(placement)
+    }
+
+    /// Places a frozen activity on its locked assignments.
+    fn place_frozen<'a>(
+        &'a self,
+        pass: &mut Pass<'a>,
+        activity: &'a Activity,
+        progress: &mut TaskProgress<'a>,
+        commit: bool,
+    ) -> Placement<'a> {
+        let (mut resource_id, mut start, mut setup, mut end) = ("", i64::MAX, 0, i64::MIN);
+        for a in self.locks.frozen_assignments(&activity.id) {
+            if a.start_ms < start {
+                (resource_id, start, setup) = (&a.resource_id, a.start_ms, a.setup_ms);
+            }
+            end = end.max(a.end_ms);
+            if commit {
+                pass.schedule.add_assignment(a.clone());
+            }
+        }
+        if commit {
+            progress.previous = Some((start + setup, end));
+            progress.spans.insert(&activity.id, (start + setup, end));
+        }
+        Placement {
+            resource_id,
+            start_ms: start,
+            end_ms: end,
+        }
+    }
+
+    /// What `activity` of `task` needs on any machine, after the task's
+    /// placed activities.
+    fn demand<'a>(
+        &'a self,
+        pass: &Pass<'a>,
+        task: &'a Task,
+        activity: &'a Activity,
+        progress: &TaskProgress<'a>,
+    ) -> Demand<'a> {
+        let release = task
+            .release_time
+            .unwrap_or(pass.start_time_ms)
+            .max(pass.start_time_ms);
+        let task_start = match progress.previous.filter(|_| !task.is_dag()) {
+            Some((prev_start, prev_end)) => release.max(activity.precedence_mode.earliest_start(
+                prev_start,
+                prev_end,
+                activity.precedence_lag_ms,
+                activity.duration.process_ms,
+            )),
+            None => release,
+        };
+        let task_start = activity
+            .predecessors
+            .iter()
+            .filter_map(|p| progress.spans.get(p.as_str()))
+            .fold(task_start, |t, &(_, end)| t.max(end));
+        let task_start = pass
+            .floors
+            .get(&activity.id)
+            .map_or(task_start, |&floor| task_start.max(floor));
+
+        // Tool pool: the category's, else the activity's own
+        let (tool_req, tool_pool) = match self.tooling.tools_for(&task.category) {
+            Some(pool) => (None, Some(pool.iter().map(String::as_str).collect())),
+            None => {
+                let req = activity.tool_requirement(pass.resources);
+                let pool: Option<Vec<&str>> =
+                    req.map(|k| activity.resource_requirements[k].resolve(pass.resources));
+                (req, pool)
+            }
+        };
+        Demand {
+            task,
+            activity,
+            task_start,
+            setup_crew: activity
+                .setup_requirement
+                .as_ref()
+                .filter(|req| !req.candidates.is_empty()),
+            tool_req,
+            tool_pool,
+            tail_ms: task
+                .activities
+                .iter()
+                .filter(|a| a.sequence > activity.sequence)
+                .map(|a| a.duration.process_ms.max(0))
+                .sum(),
+        }
+    }
+
+    /// How `demand` would run on `machine`; `None` if it cannot.
+    fn evaluate<'a>(
+        &'a self,
+        pass: &Pass<'a>,
+        demand: &Demand<'a>,
+        machine: &'a str,
+    ) -> Option<Candidate<'a>> {
+        let activity = demand.activity;
+        let available = *pass.state.available.get(machine)?;
+        let setup_time = self.setup_time(pass, demand, machine);
+        let secondary = self.secondary_resources(pass, demand, machine, setup_time)?;
+        let held: Vec<&str> = std::iter::once(machine)
+            .chain(secondary.helpers.iter().map(|&(id, _)| id))
+            .collect();
+        let qualified = pass.qualified_from(activity, &held);
+        let ready = available
+            .max(demand.task_start)
+            .max(secondary.ready)
+            .max(qualified);
+        let setup_floor = pass.setup_floor(demand, machine, ready);
+        let ready = ready.max(setup_floor);
+
+        let join = pass.joinable_batch(demand, machine, &secondary, qualified, setup_time);
+        let segments = if let Some(i) = join {
+            let b = &pass.batches[machine][i];
+            vec![(b.start, b.end, b.setup)]
+        } else if activity.splittable && !held.iter().any(|id| pass.work_rules.contains_key(id)) {
+            let from = if secondary.crew.is_some() || secondary.tool.is_some() {
+                ready
+            } else {
+                demand.task_start.max(qualified).max(setup_floor)
+            };
+            pass.split_fit(demand, machine, &held, from, setup_time)?
+        } else {
+            vec![pass.contiguous_fit(demand, machine, &held, ready, setup_time)?]
+        };
+        let (start, end) = (segments[0].0, segments[segments.len() - 1].1);
+        if pass.skills_lapse(activity, &held, start, end) {
+            return None;
+        }
+        Some(Candidate {
+            resource_id: machine,
+            cost: self.candidate_cost(pass, demand, machine, &segments),
+            segments,
+            secondary,
+            join,
+        })
+    }
+
+    /// Setup crew, tool and further units `demand` holds alongside
+    /// `machine`; `None` if it needs a setter or tool and none exists.
+    fn secondary_resources<'a>(
+        &'a self,
+        pass: &Pass<'a>,
+        demand: &Demand<'a>,
+        machine: &str,
+        setup_time: i64,
+    ) -> Option<Secondary<'a>> {
+        let available = &pass.state.available;
+        let mut ready = i64::MIN;
+        // Setup crew: the setup waits for the earliest-free setter
+        let mut crew = None;
+        if let Some(req) = demand.setup_crew.filter(|_| setup_time > 0) {
+            let (setter, free) = req
+                .candidates
+                .iter()
+                .filter_map(|c| available.get(c.as_str()).map(|&t| (c.as_str(), t)))
+                .min_by_key(|&(_, t)| t)?;
+            ready = ready.max(free);
+            crew = Some(setter);
+        }
+        // Tooling: the earliest-ready tool of the pool, moved if
+        // it was last mounted on another machine
+        let mut tool = None;
+        if let Some(pool) = &demand.tool_pool {
+            let (tool_id, at) = pool
+                .iter()
+                .filter_map(|&t| {
+                    let free = *available.get(t)?;
+                    let moved = pass
+                        .state
+                        .tool_location
+                        .get(t)
+                        .is_some_and(|m| m != machine);
+                    let transfer = if moved {
+                        self.tooling.transfer_time(t)
                     } else {
                         0
                     };
+                    Some((t, free + transfer))
+                })
+                .min_by_key(|&(_, at)| at)?;
+            ready = ready.max(at);
+            tool = Some(tool_id);
+        }
+        // Further units and requirements: the earliest-free resources
+        let helpers = co_resources(
+            demand.activity,
+            machine,
+            demand.tool_req,
+            pass.resources,
+            available,
+        );
+        ready = helpers.iter().fold(ready, |t, &(_, free)| t.max(free));
+        Some(Secondary {
+            ready,
+            crew,
+            tool,
+            helpers,
+        })
+    }
 
-                    let start = best_start;
-                    let end = start + setup_time + activity.duration.process_ms;
-
-                    let assignment =
-                        Assignment::new(&activity.id, &task.id, resource_id, start, end)
-                            .with_setup(setup_time);
-
-                    schedule.add_assignment(assignment);
+    /// The activity's own setup plus the changeover from transition
+    /// matrices on `machine`.
+    fn setup_time(&self, pass: &Pass<'_>, demand: &Demand<'_>, machine: &str) -> i64 {
+        demand.activity.duration.setup_ms
+            + pass.state.last_category.get(machine).map_or(0, |prev| {
+                self.transition_matrices
+                    .get_transition_time(machine, prev, &demand.task.category)
+            })
+    }
 
-                    // Update state
-                    resource_available.insert(resource_id.to_string(), end);
-                    last_category.insert(resource_id.to_string(), task.category.clone());
-                    task_start = end; // Enforce intra-task precedence
+    /// Rank of running `demand` as `segments` on `machine`. Lower is
+    /// better: (misses deadline, primary, secondary).
+    fn candidate_cost(
+        &self,
+        pass: &Pass<'_>,
+        demand: &Demand<'_>,
+        machine: &str,
+        segments: &[(i64, i64, i64)],
+    ) -> (bool, f64, f64) {
+        let (task, activity) = (demand.task, demand.activity);
+        let resource = pass.resource_by_id.get(machine);
+        // Split work competes on completion, contiguous work on start
+        let (start, end) = (segments[0].0, segments[segments.len() - 1].1);
+        let key = if activity.splittable { end } else { start };
+        let time = match resource {
+            Some(resource) if !self.affinity.is_empty() => {
+                key as f64 - self.affinity.score(task, resource)
+            }
+            _ => key as f64,
+        };
+        let price = resource.and_then(|r| r.cost_per_hour).map_or(0.0, |rate| {
+            let held: i64 = segments.iter().map(|(s, e, _)| e - s).sum();
+            rate * held as f64 / HOUR_MS as f64
+        });
+        if let Some((rule, resource)) = self.machine_rule.as_ref().zip(resource) {
+            let option = MachineCandidate {
+                task,
+                activity,
+                resource,
+                start_ms: start,
+                end_ms: end,
+                setup_ms: segments[0].2,
+                booked_ms: pass
+                    .state
+                    .busy
+                    .get(machine)
+                    .map_or(0, |b| b.iter().map(|(s, e)| e - s).sum()),
+            };
+            return (false, rule.score(&option), time);
+        }
+        match self.selection {
+            SelectionPolicy::EarliestStart => (false, time, 0.0),
+            SelectionPolicy::Weighted { ms_per_cost } => (false, time + ms_per_cost * price, 0.0),
+            SelectionPolicy::CheapestFeasible => {
+                if task.deadline.is_some_and(|d| end + demand.tail_ms > d) {
+                    (true, time, price)
+                } else {
+                    (false, price, time)
                 }
             }
         }
-
-        schedule
     }
 
     /// Schedules from a request.
@@ -198,21 +1008,55 @@ impl SimpleScheduler {
         let scheduler = Self {
             transition_matrices: request.transition_matrices.clone(),
             rule_engine: self.rule_engine.clone(),
+            affinity: request.affinity.clone(),
+            locks: request.locks.clone(),
+            tooling: request.tooling.clone(),
+            throughput: request.throughput_targets.clone(),
+            tie_break: self.tie_break,
+            selection: self.selection,
+            machine_rule: self.machine_rule.clone(),
+            scheme: self.scheme,
         };
         scheduler.schedule(&request.tasks, &request.resources, request.start_time_ms)
     }
 
-    /// Returns task indices sorted by rule engine or priority.
-    fn sort_tasks(&self, tasks: &[Task], start_time_ms: i64) -> Vec<usize> {
-        if let Some(ref engine) = self.rule_engine {
-            let ctx = SchedulingContext::at_time(start_time_ms);
+    /// Returns task indices sorted by priority class, then by rule
+    /// engine or priority.
+    ///
+    /// Since resources are filled in order without backfilling, placing
+    /// every higher-class task first guarantees that no lower-class task
+    /// delays it.
+    pub(crate) fn sort_tasks(&self, tasks: &[Task], start_time_ms: i64) -> Vec<usize> {
+        let mut indices = if let Some(ref engine) = self.rule_engine {
+            // Queue lengths and remaining work of the unscheduled problem
+            let ctx = SchedulingContext::from_schedule(start_time_ms, &Schedule::new(), tasks);
             engine.sort_indices(tasks, &ctx)
         } else {
             // Default: sort by priority descending
             let mut indices: Vec<usize> = (0..tasks.len()).collect();
             indices.sort_by(|&a, &b| tasks[b].priority.cmp(&tasks[a].priority));
             indices
+        };
+        // Tasks that fill a category's throughput target
+        let mut counted: HashMap<&str, usize> = HashMap::new();
+        let mut for_target = vec![false; tasks.len()];
+        for &i in &indices {
+            let category = tasks[i].category.as_str();
+            let count = counted.entry(category).or_default();
+            if *count < self.throughput.target(category) {
+                *count += 1;
+                for_target[i] = true;
+            }
         }
+        indices.sort_by_key(|&i| {
+            let task = &tasks[i];
+            (
+                task.priority_class,
+                !for_target[i],
+                task.flow_deadline().unwrap_or(i64::MAX),
+            )
+        });
+        indices
     }
 }
 
@@ -222,13 +1066,561 @@ impl Default for SimpleScheduler {
     }
 }
 
+/// Start of the later [calendar window](Resource::calendar_window) a
+/// changeover of `resource` at `from` is deferred to, given the start
+/// times of its earlier changeovers: the first one with changeovers left
+/// under the resource's limit.
+///
+/// `None` if the window of `from` has changeovers left, or if there is no
+/// limit or no later window to defer to; the schedule audit reports an
+/// exceeded limit.
+fn deferred_changeover(resource: &Resource, changeovers: &[i64], from: i64) -> Option<i64> {
+    let (Some(limit), Some(calendar)) = (resource.max_setups_per_window, &resource.calendar) else {
+        return None;
+    };
+    let current = resource.calendar_window(from);
+    let used = |window: &TimeWindow| {
+        changeovers
+            .iter()
+            .filter(|&&t| resource.calendar_window(t) == *window)
+            .count()
+    };
+    if used(&current) < limit {
+        return None;
+    }
+    calendar
+        .available_intervals()
+        .into_iter()
+        .filter(|w| w.start_ms > from)
+        .find(|w| used(w) < limit)
+        .map(|w| w.start_ms)
+}
+
+/// The resources held alongside `primary` (a resource of the first
+/// requirement of `activity`), with the time each becomes free: the
+/// earliest-free resources not yet held, `quantity − 1` more of the first
+/// requirement and `quantity` of each other one but the `tool`
+/// requirement.
+///
+/// A requirement short of resources holds what there is; the schedule
+/// audit reports the missing units.
+fn co_resources<'a>(
+    activity: &'a Activity,
+    primary: &str,
+    tool: Option<usize>,
+    resources: &'a [Resource],
+    available: &HashMap<String, i64>,
+) -> Vec<(&'a str, i64)> {
+    let mut held: Vec<(&str, i64)> = Vec::new();
+    for (i, req) in activity.resource_requirements.iter().enumerate() {
+        if Some(i) == tool {
+            continue;
+        }
+        let units = (req.quantity.max(1) as usize).saturating_sub(usize::from(i == 0));
+        let mut pool: Vec<(&str, i64)> = req
+            .resolve(resources)
+            .into_iter()
+            .filter(|&id| id != primary)
+            .filter_map(|id| available.get(id).map(|&free| (id, free)))
+            .collect();
+        pool.sort_by_key(|&(_, free)| free);
+        let taken = held.len();
+        for pick in pool {
+            if held.len() - taken == units {
+                break;
+            }
+            if held.iter().all(|&(h, _)| h != pick.0) {
+                held.push(pick);
+            }
+        }
+    }
+    held
+}
+
+/// Shared state of one greedy pass.
+struct Pass<'a> {
+    resources: &'a [Resource],
+    resource_by_id: HashMap<&'a str, &'a Resource>,
+    work_rules: HashMap<&'a str, &'a WorkRules>,
+    /// Reserved and locked time per resource.
+    blocked: HashMap<String, Vec<TimeWindow>>,
+    /// Start floors of synchronized activities.
+    floors: &'a HashMap<String, i64>,
+    start_time_ms: i64,
+    state: &'a mut ResourceState,
+    /// Batches on batching resources, joinable until they start.
+    batches: HashMap<String, Vec<Batch>>,
+    schedule: Schedule,
+}
+
+impl<'a> Pass<'a> {
+    /// Certifications: when every resource of `held` qualifies.
+    fn qualified_from(&self, activity: &Activity, held: &[&str]) -> i64 {
+        held.iter()
+            .filter_map(|id| self.resource_by_id.get(id))
+            .map(|r| activity.qualified_from(r))
+            .max()
+            .unwrap_or(i64::MIN)
+    }
+
+    /// Whether a qualification of `held` lapses before `end`, ruling the
+    /// resources out.
+    fn skills_lapse(&self, activity: &Activity, held: &[&str], start: i64, end: i64) -> bool {
+        held.iter()
+            .filter_map(|id| self.resource_by_id.get(id))
+            .any(|r| !activity.lapsed_skills(r, start, end).is_empty())
+    }
+
+    /// Setup limit: a changeover on `machine` waits for a calendar window
+    /// with changeovers left (`i64::MIN` if it need not wait).
+    fn setup_floor(&self, demand: &Demand<'_>, machine: &str, ready: i64) -> i64 {
+        match self.resource_by_id.get(machine) {
+            Some(resource)
+                if self
+                    .state
+                    .last_category
+                    .get(machine)
+                    .is_some_and(|c| *c != demand.task.category) =>
+            {
+                deferred_changeover(
+                    resource,
+                    self.state
+                        .changeovers
+                        .get(machine)
+                        .map_or(&[][..], Vec::as_slice),
+                    ready,
+                )
+            }
+            _ => None,
+        }
+        .unwrap_or(i64::MIN)
+    }
+
+    /// Processing time of `demand` on `machine` with its setup starting
+    /// at `start`, stretched by the learning curve.
+    fn process_ms(&self, demand: &Demand<'_>, machine: &str, setup_time: i64, start: i64) -> i64 {
+        let (task, process) = (demand.task, demand.activity.duration.process_ms);
+        match self
+            .resource_by_id
+            .get(machine)
+            .and_then(|r| r.learning.as_ref())
+        {
+            Some(curve) => {
+                let unit = curve.experience_of(&task.category)
+                    + self
+                        .state
+                        .produced
+                        .get(machine)
+                        .and_then(|p| p.get(&task.category))
+                        .copied()
+                        .unwrap_or(0)
+                    + 1;
+                curve.process_ms(process, start + setup_time, unit)
+            }
+            None => process,
+        }
+    }
+
+    /// Batching: the earliest compatible batch on `machine` with room
+    /// that starts after the activity is ready and whose cycle covers its
+    /// processing.
+    fn joinable_batch(
+        &self,
+        demand: &Demand<'_>,
+        machine: &str,
+        secondary: &Secondary<'_>,
+        qualified: i64,
+        setup_time: i64,
+    ) -> Option<usize> {
+        let rules = self.resource_by_id.get(machine)?.batching.as_ref()?;
+        if !secondary.is_empty() || demand.activity.splittable {
+            return None;
+        }
+        let category = &demand.task.category;
+        self.batches
+            .get(machine)?
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| {
+                b.start >= demand.task_start.max(qualified)
+                    && b.categories.len() < rules.max_size
+                    && b.categories.iter().all(|c| rules.compatible(c, category))
+                    && self.process_ms(demand, machine, setup_time, b.start)
+                        <= b.end - b.start - b.setup - b.teardown
+            })
+            .min_by_key(|(_, b)| b.start)
+            .map(|(i, _)| i)
+    }
+
+    /// Splittable work: fills the free calendar time common to `held`
+    /// from `from`, around booked and locked work.
+    fn split_fit(
+        &self,
+        demand: &Demand<'_>,
+        machine: &str,
+        held: &[&str],
+        from: i64,
+        setup_time: i64,
+    ) -> Option<Vec<(i64, i64, i64)>> {
+        let free_of = |id: &str| {
+            let calendar = self
+                .resource_by_id
+                .get(id)
+                .and_then(|r| r.calendar.as_ref())
+                .map_or_else(
+                    || vec![TimeWindow::new(i64::MIN, i64::MAX)],
+                    |c| c.available_intervals(),
+                );
+            let mut taken: Vec<TimeWindow> = self
+                .blocked
+                .get(id)
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect();
+            taken.extend(
+                self.state
+                    .busy
+                    .get(id)
+                    .into_iter()
+                    .flatten()
+                    .map(|&(s, e)| TimeWindow::new(s, e)),
+            );
+            subtract_windows(&calendar, &taken)
+        };
+        let free = held[1..].iter().fold(free_of(held[0]), |free, id| {
+            intersect_windows(&free, &free_of(id))
+        });
+        split_segments(
+            &free,
+            from,
+            setup_time,
+            self.process_ms(demand, machine, setup_time, from)
+                + demand.activity.duration.teardown_ms,
+            demand.activity.min_split_ms,
+        )
+    }
+
+    /// Contiguous work: the earliest start from `ready` that satisfies
+    /// the working-time rules of `held` and avoids their locked time, as
+    /// a `(start, end, setup)` segment.
+    fn contiguous_fit(
+        &self,
+        demand: &Demand<'_>,
+        machine: &str,
+        held: &[&str],
+        ready: i64,
+        setup_time: i64,
+    ) -> Option<(i64, i64, i64)> {
+        let windows = merge_windows(
+            &held
+                .iter()
+                .flat_map(|id| self.blocked.get(*id).into_iter().flatten().cloned())
+                .collect::<Vec<_>>(),
+        );
+        let teardown = demand.activity.duration.teardown_ms;
+        let mut start = ready;
+        loop {
+            let duration =
+                setup_time + self.process_ms(demand, machine, setup_time, start) + teardown;
+            let mut t = earliest_fit(&windows, start, duration);
+            for id in held {
+                let Some(rules) = self.work_rules.get(id) else {
+                    continue;
+                };
+                let history = self.state.busy.get(*id).map_or(&[][..], |b| b.as_slice());
+                t = rules.earliest_start(history, t, duration)?;
+            }
+            if t == start {
+                return Some((start, start + duration, setup_time));
+            }
+            start = t;
+        }
+    }
+
+    /// Books `candidate` for `demand`: its assignments and the state of
+    /// every resource it holds.
+    fn commit(
+        &mut self,
+        demand: &Demand<'a>,
+        progress: &mut TaskProgress<'a>,
+        candidate: Candidate<'a>,
+    ) {
+        let (task, activity) = (demand.task, demand.activity);
+        let Candidate {
+            resource_id,
+            segments,
+            secondary,
+            join,
+            ..
+        } = candidate;
+        let state = &mut *self.state;
+        let (start, setup_time) = (segments[0].0, segments[0].2);
+        let end = segments[segments.len() - 1].1;
+        *state
+            .produced
+            .entry(resource_id.to_string())
+            .or_default()
+            .entry(task.category.clone())
+            .or_default() += 1;
+        progress.previous = Some((start + setup_time, end)); // Intra-task precedence
+        progress
+            .spans
+            .insert(&activity.id, (start + setup_time, end));
+
+        if let Some(i) = join {
+            // Joined batch: start and end with it
+            let b = &mut self.batches.get_mut(resource_id).expect("joined batch")[i];
+            b.categories.push(task.category.clone());
+            self.schedule.add_assignment(
+                Assignment::new(&activity.id, &task.id, resource_id, b.start, b.end)
+                    .with_setup(b.setup)
+                    .with_teardown(b.teardown),
+            );
+            return;
+        }
+
+        // The teardown closes the last segment
+        let last = segments.len() - 1;
+        let teardown_of = |i: usize, (s, e, setup): (i64, i64, i64)| {
+            if i == last {
+                activity
+                    .duration
+                    .teardown_ms
+                    .max(0)
+                    .min((e - s - setup).max(0))
+            } else {
+                0
+            }
+        };
+        for (i, &(s, e, setup)) in segments.iter().enumerate() {
+            self.schedule.add_assignment(
+                Assignment::new(&activity.id, &task.id, resource_id, s, e)
+                    .with_setup(setup)
+                    .with_teardown(teardown_of(i, (s, e, setup))),
+            );
+        }
+        for &(helper, _) in &secondary.helpers {
+            for (i, &(s, e, setup)) in segments.iter().enumerate() {
+                self.schedule.add_assignment(
+                    Assignment::new(&activity.id, &task.id, helper, s, e)
+                        .with_setup(setup)
+                        .with_teardown(teardown_of(i, (s, e, setup))),
+                );
+            }
+            let available = state.available.entry(helper.to_string()).or_default();
+            *available = (*available).max(end);
+            state
+                .busy
+                .entry(helper.to_string())
+                .or_default()
+                .extend(segments.iter().map(|&(s, e, _)| (s, e)));
+        }
+        if let Some(setter) = secondary.crew {
+            let setup_end = start + setup_time;
+            self.schedule.add_assignment(
+                Assignment::new(&activity.id, &task.id, setter, start, setup_end)
+                    .with_setup(setup_time),
+            );
+            state.available.insert(setter.to_string(), setup_end);
+            state
+                .busy
+                .entry(setter.to_string())
+                .or_default()
+                .push((start, setup_end));
+        }
+        if let Some(tool_id) = secondary.tool {
+            self.schedule.add_assignment(
+                Assignment::new(&activity.id, &task.id, tool_id, start, end)
+                    .with_setup(setup_time)
+                    .with_teardown(teardown_of(last, segments[last])),
+            );
+            state.available.insert(tool_id.to_string(), end);
+            state
+                .tool_location
+                .insert(tool_id.to_string(), resource_id.to_string());
+            state
+                .busy
+                .entry(tool_id.to_string())
+                .or_default()
+                .push((start, end));
+        }
+
+        // Update state; work filled into an earlier gap does not
+        // change the resource's last category
+        let available = state
+            .available
+            .entry(resource_id.to_string())
+            .or_insert(end);
+        if end >= *available {
+            *available = end;
+            let previous = state
+                .last_category
+                .insert(resource_id.to_string(), task.category.clone());
+            if previous.is_some_and(|p| p != task.category) {
+                state
+                    .changeovers
+                    .entry(resource_id.to_string())
+                    .or_default()
+                    .push(start);
+            }
+        }
+        state
+            .busy
+            .entry(resource_id.to_string())
+            .or_default()
+            .extend(segments.iter().map(|&(s, e, _)| (s, e)));
+        // Contiguous work alone on a batching resource opens a batch
+        if segments.len() == 1
+            && secondary.is_empty()
+            && self
+                .resource_by_id
+                .get(resource_id)
+                .is_some_and(|r| r.batching.is_some())
+        {
+            self.batches
+                .entry(resource_id.to_string())
+                .or_default()
+                .push(Batch {
+                    start,
+                    end,
+                    setup: setup_time,
+                    teardown: teardown_of(last, segments[last]),
+                    categories: vec![task.category.clone()],
+                });
+        }
+    }
+}
+
+/// Placed activities of a task in a pass.
+#[derive(Default)]
+struct TaskProgress<'a> {
+    /// Processing interval of the previous activity (chains).
+    previous: Option<(i64, i64)>,
+    /// Processing interval of each placed activity (DAGs).
+    spans: HashMap<&'a str, (i64, i64)>,
+}
+
+/// Where an activity goes: its resource and the span of its work.
+#[derive(Debug, Clone, Copy)]
+struct Placement<'a> {
+    resource_id: &'a str,
+    start_ms: i64,
+    end_ms: i64,
+}
+
+/// What an activity needs, whichever machine it goes to.
+struct Demand<'a> {
+    task: &'a Task,
+    activity: &'a Activity,
+    /// Earliest start from release, predecessors and sync floors.
+    task_start: i64,
+    /// Setters for the setup, if the activity needs one.
+    setup_crew: Option<&'a ResourceRequirement>,
+    /// Requirement the tool fills (none for a category's tool pool).
+    tool_req: Option<usize>,
+    /// Tools that can serve the activity.
+    tool_pool: Option<Vec<&'a str>>,
+    /// Work left after the activity, for deadline checks.
+    tail_ms: i64,
+}
+
+/// Resources an activity holds besides its machine.
+struct Secondary<'a> {
+    /// When all of them are free.
+    ready: i64,
+    /// Setter doing the setup.
+    crew: Option<&'a str>,
+    /// Tool mounted on the machine.
+    tool: Option<&'a str>,
+    /// Further units and requirements, with their free times.
+    helpers: Vec<(&'a str, i64)>,
+}
+
+impl Secondary<'_> {
+    fn is_empty(&self) -> bool {
+        self.helpers.is_empty() && self.crew.is_none() && self.tool.is_none()
+    }
+}
+
+/// An activity evaluated on one candidate machine.
+struct Candidate<'a> {
+    resource_id: &'a str,
+    /// Work as `(start, end, setup)` segments.
+    segments: Vec<(i64, i64, i64)>,
+    /// Lower is better: (misses deadline, primary, secondary).
+    cost: (bool, f64, f64),
+    secondary: Secondary<'a>,
+    /// Batch joined on the machine.
+    join: Option<usize>,
+}
+
+impl<'a> Candidate<'a> {
+    fn placement(&self) -> Placement<'a> {
+        Placement {
+            resource_id: self.resource_id,
+            start_ms: self.segments[0].0,
+            end_ms: self.segments[self.segments.len() - 1].1,
+        }
+    }
+}
+
+/// A batch on a batching resource: its cycle and members' categories.
+struct Batch {
+    start: i64,
+    end: i64,
+    setup: i64,
+    teardown: i64,
+    categories: Vec<String>,
+}
+
+/// Places a splittable activity into `free` windows (sorted, disjoint)
+/// from `from_ms`, as `(start, end, setup)` segments.
+///
+/// The setup precedes the first segment. Every processing segment is at
+/// least `min_split_ms` long (or holds all the remaining work), so a cut
+/// never leaves a remainder below the minimum. Returns `None` if the
+/// work does not fit into the windows.
+fn split_segments(
+    free: &[TimeWindow],
+    from_ms: i64,
+    setup_ms: i64,
+    process_ms: i64,
+    min_split_ms: i64,
+) -> Option<Vec<(i64, i64, i64)>> {
+    let mut segments = Vec::new();
+    let mut remaining = process_ms;
+    for w in free.iter().filter(|w| w.end_ms > from_ms) {
+        let start = w.start_ms.max(from_ms);
+        let setup = if segments.is_empty() { setup_ms } else { 0 };
+        let room = w.end_ms.saturating_sub(start).saturating_sub(setup);
+        let take = if room >= remaining {
+            remaining
+        } else {
+            room.min(remaining - min_split_ms)
+        };
+        if take < remaining && take < min_split_ms.max(1) {
+            continue;
+        }
+        segments.push((start, start + setup + take, setup));
+        remaining -= take;
+        if remaining == 0 {
+            return Some(segments);
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::dispatching::rules;
     use crate::models::{
-        Activity, ActivityDuration, Resource, ResourceRequirement, ResourceType, TransitionMatrix,
+        Activity, ActivityDuration, BatchRules, Calendar, LearningCurve, PrecedenceMode, Resource,
+        ResourceRequirement, ResourceType, ThroughputTargets, ToolingRules, TransitionMatrix,
+        ViolationType,
     };
+    use crate::scheduler::{EarliestFinish, LeastLoaded, LowestSetup, RandomMachine, ScheduleKpi};
 
     fn make_resource(id: &str) -> Resource {
         Resource::new(id, ResourceType::Primary)
@@ -354,6 +1746,42 @@ mod tests {
         assert_eq!(o2.end_ms, 3000);
     }
 
+    #[test]
+    fn test_precedence_modes() {
+        let resources = vec![make_resource("M1"), make_resource("M2")];
+        let task_with = |mode: PrecedenceMode, lag: i64| {
+            vec![Task::new("J1")
+                .with_activity(
+                    Activity::new("O1", "J1", 0)
+                        .with_duration(ActivityDuration::fixed(1000))
+                        .with_requirement(
+                            ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                        ),
+                )
+                .with_activity(
+                    Activity::new("O2", "J1", 1)
+                        .with_duration(ActivityDuration::fixed(400))
+                        .with_requirement(
+                            ResourceRequirement::new("Machine").with_candidates(vec!["M2".into()]),
+                        )
+                        .with_precedence_mode(mode, lag),
+                )]
+        };
+        let second_start = |mode, lag| {
+            let schedule = SimpleScheduler::new().schedule(&task_with(mode, lag), &resources, 0);
+            schedule.assignment_for_activity("O2").unwrap().start_ms
+        };
+
+        assert_eq!(second_start(PrecedenceMode::FinishToStart, 0), 1000);
+        assert_eq!(second_start(PrecedenceMode::FinishToStart, 200), 1200);
+        assert_eq!(second_start(PrecedenceMode::StartToStart, 100), 100);
+        assert_eq!(second_start(PrecedenceMode::FinishToFinish, 0), 600);
+        assert_eq!(
+            second_start(PrecedenceMode::Overlap { percent: 30.0 }, 0),
+            300
+        );
+    }
+
     #[test]
     fn test_transition_matrix_setup() {
         let mut tm = TransitionMatrix::new("changeover", "M1").with_default(500);
@@ -396,10 +1824,358 @@ mod tests {
     }
 
     #[test]
-    fn test_with_rule_engine() {
-        // Use SPT rule → shorter task first regardless of priority
-        let tasks = vec![
-            make_task_with_resource("long", 5000, "M1", 100), // High priority but long
+    fn test_activity_setup_and_teardown() {
+        let job = |id: &str, priority: i32| {
+            Task::new(id).with_priority(priority).with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_duration(ActivityDuration::new(200, 1000, 300))
+                    .with_requirement(
+                        ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                    ),
+            )
+        };
+        let tasks = vec![job("J1", 10), job("J2", 5)];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &[make_resource("M1")], 0);
+
+        let o1 = schedule.assignment_for_activity("J1_O1").unwrap();
+        assert_eq!((o1.start_ms, o1.end_ms), (0, 1500));
+        assert_eq!(
+            (o1.setup_ms, o1.teardown_ms, o1.process_ms()),
+            (200, 300, 1000)
+        );
+        // The next job waits for the teardown.
+        let o2 = schedule.assignment_for_activity("J2_O1").unwrap();
+        assert_eq!((o2.start_ms, o2.end_ms), (1500, 3000));
+        assert_eq!(o2.teardown_ms, 300);
+        assert!(schedule.violations.is_empty());
+
+        // Split work closes its last segment with the teardown.
+        let calendar = Calendar::new("shifts")
+            .with_window(0, 1000)
+            .with_window(2000, 10_000);
+        let resources = vec![make_resource("M1").with_calendar(calendar)];
+        let mut split = job("J3", 1);
+        split.activities[0] = split.activities[0].clone().with_splitting(100);
+        let schedule = SimpleScheduler::new().schedule(&[split], &resources, 0);
+        let parts: Vec<_> = schedule
+            .assignments
+            .iter()
+            .filter(|a| a.activity_id == "J3_O1")
+            .collect();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(
+            (parts[0].start_ms, parts[0].end_ms, parts[0].setup_ms),
+            (0, 1000, 200)
+        );
+        assert_eq!(
+            (parts[1].start_ms, parts[1].end_ms, parts[1].teardown_ms),
+            (2000, 2500, 300)
+        );
+    }
+
+    #[test]
+    fn test_negative_process_span_does_not_panic() {
+        let job = |id: &str, duration: ActivityDuration| {
+            Task::new(id).with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_duration(duration)
+                    .with_requirement(
+                        ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                    ),
+            )
+        };
+        let tasks = vec![
+            job("J1", ActivityDuration::new(0, -500, 0)),
+            job("J2", ActivityDuration::new(0, -500, 300)),
+        ];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &[make_resource("M1")], 0);
+
+        for id in ["J1_O1", "J2_O1"] {
+            let a = schedule.assignment_for_activity(id).unwrap();
+            assert_eq!(a.teardown_ms, 0);
+        }
+    }
+
+    #[test]
+    fn test_batching_resource() {
+        let job = |id: &str, category: &str, duration: i64, priority: i32| {
+            Task::new(id)
+                .with_category(category)
+                .with_priority(priority)
+                .with_activity(
+                    Activity::new(format!("{id}_O1"), id, 0)
+                        .with_process_time(duration)
+                        .with_requirement(
+                            ResourceRequirement::new("Oven").with_candidates(vec!["OVEN".into()]),
+                        ),
+                )
+        };
+        let tasks = vec![
+            job("J1", "steel", 1000, 10),
+            job("J2", "alloy", 800, 9),
+            job("J3", "steel", 1000, 8),
+            job("J4", "glass", 500, 7),
+        ];
+        let oven = make_resource("OVEN").with_batching(
+            BatchRules::new(2).with_compatible(vec!["steel".into(), "alloy".into()]),
+        );
+        let schedule = SimpleScheduler::new().schedule(&tasks, &[oven], 0);
+
+        let span = |id: &str| {
+            let a = schedule.assignment_for_activity(id).unwrap();
+            (a.start_ms, a.end_ms)
+        };
+        // Compatible jobs share the cycle; the full batch sends J3 to the
+        // next one, and glass runs alone.
+        assert_eq!(span("J1_O1"), (0, 1000));
+        assert_eq!(span("J2_O1"), (0, 1000));
+        assert_eq!(span("J3_O1"), (1000, 2000));
+        assert_eq!(span("J4_O1"), (2000, 2500));
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_tie_break_policies() {
+        let job = |id: &str, duration: i64, candidates: &[&str], priority: i32| {
+            Task::new(id).with_priority(priority).with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_process_time(duration)
+                    .with_requirement(
+                        ResourceRequirement::new("Machine")
+                            .with_candidates(candidates.iter().map(|c| c.to_string()).collect()),
+                    ),
+            )
+        };
+        let resources = vec![
+            make_resource("M1").with_cost(50.0),
+            make_resource("M2").with_cost(40.0),
+        ];
+        let pick = |tie_break: TieBreak, tasks: &[Task], id: &str| {
+            SimpleScheduler::new()
+                .with_tie_break(tie_break)
+                .schedule(tasks, &resources, 0)
+                .assignment_for_activity(id)
+                .unwrap()
+                .resource_id
+                .clone()
+        };
+
+        let single = [job("J", 1000, &["M2", "M1"], 0)];
+        assert_eq!(pick(TieBreak::CandidateOrder, &single, "J_O1"), "M2");
+        assert_eq!(pick(TieBreak::ResourceId, &single, "J_O1"), "M1");
+        assert_eq!(pick(TieBreak::LowestCost, &single, "J_O1"), "M2");
+
+        // Both machines free at 3000; M1 has carried less work.
+        let loaded = [
+            job("J1", 1000, &["M1"], 3),
+            job("J2", 3000, &["M2"], 2),
+            job("J3", 1000, &["M2", "M1"], 1).with_release_time(3000),
+        ];
+        assert_eq!(pick(TieBreak::CandidateOrder, &loaded, "J3_O1"), "M2");
+        assert_eq!(pick(TieBreak::LowestUtilization, &loaded, "J3_O1"), "M1");
+
+        // Earliest start still wins over the tie-break.
+        let busy = [
+            job("J1", 1000, &["M1"], 2),
+            job("J2", 1000, &["M2", "M1"], 1),
+        ];
+        assert_eq!(pick(TieBreak::ResourceId, &busy, "J2_O1"), "M2");
+    }
+
+    #[test]
+    fn test_selection_policies() {
+        let job = |id: &str, priority: i32| {
+            Task::new(id).with_priority(priority).with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_process_time(1000)
+                    .with_requirement(
+                        ResourceRequirement::new("Machine")
+                            .with_candidates(vec!["EXP".into(), "CHEAP".into()]),
+                    ),
+            )
+        };
+        let tasks = vec![job("J1", 3), job("J2", 2), job("J3", 1)];
+        // 1.0 and 0.1 per 1000 ms
+        let resources = vec![
+            make_resource("EXP").with_cost(3600.0),
+            make_resource("CHEAP").with_cost(360.0),
+        ];
+        let run = |policy: SelectionPolicy, tasks: &[Task]| {
+            SimpleScheduler::new()
+                .with_selection_policy(policy)
+                .schedule(tasks, &resources, 0)
+        };
+        let placement = |schedule: &Schedule| -> Vec<(String, i64)> {
+            ["J1_O1", "J2_O1", "J3_O1"]
+                .iter()
+                .map(|id| {
+                    let a = schedule.assignment_for_activity(id).unwrap();
+                    (a.resource_id.clone(), a.start_ms)
+                })
+                .collect()
+        };
+        let on = |r: &str, s: i64| (r.to_string(), s);
+
+        let earliest = run(SelectionPolicy::EarliestStart, &tasks);
+        assert_eq!(
+            placement(&earliest),
+            [on("EXP", 0), on("CHEAP", 0), on("EXP", 1000)]
+        );
+        let cheapest = run(SelectionPolicy::CheapestFeasible, &tasks);
+        assert_eq!(
+            placement(&cheapest),
+            [on("CHEAP", 0), on("CHEAP", 1000), on("CHEAP", 2000)]
+        );
+        let kpi = |s: &Schedule| {
+            ScheduleKpi::calculate(s, &tasks)
+                .with_resource_cost(s, &resources)
+                .resource_cost
+        };
+        assert!((kpi(&earliest) - 2.1).abs() < 1e-9);
+        assert!((kpi(&cheapest) - 0.3).abs() < 1e-9);
+
+        // J3's deadline needs the expensive machine.
+        let mut urgent = tasks.clone();
+        urgent[2] = urgent[2].clone().with_deadline(2000);
+        assert_eq!(
+            placement(&run(SelectionPolicy::CheapestFeasible, &urgent))[2],
+            on("EXP", 0)
+        );
+
+        // Delay is worth 500 ms per cost unit: EXP costs 450 ms more than
+        // CHEAP, so J2 takes EXP rather than wait 1000 ms.
+        let weighted = run(SelectionPolicy::Weighted { ms_per_cost: 500.0 }, &tasks);
+        assert_eq!(
+            placement(&weighted),
+            [on("CHEAP", 0), on("EXP", 0), on("CHEAP", 1000)]
+        );
+        let weighted = run(
+            SelectionPolicy::Weighted {
+                ms_per_cost: 5000.0,
+            },
+            &tasks,
+        );
+        assert_eq!(placement(&weighted), placement(&cheapest));
+    }
+
+    #[test]
+    fn test_machine_selection_rules() {
+        let job = |id: &str, priority: i32, category: &str, ms: i64| {
+            Task::new(id)
+                .with_priority(priority)
+                .with_category(category)
+                .with_activity(
+                    Activity::new(format!("{id}_O1"), id, 0)
+                        .with_duration(ActivityDuration::fixed(ms))
+                        .with_requirement(
+                            ResourceRequirement::new("Machine")
+                                .with_candidates(vec!["M1".into(), "M2".into()]),
+                        ),
+                )
+        };
+        // M1 runs A for 3000 ms and M2 runs B for 1000 ms before J3 (A).
+        let tasks = vec![
+            job("J1", 3, "A", 3000),
+            job("J2", 2, "B", 1000),
+            job("J3", 1, "A", 1000),
+        ];
+        let resources = vec![make_resource("M1"), make_resource("M2")];
+        let matrices = TransitionMatrixCollection::new()
+            .with_matrix(TransitionMatrix::new("m1", "M1").with_default(500))
+            .with_matrix(TransitionMatrix::new("m2", "M2").with_default(500));
+        let run = |scheduler: SimpleScheduler| {
+            scheduler
+                .with_transition_matrices(matrices.clone())
+                .schedule(&tasks, &resources, 0)
+        };
+        let with = SimpleScheduler::new;
+        let j3 = |schedule: &Schedule| {
+            let a = schedule.assignment_for_activity("J3_O1").unwrap();
+            (a.resource_id.clone(), a.start_ms, a.end_ms)
+        };
+
+        // M2 finishes at 2500 (500 ms changeover), M1 at 4000.
+        let finish = run(with().with_machine_rule(EarliestFinish));
+        assert_eq!(j3(&finish), ("M2".into(), 1000, 2500));
+        assert_eq!(j3(&run(with().with_machine_rule(LeastLoaded))).0, "M2");
+        // M1 is already set up for A.
+        assert_eq!(
+            j3(&run(with().with_machine_rule(LowestSetup))),
+            ("M1".into(), 3000, 4000)
+        );
+
+        let random = |seed| {
+            let schedule = run(with().with_machine_rule(RandomMachine::new(seed)));
+            assert_eq!(schedule.assignment_count(), 3);
+            ["J1_O1", "J2_O1", "J3_O1"].map(|id| {
+                schedule
+                    .assignment_for_activity(id)
+                    .unwrap()
+                    .resource_id
+                    .clone()
+            })
+        };
+        assert_eq!(random(7), random(7));
+        assert!((0..16).any(|seed| random(seed) != random(7)));
+    }
+
+    #[test]
+    fn test_generation_schemes() {
+        let job = |id: &str, priority: i32, route: [(&str, i64); 2]| {
+            route.iter().enumerate().fold(
+                Task::new(id).with_priority(priority),
+                |task, (i, &(machine, ms))| {
+                    task.with_activity(
+                        Activity::new(format!("{id}_O{}", i + 1), id, i as i32)
+                            .with_duration(ActivityDuration::fixed(ms))
+                            .with_requirement(
+                                ResourceRequirement::new("Machine")
+                                    .with_candidates(vec![machine.into()]),
+                            ),
+                    )
+                },
+            )
+        };
+        let resources = vec![make_resource("M1"), make_resource("M2")];
+        let run = |scheme: GenerationScheme, tasks: &[Task]| {
+            SimpleScheduler::new()
+                .with_generation_scheme(scheme)
+                .schedule(tasks, &resources, 0)
+        };
+        let start =
+            |schedule: &Schedule, id: &str| schedule.assignment_for_activity(id).unwrap().start_ms;
+
+        // Serial placement leaves M2 idle while J1 runs on M1; both
+        // parallel schemes start J2 on M2 at once.
+        let tasks = vec![
+            job("J1", 2, [("M1", 1000), ("M2", 3000)]),
+            job("J2", 1, [("M2", 1000), ("M1", 1000)]),
+        ];
+        let serial = run(GenerationScheme::Serial, &tasks);
+        assert_eq!(serial.makespan_ms(), 6000);
+        for scheme in [GenerationScheme::Active, GenerationScheme::NonDelay] {
+            let schedule = run(scheme, &tasks);
+            assert_eq!(start(&schedule, "J2_O1"), 0);
+            assert_eq!(start(&schedule, "J1_O2"), 1000);
+            assert_eq!(schedule.makespan_ms(), 4000);
+        }
+
+        // J1 is released at 500: active waits for it, non-delay starts J2.
+        let tasks = vec![
+            job("J1", 2, [("M1", 1000), ("M2", 100)]).with_release_time(500),
+            job("J2", 1, [("M1", 2000), ("M2", 100)]),
+        ];
+        assert_eq!(start(&run(GenerationScheme::Active, &tasks), "J1_O1"), 500);
+        let non_delay = run(GenerationScheme::NonDelay, &tasks);
+        assert_eq!(start(&non_delay, "J2_O1"), 0);
+        assert_eq!(start(&non_delay, "J1_O1"), 2000);
+    }
+
+    #[test]
+    fn test_with_rule_engine() {
+        // Use SPT rule → shorter task first regardless of priority
+        let tasks = vec![
+            make_task_with_resource("long", 5000, "M1", 100), // High priority but long
             make_task_with_resource("short", 1000, "M1", 1),  // Low priority but short
         ];
         let resources = vec![make_resource("M1")];
@@ -414,6 +2190,285 @@ mod tests {
         assert!(long_a.start_ms >= short_a.end_ms);
     }
 
+    #[test]
+    fn test_priority_classes_dominate() {
+        use crate::models::PriorityClass;
+
+        let tasks = vec![
+            make_task_with_resource("filler", 500, "M1", 100)
+                .with_priority_class(PriorityClass::Filler),
+            make_task_with_resource("normal", 1000, "M1", 50),
+            make_task_with_resource("rush", 5000, "M1", 1).with_priority_class(PriorityClass::Rush),
+        ];
+        let resources = vec![make_resource("M1")];
+
+        for scheduler in [
+            SimpleScheduler::new(),
+            SimpleScheduler::new().with_rule_engine(RuleEngine::new().with_rule(rules::Spt)),
+        ] {
+            let schedule = scheduler.schedule(&tasks, &resources, 0);
+            let start = |id: &str| schedule.assignment_for_activity(id).unwrap().start_ms;
+            assert_eq!(start("rush_O1"), 0);
+            assert_eq!(start("normal_O1"), 5000);
+            assert_eq!(start("filler_O1"), 6000);
+        }
+    }
+
+    #[test]
+    fn test_priority_classes_dominate_parallel_schemes() {
+        use crate::models::PriorityClass;
+
+        let op = |id: &str, task: &str, seq: i32, machine: &str, ms: i64| {
+            Activity::new(id, task, seq)
+                .with_duration(ActivityDuration::fixed(ms))
+                .with_requirement(
+                    ResourceRequirement::new("Machine").with_candidates(vec![machine.into()]),
+                )
+        };
+        // The filler could start on M2 at once, ahead of A's second step
+        let tasks = vec![
+            Task::new("A")
+                .with_priority_class(PriorityClass::Rush)
+                .with_activity(op("A_O1", "A", 0, "M1", 10))
+                .with_activity(op("A_O2", "A", 1, "M2", 10)),
+            Task::new("B1")
+                .with_priority(100)
+                .with_priority_class(PriorityClass::Filler)
+                .with_activity(op("B1_O1", "B1", 0, "M2", 15)),
+        ];
+        let resources = vec![make_resource("M1"), make_resource("M2")];
+
+        for scheme in [
+            GenerationScheme::Serial,
+            GenerationScheme::Active,
+            GenerationScheme::NonDelay,
+        ] {
+            let schedule = SimpleScheduler::new()
+                .with_generation_scheme(scheme)
+                .schedule(&tasks, &resources, 0);
+            let a = schedule.assignment_for_activity("A_O2").unwrap();
+            assert_eq!((a.start_ms, a.end_ms), (10, 20), "{scheme:?}");
+            assert_eq!(
+                schedule.assignment_for_activity("B1_O1").unwrap().start_ms,
+                20,
+                "{scheme:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_work_rules_delay_human_resource() {
+        use crate::models::WorkRules;
+
+        let tasks: Vec<Task> = (0..3)
+            .map(|i| make_task_with_resource(&format!("J{i}"), 3000, "W1", 10 - i))
+            .collect();
+        let rules = WorkRules::new()
+            .with_max_continuous_ms(6500)
+            .with_break_ms(1000)
+            .with_min_rest_ms(200);
+
+        let human = vec![Resource::human("W1").with_work_rules(rules.clone())];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &human, 0);
+        let start = |id: &str| schedule.assignment_for_activity(id).unwrap().start_ms;
+        assert_eq!(start("J0_O1"), 0);
+        assert_eq!(start("J1_O1"), 3200);
+        // Third block would stretch 0..9400 past 6500 ms: take a break.
+        assert_eq!(start("J2_O1"), 7200);
+        assert!(crate::validation::check_work_rules(&schedule, &human).is_empty());
+
+        // Rules on a machine are ignored.
+        let machine = vec![make_resource("W1").with_work_rules(rules)];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &machine, 0);
+        assert_eq!(schedule.makespan_ms(), 9000);
+    }
+
+    #[test]
+    fn test_affinity_biases_resource_choice() {
+        use crate::models::AffinityRule;
+
+        let task = |id: &str, preferred: &str| {
+            Task::new(id)
+                .with_attribute("preferred_machine", preferred)
+                .with_activity(
+                    Activity::new(format!("{id}_O1"), id, 0)
+                        .with_duration(ActivityDuration::fixed(1000))
+                        .with_requirement(
+                            ResourceRequirement::new("Machine")
+                                .with_candidates(vec!["M1".into(), "M2".into()]),
+                        ),
+                )
+        };
+        let tasks = vec![task("J1", "M2"), task("J2", "M2")];
+        let resources = vec![make_resource("M1"), make_resource("M2")];
+
+        // Without affinity both machines are used.
+        let plain = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+        assert_eq!(
+            plain.assignment_for_activity("J1_O1").unwrap().resource_id,
+            "M1"
+        );
+
+        // Worth waiting up to 500 ms: J1 goes to M2, J2 falls back to M1.
+        let affinity = AffinityTable::new().with_rule(AffinityRule::matching(
+            "preferred_machine",
+            "id",
+            500.0,
+        ));
+        let request =
+            ScheduleRequest::new(tasks.clone(), resources.clone()).with_affinity(affinity.clone());
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+        assert_eq!(
+            schedule
+                .assignment_for_activity("J1_O1")
+                .unwrap()
+                .resource_id,
+            "M2"
+        );
+        assert_eq!(
+            schedule
+                .assignment_for_activity("J2_O1")
+                .unwrap()
+                .resource_id,
+            "M1"
+        );
+
+        // Worth waiting 2 s: both queue on M2.
+        let strong = AffinityTable::new().with_rule(AffinityRule::matching(
+            "preferred_machine",
+            "id",
+            2000.0,
+        ));
+        let schedule = SimpleScheduler::new()
+            .with_affinity(strong)
+            .schedule(&tasks, &resources, 0);
+        assert_eq!(schedule.assignments_for_resource("M2").len(), 2);
+    }
+
+    #[test]
+    fn test_unique_tool_moves_between_machines() {
+        let tasks = vec![
+            make_task_with_resource("J1", 1000, "M1", 3).with_category("housing"),
+            make_task_with_resource("J2", 1000, "M2", 2).with_category("housing"),
+            make_task_with_resource("J3", 1000, "M2", 1).with_category("cover"),
+        ];
+        let resources = vec![
+            make_resource("M1"),
+            make_resource("M2"),
+            Resource::secondary("DIE"),
+        ];
+        let tooling = ToolingRules::new()
+            .with_requirement("housing", vec!["DIE".into()])
+            .with_transfer_ms(500);
+        let request = ScheduleRequest::new(tasks, resources).with_tooling(tooling);
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+
+        // J2 waits for the die to finish on M1 and move to M2.
+        let die: Vec<(i64, i64)> = schedule
+            .assignments_for_resource("DIE")
+            .iter()
+            .map(|a| (a.start_ms, a.end_ms))
+            .collect();
+        assert_eq!(die, vec![(0, 1000), (1500, 2500)]);
+        assert_eq!(schedule.task_completion_time("J2"), Some(2500));
+        assert_eq!(schedule.task_completion_time("J3"), Some(3500));
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_activity_fixture_moves_between_machines() {
+        let task = |id: &str, machine: &str, priority: i32| {
+            Task::new(id).with_priority(priority).with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_process_time(1000)
+                    .with_requirement(
+                        ResourceRequirement::new("Machine").with_candidates(vec![machine.into()]),
+                    )
+                    .with_requirement(
+                        ResourceRequirement::new("Fixture").with_candidates(vec!["FX1".into()]),
+                    ),
+            )
+        };
+        let tasks = vec![
+            task("J1", "M1", 3),
+            task("J2", "M2", 2),
+            task("J3", "M1", 1),
+        ];
+        let resources = vec![
+            make_resource("M1"),
+            make_resource("M2"),
+            Resource::secondary("FX1"),
+        ];
+        let tooling = ToolingRules::new().with_transfer_ms(500);
+        let request = ScheduleRequest::new(tasks, resources).with_tooling(tooling);
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+
+        // J2 waits for FX1 to move from M1; J3 waits for it to come back.
+        let fixture: Vec<(i64, i64)> = schedule
+            .assignments_for_resource("FX1")
+            .iter()
+            .map(|a| (a.start_ms, a.end_ms))
+            .collect();
+        assert_eq!(fixture, vec![(0, 1000), (1500, 2500), (3000, 4000)]);
+        assert_eq!(schedule.task_completion_time("J3"), Some(4000));
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_setup_crew_is_shared() {
+        let task = |id: &str, category: &str, machine: &str, priority: i32| {
+            Task::new(id)
+                .with_priority(priority)
+                .with_category(category)
+                .with_activity(
+                    Activity::new(format!("{id}_O1"), id, 0)
+                        .with_duration(ActivityDuration::fixed(1000))
+                        .with_requirement(
+                            ResourceRequirement::new("Machine")
+                                .with_candidates(vec![machine.into()]),
+                        )
+                        .with_setup_requirement(
+                            ResourceRequirement::new("Setter").with_candidates(vec!["S1".into()]),
+                        ),
+                )
+        };
+        let tasks = vec![
+            task("J1", "A", "M1", 4),
+            task("J2", "A", "M2", 3),
+            task("J3", "B", "M1", 2),
+            task("J4", "B", "M2", 1),
+        ];
+        let resources = vec![
+            make_resource("M1"),
+            make_resource("M2"),
+            Resource::human("S1"),
+        ];
+        let matrices = TransitionMatrixCollection::new()
+            .with_matrix(TransitionMatrix::new("m1", "M1").with_default(500))
+            .with_matrix(TransitionMatrix::new("m2", "M2").with_default(500));
+
+        let schedule = SimpleScheduler::new()
+            .with_transition_matrices(matrices)
+            .schedule(&tasks, &resources, 0);
+
+        let j3 = schedule.assignment_for_activity("J3_O1").unwrap();
+        assert_eq!(
+            (j3.resource_id.as_str(), j3.start_ms, j3.end_ms),
+            ("M1", 1000, 2500)
+        );
+        // J4's changeover waits until the setter finishes J3's.
+        let j4 = schedule.assignment_for_activity("J4_O1").unwrap();
+        assert_eq!((j4.start_ms, j4.end_ms), (1500, 3000));
+
+        // First jobs on each machine need no setup, hence no setter.
+        let setter: Vec<(i64, i64)> = schedule
+            .assignments_for_resource("S1")
+            .iter()
+            .map(|a| (a.start_ms, a.end_ms))
+            .collect();
+        assert_eq!(setter, vec![(1000, 1500), (1500, 2000)]);
+    }
+
     #[test]
     fn test_schedule_request() {
         let tasks = vec![make_task_with_resource("J1", 1000, "M1", 0)];
@@ -428,6 +2483,104 @@ mod tests {
         assert_eq!(a.end_ms, 6000);
     }
 
+    #[test]
+    fn test_locks() {
+        use crate::models::{Lock, LockSet};
+
+        let two_machines = |id: &str, priority: i32| {
+            Task::new(id).with_priority(priority).with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_process_time(1000)
+                    .with_requirement(
+                        ResourceRequirement::new("Machine")
+                            .with_candidates(vec!["M1".into(), "M2".into()]),
+                    ),
+            )
+        };
+        let tasks = vec![
+            two_machines("J1", 3),
+            two_machines("J2", 2),
+            two_machines("J3", 1),
+        ];
+        let resources = vec![make_resource("M1"), make_resource("M2")];
+
+        // J1 pinned by hand to M2 at 5000; J2 must run on M2; M1 is
+        // reserved in [0, 500).
+        let mut manual = Schedule::new();
+        manual.add_assignment(Assignment::new("J1_O1", "J1", "M2", 5000, 6000));
+        let locks = LockSet::new()
+            .with_lock(Lock::task(&manual, "J1"))
+            .with_lock(Lock::activity_resource("J2_O1", "M2"))
+            .with_lock(Lock::resource_window("M1", 0, 500));
+        let request = ScheduleRequest::new(tasks, resources).with_locks(locks);
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+
+        let j1 = schedule.assignment_for_activity("J1_O1").unwrap();
+        assert_eq!((j1.resource_id.as_str(), j1.start_ms), ("M2", 5000));
+        let j2 = schedule.assignment_for_activity("J2_O1").unwrap();
+        assert_eq!((j2.resource_id.as_str(), j2.start_ms), ("M2", 0));
+        let j3 = schedule.assignment_for_activity("J3_O1").unwrap();
+        assert_eq!((j3.resource_id.as_str(), j3.start_ms), ("M1", 500));
+
+        // Work that no longer fits before the locked task goes after it.
+        let mut long = request.clone();
+        long.tasks[1].activities[0].duration = ActivityDuration::fixed(5500);
+        let schedule = SimpleScheduler::new().schedule_request(&long);
+        assert_eq!(
+            schedule.assignment_for_activity("J2_O1").unwrap().start_ms,
+            6000
+        );
+    }
+
+    #[test]
+    fn test_replan_around_frozen_activities() {
+        use crate::models::LockSet;
+
+        let chain = |id: &str, priority: i32| {
+            Task::new(id)
+                .with_priority(priority)
+                .with_activity(
+                    Activity::new(format!("{id}_O1"), id, 0)
+                        .with_process_time(1000)
+                        .with_requirement(
+                            ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                        ),
+                )
+                .with_activity(
+                    Activity::new(format!("{id}_O2"), id, 1)
+                        .with_process_time(1000)
+                        .with_requirement(
+                            ResourceRequirement::new("Machine").with_candidates(vec!["M2".into()]),
+                        ),
+                )
+        };
+        let tasks = vec![chain("J1", 1), chain("J2", 2)];
+        let resources = vec![make_resource("M1"), make_resource("M2")];
+
+        // J1_O1 started on M1 at 500, off the plan a fresh run would make;
+        // J2 became urgent. Replanning at 1000 keeps the started work.
+        let mut current = Schedule::new();
+        current.add_assignment(Assignment::new("J1_O1", "J1", "M1", 500, 1500));
+        current.add_assignment(Assignment::new("J1_O2", "J1", "M2", 1500, 2500));
+        let locks = LockSet::freeze(&current, |a| a.start_ms < 1000);
+        let request = ScheduleRequest::new(tasks, resources)
+            .with_start_time(1000)
+            .with_locks(locks);
+        let plan = SimpleScheduler::new().schedule_request(&request);
+
+        let at = |id: &str| {
+            let a = plan.assignment_for_activity(id).unwrap();
+            (a.resource_id.as_str(), a.start_ms, a.end_ms)
+        };
+        assert_eq!(at("J1_O1"), ("M1", 500, 1500));
+        // J2 goes first but avoids the frozen interval on M1.
+        assert_eq!(at("J2_O1"), ("M1", 1500, 2500));
+        assert_eq!(at("J2_O2"), ("M2", 2500, 3500));
+        // J1 continues after its frozen operation, behind J2 on M2.
+        assert_eq!(at("J1_O2"), ("M2", 3500, 4500));
+        assert_eq!(plan.assignments.len(), 4);
+    }
+
     #[test]
     fn test_release_time_respected() {
         let mut task = make_task_with_resource("J1", 1000, "M1", 0);
@@ -441,6 +2594,21 @@ mod tests {
         assert_eq!(a.start_ms, 5000);
     }
 
+    #[test]
+    fn test_deadline_miss_recorded() {
+        let tasks = vec![
+            make_task_with_resource("J1", 3_600_000, "M1", 10),
+            make_task_with_resource("J2", 3_600_000, "M1", 1).with_deadline(3_600_000),
+        ];
+        let resources = vec![make_resource("M1")];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+
+        assert_eq!(schedule.violations.len(), 1);
+        assert_eq!(schedule.violations[0].entity_id, "J2");
+        // One hour late at deadline-miss severity.
+        assert_eq!(schedule.penalty_total(), 80.0);
+    }
+
     #[test]
     fn test_empty_input() {
         let scheduler = SimpleScheduler::new();
@@ -461,4 +2629,490 @@ mod tests {
         let schedule = scheduler.schedule(&[task], &resources, 0);
         assert_eq!(schedule.assignment_count(), 0);
     }
+
+    fn splittable(id: &str, ms: i64, min_split_ms: i64) -> Task {
+        Task::new(id).with_activity(
+            Activity::new(format!("{id}_O1"), id, 0)
+                .with_duration(ActivityDuration::fixed(ms))
+                .with_requirement(
+                    ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                )
+                .with_splitting(min_split_ms),
+        )
+    }
+
+    fn segments(schedule: &Schedule, activity_id: &str) -> Vec<(i64, i64)> {
+        schedule
+            .assignments
+            .iter()
+            .filter(|a| a.activity_id == activity_id)
+            .map(|a| (a.start_ms, a.end_ms))
+            .collect()
+    }
+
+    #[test]
+    fn test_split_at_calendar_boundary() {
+        let calendar = Calendar::new("shifts")
+            .with_window(0, 4000)
+            .with_window(6000, 20_000);
+        let resources = vec![make_resource("M1").with_calendar(calendar)];
+
+        let schedule =
+            SimpleScheduler::new().schedule(&[splittable("J1", 6000, 1000)], &resources, 0);
+        assert_eq!(segments(&schedule, "J1_O1"), vec![(0, 4000), (6000, 8000)]);
+        assert!(schedule.violations.is_empty());
+
+        // A cut never leaves less than the minimum split to resume.
+        let schedule =
+            SimpleScheduler::new().schedule(&[splittable("J1", 4500, 1000)], &resources, 0);
+        assert_eq!(segments(&schedule, "J1_O1"), vec![(0, 3500), (6000, 7000)]);
+    }
+
+    #[test]
+    fn test_split_around_higher_priority_arrival() {
+        let resources = vec![make_resource("M1")];
+        let urgent =
+            |release| make_task_with_resource("HI", 1000, "M1", 10).with_release_time(release);
+
+        // LOW pauses for HI and resumes after it.
+        let tasks = vec![splittable("LOW", 5000, 1000), urgent(2000)];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+        assert_eq!(segments(&schedule, "HI_O1"), vec![(2000, 3000)]);
+        assert_eq!(segments(&schedule, "LOW_O1"), vec![(0, 2000), (3000, 6000)]);
+
+        // A gap below the minimum split stays empty.
+        let tasks = vec![splittable("LOW", 5000, 1000), urgent(500)];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+        assert_eq!(segments(&schedule, "LOW_O1"), vec![(1500, 6500)]);
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_type_based_requirement() {
+        // No candidates: any Human resource with the skill will do.
+        let weld = |id: &str| {
+            Task::new(id).with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_duration(ActivityDuration::fixed(1000))
+                    .with_requirement(ResourceRequirement::new("Human").with_skill("welding")),
+            )
+        };
+        let resources = vec![
+            make_resource("M1"),
+            Resource::human("W1").with_skill("welding", 0.9),
+            Resource::human("W2").with_skill("welding", 0.5),
+            Resource::human("W3"),
+        ];
+        let schedule = SimpleScheduler::new().schedule(&[weld("J1"), weld("J2")], &resources, 0);
+        assert_eq!(schedule.assignment_count(), 2);
+        let mut used: Vec<&str> = schedule
+            .assignments
+            .iter()
+            .map(|a| a.resource_id.as_str())
+            .collect();
+        used.sort_unstable();
+        assert_eq!(used, vec!["W1", "W2"]);
+        assert_eq!(schedule.makespan_ms(), 1000);
+    }
+
+    #[test]
+    fn test_synchronized_activities_start_together() {
+        let mut console = make_task_with_resource("OPS", 600, "C1", 5);
+        console.activities[0].synchronized_with = vec!["TEST_O2".into()];
+        let rig = |id: &str, seq| {
+            Activity::new(id, "TEST", seq)
+                .with_duration(ActivityDuration::fixed(if seq == 0 { 1000 } else { 600 }))
+                .with_requirement(
+                    ResourceRequirement::new("Machine").with_candidates(vec!["R1".into()]),
+                )
+        };
+        let tasks = vec![
+            make_task_with_resource("OTHER", 1500, "C1", 9),
+            console,
+            Task::new("TEST")
+                .with_priority(1)
+                .with_activity(rig("TEST_O1", 0))
+                .with_activity(rig("TEST_O2", 1)),
+        ];
+        let resources = vec![make_resource("C1"), make_resource("R1")];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+
+        // The console is busy until 1500, so the rig waits for it.
+        let start = |id: &str| schedule.assignment_for_activity(id).unwrap().start_ms;
+        assert_eq!(start("OPS_O1"), 1500);
+        assert_eq!(start("TEST_O2"), 1500);
+        assert_eq!(start("TEST_O1"), 0);
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_machine_and_operator_held_together() {
+        let job = |id: &str| {
+            Task::new(id).with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_duration(ActivityDuration::fixed(1000))
+                    .with_requirement(
+                        ResourceRequirement::new("Machine")
+                            .with_candidates(vec!["M1".into(), "M2".into()]),
+                    )
+                    .with_requirement(ResourceRequirement::new("Human")),
+            )
+        };
+        let tasks = [job("J1"), job("J2")];
+        let mut resources = vec![
+            make_resource("M1"),
+            make_resource("M2"),
+            Resource::human("W1"),
+        ];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+
+        // One operator: the second job waits for W1 although M2 is free.
+        let held = |schedule: &Schedule, id: &str| -> Vec<(String, i64)> {
+            schedule
+                .assignments
+                .iter()
+                .filter(|a| a.activity_id == id)
+                .map(|a| (a.resource_id.clone(), a.start_ms))
+                .collect()
+        };
+        assert_eq!(
+            held(&schedule, "J2_O1"),
+            vec![("M1".into(), 1000), ("W1".into(), 1000)]
+        );
+        assert!(schedule.violations.is_empty());
+
+        // Two operators run both jobs at once.
+        resources.push(Resource::human("W2"));
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+        assert_eq!(
+            held(&schedule, "J2_O1"),
+            vec![("M2".into(), 0), ("W2".into(), 0)]
+        );
+        assert_eq!(schedule.makespan_ms(), 1000);
+    }
+
+    #[test]
+    fn test_throughput_targets_go_first() {
+        let job = |id: &str, category: &str, priority: i32| {
+            Task::new(id)
+                .with_category(category)
+                .with_priority(priority)
+                .with_activity(
+                    Activity::new(format!("{id}_O1"), id, 0)
+                        .with_duration(ActivityDuration::fixed(1000))
+                        .with_requirement(
+                            ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                        ),
+                )
+        };
+        let tasks = vec![
+            job("A1", "A", 9),
+            job("A2", "A", 8),
+            job("B1", "B", 2),
+            job("B2", "B", 1),
+            job("B3", "B", 0),
+        ];
+        let targets = ThroughputTargets::new(3000).with_target("B", 2);
+        let request = ScheduleRequest::new(tasks.clone(), vec![make_resource("M1")])
+            .with_throughput_targets(targets.clone());
+
+        let plain = SimpleScheduler::new().schedule(&request.tasks, &request.resources, 0);
+        let kpi = ScheduleKpi::calculate(&plain, &tasks).with_throughput(&plain, &tasks, &targets);
+        assert_eq!(kpi.shortfalls().len(), 1);
+        assert_eq!(kpi.throughput[0].planned, 1);
+
+        // B is behind its target, so two B tasks run before A.
+        let schedule = SimpleScheduler::new().schedule_request(&request);
+        let start = |id: &str| schedule.assignment_for_activity(id).unwrap().start_ms;
+        assert_eq!(start("B1_O1"), 0);
+        assert_eq!(start("B2_O1"), 1000);
+        assert_eq!(start("A1_O1"), 2000);
+        assert_eq!(start("B3_O1"), 4000);
+        let kpi =
+            ScheduleKpi::calculate(&schedule, &tasks).with_throughput(&schedule, &tasks, &targets);
+        assert!(kpi.shortfalls().is_empty());
+    }
+
+    #[test]
+    fn test_requirement_quantity_holds_distinct_units() {
+        let job = |id: &str| {
+            Task::new(id).with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_duration(ActivityDuration::fixed(1000))
+                    .with_requirement(
+                        ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                    )
+                    .with_requirement(ResourceRequirement::new("Human").with_quantity(2)),
+            )
+        };
+        let tasks = [job("J1")];
+        let mut resources = vec![
+            make_resource("M1"),
+            Resource::human("W1"),
+            Resource::human("W2"),
+            Resource::human("W3"),
+        ];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+        let mut crew: Vec<&str> = schedule
+            .assignments
+            .iter()
+            .filter(|a| a.resource_id.starts_with('W'))
+            .map(|a| a.resource_id.as_str())
+            .collect();
+        crew.sort_unstable();
+        assert_eq!(crew, ["W1", "W2"]);
+        assert!(schedule.violations.is_empty());
+
+        // One operator short: the audit reports the missing unit.
+        resources.truncate(2);
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+        assert_eq!(schedule.assignments.len(), 2);
+        assert_eq!(
+            schedule
+                .violations
+                .iter()
+                .map(|v| (v.violation_type.clone(), v.penalty))
+                .collect::<Vec<_>>(),
+            [(ViolationType::InsufficientUnits, 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_split_work_uses_common_free_time() {
+        let tasks = vec![Task::new("J1").with_activity(
+            Activity::new("J1_O1", "J1", 0)
+                .with_duration(ActivityDuration::fixed(1000))
+                .with_splitting(100)
+                .with_requirement(
+                    ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+                )
+                .with_requirement(
+                    ResourceRequirement::new("Human").with_candidates(vec!["W1".into()]),
+                ),
+        )];
+        let resources = vec![
+            make_resource("M1").with_calendar(Calendar::new("m").with_blocked(500, 1000)),
+            Resource::human("W1").with_calendar(Calendar::new("w").with_blocked(1200, 1500)),
+        ];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+        let spans: Vec<(&str, i64, i64)> = schedule
+            .assignments
+            .iter()
+            .map(|a| (a.resource_id.as_str(), a.start_ms, a.end_ms))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("M1", 0, 500),
+                ("M1", 1000, 1200),
+                ("M1", 1500, 1800),
+                ("W1", 0, 500),
+                ("W1", 1000, 1200),
+                ("W1", 1500, 1800),
+            ]
+        );
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_certifications_respected() {
+        use crate::models::Skill;
+        let weld = |id: &str| {
+            Task::new(id).with_activity(
+                Activity::new(format!("{id}_O1"), id, 0)
+                    .with_duration(ActivityDuration::fixed(1000))
+                    .with_requirement(ResourceRequirement::new("Human").with_skill("welding")),
+            )
+        };
+        // W1's certificate lapses at 1500; W2 qualifies from 3000.
+        let resources = vec![
+            Resource::human("W1")
+                .with_certification(Skill::new("welding", 0.9).with_valid_until(1500)),
+            Resource::human("W2")
+                .with_certification(Skill::new("welding", 0.9).with_valid_from(3000)),
+        ];
+        let tasks = [weld("J1"), weld("J2"), weld("J3")];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+        let placed: Vec<(&str, i64)> = schedule
+            .assignments
+            .iter()
+            .map(|a| (a.resource_id.as_str(), a.start_ms))
+            .collect();
+        assert_eq!(placed, vec![("W1", 0), ("W2", 3000), ("W2", 4000)]);
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_flow_limited_task_dispatched_first() {
+        // J2 has the lower priority but would spoil waiting behind J1.
+        let tasks = vec![
+            make_task_with_resource("J1", 5000, "M1", 10),
+            make_task_with_resource("J2", 1000, "M1", 1).with_max_flow(2000),
+        ];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &[make_resource("M1")], 0);
+        assert_eq!(schedule.task_completion_time("J2"), Some(1000));
+        assert_eq!(schedule.task_completion_time("J1"), Some(6000));
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_learning_curves_stretch_processing() {
+        let tasks = vec![
+            make_task_with_resource("J1", 1000, "M1", 4),
+            make_task_with_resource("J2", 1000, "M1", 3),
+            make_task_with_resource("J3", 1000, "M2", 2),
+            make_task_with_resource("J4", 1000, "M2", 1),
+        ];
+        let resources = vec![
+            make_resource("M1").with_learning(LearningCurve::new().with_repetition(2.0, 0.8)),
+            make_resource("M2").with_learning(LearningCurve::new().with_ramp(0, 4000, 0.5)),
+        ];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+        let ends: Vec<i64> = ["J1", "J2", "J3", "J4"]
+            .iter()
+            .map(|id| schedule.task_completion_time(id).unwrap())
+            .collect();
+        // Repetition: 2.0× then 1.6×. Ramp-up: 50% at t=0, 75% at t=2000.
+        assert_eq!(ends, vec![2000, 3600, 2000, 3333]);
+
+        // Experience carries over from before the plan.
+        let experienced = vec![make_resource("M1").with_learning(
+            LearningCurve::new()
+                .with_repetition(2.0, 0.5)
+                .with_experience("default", 1),
+        )];
+        let schedule = SimpleScheduler::new().schedule(&tasks[..1], &experienced, 0);
+        assert_eq!(schedule.task_completion_time("J1"), Some(1000));
+    }
+
+    #[test]
+    fn test_warm_up_and_cool_down_per_window() {
+        let tasks = vec![
+            make_task_with_resource("J1", 5000, "M1", 2),
+            make_task_with_resource("J2", 5000, "M1", 1).with_release_time(16_000),
+        ];
+        let resources = vec![make_resource("M1")
+            .with_calendar(
+                Calendar::new("shifts")
+                    .with_window(0, 8000)
+                    .with_window(16_000, 24_000),
+            )
+            .with_warm_up(500)
+            .with_cool_down(1000)];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+
+        assert_eq!(schedule.task_completion_time("J2"), Some(21_000));
+        let mut conditioning: Vec<_> = schedule
+            .assignments
+            .iter()
+            .filter(|a| a.is_conditioning())
+            .map(|a| (a.activity_id.as_str(), a.start_ms, a.end_ms))
+            .collect();
+        conditioning.sort_by_key(|c| c.1);
+        assert_eq!(
+            conditioning,
+            vec![
+                (Assignment::WARM_UP, -500, 0),
+                (Assignment::COOL_DOWN, 5000, 6000),
+                (Assignment::WARM_UP, 15_500, 16_000),
+                (Assignment::COOL_DOWN, 21_000, 22_000),
+            ]
+        );
+        assert!(schedule.violations.is_empty());
+        // 10 s of work plus 3 s of conditioning over a 22 s makespan.
+        let utilization = schedule.all_utilizations()["M1"];
+        assert!((utilization - 13.0 / 22.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dag_branches_run_in_parallel() {
+        let op = |id: &str, ms: i64, machine: &str| {
+            Activity::new(id, "J1", 0)
+                .with_duration(ActivityDuration::fixed(ms))
+                .with_requirement(
+                    ResourceRequirement::new("Machine").with_candidates(vec![machine.into()]),
+                )
+        };
+        // O1 → {O2, O3} → O4
+        let task = Task::new("J1")
+            .with_activity(op("O1", 1000, "M1"))
+            .with_activity(op("O2", 2000, "M2").with_predecessor("O1"))
+            .with_activity(op("O3", 1000, "M3").with_predecessor("O1"))
+            .with_activity(
+                op("O4", 500, "M1")
+                    .with_predecessor("O2")
+                    .with_predecessor("O3"),
+            );
+        let resources = ["M1", "M2", "M3"].map(make_resource).to_vec();
+        let schedule = SimpleScheduler::new().schedule(&[task], &resources, 0);
+
+        let start = |id| schedule.assignment_for_activity(id).unwrap().start_ms;
+        assert_eq!(start("O2"), 1000);
+        assert_eq!(start("O3"), 1000);
+        assert_eq!(start("O4"), 3000);
+        assert_eq!(schedule.makespan_ms(), 3500);
+        assert!(schedule.violations.is_empty());
+    }
+
+    #[test]
+    fn test_setup_limit_defers_changeover() {
+        let tasks = vec![
+            make_task_with_resource("A1", 1000, "M1", 3).with_category("A"),
+            make_task_with_resource("B1", 1000, "M1", 2).with_category("B"),
+            make_task_with_resource("A2", 1000, "M1", 1).with_category("A"),
+        ];
+        let shifts = Calendar::new("shifts")
+            .with_window(0, 10_000)
+            .with_window(20_000, 30_000);
+        let resources = vec![make_resource("M1")
+            .with_calendar(shifts)
+            .with_max_setups_per_window(1)];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+
+        // The second changeover waits for the next shift.
+        assert_eq!(schedule.task_completion_time("B1"), Some(2000));
+        assert_eq!(schedule.task_completion_time("A2"), Some(21_000));
+        assert!(schedule.violations.is_empty());
+        let kpi = ScheduleKpi::calculate(&schedule, &tasks)
+            .with_changeovers(&schedule, &tasks, &resources);
+        assert_eq!(kpi.changeovers_per_window["M1"], vec![1, 1]);
+
+        // Without a calendar there is no later window; the audit reports it.
+        let resources = vec![make_resource("M1").with_max_setups_per_window(1)];
+        let schedule = SimpleScheduler::new().schedule(&tasks, &resources, 0);
+        assert_eq!(schedule.task_completion_time("A2"), Some(3000));
+        let excess: Vec<_> = schedule
+            .violations
+            .iter()
+            .map(|v| (v.violation_type.clone(), v.penalty))
+            .collect();
+        assert_eq!(excess, [(ViolationType::TooManySetups, 1.0)]);
+    }
+
+    #[test]
+    fn test_request_json_roundtrip() {
+        let mut tm = TransitionMatrix::new("changeover", "M1");
+        tm.set_transition("A", "B", 500);
+        let request = ScheduleRequest::new(
+            vec![make_task_with_resource("J1", 1000, "M1", 0)],
+            vec![make_resource("M1")],
+        )
+        .with_start_time(100)
+        .with_transition_matrices(TransitionMatrixCollection::new().with_matrix(tm));
+
+        let json = serde_json::to_string(&request).unwrap();
+        let back: ScheduleRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.start_time_ms, 100);
+        assert_eq!(back.tasks.len(), 1);
+        assert_eq!(
+            back.transition_matrices.get_transition_time("M1", "A", "B"),
+            500
+        );
+
+        // Optional fields default.
+        let minimal: ScheduleRequest =
+            serde_json::from_str(r#"{ "tasks": [], "resources": [] }"#).unwrap();
+        assert_eq!(minimal.start_time_ms, 0);
+        assert!(minimal.transition_matrices.is_empty());
+    }
 }
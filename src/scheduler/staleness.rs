@@ -0,0 +1,432 @@
+//! Which parts of a schedule survive an input change.
+//!
+//! After a small edit to the problem — one duration corrected, an order
+//! cancelled, a machine taken off a routing — most of an existing plan is
+//! still good. [`stale_assignments`] compares the request a schedule was
+//! built from with the edited request and sorts the schedule's
+//! activities into three groups:
+//!
+//! - **Stale**: an assignment contradicts the new input (see
+//!   [`StaleReason`]) and must be replanned.
+//! - **Affected**: valid in itself, but after a stale activity in its
+//!   task, through an explicit predecessor, or later on the same
+//!   single-capacity resource, transitively, so it may have to move.
+//! - **Valid**: everything else, which a targeted repair can keep.
+//!
+//! [`StalenessReport::locks`] freezes the valid activities, so that any
+//! scheduler taking a [`LockSet`] (e.g. the
+//! [`SimpleScheduler`](super::SimpleScheduler)) replans only the rest.
+//! Removed tasks and activities free their time without moving anything.
+//!
+//! Only the task and resource definitions are compared: calendar, setup
+//! matrix and tooling changes are not detected.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::edit::work_sequence;
+use super::ScheduleRequest;
+use crate::models::{Activity, LockSet, Schedule, Task};
+
+/// Why an activity's assignments no longer fit the input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StaleReason {
+    /// The activity's task was removed.
+    RemovedTask,
+    /// The activity was removed from its task.
+    RemovedActivity,
+    /// An assigned resource was removed.
+    RemovedResource {
+        /// Resource ID.
+        resource_id: String,
+    },
+    /// An assigned resource is no longer a candidate of the activity.
+    IneligibleResource {
+        /// Resource ID.
+        resource_id: String,
+    },
+    /// The activity's setup, processing or teardown time changed.
+    DurationChanged {
+        /// Total duration before the change (ms).
+        before_ms: i64,
+        /// Total duration after the change (ms).
+        after_ms: i64,
+    },
+    /// The activity's predecessors, precedence mode or lag changed.
+    PrecedenceChanged,
+    /// The task is now released after the activity starts.
+    ReleasedLater {
+        /// New release time (ms).
+        release_ms: i64,
+    },
+}
+
+/// A scheduled activity invalidated by an input change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaleActivity {
+    /// Activity ID.
+    pub activity_id: String,
+    /// Task ID.
+    pub task_id: String,
+    /// Every reason the assignments are invalid.
+    pub reasons: Vec<StaleReason>,
+}
+
+/// Split of a schedule into stale, affected and valid activities.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StalenessReport {
+    /// Invalidated activities, in assignment order.
+    pub stale: Vec<StaleActivity>,
+    /// Valid activities that may move with a stale one, in assignment
+    /// order.
+    pub affected: Vec<String>,
+    /// Activities that keep their assignments, in assignment order.
+    pub valid: Vec<String>,
+    /// Activities of the new input without an assignment (e.g. added
+    /// tasks), in input order.
+    pub unscheduled: Vec<String>,
+}
+
+impl StalenessReport {
+    /// Whether the whole schedule still fits the new input.
+    pub fn is_clean(&self) -> bool {
+        self.stale.is_empty() && self.unscheduled.is_empty()
+    }
+
+    /// Freezes the valid activities to their assignments in `schedule`.
+    pub fn locks(&self, schedule: &Schedule) -> LockSet {
+        let valid: HashSet<&str> = self.valid.iter().map(String::as_str).collect();
+        LockSet::freeze(schedule, |a| valid.contains(a.activity_id.as_str()))
+    }
+}
+
+/// Sorts the activities of `schedule`, built from `before`, by how the
+/// changes in `after` invalidate them.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, Resource, ResourceRequirement, Task};
+/// use u_schedule::scheduler::{stale_assignments, ScheduleRequest, SimpleScheduler, StaleReason};
+///
+/// let on = |id: &str| ResourceRequirement::new("Machine").with_candidates(vec![id.into()]);
+/// let op = |id: &str, task: &str, seq, m: &str| {
+///     Activity::new(id, task, seq).with_process_time(1000).with_requirement(on(m))
+/// };
+/// let before = ScheduleRequest::new(
+///     vec![
+///         Task::new("J1").with_activity(op("O1", "J1", 0, "M1")).with_activity(op("O2", "J1", 1, "M2")),
+///         Task::new("J2").with_activity(op("O3", "J2", 0, "M3")),
+///     ],
+///     vec![Resource::primary("M1"), Resource::primary("M2"), Resource::primary("M3")],
+/// );
+/// let plan = SimpleScheduler::new().schedule_request(&before);
+///
+/// // O1 takes longer than modeled: O2 may move, O3 stays.
+/// let mut after = before.clone();
+/// after.tasks[0].activities[0].duration.process_ms = 1500;
+/// let report = stale_assignments(&plan, &before, &after);
+/// assert_eq!(report.stale[0].activity_id, "O1");
+/// assert_eq!(
+///     report.stale[0].reasons,
+///     [StaleReason::DurationChanged { before_ms: 1000, after_ms: 1500 }]
+/// );
+/// assert_eq!(report.affected, ["O2"]);
+/// assert_eq!(report.valid, ["O3"]);
+///
+/// // Replan only O1 and O2.
+/// after.locks = report.locks(&plan);
+/// let repaired = SimpleScheduler::new().schedule_request(&after);
+/// assert_eq!(repaired.assignment_for_activity("O2").unwrap().start_ms, 1500);
+/// ```
+pub fn stale_assignments(
+    schedule: &Schedule,
+    before: &ScheduleRequest,
+    after: &ScheduleRequest,
+) -> StalenessReport {
+    let old = activity_index(&before.tasks);
+    let new = activity_index(&after.tasks);
+    let old_tasks: HashMap<&str, &Task> = before.tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    let new_tasks: HashMap<&str, &Task> = after.tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    let resources: HashSet<&str> = after.resources.iter().map(|r| r.id.as_str()).collect();
+
+    // Scheduled activities, in assignment order
+    let mut order: Vec<&str> = Vec::new();
+    let mut assigned: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, a) in schedule.assignments.iter().enumerate() {
+        let entry = assigned.entry(a.activity_id.as_str()).or_default();
+        if entry.is_empty() {
+            order.push(&a.activity_id);
+        }
+        entry.push(i);
+    }
+
+    let mut stale = Vec::new();
+    for &id in &order {
+        let assignments = &assigned[id];
+        let first = &schedule.assignments[assignments[0]];
+        let mut reasons = Vec::new();
+        let Some(&(task, activity)) = new.get(id) else {
+            reasons.push(if new_tasks.contains_key(first.task_id.as_str()) {
+                StaleReason::RemovedActivity
+            } else {
+                StaleReason::RemovedTask
+            });
+            stale.push(StaleActivity {
+                activity_id: id.to_string(),
+                task_id: first.task_id.clone(),
+                reasons,
+            });
+            continue;
+        };
+
+        let previous = old.get(id).map(|&(_, a)| a);
+        let eligible_before: HashSet<&str> =
+            previous.map_or_else(HashSet::new, |a| eligible(a, before));
+        let eligible_after = eligible(activity, after);
+        let mut seen = HashSet::new();
+        for a in assignments.iter().map(|&i| &schedule.assignments[i]) {
+            let resource_id = a.resource_id.as_str();
+            if !seen.insert(resource_id) {
+                continue;
+            }
+            if !resources.contains(resource_id) {
+                reasons.push(StaleReason::RemovedResource {
+                    resource_id: resource_id.to_string(),
+                });
+            } else if eligible_before.contains(resource_id) && !eligible_after.contains(resource_id)
+            {
+                reasons.push(StaleReason::IneligibleResource {
+                    resource_id: resource_id.to_string(),
+                });
+            }
+        }
+        if let Some(previous) = previous {
+            let (b, n) = (&previous.duration, &activity.duration);
+            if (b.setup_ms, b.process_ms, b.teardown_ms)
+                != (n.setup_ms, n.process_ms, n.teardown_ms)
+            {
+                reasons.push(StaleReason::DurationChanged {
+                    before_ms: b.total_ms(),
+                    after_ms: n.total_ms(),
+                });
+            }
+            if previous.predecessors != activity.predecessors
+                || previous.precedence_mode != activity.precedence_mode
+                || previous.precedence_lag_ms != activity.precedence_lag_ms
+            {
+                reasons.push(StaleReason::PrecedenceChanged);
+            }
+        }
+        let start = assignments
+            .iter()
+            .map(|&i| schedule.assignments[i].start_ms)
+            .min()
+            .unwrap_or(0);
+        let release = task.release_time;
+        let old_release = old_tasks.get(task.id.as_str()).and_then(|t| t.release_time);
+        if let Some(release_ms) = release.filter(|&r| r > start && release != old_release) {
+            reasons.push(StaleReason::ReleasedLater { release_ms });
+        }
+        if !reasons.is_empty() {
+            stale.push(StaleActivity {
+                activity_id: id.to_string(),
+                task_id: task.id.clone(),
+                reasons,
+            });
+        }
+    }
+
+    // Activities reached from a stale, still existing activity
+    let mut moving: HashSet<&str> = stale
+        .iter()
+        .filter(|s| new.contains_key(s.activity_id.as_str()))
+        .map(|s| s.activity_id.as_str())
+        .collect();
+    let mut followers: HashMap<&str, Vec<&str>> = HashMap::new();
+    for task in &after.tasks {
+        for (k, activity) in task.activities.iter().enumerate() {
+            if let Some(prev) = task.chained_predecessor(k) {
+                followers
+                    .entry(task.activities[prev].id.as_str())
+                    .or_default()
+                    .push(&activity.id);
+            }
+            for pred in &activity.predecessors {
+                followers
+                    .entry(pred.as_str())
+                    .or_default()
+                    .push(&activity.id);
+            }
+        }
+    }
+    for (i, prev) in work_sequence(after, schedule, None) {
+        followers
+            .entry(schedule.assignments[prev].activity_id.as_str())
+            .or_default()
+            .push(&schedule.assignments[i].activity_id);
+    }
+    let mut queue: Vec<&str> = moving.iter().copied().collect();
+    while let Some(id) = queue.pop() {
+        for &next in followers.get(id).into_iter().flatten() {
+            if assigned.contains_key(next) && moving.insert(next) {
+                queue.push(next);
+            }
+        }
+    }
+
+    let stale_ids: HashSet<&str> = stale.iter().map(|s| s.activity_id.as_str()).collect();
+    let (affected, valid): (Vec<&str>, Vec<&str>) = order
+        .iter()
+        .filter(|id| !stale_ids.contains(*id))
+        .partition(|id| moving.contains(*id));
+    let unscheduled = after
+        .tasks
+        .iter()
+        .flat_map(|t| &t.activities)
+        .filter(|a| !assigned.contains_key(a.id.as_str()))
+        .map(|a| a.id.clone())
+        .collect();
+
+    StalenessReport {
+        stale,
+        affected: affected.into_iter().map(String::from).collect(),
+        valid: valid.into_iter().map(String::from).collect(),
+        unscheduled,
+    }
+}
+
+/// Task and activity per activity ID.
+fn activity_index(tasks: &[Task]) -> HashMap<&str, (&Task, &Activity)> {
+    tasks
+        .iter()
+        .flat_map(|t| t.activities.iter().map(move |a| (a.id.as_str(), (t, a))))
+        .collect()
+}
+
+/// Resources any requirement of `activity` accepts in `request`.
+fn eligible<'a>(activity: &'a Activity, request: &'a ScheduleRequest) -> HashSet<&'a str> {
+    activity
+        .resource_requirements
+        .iter()
+        .chain(&activity.setup_requirement)
+        .flat_map(|r| r.resolve(&request.resources))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Resource, ResourceRequirement};
+    use crate::scheduler::SimpleScheduler;
+
+    fn op(id: &str, task: &str, seq: i32, candidates: &[&str]) -> Activity {
+        Activity::new(id, task, seq)
+            .with_process_time(1000)
+            .with_requirement(
+                ResourceRequirement::new("Machine")
+                    .with_candidates(candidates.iter().map(|c| c.to_string()).collect()),
+            )
+    }
+
+    /// J1: O1 (M1) → O2 (M2); J2: O3 (M2), queued behind O2; J3: O4 (M3).
+    fn request() -> ScheduleRequest {
+        ScheduleRequest::new(
+            vec![
+                Task::new("J1")
+                    .with_activity(op("O1", "J1", 0, &["M1"]))
+                    .with_activity(op("O2", "J1", 1, &["M2"])),
+                Task::new("J2")
+                    .with_priority(-1)
+                    .with_activity(op("O3", "J2", 0, &["M2"])),
+                Task::new("J3").with_activity(op("O4", "J3", 0, &["M3", "M1"])),
+            ],
+            vec![
+                Resource::primary("M1"),
+                Resource::primary("M2"),
+                Resource::primary("M3"),
+            ],
+        )
+    }
+
+    fn reasons<'a>(report: &'a StalenessReport, id: &str) -> &'a [StaleReason] {
+        &report
+            .stale
+            .iter()
+            .find(|s| s.activity_id == id)
+            .unwrap()
+            .reasons
+    }
+
+    #[test]
+    fn test_unchanged_input_is_clean() {
+        let request = request();
+        let plan = SimpleScheduler::new().schedule_request(&request);
+        let report = stale_assignments(&plan, &request, &request);
+        assert!(report.is_clean());
+        assert!(report.affected.is_empty());
+        assert_eq!(report.valid.len(), 4);
+        assert_eq!(report.locks(&plan).frozen_activities().len(), 4);
+    }
+
+    #[test]
+    fn test_changes_invalidate_and_propagate() {
+        let before = request();
+        let plan = SimpleScheduler::new().schedule_request(&before);
+        assert_eq!(plan.assignment_for_activity("O3").unwrap().start_ms, 2000);
+
+        let mut after = before.clone();
+        // O1 loses its only machine's eligibility, O4 its machine.
+        after.tasks[0].activities[0].resource_requirements[0].candidates = vec!["M3".into()];
+        after.resources.retain(|r| r.id != "M3");
+        after.resources.push(Resource::primary("M4"));
+        // J3 gains an activity.
+        after.tasks[2] = after.tasks[2]
+            .clone()
+            .with_activity(op("O5", "J3", 1, &["M1"]));
+        let report = stale_assignments(&plan, &before, &after);
+        assert_eq!(
+            reasons(&report, "O1"),
+            [StaleReason::IneligibleResource {
+                resource_id: "M1".into()
+            }]
+        );
+        assert_eq!(
+            reasons(&report, "O4"),
+            [StaleReason::RemovedResource {
+                resource_id: "M3".into()
+            }]
+        );
+        // O2 follows O1 in J1, O3 follows O2 on M2.
+        assert_eq!(report.affected, ["O2", "O3"]);
+        assert!(report.valid.is_empty());
+        assert_eq!(report.unscheduled, ["O5"]);
+        assert!(!report.is_clean());
+
+        // Removing J1 frees M2 without moving O3; a later release and new
+        // predecessor make O4 stale.
+        let mut after = before.clone();
+        after.tasks.remove(0);
+        after.tasks[1].release_time = Some(500);
+        after.tasks[1].activities[0].predecessors.push("O3".into());
+        let report = stale_assignments(&plan, &before, &after);
+        assert_eq!(reasons(&report, "O1"), [StaleReason::RemovedTask]);
+        assert_eq!(
+            reasons(&report, "O4"),
+            [
+                StaleReason::PrecedenceChanged,
+                StaleReason::ReleasedLater { release_ms: 500 }
+            ]
+        );
+        assert!(report.affected.is_empty());
+        assert_eq!(report.valid, ["O3"]);
+
+        let mut after = before.clone();
+        after.tasks[0].activities.pop();
+        let report = stale_assignments(&plan, &before, &after);
+        assert_eq!(reasons(&report, "O2"), [StaleReason::RemovedActivity]);
+        assert_eq!(report.stale.len(), 1);
+    }
+}
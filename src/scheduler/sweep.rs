@@ -0,0 +1,426 @@
+//! Single-parameter what-if sweeps.
+//!
+//! "What if this machine had a second unit?", "which ATC look-ahead suits
+//! this order book?", "how much makespan does weighting tardiness cost?"
+//! are answered by re-solving the same request across a range of one
+//! parameter. [`ParameterSweep`] does this in-process, on worker threads
+//! (one after another on wasm32), and returns the KPIs per value as a [`SweepCurve`].
+//!
+//! # Parameters
+//!
+//! | Parameter | Solve per value |
+//! |-----------|-----------------|
+//! | [`ResourceCapacity`](SweepParameter::ResourceCapacity) | Greedy, with the resource as `round(value)` parallel units |
+//! | [`AtcK`](SweepParameter::AtcK) | Greedy, dispatching by [`Atc`] with `k = value` |
+//! | [`TardinessWeight`](SweepParameter::TardinessWeight) | Greedy, then [`LnsImprover`] with tardiness weight `value` |
+//!
+//! The greedy scheduler treats every resource as a single unit, so a
+//! capacity of `c` is modelled as the resource plus `c − 1` clones, as in
+//! [`CapacityAnalyzer::expand`](super::CapacityAnalyzer::expand).
+
+use serde::{Deserialize, Serialize};
+
+use super::capacity::add_clone;
+use super::cost::csv_field;
+use super::{LnsImprover, ScheduleKpi, ScheduleRequest, SimpleScheduler};
+use crate::dispatching::rules::Atc;
+use crate::dispatching::RuleEngine;
+use crate::models::Schedule;
+
+/// The parameter varied by a sweep.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SweepParameter {
+    /// Number of parallel units of a resource (values rounded, at least 1).
+    ResourceCapacity(String),
+    /// Look-ahead `k` of the ATC dispatching rule.
+    AtcK,
+    /// Tardiness weight of the LNS cost (values clamped to `[0, 1]`).
+    TardinessWeight,
+}
+
+impl SweepParameter {
+    /// Short name for reports (`capacity:<id>`, `atc_k`, `tardiness_weight`).
+    pub fn name(&self) -> String {
+        match self {
+            Self::ResourceCapacity(id) => format!("capacity:{id}"),
+            Self::AtcK => "atc_k".to_string(),
+            Self::TardinessWeight => "tardiness_weight".to_string(),
+        }
+    }
+}
+
+/// KPIs of one parameter value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepPoint {
+    /// Parameter value.
+    pub value: f64,
+    /// KPIs of the schedule solved with this value.
+    pub kpi: ScheduleKpi,
+    /// Activities of the request without an assignment.
+    pub unscheduled_activities: usize,
+}
+
+/// KPIs per parameter value, in the order the values were given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepCurve {
+    /// The varied parameter.
+    pub parameter: SweepParameter,
+    /// One point per value.
+    pub points: Vec<SweepPoint>,
+}
+
+impl SweepCurve {
+    /// `(value, metric)` pairs for one KPI, e.g.
+    /// `curve.series(|k| k.makespan_ms as f64)`.
+    pub fn series(&self, metric: impl Fn(&ScheduleKpi) -> f64) -> Vec<(f64, f64)> {
+        self.points
+            .iter()
+            .map(|p| (p.value, metric(&p.kpi)))
+            .collect()
+    }
+
+    /// The fully scheduled point with the lowest `metric` (first on ties).
+    pub fn best_by(&self, metric: impl Fn(&ScheduleKpi) -> f64) -> Option<&SweepPoint> {
+        self.points
+            .iter()
+            .filter(|p| p.unscheduled_activities == 0)
+            .fold(None, |best: Option<(&SweepPoint, f64)>, p| {
+                let m = metric(&p.kpi);
+                match best {
+                    Some((_, b)) if b <= m => best,
+                    _ => Some((p, m)),
+                }
+            })
+            .map(|(p, _)| p)
+    }
+
+    /// The curve as CSV (header row included).
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "parameter,value,makespan_ms,total_tardiness_ms,tardiness_cost,on_time_rate,avg_utilization,avg_flow_time_ms,unscheduled_activities\n",
+        );
+        let name = csv_field(&self.parameter.name());
+        for p in &self.points {
+            csv.push_str(&format!(
+                "{name},{},{},{},{:.2},{:.4},{:.4},{:.1},{}\n",
+                p.value,
+                p.kpi.makespan_ms,
+                p.kpi.total_tardiness_ms,
+                p.kpi.tardiness_cost,
+                p.kpi.on_time_rate,
+                p.kpi.avg_utilization,
+                p.kpi.avg_flow_time_ms,
+                p.unscheduled_activities,
+            ));
+        }
+        csv
+    }
+}
+
+/// Re-solves a request for each value of one parameter.
+///
+/// Transition matrices, affinity, locks and tooling are taken from the request;
+/// KPIs are computed against the request's tasks.
+///
+/// # Example
+///
+/// ```
+/// use u_schedule::models::{Activity, ActivityDuration, Resource, ResourceRequirement, Task};
+/// use u_schedule::scheduler::{ParameterSweep, ScheduleRequest, SweepParameter};
+///
+/// let tasks = (0..4)
+///     .map(|i| {
+///         let id = format!("J{i}");
+///         Task::new(&id).with_activity(
+///             Activity::new(format!("{id}_O1"), &id, 0)
+///                 .with_duration(ActivityDuration::fixed(1000))
+///                 .with_requirement(
+///                     ResourceRequirement::new("Machine").with_candidates(vec!["M1".into()]),
+///                 ),
+///         )
+///     })
+///     .collect();
+/// let request = ScheduleRequest::new(tasks, vec![Resource::primary("M1")]);
+///
+/// let curve = ParameterSweep::new().run(
+///     &request,
+///     &SweepParameter::ResourceCapacity("M1".into()),
+///     &[1.0, 2.0, 3.0, 4.0],
+/// );
+/// let makespans: Vec<f64> = curve.series(|k| k.makespan_ms as f64).iter().map(|p| p.1).collect();
+/// assert_eq!(makespans, vec![4000.0, 2000.0, 2000.0, 1000.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParameterSweep {
+    scheduler: SimpleScheduler,
+    improver: LnsImprover,
+    threads: usize,
+}
+
+impl Default for ParameterSweep {
+    fn default() -> Self {
+        Self {
+            scheduler: SimpleScheduler::new(),
+            improver: LnsImprover::new().with_iterations(200).with_seed(0),
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+impl ParameterSweep {
+    /// Creates a sweep with the default greedy scheduler, a seeded
+    /// 200-iteration LNS, and one thread per core.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the scheduler (e.g., with a rule engine; replaced by the ATC
+    /// engine when sweeping [`SweepParameter::AtcK`]).
+    pub fn with_scheduler(mut self, scheduler: SimpleScheduler) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Sets the improver used for [`SweepParameter::TardinessWeight`]
+    /// (its tardiness weight is overridden per value).
+    pub fn with_improver(mut self, improver: LnsImprover) -> Self {
+        self.improver = improver;
+        self
+    }
+
+    /// Sets the number of worker threads (at least 1; ignored on wasm32).
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// `n` evenly spaced values from `from` to `to` (inclusive).
+    pub fn linspace(from: f64, to: f64, n: usize) -> Vec<f64> {
+        match n {
+            0 => Vec::new(),
+            1 => vec![from],
+            _ => (0..n)
+                .map(|i| from + (to - from) * i as f64 / (n - 1) as f64)
+                .collect(),
+        }
+    }
+
+    /// Solves `request` once per value of `parameter`.
+    ///
+    /// An unknown resource in [`SweepParameter::ResourceCapacity`] leaves
+    /// the request unchanged.
+    pub fn run(
+        &self,
+        request: &ScheduleRequest,
+        parameter: &SweepParameter,
+        values: &[f64],
+    ) -> SweepCurve {
+        SweepCurve {
+            parameter: parameter.clone(),
+            points: self.run_chunks(request, parameter, values),
+        }
+    }
+
+    /// Solves the values in chunks, each on its own thread (on the
+    /// calling thread with a single worker).
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run_chunks(
+        &self,
+        request: &ScheduleRequest,
+        parameter: &SweepParameter,
+        values: &[f64],
+    ) -> Vec<SweepPoint> {
+        if self.threads == 1 {
+            return self.run_chunk(request, parameter, values);
+        }
+        let chunk = values.len().div_ceil(self.threads).max(1);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = values
+                .chunks(chunk)
+                .map(|chunk| scope.spawn(move || self.run_chunk(request, parameter, chunk)))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().expect("sweep thread panicked"))
+                .collect()
+        })
+    }
+
+    /// Solves the values one after another (no threads on wasm32).
+    #[cfg(target_arch = "wasm32")]
+    fn run_chunks(
+        &self,
+        request: &ScheduleRequest,
+        parameter: &SweepParameter,
+        values: &[f64],
+    ) -> Vec<SweepPoint> {
+        self.run_chunk(request, parameter, values)
+    }
+
+    /// Solves one chunk of values.
+    fn run_chunk(
+        &self,
+        request: &ScheduleRequest,
+        parameter: &SweepParameter,
+        values: &[f64],
+    ) -> Vec<SweepPoint> {
+        values
+            .iter()
+            .map(|&value| self.point(request, parameter, value))
+            .collect()
+    }
+
+    /// Solves and measures one value.
+    fn point(
+        &self,
+        request: &ScheduleRequest,
+        parameter: &SweepParameter,
+        value: f64,
+    ) -> SweepPoint {
+        let schedule = match parameter {
+            SweepParameter::ResourceCapacity(resource_id) => {
+                let mut varied = request.clone();
+                if let Some(template) = request.resources.iter().find(|r| &r.id == resource_id) {
+                    let units = value.round().max(1.0) as usize;
+                    for n in 1..units {
+                        add_clone(&mut varied, template, n);
+                    }
+                }
+                self.scheduler.schedule_request(&varied)
+            }
+            SweepParameter::AtcK => self
+                .scheduler
+                .clone()
+                .with_rule_engine(RuleEngine::new().with_rule(Atc::with_k(value)))
+                .schedule_request(request),
+            SweepParameter::TardinessWeight => {
+                let initial = self.scheduler.schedule_request(request);
+                self.improver
+                    .clone()
+                    .with_scheduler(self.scheduler.clone())
+                    .with_tardiness_weight(value)
+                    .improve(request, &initial)
+                    .0
+            }
+        };
+        SweepPoint {
+            value,
+            kpi: ScheduleKpi::calculate(&schedule, &request.tasks),
+            unscheduled_activities: unscheduled(request, &schedule),
+        }
+    }
+}
+
+/// Activities of the request with candidates but no assignment.
+fn unscheduled(request: &ScheduleRequest, schedule: &Schedule) -> usize {
+    request
+        .tasks
+        .iter()
+        .flat_map(|t| &t.activities)
+        .filter(|a| !a.resolved_candidates(&request.resources).is_empty())
+        .filter(|a| schedule.assignment_for_activity(&a.id).is_none())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Activity, ActivityDuration, Resource, ResourceRequirement, Task};
+
+    fn job(id: &str, ms: i64, deadline: i64, candidates: &[&str]) -> Task {
+        Task::new(id).with_deadline(deadline).with_activity(
+            Activity::new(format!("{id}_O1"), id, 0)
+                .with_duration(ActivityDuration::fixed(ms))
+                .with_requirement(
+                    ResourceRequirement::new("Machine")
+                        .with_candidates(candidates.iter().map(|c| c.to_string()).collect()),
+                ),
+        )
+    }
+
+    fn request() -> ScheduleRequest {
+        let tasks = vec![
+            job("J1", 4000, 10_000, &["M1"]),
+            job("J2", 1000, 1000, &["M1"]),
+            job("J3", 2000, 3000, &["M1", "M2"]),
+            job("J4", 3000, 9000, &["M2"]),
+        ];
+        ScheduleRequest::new(
+            tasks,
+            vec![Resource::primary("M1"), Resource::primary("M2")],
+        )
+    }
+
+    #[test]
+    fn test_capacity_sweep() {
+        let tasks = vec![
+            job("J1", 1000, 1000, &["M1"]),
+            job("J2", 1000, 1000, &["M1"]),
+            job("J3", 1000, 1000, &["M1"]),
+            job("J4", 500, 1000, &["M2"]),
+        ];
+        let request = ScheduleRequest::new(
+            tasks,
+            vec![Resource::primary("M1"), Resource::primary("M2")],
+        );
+        let parameter = SweepParameter::ResourceCapacity("M1".into());
+        let curve = ParameterSweep::new().run(&request, &parameter, &[1.0, 2.0, 3.0]);
+
+        let makespans = curve.series(|k| k.makespan_ms as f64);
+        assert_eq!(makespans, vec![(1.0, 3000.0), (2.0, 2000.0), (3.0, 1000.0)]);
+        assert_eq!(curve.points[0].kpi.total_tardiness_ms, 3000);
+        assert!(curve.points.iter().all(|p| p.unscheduled_activities == 0));
+        assert_eq!(curve.best_by(|k| k.makespan_ms as f64).unwrap().value, 3.0);
+
+        // Threading does not change the result.
+        let serial =
+            ParameterSweep::new()
+                .with_threads(1)
+                .run(&request, &parameter, &[1.0, 2.0, 3.0]);
+        assert_eq!(serial.series(|k| k.makespan_ms as f64), makespans);
+
+        let unknown = SweepParameter::ResourceCapacity("M9".into());
+        let flat = ParameterSweep::new().run(&request, &unknown, &[1.0, 4.0]);
+        assert_eq!(
+            flat.points[0].kpi.makespan_ms,
+            flat.points[1].kpi.makespan_ms
+        );
+    }
+
+    #[test]
+    fn test_atc_and_tardiness_weight_sweeps() {
+        let request = request();
+        let values = ParameterSweep::linspace(1.0, 5.0, 5);
+        assert_eq!(values, vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+
+        let curve = ParameterSweep::new().run(&request, &SweepParameter::AtcK, &values);
+        assert_eq!(curve.points.len(), 5);
+        assert_eq!(curve.points[3].value, 4.0);
+        assert!(curve.best_by(|k| k.total_tardiness_ms as f64).is_some());
+
+        let curve = ParameterSweep::new().run(
+            &request,
+            &SweepParameter::TardinessWeight,
+            &ParameterSweep::linspace(0.0, 1.0, 3),
+        );
+        let tardiness = curve.series(|k| k.total_tardiness_ms as f64);
+        assert!(tardiness[2].1 <= tardiness[0].1);
+
+        let csv = curve.to_csv();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("tardiness_weight,0,"));
+    }
+
+    #[test]
+    fn test_empty_sweep() {
+        let curve = ParameterSweep::new().run(&request(), &SweepParameter::AtcK, &[]);
+        assert!(curve.points.is_empty());
+        assert!(curve.best_by(|k| k.makespan_ms as f64).is_none());
+        assert!(ParameterSweep::linspace(0.0, 1.0, 0).is_empty());
+        assert_eq!(ParameterSweep::linspace(2.0, 3.0, 1), vec![2.0]);
+    }
+}
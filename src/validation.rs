@@ -3,16 +3,27 @@
 //! Checks structural integrity of tasks, activities, and resources
 //! before scheduling. Detects:
 //! - Duplicate IDs
+//! - Non-unique activity sequence numbers within a task
 //! - Missing resource references
 //! - Circular precedence dependencies (DAG validation)
 //! - Empty tasks
+//! - Malformed resource calendars
+//! - Numeric nonsense: negative or zero durations, deadlines before
+//!   release times, non-positive maximum flow times, capacities and
+//!   quantities, negative or non-finite rates
+//!
+//! [`check_schedule`] audits a finished (e.g., imported or hand-edited)
+//! schedule against deadlines, maximum flow times, precedences, capacities, calendars and
+//! working-time rules; [`check_work_rules`] covers the latter alone.
 //!
 //! # Reference
 //! Cormen et al. (2009), "Introduction to Algorithms", Ch. 22.4 (Topological Sort)
 
-use crate::models::{Resource, Task};
+use crate::models::{Activity, Assignment, Resource, Schedule, SyncGroups, Task, Violation};
 use std::collections::{HashMap, HashSet};
 
+const HOUR_MS: f64 = 3_600_000.0;
+
 /// Validation result.
 pub type ValidationResult = Result<(), Vec<ValidationError>>;
 
@@ -30,18 +41,35 @@ pub struct ValidationError {
 pub enum ValidationErrorKind {
     /// Two entities share the same ID.
     DuplicateId,
+    /// Two activities of a task share a sequence number.
+    DuplicateSequence,
     /// An activity references a resource that doesn't exist.
     InvalidResourceReference,
     /// Precedence graph contains a cycle.
     CyclicDependency,
     /// A task has no activities.
     EmptyTask,
-    /// An activity references a predecessor that doesn't exist.
+    /// An activity references a predecessor (or an activity to start
+    /// with) that doesn't exist.
     InvalidPredecessor,
+    /// A resource calendar is malformed (see [`Calendar::check`](crate::models::Calendar::check)).
+    InvalidCalendar,
+    /// An activity has a negative duration component, a negative split
+    /// minimum, or a zero total duration.
+    InvalidDuration,
+    /// A task's deadline lies before its release time, its maximum flow
+    /// time is not positive, or a skill expires before it becomes valid.
+    InvalidTimeWindow,
+    /// A resource capacity or requirement quantity is not positive, or a
+    /// requirement needs more units than there are resources to fulfill it.
+    InvalidQuantity,
+    /// An efficiency is not positive, a cost rate or skill level is
+    /// negative or not finite, or a learning curve is out of range.
+    InvalidRate,
 }
 
 impl ValidationError {
-    fn new(kind: ValidationErrorKind, message: impl Into<String>) -> Self {
+    pub(crate) fn new(kind: ValidationErrorKind, message: impl Into<String>) -> Self {
         Self {
             kind,
             message: message.into(),
@@ -54,11 +82,18 @@ impl ValidationError {
 /// Checks:
 /// 1. No duplicate task IDs
 /// 2. No duplicate activity IDs (across all tasks)
-/// 3. No duplicate resource IDs
-/// 4. All tasks have at least one activity
-/// 5. All resource references in activities point to existing resources
-/// 6. All predecessor references point to existing activities
-/// 7. No circular precedence dependencies
+/// 3. No two activities of a task with the same sequence number
+/// 4. No duplicate resource IDs
+/// 5. All tasks have at least one activity
+/// 6. All resource references in activities point to existing resources
+/// 7. All predecessor and synchronization references point to existing
+///    activities
+/// 8. No circular precedence dependencies
+/// 9. Resource calendars are well-formed
+/// 10. Numbers are sane: activity durations non-negative with a positive
+///     total, deadlines not before release times, maximum flow times,
+///     capacities and quantities positive, efficiencies positive, rates non-negative and
+///     finite
 ///
 /// # Returns
 /// `Ok(())` if all checks pass, `Err(errors)` with all detected issues.
@@ -76,6 +111,16 @@ pub fn validate_input(tasks: &[Task], resources: &[Resource]) -> ValidationResul
         }
     }
 
+    // Check resource calendars
+    for r in resources {
+        for issue in r.calendar.iter().flat_map(|c| c.check()) {
+            errors.push(ValidationError::new(
+                ValidationErrorKind::InvalidCalendar,
+                format!("Resource '{}' calendar: {}", r.id, issue),
+            ));
+        }
+    }
+
     // Collect task and activity IDs
     let mut task_ids = HashSet::new();
     let mut activity_ids = HashSet::new();
@@ -95,6 +140,7 @@ pub fn validate_input(tasks: &[Task], resources: &[Resource]) -> ValidationResul
             ));
         }
 
+        let mut sequences = HashSet::new();
         for act in &task.activities {
             if !activity_ids.insert(act.id.as_str()) {
                 errors.push(ValidationError::new(
@@ -102,13 +148,26 @@ pub fn validate_input(tasks: &[Task], resources: &[Resource]) -> ValidationResul
                     format!("Duplicate activity ID: {}", act.id),
                 ));
             }
+            if !sequences.insert(act.sequence) {
+                errors.push(ValidationError::new(
+                    ValidationErrorKind::DuplicateSequence,
+                    format!(
+                        "Task '{}': activity '{}' reuses sequence {}",
+                        task.id, act.id, act.sequence
+                    ),
+                ));
+            }
         }
     }
 
     // Check resource references
     for task in tasks {
         for act in &task.activities {
-            for req in &act.resource_requirements {
+            for req in act
+                .resource_requirements
+                .iter()
+                .chain(act.setup_requirement.as_ref())
+            {
                 for cand in &req.candidates {
                     if !resource_ids.contains(cand.as_str()) {
                         errors.push(ValidationError::new(
@@ -138,6 +197,17 @@ pub fn validate_input(tasks: &[Task], resources: &[Resource]) -> ValidationResul
                     ));
                 }
             }
+            for other in &act.synchronized_with {
+                if !activity_ids.contains(other.as_str()) {
+                    errors.push(ValidationError::new(
+                        ValidationErrorKind::InvalidPredecessor,
+                        format!(
+                            "Activity '{}' is synchronized with unknown activity '{}'",
+                            act.id, other
+                        ),
+                    ));
+                }
+            }
         }
     }
 
@@ -146,6 +216,8 @@ pub fn validate_input(tasks: &[Task], resources: &[Resource]) -> ValidationResul
         errors.push(cycle_err);
     }
 
+    check_numbers(tasks, resources, &mut errors);
+
     if errors.is_empty() {
         Ok(())
     } else {
@@ -153,6 +225,500 @@ pub fn validate_input(tasks: &[Task], resources: &[Resource]) -> ValidationResul
     }
 }
 
+/// Appends numeric sanity errors of tasks and resources.
+fn check_numbers(tasks: &[Task], resources: &[Resource], errors: &mut Vec<ValidationError>) {
+    let mut push = |kind, message: String| errors.push(ValidationError::new(kind, message));
+
+    for r in resources {
+        if r.capacity <= 0 {
+            push(
+                ValidationErrorKind::InvalidQuantity,
+                format!("Resource '{}' has capacity {}", r.id, r.capacity),
+            );
+        }
+        if !(r.efficiency.is_finite() && r.efficiency > 0.0) {
+            push(
+                ValidationErrorKind::InvalidRate,
+                format!("Resource '{}' has efficiency {}", r.id, r.efficiency),
+            );
+        }
+        for (part, ms) in [("warm-up", r.warm_up_ms), ("cool-down", r.cool_down_ms)] {
+            if ms < 0 {
+                push(
+                    ValidationErrorKind::InvalidDuration,
+                    format!("Resource '{}' has negative {} time {}", r.id, part, ms),
+                );
+            }
+        }
+        for problem in r.learning.iter().flat_map(|l| l.check()) {
+            push(
+                ValidationErrorKind::InvalidRate,
+                format!("Resource '{}' learning curve: {}", r.id, problem),
+            );
+        }
+        if let Some(cost) = r.cost_per_hour.filter(|c| !(c.is_finite() && *c >= 0.0)) {
+            push(
+                ValidationErrorKind::InvalidRate,
+                format!("Resource '{}' has cost per hour {}", r.id, cost),
+            );
+        }
+        for skill in r
+            .skills
+            .iter()
+            .filter(|s| !(s.level.is_finite() && s.level >= 0.0))
+        {
+            push(
+                ValidationErrorKind::InvalidRate,
+                format!(
+                    "Resource '{}' skill '{}' has level {}",
+                    r.id, skill.name, skill.level
+                ),
+            );
+        }
+        for skill in &r.skills {
+            if let (Some(from), Some(until)) = (skill.valid_from_ms, skill.valid_until_ms) {
+                if until < from {
+                    push(
+                        ValidationErrorKind::InvalidTimeWindow,
+                        format!(
+                            "Resource '{}' skill '{}' expires at {} before it is valid from {}",
+                            r.id, skill.name, until, from
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    for task in tasks {
+        if let (Some(release), Some(deadline)) = (task.release_time, task.deadline) {
+            if deadline < release {
+                push(
+                    ValidationErrorKind::InvalidTimeWindow,
+                    format!(
+                        "Task '{}' has deadline {} before release time {}",
+                        task.id, deadline, release
+                    ),
+                );
+            }
+        }
+        if let Some(max_flow) = task.max_flow_ms.filter(|&f| f <= 0) {
+            push(
+                ValidationErrorKind::InvalidTimeWindow,
+                format!("Task '{}' has maximum flow time {}", task.id, max_flow),
+            );
+        }
+        if let Some(cost) = task
+            .tardiness_cost_per_hour
+            .filter(|c| !(c.is_finite() && *c >= 0.0))
+        {
+            push(
+                ValidationErrorKind::InvalidRate,
+                format!("Task '{}' has tardiness cost per hour {}", task.id, cost),
+            );
+        }
+
+        for act in &task.activities {
+            let d = &act.duration;
+            for (part, ms) in [
+                ("setup", d.setup_ms),
+                ("process", d.process_ms),
+                ("teardown", d.teardown_ms),
+                ("minimum split", act.min_split_ms),
+            ] {
+                if ms < 0 {
+                    push(
+                        ValidationErrorKind::InvalidDuration,
+                        format!("Activity '{}' has negative {} time {}", act.id, part, ms),
+                    );
+                }
+            }
+            if d.setup_ms >= 0 && d.process_ms >= 0 && d.teardown_ms >= 0 && d.total_ms() == 0 {
+                push(
+                    ValidationErrorKind::InvalidDuration,
+                    format!("Activity '{}' has zero duration", act.id),
+                );
+            }
+            for req in act
+                .resource_requirements
+                .iter()
+                .chain(act.setup_requirement.as_ref())
+                .filter(|req| req.quantity <= 0)
+            {
+                push(
+                    ValidationErrorKind::InvalidQuantity,
+                    format!(
+                        "Activity '{}' requires {} units of '{}'",
+                        act.id, req.quantity, req.resource_type
+                    ),
+                );
+            }
+            for req in act.resource_requirements.iter().filter(|r| r.quantity > 1) {
+                let mut pool: Vec<&str> = req
+                    .resolve(resources)
+                    .into_iter()
+                    .filter(|id| resources.iter().any(|r| r.id == *id))
+                    .collect();
+                pool.sort_unstable();
+                pool.dedup();
+                if pool.len() < req.quantity as usize {
+                    push(
+                        ValidationErrorKind::InvalidQuantity,
+                        format!(
+                            "Activity '{}' needs {} '{}' but only {} resources can fulfill it",
+                            act.id,
+                            req.quantity,
+                            req.resource_type,
+                            pool.len()
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Checks a schedule against its tasks and resources.
+///
+/// Reports, in this order:
+/// 1. Deadline misses, then maximum flow time overruns (per task)
+/// 2. Precedence violations: an activity starting before the previous
+///    activity of its task allows (through its precedence mode and lag),
+///    or before an explicit predecessor finishes
+/// 3. Skill mismatches: assignments outside the validity of a required
+///    skill the resource holds (an expired or not yet valid
+///    certification), then assignments on unknown resources or outside
+///    the resource calendar
+/// 4. Resources running more assignments at once than their capacity;
+///    on a [batching](crate::models::Resource::batching) resource,
+///    assignments with equal start and end that form a valid batch count
+///    once
+/// 5. Activities holding fewer distinct resources than a requirement's
+///    [`quantity`](crate::models::ResourceRequirement::quantity)
+/// 6. Calendar windows in which a resource changes category more often
+///    than its
+///    [`max_setups_per_window`](crate::models::Resource::max_setups_per_window)
+/// 7. Synchronized activities (see [`SyncGroups`]) starting at different
+///    times
+/// 8. Working-time rule violations (see [`check_work_rules`])
+///
+/// Deadline misses and flow-time overruns carry the hours late as their
+/// [`penalty`](Violation::penalty), capacity violations the excess
+/// load at the first overload, unit shortages the missing units and
+/// setup limits the excess changeovers; see
+/// [`Schedule::penalty_total`].
+pub fn check_schedule(
+    schedule: &Schedule,
+    tasks: &[Task],
+    resources: &[Resource],
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for task in tasks {
+        let (Some(deadline), Some(completion)) =
+            (task.deadline, schedule.task_completion_time(&task.id))
+        else {
+            continue;
+        };
+        if completion > deadline {
+            violations.push(
+                Violation::deadline_miss(
+                    &task.id,
+                    format!(
+                        "{} completes at {completion}, {} ms after its deadline",
+                        task.id,
+                        completion - deadline
+                    ),
+                )
+                .with_penalty((completion - deadline) as f64 / HOUR_MS),
+            );
+        }
+    }
+
+    for task in tasks {
+        let (Some(limit), Some(completion)) = (
+            task.flow_deadline(),
+            schedule.task_completion_time(&task.id),
+        ) else {
+            continue;
+        };
+        if completion > limit {
+            violations.push(
+                Violation::flow_time_exceeded(
+                    &task.id,
+                    format!(
+                        "{} completes at {completion}, {} ms past its maximum flow time",
+                        task.id,
+                        completion - limit
+                    ),
+                )
+                .with_penalty((completion - limit) as f64 / HOUR_MS),
+            );
+        }
+    }
+
+    // Processing interval (start after setup, end) of each activity
+    let mut spans: HashMap<&str, (i64, i64)> = HashMap::new();
+    let mut by_activity: HashMap<&str, Vec<&Assignment>> = HashMap::new();
+    for a in schedule.assignments.iter().filter(|a| a.process_ms() > 0) {
+        by_activity
+            .entry(a.activity_id.as_str())
+            .or_default()
+            .push(a);
+        let span = spans
+            .entry(a.activity_id.as_str())
+            .or_insert((a.start_ms + a.setup_ms, a.end_ms));
+        span.0 = span.0.min(a.start_ms + a.setup_ms);
+        span.1 = span.1.max(a.end_ms);
+    }
+    for task in tasks {
+        for (i, act) in task.activities.iter().enumerate() {
+            let own = by_activity
+                .get(act.id.as_str())
+                .map_or(&[][..], Vec::as_slice);
+            let Some(start) = own.iter().map(|a| a.start_ms).min() else {
+                continue;
+            };
+            let process_ms = own.iter().map(|a| a.process_ms()).max().unwrap_or(0);
+            let previous = task.chained_predecessor(i).map(|j| &task.activities[j].id);
+            let mut bounds: Vec<(&String, i64)> = Vec::new();
+            if let Some((prev, &(prev_start, prev_end))) =
+                previous.and_then(|p| spans.get(p.as_str()).map(|s| (p, s)))
+            {
+                let bound = act.precedence_mode.earliest_start(
+                    prev_start,
+                    prev_end,
+                    act.precedence_lag_ms,
+                    process_ms,
+                );
+                bounds.push((prev, bound));
+            }
+            for pred in &act.predecessors {
+                if let Some(&(_, end)) = spans.get(pred.as_str()) {
+                    bounds.push((pred, end));
+                }
+            }
+            for (pred, bound) in bounds {
+                if start < bound {
+                    violations.push(Violation::precedence_violation(
+                        &act.id,
+                        format!(
+                            "{} starts at {start}, {} ms before predecessor {pred} allows",
+                            act.id,
+                            bound - start
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    let resource_by_id: HashMap<&str, &Resource> =
+        resources.iter().map(|r| (r.id.as_str(), r)).collect();
+    let activity_by_id: HashMap<&str, &Activity> = tasks
+        .iter()
+        .flat_map(|t| &t.activities)
+        .map(|a| (a.id.as_str(), a))
+        .collect();
+    for a in &schedule.assignments {
+        if let (Some(resource), Some(activity)) = (
+            resource_by_id.get(a.resource_id.as_str()),
+            activity_by_id.get(a.activity_id.as_str()),
+        ) {
+            for skill in activity.lapsed_skills(resource, a.start_ms, a.end_ms) {
+                violations.push(Violation::skill_mismatch(
+                    &a.resource_id,
+                    format!(
+                        "{} on {} in [{}, {}) is outside the validity of its {skill} qualification",
+                        a.activity_id, a.resource_id, a.start_ms, a.end_ms
+                    ),
+                ));
+            }
+        }
+        match resource_by_id.get(a.resource_id.as_str()) {
+            None => violations.push(Violation::resource_unavailable(
+                &a.resource_id,
+                format!(
+                    "{} is assigned to unknown resource {}",
+                    a.activity_id, a.resource_id
+                ),
+            )),
+            Some(resource) => {
+                // Warm-ups may run before the window opens.
+                let Some(calendar) = resource.calendar.as_ref().filter(|_| !a.is_conditioning())
+                else {
+                    continue;
+                };
+                if calendar.available_time_in_range(a.start_ms, a.end_ms) < a.duration_ms() {
+                    violations.push(Violation::resource_unavailable(
+                        &a.resource_id,
+                        format!(
+                            "{} on {} runs outside working time in [{}, {})",
+                            a.activity_id, a.resource_id, a.start_ms, a.end_ms
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    // A valid batch (members starting and ending together) is one load
+    let category_of: HashMap<&str, &str> = tasks
+        .iter()
+        .map(|t| (t.id.as_str(), t.category.as_str()))
+        .collect();
+    let mut batches: HashMap<(&str, i64, i64), Vec<&str>> = HashMap::new();
+    let mut events_by_resource: HashMap<&str, Vec<(i64, i32)>> = HashMap::new();
+    for a in schedule
+        .assignments
+        .iter()
+        .filter(|a| a.end_ms > a.start_ms)
+    {
+        let batching = resource_by_id
+            .get(a.resource_id.as_str())
+            .is_some_and(|r| r.batching.is_some());
+        match category_of.get(a.task_id.as_str()) {
+            Some(&category) if batching => batches
+                .entry((a.resource_id.as_str(), a.start_ms, a.end_ms))
+                .or_default()
+                .push(category),
+            _ => events_by_resource
+                .entry(a.resource_id.as_str())
+                .or_default()
+                .extend([(a.start_ms, 1), (a.end_ms, -1)]),
+        }
+    }
+    for ((resource_id, start, end), categories) in &batches {
+        let admitted = resource_by_id[resource_id]
+            .batching
+            .as_ref()
+            .is_some_and(|rules| rules.admits(categories));
+        let load = if admitted { 1 } else { categories.len() as i32 };
+        events_by_resource
+            .entry(resource_id)
+            .or_default()
+            .extend([(*start, load), (*end, -load)]);
+    }
+    for resource in resources {
+        let Some(events) = events_by_resource.get_mut(resource.id.as_str()) else {
+            continue;
+        };
+        // Ends sort before starts at the same time
+        events.sort_unstable();
+        let capacity = resource.capacity.max(1);
+        let mut load = 0;
+        for &(time, delta) in events.iter() {
+            load += delta;
+            if load > capacity {
+                violations.push(
+                    Violation::capacity_exceeded(
+                        &resource.id,
+                        format!(
+                            "{}: {load} assignments at {time} exceed capacity {capacity}",
+                            resource.id
+                        ),
+                    )
+                    .with_penalty((load - capacity) as f64),
+                );
+                break;
+            }
+        }
+    }
+
+    let mut held: HashMap<&str, Vec<&str>> = HashMap::new();
+    for a in &schedule.assignments {
+        held.entry(a.activity_id.as_str())
+            .or_default()
+            .push(a.resource_id.as_str());
+    }
+    for act in tasks.iter().flat_map(|t| &t.activities) {
+        let Some(assigned) = held.get(act.id.as_str()) else {
+            continue;
+        };
+        for req in act.resource_requirements.iter().filter(|r| r.quantity > 1) {
+            let mut units = req.resolve(resources);
+            units.retain(|id| assigned.contains(id));
+            units.sort_unstable();
+            units.dedup();
+            let missing = req.quantity as usize - units.len().min(req.quantity as usize);
+            if missing > 0 {
+                violations.push(
+                    Violation::insufficient_units(
+                        &act.id,
+                        format!(
+                            "{} holds {} of {} units of '{}'",
+                            act.id,
+                            units.len(),
+                            req.quantity,
+                            req.resource_type
+                        ),
+                    )
+                    .with_penalty(missing as f64),
+                );
+            }
+        }
+    }
+
+    for resource in resources {
+        let Some(limit) = resource.max_setups_per_window else {
+            continue;
+        };
+        for (window, count) in schedule.changeovers_per_window(resource, tasks) {
+            if count > limit {
+                violations.push(
+                    Violation::too_many_setups(
+                        &resource.id,
+                        format!(
+                            "{}: {count} changeovers in [{}, {}) exceed the limit of {limit}",
+                            resource.id, window.start_ms, window.end_ms
+                        ),
+                    )
+                    .with_penalty((count - limit) as f64),
+                );
+            }
+        }
+    }
+
+    for (group, latest) in SyncGroups::from_tasks(tasks).misaligned(schedule) {
+        violations.push(Violation::unsynchronized(
+            &group[0],
+            format!(
+                "{} must start together, but not all start at {latest}",
+                group.join(", ")
+            ),
+        ));
+    }
+
+    violations.extend(check_work_rules(schedule, resources));
+    violations
+}
+
+/// Checks a schedule against the working-time rules of human resources.
+///
+/// Returns one [`Violation`] per broken rule instance; resources without
+/// enforced rules are skipped.
+pub fn check_work_rules(schedule: &Schedule, resources: &[Resource]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for resource in resources {
+        let Some(rules) = resource.enforced_work_rules() else {
+            continue;
+        };
+        let mut busy: Vec<(i64, i64)> = schedule
+            .assignments_for_resource(&resource.id)
+            .iter()
+            .map(|a| (a.start_ms, a.end_ms))
+            .collect();
+        busy.sort_unstable();
+        violations.extend(
+            rules
+                .check(&busy)
+                .into_iter()
+                .map(|msg| Violation::work_rule(&resource.id, format!("{}: {msg}", resource.id))),
+        );
+    }
+    violations
+}
+
 /// Detects cycles in the precedence graph using DFS.
 ///
 /// # Algorithm
@@ -218,7 +784,9 @@ fn has_cycle_dfs<'a>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{Activity, ActivityDuration, Resource, ResourceRequirement, Task};
+    use crate::models::{
+        Activity, ActivityDuration, Calendar, LearningCurve, Resource, ResourceRequirement, Task,
+    };
 
     fn sample_resources() -> Vec<Resource> {
         vec![
@@ -277,6 +845,22 @@ mod tests {
             .any(|e| e.kind == ValidationErrorKind::DuplicateId));
     }
 
+    #[test]
+    fn test_duplicate_sequence() {
+        let tasks = vec![Task::new("J1")
+            .with_activity(Activity::new("O1", "J1", 0).with_process_time(100))
+            .with_activity(Activity::new("O2", "J1", 1).with_process_time(100))
+            .with_activity(Activity::new("O3", "J1", 1).with_process_time(100))];
+
+        let errors = validate_input(&tasks, &sample_resources()).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ValidationErrorKind::DuplicateSequence);
+        assert!(errors[0].message.contains("O3"));
+
+        // The same sequence in different tasks is fine.
+        assert!(validate_input(&sample_tasks(), &sample_resources()).is_ok());
+    }
+
     #[test]
     fn test_duplicate_resource_id() {
         let tasks = sample_tasks();
@@ -314,6 +898,17 @@ mod tests {
         assert!(errors
             .iter()
             .any(|e| e.kind == ValidationErrorKind::InvalidResourceReference));
+
+        // Setup crews are checked too.
+        let tasks = vec![Task::new("J1").with_activity(
+            Activity::new("O1", "J1", 0)
+                .with_process_time(100)
+                .with_setup_requirement(
+                    ResourceRequirement::new("Setter").with_candidates(vec!["NOBODY".into()]),
+                ),
+        )];
+        let errors = validate_input(&tasks, &resources).unwrap_err();
+        assert!(errors[0].message.contains("NOBODY"));
     }
 
     #[test]
@@ -378,6 +973,164 @@ mod tests {
         assert!(validate_input(&tasks, &resources).is_ok());
     }
 
+    #[test]
+    fn test_check_work_rules() {
+        use crate::models::{Assignment, ViolationType, WorkRules};
+
+        let rules = WorkRules::new().with_min_rest_ms(100);
+        let resources = vec![
+            Resource::human("W1").with_work_rules(rules.clone()),
+            // Rules on non-human resources are not enforced.
+            Resource::primary("M1").with_work_rules(rules),
+        ];
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("A1", "T1", "W1", 0, 1000));
+        schedule.add_assignment(Assignment::new("A2", "T1", "W1", 1050, 2000));
+        schedule.add_assignment(Assignment::new("A3", "T2", "M1", 0, 1000));
+        schedule.add_assignment(Assignment::new("A4", "T2", "M1", 1000, 2000));
+
+        let violations = check_work_rules(&schedule, &resources);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].violation_type, ViolationType::WorkRule);
+        assert_eq!(violations[0].entity_id, "W1");
+    }
+
+    #[test]
+    fn test_check_schedule() {
+        use crate::models::{Assignment, ViolationType};
+
+        let mut tasks = sample_tasks();
+        tasks[1].deadline = Some(2000);
+        let mut resources = sample_resources();
+        resources[1].calendar = Some(Calendar::new("M2").with_window(0, 2500));
+
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("O1", "J1", "M1", 0, 1000));
+        schedule.add_assignment(Assignment::new("O2", "J1", "M2", 1000, 3000));
+        schedule.add_assignment(Assignment::new("O3", "J2", "M1", 1000, 2500));
+        // O2 runs past the M2 calendar, J2 misses its deadline.
+        let kinds: Vec<ViolationType> = check_schedule(&schedule, &tasks, &resources)
+            .into_iter()
+            .map(|v| v.violation_type)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ViolationType::DeadlineMiss,
+                ViolationType::ResourceUnavailable
+            ]
+        );
+
+        // Moving O2 before O1 ends breaks its explicit predecessor (J1 is a
+        // DAG, so there is no separate sequence bound); moving O3 onto O1
+        // overloads M1.
+        schedule.assignments[1].start_ms = 500;
+        schedule.assignments[1].end_ms = 2500;
+        schedule.assignments[2].start_ms = 0;
+        schedule.assignments[2].end_ms = 1500;
+        let violations = check_schedule(&schedule, &tasks, &resources);
+        let kinds: Vec<&ViolationType> = violations.iter().map(|v| &v.violation_type).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &ViolationType::PrecedenceViolation,
+                &ViolationType::CapacityExceeded,
+            ]
+        );
+        assert!(violations[0]
+            .message
+            .contains("500 ms before predecessor O1"));
+
+        schedule.assignments[2].resource_id = "M9".into();
+        assert!(check_schedule(&schedule, &tasks, &resources)
+            .iter()
+            .any(|v| v.message.contains("unknown resource M9")));
+    }
+
+    #[test]
+    fn test_check_schedule_synchronized() {
+        use crate::models::{Assignment, ViolationType};
+
+        let mut tasks = sample_tasks();
+        tasks[1].activities[0].synchronized_with = vec!["O2".into()];
+        assert!(validate_input(&tasks, &sample_resources()).is_ok());
+
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("O1", "J1", "M1", 0, 1000));
+        schedule.add_assignment(Assignment::new("O2", "J1", "M2", 1000, 3000));
+        schedule.add_assignment(Assignment::new("O3", "J2", "M1", 1000, 2500));
+        assert!(check_schedule(&schedule, &tasks, &sample_resources()).is_empty());
+
+        schedule.assignments[2].start_ms = 1200;
+        schedule.assignments[2].end_ms = 2700;
+        let violations = check_schedule(&schedule, &tasks, &sample_resources());
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].violation_type, ViolationType::Unsynchronized);
+        assert!(violations[0]
+            .message
+            .starts_with("O2, O3 must start together"));
+
+        tasks[1].activities[0].synchronized_with = vec!["O9".into()];
+        let errors = validate_input(&tasks, &sample_resources()).unwrap_err();
+        assert_eq!(errors[0].kind, ValidationErrorKind::InvalidPredecessor);
+    }
+
+    #[test]
+    fn test_check_schedule_certifications() {
+        use crate::models::{Assignment, Skill, ViolationType};
+
+        let tasks = vec![Task::new("J1").with_activity(
+            Activity::new("O1", "J1", 0)
+                .with_process_time(1000)
+                .with_requirement(
+                    ResourceRequirement::new("Human")
+                        .with_candidates(vec!["W1".into()])
+                        .with_skill("welding"),
+                ),
+        )];
+        let resources = vec![Resource::human("W1").with_certification(
+            Skill::new("welding", 0.8)
+                .with_valid_from(1000)
+                .with_valid_until(5000),
+        )];
+        assert!(validate_input(&tasks, &resources).is_ok());
+
+        let mut schedule = Schedule::new();
+        schedule.add_assignment(Assignment::new("O1", "J1", "W1", 2000, 3000));
+        assert!(check_schedule(&schedule, &tasks, &resources).is_empty());
+
+        schedule.assignments[0].start_ms = 4500;
+        schedule.assignments[0].end_ms = 5500;
+        let violations = check_schedule(&schedule, &tasks, &resources);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].violation_type, ViolationType::SkillMismatch);
+        assert!(violations[0].message.contains("welding qualification"));
+
+        let expired = vec![Resource::human("W1").with_certification(
+            Skill::new("welding", 0.8)
+                .with_valid_from(5000)
+                .with_valid_until(1000),
+        )];
+        let errors = validate_input(&tasks, &expired).unwrap_err();
+        assert_eq!(errors[0].kind, ValidationErrorKind::InvalidTimeWindow);
+    }
+
+    #[test]
+    fn test_invalid_calendar() {
+        let calendar = Calendar::new("cal")
+            .with_window(0, 100)
+            .with_window(50, 150);
+        let mut resources = sample_resources();
+        resources[0].calendar = Some(calendar);
+        let errors = validate_input(&sample_tasks(), &resources).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ValidationErrorKind::InvalidCalendar);
+        assert!(errors[0].message.contains("M1"));
+
+        resources[0].calendar.as_mut().unwrap().normalize();
+        assert!(validate_input(&sample_tasks(), &resources).is_ok());
+    }
+
     #[test]
     fn test_multiple_errors() {
         // Empty task + invalid resource reference
@@ -396,4 +1149,81 @@ mod tests {
         let errors = validate_input(&tasks, &resources).unwrap_err();
         assert!(errors.len() >= 2);
     }
+
+    #[test]
+    fn test_numeric_sanity() {
+        let kinds = |tasks: &[Task], resources: &[Resource]| -> Vec<ValidationErrorKind> {
+            validate_input(tasks, resources)
+                .err()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|e| e.kind)
+                .collect()
+        };
+        let job = |activity: Activity| vec![Task::new("J1").with_activity(activity)];
+        let resources = sample_resources();
+
+        let negative =
+            Activity::new("O1", "J1", 0).with_duration(ActivityDuration::new(-5, 100, 0));
+        assert_eq!(
+            kinds(&job(negative), &resources),
+            vec![ValidationErrorKind::InvalidDuration]
+        );
+        let zero = Activity::new("O1", "J1", 0);
+        assert_eq!(
+            kinds(&job(zero), &resources),
+            vec![ValidationErrorKind::InvalidDuration]
+        );
+        let no_units = Activity::new("O1", "J1", 0)
+            .with_process_time(100)
+            .with_requirement(ResourceRequirement::new("Machine").with_quantity(0));
+        assert_eq!(
+            kinds(&job(no_units), &resources),
+            vec![ValidationErrorKind::InvalidQuantity]
+        );
+        let crew = |n| {
+            Activity::new("O1", "J1", 0)
+                .with_process_time(100)
+                .with_requirement(ResourceRequirement::new("Human").with_quantity(n))
+        };
+        assert_eq!(
+            kinds(&job(crew(2)), &resources),
+            vec![ValidationErrorKind::InvalidQuantity]
+        );
+        assert!(kinds(&job(crew(1)), &resources).is_empty());
+
+        let backwards = vec![Task::new("J1")
+            .with_release_time(5000)
+            .with_deadline(1000)
+            .with_activity(Activity::new("O1", "J1", 0).with_process_time(100))];
+        assert_eq!(
+            kinds(&backwards, &resources),
+            vec![ValidationErrorKind::InvalidTimeWindow]
+        );
+        let no_flow = vec![Task::new("J1")
+            .with_max_flow(0)
+            .with_activity(Activity::new("O1", "J1", 0).with_process_time(100))];
+        assert_eq!(
+            kinds(&no_flow, &resources),
+            vec![ValidationErrorKind::InvalidTimeWindow]
+        );
+
+        let broken = vec![
+            Resource::primary("M1").with_capacity(0),
+            Resource::primary("M2").with_efficiency(f64::NAN),
+            Resource::primary("M3").with_cost(-1.0),
+            Resource::primary("M4").with_learning(LearningCurve::new().with_repetition(2.0, 0.0)),
+            Resource::primary("M5").with_warm_up(-1),
+        ];
+        assert_eq!(
+            kinds(&sample_tasks(), &broken),
+            vec![
+                ValidationErrorKind::InvalidQuantity,
+                ValidationErrorKind::InvalidRate,
+                ValidationErrorKind::InvalidRate,
+                ValidationErrorKind::InvalidRate,
+                ValidationErrorKind::InvalidDuration,
+            ]
+        );
+    }
 }
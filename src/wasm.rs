@@ -0,0 +1,214 @@
+//! Browser facade (feature `wasm`).
+//!
+//! JSON-in/JSON-out functions for what-if scheduling in the browser.
+//! Problems use the [`ScheduleRequest`] JSON instance format; results
+//! are `{ "schedule": ..., "kpi": ... }`.
+//!
+//! # Platform Notes
+//!
+//! `wasm32-unknown-unknown` has no threads and no `std::time::Instant`,
+//! so on that target the GA runs single-threaded and is bounded by
+//! generation count (not wall time). Runs are seeded (default seed 0),
+//! so no OS entropy is needed at runtime.
+//!
+//! # Build
+//!
+//! ```text
+//! wasm-pack build --target web --features wasm
+//! ```
+//!
+//! # Example (JavaScript)
+//!
+//! ```text
+//! import init, { solveGreedy } from "./pkg/u_schedule.js";
+//! await init();
+//! const { schedule, kpi } = JSON.parse(solveGreedy(problemJson, null));
+//! ```
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::bundle::SolverSpec;
+use crate::dispatching::{RuleEngine, RuleEngineSpec};
+use crate::ga::{evolve, SchedulingGaOptions};
+use crate::models::Schedule;
+use crate::scheduler::{ScheduleKpi, ScheduleRequest, SimpleScheduler};
+use crate::validation::validate_input;
+
+/// Schedule and its KPIs, as returned to JavaScript.
+#[derive(Debug, Serialize)]
+pub struct SolveOutput {
+    /// The computed schedule.
+    pub schedule: Schedule,
+    /// KPIs of the schedule.
+    pub kpi: ScheduleKpi,
+}
+
+/// GA options plus a small evaluation budget.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GaBudget {
+    /// Problem configuration and seed.
+    #[serde(flatten)]
+    pub options: SchedulingGaOptions,
+    /// Population size (default: 30).
+    pub population_size: usize,
+    /// Number of generations (default: 50).
+    pub generations: usize,
+    /// Per-offspring mutation probability (default: 0.1).
+    pub mutation_rate: f64,
+}
+
+impl Default for GaBudget {
+    fn default() -> Self {
+        Self {
+            options: SchedulingGaOptions::default(),
+            population_size: 30,
+            generations: 50,
+            mutation_rate: 0.1,
+        }
+    }
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(json: &str, what: &str) -> Result<T, String> {
+    serde_json::from_str(json).map_err(|e| format!("invalid {what} JSON: {e}"))
+}
+
+fn output(request: &ScheduleRequest, schedule: Schedule) -> Result<String, String> {
+    let kpi = ScheduleKpi::calculate(&schedule, &request.tasks);
+    serde_json::to_string(&SolveOutput { schedule, kpi }).map_err(|e| e.to_string())
+}
+
+/// Validates a problem; returns a JSON array of error messages.
+pub fn validate_json(problem_json: &str) -> Result<String, String> {
+    let request: ScheduleRequest = parse(problem_json, "problem")?;
+    let messages: Vec<String> = match validate_input(&request.tasks, &request.resources) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.into_iter().map(|e| e.message).collect(),
+    };
+    serde_json::to_string(&messages).map_err(|e| e.to_string())
+}
+
+/// Runs the greedy scheduler, optionally with a [`RuleEngineSpec`].
+pub fn solve_greedy_json(problem_json: &str, rules_json: Option<&str>) -> Result<String, String> {
+    let request: ScheduleRequest = parse(problem_json, "problem")?;
+    let mut scheduler = SimpleScheduler::new();
+    if let Some(rules) = rules_json {
+        let spec: RuleEngineSpec = parse(rules, "rule engine")?;
+        scheduler = scheduler.with_rule_engine(RuleEngine::from_spec(&spec));
+    }
+    let schedule = scheduler.schedule_request(&request);
+    output(&request, schedule)
+}
+
+/// Runs a budgeted GA, optionally with a [`GaBudget`].
+pub fn solve_ga_json(problem_json: &str, budget_json: Option<&str>) -> Result<String, String> {
+    let request: ScheduleRequest = parse(problem_json, "problem")?;
+    let budget: GaBudget = match budget_json {
+        Some(json) => parse(json, "GA budget")?,
+        None => GaBudget::default(),
+    };
+    let spec = SolverSpec::Ga {
+        options: SchedulingGaOptions {
+            seed: Some(budget.options.seed.unwrap_or(0)),
+            ..budget.options
+        },
+        population_size: Some(budget.population_size.max(2)),
+        generations: None,
+        time_limit_ms: None,
+        generations_run: Some(budget.generations),
+    };
+    let (problem, config) = spec
+        .ga_setup(&request)
+        .map_err(|e| e.message)?
+        .expect("GA spec");
+    let config = config.with_mutation_rate(budget.mutation_rate.clamp(0.0, 1.0));
+    config.validate()?;
+    let result = evolve(&problem, &config, None);
+    output(&request, problem.decode(&result.best))
+}
+
+/// Computes KPIs of a schedule for a problem.
+pub fn kpi_json(problem_json: &str, schedule_json: &str) -> Result<String, String> {
+    let request: ScheduleRequest = parse(problem_json, "problem")?;
+    let schedule: Schedule = parse(schedule_json, "schedule")?;
+    let kpi = ScheduleKpi::calculate(&schedule, &request.tasks);
+    serde_json::to_string(&kpi).map_err(|e| e.to_string())
+}
+
+/// Validates a problem (JSON) and returns a JSON array of errors.
+#[wasm_bindgen(js_name = validate)]
+pub fn validate_js(problem_json: &str) -> Result<String, JsError> {
+    validate_json(problem_json).map_err(|e| JsError::new(&e))
+}
+
+/// Runs the greedy scheduler; `rules_json` is an optional rule engine spec.
+#[wasm_bindgen(js_name = solveGreedy)]
+pub fn solve_greedy_js(problem_json: &str, rules_json: Option<String>) -> Result<String, JsError> {
+    solve_greedy_json(problem_json, rules_json.as_deref()).map_err(|e| JsError::new(&e))
+}
+
+/// Runs a small GA; `budget_json` is optional (population, generations, options).
+#[wasm_bindgen(js_name = solveGa)]
+pub fn solve_ga_js(problem_json: &str, budget_json: Option<String>) -> Result<String, JsError> {
+    solve_ga_json(problem_json, budget_json.as_deref()).map_err(|e| JsError::new(&e))
+}
+
+/// Computes KPIs for a schedule (JSON) against a problem (JSON).
+#[wasm_bindgen(js_name = computeKpi)]
+pub fn kpi_js(problem_json: &str, schedule_json: &str) -> Result<String, JsError> {
+    kpi_json(problem_json, schedule_json).map_err(|e| JsError::new(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::golden;
+
+    fn problem_json() -> String {
+        serde_json::to_string(&golden::flexible_shop().request()).unwrap()
+    }
+
+    #[test]
+    fn test_validate_json() {
+        assert_eq!(validate_json(&problem_json()).unwrap(), "[]");
+        assert!(validate_json("{")
+            .unwrap_err()
+            .contains("invalid problem JSON"));
+    }
+
+    #[test]
+    fn test_solve_greedy_json() {
+        let out = solve_greedy_json(&problem_json(), Some(r#"{ "rules": [{ "rule": "SPT" }] }"#))
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(
+            value["schedule"]["assignments"].as_array().unwrap().len(),
+            8
+        );
+        assert!(value["kpi"]["makespan_ms"].as_i64().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_solve_ga_json_is_seeded() {
+        let budget = r#"{ "population_size": 10, "generations": 5, "seed": 3 }"#;
+        let run = || {
+            let out = solve_ga_json(&problem_json(), Some(budget)).unwrap();
+            let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+            value["schedule"].clone()
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn test_kpi_json() {
+        let out = solve_greedy_json(&problem_json(), None).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+        let kpi = kpi_json(&problem_json(), &value["schedule"].to_string()).unwrap();
+        let kpi: ScheduleKpi = serde_json::from_str(&kpi).unwrap();
+        assert_eq!(
+            kpi.makespan_ms,
+            value["kpi"]["makespan_ms"].as_i64().unwrap()
+        );
+    }
+}